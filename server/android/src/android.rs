@@ -46,6 +46,7 @@ pub mod android {
                 cors_origins: vec!["http://localhost".to_string()],
                 base_dir: Some(files_dir.to_str().unwrap().to_string()),
                 machine_uid: Some(android_id),
+                metrics_enabled: false,
             },
             database: DatabaseSettings {
                 username: "n/a".to_string(),
@@ -64,6 +65,7 @@ pub mod android {
                     .with_directory(files_dir.to_string_lossy().to_string()),
             ),
             backup: None,
+            oidc: None,
         };
 
         logging_init(settings.logging.clone(), None);
@@ -10,18 +10,21 @@ use report_builder::{build::build_report_definition, BuildArgs};
 use repository::{
     get_storage_connection_manager, schema_from_row, test_db, ContextType, EqualFilter,
     FormSchemaRow, FormSchemaRowRepository, KeyType, KeyValueStoreRepository, ReportFilter,
-    ReportRepository, ReportRow, ReportRowRepository, SyncBufferRowRepository,
+    ReportRepository, ReportRow, ReportRowRepository, StockAggregateRepository,
+    SyncBufferRowRepository,
 };
 use serde::{Deserialize, Serialize};
 use server::configuration;
 use service::{
     apis::login_v4::LoginUserInfoV4,
     auth_data::AuthData,
+    clinical_check::DrugInteractionData,
     login::{LoginInput, LoginService},
     plugin::validation::sign_plugin,
     service_provider::{ServiceContext, ServiceProvider},
     settings::Settings,
     standard_reports::{ReportData, ReportsData, StandardReports},
+    support_bundle::{support_bundle_file_name, SupportBundleInput},
     sync::{
         file_sync_driver::FileSyncDriver, settings::SyncSettings, sync_status::logger::SyncLogger,
         synchroniser::integrate_and_translate_sync_buffer, synchroniser_driver::SynchroniserDriver,
@@ -39,6 +42,8 @@ use util::inline_init;
 
 mod backup;
 use backup::*;
+mod migrate_database;
+use migrate_database::*;
 
 const DATA_EXPORT_FOLDER: &str = "data";
 
@@ -143,6 +148,31 @@ enum Action {
     /// User can specify max number of backup to keep, see example configuration file
     Backup,
     Restore(RestoreArguments),
+    /// Copies all data between a SQLite database file and the Postgres database from the configuration files, in either direction.
+    /// Both databases need to be migrated to the same version, data in the target database is replaced.
+    /// Row counts and checksums of every table are verified before the copy is committed.
+    /// Requires the cli to be built with the postgres feature
+    MigrateDatabase(MigrateDatabaseArguments),
+    /// Recalculates stock on hand and daily consumption aggregate tables from stock lines and invoices.
+    /// Aggregates are kept up to date by the server, this is only needed after changing data directly in the database
+    RebuildStockAggregates,
+    /// Generates a zip with recent logs, sanitised settings, sync history and database diagnostics, to send to support
+    SupportBundle {
+        /// Path of the zip file, defaults to a timestamped file in the current directory
+        #[clap(short, long)]
+        output: Option<String>,
+
+        /// Include a copy of the database with patient data and secrets removed (sqlite only)
+        #[clap(long, action = ArgAction::SetTrue)]
+        include_database_extract: bool,
+    },
+    /// Replaces the drug interaction dataset used for prescription clinical checks, from a json file with
+    /// `ingredients` (item code to active ingredient) and `interactions` (pairs of item codes or ingredients with severity and description)
+    ImportDrugInteractions {
+        /// Path to the json file
+        #[clap(short, long)]
+        path: String,
+    },
     BuildStandardReports,
     UpsertReportsJson {
         /// Optional reports json path. This needs to be of type ReportsData. If none supplied, will upload the standard generated reports
@@ -212,6 +242,7 @@ async fn initialise_from_central(
             username: user[0].to_string(),
             password: user[1].to_string(),
             central_server_url: central_server_url.clone(),
+            client_ip: None,
         };
         LoginService::login(&service_provider, &auth_data, input.clone(), 0)
             .await
@@ -273,6 +304,7 @@ async fn main() -> anyhow::Result<()> {
                     username: user[0].to_string(),
                     password: user[1].to_string(),
                     central_server_url: url.to_string(),
+                    client_ip: None,
                 };
                 synced_user_info_rows.push((
                     input.clone(),
@@ -618,6 +650,77 @@ async fn main() -> anyhow::Result<()> {
         Action::Restore(arguments) => {
             restore(&settings, arguments)?;
         }
+        Action::MigrateDatabase(arguments) => {
+            migrate_database(&settings, arguments)?;
+        }
+        Action::RebuildStockAggregates => {
+            let connection_manager = get_storage_connection_manager(&settings.database);
+            let connection = connection_manager.connection()?;
+
+            info!("Rebuilding stock aggregates");
+            connection
+                .transaction_sync(|connection| StockAggregateRepository::new(connection).rebuild())
+                .map_err(|error| error.to_inner_error())?;
+            info!("Finished rebuilding stock aggregates");
+        }
+        Action::SupportBundle {
+            output,
+            include_database_extract,
+        } => {
+            let connection_manager = get_storage_connection_manager(&settings.database);
+            let app_data_folder = settings
+                .server
+                .base_dir
+                .clone()
+                .ok_or(anyhow!("based dir not set in yaml configurations"))?;
+            let service_provider =
+                ServiceProvider::new(connection_manager.clone(), &app_data_folder);
+            let ctx = service_provider.basic_context()?;
+
+            let machine_uid = machine_uid::get().expect("Failed to query OS for hardware id");
+            service_provider
+                .app_data_service
+                .set_hardware_id(machine_uid)?;
+
+            info!("Generating support bundle");
+            let bundle = service_provider
+                .support_bundle_service
+                .generate_support_bundle(
+                    &ctx,
+                    &service_provider,
+                    &settings,
+                    SupportBundleInput {
+                        include_database_extract,
+                    },
+                )
+                .map_err(|error| anyhow!("Failed to generate support bundle {:?}", error))?;
+
+            let output = output.unwrap_or_else(support_bundle_file_name);
+            fs::write(&output, bundle)?;
+            info!("Support bundle saved in {}", output);
+        }
+        Action::ImportDrugInteractions { path } => {
+            let connection_manager = get_storage_connection_manager(&settings.database);
+            let app_data_folder = settings
+                .server
+                .base_dir
+                .clone()
+                .ok_or(anyhow!("based dir not set in yaml configurations"))?;
+            let service_provider = ServiceProvider::new(connection_manager, &app_data_folder);
+            let ctx = service_provider.basic_context()?;
+
+            let data: DrugInteractionData = serde_json::from_str(&fs::read_to_string(path)?)?;
+
+            info!("Importing drug interactions");
+            let result = service_provider
+                .clinical_check_service
+                .import_drug_interactions(&ctx, data)
+                .map_err(|error| anyhow!("Failed to import drug interactions {:?}", error))?;
+            info!(
+                "Imported {} ingredients and {} interactions",
+                result.ingredient_count, result.interaction_count
+            );
+        }
     }
 
     Ok(())
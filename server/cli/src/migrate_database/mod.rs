@@ -0,0 +1,460 @@
+mod postgres;
+mod sqlite;
+
+use std::{io, path::PathBuf};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Timelike};
+use log::info;
+use repository::{get_storage_connection_manager, RepositoryError};
+use serde_json::{Number, Value};
+use service::settings::{is_develop, Settings};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use self::{postgres::PostgresDatabase, sqlite::SqliteDatabase};
+
+/// Diesel's own migration bookkeeping, it differs between the backends and is not copied
+const EXCLUDED_TABLES: [&str; 1] = ["__diesel_schema_migrations"];
+const BATCH_SIZE: usize = 1000;
+
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq)]
+pub(super) enum MigrationDirection {
+    SqliteToPostgres,
+    PostgresToSqlite,
+}
+
+#[derive(clap::Parser, Debug)]
+pub(super) struct MigrateDatabaseArguments {
+    /// Path to the SQLite database file, it needs to be migrated to the same version as the
+    /// Postgres database configured in the configuration files
+    #[clap(short, long)]
+    sqlite_file: PathBuf,
+    #[clap(short, long, value_enum)]
+    direction: MigrationDirection,
+    /// In dev can specify this to skip confirmation
+    #[clap(long)]
+    skip_confirmation: bool,
+}
+
+#[derive(Error, Debug)]
+pub(super) enum MigrateDatabaseError {
+    #[error("Migrating between SQLite and Postgres requires the cli to be built with the postgres feature")]
+    RequiresPostgresBuild,
+    #[error("Failed to confirm database migration")]
+    MigrationNotConfirmed,
+    #[error("Cannot find SQLite database file {0}")]
+    SqliteFileNotFound(PathBuf),
+    #[error("Database versions differ (SQLite: {sqlite:?}, Postgres: {postgres:?}), both databases need to be migrated to the same version")]
+    DatabaseVersionMismatch {
+        sqlite: Option<String>,
+        postgres: Option<String>,
+    },
+    #[error("Table {0} has different columns in the SQLite and the Postgres database")]
+    SchemaMismatch(String),
+    #[error("Invalid value in {table}.{column}: {value}")]
+    InvalidValue {
+        table: String,
+        column: String,
+        value: String,
+    },
+    #[error("Row counts or checksums differ for tables {0:?}, target database was not changed")]
+    VerificationFailed(Vec<String>),
+    #[error(transparent)]
+    StdIO(#[from] io::Error),
+    #[error(transparent)]
+    SqliteError(#[from] rusqlite::Error),
+    #[error(transparent)]
+    PostgresError(#[from] diesel::result::Error),
+    #[error(transparent)]
+    DatabaseError(#[from] RepositoryError),
+}
+
+/// How values are normalised, so that they are written correctly to the other backend and the
+/// checksums of both databases can be compared
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColumnKind {
+    Boolean,
+    Integer,
+    Float,
+    Timestamp,
+    Date,
+    Text,
+}
+
+#[derive(Debug, Clone)]
+struct Column {
+    name: String,
+    kind: ColumnKind,
+    /// Column is backed by a Postgres sequence, e.g. changelog cursor
+    is_serial: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Table {
+    name: String,
+    columns: Vec<Column>,
+}
+
+/// Normalised values in the column order of the table
+type Row = Vec<Value>;
+
+/// Order independent checksum of all rows in a table
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct TableChecksum {
+    rows: u64,
+    checksum: u64,
+}
+
+impl TableChecksum {
+    /// Sum of the first 8 bytes of the sha256 of each row, unlike `DefaultHasher` this doesn't
+    /// change between Rust versions so printed checksums can be compared across builds
+    fn add(&mut self, row: &Row) {
+        let digest = Sha256::digest(serde_json::to_string(row).unwrap_or_default());
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        self.rows += 1;
+        self.checksum = self.checksum.wrapping_add(u64::from_be_bytes(bytes));
+    }
+}
+
+/// Both backends are accessed through this, rows are normalised when they are read
+trait MigrationDatabase {
+    fn database_version(&mut self) -> Result<Option<String>, MigrateDatabaseError>;
+    /// Column names of the table, empty if the table doesn't exist
+    fn column_names(&mut self, table: &str) -> Result<Vec<String>, MigrateDatabaseError>;
+    /// Starts a transaction, writes are done with triggers and foreign key checks disabled so
+    /// that tables can be copied in any order without creating changelog entries
+    fn begin(&mut self, write: bool) -> Result<(), MigrateDatabaseError>;
+    fn read_rows(
+        &mut self,
+        table: &Table,
+        on_rows: &mut dyn FnMut(Vec<Row>) -> Result<(), MigrateDatabaseError>,
+    ) -> Result<(), MigrateDatabaseError>;
+    fn clear_table(&mut self, table: &Table) -> Result<(), MigrateDatabaseError>;
+    fn insert_rows(&mut self, table: &Table, rows: &[Row]) -> Result<(), MigrateDatabaseError>;
+    /// Called once all tables are written, before the verification
+    fn finish_write(&mut self, tables: &[Table]) -> Result<(), MigrateDatabaseError>;
+    fn commit(&mut self) -> Result<(), MigrateDatabaseError>;
+    fn rollback(&mut self) -> Result<(), MigrateDatabaseError>;
+}
+
+struct TableReport {
+    table: String,
+    source: TableChecksum,
+    target: TableChecksum,
+}
+
+pub(crate) fn migrate_database(
+    settings: &Settings,
+    MigrateDatabaseArguments {
+        sqlite_file,
+        direction,
+        skip_confirmation,
+    }: MigrateDatabaseArguments,
+) -> Result<(), MigrateDatabaseError> {
+    if !cfg!(feature = "postgres") {
+        return Err(MigrateDatabaseError::RequiresPostgresBuild);
+    }
+    if !sqlite_file.is_file() {
+        return Err(MigrateDatabaseError::SqliteFileNotFound(sqlite_file));
+    }
+
+    confirmation(skip_confirmation, &direction)?;
+
+    let connection_manager = get_storage_connection_manager(&settings.database);
+    let connection = connection_manager.connection()?;
+    let mut postgres = PostgresDatabase::new(&connection);
+    let mut sqlite = SqliteDatabase::open(&sqlite_file)?;
+
+    let sqlite_version = sqlite.database_version()?;
+    let postgres_version = postgres.database_version()?;
+    if sqlite_version != postgres_version {
+        return Err(MigrateDatabaseError::DatabaseVersionMismatch {
+            sqlite: sqlite_version,
+            postgres: postgres_version,
+        });
+    }
+
+    // Postgres column types are used for both databases, SQLite columns are loosely typed
+    let tables = postgres.tables()?;
+    for table in &tables {
+        let mut postgres_columns: Vec<&str> =
+            table.columns.iter().map(|c| c.name.as_str()).collect();
+        let mut sqlite_columns = sqlite.column_names(&table.name)?;
+        postgres_columns.sort_unstable();
+        sqlite_columns.sort_unstable();
+        if postgres_columns != sqlite_columns {
+            return Err(MigrateDatabaseError::SchemaMismatch(table.name.clone()));
+        }
+    }
+
+    let reports = match direction {
+        MigrationDirection::SqliteToPostgres => copy_tables(&mut sqlite, &mut postgres, &tables)?,
+        MigrationDirection::PostgresToSqlite => copy_tables(&mut postgres, &mut sqlite, &tables)?,
+    };
+
+    for TableReport {
+        table,
+        source,
+        target,
+    } in reports
+    {
+        info!(
+            "{table}: {} rows copied, checksum {:016x} (source {} rows, checksum {:016x})",
+            target.rows, target.checksum, source.rows, source.checksum
+        );
+    }
+    info!("Database migration completed");
+
+    Ok(())
+}
+
+/// Replaces all data of the target database with the data of the source database, within one
+/// transaction that is only committed if row counts and checksums of all tables match
+fn copy_tables(
+    source: &mut dyn MigrationDatabase,
+    target: &mut dyn MigrationDatabase,
+    tables: &[Table],
+) -> Result<Vec<TableReport>, MigrateDatabaseError> {
+    source.begin(false)?;
+    target.begin(true)?;
+
+    let result = copy_and_verify(source, target, tables);
+    // Source is only read
+    source.rollback()?;
+    match result {
+        Ok(reports) => {
+            target.commit()?;
+            Ok(reports)
+        }
+        Err(error) => {
+            target.rollback()?;
+            Err(error)
+        }
+    }
+}
+
+fn copy_and_verify(
+    source: &mut dyn MigrationDatabase,
+    target: &mut dyn MigrationDatabase,
+    tables: &[Table],
+) -> Result<Vec<TableReport>, MigrateDatabaseError> {
+    let mut source_checksums = Vec::new();
+    for table in tables {
+        info!("Copying {}", table.name);
+        target.clear_table(table)?;
+
+        let mut checksum = TableChecksum::default();
+        source.read_rows(table, &mut |rows| {
+            rows.iter().for_each(|row| checksum.add(row));
+            target.insert_rows(table, &rows)
+        })?;
+        source_checksums.push(checksum);
+    }
+    target.finish_write(tables)?;
+
+    let mut reports = Vec::new();
+    for (table, source_checksum) in tables.iter().zip(source_checksums) {
+        let mut checksum = TableChecksum::default();
+        target.read_rows(table, &mut |rows| {
+            rows.iter().for_each(|row| checksum.add(row));
+            Ok(())
+        })?;
+        reports.push(TableReport {
+            table: table.name.clone(),
+            source: source_checksum,
+            target: checksum,
+        });
+    }
+
+    let mismatched: Vec<String> = reports
+        .iter()
+        .filter(|report| report.source != report.target)
+        .map(|report| report.table.clone())
+        .collect();
+    if !mismatched.is_empty() {
+        return Err(MigrateDatabaseError::VerificationFailed(mismatched));
+    }
+
+    Ok(reports)
+}
+
+fn confirmation(
+    skip_confirmation: bool,
+    direction: &MigrationDirection,
+) -> Result<(), MigrateDatabaseError> {
+    if is_develop() && skip_confirmation {
+        return Ok(());
+    }
+
+    let target = match direction {
+        MigrationDirection::SqliteToPostgres => "Postgres",
+        MigrationDirection::PostgresToSqlite => "SQLite",
+    };
+    let confirmation = "I understand";
+    // The prompt needs an answer, it's always written to the terminal unlike the log output
+    println!(
+        r#"This operation will replace all data in the {target} database, the omSupply server must not be running. Are you sure (please type "{confirmation}" to continue): "#
+    );
+    let mut buffer = String::new();
+    io::stdin().read_line(&mut buffer)?;
+
+    if buffer.to_lowercase().trim() != confirmation.to_lowercase() {
+        return Err(MigrateDatabaseError::MigrationNotConfirmed);
+    }
+
+    Ok(())
+}
+
+const TIMESTAMP_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+
+fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    TIMESTAMP_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+}
+
+/// Brings a value read from either backend into the same representation, e.g. SQLite booleans
+/// are stored as integers and timestamps as text with nanosecond precision
+fn normalise(value: Value, kind: ColumnKind) -> Result<Value, Value> {
+    let normalised = match (kind, &value) {
+        (_, Value::Null) => Some(Value::Null),
+        (ColumnKind::Boolean, Value::Bool(_)) => Some(value.clone()),
+        (ColumnKind::Boolean, Value::Number(number)) => {
+            number.as_i64().map(|number| Value::Bool(number != 0))
+        }
+        (ColumnKind::Integer, Value::Number(number)) => number
+            .as_i64()
+            .or_else(|| {
+                number
+                    .as_f64()
+                    .filter(|f| f.fract() == 0.0)
+                    .map(|f| f as i64)
+            })
+            .map(Value::from),
+        (ColumnKind::Integer, Value::String(string)) => string.parse::<i64>().ok().map(Value::from),
+        (ColumnKind::Float, Value::Number(number)) => number
+            .as_f64()
+            .and_then(Number::from_f64)
+            .map(Value::Number),
+        (ColumnKind::Float, Value::String(string)) => string
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map(Value::Number),
+        (ColumnKind::Timestamp, Value::String(string)) => parse_timestamp(string)
+            // Postgres only stores microseconds
+            .and_then(|datetime| datetime.with_nanosecond(datetime.nanosecond() / 1000 * 1000))
+            .map(|datetime| Value::String(datetime.format(TIMESTAMP_FORMATS[0]).to_string())),
+        // SQLite default for timestamp columns
+        (ColumnKind::Timestamp, Value::Number(number)) => number
+            .as_i64()
+            .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+            .map(|datetime| {
+                Value::String(
+                    datetime
+                        .naive_utc()
+                        .format(TIMESTAMP_FORMATS[0])
+                        .to_string(),
+                )
+            }),
+        (ColumnKind::Date, Value::String(string)) => NaiveDate::parse_from_str(string, "%Y-%m-%d")
+            .ok()
+            .map(|date| Value::String(date.to_string())),
+        (ColumnKind::Text, Value::String(_)) => Some(value.clone()),
+        (ColumnKind::Text, _) => Some(Value::String(value.to_string())),
+        _ => None,
+    };
+
+    normalised.ok_or(value)
+}
+
+fn normalise_row(
+    table: &Table,
+    values: impl IntoIterator<Item = Value>,
+) -> Result<Row, MigrateDatabaseError> {
+    table
+        .columns
+        .iter()
+        .zip(values)
+        .map(|(column, value)| {
+            normalise(value, column.kind).map_err(|value| MigrateDatabaseError::InvalidValue {
+                table: table.name.clone(),
+                column: column.name.clone(),
+                value: value.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{normalise_row, Column, ColumnKind, Table, TableChecksum};
+
+    #[test]
+    fn checksums_match_across_backends() {
+        let column = |name: &str, kind| Column {
+            name: name.to_string(),
+            kind,
+            is_serial: false,
+        };
+        let table = Table {
+            name: "invoice".to_string(),
+            columns: vec![
+                column("id", ColumnKind::Text),
+                column("is_active", ColumnKind::Boolean),
+                column("cursor", ColumnKind::Integer),
+                column("total", ColumnKind::Float),
+                column("created_datetime", ColumnKind::Timestamp),
+                column("verified_datetime", ColumnKind::Timestamp),
+                column("expiry_date", ColumnKind::Date),
+            ],
+        };
+
+        // As read from SQLite and from Postgres (row_to_json)
+        let sqlite = vec![
+            json!("invoice_a"),
+            json!(1),
+            json!(5),
+            json!(2.0),
+            json!("2024-06-01 10:15:30.123456789"),
+            json!(0),
+            json!("2025-01-31"),
+        ];
+        let postgres = vec![
+            json!("invoice_a"),
+            json!(true),
+            json!(5),
+            json!(2),
+            json!("2024-06-01T10:15:30.123456"),
+            json!("1970-01-01T00:00:00"),
+            json!("2025-01-31"),
+        ];
+
+        let sqlite = normalise_row(&table, sqlite).unwrap();
+        let postgres = normalise_row(&table, postgres).unwrap();
+        assert_eq!(sqlite, postgres);
+
+        let mut sqlite_checksum = TableChecksum::default();
+        sqlite_checksum.add(&sqlite);
+        let mut postgres_checksum = TableChecksum::default();
+        postgres_checksum.add(&postgres);
+        assert_eq!(sqlite_checksum, postgres_checksum);
+
+        // Changed rows change the checksum
+        let mut changed = postgres.clone();
+        changed[1] = json!(false);
+        let mut changed_checksum = TableChecksum::default();
+        changed_checksum.add(&changed);
+        assert_ne!(changed_checksum, postgres_checksum);
+
+        // Invalid values are reported
+        assert!(normalise_row(&table, vec![json!("invoice_a"), json!("yes")]).is_err());
+    }
+}
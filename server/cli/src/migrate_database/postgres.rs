@@ -0,0 +1,237 @@
+use diesel::{
+    connection::SimpleConnection,
+    sql_query,
+    sql_types::{Nullable, Text},
+    QueryableByName, RunQueryDsl,
+};
+use repository::StorageConnection;
+use serde_json::{Map, Value};
+
+use super::{
+    normalise_row, quote, Column, ColumnKind, MigrateDatabaseError, MigrationDatabase, Row, Table,
+    BATCH_SIZE, EXCLUDED_TABLES,
+};
+
+const CURSOR_NAME: &str = "migrate_database_rows";
+
+#[derive(QueryableByName)]
+struct NameRow {
+    #[diesel(sql_type = Text)]
+    name: String,
+}
+
+#[derive(QueryableByName)]
+struct ColumnRow {
+    #[diesel(sql_type = Text)]
+    name: String,
+    #[diesel(sql_type = Text)]
+    data_type: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    column_default: Option<String>,
+}
+
+#[derive(QueryableByName)]
+struct JsonRow {
+    #[diesel(sql_type = Text)]
+    data: String,
+}
+
+#[derive(QueryableByName)]
+struct VersionRow {
+    #[diesel(sql_type = Nullable<Text>)]
+    value_string: Option<String>,
+}
+
+pub(super) struct PostgresDatabase<'a> {
+    connection: &'a StorageConnection,
+}
+
+fn column_kind(data_type: &str) -> ColumnKind {
+    match data_type {
+        "boolean" => ColumnKind::Boolean,
+        "smallint" | "integer" | "bigint" => ColumnKind::Integer,
+        "real" | "double precision" | "numeric" => ColumnKind::Float,
+        "timestamp without time zone" | "timestamp with time zone" => ColumnKind::Timestamp,
+        "date" => ColumnKind::Date,
+        // text, enums and json
+        _ => ColumnKind::Text,
+    }
+}
+
+impl<'a> PostgresDatabase<'a> {
+    pub(super) fn new(connection: &'a StorageConnection) -> Self {
+        PostgresDatabase { connection }
+    }
+
+    fn execute(&self, sql: &str) -> Result<(), MigrateDatabaseError> {
+        self.connection.lock().connection().batch_execute(sql)?;
+        Ok(())
+    }
+
+    fn columns(&self, table: &str) -> Result<Vec<ColumnRow>, MigrateDatabaseError> {
+        let columns = sql_query(
+            r#"
+                SELECT column_name::text AS name, data_type::text, column_default::text
+                FROM information_schema.columns
+                WHERE table_schema = current_schema() AND table_name = $1
+                ORDER BY ordinal_position
+            "#,
+        )
+        .bind::<Text, _>(table)
+        .load::<ColumnRow>(self.connection.lock().connection())?;
+        Ok(columns)
+    }
+
+    /// All tables with their column types
+    pub(super) fn tables(&self) -> Result<Vec<Table>, MigrateDatabaseError> {
+        let names = sql_query(
+            r#"
+                SELECT table_name::text AS name
+                FROM information_schema.tables
+                WHERE table_schema = current_schema() AND table_type = 'BASE TABLE'
+                ORDER BY table_name
+            "#,
+        )
+        .load::<NameRow>(self.connection.lock().connection())?;
+
+        let mut tables = Vec::new();
+        for NameRow { name } in names {
+            if EXCLUDED_TABLES.contains(&name.as_str()) {
+                continue;
+            }
+            let columns = self
+                .columns(&name)?
+                .into_iter()
+                .map(|column| Column {
+                    kind: column_kind(&column.data_type),
+                    is_serial: column
+                        .column_default
+                        .is_some_and(|default| default.starts_with("nextval(")),
+                    name: column.name,
+                })
+                .collect();
+            tables.push(Table { name, columns });
+        }
+
+        Ok(tables)
+    }
+}
+
+impl<'a> MigrationDatabase for PostgresDatabase<'a> {
+    fn database_version(&mut self) -> Result<Option<String>, MigrateDatabaseError> {
+        let version =
+            sql_query("SELECT value_string FROM key_value_store WHERE id = 'DATABASE_VERSION'")
+                .load::<VersionRow>(self.connection.lock().connection())?
+                .pop()
+                .and_then(|row| row.value_string);
+        Ok(version)
+    }
+
+    fn column_names(&mut self, table: &str) -> Result<Vec<String>, MigrateDatabaseError> {
+        Ok(self
+            .columns(table)?
+            .into_iter()
+            .map(|column| column.name)
+            .collect())
+    }
+
+    fn begin(&mut self, write: bool) -> Result<(), MigrateDatabaseError> {
+        if write {
+            // Disables triggers, including foreign key checks and changelog triggers
+            self.execute("BEGIN; SET LOCAL session_replication_role = replica;")
+        } else {
+            self.execute("BEGIN ISOLATION LEVEL REPEATABLE READ READ ONLY;")
+        }
+    }
+
+    fn read_rows(
+        &mut self,
+        table: &Table,
+        on_rows: &mut dyn FnMut(Vec<Row>) -> Result<(), MigrateDatabaseError>,
+    ) -> Result<(), MigrateDatabaseError> {
+        self.execute(&format!(
+            "DECLARE {CURSOR_NAME} NO SCROLL CURSOR FOR SELECT row_to_json(t)::text AS data FROM {} t;",
+            quote(&table.name)
+        ))?;
+        loop {
+            let json_rows = sql_query(format!("FETCH FORWARD {BATCH_SIZE} FROM {CURSOR_NAME}"))
+                .load::<JsonRow>(self.connection.lock().connection())?;
+            if json_rows.is_empty() {
+                break;
+            }
+
+            let mut rows = Vec::new();
+            for json_row in json_rows {
+                let mut object: Map<String, Value> =
+                    serde_json::from_str(&json_row.data).map_err(|error| {
+                        MigrateDatabaseError::InvalidValue {
+                            table: table.name.clone(),
+                            column: "*".to_string(),
+                            value: error.to_string(),
+                        }
+                    })?;
+                let values = table
+                    .columns
+                    .iter()
+                    .map(|column| object.remove(&column.name).unwrap_or(Value::Null));
+                rows.push(normalise_row(table, values)?);
+            }
+            on_rows(rows)?;
+        }
+        self.execute(&format!("CLOSE {CURSOR_NAME};"))
+    }
+
+    fn clear_table(&mut self, table: &Table) -> Result<(), MigrateDatabaseError> {
+        self.execute(&format!("DELETE FROM {};", quote(&table.name)))
+    }
+
+    fn insert_rows(&mut self, table: &Table, rows: &[Row]) -> Result<(), MigrateDatabaseError> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let objects: Vec<Value> = rows
+            .iter()
+            .map(|row| {
+                let object: Map<String, Value> = table
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .map(|(column, value)| (column.name.clone(), value.clone()))
+                    .collect();
+                Value::Object(object)
+            })
+            .collect();
+
+        let table_name = quote(&table.name);
+        // Postgres converts the json values to the column types, e.g. enums and timestamps
+        sql_query(format!(
+            "INSERT INTO {table_name} SELECT * FROM json_populate_recordset(NULL::{table_name}, $1::json)"
+        ))
+        .bind::<Text, _>(Value::Array(objects).to_string())
+        .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    fn finish_write(&mut self, tables: &[Table]) -> Result<(), MigrateDatabaseError> {
+        // Sequences continue after the copied values, e.g. for new changelog cursors
+        for table in tables {
+            for column in table.columns.iter().filter(|column| column.is_serial) {
+                let table_name = quote(&table.name);
+                let column_name = quote(&column.name);
+                self.execute(&format!(
+                    "SELECT setval(pg_get_serial_sequence('{table_name}', '{}'), COALESCE(MAX({column_name}), 0) + 1, false) FROM {table_name};",
+                    column.name
+                ))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn commit(&mut self) -> Result<(), MigrateDatabaseError> {
+        self.execute("COMMIT;")
+    }
+
+    fn rollback(&mut self) -> Result<(), MigrateDatabaseError> {
+        self.execute("ROLLBACK;")
+    }
+}
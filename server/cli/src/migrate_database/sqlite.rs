@@ -0,0 +1,333 @@
+use std::path::Path;
+
+use rusqlite::{
+    params_from_iter,
+    types::{Value as SqliteValue, ValueRef},
+    Connection, OptionalExtension,
+};
+use serde_json::{Number, Value};
+
+use super::{
+    normalise_row, parse_timestamp, quote, ColumnKind, MigrateDatabaseError, MigrationDatabase,
+    Row, Table, BATCH_SIZE,
+};
+
+pub(super) struct SqliteDatabase {
+    connection: Connection,
+    /// Triggers dropped while writing, they are recreated before committing
+    triggers: Vec<String>,
+}
+
+impl SqliteDatabase {
+    pub(super) fn open(path: &Path) -> Result<Self, MigrateDatabaseError> {
+        Ok(SqliteDatabase {
+            connection: Connection::open(path)?,
+            triggers: Vec::new(),
+        })
+    }
+}
+
+/// Blobs are not used in the omSupply schema
+fn to_json(value: ValueRef) -> Option<Value> {
+    let value = match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(integer) => Value::from(integer),
+        ValueRef::Real(real) => Number::from_f64(real)
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        ValueRef::Text(text) => Value::String(String::from_utf8_lossy(text).to_string()),
+        ValueRef::Blob(_) => return None,
+    };
+    Some(value)
+}
+
+/// Stores values the way diesel does for SQLite, e.g. booleans as integers
+fn to_sqlite(value: &Value, kind: ColumnKind) -> SqliteValue {
+    match (value, kind) {
+        (Value::Null, _) => SqliteValue::Null,
+        (Value::Bool(bool), _) => SqliteValue::Integer(*bool as i64),
+        (Value::Number(number), ColumnKind::Integer) => {
+            SqliteValue::Integer(number.as_i64().unwrap_or_default())
+        }
+        (Value::Number(number), _) => SqliteValue::Real(number.as_f64().unwrap_or_default()),
+        (Value::String(string), ColumnKind::Timestamp) => match parse_timestamp(string) {
+            Some(datetime) => {
+                SqliteValue::Text(datetime.format("%Y-%m-%d %H:%M:%S%.f").to_string())
+            }
+            None => SqliteValue::Text(string.clone()),
+        },
+        (Value::String(string), _) => SqliteValue::Text(string.clone()),
+        (value, _) => SqliteValue::Text(value.to_string()),
+    }
+}
+
+impl MigrationDatabase for SqliteDatabase {
+    fn database_version(&mut self) -> Result<Option<String>, MigrateDatabaseError> {
+        let version = self
+            .connection
+            .query_row(
+                "SELECT value_string FROM key_value_store WHERE id = 'DATABASE_VERSION'",
+                [],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(version)
+    }
+
+    fn column_names(&mut self, table: &str) -> Result<Vec<String>, MigrateDatabaseError> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT name FROM pragma_table_info(?1)")?;
+        let names = statement
+            .query_map([table], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(names)
+    }
+
+    fn begin(&mut self, write: bool) -> Result<(), MigrateDatabaseError> {
+        if !write {
+            self.connection.execute_batch("BEGIN;")?;
+            return Ok(());
+        }
+
+        // Foreign keys can only be disabled outside of a transaction
+        self.connection
+            .execute_batch("PRAGMA foreign_keys = OFF; BEGIN IMMEDIATE;")?;
+        let mut statement = self
+            .connection
+            .prepare("SELECT name, sql FROM sqlite_master WHERE type = 'trigger'")?;
+        let triggers = statement
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<Result<Vec<(String, String)>, _>>()?;
+        drop(statement);
+
+        for (name, sql) in triggers {
+            self.connection
+                .execute_batch(&format!("DROP TRIGGER {};", quote(&name)))?;
+            self.triggers.push(sql);
+        }
+        Ok(())
+    }
+
+    fn read_rows(
+        &mut self,
+        table: &Table,
+        on_rows: &mut dyn FnMut(Vec<Row>) -> Result<(), MigrateDatabaseError>,
+    ) -> Result<(), MigrateDatabaseError> {
+        let column_list = table
+            .columns
+            .iter()
+            .map(|column| quote(&column.name))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let mut statement = self
+            .connection
+            .prepare(&format!("SELECT {column_list} FROM {}", quote(&table.name)))?;
+        let mut result = statement.query([])?;
+
+        let mut rows = Vec::new();
+        while let Some(row) = result.next()? {
+            let mut values = Vec::new();
+            for (index, column) in table.columns.iter().enumerate() {
+                let value = to_json(row.get_ref(index)?).ok_or_else(|| {
+                    MigrateDatabaseError::InvalidValue {
+                        table: table.name.clone(),
+                        column: column.name.clone(),
+                        value: "blob".to_string(),
+                    }
+                })?;
+                values.push(value);
+            }
+            rows.push(normalise_row(table, values)?);
+            if rows.len() == BATCH_SIZE {
+                on_rows(std::mem::take(&mut rows))?;
+            }
+        }
+        if !rows.is_empty() {
+            on_rows(rows)?;
+        }
+        Ok(())
+    }
+
+    fn clear_table(&mut self, table: &Table) -> Result<(), MigrateDatabaseError> {
+        self.connection
+            .execute_batch(&format!("DELETE FROM {};", quote(&table.name)))?;
+        Ok(())
+    }
+
+    fn insert_rows(&mut self, table: &Table, rows: &[Row]) -> Result<(), MigrateDatabaseError> {
+        let column_list = table
+            .columns
+            .iter()
+            .map(|column| quote(&column.name))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let placeholders = vec!["?"; table.columns.len()].join(", ");
+        let mut statement = self.connection.prepare_cached(&format!(
+            "INSERT INTO {} ({column_list}) VALUES ({placeholders})",
+            quote(&table.name)
+        ))?;
+
+        for row in rows {
+            let values = table
+                .columns
+                .iter()
+                .zip(row)
+                .map(|(column, value)| to_sqlite(value, column.kind));
+            statement.execute(params_from_iter(values))?;
+        }
+        Ok(())
+    }
+
+    fn finish_write(&mut self, _: &[Table]) -> Result<(), MigrateDatabaseError> {
+        // AUTOINCREMENT sequences are updated by SQLite when inserting explicit values
+        for sql in std::mem::take(&mut self.triggers) {
+            self.connection.execute_batch(&sql)?;
+        }
+        Ok(())
+    }
+
+    fn commit(&mut self) -> Result<(), MigrateDatabaseError> {
+        self.connection
+            .execute_batch("COMMIT; PRAGMA foreign_keys = ON;")?;
+        Ok(())
+    }
+
+    fn rollback(&mut self) -> Result<(), MigrateDatabaseError> {
+        self.triggers.clear();
+        self.connection
+            .execute_batch("ROLLBACK; PRAGMA foreign_keys = ON;")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use serde_json::json;
+
+    use super::{
+        super::{copy_tables, Column, ColumnKind, MigrationDatabase, Table},
+        SqliteDatabase,
+    };
+
+    fn database(rows: &str) -> SqliteDatabase {
+        let database = SqliteDatabase::open(Path::new(":memory:")).unwrap();
+        database
+            .connection
+            .execute_batch(
+                r#"
+                CREATE TABLE invoice (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    is_active BOOLEAN NOT NULL,
+                    total DOUBLE,
+                    created_datetime TIMESTAMP NOT NULL,
+                    expiry_date DATE
+                );
+                CREATE TABLE changelog (
+                    cursor INTEGER PRIMARY KEY AUTOINCREMENT,
+                    record_id TEXT NOT NULL
+                );
+                CREATE TRIGGER invoice_insert_trigger AFTER INSERT ON invoice
+                BEGIN
+                    INSERT INTO changelog (record_id) VALUES (NEW.id);
+                END;
+                "#,
+            )
+            .unwrap();
+        database.connection.execute_batch(rows).unwrap();
+        database
+    }
+
+    fn count(database: &SqliteDatabase, sql: &str) -> i64 {
+        database
+            .connection
+            .query_row(sql, [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let column = |name: &str, kind| Column {
+            name: name.to_string(),
+            kind,
+            is_serial: false,
+        };
+        let tables = vec![Table {
+            name: "invoice".to_string(),
+            columns: vec![
+                column("id", ColumnKind::Text),
+                column("is_active", ColumnKind::Boolean),
+                column("total", ColumnKind::Float),
+                column("created_datetime", ColumnKind::Timestamp),
+                column("expiry_date", ColumnKind::Date),
+            ],
+        }];
+
+        let mut source = database(
+            r#"
+            INSERT INTO invoice VALUES ('invoice_a', 1, 2.5, '2024-06-01 10:15:30.123456', '2025-01-31');
+            INSERT INTO invoice VALUES ('invoice_b', 0, NULL, '2024-06-02 08:00:00', NULL);
+            "#,
+        );
+        let mut target = database(
+            "INSERT INTO invoice VALUES ('invoice_old', 1, 1, '2020-01-01 00:00:00', NULL);",
+        );
+        let changelog_before = count(&target, "SELECT COUNT(*) FROM changelog");
+
+        let reports = copy_tables(&mut source, &mut target, &tables).unwrap();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].source.rows, 2);
+        assert_eq!(reports[0].source, reports[0].target);
+
+        let mut rows = Vec::new();
+        target
+            .read_rows(&tables[0], &mut |batch| {
+                rows.extend(batch);
+                Ok(())
+            })
+            .unwrap();
+        rows.sort_by_key(|row| row[0].to_string());
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    json!("invoice_a"),
+                    json!(true),
+                    json!(2.5),
+                    json!("2024-06-01T10:15:30.123456"),
+                    json!("2025-01-31"),
+                ],
+                vec![
+                    json!("invoice_b"),
+                    json!(false),
+                    json!(null),
+                    json!("2024-06-02T08:00:00"),
+                    json!(null),
+                ],
+            ]
+        );
+
+        // Triggers are disabled while copying and restored afterwards
+        assert_eq!(
+            count(&target, "SELECT COUNT(*) FROM changelog"),
+            changelog_before
+        );
+        assert_eq!(
+            count(
+                &target,
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'trigger'"
+            ),
+            1
+        );
+
+        // And back again
+        let mut copy = database("");
+        let reports = copy_tables(&mut target, &mut copy, &tables).unwrap();
+        assert_eq!(reports[0].target.checksum, reports[0].source.checksum);
+    }
+}
@@ -6,6 +6,7 @@ use diesel::sql_query;
 use diesel::sql_types::*;
 use repository::DBType;
 use repository::RepositoryError;
+use repository::StockAggregateRepository;
 use repository::StorageConnection;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -179,6 +180,8 @@ impl<'a> RefreshDatesRepository<'a> {
 
         self.update_timestamps(updated_values.timestamps)?;
         self.update_dates(updated_values.dates)?;
+        // Dates are updated directly, bypassing the row repositories
+        StockAggregateRepository::new(self.connection).rebuild()?;
         Ok(Some((max_timestamp, days_adjustment)))
     }
 
@@ -37,6 +37,7 @@ pub trait ContextExt {
     fn service_provider(&self) -> &ServiceProvider;
     fn get_auth_data(&self) -> &AuthData;
     fn get_auth_token(&self) -> Option<String>;
+    fn get_client_ip(&self) -> Option<String>;
     fn self_request(&self) -> Option<&BoxedSelfRequest>;
     fn get_settings(&self) -> &Settings;
     fn get_validated_plugins(&self) -> &Mutex<ValidatedPluginBucket>;
@@ -65,6 +66,11 @@ impl<'a> ContextExt for Context<'a> {
             .and_then(|d| d.auth_token.to_owned())
     }
 
+    fn get_client_ip(&self) -> Option<String> {
+        self.data_opt::<RequestUserData>()
+            .and_then(|d| d.client_ip.to_owned())
+    }
+
     fn get_settings(&self) -> &Settings {
         self.data_unchecked::<Data<Settings>>()
     }
@@ -87,6 +93,8 @@ impl<'a> ContextExt for Context<'a> {
 pub struct RequestUserData {
     auth_token: Option<String>,
     pub refresh_token: Option<String>,
+    /// Address of the connected client, used to limit failed login attempts
+    client_ip: Option<String>,
 }
 
 pub fn auth_data_from_request(http_req: &HttpRequest) -> RequestUserData {
@@ -120,9 +128,12 @@ pub fn auth_data_from_request(http_req: &HttpRequest) -> RequestUserData {
             .map(|cookie| cookie.value().to_owned())
     });
 
+    let client_ip = http_req.peer_addr().map(|address| address.ip().to_string());
+
     RequestUserData {
         auth_token,
         refresh_token,
+        client_ip,
     }
 }
 
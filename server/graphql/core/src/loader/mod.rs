@@ -27,6 +27,7 @@ mod name;
 mod name_row;
 mod packaging_variant;
 mod patient;
+mod prescription_line_direction;
 mod program_enrolment;
 mod reason_option;
 mod requisition;
@@ -36,6 +37,7 @@ mod return_reason;
 mod rnr_form_line;
 mod sensor;
 mod stock_line;
+mod stocktake;
 mod stocktake_lines;
 mod store;
 mod sync_file_reference;
@@ -57,6 +59,7 @@ pub use asset_location::*;
 pub use asset_log_reason::*;
 pub use asset_status_log::*;
 pub use asset_type::*;
+pub use bundled_item::*;
 pub use clinician::*;
 pub use cold_storage_type::*;
 pub use demographic::*;
@@ -69,7 +72,6 @@ pub use item::ItemLoader;
 pub use item_stats::*;
 pub use item_stock_on_hand::*;
 pub use item_variant::*;
-pub use bundled_item::*;
 pub use json_schema::*;
 pub use loader_registry::{get_loaders, LoaderMap, LoaderRegistry};
 pub use location::LocationByIdLoader;
@@ -78,6 +80,7 @@ pub use name::*;
 pub use name_row::*;
 pub use packaging_variant::*;
 pub use patient::*;
+pub use prescription_line_direction::*;
 pub use program_enrolment::*;
 pub use reason_option::ReasonOptionLoader;
 pub use requisition::*;
@@ -87,6 +90,7 @@ pub use return_reason::*;
 pub use rnr_form_line::*;
 pub use sensor::*;
 pub use stock_line::*;
+pub use stocktake::*;
 pub use stocktake_lines::*;
 pub use store::*;
 pub use sync_file_reference::*;
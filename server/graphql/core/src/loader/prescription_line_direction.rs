@@ -0,0 +1,33 @@
+use repository::{
+    PrescriptionLineDirectionRow, PrescriptionLineDirectionRowRepository, RepositoryError,
+    StorageConnectionManager,
+};
+
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use std::collections::HashMap;
+
+/// Loads prescription directions by invoice line id
+pub struct PrescriptionLineDirectionLoader {
+    pub connection_manager: StorageConnectionManager,
+}
+
+impl Loader<String> for PrescriptionLineDirectionLoader {
+    type Value = PrescriptionLineDirectionRow;
+    type Error = RepositoryError;
+
+    async fn load(
+        &self,
+        invoice_line_ids: &[String],
+    ) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let connection = self.connection_manager.connection()?;
+        let repo = PrescriptionLineDirectionRowRepository::new(&connection);
+
+        let result = repo.find_many_by_invoice_line_ids(invoice_line_ids)?;
+
+        Ok(result
+            .into_iter()
+            .map(|direction| (direction.invoice_line_id.clone(), direction))
+            .collect())
+    }
+}
@@ -0,0 +1,31 @@
+use async_graphql::dataloader::*;
+use repository::{
+    EqualFilter, Pagination, RepositoryError, Stocktake, StocktakeFilter, StocktakeRepository,
+    StorageConnectionManager,
+};
+use std::collections::HashMap;
+
+pub struct StocktakeByIdLoader {
+    pub connection_manager: StorageConnectionManager,
+}
+
+impl Loader<String> for StocktakeByIdLoader {
+    type Value = Stocktake;
+    type Error = RepositoryError;
+
+    async fn load(&self, ids: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let connection = self.connection_manager.connection()?;
+        let repo = StocktakeRepository::new(&connection);
+
+        let result = repo.query(
+            Pagination::all(),
+            Some(StocktakeFilter::new().id(EqualFilter::equal_any(ids.to_owned()))),
+            None,
+        )?;
+
+        Ok(result
+            .into_iter()
+            .map(|stocktake| (stocktake.id.clone(), stocktake))
+            .collect())
+    }
+}
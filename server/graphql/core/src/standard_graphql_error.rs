@@ -4,6 +4,7 @@ use async_graphql::{Context, ErrorExtensions, Result};
 use repository::RepositoryError;
 use service::{
     auth::{AuthDeniedKind, AuthError, ResourceAccessRequest, ValidatedUser},
+    log_context::LogContext,
     ListError,
 };
 use thiserror::Error;
@@ -84,6 +85,9 @@ pub fn validate_auth(
         &ctx.get_auth_token(),
         access_request,
     );
+    if let Ok(user) = &result {
+        LogContext::set_user(&user.user_id, access_request.store_id.as_deref());
+    }
     result.map_err(|err| {
         let graphql_error = match err {
             AuthError::Denied(kind) => match kind {
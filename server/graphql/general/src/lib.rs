@@ -11,10 +11,12 @@ use service::sync::CentralServerConfig;
 
 use crate::store_preference::store_preferences;
 use graphql_types::types::{
-    CurrenciesResponse, CurrencyFilterInput, CurrencySortInput, MasterListFilterInput,
-    StorePreferenceNode,
+    AllocationRuleNode, CurrenciesResponse, CurrencyFilterInput, CurrencySortInput, DeleteResponse,
+    MasterListFilterInput, StorePreferenceNode,
 };
 use mutations::{
+    allocation_rule::{delete_allocation_rule, upsert_allocation_rule, UpsertAllocationRuleInput},
+    api_key::{create_api_key, revoke_api_key, CreateApiKeyInput, CreatedApiKeyNode},
     barcode::{insert_barcode, BarcodeInput},
     common::SyncSettingsInput,
     display_settings::{
@@ -26,12 +28,20 @@ use mutations::{
         UpdateLabelPrinterSettingsResponse,
     },
     log::{update_log_level, LogLevelInput, UpsertLogLevelResponse},
+    login_security_settings::{update_login_security_settings, LoginSecuritySettingsInput},
     manual_sync::manual_sync,
+    pricing::{
+        delete_price_list, delete_tax_rule, set_name_price_category, upsert_price_list,
+        upsert_tax_rule, NamePriceCategoryNode, SetNamePriceCategoryInput, UpsertPriceListInput,
+        UpsertTaxRuleInput,
+    },
+    support_bundle::{generate_support_bundle, GenerateSupportBundleInput, SupportBundleNode},
     sync_settings::{update_sync_settings, UpdateSyncSettingsResponse},
     update_name_properties::{
         update_name_properties, UpdateNamePropertiesInput, UpdateNamePropertiesResponse,
     },
     update_user,
+    user_pin::{remove_user_pin, set_user_pin, SetUserPinInput, UserPinNode},
 };
 use queries::{
     currency::currencies,
@@ -62,6 +72,18 @@ impl GeneralQueries {
         login(ctx, &username, &password).await
     }
 
+    /// Switches to another user with the PIN they set on this device, the refresh token is
+    /// returned as a cookie
+    pub async fn pin_auth_token(
+        &self,
+        ctx: &Context<'_>,
+        username: String,
+        #[graphql(desc = "Token issued to this device when the PIN was set")] device_token: String,
+        pin: String,
+    ) -> Result<AuthTokenResponse> {
+        pin_login(ctx, &username, &device_token, &pin).await
+    }
+
     pub async fn item_price(
         &self,
         ctx: &Context<'_>,
@@ -71,6 +93,28 @@ impl GeneralQueries {
         item_price(ctx, store_id, input).await
     }
 
+    /// Price lists available to the store, including lists for all stores
+    pub async fn price_lists(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<PriceListNode>> {
+        price_lists(ctx, store_id)
+    }
+
+    pub async fn tax_rules(&self, ctx: &Context<'_>, store_id: String) -> Result<Vec<TaxRuleNode>> {
+        tax_rules(ctx, store_id)
+    }
+
+    /// Outbound shipment allocation rules of the store, highest priority first
+    pub async fn allocation_rules(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<AllocationRuleNode>> {
+        allocation_rules(ctx, store_id)
+    }
+
     pub async fn logout(&self, ctx: &Context<'_>) -> Result<LogoutResponse> {
         logout(ctx)
     }
@@ -274,6 +318,15 @@ impl GeneralQueries {
         item_counts(ctx, store_id, low_stock_threshold)
     }
 
+    /// Stock and supply KPIs per store, over a number of consecutive periods
+    pub async fn dashboard_kpis(
+        &self,
+        ctx: &Context<'_>,
+        input: DashboardKpisInput,
+    ) -> Result<Vec<StoreKpisNode>> {
+        dashboard_kpis(ctx, input)
+    }
+
     pub async fn store_preferences(
         &self,
         ctx: &Context<'_>,
@@ -307,8 +360,9 @@ impl GeneralQueries {
         &self,
         ctx: &Context<'_>,
         file_name: Option<String>,
+        filter: Option<LogFilterInput>,
     ) -> Result<LogNode> {
-        log_content(ctx, file_name)
+        log_content(ctx, file_name, filter)
     }
 
     pub async fn log_level(&self, ctx: &Context<'_>) -> Result<LogLevelNode> {
@@ -409,6 +463,19 @@ impl GeneralQueries {
     ) -> Result<ColdStorageTypesResponse> {
         cold_storage_types(ctx, store_id, page, filter, sort)
     }
+
+    /// API keys used by machine-to-machine integrations
+    pub async fn api_keys(&self, ctx: &Context<'_>) -> Result<Vec<ApiKeyNode>> {
+        api_keys(ctx)
+    }
+
+    /// Failed login lockout and password policy settings of this site
+    pub async fn login_security_settings(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<LoginSecuritySettingsNode> {
+        login_security_settings(ctx)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -463,6 +530,15 @@ impl GeneralMutations {
         update_log_level(ctx, store_id, input)
     }
 
+    /// Generates a zip of logs, settings and diagnostics for troubleshooting a site
+    pub async fn generate_support_bundle(
+        &self,
+        ctx: &Context<'_>,
+        input: Option<GenerateSupportBundleInput>,
+    ) -> Result<SupportBundleNode> {
+        generate_support_bundle(ctx, input)
+    }
+
     pub async fn update_user(&self, ctx: &Context<'_>) -> Result<update_user::UpdateResponse> {
         update_user::update_user(ctx).await
     }
@@ -483,6 +559,63 @@ impl GeneralMutations {
     ) -> Result<UpdateNamePropertiesResponse> {
         update_name_properties(ctx, &store_id, input)
     }
+
+    pub async fn create_api_key(
+        &self,
+        ctx: &Context<'_>,
+        input: CreateApiKeyInput,
+    ) -> Result<CreatedApiKeyNode> {
+        create_api_key(ctx, input)
+    }
+
+    pub async fn revoke_api_key(&self, ctx: &Context<'_>, id: String) -> Result<ApiKeyNode> {
+        revoke_api_key(ctx, &id)
+    }
+
+    pub async fn update_login_security_settings(
+        &self,
+        ctx: &Context<'_>,
+        input: LoginSecuritySettingsInput,
+    ) -> Result<LoginSecuritySettingsNode> {
+        update_login_security_settings(ctx, input)
+    }
+
+    /// Sets a PIN for quick switching to the logged in user on this device
+    pub async fn set_user_pin(
+        &self,
+        ctx: &Context<'_>,
+        input: SetUserPinInput,
+    ) -> Result<UserPinNode> {
+        set_user_pin(ctx, input)
+    }
+
+    pub async fn remove_user_pin(
+        &self,
+        ctx: &Context<'_>,
+        device_token: String,
+    ) -> Result<DeleteResponse> {
+        remove_user_pin(ctx, &device_token)
+    }
+
+    /// How placeholder lines of outbound shipments are allocated, for the customers with the
+    /// name tag or for every customer of the store
+    pub async fn upsert_allocation_rule(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpsertAllocationRuleInput,
+    ) -> Result<AllocationRuleNode> {
+        upsert_allocation_rule(ctx, &store_id, input)
+    }
+
+    pub async fn delete_allocation_rule(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<DeleteResponse> {
+        delete_allocation_rule(ctx, &store_id, id)
+    }
 }
 
 /// Auth is not checked during initialisation stage
@@ -555,4 +688,38 @@ impl CentralGeneralMutations {
     ) -> Result<ConfigureNamePropertiesResponse> {
         configure_name_properties(ctx, input)
     }
+
+    /// Lines of the price list are replaced by the input lines
+    pub async fn upsert_price_list(
+        &self,
+        ctx: &Context<'_>,
+        input: UpsertPriceListInput,
+    ) -> Result<PriceListNode> {
+        upsert_price_list(ctx, input)
+    }
+
+    pub async fn delete_price_list(&self, ctx: &Context<'_>, id: String) -> Result<DeleteResponse> {
+        delete_price_list(ctx, id)
+    }
+
+    pub async fn upsert_tax_rule(
+        &self,
+        ctx: &Context<'_>,
+        input: UpsertTaxRuleInput,
+    ) -> Result<TaxRuleNode> {
+        upsert_tax_rule(ctx, input)
+    }
+
+    pub async fn delete_tax_rule(&self, ctx: &Context<'_>, id: String) -> Result<DeleteResponse> {
+        delete_tax_rule(ctx, id)
+    }
+
+    /// Price category of a customer or patient, used to select price lists and tax rules
+    pub async fn set_name_price_category(
+        &self,
+        ctx: &Context<'_>,
+        input: SetNamePriceCategoryInput,
+    ) -> Result<NamePriceCategoryNode> {
+        set_name_price_category(ctx, input)
+    }
 }
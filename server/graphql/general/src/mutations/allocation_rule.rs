@@ -0,0 +1,124 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{AllocationRuleNode, AllocationStrategyNodeType, DeleteResponse};
+use service::{
+    allocation_rule::{DeleteAllocationRuleError, UpsertAllocationRule, UpsertAllocationRuleError},
+    auth::{Resource, ResourceAccessRequest},
+};
+
+#[derive(InputObject)]
+pub struct UpsertAllocationRuleInput {
+    pub id: String,
+    /// Applies to every customer of the store if not set
+    pub name_tag_id: Option<String>,
+    pub strategy: AllocationStrategyNodeType,
+    /// Overrides the default expiring soon offset of 6 weeks
+    pub expiring_soon_days: Option<i32>,
+    /// Stock expiring within this many days is not allocated
+    pub min_remaining_shelf_life_days: Option<i32>,
+    /// Stock lines of this item variant are not allocated
+    pub avoid_item_variant_id: Option<String>,
+    /// Higher priority wins when a customer has more than one matching name tag, defaults to 0
+    pub priority: Option<i32>,
+}
+
+pub fn upsert_allocation_rule(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpsertAllocationRuleInput,
+) -> Result<AllocationRuleNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateOutboundShipment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let result = service_provider
+        .allocation_rule_service
+        .upsert_allocation_rule(&service_context, input.to_domain());
+
+    match result {
+        Ok(allocation_rule) => Ok(AllocationRuleNode::from_domain(allocation_rule)),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                UpsertAllocationRuleError::NotThisStoreRule
+                | UpsertAllocationRuleError::NameTagDoesNotExist
+                | UpsertAllocationRuleError::ItemVariantDoesNotExist
+                | UpsertAllocationRuleError::InvalidNumberOfDays => BadUserInput(formatted_error),
+                UpsertAllocationRuleError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn delete_allocation_rule(
+    ctx: &Context<'_>,
+    store_id: &str,
+    id: String,
+) -> Result<DeleteResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateOutboundShipment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let result = service_provider
+        .allocation_rule_service
+        .delete_allocation_rule(&service_context, id);
+
+    match result {
+        Ok(id) => Ok(DeleteResponse(id)),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                DeleteAllocationRuleError::RuleDoesNotExist
+                | DeleteAllocationRuleError::NotThisStoreRule => BadUserInput(formatted_error),
+                DeleteAllocationRuleError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+impl UpsertAllocationRuleInput {
+    pub fn to_domain(self) -> UpsertAllocationRule {
+        let UpsertAllocationRuleInput {
+            id,
+            name_tag_id,
+            strategy,
+            expiring_soon_days,
+            min_remaining_shelf_life_days,
+            avoid_item_variant_id,
+            priority,
+        } = self;
+
+        UpsertAllocationRule {
+            id,
+            name_tag_id,
+            strategy: strategy.into(),
+            expiring_soon_days,
+            min_remaining_shelf_life_days,
+            avoid_item_variant_id,
+            priority: priority.unwrap_or(0),
+        }
+    }
+}
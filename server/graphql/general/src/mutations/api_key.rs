@@ -0,0 +1,129 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    api_key::{CreateApiKey, CreateApiKeyError, CreatedApiKey, RevokeApiKeyError},
+    auth::{Resource, ResourceAccessRequest},
+};
+
+use crate::queries::ApiKeyNode;
+
+#[derive(InputObject)]
+pub struct CreateApiKeyInput {
+    pub id: String,
+    pub name: String,
+    pub store_ids: Vec<String>,
+    /// Names of the resources the key can access, e.g. "QueryStocktake"
+    pub resources: Vec<String>,
+    /// The key never expires if not set
+    pub expiry_datetime: Option<DateTime<Utc>>,
+}
+
+pub struct CreatedApiKeyNode {
+    pub created: CreatedApiKey,
+}
+
+#[Object]
+impl CreatedApiKeyNode {
+    pub async fn api_key(&self) -> ApiKeyNode {
+        ApiKeyNode::from_domain(self.created.api_key.clone())
+    }
+
+    /// The key to use as bearer token, it is only returned once and can't be retrieved later on
+    pub async fn key(&self) -> &str {
+        &self.created.key
+    }
+}
+
+pub fn create_api_key(ctx: &Context<'_>, input: CreateApiKeyInput) -> Result<CreatedApiKeyNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateApiKey,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user.user_id)?;
+    let result = service_provider
+        .api_key_service
+        .create_api_key(&service_context, input.to_domain());
+
+    match result {
+        Ok(created) => Ok(CreatedApiKeyNode { created }),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                CreateApiKeyError::ApiKeyAlreadyExists
+                | CreateApiKeyError::NameCannotBeEmpty
+                | CreateApiKeyError::NoStoresSpecified
+                | CreateApiKeyError::NoResourcesSpecified
+                | CreateApiKeyError::UnknownResource(_)
+                | CreateApiKeyError::StoreNotAccessible(_)
+                | CreateApiKeyError::ExpiryDatetimeInThePast => BadUserInput(formatted_error),
+                CreateApiKeyError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn revoke_api_key(ctx: &Context<'_>, id: &str) -> Result<ApiKeyNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateApiKey,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user.user_id)?;
+    let result = service_provider
+        .api_key_service
+        .revoke_api_key(&service_context, id);
+
+    match result {
+        Ok(api_key) => Ok(ApiKeyNode::from_domain(api_key)),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                RevokeApiKeyError::ApiKeyDoesNotExist | RevokeApiKeyError::ApiKeyAlreadyRevoked => {
+                    BadUserInput(formatted_error)
+                }
+                RevokeApiKeyError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+impl CreateApiKeyInput {
+    pub fn to_domain(self) -> CreateApiKey {
+        let CreateApiKeyInput {
+            id,
+            name,
+            store_ids,
+            resources,
+            expiry_datetime,
+        } = self;
+
+        CreateApiKey {
+            id,
+            name,
+            store_ids,
+            resources,
+            expiry_datetime: expiry_datetime.map(|datetime| datetime.naive_utc()),
+        }
+    }
+}
@@ -1,10 +1,7 @@
 use async_graphql::*;
 use graphql_core::standard_graphql_error::validate_auth;
 use graphql_core::ContextExt;
-use service::{
-    auth::{Resource, ResourceAccessRequest},
-    settings::Level,
-};
+use service::auth::{Resource, ResourceAccessRequest};
 
 use crate::queries::LogLevelEnum;
 
@@ -32,13 +29,7 @@ pub fn update_log_level(
         },
     )?;
 
-    let level = match input.level {
-        LogLevelEnum::Error => Level::Error,
-        LogLevelEnum::Warn => Level::Warn,
-        LogLevelEnum::Info => Level::Info,
-        LogLevelEnum::Debug => Level::Debug,
-        LogLevelEnum::Trace => Level::Trace,
-    };
+    let level = input.level.to_domain();
 
     let service_provider = ctx.service_provider();
     let service_context = service_provider.basic_context()?;
@@ -0,0 +1,106 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    login_security::{LoginSecuritySettings, PasswordPolicy, UpdateLoginSecuritySettingsError},
+};
+
+use crate::queries::LoginSecuritySettingsNode;
+
+#[derive(InputObject)]
+pub struct LoginSecuritySettingsInput {
+    pub max_failed_attempts_per_username: u32,
+    pub max_failed_attempts_per_ip: u32,
+    pub lockout_base_seconds: u64,
+    pub lockout_max_seconds: u64,
+    pub failed_attempts_window_seconds: u64,
+    pub password_min_length: u32,
+    pub password_require_uppercase: bool,
+    pub password_require_lowercase: bool,
+    pub password_require_digit: bool,
+    pub password_require_symbol: bool,
+    pub pin_validity_seconds: u64,
+    pub max_failed_pin_attempts: u32,
+}
+
+pub fn update_login_security_settings(
+    ctx: &Context<'_>,
+    input: LoginSecuritySettingsInput,
+) -> Result<LoginSecuritySettingsNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user.user_id)?;
+    let result = service_provider
+        .login_security_service
+        .update_login_security_settings(&service_context, input.to_domain());
+
+    match result {
+        Ok(settings) => Ok(LoginSecuritySettingsNode::from_domain(settings)),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                UpdateLoginSecuritySettingsError::MaxFailedAttemptsMustBePositive
+                | UpdateLoginSecuritySettingsError::LockoutBaseSecondsMustBePositive
+                | UpdateLoginSecuritySettingsError::LockoutMaxSecondsLessThanBaseSeconds
+                | UpdateLoginSecuritySettingsError::PasswordMinLengthMustBePositive
+                | UpdateLoginSecuritySettingsError::PinValiditySecondsMustBePositive
+                | UpdateLoginSecuritySettingsError::MaxFailedPinAttemptsMustBePositive => {
+                    BadUserInput(formatted_error)
+                }
+                UpdateLoginSecuritySettingsError::DatabaseError(_) => {
+                    InternalError(formatted_error)
+                }
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+impl LoginSecuritySettingsInput {
+    pub fn to_domain(self) -> LoginSecuritySettings {
+        let LoginSecuritySettingsInput {
+            max_failed_attempts_per_username,
+            max_failed_attempts_per_ip,
+            lockout_base_seconds,
+            lockout_max_seconds,
+            failed_attempts_window_seconds,
+            password_min_length,
+            password_require_uppercase,
+            password_require_lowercase,
+            password_require_digit,
+            password_require_symbol,
+            pin_validity_seconds,
+            max_failed_pin_attempts,
+        } = self;
+
+        LoginSecuritySettings {
+            max_failed_attempts_per_username,
+            max_failed_attempts_per_ip,
+            lockout_base_seconds,
+            lockout_max_seconds,
+            failed_attempts_window_seconds,
+            password_policy: PasswordPolicy {
+                min_length: password_min_length,
+                require_uppercase: password_require_uppercase,
+                require_lowercase: password_require_lowercase,
+                require_digit: password_require_digit,
+                require_symbol: password_require_symbol,
+            },
+            pin_validity_seconds,
+            max_failed_pin_attempts,
+        }
+    }
+}
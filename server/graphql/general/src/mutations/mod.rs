@@ -1,10 +1,16 @@
+pub mod allocation_rule;
+pub mod api_key;
 pub mod barcode;
 pub mod common;
 pub mod display_settings;
 pub mod initialise_site;
 pub mod label_printer_settings;
 pub mod log;
+pub mod login_security_settings;
 pub mod manual_sync;
+pub mod pricing;
+pub mod support_bundle;
 pub mod sync_settings;
 pub mod update_name_properties;
 pub mod update_user;
+pub mod user_pin;
@@ -0,0 +1,310 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::DeleteResponse;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    pricing::{
+        price_list::{
+            SetNamePriceCategoryError, UpsertPriceList, UpsertPriceListError, UpsertPriceListLine,
+        },
+        tax_rule::{UpsertTaxRule, UpsertTaxRuleError},
+    },
+};
+
+use crate::queries::{PriceListNode, TaxRuleNode};
+
+#[derive(InputObject)]
+pub struct UpsertPriceListLineInput {
+    pub id: String,
+    pub item_id: String,
+    /// Minimum number of units for the line to apply, defaults to 0
+    pub min_quantity: Option<f64>,
+    /// Default price list price is used if not set
+    pub price_per_unit: Option<f64>,
+    pub discount_percentage: Option<f64>,
+}
+
+#[derive(InputObject)]
+pub struct UpsertPriceListInput {
+    pub id: String,
+    pub name: String,
+    /// Applies to all stores if not set
+    pub store_id: Option<String>,
+    /// Customer or patient the list applies to
+    pub name_id: Option<String>,
+    pub name_tag_id: Option<String>,
+    pub price_category: Option<String>,
+    pub valid_from: Option<NaiveDate>,
+    pub valid_to: Option<NaiveDate>,
+    /// Higher priority wins between lists of the same kind, defaults to 0
+    pub priority: Option<i32>,
+    /// Defaults to true
+    pub is_active: Option<bool>,
+    /// Replaces the existing lines of the price list
+    pub lines: Vec<UpsertPriceListLineInput>,
+}
+
+#[derive(InputObject)]
+pub struct UpsertTaxRuleInput {
+    pub id: String,
+    /// Applies to all items if not set
+    pub item_id: Option<String>,
+    /// Applies to all price categories if not set
+    pub price_category: Option<String>,
+    pub tax_percentage: f64,
+    pub valid_from: Option<NaiveDate>,
+    pub valid_to: Option<NaiveDate>,
+}
+
+#[derive(InputObject)]
+pub struct SetNamePriceCategoryInput {
+    pub name_id: String,
+    /// Clears the price category if not set
+    pub price_category: Option<String>,
+}
+
+pub struct NamePriceCategoryNode {
+    pub name_id: String,
+    pub price_category: Option<String>,
+}
+
+#[Object]
+impl NamePriceCategoryNode {
+    pub async fn name_id(&self) -> &str {
+        &self.name_id
+    }
+
+    pub async fn price_category(&self) -> Option<&str> {
+        self.price_category.as_deref()
+    }
+}
+
+pub fn upsert_price_list(ctx: &Context<'_>, input: UpsertPriceListInput) -> Result<PriceListNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutatePricing,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user.user_id)?;
+    let result = service_provider
+        .pricing_service
+        .upsert_price_list(&service_context, input.to_domain());
+
+    match result {
+        Ok(price_list) => Ok(PriceListNode { price_list }),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                UpsertPriceListError::NameCannotBeEmpty
+                | UpsertPriceListError::StoreDoesNotExist
+                | UpsertPriceListError::NameDoesNotExist
+                | UpsertPriceListError::NameTagDoesNotExist
+                | UpsertPriceListError::ValidToBeforeValidFrom
+                | UpsertPriceListError::ItemDoesNotExist(_)
+                | UpsertPriceListError::LineHasNoPrice(_)
+                | UpsertPriceListError::PriceCannotBeNegative(_)
+                | UpsertPriceListError::InvalidDiscountPercentage(_)
+                | UpsertPriceListError::MinQuantityCannotBeNegative(_)
+                | UpsertPriceListError::DuplicateQuantityBreak(_) => BadUserInput(formatted_error),
+                UpsertPriceListError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn delete_price_list(ctx: &Context<'_>, id: String) -> Result<DeleteResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutatePricing,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user.user_id)?;
+    let id = service_provider
+        .pricing_service
+        .delete_price_list(&service_context, id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(DeleteResponse(id))
+}
+
+pub fn upsert_tax_rule(ctx: &Context<'_>, input: UpsertTaxRuleInput) -> Result<TaxRuleNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutatePricing,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user.user_id)?;
+    let result = service_provider
+        .pricing_service
+        .upsert_tax_rule(&service_context, input.to_domain());
+
+    match result {
+        Ok(tax_rule) => Ok(TaxRuleNode { tax_rule }),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                UpsertTaxRuleError::ItemDoesNotExist
+                | UpsertTaxRuleError::InvalidTaxPercentage
+                | UpsertTaxRuleError::ValidToBeforeValidFrom => BadUserInput(formatted_error),
+                UpsertTaxRuleError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn delete_tax_rule(ctx: &Context<'_>, id: String) -> Result<DeleteResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutatePricing,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user.user_id)?;
+    let id = service_provider
+        .pricing_service
+        .delete_tax_rule(&service_context, id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(DeleteResponse(id))
+}
+
+pub fn set_name_price_category(
+    ctx: &Context<'_>,
+    input: SetNamePriceCategoryInput,
+) -> Result<NamePriceCategoryNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutatePricing,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user.user_id)?;
+    let SetNamePriceCategoryInput {
+        name_id,
+        price_category,
+    } = input;
+    let result = service_provider.pricing_service.set_name_price_category(
+        &service_context,
+        name_id.clone(),
+        price_category,
+    );
+
+    match result {
+        Ok(row) => Ok(NamePriceCategoryNode {
+            name_id,
+            price_category: row.map(|row| row.price_category),
+        }),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                SetNamePriceCategoryError::NameDoesNotExist => BadUserInput(formatted_error),
+                SetNamePriceCategoryError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+impl UpsertPriceListInput {
+    pub fn to_domain(self) -> UpsertPriceList {
+        let UpsertPriceListInput {
+            id,
+            name,
+            store_id,
+            name_id,
+            name_tag_id,
+            price_category,
+            valid_from,
+            valid_to,
+            priority,
+            is_active,
+            lines,
+        } = self;
+
+        UpsertPriceList {
+            id,
+            name,
+            store_id,
+            name_id,
+            name_tag_id,
+            price_category,
+            valid_from,
+            valid_to,
+            priority: priority.unwrap_or(0),
+            is_active: is_active.unwrap_or(true),
+            lines: lines
+                .into_iter()
+                .map(
+                    |UpsertPriceListLineInput {
+                         id,
+                         item_id,
+                         min_quantity,
+                         price_per_unit,
+                         discount_percentage,
+                     }| UpsertPriceListLine {
+                        id,
+                        item_id,
+                        min_quantity: min_quantity.unwrap_or(0.0),
+                        price_per_unit,
+                        discount_percentage,
+                    },
+                )
+                .collect(),
+        }
+    }
+}
+
+impl UpsertTaxRuleInput {
+    pub fn to_domain(self) -> UpsertTaxRule {
+        let UpsertTaxRuleInput {
+            id,
+            item_id,
+            price_category,
+            tax_percentage,
+            valid_from,
+            valid_to,
+        } = self;
+
+        UpsertTaxRule {
+            id,
+            item_id,
+            price_category,
+            tax_percentage,
+            valid_from,
+            valid_to,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    support_bundle::{SupportBundleError, SupportBundleInput},
+};
+
+#[derive(InputObject)]
+pub struct GenerateSupportBundleInput {
+    /// Include a copy of the database with patient data and secrets removed (sqlite only)
+    pub include_database_extract: Option<bool>,
+}
+
+#[derive(SimpleObject)]
+pub struct SupportBundleNode {
+    /// Id of the zip file, download with /files?id={file_id}
+    pub file_id: String,
+}
+
+pub fn generate_support_bundle(
+    ctx: &Context<'_>,
+    input: Option<GenerateSupportBundleInput>,
+) -> Result<SupportBundleNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+
+    let input = SupportBundleInput {
+        include_database_extract: input
+            .and_then(|input| input.include_database_extract)
+            .unwrap_or(false),
+    };
+
+    let result = service_provider
+        .support_bundle_service
+        .export_support_bundle(
+            &service_context,
+            service_provider,
+            ctx.get_settings(),
+            input,
+        );
+
+    match result {
+        Ok(file_id) => Ok(SupportBundleNode { file_id }),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                SupportBundleError::DatabaseExtractNotSupported => BadUserInput(formatted_error),
+                SupportBundleError::DatabaseError(_) | SupportBundleError::FileError(_) => {
+                    InternalError(formatted_error)
+                }
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
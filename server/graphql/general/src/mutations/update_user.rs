@@ -80,6 +80,7 @@ pub async fn update_user(ctx: &Context<'_>) -> Result<UpdateResponse> {
                 | LoginError::UpdateUserError(_)
                 | LoginError::LoginFailure(LoginFailure::AccountBlocked(_))
                 | LoginError::LoginFailure(LoginFailure::NoSiteAccess)
+                | LoginError::LoginFailure(LoginFailure::PinNotAvailable)
                 | LoginError::InternalError(_)
                 | LoginError::DatabaseError(_)
                 | LoginError::FailedToGenerateToken(_) 
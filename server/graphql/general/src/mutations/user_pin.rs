@@ -0,0 +1,107 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::DeleteResponse;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    user_pin::{SetUserPin, SetUserPinError, SetUserPinResult},
+};
+
+#[derive(InputObject)]
+pub struct SetUserPinInput {
+    /// Token returned when a PIN was set on this device before, a new token is issued when empty
+    pub device_token: Option<String>,
+    /// 4 to 8 digits
+    pub pin: String,
+    /// Password of the logged in user, required to set a PIN
+    pub password: String,
+}
+
+pub struct UserPinNode {
+    pub result: SetUserPinResult,
+}
+
+#[Object]
+impl UserPinNode {
+    /// To be stored on the device, it's required to switch user with the PIN
+    pub async fn device_token(&self) -> &str {
+        &self.result.device_token
+    }
+
+    /// The PIN can't be used after this, a full login is required to set a new PIN
+    pub async fn expiry_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.result.user_pin.expiry_datetime, Utc)
+    }
+}
+
+pub fn set_user_pin(ctx: &Context<'_>, input: SetUserPinInput) -> Result<UserPinNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::RouteMe,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user.user_id)?;
+    let result = service_provider
+        .user_pin_service
+        .set_user_pin(&service_context, input.to_domain());
+
+    match result {
+        Ok(result) => Ok(UserPinNode { result }),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                SetUserPinError::InvalidPassword | SetUserPinError::InvalidPin => {
+                    BadUserInput(formatted_error)
+                }
+                SetUserPinError::UserDoesNotExist
+                | SetUserPinError::PinHashError(_)
+                | SetUserPinError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn remove_user_pin(ctx: &Context<'_>, device_token: &str) -> Result<DeleteResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::RouteMe,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context("".to_string(), user.user_id)?;
+    service_provider
+        .user_pin_service
+        .remove_user_pin(&service_context, device_token)?;
+
+    Ok(DeleteResponse(device_token.to_string()))
+}
+
+impl SetUserPinInput {
+    pub fn to_domain(self) -> SetUserPin {
+        let SetUserPinInput {
+            device_token,
+            pin,
+            password,
+        } = self;
+
+        SetUserPin {
+            device_token,
+            pin,
+            password,
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::AllocationRuleNode;
+use service::auth::{Resource, ResourceAccessRequest};
+
+pub fn allocation_rules(ctx: &Context<'_>, store_id: String) -> Result<Vec<AllocationRuleNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStorePreferences,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let allocation_rules = service_provider
+        .allocation_rule_service
+        .get_allocation_rules(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(allocation_rules
+        .into_iter()
+        .map(AllocationRuleNode::from_domain)
+        .collect())
+}
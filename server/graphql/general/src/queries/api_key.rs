@@ -0,0 +1,99 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{standard_graphql_error::validate_auth, ContextExt};
+use repository::ApiKeyRow;
+use service::{
+    api_key::ApiKeyScope,
+    auth::{Resource, ResourceAccessRequest},
+};
+
+#[derive(PartialEq, Debug)]
+pub struct ApiKeyNode {
+    pub api_key: ApiKeyRow,
+}
+
+#[Object]
+impl ApiKeyNode {
+    pub async fn id(&self) -> &str {
+        &self.api_key.id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.api_key.name
+    }
+
+    /// First characters of the key, to help identifying it
+    pub async fn key_prefix(&self) -> &str {
+        &self.api_key.key_prefix
+    }
+
+    /// User the key acts on behalf of
+    pub async fn user_id(&self) -> &str {
+        &self.api_key.user_id
+    }
+
+    pub async fn store_ids(&self) -> Vec<String> {
+        self.scope().store_ids
+    }
+
+    pub async fn resources(&self) -> Vec<String> {
+        self.scope()
+            .resources
+            .iter()
+            .map(|resource| format!("{:?}", resource))
+            .collect()
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.api_key.created_datetime, Utc)
+    }
+
+    pub async fn expiry_datetime(&self) -> Option<DateTime<Utc>> {
+        self.api_key
+            .expiry_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+
+    pub async fn last_used_datetime(&self) -> Option<DateTime<Utc>> {
+        self.api_key
+            .last_used_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+
+    pub async fn revoked_datetime(&self) -> Option<DateTime<Utc>> {
+        self.api_key
+            .revoked_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+}
+
+impl ApiKeyNode {
+    pub fn from_domain(api_key: ApiKeyRow) -> Self {
+        ApiKeyNode { api_key }
+    }
+
+    fn scope(&self) -> ApiKeyScope {
+        ApiKeyScope::from_row(&self.api_key).unwrap_or(ApiKeyScope {
+            store_ids: Vec::new(),
+            resources: Vec::new(),
+        })
+    }
+}
+
+pub fn api_keys(ctx: &Context<'_>) -> Result<Vec<ApiKeyNode>> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryApiKey,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+    let api_keys = service_provider
+        .api_key_service
+        .get_api_keys(&service_context)?;
+
+    Ok(api_keys.into_iter().map(ApiKeyNode::from_domain).collect())
+}
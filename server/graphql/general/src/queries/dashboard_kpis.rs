@@ -0,0 +1,129 @@
+use async_graphql::*;
+use chrono::{NaiveDate, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    dashboard::kpi::{KpiError, KpiInput, KpiPeriodLength, KpiPeriodValues, StoreKpis},
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(remote = "service::dashboard::kpi::KpiPeriodLength")]
+pub enum KpiPeriodLengthInput {
+    Week,
+    Month,
+    Quarter,
+}
+
+#[derive(InputObject)]
+pub struct DashboardKpisInput {
+    pub store_ids: Vec<String>,
+    pub period_length: KpiPeriodLengthInput,
+    pub number_of_periods: u32,
+    /// The last period is the one containing this date, defaults to today
+    pub end_date: Option<NaiveDate>,
+}
+
+pub struct StoreKpisNode {
+    store_kpis: StoreKpis,
+}
+
+pub struct KpiPeriodNode {
+    values: KpiPeriodValues,
+}
+
+#[Object]
+impl StoreKpisNode {
+    pub async fn store_id(&self) -> &str {
+        &self.store_kpis.store_id
+    }
+
+    pub async fn periods(&self) -> Vec<KpiPeriodNode> {
+        self.store_kpis
+            .periods
+            .iter()
+            .cloned()
+            .map(|values| KpiPeriodNode { values })
+            .collect()
+    }
+}
+
+#[Object]
+impl KpiPeriodNode {
+    pub async fn start_date(&self) -> NaiveDate {
+        self.values.period.start_date
+    }
+
+    pub async fn end_date(&self) -> NaiveDate {
+        self.values.period.end_date
+    }
+
+    pub async fn stockout_rate(&self) -> Option<f64> {
+        self.values.stockout_rate
+    }
+
+    pub async fn order_fill_rate(&self) -> Option<f64> {
+        self.values.order_fill_rate
+    }
+
+    pub async fn average_lead_time_days(&self) -> Option<f64> {
+        self.values.average_lead_time_days
+    }
+
+    pub async fn stock_value(&self) -> f64 {
+        self.values.stock_value
+    }
+
+    pub async fn expiry_wastage_value(&self) -> f64 {
+        self.values.expiry_wastage_value
+    }
+}
+
+pub fn dashboard_kpis(ctx: &Context<'_>, input: DashboardKpisInput) -> Result<Vec<StoreKpisNode>> {
+    for store_id in &input.store_ids {
+        validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryDashboardKpis,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+    }
+
+    let service_provider = ctx.service_provider();
+    let service_ctx = service_provider.basic_context()?;
+
+    let result = service_provider.kpi_service.get_kpis(
+        &service_ctx,
+        KpiInput {
+            store_ids: input.store_ids,
+            period_length: KpiPeriodLength::from(input.period_length),
+            number_of_periods: input.number_of_periods,
+            end_date: input
+                .end_date
+                .unwrap_or_else(|| Utc::now().naive_utc().date()),
+        },
+    );
+
+    let store_kpis = match result {
+        Ok(store_kpis) => store_kpis,
+        Err(error) => {
+            let formatted_error = format!("{:#?}", error);
+            let graphql_error = match error {
+                KpiError::NoStoresSelected | KpiError::InvalidNumberOfPeriods => {
+                    StandardGraphqlError::BadUserInput(formatted_error)
+                }
+                KpiError::DatabaseError(_) => StandardGraphqlError::InternalError(formatted_error),
+            };
+            return Err(graphql_error.extend());
+        }
+    };
+
+    Ok(store_kpis
+        .into_iter()
+        .map(|store_kpis| StoreKpisNode { store_kpis })
+        .collect())
+}
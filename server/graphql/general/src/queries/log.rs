@@ -1,7 +1,9 @@
 use async_graphql::*;
+use chrono::{DateTime, Utc};
 use graphql_core::{standard_graphql_error::validate_auth, ContextExt};
 use service::{
     auth::{Resource, ResourceAccessRequest},
+    log_context::LogLineFilter,
     settings::Level,
 };
 
@@ -29,11 +31,51 @@ pub enum LogLevelEnum {
     Trace,
 }
 
+impl LogLevelEnum {
+    pub fn to_domain(self) -> Level {
+        match self {
+            LogLevelEnum::Error => Level::Error,
+            LogLevelEnum::Warn => Level::Warn,
+            LogLevelEnum::Info => Level::Info,
+            LogLevelEnum::Debug => Level::Debug,
+            LogLevelEnum::Trace => Level::Trace,
+        }
+    }
+}
+
 #[derive(SimpleObject)]
 pub struct LogLevelNode {
     pub level: LogLevelEnum,
 }
 
+/// Filters only apply to structured (JSON) log lines, plain text lines are excluded when filtering
+#[derive(InputObject)]
+pub struct LogFilterInput {
+    /// Include lines of this level and more severe levels
+    pub level: Option<LogLevelEnum>,
+    pub from_datetime: Option<DateTime<Utc>>,
+    pub to_datetime: Option<DateTime<Utc>>,
+    pub correlation_id: Option<String>,
+}
+
+impl LogFilterInput {
+    pub fn to_domain(self) -> LogLineFilter {
+        let LogFilterInput {
+            level,
+            from_datetime,
+            to_datetime,
+            correlation_id,
+        } = self;
+
+        LogLineFilter {
+            level: level.map(LogLevelEnum::to_domain),
+            from_datetime,
+            to_datetime,
+            correlation_id,
+        }
+    }
+}
+
 pub fn log_file_names(ctx: &Context<'_>) -> Result<LogNode> {
     validate_auth(
         ctx,
@@ -51,7 +93,11 @@ pub fn log_file_names(ctx: &Context<'_>) -> Result<LogNode> {
     Ok(LogNode::from_domain(Some(file_names), None))
 }
 
-pub fn log_content(ctx: &Context<'_>, file_name: Option<String>) -> Result<LogNode> {
+pub fn log_content(
+    ctx: &Context<'_>,
+    file_name: Option<String>,
+    filter: Option<LogFilterInput>,
+) -> Result<LogNode> {
     validate_auth(
         ctx,
         &ResourceAccessRequest {
@@ -63,7 +109,11 @@ pub fn log_content(ctx: &Context<'_>, file_name: Option<String>) -> Result<LogNo
     let service_provider = ctx.service_provider();
     let service_context = service_provider.basic_context()?;
     let log_service = &service_provider.log_service;
-    let content = log_service.get_log_content(&service_context, file_name)?;
+    let content = log_service.get_log_content(
+        &service_context,
+        file_name,
+        filter.map(LogFilterInput::to_domain),
+    )?;
 
     Ok(LogNode::from_domain(Some(vec![content.0]), Some(content.1)))
 }
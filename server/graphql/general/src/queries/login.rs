@@ -4,7 +4,7 @@ use graphql_core::{standard_graphql_error::StandardGraphqlError, ContextExt};
 
 use http2::header::SET_COOKIE;
 use service::{
-    login::{LoginError, LoginFailure, LoginInput, LoginService},
+    login::{LoginError, LoginFailure, LoginInput, LoginService, PinLoginInput},
     token::TokenPair,
 };
 
@@ -55,7 +55,6 @@ impl CentralSyncRequired {
     }
 }
 
-
 pub struct AccountBlocked {
     pub timeout_remaining: u64,
 }
@@ -71,6 +70,14 @@ impl AccountBlocked {
     }
 }
 
+pub struct PinNotAvailable;
+#[Object]
+impl PinNotAvailable {
+    pub async fn description(&self) -> &str {
+        "No valid PIN for this user on this device, a full login is required"
+    }
+}
+
 #[derive(Interface)]
 #[graphql(field(name = "description", ty = "&str"))]
 pub enum AuthTokenErrorInterface {
@@ -78,6 +85,7 @@ pub enum AuthTokenErrorInterface {
     AccountBlocked(AccountBlocked),
     NoSiteAccess(NoSiteAccess),
     CentralSyncRequired(CentralSyncRequired),
+    PinNotAvailable(PinNotAvailable),
 }
 
 #[derive(SimpleObject)]
@@ -102,18 +110,53 @@ pub async fn login(ctx: &Context<'_>, username: &str, password: &str) -> Result<
             "Sync settings not available".to_string(),
         ))?;
 
-    let pair = match LoginService::login(
+    let result = LoginService::login(
         service_provider,
         auth_data,
         LoginInput {
             username: username.to_string(),
             password: password.to_string(),
             central_server_url: sync_settings.url.clone(),
+            client_ip: ctx.get_client_ip(),
+        },
+        MIN_ERR_RESPONSE_TIME_SEC,
+    )
+    .await;
+
+    auth_token_response(ctx, result)
+}
+
+/// Switches to the user with the PIN they set on this device
+pub async fn pin_login(
+    ctx: &Context<'_>,
+    username: &str,
+    device_token: &str,
+    pin: &str,
+) -> Result<AuthTokenResponse> {
+    let service_provider = ctx.service_provider();
+    let auth_data = ctx.get_auth_data();
+
+    let result = LoginService::pin_login(
+        service_provider,
+        auth_data,
+        PinLoginInput {
+            username: username.to_string(),
+            device_token: device_token.to_string(),
+            pin: pin.to_string(),
+            client_ip: ctx.get_client_ip(),
         },
         MIN_ERR_RESPONSE_TIME_SEC,
     )
-    .await
-    {
+    .await;
+
+    auth_token_response(ctx, result)
+}
+
+fn auth_token_response(
+    ctx: &Context<'_>,
+    result: Result<TokenPair, LoginError>,
+) -> Result<AuthTokenResponse> {
+    let pair = match result {
         Ok(pair) => pair,
         Err(error) => {
             let formatted_error = format!("{:#?}", error);
@@ -132,7 +175,7 @@ pub async fn login(ctx: &Context<'_>, username: &str, password: &str) -> Result<
                 }
                 LoginError::MSupplyCentralNotReached => {
                     return Ok(AuthTokenResponse::Error(AuthTokenError {
-                        error: AuthTokenErrorInterface::CentralSyncRequired(CentralSyncRequired)
+                        error: AuthTokenErrorInterface::CentralSyncRequired(CentralSyncRequired),
                     }))
                 }
                 LoginError::LoginFailure(LoginFailure::NoSiteAccess) => {
@@ -140,6 +183,11 @@ pub async fn login(ctx: &Context<'_>, username: &str, password: &str) -> Result<
                         error: AuthTokenErrorInterface::NoSiteAccess(NoSiteAccess),
                     }))
                 }
+                LoginError::LoginFailure(LoginFailure::PinNotAvailable) => {
+                    return Ok(AuthTokenResponse::Error(AuthTokenError {
+                        error: AuthTokenErrorInterface::PinNotAvailable(PinNotAvailable),
+                    }))
+                }
                 LoginError::FailedToGenerateToken(_) => {
                     StandardGraphqlError::InternalError(formatted_error)
                 }
@@ -165,7 +213,7 @@ pub async fn login(ctx: &Context<'_>, username: &str, password: &str) -> Result<
         ctx,
         &pair.refresh,
         pair.refresh_expiry_date - now,
-        auth_data.no_ssl,
+        ctx.get_auth_data().no_ssl,
     );
 
     Ok(AuthTokenResponse::Response(AuthToken { pair }))
@@ -0,0 +1,76 @@
+use async_graphql::*;
+use graphql_core::{standard_graphql_error::validate_auth, ContextExt};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    login_security::LoginSecuritySettings,
+};
+
+#[derive(SimpleObject)]
+pub struct LoginSecuritySettingsNode {
+    /// Number of consecutive failed logins for a username before it is locked
+    pub max_failed_attempts_per_username: u32,
+    /// Number of consecutive failed logins from a client ip before it is locked
+    pub max_failed_attempts_per_ip: u32,
+    /// Duration of the first lockout, doubled for every further failed attempt
+    pub lockout_base_seconds: u64,
+    pub lockout_max_seconds: u64,
+    /// Failed attempts older than this are forgotten
+    pub failed_attempts_window_seconds: u64,
+    pub password_min_length: u32,
+    pub password_require_uppercase: bool,
+    pub password_require_lowercase: bool,
+    pub password_require_digit: bool,
+    pub password_require_symbol: bool,
+    /// How long a user PIN can be used for quick user switching before a full login is required
+    pub pin_validity_seconds: u64,
+    /// Number of wrong PIN entries after which the PIN is removed
+    pub max_failed_pin_attempts: u32,
+}
+
+impl LoginSecuritySettingsNode {
+    pub fn from_domain(from: LoginSecuritySettings) -> LoginSecuritySettingsNode {
+        let LoginSecuritySettings {
+            max_failed_attempts_per_username,
+            max_failed_attempts_per_ip,
+            lockout_base_seconds,
+            lockout_max_seconds,
+            failed_attempts_window_seconds,
+            password_policy,
+            pin_validity_seconds,
+            max_failed_pin_attempts,
+        } = from;
+
+        LoginSecuritySettingsNode {
+            max_failed_attempts_per_username,
+            max_failed_attempts_per_ip,
+            lockout_base_seconds,
+            lockout_max_seconds,
+            failed_attempts_window_seconds,
+            password_min_length: password_policy.min_length,
+            password_require_uppercase: password_policy.require_uppercase,
+            password_require_lowercase: password_policy.require_lowercase,
+            password_require_digit: password_policy.require_digit,
+            password_require_symbol: password_policy.require_symbol,
+            pin_validity_seconds,
+            max_failed_pin_attempts,
+        }
+    }
+}
+
+pub(crate) fn login_security_settings(ctx: &Context<'_>) -> Result<LoginSecuritySettingsNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+    let settings = service_provider
+        .login_security_service
+        .get_login_security_settings(&service_context)?;
+
+    Ok(LoginSecuritySettingsNode::from_domain(settings))
+}
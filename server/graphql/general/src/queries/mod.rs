@@ -28,6 +28,8 @@ pub mod store;
 pub use self::store::*;
 pub mod activity_log;
 pub use self::activity_log::*;
+pub mod api_key;
+pub use self::api_key::*;
 pub mod database_settings;
 pub use self::database_settings::*;
 pub mod display_settings;
@@ -43,6 +45,8 @@ pub mod inventory_adjustment_reason;
 pub use self::inventory_adjustment_reason::*;
 pub mod item_counts;
 pub use self::item_counts::*;
+pub mod dashboard_kpis;
+pub use self::dashboard_kpis::*;
 pub mod barcode;
 pub mod requisition_counts;
 pub mod store_preference;
@@ -56,8 +60,12 @@ pub use self::plugin::*;
 pub mod currency;
 pub mod label_printer_settings;
 pub use self::label_printer_settings::*;
+pub mod login_security_settings;
+pub use self::login_security_settings::*;
 pub mod pricing;
 pub use self::pricing::*;
+pub mod allocation_rule;
+pub use self::allocation_rule::*;
 pub mod reason_option;
 pub use self::reason_option::*;
 
@@ -1,23 +1,43 @@
 use async_graphql::*;
+use chrono::NaiveDate;
 use graphql_core::{
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
 };
 
+use repository::{PriceListLineRow, PriceListRow, TaxRuleRow};
 use service::{
     auth::{Resource, ResourceAccessRequest},
-    pricing::item_price::{ItemPrice, ItemPriceLookup},
+    pricing::{
+        price_list::PriceList,
+        resolve_price::{ResolvePrice, ResolvedPrice},
+    },
 };
 
 #[derive(InputObject, Clone)]
 pub struct ItemPriceInput {
     item_id: String,
     name_id: Option<String>, // Name Id could be used to get discount for a specific name
+    /// Number of units, used to select quantity breaks, defaults to 0
+    quantity: Option<f64>,
+    /// Defaults to today
+    date: Option<NaiveDate>,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "service::pricing::resolve_price::PriceRuleType")]
+pub enum PriceRuleTypeNode {
+    CustomerPriceList,
+    NameTagPriceList,
+    PriceCategoryPriceList,
+    GeneralPriceList,
+    DefaultPriceList,
+    NoPrice,
 }
 
 #[derive(PartialEq, Debug)]
 pub struct ItemPriceNode {
-    pricing: ItemPrice,
+    pricing: ResolvedPrice,
 }
 
 #[Object]
@@ -27,7 +47,7 @@ impl ItemPriceNode {
     }
 
     pub async fn default_price_per_unit(&self) -> Option<f64> {
-        self.pricing.default_price_per_unit
+        self.pricing.base_price_per_unit
     }
 
     pub async fn discount_percentage(&self) -> Option<f64> {
@@ -35,7 +55,46 @@ impl ItemPriceNode {
     }
 
     pub async fn calculated_price_per_unit(&self) -> Option<f64> {
-        self.pricing.calculated_price_per_unit
+        self.pricing.price_per_unit
+    }
+
+    /// Rule the price was taken from
+    pub async fn rule(&self) -> PriceRuleTypeNode {
+        PriceRuleTypeNode::from(self.pricing.rule)
+    }
+
+    pub async fn price_list_id(&self) -> Option<&str> {
+        self.pricing
+            .price_list
+            .as_ref()
+            .map(|list| list.id.as_str())
+    }
+
+    pub async fn price_list_name(&self) -> Option<&str> {
+        self.pricing
+            .price_list
+            .as_ref()
+            .map(|list| list.name.as_str())
+    }
+
+    /// Quantity break that applied
+    pub async fn min_quantity(&self) -> Option<f64> {
+        self.pricing
+            .price_list_line
+            .as_ref()
+            .map(|line| line.min_quantity)
+    }
+
+    pub async fn price_category(&self) -> Option<&str> {
+        self.pricing.price_category.as_deref()
+    }
+
+    pub async fn tax_percentage(&self) -> Option<f64> {
+        self.pricing.tax_percentage()
+    }
+
+    pub async fn tax_rule_id(&self) -> Option<&str> {
+        self.pricing.tax_rule.as_ref().map(|rule| rule.id.as_str())
     }
 }
 
@@ -62,19 +121,199 @@ pub async fn item_price(
 
     let pricing = service_provider
         .pricing_service
-        .get_pricing_for_item(&service_context, input.to_domain())
+        .resolve_price(&service_context, input.to_domain())
         .map_err(|e| StandardGraphqlError::from_repository_error(e))?;
 
     Ok(ItemPriceResponse::Response(ItemPriceNode { pricing }))
 }
 
 impl ItemPriceInput {
-    pub fn to_domain(self) -> ItemPriceLookup {
-        let ItemPriceInput { name_id, item_id } = self;
+    pub fn to_domain(self) -> ResolvePrice {
+        let ItemPriceInput {
+            name_id,
+            item_id,
+            quantity,
+            date,
+        } = self;
 
-        ItemPriceLookup {
-            customer_name_id: name_id,
+        ResolvePrice {
             item_id,
+            name_id,
+            quantity: quantity.unwrap_or(0.0),
+            date,
         }
     }
 }
+
+#[derive(PartialEq, Debug)]
+pub struct PriceListLineNode {
+    pub price_list_line: PriceListLineRow,
+}
+
+#[Object]
+impl PriceListLineNode {
+    pub async fn id(&self) -> &str {
+        &self.price_list_line.id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.price_list_line.item_id
+    }
+
+    /// Minimum number of units for the line to apply
+    pub async fn min_quantity(&self) -> f64 {
+        self.price_list_line.min_quantity
+    }
+
+    pub async fn price_per_unit(&self) -> Option<f64> {
+        self.price_list_line.price_per_unit
+    }
+
+    pub async fn discount_percentage(&self) -> Option<f64> {
+        self.price_list_line.discount_percentage
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct PriceListNode {
+    pub price_list: PriceList,
+}
+
+#[Object]
+impl PriceListNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.row().name
+    }
+
+    /// Applies to all stores if not set
+    pub async fn store_id(&self) -> Option<&str> {
+        self.row().store_id.as_deref()
+    }
+
+    pub async fn name_id(&self) -> Option<&str> {
+        self.row().name_id.as_deref()
+    }
+
+    pub async fn name_tag_id(&self) -> Option<&str> {
+        self.row().name_tag_id.as_deref()
+    }
+
+    pub async fn price_category(&self) -> Option<&str> {
+        self.row().price_category.as_deref()
+    }
+
+    pub async fn valid_from(&self) -> Option<NaiveDate> {
+        self.row().valid_from
+    }
+
+    pub async fn valid_to(&self) -> Option<NaiveDate> {
+        self.row().valid_to
+    }
+
+    pub async fn priority(&self) -> i32 {
+        self.row().priority
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.row().is_active
+    }
+
+    pub async fn lines(&self) -> Vec<PriceListLineNode> {
+        self.price_list
+            .lines
+            .iter()
+            .cloned()
+            .map(|price_list_line| PriceListLineNode { price_list_line })
+            .collect()
+    }
+}
+
+impl PriceListNode {
+    fn row(&self) -> &PriceListRow {
+        &self.price_list.price_list_row
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct TaxRuleNode {
+    pub tax_rule: TaxRuleRow,
+}
+
+#[Object]
+impl TaxRuleNode {
+    pub async fn id(&self) -> &str {
+        &self.tax_rule.id
+    }
+
+    /// Applies to all items if not set
+    pub async fn item_id(&self) -> Option<&str> {
+        self.tax_rule.item_id.as_deref()
+    }
+
+    /// Applies to all price categories if not set
+    pub async fn price_category(&self) -> Option<&str> {
+        self.tax_rule.price_category.as_deref()
+    }
+
+    pub async fn tax_percentage(&self) -> f64 {
+        self.tax_rule.tax_percentage
+    }
+
+    pub async fn valid_from(&self) -> Option<NaiveDate> {
+        self.tax_rule.valid_from
+    }
+
+    pub async fn valid_to(&self) -> Option<NaiveDate> {
+        self.tax_rule.valid_to
+    }
+}
+
+pub fn price_lists(ctx: &Context<'_>, store_id: String) -> Result<Vec<PriceListNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryItems,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let price_lists = service_provider
+        .pricing_service
+        .get_price_lists(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(price_lists
+        .into_iter()
+        .map(|price_list| PriceListNode { price_list })
+        .collect())
+}
+
+pub fn tax_rules(ctx: &Context<'_>, store_id: String) -> Result<Vec<TaxRuleNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryItems,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let tax_rules = service_provider
+        .pricing_service
+        .get_tax_rules(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(tax_rules
+        .into_iter()
+        .map(|tax_rule| TaxRuleNode { tax_rule })
+        .collect())
+}
@@ -0,0 +1,124 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    clinical_check::{ClinicalCheckError, ClinicalWarning},
+    usize_to_u32,
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "repository::DrugInteractionSeverity")]
+pub enum DrugInteractionSeverityType {
+    Minor,
+    Moderate,
+    Major,
+    Contraindicated,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "service::clinical_check::ClinicalWarningType")]
+pub enum ClinicalWarningNodeType {
+    Interaction,
+    DuplicateTherapy,
+}
+
+pub struct ClinicalWarningNode {
+    pub warning: ClinicalWarning,
+}
+
+#[derive(SimpleObject)]
+pub struct ClinicalWarningConnector {
+    total_count: u32,
+    nodes: Vec<ClinicalWarningNode>,
+}
+
+#[Object]
+impl ClinicalWarningNode {
+    /// Used to acknowledge the warning
+    pub async fn id(&self) -> &str {
+        &self.warning.id
+    }
+
+    pub async fn r#type(&self) -> ClinicalWarningNodeType {
+        ClinicalWarningNodeType::from(self.warning.r#type.clone())
+    }
+
+    pub async fn severity(&self) -> DrugInteractionSeverityType {
+        DrugInteractionSeverityType::from(self.warning.severity.clone())
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.warning.item_id
+    }
+
+    pub async fn item_name(&self) -> &str {
+        &self.warning.item_name
+    }
+
+    pub async fn interacting_item_id(&self) -> &str {
+        &self.warning.interacting_item_id
+    }
+
+    pub async fn interacting_item_name(&self) -> &str {
+        &self.warning.interacting_item_name
+    }
+
+    /// Prescription the interacting item was dispensed in, empty when it is on the prescription
+    /// being checked
+    pub async fn interacting_invoice_id(&self) -> &Option<String> {
+        &self.warning.interacting_invoice_id
+    }
+
+    pub async fn description(&self) -> &str {
+        &self.warning.description
+    }
+}
+
+pub fn prescription_warnings(
+    ctx: &Context<'_>,
+    store_id: String,
+    invoice_id: String,
+    item_id: Option<String>,
+) -> Result<ClinicalWarningConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryInvoice,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let warnings = service_provider
+        .clinical_check_service
+        .get_prescription_warnings(&service_context, &invoice_id, item_id)
+        .map_err(map_error)?;
+
+    Ok(ClinicalWarningConnector {
+        total_count: usize_to_u32(warnings.len()),
+        nodes: warnings
+            .into_iter()
+            .map(|warning| ClinicalWarningNode { warning })
+            .collect(),
+    })
+}
+
+fn map_error(error: ClinicalCheckError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ClinicalCheckError::InvoiceDoesNotExist
+        | ClinicalCheckError::NotThisStoreInvoice
+        | ClinicalCheckError::NotAPrescriptionInvoice
+        | ClinicalCheckError::ItemDoesNotExist => BadUserInput(formatted_error),
+        ClinicalCheckError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
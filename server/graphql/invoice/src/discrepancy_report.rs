@@ -0,0 +1,170 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{InvoiceLineDiscrepancyNodeType, InvoiceNodeType};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    invoice::{
+        DiscrepancyReport, DiscrepancyReportError, DiscrepancyReportLine, DiscrepancyTypeTotal,
+    },
+};
+
+pub struct DiscrepancyReportLineNode {
+    pub report_line: DiscrepancyReportLine,
+}
+
+#[Object]
+impl DiscrepancyReportLineNode {
+    pub async fn invoice_line_id(&self) -> &str {
+        &self.report_line.line.id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.report_line.line.item_link_id
+    }
+
+    pub async fn item_name(&self) -> &str {
+        &self.report_line.line.item_name
+    }
+
+    pub async fn item_code(&self) -> &str {
+        &self.report_line.line.item_code
+    }
+
+    pub async fn batch(&self) -> Option<&str> {
+        self.report_line.line.batch.as_deref()
+    }
+
+    pub async fn pack_size(&self) -> f64 {
+        self.report_line.line.pack_size
+    }
+
+    /// Not known for inbound shipment lines until the outbound shipment is synced to this site
+    pub async fn shipped_number_of_packs(&self) -> Option<f64> {
+        self.report_line.shipped_number_of_packs
+    }
+
+    pub async fn number_of_packs(&self) -> f64 {
+        self.report_line.line.number_of_packs
+    }
+
+    pub async fn discrepancy_type(&self) -> Option<InvoiceLineDiscrepancyNodeType> {
+        self.report_line
+            .line
+            .discrepancy_type
+            .clone()
+            .map(InvoiceLineDiscrepancyNodeType::from)
+    }
+
+    pub async fn discrepancy_number_of_packs(&self) -> f64 {
+        self.report_line.line.discrepancy_number_of_packs
+    }
+
+    pub async fn discrepancy_reason(&self) -> Option<&str> {
+        self.report_line.line.discrepancy_reason.as_deref()
+    }
+
+    pub async fn value(&self) -> f64 {
+        self.report_line.value
+    }
+}
+
+pub struct DiscrepancyTypeTotalNode {
+    pub total: DiscrepancyTypeTotal,
+}
+
+#[Object]
+impl DiscrepancyTypeTotalNode {
+    pub async fn r#type(&self) -> InvoiceLineDiscrepancyNodeType {
+        InvoiceLineDiscrepancyNodeType::from(self.total.r#type.clone())
+    }
+
+    pub async fn line_count(&self) -> u32 {
+        self.total.line_count
+    }
+
+    pub async fn value(&self) -> f64 {
+        self.total.value
+    }
+}
+
+pub struct DiscrepancyReportNode {
+    pub report: DiscrepancyReport,
+}
+
+#[Object]
+impl DiscrepancyReportNode {
+    pub async fn invoice_id(&self) -> &str {
+        &self.report.invoice.id
+    }
+
+    pub async fn invoice_number(&self) -> i64 {
+        self.report.invoice.invoice_number
+    }
+
+    pub async fn invoice_type(&self) -> InvoiceNodeType {
+        InvoiceNodeType::from_domain(&self.report.invoice.r#type)
+    }
+
+    pub async fn linked_invoice_id(&self) -> Option<&str> {
+        self.report.invoice.linked_invoice_id.as_deref()
+    }
+
+    pub async fn lines(&self) -> Vec<DiscrepancyReportLineNode> {
+        self.report
+            .lines
+            .iter()
+            .cloned()
+            .map(|report_line| DiscrepancyReportLineNode { report_line })
+            .collect()
+    }
+
+    pub async fn totals(&self) -> Vec<DiscrepancyTypeTotalNode> {
+        self.report
+            .totals
+            .iter()
+            .cloned()
+            .map(|total| DiscrepancyTypeTotalNode { total })
+            .collect()
+    }
+}
+
+pub fn discrepancy_report(
+    ctx: &Context<'_>,
+    store_id: String,
+    invoice_id: String,
+) -> Result<DiscrepancyReportNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryInvoice,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let result = service_provider
+        .invoice_service
+        .get_discrepancy_report(&service_context, &invoice_id);
+
+    match result {
+        Ok(report) => Ok(DiscrepancyReportNode { report }),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                DiscrepancyReportError::InvoiceDoesNotExist
+                | DiscrepancyReportError::NotThisStoreInvoice
+                | DiscrepancyReportError::NotAShipment => BadUserInput(formatted_error),
+                DiscrepancyReportError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
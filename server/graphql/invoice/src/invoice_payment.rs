@@ -0,0 +1,432 @@
+use async_graphql::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::InvoiceNodeType;
+use repository::InvoicePaymentRow;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    invoice_payment::{
+        AgingBuckets, CashierReconciliation, CashierReconciliationInput, CustomerBalance,
+        CustomerBalanceError, CustomerBalanceInput, InvoicePaymentSummary, OutstandingInvoice,
+        PaymentMethodTotal,
+    },
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "repository::InvoicePaymentType")]
+pub enum InvoicePaymentNodeType {
+    Payment,
+    CreditNote,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "repository::PaymentMethod")]
+pub enum PaymentMethodType {
+    Cash,
+    Card,
+    MobileMoney,
+    BankTransfer,
+    Cheque,
+    Insurance,
+    Other,
+}
+
+pub struct InvoicePaymentNode {
+    pub payment: InvoicePaymentRow,
+}
+
+#[Object]
+impl InvoicePaymentNode {
+    pub async fn id(&self) -> &str {
+        &self.payment.id
+    }
+
+    pub async fn invoice_id(&self) -> Option<&str> {
+        self.payment.invoice_id.as_deref()
+    }
+
+    pub async fn customer_return_id(&self) -> Option<&str> {
+        self.payment.customer_return_id.as_deref()
+    }
+
+    pub async fn r#type(&self) -> InvoicePaymentNodeType {
+        InvoicePaymentNodeType::from(self.payment.r#type.clone())
+    }
+
+    pub async fn payment_method(&self) -> Option<PaymentMethodType> {
+        self.payment
+            .payment_method
+            .clone()
+            .map(PaymentMethodType::from)
+    }
+
+    /// Receipt number for payments, credit note number for credit notes
+    pub async fn receipt_number(&self) -> i64 {
+        self.payment.receipt_number
+    }
+
+    /// In the payment currency
+    pub async fn amount(&self) -> f64 {
+        self.payment.amount
+    }
+
+    pub async fn currency_id(&self) -> Option<&str> {
+        self.payment.currency_id.as_deref()
+    }
+
+    pub async fn currency_rate(&self) -> f64 {
+        self.payment.currency_rate
+    }
+
+    pub async fn home_currency_amount(&self) -> f64 {
+        self.payment.home_currency_amount
+    }
+
+    pub async fn reference(&self) -> Option<&str> {
+        self.payment.reference.as_deref()
+    }
+
+    pub async fn comment(&self) -> Option<&str> {
+        self.payment.comment.as_deref()
+    }
+
+    pub async fn user_id(&self) -> Option<&str> {
+        self.payment.user_id.as_deref()
+    }
+
+    pub async fn payment_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.payment.payment_datetime, Utc)
+    }
+
+    pub async fn cancelled_datetime(&self) -> Option<DateTime<Utc>> {
+        self.payment
+            .cancelled_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+}
+
+pub struct InvoicePaymentSummaryNode {
+    pub summary: InvoicePaymentSummary,
+}
+
+#[Object]
+impl InvoicePaymentSummaryNode {
+    pub async fn invoice_id(&self) -> &str {
+        &self.summary.invoice_id
+    }
+
+    pub async fn total(&self) -> f64 {
+        self.summary.total
+    }
+
+    pub async fn paid(&self) -> f64 {
+        self.summary.paid
+    }
+
+    pub async fn credited(&self) -> f64 {
+        self.summary.credited
+    }
+
+    pub async fn outstanding(&self) -> f64 {
+        self.summary.outstanding()
+    }
+
+    /// Includes cancelled payments
+    pub async fn payments(&self) -> Vec<InvoicePaymentNode> {
+        to_payment_nodes(&self.summary.payments)
+    }
+}
+
+pub struct OutstandingInvoiceNode {
+    pub invoice: OutstandingInvoice,
+}
+
+#[Object]
+impl OutstandingInvoiceNode {
+    pub async fn invoice_id(&self) -> &str {
+        &self.invoice.invoice_row.id
+    }
+
+    pub async fn invoice_number(&self) -> i64 {
+        self.invoice.invoice_row.invoice_number
+    }
+
+    pub async fn r#type(&self) -> InvoiceNodeType {
+        InvoiceNodeType::from_domain(&self.invoice.invoice_row.r#type)
+    }
+
+    pub async fn total(&self) -> f64 {
+        self.invoice.summary.total
+    }
+
+    pub async fn outstanding(&self) -> f64 {
+        self.invoice.summary.outstanding()
+    }
+
+    pub async fn days_outstanding(&self) -> i64 {
+        self.invoice.days_outstanding
+    }
+}
+
+pub struct AgingBucketsNode {
+    pub aging: AgingBuckets,
+}
+
+#[Object]
+impl AgingBucketsNode {
+    /// Up to 30 days
+    pub async fn current(&self) -> f64 {
+        self.aging.current
+    }
+
+    pub async fn days_31_to_60(&self) -> f64 {
+        self.aging.days_31_to_60
+    }
+
+    pub async fn days_61_to_90(&self) -> f64 {
+        self.aging.days_61_to_90
+    }
+
+    pub async fn over_90_days(&self) -> f64 {
+        self.aging.over_90_days
+    }
+}
+
+pub struct CustomerBalanceNode {
+    pub balance: CustomerBalance,
+}
+
+#[Object]
+impl CustomerBalanceNode {
+    pub async fn name_id(&self) -> &str {
+        &self.balance.name_id
+    }
+
+    pub async fn total_invoiced(&self) -> f64 {
+        self.balance.total_invoiced
+    }
+
+    pub async fn total_paid(&self) -> f64 {
+        self.balance.total_paid
+    }
+
+    pub async fn total_credited(&self) -> f64 {
+        self.balance.total_credited
+    }
+
+    /// Credit notes not applied to an invoice
+    pub async fn unapplied_credit(&self) -> f64 {
+        self.balance.unapplied_credit
+    }
+
+    /// Amount owed by the customer, negative if the customer is in credit
+    pub async fn balance(&self) -> f64 {
+        self.balance.balance
+    }
+
+    pub async fn aging(&self) -> AgingBucketsNode {
+        AgingBucketsNode {
+            aging: self.balance.aging.clone(),
+        }
+    }
+
+    /// Oldest first
+    pub async fn outstanding_invoices(&self) -> Vec<OutstandingInvoiceNode> {
+        self.balance
+            .outstanding_invoices
+            .iter()
+            .cloned()
+            .map(|invoice| OutstandingInvoiceNode { invoice })
+            .collect()
+    }
+}
+
+pub struct PaymentMethodTotalNode {
+    pub total: PaymentMethodTotal,
+}
+
+#[Object]
+impl PaymentMethodTotalNode {
+    pub async fn payment_method(&self) -> PaymentMethodType {
+        PaymentMethodType::from(self.total.payment_method.clone())
+    }
+
+    pub async fn currency_id(&self) -> Option<&str> {
+        self.total.currency_id.as_deref()
+    }
+
+    pub async fn count(&self) -> u32 {
+        self.total.count
+    }
+
+    /// In the payment currency
+    pub async fn amount(&self) -> f64 {
+        self.total.amount
+    }
+
+    pub async fn home_currency_amount(&self) -> f64 {
+        self.total.home_currency_amount
+    }
+}
+
+pub struct CashierReconciliationNode {
+    pub reconciliation: CashierReconciliation,
+}
+
+#[Object]
+impl CashierReconciliationNode {
+    /// Totals by payment method and currency
+    pub async fn totals(&self) -> Vec<PaymentMethodTotalNode> {
+        self.reconciliation
+            .totals
+            .iter()
+            .cloned()
+            .map(|total| PaymentMethodTotalNode { total })
+            .collect()
+    }
+
+    pub async fn total_home_currency_amount(&self) -> f64 {
+        self.reconciliation.total_home_currency_amount
+    }
+
+    pub async fn credit_note_count(&self) -> u32 {
+        self.reconciliation.credit_note_count
+    }
+
+    pub async fn credit_note_total(&self) -> f64 {
+        self.reconciliation.credit_note_total
+    }
+
+    pub async fn cancelled_count(&self) -> u32 {
+        self.reconciliation.cancelled_count
+    }
+
+    pub async fn cancelled_home_currency_amount(&self) -> f64 {
+        self.reconciliation.cancelled_home_currency_amount
+    }
+
+    /// Payments and credit notes in receipt number order, including cancelled
+    pub async fn payments(&self) -> Vec<InvoicePaymentNode> {
+        to_payment_nodes(&self.reconciliation.payments)
+    }
+}
+
+#[derive(InputObject)]
+pub struct CashierReconciliationInputNode {
+    pub from_datetime: DateTime<Utc>,
+    pub to_datetime: DateTime<Utc>,
+    /// All users if not set
+    pub user_id: Option<String>,
+}
+
+fn to_payment_nodes(payments: &[InvoicePaymentRow]) -> Vec<InvoicePaymentNode> {
+    payments
+        .iter()
+        .cloned()
+        .map(|payment| InvoicePaymentNode { payment })
+        .collect()
+}
+
+pub fn invoice_payments(
+    ctx: &Context<'_>,
+    store_id: String,
+    invoice_id: String,
+) -> Result<InvoicePaymentSummaryNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryInvoicePayment,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let summary = service_provider
+        .invoice_payment_service
+        .get_invoice_payment_summary(&service_context, &invoice_id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(InvoicePaymentSummaryNode { summary })
+}
+
+pub fn customer_balance(
+    ctx: &Context<'_>,
+    store_id: String,
+    name_id: String,
+    as_of: Option<NaiveDate>,
+) -> Result<CustomerBalanceNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryInvoicePayment,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let result = service_provider
+        .invoice_payment_service
+        .get_customer_balance(&service_context, CustomerBalanceInput { name_id, as_of });
+
+    match result {
+        Ok(balance) => Ok(CustomerBalanceNode { balance }),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                CustomerBalanceError::NameDoesNotExist => BadUserInput(formatted_error),
+                CustomerBalanceError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn cashier_reconciliation(
+    ctx: &Context<'_>,
+    store_id: String,
+    input: CashierReconciliationInputNode,
+) -> Result<CashierReconciliationNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryInvoicePayment,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let reconciliation = service_provider
+        .invoice_payment_service
+        .get_cashier_reconciliation(&service_context, input.to_domain())
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(CashierReconciliationNode { reconciliation })
+}
+
+impl CashierReconciliationInputNode {
+    pub fn to_domain(self) -> CashierReconciliationInput {
+        let CashierReconciliationInputNode {
+            from_datetime,
+            to_datetime,
+            user_id,
+        } = self;
+
+        CashierReconciliationInput {
+            from_datetime: from_datetime.naive_utc(),
+            to_datetime: to_datetime.naive_utc(),
+            user_id,
+        }
+    }
+}
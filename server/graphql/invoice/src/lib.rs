@@ -1,16 +1,33 @@
 use async_graphql::*;
+use chrono::NaiveDate;
 use graphql_core::pagination::PaginationInput;
 use graphql_types::types::*;
 use mutations::AddToShipmentFromMasterListInput;
 
+pub mod clinical_check;
+use self::clinical_check::*;
+
+pub mod discrepancy_report;
+use self::discrepancy_report::*;
+
+pub mod invoice_payment;
+use self::invoice_payment::*;
+
 pub mod invoice_queries;
 use self::invoice_queries::*;
 
+pub mod medication_history;
+use self::medication_history::*;
+
 pub mod mutations;
 use self::mutations::{
-    customer_return, inbound_shipment, outbound_shipment, prescription, supplier_return,
+    customer_return, inbound_shipment, invoice_payment as payment, outbound_shipment,
+    pick_list as picking, prescription, supplier_return,
 };
 
+pub mod pick_list;
+use self::pick_list::*;
+
 #[cfg(test)]
 mod query_tests;
 
@@ -50,6 +67,70 @@ impl InvoiceQueries {
         get_invoices(ctx, store_id, page, filter, sort)
     }
 
+    /// Medication dispensed to the patient in picked or verified prescriptions, most recent first
+    pub async fn medication_history(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        patient_id: String,
+    ) -> Result<MedicationHistoryConnector> {
+        medication_history(ctx, store_id, patient_id)
+    }
+
+    /// Drug interaction and duplicate therapy warnings for the item being added to the
+    /// prescription, or for every item on the prescription when no item is provided
+    pub async fn prescription_warnings(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        invoice_id: String,
+        item_id: Option<String>,
+    ) -> Result<ClinicalWarningConnector> {
+        prescription_warnings(ctx, store_id, invoice_id, item_id)
+    }
+
+    /// Shortages, damages, wrong batches and excess recorded by the receiving store of a
+    /// transferred shipment, for either the inbound or the outbound shipment
+    pub async fn discrepancy_report(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        invoice_id: String,
+    ) -> Result<DiscrepancyReportNode> {
+        discrepancy_report(ctx, store_id, invoice_id)
+    }
+
+    /// Total, amount paid, amount credited and payment history of the invoice
+    pub async fn invoice_payments(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        invoice_id: String,
+    ) -> Result<InvoicePaymentSummaryNode> {
+        invoice_payments(ctx, store_id, invoice_id)
+    }
+
+    /// Outstanding balance of the customer, with unpaid invoices grouped by age
+    pub async fn customer_balance(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        name_id: String,
+        as_of: Option<NaiveDate>,
+    ) -> Result<CustomerBalanceNode> {
+        customer_balance(ctx, store_id, name_id, as_of)
+    }
+
+    /// Payments taken in the period, totalled by payment method for the end of day cash up
+    pub async fn cashier_reconciliation(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: CashierReconciliationInputNode,
+    ) -> Result<CashierReconciliationNode> {
+        cashier_reconciliation(ctx, store_id, input)
+    }
+
     async fn insert_prescription(
         &self,
         ctx: &Context<'_>,
@@ -58,6 +139,35 @@ impl InvoiceQueries {
     ) -> Result<prescription::insert::InsertResponse> {
         prescription::insert::insert(ctx, &store_id, input)
     }
+
+    /// Pick list of the store with its lines in pick path order
+    pub async fn pick_list(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<Option<PickListNode>> {
+        pick_list(ctx, store_id, id)
+    }
+
+    /// Newest first
+    pub async fn pick_lists(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        status: Option<PickListNodeStatus>,
+    ) -> Result<Vec<PickListNode>> {
+        pick_lists(ctx, store_id, status)
+    }
+
+    /// Locations of the store in the order pick lists walk past them
+    pub async fn pick_path(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<PickPathLocationNode>> {
+        pick_path(ctx, store_id)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -174,6 +284,15 @@ impl InvoiceMutations {
         prescription::delete::delete(ctx, &store_id, id)
     }
 
+    async fn acknowledge_clinical_warning(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: prescription::acknowledge_warning::AcknowledgeClinicalWarningInput,
+    ) -> Result<prescription::acknowledge_warning::AcknowledgeClinicalWarningResponse> {
+        prescription::acknowledge_warning::acknowledge_warning(ctx, &store_id, input)
+    }
+
     async fn insert_supplier_return(
         &self,
         ctx: &Context<'_>,
@@ -254,4 +373,69 @@ impl InvoiceMutations {
     ) -> Result<customer_return::delete::DeleteResponse> {
         customer_return::delete::delete(ctx, &store_id, id)
     }
+    async fn insert_invoice_payment(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: payment::insert::InsertInput,
+    ) -> Result<payment::insert::InsertResponse> {
+        payment::insert::insert(ctx, &store_id, input)
+    }
+
+    async fn insert_credit_note(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: payment::insert_credit_note::InsertCreditNoteInput,
+    ) -> Result<payment::insert_credit_note::InsertCreditNoteResponse> {
+        payment::insert_credit_note::insert_credit_note(ctx, &store_id, input)
+    }
+
+    async fn cancel_invoice_payment(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: payment::cancel::CancelInvoicePaymentInput,
+    ) -> Result<payment::cancel::CancelInvoicePaymentResponse> {
+        payment::cancel::cancel(ctx, &store_id, input)
+    }
+
+    /// Groups the allocated lines of one or more shipments by location along the pick path
+    async fn generate_pick_list(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: picking::GeneratePickListInput,
+    ) -> Result<PickListNode> {
+        picking::generate_pick_list(ctx, &store_id, input)
+    }
+
+    /// Short picks are reallocated from other stock lines and added to the end of the pick list
+    async fn confirm_pick_list_line(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: picking::ConfirmPickListLineInput,
+    ) -> Result<picking::ConfirmPickListLineNode> {
+        picking::confirm_pick_list_line(ctx, &store_id, input)
+    }
+
+    /// All lines must be confirmed, allocated shipments of the pick list are moved to Picked
+    async fn complete_pick_list(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<PickListNode> {
+        picking::complete_pick_list(ctx, &store_id, id)
+    }
+
+    async fn set_pick_path(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: picking::SetPickPathInput,
+    ) -> Result<Vec<PickPathLocationNode>> {
+        picking::set_pick_path(ctx, &store_id, input)
+    }
 }
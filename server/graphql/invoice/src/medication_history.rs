@@ -0,0 +1,96 @@
+use async_graphql::*;
+use chrono::{Local, NaiveDate};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{InvoiceLineNode, PrescriptionLineDirectionNode};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    invoice::prescription::MedicationHistoryEntry,
+    usize_to_u32,
+};
+
+pub struct MedicationHistoryNode {
+    pub entry: MedicationHistoryEntry,
+}
+
+#[derive(SimpleObject)]
+pub struct MedicationHistoryConnector {
+    total_count: u32,
+    nodes: Vec<MedicationHistoryNode>,
+}
+
+#[Object]
+impl MedicationHistoryNode {
+    pub async fn invoice_line(&self) -> InvoiceLineNode {
+        InvoiceLineNode::from_domain(self.entry.invoice_line.clone())
+    }
+
+    pub async fn invoice_number(&self) -> i64 {
+        self.entry.invoice_line.invoice_row.invoice_number
+    }
+
+    pub async fn item_name(&self) -> &str {
+        &self.entry.invoice_line.item_row.name
+    }
+
+    pub async fn direction(&self) -> Option<PrescriptionLineDirectionNode> {
+        self.entry
+            .direction
+            .clone()
+            .map(PrescriptionLineDirectionNode::from_domain)
+    }
+
+    pub async fn prescription_date(&self) -> NaiveDate {
+        self.entry.prescription_date
+    }
+
+    /// Number of days the dispensed quantity lasts when following the directions
+    pub async fn days_of_supply(&self) -> Option<i32> {
+        self.entry.days_of_supply
+    }
+
+    pub async fn refill_due_date(&self) -> Option<NaiveDate> {
+        self.entry.refill_due_date
+    }
+
+    /// False when the same item has been dispensed to the patient again since
+    pub async fn is_latest_for_item(&self) -> bool {
+        self.entry.is_latest_for_item
+    }
+
+    pub async fn due_for_refill(&self) -> bool {
+        self.entry.is_due_for_refill(Local::now().date_naive())
+    }
+}
+
+pub fn medication_history(
+    ctx: &Context<'_>,
+    store_id: String,
+    patient_id: String,
+) -> Result<MedicationHistoryConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryInvoice,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let entries = service_provider
+        .invoice_service
+        .get_medication_history(&service_context, &patient_id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(MedicationHistoryConnector {
+        total_count: usize_to_u32(entries.len()),
+        nodes: entries
+            .into_iter()
+            .map(|entry| MedicationHistoryNode { entry })
+            .collect(),
+    })
+}
@@ -0,0 +1,101 @@
+use async_graphql::*;
+use graphql_core::simple_generic_errors::RecordNotFound;
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+
+use repository::InvoicePaymentRow;
+use service::auth::{Resource, ResourceAccessRequest};
+use service::invoice_payment::{
+    CancelInvoicePayment as ServiceInput, CancelInvoicePaymentError as ServiceError,
+};
+
+use crate::invoice_payment::InvoicePaymentNode;
+
+#[derive(InputObject)]
+pub struct CancelInvoicePaymentInput {
+    pub id: String,
+    /// Recorded in the activity log
+    pub reason: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct CancelInvoicePaymentError {
+    pub error: CancelInvoicePaymentErrorInterface,
+}
+
+#[derive(Union)]
+pub enum CancelInvoicePaymentResponse {
+    Error(CancelInvoicePaymentError),
+    Response(InvoicePaymentNode),
+}
+
+#[derive(Interface)]
+#[graphql(field(name = "description", ty = "&str"))]
+pub enum CancelInvoicePaymentErrorInterface {
+    RecordNotFound(RecordNotFound),
+}
+
+pub fn cancel(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: CancelInvoicePaymentInput,
+) -> Result<CancelInvoicePaymentResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateInvoicePayment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .invoice_payment_service
+            .cancel_invoice_payment(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(
+    from: Result<InvoicePaymentRow, ServiceError>,
+) -> Result<CancelInvoicePaymentResponse> {
+    let result = match from {
+        Ok(payment) => CancelInvoicePaymentResponse::Response(InvoicePaymentNode { payment }),
+        Err(error) => CancelInvoicePaymentResponse::Error(CancelInvoicePaymentError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+impl CancelInvoicePaymentInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let CancelInvoicePaymentInput { id, reason } = self;
+
+        ServiceInput { id, reason }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<CancelInvoicePaymentErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::PaymentDoesNotExist => {
+            return Ok(CancelInvoicePaymentErrorInterface::RecordNotFound(
+                RecordNotFound {},
+            ))
+        }
+        // Standard Graphql Errors
+        ServiceError::NotThisStorePayment | ServiceError::PaymentAlreadyCancelled => {
+            BadUserInput(formatted_error)
+        }
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
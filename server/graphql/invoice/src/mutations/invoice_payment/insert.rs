@@ -0,0 +1,129 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::simple_generic_errors::RecordNotFound;
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+
+use repository::InvoicePaymentRow;
+use service::auth::{Resource, ResourceAccessRequest};
+use service::invoice_payment::{
+    InsertInvoicePayment as ServiceInput, InsertInvoicePaymentError as ServiceError,
+};
+
+use crate::invoice_payment::{InvoicePaymentNode, PaymentMethodType};
+
+#[derive(InputObject)]
+#[graphql(name = "InsertInvoicePaymentInput")]
+pub struct InsertInput {
+    pub id: String,
+    pub invoice_id: String,
+    /// In the payment currency
+    pub amount: f64,
+    pub payment_method: PaymentMethodType,
+    /// Home currency if not set
+    pub currency_id: Option<String>,
+    /// e.g. card authorisation or mobile money transaction id
+    pub reference: Option<String>,
+    pub comment: Option<String>,
+    /// Defaults to now
+    pub payment_datetime: Option<DateTime<Utc>>,
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "InsertInvoicePaymentError")]
+pub struct InsertError {
+    pub error: InsertErrorInterface,
+}
+
+#[derive(Union)]
+#[graphql(name = "InsertInvoicePaymentResponse")]
+pub enum InsertResponse {
+    Error(InsertError),
+    Response(InvoicePaymentNode),
+}
+
+#[derive(Interface)]
+#[graphql(name = "InsertInvoicePaymentErrorInterface")]
+#[graphql(field(name = "description", ty = "&str"))]
+pub enum InsertErrorInterface {
+    RecordNotFound(RecordNotFound),
+}
+
+pub fn insert(ctx: &Context<'_>, store_id: &str, input: InsertInput) -> Result<InsertResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateInvoicePayment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .invoice_payment_service
+            .insert_invoice_payment(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(from: Result<InvoicePaymentRow, ServiceError>) -> Result<InsertResponse> {
+    let result = match from {
+        Ok(payment) => InsertResponse::Response(InvoicePaymentNode { payment }),
+        Err(error) => InsertResponse::Error(InsertError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+impl InsertInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let InsertInput {
+            id,
+            invoice_id,
+            amount,
+            payment_method,
+            currency_id,
+            reference,
+            comment,
+            payment_datetime,
+        } = self;
+
+        ServiceInput {
+            id,
+            invoice_id,
+            amount,
+            payment_method: payment_method.into(),
+            currency_id,
+            reference,
+            comment,
+            payment_datetime: payment_datetime.map(|datetime| datetime.naive_utc()),
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::InvoiceDoesNotExist => {
+            return Ok(InsertErrorInterface::RecordNotFound(RecordNotFound {}))
+        }
+        // Standard Graphql Errors
+        ServiceError::PaymentAlreadyExists
+        | ServiceError::NotThisStoreInvoice
+        | ServiceError::CannotPayInvoiceType
+        | ServiceError::InvoiceNotFinalised
+        | ServiceError::AmountMustBePositive
+        | ServiceError::CurrencyDoesNotExist
+        | ServiceError::AmountExceedsOutstanding(_) => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
@@ -0,0 +1,131 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::simple_generic_errors::RecordNotFound;
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+
+use repository::InvoicePaymentRow;
+use service::auth::{Resource, ResourceAccessRequest};
+use service::invoice_payment::{
+    InsertCreditNote as ServiceInput, InsertCreditNoteError as ServiceError,
+};
+
+use crate::invoice_payment::InvoicePaymentNode;
+
+#[derive(InputObject)]
+pub struct InsertCreditNoteInput {
+    pub id: String,
+    pub customer_return_id: String,
+    /// Invoice of the same customer to apply the credit to, credit stays on the customer's
+    /// account if not set
+    pub invoice_id: Option<String>,
+    /// Defaults to the return total that hasn't been credited yet
+    pub amount: Option<f64>,
+    pub comment: Option<String>,
+    /// Defaults to now
+    pub payment_datetime: Option<DateTime<Utc>>,
+}
+
+#[derive(SimpleObject)]
+pub struct InsertCreditNoteError {
+    pub error: InsertCreditNoteErrorInterface,
+}
+
+#[derive(Union)]
+pub enum InsertCreditNoteResponse {
+    Error(InsertCreditNoteError),
+    Response(InvoicePaymentNode),
+}
+
+#[derive(Interface)]
+#[graphql(field(name = "description", ty = "&str"))]
+pub enum InsertCreditNoteErrorInterface {
+    RecordNotFound(RecordNotFound),
+}
+
+pub fn insert_credit_note(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: InsertCreditNoteInput,
+) -> Result<InsertCreditNoteResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateInvoicePayment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .invoice_payment_service
+            .insert_credit_note(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(
+    from: Result<InvoicePaymentRow, ServiceError>,
+) -> Result<InsertCreditNoteResponse> {
+    let result = match from {
+        Ok(payment) => InsertCreditNoteResponse::Response(InvoicePaymentNode { payment }),
+        Err(error) => InsertCreditNoteResponse::Error(InsertCreditNoteError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+impl InsertCreditNoteInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let InsertCreditNoteInput {
+            id,
+            customer_return_id,
+            invoice_id,
+            amount,
+            comment,
+            payment_datetime,
+        } = self;
+
+        ServiceInput {
+            id,
+            customer_return_id,
+            invoice_id,
+            amount,
+            comment,
+            payment_datetime: payment_datetime.map(|datetime| datetime.naive_utc()),
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<InsertCreditNoteErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::ReturnDoesNotExist | ServiceError::InvoiceDoesNotExist => {
+            return Ok(InsertCreditNoteErrorInterface::RecordNotFound(
+                RecordNotFound {},
+            ))
+        }
+        // Standard Graphql Errors
+        ServiceError::CreditNoteAlreadyExists
+        | ServiceError::NotThisStoreReturn
+        | ServiceError::NotACustomerReturn
+        | ServiceError::ReturnNotReceived
+        | ServiceError::AmountMustBePositive
+        | ServiceError::AmountExceedsReturnTotal(_)
+        | ServiceError::NotThisStoreInvoice
+        | ServiceError::CannotApplyToInvoiceType
+        | ServiceError::InvoiceNotFinalised
+        | ServiceError::InvoiceDoesNotBelongToCustomer
+        | ServiceError::AmountExceedsOutstanding(_) => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
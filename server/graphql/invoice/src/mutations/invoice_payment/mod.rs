@@ -0,0 +1,3 @@
+pub mod cancel;
+pub mod insert;
+pub mod insert_credit_note;
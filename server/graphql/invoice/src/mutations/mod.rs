@@ -2,7 +2,9 @@ use service::invoice::common::AddToShipmentFromMasterListInput as ServiceInput;
 
 pub mod customer_return;
 pub mod inbound_shipment;
+pub mod invoice_payment;
 pub mod outbound_shipment;
+pub mod pick_list;
 pub mod prescription;
 pub mod supplier_return;
 
@@ -0,0 +1,238 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    pick_list::{
+        CompletePickListError, ConfirmPickListLine, ConfirmPickListLineError,
+        ConfirmPickListLineResult, GeneratePickList, GeneratePickListError, SetPickPath,
+        SetPickPathError,
+    },
+};
+
+use crate::pick_list::{PickListLineNode, PickListNode, PickPathLocationNode};
+
+#[derive(InputObject)]
+pub struct GeneratePickListInput {
+    pub id: String,
+    /// Allocated outbound shipments to pick together
+    pub invoice_ids: Vec<String>,
+}
+
+#[derive(InputObject)]
+pub struct ConfirmPickListLineInput {
+    pub id: String,
+    pub picked_number_of_packs: f64,
+}
+
+#[derive(InputObject)]
+pub struct SetPickPathInput {
+    /// Locations of the store in the order they are walked past
+    pub location_ids: Vec<String>,
+}
+
+pub struct ConfirmPickListLineNode {
+    pub result: ConfirmPickListLineResult,
+}
+
+#[Object]
+impl ConfirmPickListLineNode {
+    pub async fn line(&self) -> PickListLineNode {
+        PickListLineNode {
+            line: self.result.line.clone(),
+        }
+    }
+
+    /// Lines added to the end of the pick list for stock allocated in place of a short pick
+    pub async fn reallocated_lines(&self) -> Vec<PickListLineNode> {
+        self.result
+            .reallocated_lines
+            .iter()
+            .cloned()
+            .map(|line| PickListLineNode { line })
+            .collect()
+    }
+
+    /// Units of a short pick that couldn't be allocated from other stock
+    pub async fn unallocated_quantity(&self) -> f64 {
+        self.result.unallocated_quantity
+    }
+}
+
+pub fn generate_pick_list(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: GeneratePickListInput,
+) -> Result<PickListNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateOutboundShipment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let GeneratePickListInput { id, invoice_ids } = input;
+    let result = service_provider
+        .pick_list_service
+        .generate_pick_list(&service_context, GeneratePickList { id, invoice_ids });
+
+    match result {
+        Ok(pick_list) => Ok(PickListNode::from_domain(pick_list)),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                GeneratePickListError::PickListAlreadyExists
+                | GeneratePickListError::NoShipmentsSelected
+                | GeneratePickListError::InvoiceDoesNotExist(_)
+                | GeneratePickListError::NotThisStoreInvoice(_)
+                | GeneratePickListError::NotAnOutboundShipment(_)
+                | GeneratePickListError::InvoiceIsNotAllocated(_)
+                | GeneratePickListError::InvoiceIsOnHold(_)
+                | GeneratePickListError::InvoiceAlreadyOnPickList(_)
+                | GeneratePickListError::NothingToPick => BadUserInput(formatted_error),
+                GeneratePickListError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn confirm_pick_list_line(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: ConfirmPickListLineInput,
+) -> Result<ConfirmPickListLineNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateOutboundShipment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let ConfirmPickListLineInput {
+        id,
+        picked_number_of_packs,
+    } = input;
+    let result = service_provider.pick_list_service.confirm_pick_list_line(
+        &service_context,
+        ConfirmPickListLine {
+            id,
+            picked_number_of_packs,
+        },
+    );
+
+    match result {
+        Ok(result) => Ok(ConfirmPickListLineNode { result }),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                ConfirmPickListLineError::PickListLineDoesNotExist
+                | ConfirmPickListLineError::NotThisStorePickList
+                | ConfirmPickListLineError::PickListIsCompleted
+                | ConfirmPickListLineError::LineAlreadyConfirmed
+                | ConfirmPickListLineError::PickedNumberOfPacksBelowZero
+                | ConfirmPickListLineError::PickedMoreThanAllocated
+                | ConfirmPickListLineError::InvoiceLineDoesNotExist => {
+                    BadUserInput(formatted_error)
+                }
+                ConfirmPickListLineError::UpdateStockOutLine(_)
+                | ConfirmPickListLineError::DeleteStockOutLine(_)
+                | ConfirmPickListLineError::AllocateShortPick(_)
+                | ConfirmPickListLineError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn complete_pick_list(ctx: &Context<'_>, store_id: &str, id: String) -> Result<PickListNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateOutboundShipment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let result = service_provider
+        .pick_list_service
+        .complete_pick_list(&service_context, id);
+
+    match result {
+        Ok(pick_list) => Ok(PickListNode::from_domain(pick_list)),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                CompletePickListError::PickListDoesNotExist
+                | CompletePickListError::NotThisStorePickList
+                | CompletePickListError::PickListIsCompleted
+                | CompletePickListError::LinesNotConfirmed(_) => BadUserInput(formatted_error),
+                CompletePickListError::UpdateOutboundShipment { .. }
+                | CompletePickListError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn set_pick_path(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: SetPickPathInput,
+) -> Result<Vec<PickPathLocationNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateLocation,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let result = service_provider.pick_list_service.set_pick_path(
+        &service_context,
+        SetPickPath {
+            location_ids: input.location_ids,
+        },
+    );
+
+    match result {
+        Ok(pick_path) => Ok(pick_path
+            .into_iter()
+            .map(|row| PickPathLocationNode { row })
+            .collect()),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                SetPickPathError::LocationDoesNotExist(_)
+                | SetPickPathError::NotThisStoreLocation(_)
+                | SetPickPathError::LocationIsRepeated(_) => BadUserInput(formatted_error),
+                SetPickPathError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
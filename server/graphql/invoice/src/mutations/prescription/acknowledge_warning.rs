@@ -0,0 +1,117 @@
+use async_graphql::*;
+use graphql_core::simple_generic_errors::RecordNotFound;
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+
+use service::auth::{Resource, ResourceAccessRequest};
+use service::clinical_check::{
+    AcknowledgeClinicalWarning as ServiceInput, AcknowledgeClinicalWarningError as ServiceError,
+    ClinicalWarning,
+};
+
+use crate::clinical_check::ClinicalWarningNode;
+
+#[derive(InputObject)]
+pub struct AcknowledgeClinicalWarningInput {
+    pub invoice_id: String,
+    /// Item the warnings were queried for
+    pub item_id: Option<String>,
+    pub warning_id: String,
+    /// Why the item is dispensed despite the warning, recorded in the activity log
+    pub reason: String,
+}
+
+#[derive(SimpleObject)]
+pub struct AcknowledgeClinicalWarningError {
+    pub error: AcknowledgeClinicalWarningErrorInterface,
+}
+
+#[derive(Union)]
+pub enum AcknowledgeClinicalWarningResponse {
+    Error(AcknowledgeClinicalWarningError),
+    Response(ClinicalWarningNode),
+}
+
+#[derive(Interface)]
+#[graphql(field(name = "description", ty = "&str"))]
+pub enum AcknowledgeClinicalWarningErrorInterface {
+    RecordNotFound(RecordNotFound),
+}
+
+pub fn acknowledge_warning(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: AcknowledgeClinicalWarningInput,
+) -> Result<AcknowledgeClinicalWarningResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutatePrescription,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .clinical_check_service
+            .acknowledge_clinical_warning(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(
+    from: Result<ClinicalWarning, ServiceError>,
+) -> Result<AcknowledgeClinicalWarningResponse> {
+    let result = match from {
+        Ok(warning) => {
+            AcknowledgeClinicalWarningResponse::Response(ClinicalWarningNode { warning })
+        }
+        Err(error) => AcknowledgeClinicalWarningResponse::Error(AcknowledgeClinicalWarningError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+impl AcknowledgeClinicalWarningInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let AcknowledgeClinicalWarningInput {
+            invoice_id,
+            item_id,
+            warning_id,
+            reason,
+        } = self;
+
+        ServiceInput {
+            invoice_id,
+            item_id,
+            warning_id,
+            reason,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<AcknowledgeClinicalWarningErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::InvoiceDoesNotExist | ServiceError::WarningDoesNotExist => {
+            return Ok(AcknowledgeClinicalWarningErrorInterface::RecordNotFound(
+                RecordNotFound {},
+            ))
+        }
+        // Standard Graphql Errors
+        ServiceError::NotThisStoreInvoice
+        | ServiceError::NotAPrescriptionInvoice
+        | ServiceError::ItemDoesNotExist
+        | ServiceError::ReasonCannotBeEmpty => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
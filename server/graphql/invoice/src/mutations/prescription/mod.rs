@@ -1,3 +1,5 @@
+pub mod acknowledge_warning;
+
 pub mod insert;
 
 pub mod update;
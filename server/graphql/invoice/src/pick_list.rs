@@ -0,0 +1,224 @@
+use async_graphql::{dataloader::DataLoader, *};
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    loader::{LocationByIdLoader, StockLineByIdLoader},
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{LocationNode, StockLineNode};
+use repository::{PickListLineRow, PickListRow, PickPathLocationRow};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    pick_list::PickList,
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "repository::PickListStatus")]
+pub enum PickListNodeStatus {
+    InProgress,
+    Completed,
+}
+
+pub struct PickListLineNode {
+    pub line: PickListLineRow,
+}
+
+#[Object]
+impl PickListLineNode {
+    pub async fn id(&self) -> &str {
+        &self.line.id
+    }
+
+    pub async fn invoice_id(&self) -> &str {
+        &self.line.invoice_id
+    }
+
+    pub async fn invoice_line_id(&self) -> &str {
+        &self.line.invoice_line_id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.line.item_link_id
+    }
+
+    /// Position along the pick path
+    pub async fn line_number(&self) -> i32 {
+        self.line.line_number
+    }
+
+    pub async fn number_of_packs(&self) -> f64 {
+        self.line.number_of_packs
+    }
+
+    /// Not set until the line is confirmed
+    pub async fn picked_number_of_packs(&self) -> Option<f64> {
+        self.line.picked_number_of_packs
+    }
+
+    pub async fn picked_datetime(&self) -> Option<DateTime<Utc>> {
+        self.line
+            .picked_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+
+    pub async fn stock_line(&self, ctx: &Context<'_>) -> Result<Option<StockLineNode>> {
+        let Some(stock_line_id) = &self.line.stock_line_id else {
+            return Ok(None);
+        };
+        let loader = ctx.get_loader::<DataLoader<StockLineByIdLoader>>();
+        let result = loader.load_one(stock_line_id.clone()).await?;
+
+        Ok(result.map(StockLineNode::from_domain))
+    }
+
+    pub async fn location(&self, ctx: &Context<'_>) -> Result<Option<LocationNode>> {
+        let Some(location_id) = &self.line.location_id else {
+            return Ok(None);
+        };
+        let loader = ctx.get_loader::<DataLoader<LocationByIdLoader>>();
+        let result = loader.load_one(location_id.clone()).await?;
+
+        Ok(result.map(LocationNode::from_domain))
+    }
+}
+
+pub struct PickListNode {
+    pub pick_list: PickList,
+}
+
+#[Object]
+impl PickListNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn status(&self) -> PickListNodeStatus {
+        PickListNodeStatus::from(self.row().status.clone())
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.row().created_datetime, Utc)
+    }
+
+    pub async fn completed_datetime(&self) -> Option<DateTime<Utc>> {
+        self.row()
+            .completed_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+
+    pub async fn user_id(&self) -> &str {
+        &self.row().user_id
+    }
+
+    /// In pick path order
+    pub async fn lines(&self) -> Vec<PickListLineNode> {
+        self.pick_list
+            .lines
+            .iter()
+            .cloned()
+            .map(|line| PickListLineNode { line })
+            .collect()
+    }
+}
+
+impl PickListNode {
+    pub fn from_domain(pick_list: PickList) -> PickListNode {
+        PickListNode { pick_list }
+    }
+
+    pub fn row(&self) -> &PickListRow {
+        &self.pick_list.pick_list
+    }
+}
+
+pub struct PickPathLocationNode {
+    pub row: PickPathLocationRow,
+}
+
+#[Object]
+impl PickPathLocationNode {
+    pub async fn location_id(&self) -> &str {
+        &self.row.location_id
+    }
+
+    pub async fn sort_order(&self) -> i32 {
+        self.row.sort_order
+    }
+
+    pub async fn location(&self, ctx: &Context<'_>) -> Result<Option<LocationNode>> {
+        let loader = ctx.get_loader::<DataLoader<LocationByIdLoader>>();
+        let result = loader.load_one(self.row.location_id.clone()).await?;
+
+        Ok(result.map(LocationNode::from_domain))
+    }
+}
+
+pub fn pick_list(ctx: &Context<'_>, store_id: String, id: String) -> Result<Option<PickListNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryInvoice,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let pick_list = service_provider
+        .pick_list_service
+        .get_pick_list(&service_context, &id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(pick_list.map(PickListNode::from_domain))
+}
+
+pub fn pick_lists(
+    ctx: &Context<'_>,
+    store_id: String,
+    status: Option<PickListNodeStatus>,
+) -> Result<Vec<PickListNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryInvoice,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let pick_lists = service_provider
+        .pick_list_service
+        .get_pick_lists(&service_context, status.map(Into::into))
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(pick_lists
+        .into_iter()
+        .map(PickListNode::from_domain)
+        .collect())
+}
+
+pub fn pick_path(ctx: &Context<'_>, store_id: String) -> Result<Vec<PickPathLocationNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryLocation,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let pick_path = service_provider
+        .pick_list_service
+        .get_pick_path(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(pick_path
+        .into_iter()
+        .map(|row| PickPathLocationNode { row })
+        .collect())
+}
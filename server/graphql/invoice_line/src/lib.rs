@@ -151,6 +151,17 @@ impl InvoiceLineMutations {
         inbound_shipment_line::line::delete::delete(ctx, &store_id, input)
     }
 
+    /// Records a shortage, damage, wrong batch or excess against a transferred inbound
+    /// shipment line, or clears it when no type is given
+    async fn update_inbound_shipment_line_discrepancy(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: inbound_shipment_line::line::update_discrepancy::UpdateDiscrepancyInput,
+    ) -> Result<inbound_shipment_line::line::update_discrepancy::UpdateDiscrepancyResponse> {
+        inbound_shipment_line::line::update_discrepancy::update_discrepancy(ctx, &store_id, input)
+    }
+
     async fn insert_inbound_shipment_service_line(
         &self,
         ctx: &Context<'_>,
@@ -204,4 +215,14 @@ impl InvoiceLineMutations {
     ) -> Result<prescription_line::delete::DeleteResponse> {
         prescription_line::delete::delete(ctx, &store_id, input)
     }
+
+    /// Sets structured dosage directions of a prescription line, from sig codes and/or fields
+    async fn set_prescription_line_directions(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: prescription_line::set_directions::SetPrescriptionLineDirectionsInput,
+    ) -> Result<prescription_line::set_directions::SetPrescriptionLineDirectionsResponse> {
+        prescription_line::set_directions::set_directions(ctx, &store_id, input)
+    }
 }
@@ -3,6 +3,7 @@ use async_graphql::*;
 pub mod delete;
 pub mod insert;
 pub mod update;
+pub mod update_discrepancy;
 
 pub struct BatchIsReserved;
 #[Object]
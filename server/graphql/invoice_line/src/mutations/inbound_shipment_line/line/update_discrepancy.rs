@@ -0,0 +1,128 @@
+use async_graphql::*;
+use graphql_core::simple_generic_errors::{CannotEditInvoice, RecordNotFound};
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+use graphql_types::types::{InvoiceLineDiscrepancyNodeType, InvoiceLineNode};
+
+use repository::InvoiceLine;
+use service::auth::{Resource, ResourceAccessRequest};
+use service::invoice_line::update_discrepancy::{
+    UpdateLineDiscrepancy as ServiceInput, UpdateLineDiscrepancyError as ServiceError,
+};
+
+#[derive(InputObject)]
+#[graphql(name = "UpdateInboundShipmentLineDiscrepancyInput")]
+pub struct UpdateDiscrepancyInput {
+    pub id: String,
+    /// Clears the discrepancy when not set
+    pub r#type: Option<InvoiceLineDiscrepancyNodeType>,
+    /// Packs short, damaged, from the wrong batch or in excess of what was shipped
+    pub number_of_packs: Option<f64>,
+    pub reason: Option<String>,
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "UpdateInboundShipmentLineDiscrepancyError")]
+pub struct UpdateDiscrepancyError {
+    pub error: UpdateDiscrepancyErrorInterface,
+}
+
+#[derive(Union)]
+#[graphql(name = "UpdateInboundShipmentLineDiscrepancyResponse")]
+pub enum UpdateDiscrepancyResponse {
+    Error(UpdateDiscrepancyError),
+    Response(InvoiceLineNode),
+}
+
+#[derive(Interface)]
+#[graphql(name = "UpdateInboundShipmentLineDiscrepancyErrorInterface")]
+#[graphql(field(name = "description", ty = "&str"))]
+pub enum UpdateDiscrepancyErrorInterface {
+    RecordNotFound(RecordNotFound),
+    CannotEditInvoice(CannotEditInvoice),
+}
+
+pub fn update_discrepancy(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpdateDiscrepancyInput,
+) -> Result<UpdateDiscrepancyResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateInboundShipment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .invoice_line_service
+            .update_line_discrepancy(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(from: Result<InvoiceLine, ServiceError>) -> Result<UpdateDiscrepancyResponse> {
+    let result = match from {
+        Ok(invoice_line) => {
+            UpdateDiscrepancyResponse::Response(InvoiceLineNode::from_domain(invoice_line))
+        }
+        Err(error) => UpdateDiscrepancyResponse::Error(UpdateDiscrepancyError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+impl UpdateDiscrepancyInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let UpdateDiscrepancyInput {
+            id,
+            r#type,
+            number_of_packs,
+            reason,
+        } = self;
+
+        ServiceInput {
+            line_id: id,
+            r#type: r#type.map(Into::into),
+            number_of_packs: number_of_packs.unwrap_or_default(),
+            reason,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<UpdateDiscrepancyErrorInterface> {
+    use ServiceError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        LineDoesNotExist => {
+            return Ok(UpdateDiscrepancyErrorInterface::RecordNotFound(
+                RecordNotFound {},
+            ))
+        }
+        CannotEditFinalised => {
+            return Ok(UpdateDiscrepancyErrorInterface::CannotEditInvoice(
+                CannotEditInvoice {},
+            ))
+        }
+        // Standard Graphql Errors
+        InvoiceDoesNotExist
+        | NotThisStoreInvoice
+        | NotAnInboundShipment
+        | NotATransferLine
+        | NumberOfPacksMustBePositive
+        | ReasonIsRequired => StandardGraphqlError::BadUserInput(formatted_error),
+        UpdatedLineDoesNotExist | DatabaseError(_) => {
+            StandardGraphqlError::InternalError(formatted_error)
+        }
+    };
+
+    Err(graphql_error.extend())
+}
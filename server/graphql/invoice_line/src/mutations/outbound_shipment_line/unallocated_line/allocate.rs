@@ -3,12 +3,14 @@ use graphql_core::{
     simple_generic_errors::RecordNotFound, standard_graphql_error::validate_auth,
     standard_graphql_error::StandardGraphqlError, ContextExt,
 };
-use graphql_types::types::{DeleteResponse, InvoiceLineConnector, StockLineConnector};
+use graphql_types::types::{
+    AllocationStrategyNodeType, DeleteResponse, InvoiceLineConnector, StockLineConnector,
+};
 use service::{
     auth::{Resource, ResourceAccessRequest},
     invoice_line::outbound_shipment_unallocated_line::{
         AllocateLineResult as ServiceResult,
-        AllocateOutboundShipmentUnallocatedLineError as ServiceError,
+        AllocateOutboundShipmentUnallocatedLineError as ServiceError, StockLineAllocationDecision,
     },
 };
 
@@ -40,6 +42,32 @@ pub struct ResponseNode {
     skipped_expired_stock_lines: StockLineConnector,
     skipped_on_hold_stock_lines: StockLineConnector,
     issued_expiring_soon_stock_lines: StockLineConnector,
+    strategy: AllocationStrategyNodeType,
+    /// Allocation rule of the store used for the customer, default FEFO allocation when not set
+    allocation_rule_id: Option<String>,
+    /// Why each available stock line was or wasn't allocated, in the order they were considered
+    stock_line_decisions: Vec<StockLineAllocationDecisionNode>,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(
+    remote = "service::invoice_line::outbound_shipment_unallocated_line::AllocationDecisionReason"
+)]
+pub enum AllocationDecisionReasonNode {
+    Allocated,
+    AllocatedExpiringSoon,
+    SkippedOnHold,
+    SkippedExpired,
+    SkippedShortShelfLife,
+    SkippedItemVariant,
+    NotNeeded,
+}
+
+#[derive(SimpleObject)]
+pub struct StockLineAllocationDecisionNode {
+    stock_line_id: String,
+    reason: AllocationDecisionReasonNode,
+    number_of_packs: f64,
 }
 
 pub fn allocate(ctx: &Context<'_>, store_id: &str, line_id: String) -> Result<AllocateResponse> {
@@ -102,6 +130,9 @@ impl ResponseNode {
             skipped_expired_stock_lines,
             skipped_on_hold_stock_lines,
             issued_expiring_soon_stock_lines,
+            strategy,
+            allocation_rule_id,
+            stock_line_decisions,
         } = from;
         ResponseNode {
             updates: InvoiceLineConnector::from_vec(updates),
@@ -112,6 +143,22 @@ impl ResponseNode {
             issued_expiring_soon_stock_lines: StockLineConnector::from_vec(
                 issued_expiring_soon_stock_lines,
             ),
+            strategy: AllocationStrategyNodeType::from(strategy),
+            allocation_rule_id,
+            stock_line_decisions: stock_line_decisions
+                .into_iter()
+                .map(
+                    |StockLineAllocationDecision {
+                         stock_line_id,
+                         reason,
+                         number_of_packs,
+                     }| StockLineAllocationDecisionNode {
+                        stock_line_id,
+                        reason: AllocationDecisionReasonNode::from(reason),
+                        number_of_packs,
+                    },
+                )
+                .collect(),
         }
     }
 }
@@ -321,6 +368,7 @@ mod graphql {
                 issued_expiring_soon_stock_lines: vec![inline_init(|r: &mut StockLine| {
                     r.stock_line_row.id = "expiring_soon".to_string();
                 })],
+                ..Default::default()
             })
         }));
 
@@ -4,8 +4,8 @@ use graphql_core::simple_generic_errors::{self, CannotEditInvoice, ForeignKey, F
 use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
 use graphql_core::ContextExt;
 
+use graphql_invoice::clinical_check::ClinicalWarningNode;
 use graphql_types::types::InvoiceLineNode;
-use repository::InvoiceLine;
 use service::auth::{Resource, ResourceAccessRequest};
 
 use crate::mutations::outbound_shipment_line::line::{
@@ -13,7 +13,8 @@ use crate::mutations::outbound_shipment_line::line::{
     StockLineAlreadyExistsInInvoice, StockLineIsOnHold,
 };
 use service::invoice_line::stock_out_line::{
-    InsertStockOutLine as ServiceInput, InsertStockOutLineError as ServiceError, StockOutType,
+    InsertPrescriptionLineResult, InsertStockOutLine as ServiceInput,
+    InsertStockOutLineError as ServiceError, StockOutType,
 };
 
 #[derive(InputObject)]
@@ -32,11 +33,34 @@ pub struct InsertError {
     pub error: InsertErrorInterface,
 }
 
+pub struct InsertPrescriptionLineNode {
+    pub result: InsertPrescriptionLineResult,
+}
+
+#[Object]
+impl InsertPrescriptionLineNode {
+    pub async fn line(&self) -> InvoiceLineNode {
+        InvoiceLineNode::from_domain(self.result.line.clone())
+    }
+
+    /// Interactions and duplicate therapy for the item of the new line, these don't stop the
+    /// line from being added and should be acknowledged when dispensing
+    pub async fn clinical_warnings(&self) -> Vec<ClinicalWarningNode> {
+        self.result
+            .clinical_warnings
+            .iter()
+            .map(|warning| ClinicalWarningNode {
+                warning: warning.clone(),
+            })
+            .collect()
+    }
+}
+
 #[derive(Union)]
 #[graphql(name = "InsertPrescriptionLineResponse")]
 pub enum InsertResponse {
     Error(InsertError),
-    Response(InvoiceLineNode),
+    Response(InsertPrescriptionLineNode),
 }
 
 pub fn insert(ctx: &Context<'_>, store_id: &str, input: InsertInput) -> Result<InsertResponse> {
@@ -54,13 +78,15 @@ pub fn insert(ctx: &Context<'_>, store_id: &str, input: InsertInput) -> Result<I
     map_response(
         service_provider
             .invoice_line_service
-            .insert_stock_out_line(&service_context, input.to_domain()),
+            .insert_prescription_line(&service_context, input.to_domain()),
     )
 }
 
-pub fn map_response(from: Result<InvoiceLine, ServiceError>) -> Result<InsertResponse> {
+pub fn map_response(
+    from: Result<InsertPrescriptionLineResult, ServiceError>,
+) -> Result<InsertResponse> {
     let result = match from {
-        Ok(invoice_line) => InsertResponse::Response(InvoiceLineNode::from_domain(invoice_line)),
+        Ok(result) => InsertResponse::Response(InsertPrescriptionLineNode { result }),
         Err(error) => InsertResponse::Error(InsertError {
             error: map_error(error)?,
         }),
@@ -190,6 +216,7 @@ mod test {
     use serde_json::json;
     use service::{
         invoice_line::{
+            stock_out_line::InsertPrescriptionLineResult,
             stock_out_line::InsertStockOutLineError as ServiceError,
             stock_out_line::{InsertStockOutLine as ServiceInput, StockOutType},
             InvoiceLineServiceTrait,
@@ -199,16 +226,17 @@ mod test {
 
     use crate::InvoiceLineMutations;
 
-    type InsertLineMethod = dyn Fn(ServiceInput) -> Result<InvoiceLine, ServiceError> + Sync + Send;
+    type InsertLineMethod =
+        dyn Fn(ServiceInput) -> Result<InsertPrescriptionLineResult, ServiceError> + Sync + Send;
 
     pub struct TestService(pub Box<InsertLineMethod>);
 
     impl InvoiceLineServiceTrait for TestService {
-        fn insert_stock_out_line(
+        fn insert_prescription_line(
             &self,
             _: &ServiceContext,
             input: ServiceInput,
-        ) -> Result<InvoiceLine, ServiceError> {
+        ) -> Result<InsertPrescriptionLineResult, ServiceError> {
             self.0(input)
         }
     }
@@ -515,10 +543,15 @@ mod test {
         let mutation = r#"
         mutation ($input: InsertPrescriptionLineInput!) {
             insertPrescriptionLine(input: $input, storeId: \"store_a\") {
-                ... on InvoiceLineNode {
-                    id
-                    invoiceId
-                    itemName
+                ... on InsertPrescriptionLineNode {
+                    line {
+                        id
+                        invoiceId
+                        itemName
+                    }
+                    clinicalWarnings {
+                        id
+                    }
                 }
             }
           }
@@ -545,12 +578,15 @@ mod test {
                     sell_price_per_pack: None
                 }
             );
-            Ok(InvoiceLine {
-                invoice_line_row: mock_prescription_a_invoice_lines()[0].clone(),
-                invoice_row: mock_prescription_a(),
-                item_row: mock_item_a(),
-                location_row_option: Some(mock_location_1()),
-                stock_line_option: None,
+            Ok(InsertPrescriptionLineResult {
+                line: InvoiceLine {
+                    invoice_line_row: mock_prescription_a_invoice_lines()[0].clone(),
+                    invoice_row: mock_prescription_a(),
+                    item_row: mock_item_a(),
+                    location_row_option: Some(mock_location_1()),
+                    stock_line_option: None,
+                },
+                clinical_warnings: vec![],
             })
         }));
 
@@ -566,7 +602,10 @@ mod test {
 
         let expected = json!({
             "insertPrescriptionLine": {
-                "id": mock_prescription_a_invoice_lines()[0].id
+                "line": {
+                    "id": mock_prescription_a_invoice_lines()[0].id
+                },
+                "clinicalWarnings": []
             }
           }
         );
@@ -3,3 +3,5 @@ pub mod insert;
 pub mod update;
 
 pub mod delete;
+
+pub mod set_directions;
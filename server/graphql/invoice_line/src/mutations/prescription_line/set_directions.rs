@@ -0,0 +1,142 @@
+use async_graphql::*;
+use graphql_core::simple_generic_errors::{CannotEditInvoice, RecordNotFound};
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+use graphql_types::types::{AdministrationRouteType, PrescriptionLineDirectionNode};
+
+use repository::PrescriptionLineDirectionRow;
+use service::auth::{Resource, ResourceAccessRequest};
+use service::invoice::prescription::{
+    SetPrescriptionLineDirections as ServiceInput,
+    SetPrescriptionLineDirectionsError as ServiceError,
+};
+
+#[derive(InputObject)]
+pub struct SetPrescriptionLineDirectionsInput {
+    pub invoice_line_id: String,
+    /// Sig codes, e.g. "1T PO BD x5/7", expanded to fill in any field not provided
+    pub sig: Option<String>,
+    pub dose: Option<f64>,
+    pub dose_unit: Option<String>,
+    pub frequency_per_day: Option<f64>,
+    pub duration_days: Option<i32>,
+    pub route: Option<AdministrationRouteType>,
+    /// Overrides the generated full text directions
+    pub directions: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct SetPrescriptionLineDirectionsError {
+    pub error: SetPrescriptionLineDirectionsErrorInterface,
+}
+
+#[derive(Union)]
+pub enum SetPrescriptionLineDirectionsResponse {
+    Error(SetPrescriptionLineDirectionsError),
+    Response(PrescriptionLineDirectionNode),
+}
+
+#[derive(Interface)]
+#[graphql(field(name = "description", ty = "&str"))]
+pub enum SetPrescriptionLineDirectionsErrorInterface {
+    RecordNotFound(RecordNotFound),
+    CannotEditInvoice(CannotEditInvoice),
+}
+
+pub fn set_directions(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: SetPrescriptionLineDirectionsInput,
+) -> Result<SetPrescriptionLineDirectionsResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutatePrescription,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .invoice_service
+            .set_prescription_line_directions(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(
+    from: Result<PrescriptionLineDirectionRow, ServiceError>,
+) -> Result<SetPrescriptionLineDirectionsResponse> {
+    let result = match from {
+        Ok(direction) => SetPrescriptionLineDirectionsResponse::Response(
+            PrescriptionLineDirectionNode::from_domain(direction),
+        ),
+        Err(error) => {
+            SetPrescriptionLineDirectionsResponse::Error(SetPrescriptionLineDirectionsError {
+                error: map_error(error)?,
+            })
+        }
+    };
+
+    Ok(result)
+}
+
+impl SetPrescriptionLineDirectionsInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let SetPrescriptionLineDirectionsInput {
+            invoice_line_id,
+            sig,
+            dose,
+            dose_unit,
+            frequency_per_day,
+            duration_days,
+            route,
+            directions,
+        } = self;
+
+        ServiceInput {
+            invoice_line_id,
+            sig,
+            dose,
+            dose_unit,
+            frequency_per_day,
+            duration_days,
+            route: route.map(Into::into),
+            directions,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<SetPrescriptionLineDirectionsErrorInterface> {
+    use ServiceError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        LineDoesNotExist => {
+            return Ok(SetPrescriptionLineDirectionsErrorInterface::RecordNotFound(
+                RecordNotFound {},
+            ))
+        }
+        CannotEditFinalised => {
+            return Ok(
+                SetPrescriptionLineDirectionsErrorInterface::CannotEditInvoice(
+                    CannotEditInvoice {},
+                ),
+            )
+        }
+        // Standard Graphql Errors
+        InvoiceDoesNotExist
+        | NotThisStoreInvoice
+        | NotAPrescriptionInvoice
+        | DoseMustBePositive
+        | FrequencyMustBePositive
+        | DurationMustBePositive
+        | NoDirections => StandardGraphqlError::BadUserInput(formatted_error),
+        DatabaseError(_) => StandardGraphqlError::InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
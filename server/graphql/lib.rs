@@ -2,11 +2,13 @@
 mod tests;
 
 use std::sync::Mutex;
+use std::time::Instant;
 
 use actix_web::web::{self, Data};
 use actix_web::HttpResponse;
 use actix_web::{guard, HttpRequest};
 
+use async_graphql::parser::types::{DocumentOperations, OperationType};
 use async_graphql::{EmptyMutation, EmptySubscription, Object, Schema};
 use async_graphql::{MergedObject, Response};
 use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
@@ -37,12 +39,12 @@ use graphql_item_bundle::BundledItemMutations;
 use graphql_item_variant::{ItemVariantMutations, ItemVariantQueries};
 use graphql_location::{LocationMutations, LocationQueries};
 use graphql_plugin::{PluginMutations, PluginQueries};
-use graphql_programs::{ProgramsMutations, ProgramsQueries};
+use graphql_programs::{CentralProgramsMutations, ProgramsMutations, ProgramsQueries};
 use graphql_repack::{RepackMutations, RepackQueries};
 use graphql_reports::ReportQueries;
 use graphql_requisition::{RequisitionMutations, RequisitionQueries};
 use graphql_requisition_line::RequisitionLineMutations;
-use graphql_stock_line::{StockLineMutations, StockLineQueries};
+use graphql_stock_line::{CentralStockLineMutations, StockLineMutations, StockLineQueries};
 use graphql_stocktake::{StocktakeMutations, StocktakeQueries};
 use graphql_stocktake_line::{StocktakeLineMutations, StocktakeLineQueries};
 
@@ -54,6 +56,7 @@ use service::service_provider::ServiceProvider;
 use service::settings::Settings;
 use service::sync::CentralServerConfig;
 use tokio::sync::RwLock;
+use util::metrics;
 
 pub type OperationalSchema =
     async_graphql::Schema<Queries, Mutations, async_graphql::EmptySubscription>;
@@ -88,6 +91,14 @@ impl CentralServerMutationNode {
     async fn general(&self) -> CentralGeneralMutations {
         CentralGeneralMutations
     }
+
+    async fn stock_line(&self) -> CentralStockLineMutations {
+        CentralStockLineMutations
+    }
+
+    async fn program(&self) -> CentralProgramsMutations {
+        CentralProgramsMutations
+    }
 }
 
 #[derive(Default, Clone)]
@@ -284,14 +295,42 @@ impl GraphqlSchema {
     }
 
     async fn execute(&self, http_req: HttpRequest, req: GraphQLRequest) -> Response {
-        let req = req.into_inner();
-        if *self.is_operational.read().await {
+        let mut req = req.into_inner();
+        let operation = operation_type(&mut req);
+        let start = Instant::now();
+
+        let response = if *self.is_operational.read().await {
             // auth_data is only available in schema in operational mode
             let user_data = auth_data_from_request(&http_req);
             self.operational.execute(req.data(user_data)).await
         } else {
             self.initialisation.execute(req).await
-        }
+        };
+
+        metrics::GRAPHQL_DURATION.observe_since(&[("operation", operation)], start);
+        let result = if response.is_ok() { "success" } else { "error" };
+        metrics::GRAPHQL_REQUESTS.inc(&[("operation", operation), ("result", result)]);
+        response
+    }
+}
+
+/// Metric label for the request, operation names are chosen by the client so the operation type
+/// is used to keep the number of label values bounded
+fn operation_type(req: &mut async_graphql::Request) -> &'static str {
+    let operation_name = req.operation_name.clone();
+    let Ok(document) = req.parsed_query() else {
+        return "invalid";
+    };
+    let operation = match (&document.operations, operation_name) {
+        (DocumentOperations::Single(operation), _) => Some(operation),
+        (DocumentOperations::Multiple(operations), Some(name)) => operations.get(name.as_str()),
+        (DocumentOperations::Multiple(operations), None) => operations.values().next(),
+    };
+    match operation.map(|operation| operation.node.ty) {
+        Some(OperationType::Query) => "query",
+        Some(OperationType::Mutation) => "mutation",
+        Some(OperationType::Subscription) => "subscription",
+        None => "unknown",
     }
 }
 
@@ -379,3 +418,27 @@ pub fn attach_discovery_graphql_schema(
 async fn discovery_index(schema: Data<DiscoverySchema>, req: GraphQLRequest) -> GraphQLResponse {
     schema.execute(req.into_inner()).await.into()
 }
+
+#[cfg(test)]
+mod operation_type_test {
+    use async_graphql::Request;
+
+    use super::operation_type;
+
+    #[test]
+    fn test_operation_type_metric_label() {
+        let mut request = Request::new("query anyClientName { me { id } }");
+        assert_eq!(operation_type(&mut request), "query");
+
+        let mut request = Request::new("query a { me { id } } mutation b { logout { userId } }")
+            .operation_name("b");
+        assert_eq!(operation_type(&mut request), "mutation");
+
+        let mut request = Request::new("query a { me { id } } query b { me { id } }")
+            .operation_name("unknown_name");
+        assert_eq!(operation_type(&mut request), "unknown");
+
+        let mut request = Request::new("not a graphql query");
+        assert_eq!(operation_type(&mut request), "invalid");
+    }
+}
@@ -56,12 +56,23 @@ use mutations::rnr_form::finalise::{
     finalise_rnr_form, FinaliseRnRFormInput, FinaliseRnRFormResponse,
 };
 use mutations::rnr_form::insert::{insert_rnr_form, InsertRnRFormInput, InsertRnRFormResponse};
+use mutations::rnr_form::lmis_code_mapping::{
+    delete_lmis_code_mapping, upsert_lmis_code_mapping, DeleteLmisCodeMappingResponse,
+    UpsertLmisCodeMappingInput, UpsertLmisCodeMappingResponse,
+};
 use mutations::rnr_form::update::update_rnr_form;
 use mutations::rnr_form::update::UpdateRnRFormInput;
 use mutations::rnr_form::update::UpdateRnRFormResponse;
+use mutations::vaccination::discard_open_vial::{
+    discard_vaccine_open_vial, DiscardVaccineOpenVialInput, DiscardVaccineOpenVialResponse,
+};
 use mutations::vaccination::insert::{
     insert_vaccination, InsertVaccinationInput, InsertVaccinationResponse,
 };
+use mutations::vaccination::tracing_attempt::{
+    log_vaccination_tracing_attempt, LogVaccinationTracingAttemptInput,
+    LogVaccinationTracingAttemptResponse,
+};
 use mutations::vaccination::update::{
     update_vaccination, UpdateVaccinationInput, UpdateVaccinationResponse,
 };
@@ -69,6 +80,9 @@ use queries::contact_trace::contact_traces;
 use service::auth::Resource;
 use service::auth::ResourceAccessRequest;
 use service::programs::patient::patient_search_central;
+use types::lmis_code_mapping::{
+    LmisCodeMappingConnector, LmisExportFormatInput, RnRFormLmisExportNode,
+};
 use types::program::ProgramFilterInput;
 use types::program::ProgramSortInput;
 use types::program::ProgramsResponse;
@@ -291,6 +305,27 @@ impl ProgramsQueries {
         get_schedules_with_periods_by_program(ctx, store_id, program_id)
     }
 
+    /// Codes used for the program, facilities, items and R&R fields when exporting to an LMIS
+    pub async fn lmis_code_mappings(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        program_id: String,
+    ) -> Result<LmisCodeMappingConnector> {
+        lmis_code_mappings(ctx, store_id, program_id)
+    }
+
+    /// Exports a finalised R&R form to an LMIS interchange format
+    pub async fn export_rnr_form_to_lmis(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        rnr_form_id: String,
+        format: LmisExportFormatInput,
+    ) -> Result<RnRFormLmisExportNode> {
+        export_rnr_form_to_lmis(ctx, store_id, rnr_form_id, format)
+    }
+
     pub async fn vaccination(
         &self,
         ctx: &Context<'_>,
@@ -307,6 +342,44 @@ impl ProgramsQueries {
     ) -> Result<VaccinationCardResponse> {
         vaccination_card(ctx, store_id, program_enrolment_id)
     }
+
+    /// Patients whose next vaccine dose is overdue, grouped by vaccine course
+    pub async fn vaccination_defaulters(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        filter: Option<VaccinationDefaulterFilterInput>,
+    ) -> Result<VaccinationDefaultersConnector> {
+        vaccination_defaulters(ctx, store_id, filter)
+    }
+
+    /// Generates a CSV file of the vaccination defaulter list
+    pub async fn export_vaccination_defaulters(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        filter: Option<VaccinationDefaulterFilterInput>,
+    ) -> Result<VaccinationDefaultersFileNode> {
+        export_vaccination_defaulters(ctx, store_id, filter)
+    }
+
+    /// Multi-dose vaccine vials that have been opened and still have doses remaining
+    pub async fn vaccine_open_vials(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<VaccineOpenVialConnector> {
+        vaccine_open_vials(ctx, store_id)
+    }
+
+    /// Open vial wastage statistics per vaccine item
+    pub async fn vaccine_wastage(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<VaccineWastageConnector> {
+        vaccine_wastage(ctx, store_id)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -484,4 +557,48 @@ impl ProgramsMutations {
     ) -> Result<UpdateVaccinationResponse> {
         update_vaccination(ctx, store_id, input)
     }
+
+    pub async fn log_vaccination_tracing_attempt(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: LogVaccinationTracingAttemptInput,
+    ) -> Result<LogVaccinationTracingAttemptResponse> {
+        log_vaccination_tracing_attempt(ctx, store_id, input)
+    }
+
+    /// Discards the doses remaining in an open vial as wastage
+    pub async fn discard_vaccine_open_vial(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: DiscardVaccineOpenVialInput,
+    ) -> Result<DiscardVaccineOpenVialResponse> {
+        discard_vaccine_open_vial(ctx, store_id, input)
+    }
+}
+
+/// LMIS code mappings are central data, synced to remote sites
+#[derive(Default, Clone)]
+pub struct CentralProgramsMutations;
+
+#[Object]
+impl CentralProgramsMutations {
+    pub async fn upsert_lmis_code_mapping(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpsertLmisCodeMappingInput,
+    ) -> Result<UpsertLmisCodeMappingResponse> {
+        upsert_lmis_code_mapping(ctx, store_id, input)
+    }
+
+    pub async fn delete_lmis_code_mapping(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<DeleteLmisCodeMappingResponse> {
+        delete_lmis_code_mapping(ctx, store_id, id)
+    }
 }
@@ -0,0 +1,120 @@
+use async_graphql::*;
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::DeleteResponse;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    rnr_form::lmis_code_mapping::{
+        UpsertLmisCodeMapping, UpsertLmisCodeMappingError as ServiceError,
+    },
+};
+
+use crate::types::lmis_code_mapping::{LmisCodeMappingNode, LmisCodeMappingNodeType};
+
+#[derive(InputObject)]
+pub struct UpsertLmisCodeMappingInput {
+    pub id: String,
+    pub program_id: String,
+    pub r#type: LmisCodeMappingNodeType,
+    pub internal_id: String,
+    pub external_code: String,
+}
+
+#[derive(Union)]
+pub enum UpsertLmisCodeMappingResponse {
+    Response(LmisCodeMappingNode),
+}
+
+#[derive(Union)]
+pub enum DeleteLmisCodeMappingResponse {
+    Response(DeleteResponse),
+}
+
+pub fn upsert_lmis_code_mapping(
+    ctx: &Context<'_>,
+    store_id: String,
+    input: UpsertLmisCodeMappingInput,
+) -> Result<UpsertLmisCodeMappingResponse> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateLmisCodeMapping,
+            store_id: Some(store_id),
+        },
+    )?;
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+
+    match service_provider
+        .rnr_form_service
+        .upsert_lmis_code_mapping(&service_context, input.to_domain())
+    {
+        Ok(row) => Ok(UpsertLmisCodeMappingResponse::Response(
+            LmisCodeMappingNode::from_domain(row),
+        )),
+        Err(error) => map_error(error),
+    }
+}
+
+pub fn delete_lmis_code_mapping(
+    ctx: &Context<'_>,
+    store_id: String,
+    id: String,
+) -> Result<DeleteLmisCodeMappingResponse> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateLmisCodeMapping,
+            store_id: Some(store_id),
+        },
+    )?;
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+
+    let id = service_provider
+        .rnr_form_service
+        .delete_lmis_code_mapping(&service_context, id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(DeleteLmisCodeMappingResponse::Response(DeleteResponse(id)))
+}
+
+fn map_error(error: ServiceError) -> Result<UpsertLmisCodeMappingResponse> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::ProgramDoesNotExist
+        | ServiceError::ExternalCodeCannotBeEmpty
+        | ServiceError::DuplicateMapping => BadUserInput(formatted_error),
+
+        ServiceError::CreatedRecordNotFound | ServiceError::DatabaseError(_) => {
+            InternalError(formatted_error)
+        }
+    };
+
+    Err(graphql_error.extend())
+}
+
+impl UpsertLmisCodeMappingInput {
+    fn to_domain(self) -> UpsertLmisCodeMapping {
+        let UpsertLmisCodeMappingInput {
+            id,
+            program_id,
+            r#type,
+            internal_id,
+            external_code,
+        } = self;
+
+        UpsertLmisCodeMapping {
+            id,
+            program_id,
+            r#type: r#type.to_domain(),
+            internal_id,
+            external_code,
+        }
+    }
+}
@@ -1,3 +1,4 @@
 pub mod finalise;
 pub mod insert;
+pub mod lmis_code_mapping;
 pub mod update;
@@ -0,0 +1,83 @@
+use async_graphql::*;
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::vaccine_open_vial::VaccineOpenVialNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    vaccination::open_vial::{DiscardOpenVial, DiscardOpenVialError as ServiceError},
+};
+
+#[derive(InputObject)]
+pub struct DiscardVaccineOpenVialInput {
+    pub id: String,
+    pub inventory_adjustment_reason_id: Option<String>,
+}
+
+impl From<DiscardVaccineOpenVialInput> for DiscardOpenVial {
+    fn from(
+        DiscardVaccineOpenVialInput {
+            id,
+            inventory_adjustment_reason_id,
+        }: DiscardVaccineOpenVialInput,
+    ) -> Self {
+        Self {
+            id,
+            inventory_adjustment_reason_id,
+        }
+    }
+}
+
+#[derive(Union)]
+pub enum DiscardVaccineOpenVialResponse {
+    Response(VaccineOpenVialNode),
+}
+
+pub fn discard_vaccine_open_vial(
+    ctx: &Context<'_>,
+    store_id: String,
+    input: DiscardVaccineOpenVialInput,
+) -> Result<DiscardVaccineOpenVialResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateInventoryAdjustment,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let result = service_provider
+        .vaccination_service
+        .discard_open_vial(&service_context, input.into());
+
+    let result = match result {
+        Ok(open_vial) => {
+            DiscardVaccineOpenVialResponse::Response(VaccineOpenVialNode::from_domain(open_vial))
+        }
+        Err(error) => map_error(error)?,
+    };
+
+    Ok(result)
+}
+
+fn map_error(error: ServiceError) -> Result<DiscardVaccineOpenVialResponse> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::OpenVialDoesNotExist
+        | ServiceError::OpenVialAlreadyClosed
+        | ServiceError::AdjustmentReasonNotProvided
+        | ServiceError::AdjustmentReasonNotValid => BadUserInput(formatted_error),
+        ServiceError::InternalError(_) | ServiceError::DatabaseError(_) => {
+            InternalError(formatted_error)
+        }
+    };
+
+    Err(graphql_error.extend())
+}
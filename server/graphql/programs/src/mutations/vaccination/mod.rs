@@ -1,6 +1,8 @@
 use async_graphql::Object;
 
+pub mod discard_open_vial;
 pub mod insert;
+pub mod tracing_attempt;
 pub mod update;
 
 pub struct NotMostRecentGivenDose;
@@ -0,0 +1,92 @@
+use async_graphql::*;
+
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::program_enrolment::ProgramEnrolmentNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    vaccination::tracing_attempt::{LogTracingAttempt, LogTracingAttemptError as ServiceError},
+};
+
+#[derive(InputObject)]
+pub struct LogVaccinationTracingAttemptInput {
+    pub program_enrolment_id: String,
+    pub outcome: String,
+    pub datetime: Option<DateTime<Utc>>,
+}
+
+impl From<LogVaccinationTracingAttemptInput> for LogTracingAttempt {
+    fn from(
+        LogVaccinationTracingAttemptInput {
+            program_enrolment_id,
+            outcome,
+            datetime,
+        }: LogVaccinationTracingAttemptInput,
+    ) -> Self {
+        Self {
+            program_enrolment_id,
+            outcome,
+            datetime: datetime.map(|datetime| datetime.naive_utc()),
+        }
+    }
+}
+
+#[derive(Union)]
+pub enum LogVaccinationTracingAttemptResponse {
+    Response(ProgramEnrolmentNode),
+}
+
+pub fn log_vaccination_tracing_attempt(
+    ctx: &Context<'_>,
+    store_id: String,
+    input: LogVaccinationTracingAttemptInput,
+) -> Result<LogVaccinationTracingAttemptResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateEncounter,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let allowed_ctx = user.capabilities();
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.clone(), user.user_id.clone())?;
+
+    let result = service_provider.vaccination_service.log_tracing_attempt(
+        &service_context,
+        input.into(),
+        allowed_ctx.clone(),
+    );
+
+    let result = match result {
+        Ok(program_enrolment) => {
+            LogVaccinationTracingAttemptResponse::Response(ProgramEnrolmentNode {
+                store_id,
+                program_enrolment,
+                allowed_ctx: allowed_ctx.clone(),
+            })
+        }
+        Err(error) => map_error(error)?,
+    };
+
+    Ok(result)
+}
+
+fn map_error(error: ServiceError) -> Result<LogVaccinationTracingAttemptResponse> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::ProgramEnrolmentDoesNotExist | ServiceError::OutcomeNotProvided => {
+            BadUserInput(formatted_error)
+        }
+        ServiceError::NotAllowedToMutateDocument => Forbidden(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
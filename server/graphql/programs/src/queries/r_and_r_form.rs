@@ -7,10 +7,14 @@ use graphql_core::{
 };
 
 use graphql_types::types::rnr_form::RnRFormNode;
-use repository::{PaginationOption, RnRFormFilter};
-use service::auth::{Resource, ResourceAccessRequest};
+use repository::{EqualFilter, LmisCodeMappingFilter, PaginationOption, RnRFormFilter};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    rnr_form::lmis_export::LmisExportError,
+};
 
 use crate::types::{
+    lmis_code_mapping::{LmisCodeMappingConnector, LmisExportFormatInput, RnRFormLmisExportNode},
     period_schedule::{PeriodSchedulesConnector, PeriodSchedulesResponse},
     r_and_r_form::{
         RnRFormConnector, RnRFormFilterInput, RnRFormResponse, RnRFormSortInput, RnRFormsResponse,
@@ -110,3 +114,76 @@ pub fn get_schedules_with_periods_by_program(
         PeriodSchedulesConnector::from_domain(result),
     ))
 }
+
+pub fn lmis_code_mappings(
+    ctx: &Context<'_>,
+    store_id: String,
+    program_id: String,
+) -> Result<LmisCodeMappingConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryRnRForms,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_provider = ctx.service_provider();
+    let context = service_provider.context(store_id, user.user_id)?;
+
+    let rows = service_provider
+        .rnr_form_service
+        .get_lmis_code_mappings(
+            &context,
+            LmisCodeMappingFilter::new().program_id(EqualFilter::equal_to(&program_id)),
+        )
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(LmisCodeMappingConnector::from_domain(rows))
+}
+
+pub fn export_rnr_form_to_lmis(
+    ctx: &Context<'_>,
+    store_id: String,
+    rnr_form_id: String,
+    format: LmisExportFormatInput,
+) -> Result<RnRFormLmisExportNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryRnRForms,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_provider = ctx.service_provider();
+    let context = service_provider.context(store_id.clone(), user.user_id)?;
+
+    let file_id = service_provider
+        .rnr_form_service
+        .export_rnr_form_to_lmis(
+            &context,
+            &ctx.get_settings().server.base_dir,
+            &store_id,
+            &rnr_form_id,
+            format.to_domain(),
+        )
+        .map_err(|error| {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                LmisExportError::RnRFormDoesNotExist
+                | LmisExportError::RnRFormDoesNotBelongToStore
+                | LmisExportError::RnRFormNotFinalised
+                | LmisExportError::ProgramCodeNotMapped
+                | LmisExportError::FacilityCodeNotMapped
+                | LmisExportError::ItemCodesNotMapped(_) => BadUserInput(formatted_error),
+
+                LmisExportError::FileError(_) | LmisExportError::DatabaseError(_) => {
+                    InternalError(formatted_error)
+                }
+            };
+            graphql_error.extend()
+        })?;
+
+    Ok(RnRFormLmisExportNode { file_id })
+}
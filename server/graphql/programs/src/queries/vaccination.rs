@@ -1,12 +1,21 @@
 use async_graphql::*;
+use chrono::NaiveDate;
 use graphql_core::{
     simple_generic_errors::{ErrorWrapper, NodeError, NodeErrorInterface, RecordNotFound},
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
 };
-use graphql_types::types::{vaccination::VaccinationNode, vaccination_card::VaccinationCardNode};
+use graphql_types::types::{
+    vaccination::VaccinationNode,
+    vaccination_card::VaccinationCardNode,
+    vaccination_defaulter::VaccineCourseDefaultersNode,
+    vaccine_open_vial::{VaccineOpenVialNode, VaccineWastageNode},
+};
 use repository::RepositoryError;
-use service::auth::{Resource, ResourceAccessRequest};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    vaccination::defaulters::VaccinationDefaulterFilter,
+};
 
 #[derive(Union)]
 pub enum VaccinationCardResponse {
@@ -74,3 +83,168 @@ pub fn vaccination(
         },
     }
 }
+
+#[derive(InputObject, Clone)]
+pub struct VaccinationDefaulterFilterInput {
+    pub program_id: Option<String>,
+    pub vaccine_course_id: Option<String>,
+    /// Only include patients whose next dose is overdue by more than this many days
+    pub min_days_overdue: Option<i64>,
+    /// Date to calculate overdue days from, defaults to today
+    pub reference_date: Option<NaiveDate>,
+}
+
+impl VaccinationDefaulterFilterInput {
+    pub fn to_domain(self) -> VaccinationDefaulterFilter {
+        let VaccinationDefaulterFilterInput {
+            program_id,
+            vaccine_course_id,
+            min_days_overdue,
+            reference_date,
+        } = self;
+
+        VaccinationDefaulterFilter {
+            program_id,
+            vaccine_course_id,
+            min_days_overdue: min_days_overdue.unwrap_or(0),
+            reference_date,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct VaccinationDefaultersConnector {
+    pub nodes: Vec<VaccineCourseDefaultersNode>,
+}
+
+pub fn vaccination_defaulters(
+    ctx: &Context<'_>,
+    store_id: String,
+    filter: Option<VaccinationDefaulterFilterInput>,
+) -> Result<VaccinationDefaultersConnector> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryEncounter,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let allowed_ctx = user.capabilities();
+    let service_provider = ctx.service_provider();
+    let context = service_provider.basic_context()?;
+
+    let courses = service_provider
+        .vaccination_service
+        .get_vaccination_defaulters(
+            &context,
+            filter.map(|f| f.to_domain()).unwrap_or_default(),
+            allowed_ctx.clone(),
+        )
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(VaccinationDefaultersConnector {
+        nodes: courses
+            .into_iter()
+            .map(VaccineCourseDefaultersNode::from_domain)
+            .collect(),
+    })
+}
+
+pub struct VaccinationDefaultersFileNode {
+    file_id: String,
+}
+
+#[Object]
+impl VaccinationDefaultersFileNode {
+    /// The CSV file can be fetched using the /files?id={id} endpoint
+    pub async fn file_id(&self) -> &str {
+        &self.file_id
+    }
+}
+
+pub fn export_vaccination_defaulters(
+    ctx: &Context<'_>,
+    store_id: String,
+    filter: Option<VaccinationDefaulterFilterInput>,
+) -> Result<VaccinationDefaultersFileNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryEncounter,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let allowed_ctx = user.capabilities();
+    let service_provider = ctx.service_provider();
+    let context = service_provider.basic_context()?;
+
+    let file_id = service_provider
+        .vaccination_service
+        .export_vaccination_defaulters(
+            &context,
+            &ctx.get_settings().server.base_dir,
+            filter.map(|f| f.to_domain()).unwrap_or_default(),
+            allowed_ctx.clone(),
+        )
+        .map_err(|err| StandardGraphqlError::InternalError(format!("{:#?}", err)).extend())?;
+
+    Ok(VaccinationDefaultersFileNode { file_id })
+}
+
+#[derive(SimpleObject)]
+pub struct VaccineOpenVialConnector {
+    pub nodes: Vec<VaccineOpenVialNode>,
+}
+
+pub fn vaccine_open_vials(ctx: &Context<'_>, store_id: String) -> Result<VaccineOpenVialConnector> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStockLine,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_provider = ctx.service_provider();
+    let context = service_provider.basic_context()?;
+
+    let open_vials = service_provider
+        .vaccination_service
+        .get_open_vials(&context, &store_id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(VaccineOpenVialConnector {
+        nodes: open_vials
+            .into_iter()
+            .map(VaccineOpenVialNode::from_domain)
+            .collect(),
+    })
+}
+
+#[derive(SimpleObject)]
+pub struct VaccineWastageConnector {
+    pub nodes: Vec<VaccineWastageNode>,
+}
+
+pub fn vaccine_wastage(ctx: &Context<'_>, store_id: String) -> Result<VaccineWastageConnector> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStockLine,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+    let service_provider = ctx.service_provider();
+    let context = service_provider.basic_context()?;
+
+    let wastage = service_provider
+        .vaccination_service
+        .get_vaccine_wastage(&context, &store_id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(VaccineWastageConnector {
+        nodes: wastage
+            .into_iter()
+            .map(VaccineWastageNode::from_domain)
+            .collect(),
+    })
+}
@@ -0,0 +1,110 @@
+use async_graphql::*;
+use repository::{LmisCodeMappingRow, LmisCodeMappingType};
+use service::rnr_form::lmis_export::LmisExportFormat;
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LmisCodeMappingNodeType {
+    Program,
+    Facility,
+    Item,
+    Field,
+}
+
+impl LmisCodeMappingNodeType {
+    pub fn from_domain(r#type: &LmisCodeMappingType) -> Self {
+        match r#type {
+            LmisCodeMappingType::Program => LmisCodeMappingNodeType::Program,
+            LmisCodeMappingType::Facility => LmisCodeMappingNodeType::Facility,
+            LmisCodeMappingType::Item => LmisCodeMappingNodeType::Item,
+            LmisCodeMappingType::Field => LmisCodeMappingNodeType::Field,
+        }
+    }
+
+    pub fn to_domain(self) -> LmisCodeMappingType {
+        match self {
+            LmisCodeMappingNodeType::Program => LmisCodeMappingType::Program,
+            LmisCodeMappingNodeType::Facility => LmisCodeMappingType::Facility,
+            LmisCodeMappingNodeType::Item => LmisCodeMappingType::Item,
+            LmisCodeMappingNodeType::Field => LmisCodeMappingType::Field,
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LmisExportFormatInput {
+    OpenLmisJson,
+    Dhis2DataValueSet,
+    Csv,
+}
+
+impl LmisExportFormatInput {
+    pub fn to_domain(self) -> LmisExportFormat {
+        match self {
+            LmisExportFormatInput::OpenLmisJson => LmisExportFormat::OpenLmisJson,
+            LmisExportFormatInput::Dhis2DataValueSet => LmisExportFormat::Dhis2DataValueSet,
+            LmisExportFormatInput::Csv => LmisExportFormat::Csv,
+        }
+    }
+}
+
+pub struct LmisCodeMappingNode {
+    pub row: LmisCodeMappingRow,
+}
+
+#[Object]
+impl LmisCodeMappingNode {
+    pub async fn id(&self) -> &str {
+        &self.row.id
+    }
+
+    pub async fn program_id(&self) -> &str {
+        &self.row.program_id
+    }
+
+    pub async fn r#type(&self) -> LmisCodeMappingNodeType {
+        LmisCodeMappingNodeType::from_domain(&self.row.r#type)
+    }
+
+    /// Program, store or item id, or the R&R field key for field mappings
+    pub async fn internal_id(&self) -> &str {
+        &self.row.internal_id
+    }
+
+    pub async fn external_code(&self) -> &str {
+        &self.row.external_code
+    }
+}
+
+impl LmisCodeMappingNode {
+    pub fn from_domain(row: LmisCodeMappingRow) -> LmisCodeMappingNode {
+        LmisCodeMappingNode { row }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct LmisCodeMappingConnector {
+    pub nodes: Vec<LmisCodeMappingNode>,
+}
+
+impl LmisCodeMappingConnector {
+    pub fn from_domain(rows: Vec<LmisCodeMappingRow>) -> LmisCodeMappingConnector {
+        LmisCodeMappingConnector {
+            nodes: rows
+                .into_iter()
+                .map(LmisCodeMappingNode::from_domain)
+                .collect(),
+        }
+    }
+}
+
+pub struct RnRFormLmisExportNode {
+    pub file_id: String,
+}
+
+#[Object]
+impl RnRFormLmisExportNode {
+    /// The export file can be fetched using the /files?id={id} endpoint
+    pub async fn file_id(&self) -> &str {
+        &self.file_id
+    }
+}
@@ -1,3 +1,4 @@
+pub mod lmis_code_mapping;
 pub mod period_schedule;
 pub mod program;
 pub mod r_and_r_form;
@@ -0,0 +1,162 @@
+use async_graphql::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::BackorderRow;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    backorder::{OutstandingBackorder, OutstandingBackordersInput},
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "repository::BackorderStatus")]
+pub enum BackorderNodeStatus {
+    Open,
+    Fulfilled,
+    Cancelled,
+}
+
+pub struct BackorderNode {
+    pub backorder: BackorderRow,
+}
+
+#[Object]
+impl BackorderNode {
+    pub async fn id(&self) -> &str {
+        &self.backorder.id
+    }
+
+    pub async fn requisition_id(&self) -> Option<&str> {
+        self.backorder.requisition_id.as_deref()
+    }
+
+    /// Units that could not be supplied when the backorder was raised
+    pub async fn quantity(&self) -> f64 {
+        self.backorder.quantity
+    }
+
+    pub async fn allocated_quantity(&self) -> f64 {
+        self.backorder.allocated_quantity
+    }
+
+    pub async fn remaining_quantity(&self) -> f64 {
+        self.backorder.remaining_quantity()
+    }
+
+    pub async fn status(&self) -> BackorderNodeStatus {
+        BackorderNodeStatus::from(self.backorder.status.clone())
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.backorder.created_datetime, Utc)
+    }
+
+    pub async fn fulfilled_datetime(&self) -> Option<DateTime<Utc>> {
+        self.backorder
+            .fulfilled_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+
+    pub async fn cancelled_datetime(&self) -> Option<DateTime<Utc>> {
+        self.backorder
+            .cancelled_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+
+    pub async fn cancellation_reason(&self) -> Option<&str> {
+        self.backorder.cancellation_reason.as_deref()
+    }
+}
+
+pub struct OutstandingBackorderNode {
+    pub outstanding: OutstandingBackorder,
+}
+
+#[Object]
+impl OutstandingBackorderNode {
+    pub async fn backorder(&self) -> BackorderNode {
+        BackorderNode {
+            backorder: self.outstanding.backorder.clone(),
+        }
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.outstanding.item_id
+    }
+
+    pub async fn item_code(&self) -> &str {
+        &self.outstanding.item_code
+    }
+
+    pub async fn item_name(&self) -> &str {
+        &self.outstanding.item_name
+    }
+
+    pub async fn name_id(&self) -> &str {
+        &self.outstanding.name_id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.outstanding.name
+    }
+
+    pub async fn days_outstanding(&self) -> i64 {
+        self.outstanding.days_outstanding
+    }
+}
+
+#[derive(InputObject)]
+pub struct OutstandingBackordersFilterInput {
+    pub name_id: Option<String>,
+    pub item_id: Option<String>,
+    /// Date backorder age is calculated from, defaults to today
+    pub as_of: Option<NaiveDate>,
+}
+
+pub fn outstanding_backorders(
+    ctx: &Context<'_>,
+    store_id: String,
+    filter: Option<OutstandingBackordersFilterInput>,
+) -> Result<Vec<OutstandingBackorderNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryRequisition,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let backorders = service_provider
+        .backorder_service
+        .get_outstanding_backorders(
+            &service_context,
+            filter.map(|filter| filter.to_domain()).unwrap_or_default(),
+        )
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(backorders
+        .into_iter()
+        .map(|outstanding| OutstandingBackorderNode { outstanding })
+        .collect())
+}
+
+impl OutstandingBackordersFilterInput {
+    pub fn to_domain(self) -> OutstandingBackordersInput {
+        let OutstandingBackordersFilterInput {
+            name_id,
+            item_id,
+            as_of,
+        } = self;
+
+        OutstandingBackordersInput {
+            name_id,
+            item_id,
+            as_of,
+        }
+    }
+}
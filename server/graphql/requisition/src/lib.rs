@@ -1,7 +1,11 @@
+mod backorder;
 pub mod mutations;
 mod program_settings;
 mod requisition_queries;
 use async_graphql::*;
+use backorder::{
+    outstanding_backorders, OutstandingBackorderNode, OutstandingBackordersFilterInput,
+};
 use graphql_core::pagination::PaginationInput;
 use graphql_types::types::RequisitionNodeType;
 use program_settings::{
@@ -9,7 +13,7 @@ use program_settings::{
     CustomerProgramRequisitionSettingNode, SupplierProgramRequisitionSettingNode,
 };
 
-use self::mutations::{request_requisition, response_requisition};
+use self::mutations::{cancel_backorder, request_requisition, response_requisition};
 use self::requisition_queries::*;
 #[derive(Default, Clone)]
 pub struct RequisitionQueries;
@@ -61,6 +65,16 @@ impl RequisitionQueries {
     ) -> Result<Vec<CustomerProgramRequisitionSettingNode>> {
         get_customer_program_requisition_settings(ctx, &store_id)
     }
+
+    /// Open backorders, oldest first
+    pub async fn outstanding_backorders(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        filter: Option<OutstandingBackordersFilterInput>,
+    ) -> Result<Vec<OutstandingBackorderNode>> {
+        outstanding_backorders(ctx, store_id, filter)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -177,6 +191,8 @@ impl RequisitionMutations {
     /// Will create Outbound Shipment with placeholder lines for each requisition line
     /// placeholder line quantity will be set to requisitionLine.supply - all linked outbound shipments
     /// lines quantity (placeholder and filled) for requisitionLine.item
+    /// With createBackorders, placeholder lines are limited to available stock and the rest is
+    /// backordered, to be allocated when stock is received on an inbound shipment
     async fn create_requisition_shipment(
         &self,
         ctx: &Context<'_>,
@@ -188,6 +204,16 @@ impl RequisitionMutations {
             ctx, &store_id, input,
         )
     }
+
+    /// Stop allocating stock to a backorder
+    async fn cancel_backorder(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: cancel_backorder::CancelBackorderInput,
+    ) -> Result<cancel_backorder::CancelBackorderResponse> {
+        cancel_backorder::cancel_backorder(ctx, &store_id, input)
+    }
 }
 
 #[cfg(test)]
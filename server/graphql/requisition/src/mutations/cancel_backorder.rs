@@ -0,0 +1,96 @@
+use async_graphql::*;
+use graphql_core::simple_generic_errors::RecordNotFound;
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+
+use repository::BackorderRow;
+use service::auth::{Resource, ResourceAccessRequest};
+use service::backorder::{CancelBackorder as ServiceInput, CancelBackorderError as ServiceError};
+
+use crate::backorder::BackorderNode;
+
+#[derive(InputObject)]
+pub struct CancelBackorderInput {
+    pub id: String,
+    pub reason: String,
+}
+
+#[derive(SimpleObject)]
+pub struct CancelBackorderError {
+    pub error: CancelBackorderErrorInterface,
+}
+
+#[derive(Union)]
+pub enum CancelBackorderResponse {
+    Error(CancelBackorderError),
+    Response(BackorderNode),
+}
+
+#[derive(Interface)]
+#[graphql(field(name = "description", ty = "&str"))]
+pub enum CancelBackorderErrorInterface {
+    RecordNotFound(RecordNotFound),
+}
+
+pub fn cancel_backorder(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: CancelBackorderInput,
+) -> Result<CancelBackorderResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateRequisition,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+
+    map_response(
+        service_provider
+            .backorder_service
+            .cancel_backorder(&service_context, input.to_domain()),
+    )
+}
+
+pub fn map_response(from: Result<BackorderRow, ServiceError>) -> Result<CancelBackorderResponse> {
+    let result = match from {
+        Ok(backorder) => CancelBackorderResponse::Response(BackorderNode { backorder }),
+        Err(error) => CancelBackorderResponse::Error(CancelBackorderError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+impl CancelBackorderInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let CancelBackorderInput { id, reason } = self;
+
+        ServiceInput { id, reason }
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<CancelBackorderErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::BackorderDoesNotExist => {
+            return Ok(CancelBackorderErrorInterface::RecordNotFound(
+                RecordNotFound {},
+            ))
+        }
+        // Standard Graphql Errors
+        ServiceError::NotThisStoreBackorder
+        | ServiceError::BackorderNotOpen
+        | ServiceError::ReasonIsRequired => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
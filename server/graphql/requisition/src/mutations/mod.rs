@@ -1,3 +1,4 @@
+pub mod cancel_backorder;
 pub mod errors;
 pub mod request_requisition;
 pub mod response_requisition;
@@ -16,6 +16,8 @@ use service::{
 #[derive(InputObject)]
 pub struct CreateRequisitionShipmentInput {
     pub response_requisition_id: String,
+    /// Only supply what is available in stock, and backorder the rest
+    pub create_backorders: Option<bool>,
 }
 
 #[derive(Interface)]
@@ -75,9 +77,11 @@ impl CreateRequisitionShipmentInput {
     pub fn to_domain(self) -> ServiceInput {
         let CreateRequisitionShipmentInput {
             response_requisition_id,
+            create_backorders,
         } = self;
         ServiceInput {
             response_requisition_id,
+            create_backorders: create_backorders.unwrap_or(false),
         }
     }
 }
@@ -346,6 +350,7 @@ mod test {
                 input,
                 ServiceInput {
                     response_requisition_id: "id input".to_string(),
+                    create_backorders: false,
                 }
             );
             Ok(Invoice {
@@ -1,4 +1,8 @@
 pub mod mutations;
+pub mod recall;
+use self::recall::*;
+pub mod serial_number;
+use self::serial_number::*;
 use async_graphql::*;
 use chrono::{DateTime, Utc};
 use graphql_core::{
@@ -188,6 +192,41 @@ impl StockLineQueries {
             StockLineConnector::from_domain(stock_lines),
         ))
     }
+
+    /// Serialised units received into the stock line
+    pub async fn stock_line_serial_numbers(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        stock_line_id: String,
+    ) -> Result<Vec<SerialNumberNode>> {
+        stock_line_serial_numbers(ctx, store_id, stock_line_id)
+    }
+
+    /// Where units with the serial number were received and issued
+    pub async fn trace_serial_number(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        serial: String,
+    ) -> Result<Vec<SerialNumberTraceNode>> {
+        trace_serial_number(ctx, store_id, serial)
+    }
+
+    /// Recalls of every store on the site, newest first
+    pub async fn recalls(&self, ctx: &Context<'_>, store_id: String) -> Result<Vec<RecallNode>> {
+        recalls(ctx, store_id)
+    }
+
+    /// Quarantined stock, customers and patients affected by the recall
+    pub async fn recall_impact(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<RecallImpactNode> {
+        recall_impact(ctx, store_id, id)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -212,4 +251,51 @@ impl StockLineMutations {
     ) -> Result<mutations::UpdateResponse> {
         mutations::update(ctx, &store_id, input)
     }
+
+    /// Records the serial numbers of units on an inbound shipment or customer return line
+    async fn receive_serial_numbers(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::serial_number::ReceiveSerialNumbersInput,
+    ) -> Result<Vec<SerialNumberNode>> {
+        mutations::serial_number::receive_serial_numbers(ctx, &store_id, input)
+    }
+
+    /// Replaces the units picked on an outbound shipment or prescription line
+    async fn pick_serial_numbers(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::serial_number::PickSerialNumbersInput,
+    ) -> Result<Vec<SerialNumberNode>> {
+        mutations::serial_number::pick_serial_numbers(ctx, &store_id, input)
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct CentralStockLineMutations;
+
+#[Object]
+impl CentralStockLineMutations {
+    /// Puts stock of the recalled batches on hold, remote sites apply the recall when it is synced
+    async fn insert_recall(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::recall::InsertRecallInput,
+    ) -> Result<RecallNode> {
+        mutations::recall::insert_recall(ctx, &store_id, input)
+    }
+
+    /// Serialised items need a serial number for every unit received or issued, synced to
+    /// remote sites
+    async fn update_item_is_serialised(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::serial_number::UpdateItemIsSerialisedInput,
+    ) -> Result<SerialisedItemNode> {
+        mutations::serial_number::update_item_is_serialised(ctx, &store_id, input)
+    }
 }
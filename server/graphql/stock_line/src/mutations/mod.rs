@@ -1,4 +1,6 @@
 pub mod insert;
 pub use insert::*;
+pub mod recall;
+pub mod serial_number;
 pub mod update;
 pub use update::*;
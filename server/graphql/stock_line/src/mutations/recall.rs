@@ -0,0 +1,74 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    recall::{InsertRecall, InsertRecallError},
+};
+
+use crate::recall::RecallNode;
+
+#[derive(InputObject)]
+pub struct InsertRecallInput {
+    pub id: String,
+    pub item_id: String,
+    pub batches: Vec<String>,
+    pub reason: String,
+    pub recall_date: NaiveDate,
+}
+
+pub fn insert_recall(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: InsertRecallInput,
+) -> Result<RecallNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStockLine,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let InsertRecallInput {
+        id,
+        item_id,
+        batches,
+        reason,
+        recall_date,
+    } = input;
+    let result = service_provider.recall_service.insert_recall(
+        &service_context,
+        InsertRecall {
+            id,
+            item_id,
+            batches,
+            reason,
+            recall_date,
+        },
+    );
+
+    match result {
+        Ok(recall) => Ok(RecallNode { recall }),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                InsertRecallError::RecallAlreadyExists
+                | InsertRecallError::ItemDoesNotExist
+                | InsertRecallError::NoBatches => BadUserInput(formatted_error),
+                InsertRecallError::SiteIdNotSet | InsertRecallError::DatabaseError(_) => {
+                    InternalError(formatted_error)
+                }
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
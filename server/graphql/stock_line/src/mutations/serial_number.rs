@@ -0,0 +1,175 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    serial_number::{
+        PickSerialNumbers, PickSerialNumbersError, ReceiveSerialNumbers, ReceiveSerialNumbersError,
+        UpdateItemIsSerialisedError,
+    },
+};
+
+use crate::serial_number::{SerialNumberNode, SerialisedItemNode};
+
+#[derive(InputObject)]
+pub struct ReceiveSerialNumbersInput {
+    pub invoice_line_id: String,
+    /// Scanned GS1 DataMatrix strings, one per unit
+    pub scanned_data: Vec<String>,
+}
+
+#[derive(InputObject)]
+pub struct PickSerialNumbersInput {
+    pub invoice_line_id: String,
+    pub serials: Vec<String>,
+}
+
+#[derive(InputObject)]
+pub struct UpdateItemIsSerialisedInput {
+    pub item_id: String,
+    pub is_serialised: bool,
+}
+
+pub fn receive_serial_numbers(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: ReceiveSerialNumbersInput,
+) -> Result<Vec<SerialNumberNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateInboundShipment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let result = service_provider
+        .serial_number_service
+        .receive_serial_numbers(
+            &service_context,
+            ReceiveSerialNumbers {
+                invoice_line_id: input.invoice_line_id,
+                scanned_data: input.scanned_data,
+            },
+        );
+
+    match result {
+        Ok(serial_numbers) => Ok(serial_numbers
+            .into_iter()
+            .map(|row| SerialNumberNode { row })
+            .collect()),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                ReceiveSerialNumbersError::InvoiceLineDoesNotExist
+                | ReceiveSerialNumbersError::NotThisStoreInvoice
+                | ReceiveSerialNumbersError::NotAStockInLine
+                | ReceiveSerialNumbersError::CannotEditInvoice
+                | ReceiveSerialNumbersError::StockNotYetReceived
+                | ReceiveSerialNumbersError::NotGs1Data(_)
+                | ReceiveSerialNumbersError::NoSerialNumber(_)
+                | ReceiveSerialNumbersError::GtinIsForAnotherItem(_)
+                | ReceiveSerialNumbersError::SerialNumberIsRepeated(_)
+                | ReceiveSerialNumbersError::SerialNumberAlreadyInStock(_)
+                | ReceiveSerialNumbersError::MoreSerialNumbersThanUnits => {
+                    BadUserInput(formatted_error)
+                }
+                ReceiveSerialNumbersError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn pick_serial_numbers(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: PickSerialNumbersInput,
+) -> Result<Vec<SerialNumberNode>> {
+    // Units are picked on outbound shipments and prescriptions
+    let access_request = |resource| ResourceAccessRequest {
+        resource,
+        store_id: Some(store_id.to_string()),
+    };
+    let user = validate_auth(ctx, &access_request(Resource::MutateOutboundShipment))
+        .or_else(|_| validate_auth(ctx, &access_request(Resource::MutatePrescription)))?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let result = service_provider.serial_number_service.pick_serial_numbers(
+        &service_context,
+        PickSerialNumbers {
+            invoice_line_id: input.invoice_line_id,
+            serials: input.serials,
+        },
+    );
+
+    match result {
+        Ok(serial_numbers) => Ok(serial_numbers
+            .into_iter()
+            .map(|row| SerialNumberNode { row })
+            .collect()),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                PickSerialNumbersError::InvoiceLineDoesNotExist
+                | PickSerialNumbersError::NotThisStoreInvoice
+                | PickSerialNumbersError::NotAStockOutLine
+                | PickSerialNumbersError::CannotEditInvoice
+                | PickSerialNumbersError::SerialNumberIsRepeated(_)
+                | PickSerialNumbersError::SerialNumberNotOnStockLine(_)
+                | PickSerialNumbersError::SerialNumberAlreadyIssued(_)
+                | PickSerialNumbersError::MoreSerialNumbersThanUnits => {
+                    BadUserInput(formatted_error)
+                }
+                PickSerialNumbersError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn update_item_is_serialised(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpdateItemIsSerialisedInput,
+) -> Result<SerialisedItemNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateItems,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let result = service_provider
+        .serial_number_service
+        .update_item_is_serialised(&service_context, &input.item_id, input.is_serialised);
+
+    match result {
+        Ok(row) => Ok(SerialisedItemNode { row }),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                UpdateItemIsSerialisedError::ItemDoesNotExist => BadUserInput(formatted_error),
+                UpdateItemIsSerialisedError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use async_graphql::{dataloader::DataLoader, *};
+use chrono::{DateTime, NaiveDate, Utc};
+use graphql_core::{
+    loader::{ItemLoader, StockLineByIdLoader},
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{InvoiceNode, ItemNode, StockLineNode};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    recall::{GetRecallImpactError, Recall, RecallImpact, RecallRecipient, RecalledStockLine},
+};
+
+pub struct RecallNode {
+    pub recall: Recall,
+}
+
+#[Object]
+impl RecallNode {
+    pub async fn id(&self) -> &str {
+        &self.recall.recall.id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.recall.recall.item_link_id
+    }
+
+    pub async fn item(&self, ctx: &Context<'_>) -> Result<ItemNode> {
+        let loader = ctx.get_loader::<DataLoader<ItemLoader>>();
+        let item_id = &self.recall.recall.item_link_id;
+        let item_option = loader.load_one(item_id.clone()).await?;
+
+        item_option.map(ItemNode::from_domain).ok_or(
+            StandardGraphqlError::InternalError(format!(
+                "Cannot find item ({}) linked to recall ({})",
+                item_id, &self.recall.recall.id
+            ))
+            .extend(),
+        )
+    }
+
+    pub async fn batches(&self) -> &Vec<String> {
+        &self.recall.batches
+    }
+
+    pub async fn reason(&self) -> &str {
+        &self.recall.recall.reason
+    }
+
+    pub async fn recall_date(&self) -> NaiveDate {
+        self.recall.recall.recall_date
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.recall.recall.created_datetime, Utc)
+    }
+
+    pub async fn store_id(&self) -> &str {
+        &self.recall.recall.store_id
+    }
+
+    pub async fn user_id(&self) -> &str {
+        &self.recall.recall.user_id
+    }
+}
+
+pub struct RecalledStockLineNode {
+    pub line: RecalledStockLine,
+}
+
+#[Object]
+impl RecalledStockLineNode {
+    pub async fn store_id(&self) -> &str {
+        &self.line.recall_stock_line.store_id
+    }
+
+    pub async fn stock_line_id(&self) -> &str {
+        &self.line.recall_stock_line.stock_line_id
+    }
+
+    pub async fn stock_line(&self, ctx: &Context<'_>) -> Result<Option<StockLineNode>> {
+        let loader = ctx.get_loader::<DataLoader<StockLineByIdLoader>>();
+        let result = loader
+            .load_one(self.line.recall_stock_line.stock_line_id.clone())
+            .await?;
+
+        Ok(result.map(StockLineNode::from_domain))
+    }
+
+    /// Packs of the stock line when it was put on hold
+    pub async fn quarantined_number_of_packs(&self) -> f64 {
+        self.line.recall_stock_line.quarantined_number_of_packs
+    }
+
+    pub async fn returned_number_of_packs(&self) -> f64 {
+        self.line.returned_number_of_packs
+    }
+
+    pub async fn destroyed_number_of_packs(&self) -> f64 {
+        self.line.destroyed_number_of_packs
+    }
+
+    pub async fn remaining_number_of_packs(&self) -> f64 {
+        self.line.remaining_number_of_packs()
+    }
+}
+
+pub struct RecallRecipientNode {
+    pub recipient: RecallRecipient,
+}
+
+#[Object]
+impl RecallRecipientNode {
+    pub async fn name_id(&self) -> &str {
+        &self.recipient.name.id
+    }
+
+    pub async fn code(&self) -> &str {
+        &self.recipient.name.code
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.recipient.name.name
+    }
+
+    pub async fn invoice_ids(&self) -> &Vec<String> {
+        &self.recipient.invoice_ids
+    }
+
+    pub async fn vaccination_ids(&self) -> &Vec<String> {
+        &self.recipient.vaccination_ids
+    }
+}
+
+pub struct RecallImpactNode {
+    pub store_id: String,
+    pub impact: RecallImpact,
+}
+
+#[Object]
+impl RecallImpactNode {
+    pub async fn recall(&self) -> RecallNode {
+        RecallNode {
+            recall: self.impact.recall.clone(),
+        }
+    }
+
+    /// Stock lines put on hold, with progress of their returns and write offs
+    pub async fn stock_lines(&self) -> Vec<RecalledStockLineNode> {
+        self.impact
+            .stock_lines
+            .iter()
+            .cloned()
+            .map(|line| RecalledStockLineNode { line })
+            .collect()
+    }
+
+    /// True once every quarantined pack has been returned or destroyed
+    pub async fn is_resolved(&self) -> bool {
+        self.impact.is_resolved()
+    }
+
+    pub async fn outbound_shipments(&self) -> Vec<InvoiceNode> {
+        self.impact
+            .outbound_shipments
+            .iter()
+            .cloned()
+            .map(InvoiceNode::from_domain)
+            .collect()
+    }
+
+    pub async fn customers(&self) -> Vec<RecallRecipientNode> {
+        self.impact
+            .customers
+            .iter()
+            .cloned()
+            .map(|recipient| RecallRecipientNode { recipient })
+            .collect()
+    }
+
+    /// Patients who received the recalled batches through prescriptions or vaccinations
+    pub async fn patients(&self, ctx: &Context<'_>) -> Result<Vec<RecallRecipientNode>> {
+        validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryPatient,
+                store_id: Some(self.store_id.clone()),
+            },
+        )?;
+
+        Ok(self
+            .impact
+            .patients
+            .iter()
+            .cloned()
+            .map(|recipient| RecallRecipientNode { recipient })
+            .collect())
+    }
+}
+
+pub fn recalls(ctx: &Context<'_>, store_id: String) -> Result<Vec<RecallNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStockLine,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let recalls = service_provider
+        .recall_service
+        .get_recalls(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(recalls
+        .into_iter()
+        .map(|recall| RecallNode { recall })
+        .collect())
+}
+
+pub fn recall_impact(ctx: &Context<'_>, store_id: String, id: String) -> Result<RecallImpactNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStockLine,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.clone(), user.user_id)?;
+
+    match service_provider
+        .recall_service
+        .get_recall_impact(&service_context, &id)
+    {
+        Ok(impact) => Ok(RecallImpactNode { store_id, impact }),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                GetRecallImpactError::RecallDoesNotExist => BadUserInput(formatted_error),
+                GetRecallImpactError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use async_graphql::{dataloader::DataLoader, *};
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    loader::{InvoiceByIdLoader, StockLineByIdLoader},
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{InvoiceNode, StockLineNode};
+use repository::{ItemOmsFieldsRow, SerialNumberMovementRow, SerialNumberRow};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    serial_number::SerialNumberTrace,
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "repository::SerialNumberStatus")]
+pub enum SerialNumberNodeStatus {
+    InStock,
+    Issued,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "repository::SerialNumberMovementType")]
+pub enum SerialNumberMovementNodeType {
+    Received,
+    Issued,
+}
+
+pub struct SerialNumberNode {
+    pub row: SerialNumberRow,
+}
+
+#[Object]
+impl SerialNumberNode {
+    pub async fn id(&self) -> &str {
+        &self.row.id
+    }
+
+    pub async fn store_id(&self) -> &str {
+        &self.row.store_id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.row.item_link_id
+    }
+
+    pub async fn serial(&self) -> &str {
+        &self.row.serial
+    }
+
+    pub async fn gtin(&self) -> &Option<String> {
+        &self.row.gtin
+    }
+
+    pub async fn stock_line_id(&self) -> &str {
+        &self.row.stock_line_id
+    }
+
+    pub async fn status(&self) -> SerialNumberNodeStatus {
+        SerialNumberNodeStatus::from(self.row.status.clone())
+    }
+
+    pub async fn received_invoice_line_id(&self) -> &str {
+        &self.row.received_invoice_line_id
+    }
+
+    /// Outbound shipment or prescription line the unit is picked on
+    pub async fn issued_invoice_line_id(&self) -> &Option<String> {
+        &self.row.issued_invoice_line_id
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.row.created_datetime, Utc)
+    }
+
+    pub async fn stock_line(&self, ctx: &Context<'_>) -> Result<Option<StockLineNode>> {
+        let loader = ctx.get_loader::<DataLoader<StockLineByIdLoader>>();
+        let result = loader.load_one(self.row.stock_line_id.clone()).await?;
+
+        Ok(result.map(StockLineNode::from_domain))
+    }
+}
+
+pub struct SerialNumberMovementNode {
+    pub row: SerialNumberMovementRow,
+}
+
+#[Object]
+impl SerialNumberMovementNode {
+    pub async fn id(&self) -> &str {
+        &self.row.id
+    }
+
+    pub async fn r#type(&self) -> SerialNumberMovementNodeType {
+        SerialNumberMovementNodeType::from(self.row.r#type.clone())
+    }
+
+    pub async fn store_id(&self) -> &str {
+        &self.row.store_id
+    }
+
+    pub async fn invoice_id(&self) -> &str {
+        &self.row.invoice_id
+    }
+
+    pub async fn invoice_line_id(&self) -> &str {
+        &self.row.invoice_line_id
+    }
+
+    pub async fn datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.row.datetime, Utc)
+    }
+
+    /// Includes the other party, the supplier for receipts and the customer for issues
+    pub async fn invoice(&self, ctx: &Context<'_>) -> Result<Option<InvoiceNode>> {
+        let loader = ctx.get_loader::<DataLoader<InvoiceByIdLoader>>();
+        let result = loader.load_one(self.row.invoice_id.clone()).await?;
+
+        Ok(result.map(InvoiceNode::from_domain))
+    }
+}
+
+pub struct SerialisedItemNode {
+    pub row: ItemOmsFieldsRow,
+}
+
+#[Object]
+impl SerialisedItemNode {
+    pub async fn item_id(&self) -> &str {
+        &self.row.id
+    }
+
+    /// Every unit needs a serial number before a shipment or prescription of the item is finalised
+    pub async fn is_serialised(&self) -> bool {
+        self.row.is_serialised
+    }
+}
+
+pub struct SerialNumberTraceNode {
+    pub trace: SerialNumberTrace,
+}
+
+#[Object]
+impl SerialNumberTraceNode {
+    pub async fn serial_number(&self) -> SerialNumberNode {
+        SerialNumberNode {
+            row: self.trace.serial_number.clone(),
+        }
+    }
+
+    /// Oldest first
+    pub async fn movements(&self) -> Vec<SerialNumberMovementNode> {
+        self.trace
+            .movements
+            .iter()
+            .cloned()
+            .map(|row| SerialNumberMovementNode { row })
+            .collect()
+    }
+}
+
+pub fn stock_line_serial_numbers(
+    ctx: &Context<'_>,
+    store_id: String,
+    stock_line_id: String,
+) -> Result<Vec<SerialNumberNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStockLine,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let serial_numbers = service_provider
+        .serial_number_service
+        .get_stock_line_serial_numbers(&service_context, &stock_line_id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(serial_numbers
+        .into_iter()
+        .map(|row| SerialNumberNode { row })
+        .collect())
+}
+
+pub fn trace_serial_number(
+    ctx: &Context<'_>,
+    store_id: String,
+    serial: String,
+) -> Result<Vec<SerialNumberTraceNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStockLine,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id, user.user_id)?;
+
+    let traces = service_provider
+        .serial_number_service
+        .trace_serial_number(&service_context, &serial)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(traces
+        .into_iter()
+        .map(|trace| SerialNumberTraceNode { trace })
+        .collect())
+}
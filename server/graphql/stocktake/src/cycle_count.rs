@@ -0,0 +1,342 @@
+use async_graphql::*;
+use chrono::{Local, NaiveDate};
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+use graphql_types::types::StocktakeNode;
+use repository::{CycleCountAbcWeighting, CycleCountScheduleRow};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stocktake::{
+        AbcClass, CycleCountCompliance, CycleCountItem, CycleCountPlan, GenerateCycleCountError,
+        UpsertCycleCountSchedule, UpsertCycleCountScheduleError,
+    },
+};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum CycleCountAbcWeightingType {
+    Value,
+    Movement,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum AbcClassType {
+    A,
+    B,
+    C,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct CycleCountScheduleNode {
+    pub schedule: CycleCountScheduleRow,
+}
+
+#[Object]
+impl CycleCountScheduleNode {
+    pub async fn id(&self) -> &str {
+        &self.schedule.id
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.schedule.is_active
+    }
+
+    pub async fn cycle_length_days(&self) -> i32 {
+        self.schedule.cycle_length_days
+    }
+
+    pub async fn abc_weighting(&self) -> Option<CycleCountAbcWeightingType> {
+        self.schedule
+            .abc_weighting
+            .as_ref()
+            .map(CycleCountAbcWeightingType::from_domain)
+    }
+
+    pub async fn last_generated_date(&self) -> Option<NaiveDate> {
+        self.schedule.last_generated_date
+    }
+}
+
+pub struct CycleCountItemNode {
+    pub item: CycleCountItem,
+    pub today: NaiveDate,
+}
+
+#[Object]
+impl CycleCountItemNode {
+    pub async fn item_id(&self) -> &str {
+        &self.item.item_id
+    }
+
+    pub async fn item_name(&self) -> &str {
+        &self.item.item_name
+    }
+
+    pub async fn abc_class(&self) -> Option<AbcClassType> {
+        self.item.abc_class.map(AbcClassType::from_domain)
+    }
+
+    pub async fn count_interval_days(&self) -> i64 {
+        self.item.count_interval_days
+    }
+
+    pub async fn last_counted_date(&self) -> Option<NaiveDate> {
+        self.item.last_counted_date
+    }
+
+    pub async fn next_due_date(&self) -> Option<NaiveDate> {
+        self.item.next_due_date
+    }
+
+    pub async fn is_overdue(&self) -> bool {
+        self.item.is_overdue(&self.today)
+    }
+}
+
+pub struct CycleCountPlanNode {
+    pub plan: CycleCountPlan,
+    pub today: NaiveDate,
+}
+
+#[Object]
+impl CycleCountPlanNode {
+    pub async fn period_days(&self) -> i64 {
+        self.plan.period_days
+    }
+
+    pub async fn cycle_length_days(&self) -> i64 {
+        self.plan.cycle_length_days
+    }
+
+    pub async fn items_per_cycle(&self) -> u32 {
+        self.plan.items_per_cycle as u32
+    }
+
+    pub async fn items(&self) -> Vec<CycleCountItemNode> {
+        self.plan
+            .items
+            .iter()
+            .cloned()
+            .map(|item| CycleCountItemNode {
+                item,
+                today: self.today,
+            })
+            .collect()
+    }
+}
+
+pub struct CycleCountComplianceNode {
+    pub compliance: CycleCountCompliance,
+}
+
+#[Object]
+impl CycleCountComplianceNode {
+    pub async fn total_items(&self) -> u32 {
+        self.compliance.total_items as u32
+    }
+
+    pub async fn counted_items(&self) -> u32 {
+        self.compliance.counted_items as u32
+    }
+
+    pub async fn overdue_items(&self) -> u32 {
+        self.compliance.overdue_items as u32
+    }
+
+    /// Fraction of items counted within the store stocktake frequency period
+    pub async fn coverage(&self) -> f64 {
+        self.compliance.coverage()
+    }
+}
+
+#[derive(InputObject)]
+pub struct UpsertCycleCountScheduleInput {
+    pub is_active: bool,
+    pub cycle_length_days: i32,
+    pub abc_weighting: Option<CycleCountAbcWeightingType>,
+}
+
+pub fn cycle_count_schedule(
+    ctx: &Context<'_>,
+    store_id: &str,
+) -> Result<Option<CycleCountScheduleNode>> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+    let schedule = service_provider
+        .stocktake_service
+        .get_cycle_count_schedule(&service_context, store_id)?;
+
+    Ok(schedule.map(CycleCountScheduleNode::from_domain))
+}
+
+pub fn cycle_count_plan(ctx: &Context<'_>, store_id: &str) -> Result<CycleCountPlanNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+    let today = Local::now().date_naive();
+    let plan = service_provider.stocktake_service.get_cycle_count_plan(
+        &service_context,
+        store_id,
+        today,
+    )?;
+
+    Ok(CycleCountPlanNode { plan, today })
+}
+
+pub fn cycle_count_compliance(
+    ctx: &Context<'_>,
+    store_id: &str,
+) -> Result<CycleCountComplianceNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.basic_context()?;
+    let compliance = service_provider
+        .stocktake_service
+        .get_cycle_count_compliance(&service_context, store_id, Local::now().date_naive())?;
+
+    Ok(CycleCountComplianceNode { compliance })
+}
+
+pub fn upsert_cycle_count_schedule(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpsertCycleCountScheduleInput,
+) -> Result<CycleCountScheduleNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let result = service_provider
+        .stocktake_service
+        .upsert_cycle_count_schedule(&service_context, input.to_domain());
+
+    match result {
+        Ok(schedule) => Ok(CycleCountScheduleNode::from_domain(schedule)),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                UpsertCycleCountScheduleError::InvalidStore
+                | UpsertCycleCountScheduleError::CycleLengthMustBePositive => {
+                    BadUserInput(formatted_error)
+                }
+                UpsertCycleCountScheduleError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+/// Returns null if a stocktake was already generated for the current cycle
+pub fn generate_cycle_count_stocktake(
+    ctx: &Context<'_>,
+    store_id: &str,
+) -> Result<Option<StocktakeNode>> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let result = service_provider
+        .stocktake_service
+        .generate_cycle_count_stocktake(&service_context, Local::now().date_naive());
+
+    match result {
+        Ok(stocktake) => Ok(stocktake.map(StocktakeNode::from_domain)),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                GenerateCycleCountError::ScheduleDoesNotExist
+                | GenerateCycleCountError::ScheduleNotActive => BadUserInput(formatted_error),
+                GenerateCycleCountError::InternalError(err) => InternalError(err),
+                GenerateCycleCountError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+impl CycleCountScheduleNode {
+    pub fn from_domain(schedule: CycleCountScheduleRow) -> Self {
+        CycleCountScheduleNode { schedule }
+    }
+}
+
+impl UpsertCycleCountScheduleInput {
+    pub fn to_domain(self) -> UpsertCycleCountSchedule {
+        let UpsertCycleCountScheduleInput {
+            is_active,
+            cycle_length_days,
+            abc_weighting,
+        } = self;
+
+        UpsertCycleCountSchedule {
+            is_active,
+            cycle_length_days,
+            abc_weighting: abc_weighting.map(CycleCountAbcWeightingType::to_domain),
+        }
+    }
+}
+
+impl CycleCountAbcWeightingType {
+    pub fn from_domain(weighting: &CycleCountAbcWeighting) -> Self {
+        match weighting {
+            CycleCountAbcWeighting::Value => CycleCountAbcWeightingType::Value,
+            CycleCountAbcWeighting::Movement => CycleCountAbcWeightingType::Movement,
+        }
+    }
+
+    pub fn to_domain(self) -> CycleCountAbcWeighting {
+        match self {
+            CycleCountAbcWeightingType::Value => CycleCountAbcWeighting::Value,
+            CycleCountAbcWeightingType::Movement => CycleCountAbcWeighting::Movement,
+        }
+    }
+}
+
+impl AbcClassType {
+    pub fn from_domain(class: AbcClass) -> Self {
+        match class {
+            AbcClass::A => AbcClassType::A,
+            AbcClass::B => AbcClassType::B,
+            AbcClass::C => AbcClassType::C,
+        }
+    }
+}
@@ -1,9 +1,14 @@
+mod cycle_count;
 pub mod mutations;
 mod stocktake_queries;
+mod variance;
 use self::stocktake_queries::*;
 use async_graphql::*;
+use cycle_count::*;
 use graphql_core::pagination::PaginationInput;
+use graphql_types::types::StocktakeNode;
 use mutations::{delete::*, insert::*, update::*};
+use variance::*;
 
 #[derive(Default, Clone)]
 pub struct StocktakeQueries;
@@ -38,6 +43,42 @@ impl StocktakeQueries {
     ) -> Result<StocktakesResponse> {
         stocktakes(ctx, &store_id, page, filter, sort)
     }
+
+    /// Variance between the counted and snapshot quantities, only available for blind stocktakes
+    /// once the count has been submitted
+    pub async fn stocktake_variance_summary(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        stocktake_id: String,
+    ) -> Result<StocktakeVarianceSummaryNode> {
+        stocktake_variance_summary(ctx, &store_id, &stocktake_id)
+    }
+
+    pub async fn cycle_count_schedule(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Option<CycleCountScheduleNode>> {
+        cycle_count_schedule(ctx, &store_id)
+    }
+
+    /// Items of the store in the order they are due to be cycle counted
+    pub async fn cycle_count_plan(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<CycleCountPlanNode> {
+        cycle_count_plan(ctx, &store_id)
+    }
+
+    pub async fn cycle_count_compliance(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<CycleCountComplianceNode> {
+        cycle_count_compliance(ctx, &store_id)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -71,4 +112,40 @@ impl StocktakeMutations {
     ) -> Result<DeleteResponse> {
         delete(ctx, &store_id, input)
     }
+
+    /// Submits the count or recount of a blind stocktake
+    async fn submit_stocktake_count(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<StocktakeNode> {
+        submit_stocktake_count(ctx, &store_id, &id)
+    }
+
+    async fn approve_stocktake(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<StocktakeNode> {
+        approve_stocktake(ctx, &store_id, &id)
+    }
+
+    async fn upsert_cycle_count_schedule(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpsertCycleCountScheduleInput,
+    ) -> Result<CycleCountScheduleNode> {
+        upsert_cycle_count_schedule(ctx, &store_id, input)
+    }
+
+    async fn generate_cycle_count_stocktake(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Option<StocktakeNode>> {
+        generate_cycle_count_stocktake(ctx, &store_id)
+    }
 }
@@ -25,6 +25,11 @@ pub struct InsertInput {
     pub location: Option<NullableUpdateInput<String>>,
     pub items_have_stock: Option<bool>,
     pub expires_before: Option<NaiveDate>,
+    /// Hide snapshot quantities until the count is submitted and require approval before the
+    /// stocktake can be finalised
+    pub is_blind: Option<bool>,
+    pub variance_quantity_threshold: Option<f64>,
+    pub variance_value_threshold: Option<f64>,
 }
 
 #[derive(Union)]
@@ -100,6 +105,9 @@ impl InsertInput {
             master_list_id,
             items_have_stock,
             expires_before,
+            is_blind,
+            variance_quantity_threshold,
+            variance_value_threshold,
         } = self;
 
         ServiceInput {
@@ -114,6 +122,9 @@ impl InsertInput {
             master_list_id,
             items_have_stock,
             expires_before,
+            is_blind,
+            variance_quantity_threshold,
+            variance_value_threshold,
         }
     }
 }
@@ -191,7 +202,10 @@ mod test {
                     location: None,
                     master_list_id: None,
                     items_have_stock: None,
-                    expires_before: None
+                    expires_before: None,
+                    is_blind: None,
+                    variance_quantity_threshold: None,
+                    variance_value_threshold: None,
                 }
             );
             // StocktakeNode result is checked in queries
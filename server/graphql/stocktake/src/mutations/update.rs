@@ -146,6 +146,7 @@ fn map_error(err: ServiceError) -> Result<UpdateErrorInterface> {
         ServiceError::InvalidStore => BadUserInput(formatted_error),
         ServiceError::StocktakeDoesNotExist => BadUserInput(formatted_error),
         ServiceError::NoLines => BadUserInput(formatted_error),
+        ServiceError::StocktakeNotApproved => BadUserInput(formatted_error),
         ServiceError::InternalError(err) => InternalError(err),
         ServiceError::InsertStockInLineError { .. }
         | ServiceError::InsertStockOutLineError { .. }
@@ -0,0 +1,203 @@
+use async_graphql::*;
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+use graphql_types::types::StocktakeNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stocktake::{
+        ApproveStocktakeError, StocktakeLineVariance, StocktakeVarianceSummary,
+        StocktakeVarianceSummaryError, SubmitStocktakeCountError,
+    },
+};
+
+pub struct StocktakeLineVarianceNode {
+    pub variance: StocktakeLineVariance,
+}
+
+#[Object]
+impl StocktakeLineVarianceNode {
+    pub async fn stocktake_line_id(&self) -> &str {
+        &self.variance.line.id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.variance.line.item_link_id
+    }
+
+    pub async fn item_name(&self) -> &str {
+        &self.variance.line.item_name
+    }
+
+    pub async fn batch(&self) -> &Option<String> {
+        &self.variance.line.batch
+    }
+
+    pub async fn snapshot_number_of_packs(&self) -> f64 {
+        self.variance.line.snapshot_number_of_packs
+    }
+
+    pub async fn counted_number_of_packs(&self) -> Option<f64> {
+        self.variance.line.counted_number_of_packs
+    }
+
+    /// Counted minus snapshot number of packs, null if the line hasn't been counted
+    pub async fn quantity_variance(&self) -> Option<f64> {
+        self.variance.quantity_variance
+    }
+
+    pub async fn value_variance(&self) -> Option<f64> {
+        self.variance.value_variance
+    }
+
+    pub async fn exceeds_threshold(&self) -> bool {
+        self.variance.exceeds_threshold
+    }
+
+    pub async fn requires_recount(&self) -> bool {
+        self.variance.line.requires_recount
+    }
+}
+
+pub struct StocktakeVarianceSummaryNode {
+    pub summary: StocktakeVarianceSummary,
+}
+
+#[Object]
+impl StocktakeVarianceSummaryNode {
+    pub async fn lines(&self) -> Vec<StocktakeLineVarianceNode> {
+        self.summary
+            .lines
+            .iter()
+            .cloned()
+            .map(|variance| StocktakeLineVarianceNode { variance })
+            .collect()
+    }
+
+    pub async fn total_value_variance(&self) -> f64 {
+        self.summary.total_value_variance
+    }
+
+    pub async fn lines_exceeding_threshold(&self) -> u32 {
+        self.summary.lines_exceeding_threshold as u32
+    }
+
+    pub async fn uncounted_lines(&self) -> u32 {
+        self.summary.uncounted_lines as u32
+    }
+}
+
+pub fn stocktake_variance_summary(
+    ctx: &Context<'_>,
+    store_id: &str,
+    stocktake_id: &str,
+) -> Result<StocktakeVarianceSummaryNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let result = service_provider
+        .stocktake_service
+        .get_stocktake_variance_summary(&service_context, store_id, stocktake_id);
+
+    match result {
+        Ok(summary) => Ok(StocktakeVarianceSummaryNode { summary }),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                StocktakeVarianceSummaryError::StocktakeDoesNotExist
+                | StocktakeVarianceSummaryError::InvalidStore
+                | StocktakeVarianceSummaryError::CountNotSubmitted => BadUserInput(formatted_error),
+                StocktakeVarianceSummaryError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn submit_stocktake_count(
+    ctx: &Context<'_>,
+    store_id: &str,
+    id: &str,
+) -> Result<StocktakeNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let result = service_provider
+        .stocktake_service
+        .submit_stocktake_count(&service_context, id);
+
+    match result {
+        Ok(stocktake) => Ok(StocktakeNode::from_domain(stocktake)),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                SubmitStocktakeCountError::StocktakeDoesNotExist
+                | SubmitStocktakeCountError::InvalidStore
+                | SubmitStocktakeCountError::NotABlindStocktake
+                | SubmitStocktakeCountError::StocktakeIsLocked
+                | SubmitStocktakeCountError::CountAlreadySubmitted
+                | SubmitStocktakeCountError::NoLines => BadUserInput(formatted_error),
+                SubmitStocktakeCountError::InternalError(err) => InternalError(err),
+                SubmitStocktakeCountError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
+
+pub fn approve_stocktake(ctx: &Context<'_>, store_id: &str, id: &str) -> Result<StocktakeNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ApproveStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context(store_id.to_string(), user.user_id)?;
+    let result = service_provider
+        .stocktake_service
+        .approve_stocktake(&service_context, id);
+
+    match result {
+        Ok(stocktake) => Ok(StocktakeNode::from_domain(stocktake)),
+        Err(error) => {
+            use StandardGraphqlError::*;
+            let formatted_error = format!("{:#?}", error);
+
+            let graphql_error = match error {
+                ApproveStocktakeError::StocktakeDoesNotExist
+                | ApproveStocktakeError::InvalidStore
+                | ApproveStocktakeError::NotABlindStocktake
+                | ApproveStocktakeError::StocktakeIsLocked
+                | ApproveStocktakeError::StocktakeNotPendingApproval => {
+                    BadUserInput(formatted_error)
+                }
+                ApproveStocktakeError::InternalError(err) => InternalError(err),
+                ApproveStocktakeError::DatabaseError(_) => InternalError(formatted_error),
+            };
+
+            Err(graphql_error.extend())
+        }
+    }
+}
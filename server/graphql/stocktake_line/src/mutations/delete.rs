@@ -71,7 +71,7 @@ fn map_error(error: ServiceError) -> Result<DeleteErrorInterface> {
 
     let graphql_error = match error {
         // Structured Errors
-        ServiceError::CannotEditFinalised => {
+        ServiceError::CannotEditFinalised | ServiceError::CountAlreadySubmitted => {
             return Ok(DeleteErrorInterface::CannotEditStocktake(
                 CannotEditStocktake {},
             ))
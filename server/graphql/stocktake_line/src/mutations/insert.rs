@@ -95,7 +95,7 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
 
     let graphql_error = match error {
         // Structured Errors
-        ServiceError::CannotEditFinalised => {
+        ServiceError::CannotEditFinalised | ServiceError::CountAlreadySubmitted => {
             return Ok(InsertErrorInterface::CannotEditStocktake(
                 CannotEditStocktake {},
             ))
@@ -292,6 +292,7 @@ mod test {
                     sell_price_per_pack: Some(12.0),
                     note: Some("note".to_string()),
                     inventory_adjustment_reason_id: None,
+                    requires_recount: false,
                 },
                 stock_line: Some(mock_stock_line_a()),
                 location: Some(mock_location_1()),
@@ -79,6 +79,14 @@ pub enum ActivityLogNodeType {
     DemographicIndicatorUpdated,
     DemographicProjectionCreated,
     DemographicProjectionUpdated,
+    StocktakeStatusRecount,
+    StocktakeStatusPendingApproval,
+    StocktakeStatusApproved,
+    UserLoginFailed,
+    UserAccountLocked,
+    ClinicalWarningAcknowledged,
+    InvoicePaymentCreated,
+    InvoicePaymentCancelled,
 }
 
 #[Object]
@@ -213,6 +221,14 @@ impl ActivityLogNodeType {
             from::DemographicIndicatorUpdated => to::DemographicIndicatorUpdated,
             from::DemographicProjectionCreated => to::DemographicProjectionCreated,
             from::DemographicProjectionUpdated => to::DemographicProjectionUpdated,
+            from::StocktakeStatusRecount => to::StocktakeStatusRecount,
+            from::StocktakeStatusPendingApproval => to::StocktakeStatusPendingApproval,
+            from::StocktakeStatusApproved => to::StocktakeStatusApproved,
+            from::UserLoginFailed => to::UserLoginFailed,
+            from::UserAccountLocked => to::UserAccountLocked,
+            from::ClinicalWarningAcknowledged => to::ClinicalWarningAcknowledged,
+            from::InvoicePaymentCreated => to::InvoicePaymentCreated,
+            from::InvoicePaymentCancelled => to::InvoicePaymentCancelled,
         }
     }
 
@@ -277,6 +293,14 @@ impl ActivityLogNodeType {
             from::DemographicIndicatorUpdated => to::DemographicIndicatorUpdated,
             from::DemographicProjectionCreated => to::DemographicProjectionCreated,
             from::DemographicProjectionUpdated => to::DemographicProjectionUpdated,
+            from::StocktakeStatusRecount => to::StocktakeStatusRecount,
+            from::StocktakeStatusPendingApproval => to::StocktakeStatusPendingApproval,
+            from::StocktakeStatusApproved => to::StocktakeStatusApproved,
+            from::UserLoginFailed => to::UserLoginFailed,
+            from::UserAccountLocked => to::UserAccountLocked,
+            from::ClinicalWarningAcknowledged => to::ClinicalWarningAcknowledged,
+            from::InvoicePaymentCreated => to::InvoicePaymentCreated,
+            from::InvoicePaymentCancelled => to::InvoicePaymentCancelled,
         }
     }
 }
@@ -0,0 +1,54 @@
+use async_graphql::*;
+use repository::AllocationRuleRow;
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "repository::AllocationStrategyType")]
+pub enum AllocationStrategyNodeType {
+    Fefo,
+    Fifo,
+    PickPath,
+    FullPacksFirst,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct AllocationRuleNode {
+    pub allocation_rule: AllocationRuleRow,
+}
+
+#[Object]
+impl AllocationRuleNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+    pub async fn store_id(&self) -> &str {
+        &self.row().store_id
+    }
+    /// Rule applies to every customer of the store when not set
+    pub async fn name_tag_id(&self) -> Option<&str> {
+        self.row().name_tag_id.as_deref()
+    }
+    pub async fn strategy(&self) -> AllocationStrategyNodeType {
+        AllocationStrategyNodeType::from(self.row().strategy)
+    }
+    pub async fn expiring_soon_days(&self) -> Option<i32> {
+        self.row().expiring_soon_days
+    }
+    pub async fn min_remaining_shelf_life_days(&self) -> Option<i32> {
+        self.row().min_remaining_shelf_life_days
+    }
+    pub async fn avoid_item_variant_id(&self) -> Option<&str> {
+        self.row().avoid_item_variant_id.as_deref()
+    }
+    pub async fn priority(&self) -> i32 {
+        self.row().priority
+    }
+}
+
+impl AllocationRuleNode {
+    pub fn from_domain(allocation_rule: AllocationRuleRow) -> AllocationRuleNode {
+        AllocationRuleNode { allocation_rule }
+    }
+    pub fn row(&self) -> &AllocationRuleRow {
+        &self.allocation_rule
+    }
+}
@@ -1,9 +1,15 @@
-use super::{ItemNode, LocationNode, PricingNode, ReturnReasonNode, StockLineNode};
+use super::{
+    ItemNode, LocationNode, PrescriptionLineDirectionNode, PricingNode, ReturnReasonNode,
+    StockLineNode,
+};
 use async_graphql::*;
 use chrono::NaiveDate;
 use dataloader::DataLoader;
 use graphql_core::{
-    loader::{ItemLoader, LocationByIdLoader, ReturnReasonLoader, StockLineByIdLoader},
+    loader::{
+        ItemLoader, LocationByIdLoader, PrescriptionLineDirectionLoader, ReturnReasonLoader,
+        StockLineByIdLoader,
+    },
     simple_generic_errors::NodeError,
     standard_graphql_error::StandardGraphqlError,
     ContextExt,
@@ -42,6 +48,15 @@ impl InvoiceLineNodeType {
     }
 }
 
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "repository::InvoiceLineDiscrepancyType")]
+pub enum InvoiceLineDiscrepancyNodeType {
+    Short,
+    Damaged,
+    WrongBatch,
+    Excess,
+}
+
 pub struct InvoiceLineNode {
     invoice_line: InvoiceLine,
 }
@@ -180,6 +195,41 @@ impl InvoiceLineNode {
 
         Ok(result.map(ReturnReasonNode::from_domain))
     }
+
+    /// Outbound shipment line this line was generated from, only set for transferred inbound
+    /// shipment lines
+    pub async fn linked_invoice_line_id(&self) -> &Option<String> {
+        &self.row().linked_invoice_line_id
+    }
+
+    /// Recorded by the receiving store, copied to the outbound shipment line when the inbound
+    /// shipment is verified
+    pub async fn discrepancy_type(&self) -> Option<InvoiceLineDiscrepancyNodeType> {
+        self.row()
+            .discrepancy_type
+            .clone()
+            .map(InvoiceLineDiscrepancyNodeType::from)
+    }
+
+    pub async fn discrepancy_number_of_packs(&self) -> f64 {
+        self.row().discrepancy_number_of_packs
+    }
+
+    pub async fn discrepancy_reason(&self) -> &Option<String> {
+        &self.row().discrepancy_reason
+    }
+
+    /// Structured dosage directions, only set for prescription lines
+    pub async fn prescription_direction(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<PrescriptionLineDirectionNode>> {
+        let loader = ctx.get_loader::<DataLoader<PrescriptionLineDirectionLoader>>();
+
+        let result = loader.load_one(self.row().id.clone()).await?;
+
+        Ok(result.map(PrescriptionLineDirectionNode::from_domain))
+    }
 }
 
 #[derive(Union)]
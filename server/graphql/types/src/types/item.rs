@@ -11,7 +11,7 @@ use graphql_core::{
     standard_graphql_error::StandardGraphqlError,
     ContextExt,
 };
-use repository::{Item, ItemRow, ItemType, VENCategory};
+use repository::{Item, ItemRow, ItemRowRepository, ItemType, VENCategory};
 use serde_json::json;
 use service::ListResult;
 
@@ -68,6 +68,16 @@ impl ItemNode {
         self.row().vaccine_doses
     }
 
+    /// Every unit needs a serial number before a shipment or prescription of the item is finalised
+    pub async fn is_serialised(&self, ctx: &Context<'_>) -> Result<bool> {
+        let connection = ctx.get_connection_manager().connection()?;
+        let oms_fields = ItemRowRepository::new(&connection)
+            .find_one_oms_fields_by_id(&self.row().id)
+            .map_err(StandardGraphqlError::from_repository_error)?;
+
+        Ok(oms_fields.is_some_and(|fields| fields.is_serialised))
+    }
+
     pub async fn stats(
         &self,
         ctx: &Context<'_>,
@@ -76,6 +76,9 @@ pub use self::store_preference::*;
 pub mod repack;
 pub use self::repack::*;
 
+pub mod prescription_line_direction;
+pub use self::prescription_line_direction::*;
+
 pub mod property;
 pub use self::property::*;
 
@@ -115,6 +118,9 @@ pub use self::reason_option::*;
 pub mod cold_storage_type;
 pub use self::cold_storage_type::*;
 
+pub mod allocation_rule;
+pub use self::allocation_rule::*;
+
 use async_graphql::*;
 pub struct DeleteResponse(pub String);
 #[Object]
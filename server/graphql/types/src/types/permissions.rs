@@ -26,6 +26,7 @@ pub enum UserPermission {
     CreateRepack,
     StocktakeQuery,
     StocktakeMutate,
+    StocktakeApprove,
     InventoryAdjustmentMutate,
     RequisitionQuery,
     RequisitionMutate,
@@ -57,6 +58,7 @@ pub enum UserPermission {
     AssetCatalogueItemMutate,
     NamePropertiesMutate,
     EditCentralData,
+    ApiKeyMutate,
 }
 
 #[Object]
@@ -110,6 +112,7 @@ impl UserPermission {
             PermissionType::CreateRepack => UserPermission::CreateRepack,
             PermissionType::StocktakeQuery => UserPermission::StocktakeQuery,
             PermissionType::StocktakeMutate => UserPermission::StocktakeMutate,
+            PermissionType::StocktakeApprove => UserPermission::StocktakeApprove,
             PermissionType::InventoryAdjustmentMutate => UserPermission::InventoryAdjustmentMutate,
             PermissionType::RequisitionQuery => UserPermission::RequisitionQuery,
             PermissionType::RequisitionMutate => UserPermission::RequisitionMutate,
@@ -144,6 +147,7 @@ impl UserPermission {
             PermissionType::AssetCatalogueItemMutate => UserPermission::AssetCatalogueItemMutate,
             PermissionType::NamePropertiesMutate => UserPermission::NamePropertiesMutate,
             PermissionType::EditCentralData => UserPermission::EditCentralData,
+            PermissionType::ApiKeyMutate => UserPermission::ApiKeyMutate,
         }
     }
 
@@ -160,6 +164,7 @@ impl UserPermission {
             UserPermission::CreateRepack => PermissionType::CreateRepack,
             UserPermission::StocktakeQuery => PermissionType::StocktakeQuery,
             UserPermission::StocktakeMutate => PermissionType::StocktakeMutate,
+            UserPermission::StocktakeApprove => PermissionType::StocktakeApprove,
             UserPermission::InventoryAdjustmentMutate => PermissionType::InventoryAdjustmentMutate,
             UserPermission::RequisitionQuery => PermissionType::RequisitionQuery,
             UserPermission::RequisitionMutate => PermissionType::RequisitionMutate,
@@ -193,6 +198,7 @@ impl UserPermission {
             UserPermission::AssetCatalogueItemMutate => PermissionType::AssetCatalogueItemMutate,
             UserPermission::NamePropertiesMutate => PermissionType::NamePropertiesMutate,
             UserPermission::EditCentralData => PermissionType::EditCentralData,
+            UserPermission::ApiKeyMutate => PermissionType::ApiKeyMutate,
         }
     }
 }
@@ -0,0 +1,85 @@
+use async_graphql::*;
+use graphql_core::{standard_graphql_error::StandardGraphqlError, ContextExt};
+use repository::PrescriptionLineDirectionRow;
+use service::invoice::prescription::get_quantity_to_dispense;
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+#[graphql(remote = "repository::AdministrationRoute")]
+pub enum AdministrationRouteType {
+    Oral,
+    Sublingual,
+    Topical,
+    Inhaled,
+    Nasal,
+    Ophthalmic,
+    Otic,
+    Rectal,
+    Vaginal,
+    Intramuscular,
+    Intravenous,
+    Subcutaneous,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct PrescriptionLineDirectionNode {
+    pub direction: PrescriptionLineDirectionRow,
+}
+
+#[Object]
+impl PrescriptionLineDirectionNode {
+    pub async fn id(&self) -> &str {
+        &self.direction.id
+    }
+
+    pub async fn invoice_line_id(&self) -> &str {
+        &self.direction.invoice_line_id
+    }
+
+    pub async fn sig(&self) -> &Option<String> {
+        &self.direction.sig
+    }
+
+    /// Number of units per administration
+    pub async fn dose(&self) -> &Option<f64> {
+        &self.direction.dose
+    }
+
+    pub async fn dose_unit(&self) -> &Option<String> {
+        &self.direction.dose_unit
+    }
+
+    /// Empty when taken as required
+    pub async fn frequency_per_day(&self) -> &Option<f64> {
+        &self.direction.frequency_per_day
+    }
+
+    pub async fn duration_days(&self) -> &Option<i32> {
+        &self.direction.duration_days
+    }
+
+    pub async fn route(&self) -> Option<AdministrationRouteType> {
+        self.direction
+            .route
+            .clone()
+            .map(AdministrationRouteType::from)
+    }
+
+    /// Full text directions, as printed on the label
+    pub async fn directions(&self) -> &str {
+        &self.direction.directions
+    }
+
+    /// Number of units needed for the full duration, empty when dose, frequency or duration is
+    /// not known or the dose is in another unit than the item (e.g. mL of a tablet)
+    pub async fn quantity_to_dispense(&self, ctx: &Context<'_>) -> Result<Option<f64>> {
+        let connection = ctx.get_connection_manager().connection()?;
+        get_quantity_to_dispense(&connection, &self.direction)
+            .map_err(StandardGraphqlError::from_repository_error)
+    }
+}
+
+impl PrescriptionLineDirectionNode {
+    pub fn from_domain(direction: PrescriptionLineDirectionRow) -> Self {
+        PrescriptionLineDirectionNode { direction }
+    }
+}
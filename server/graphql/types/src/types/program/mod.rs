@@ -10,3 +10,5 @@ pub mod rnr_form;
 pub mod rnr_form_line;
 pub mod vaccination;
 pub mod vaccination_card;
+pub mod vaccination_defaulter;
+pub mod vaccine_open_vial;
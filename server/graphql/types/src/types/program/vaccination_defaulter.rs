@@ -0,0 +1,101 @@
+use async_graphql::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use service::vaccination::defaulters::{VaccinationDefaulter, VaccineCourseDefaulters};
+
+use crate::types::VaccineCourseNode;
+
+pub struct VaccineCourseDefaultersNode {
+    pub course: VaccineCourseDefaulters,
+}
+
+#[Object]
+impl VaccineCourseDefaultersNode {
+    pub async fn vaccine_course(&self) -> VaccineCourseNode {
+        VaccineCourseNode::from_domain(self.course.vaccine_course.clone())
+    }
+
+    pub async fn defaulters(&self) -> Vec<VaccinationDefaulterNode> {
+        self.course
+            .defaulters
+            .iter()
+            .map(|defaulter| VaccinationDefaulterNode::from_domain(defaulter.clone()))
+            .collect()
+    }
+}
+
+impl VaccineCourseDefaultersNode {
+    pub fn from_domain(course: VaccineCourseDefaulters) -> VaccineCourseDefaultersNode {
+        VaccineCourseDefaultersNode { course }
+    }
+}
+
+pub struct VaccinationDefaulterNode {
+    pub defaulter: VaccinationDefaulter,
+}
+
+#[Object]
+impl VaccinationDefaulterNode {
+    pub async fn program_enrolment_id(&self) -> &str {
+        &self.defaulter.enrolment.row.id
+    }
+    pub async fn program_name(&self) -> &str {
+        &self.defaulter.enrolment.program_row.name
+    }
+    pub async fn patient_id(&self) -> &str {
+        &self.defaulter.enrolment.patient_row.id
+    }
+    pub async fn patient_code(&self) -> &str {
+        &self.defaulter.enrolment.patient_row.code
+    }
+    pub async fn first_name(&self) -> &Option<String> {
+        &self.defaulter.enrolment.patient_row.first_name
+    }
+    pub async fn last_name(&self) -> &Option<String> {
+        &self.defaulter.enrolment.patient_row.last_name
+    }
+    pub async fn date_of_birth(&self) -> &Option<NaiveDate> {
+        &self.defaulter.enrolment.patient_row.date_of_birth
+    }
+    pub async fn phone(&self) -> &Option<String> {
+        &self.defaulter.enrolment.patient_row.phone
+    }
+    pub async fn email(&self) -> &Option<String> {
+        &self.defaulter.enrolment.patient_row.email
+    }
+    pub async fn address1(&self) -> &Option<String> {
+        &self.defaulter.enrolment.patient_row.address1
+    }
+    pub async fn address2(&self) -> &Option<String> {
+        &self.defaulter.enrolment.patient_row.address2
+    }
+    pub async fn vaccine_course_dose_id(&self) -> &str {
+        &self.defaulter.dose.vaccine_course_dose_id
+    }
+    pub async fn dose_label(&self) -> &str {
+        &self.defaulter.dose.label
+    }
+    pub async fn due_date(&self) -> &NaiveDate {
+        &self.defaulter.due_date
+    }
+    pub async fn days_overdue(&self) -> i64 {
+        self.defaulter.days_overdue
+    }
+    pub async fn last_tracing_attempt_datetime(&self) -> Option<DateTime<Utc>> {
+        self.defaulter
+            .last_tracing_attempt
+            .as_ref()
+            .map(|event| DateTime::<Utc>::from_naive_utc_and_offset(event.datetime, Utc))
+    }
+    pub async fn last_tracing_attempt_outcome(&self) -> Option<String> {
+        self.defaulter
+            .last_tracing_attempt
+            .as_ref()
+            .and_then(|event| event.data.clone())
+    }
+}
+
+impl VaccinationDefaulterNode {
+    pub fn from_domain(defaulter: VaccinationDefaulter) -> VaccinationDefaulterNode {
+        VaccinationDefaulterNode { defaulter }
+    }
+}
@@ -0,0 +1,107 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use dataloader::DataLoader;
+use graphql_core::{loader::StockLineByIdLoader, ContextExt};
+use repository::VaccineOpenVialRow;
+use service::vaccination::open_vial::VaccineWastage;
+
+use crate::types::{ItemNode, StockLineNode};
+
+#[derive(PartialEq, Debug)]
+pub struct VaccineOpenVialNode {
+    pub open_vial: VaccineOpenVialRow,
+}
+
+#[Object]
+impl VaccineOpenVialNode {
+    pub async fn id(&self) -> &str {
+        &self.open_vial.id
+    }
+
+    pub async fn stock_line_id(&self) -> &str {
+        &self.open_vial.stock_line_id
+    }
+
+    pub async fn vaccine_course_id(&self) -> &str {
+        &self.open_vial.vaccine_course_id
+    }
+
+    pub async fn opened_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.open_vial.opened_datetime, Utc)
+    }
+
+    pub async fn discard_after_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_naive_utc_and_offset(self.open_vial.discard_after_datetime, Utc)
+    }
+
+    /// Open vial policy time has passed, remaining doses should be discarded
+    pub async fn is_expired(&self) -> bool {
+        self.open_vial.discard_after_datetime <= Utc::now().naive_utc()
+    }
+
+    pub async fn total_doses(&self) -> i32 {
+        self.open_vial.total_doses
+    }
+
+    pub async fn doses_remaining(&self) -> i32 {
+        self.open_vial.doses_remaining
+    }
+
+    pub async fn discarded_datetime(&self) -> Option<DateTime<Utc>> {
+        self.open_vial
+            .discarded_datetime
+            .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+    }
+
+    pub async fn wastage_invoice_id(&self) -> &Option<String> {
+        &self.open_vial.wastage_invoice_id
+    }
+
+    pub async fn stock_line(&self, ctx: &Context<'_>) -> Result<Option<StockLineNode>> {
+        let loader = ctx.get_loader::<DataLoader<StockLineByIdLoader>>();
+        let result = loader
+            .load_one(self.open_vial.stock_line_id.clone())
+            .await?;
+
+        Ok(result.map(StockLineNode::from_domain))
+    }
+}
+
+impl VaccineOpenVialNode {
+    pub fn from_domain(open_vial: VaccineOpenVialRow) -> VaccineOpenVialNode {
+        VaccineOpenVialNode { open_vial }
+    }
+}
+
+pub struct VaccineWastageNode {
+    pub wastage: VaccineWastage,
+}
+
+#[Object]
+impl VaccineWastageNode {
+    pub async fn item(&self) -> ItemNode {
+        ItemNode::from_domain(self.wastage.item.clone())
+    }
+
+    pub async fn doses_opened(&self) -> i64 {
+        self.wastage.doses_opened
+    }
+
+    pub async fn doses_administered(&self) -> i64 {
+        self.wastage.doses_administered
+    }
+
+    pub async fn doses_wasted(&self) -> i64 {
+        self.wastage.doses_wasted
+    }
+
+    pub async fn wastage_rate(&self) -> f64 {
+        self.wastage.wastage_rate()
+    }
+}
+
+impl VaccineWastageNode {
+    pub fn from_domain(wastage: VaccineWastage) -> VaccineWastageNode {
+        VaccineWastageNode { wastage }
+    }
+}
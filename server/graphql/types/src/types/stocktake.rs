@@ -15,6 +15,9 @@ use super::{InvoiceNode, StocktakeLineConnector, UserNode};
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")] // only needed to be comparable in tests
 pub enum StocktakeNodeStatus {
     New,
+    Recount,
+    PendingApproval,
+    Approved,
     Finalised,
 }
 
@@ -59,6 +62,18 @@ impl StocktakeNode {
         self.stocktake.is_locked
     }
 
+    pub async fn is_blind(&self) -> bool {
+        self.stocktake.is_blind
+    }
+
+    pub async fn variance_quantity_threshold(&self) -> Option<f64> {
+        self.stocktake.variance_quantity_threshold
+    }
+
+    pub async fn variance_value_threshold(&self) -> Option<f64> {
+        self.stocktake.variance_value_threshold
+    }
+
     pub async fn status(&self) -> StocktakeNodeStatus {
         StocktakeNodeStatus::from_domain(&self.stocktake.status)
     }
@@ -141,6 +156,9 @@ impl StocktakeNodeStatus {
     pub fn to_domain(self) -> StocktakeStatus {
         match self {
             StocktakeNodeStatus::New => StocktakeStatus::New,
+            StocktakeNodeStatus::Recount => StocktakeStatus::Recount,
+            StocktakeNodeStatus::PendingApproval => StocktakeStatus::PendingApproval,
+            StocktakeNodeStatus::Approved => StocktakeStatus::Approved,
             StocktakeNodeStatus::Finalised => StocktakeStatus::Finalised,
         }
     }
@@ -148,6 +166,9 @@ impl StocktakeNodeStatus {
     pub fn from_domain(status: &StocktakeStatus) -> StocktakeNodeStatus {
         match status {
             StocktakeStatus::New => StocktakeNodeStatus::New,
+            StocktakeStatus::Recount => StocktakeNodeStatus::Recount,
+            StocktakeStatus::PendingApproval => StocktakeNodeStatus::PendingApproval,
+            StocktakeStatus::Approved => StocktakeNodeStatus::Approved,
             StocktakeStatus::Finalised => StocktakeNodeStatus::Finalised,
         }
     }
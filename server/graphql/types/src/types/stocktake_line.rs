@@ -2,11 +2,12 @@ use async_graphql::*;
 use chrono::NaiveDate;
 use dataloader::DataLoader;
 use repository::StocktakeLine;
-use service::usize_to_u32;
+use service::{stocktake::check_snapshot_hidden, usize_to_u32};
 
 use graphql_core::{
     loader::{
         InventoryAdjustmentReasonByIdLoader, ItemLoader, LocationByIdLoader, StockLineByIdLoader,
+        StocktakeByIdLoader,
     },
     standard_graphql_error::StandardGraphqlError,
     ContextExt,
@@ -28,16 +29,21 @@ impl StocktakeLineNode {
         &self.line.line.stocktake_id
     }
 
+    /// Quantities of the stock line are zero while `isSnapshotHidden` is true
     pub async fn stock_line(&self, ctx: &Context<'_>) -> Result<Option<StockLineNode>> {
         if let Some(ref stock_line) = self.line.stock_line {
             let loader = ctx.get_loader::<DataLoader<StockLineByIdLoader>>();
-            let stock_line = loader.load_one(stock_line.id.clone()).await?.ok_or(
+            let mut stock_line = loader.load_one(stock_line.id.clone()).await?.ok_or(
                 StandardGraphqlError::InternalError(format!(
                     "Cannot find stock line {}",
                     stock_line.id
                 ))
                 .extend(),
             )?;
+            if self.snapshot_hidden(ctx).await? {
+                stock_line.stock_line_row.total_number_of_packs = 0.0;
+                stock_line.stock_line_row.available_number_of_packs = 0.0;
+            }
             Ok(Some(StockLineNode { stock_line }))
         } else {
             Ok(None)
@@ -57,8 +63,24 @@ impl StocktakeLineNode {
         self.line.line.comment.clone()
     }
 
-    pub async fn snapshot_number_of_packs(&self) -> f64 {
-        self.line.line.snapshot_number_of_packs
+    /// Empty while `isSnapshotHidden` is true
+    pub async fn snapshot_number_of_packs(&self, ctx: &Context<'_>) -> Result<Option<f64>> {
+        if self.snapshot_hidden(ctx).await? {
+            return Ok(None);
+        }
+        Ok(Some(self.line.line.snapshot_number_of_packs))
+    }
+
+    /// The count of a blind stocktake hasn't been submitted, snapshot quantities are hidden from
+    /// the counter. This only affects how the stocktake is displayed, the quantities can still be
+    /// read through e.g. `stockLines` or the item stats
+    pub async fn is_snapshot_hidden(&self, ctx: &Context<'_>) -> Result<bool> {
+        self.snapshot_hidden(ctx).await
+    }
+
+    /// Variance of the blind count exceeded the stocktake threshold
+    pub async fn requires_recount(&self) -> bool {
+        self.line.line.requires_recount
     }
 
     pub async fn counted_number_of_packs(&self) -> Option<f64> {
@@ -161,4 +183,20 @@ impl StocktakeLineNode {
     pub fn from_domain(line: StocktakeLine) -> StocktakeLineNode {
         StocktakeLineNode { line }
     }
+
+    async fn snapshot_hidden(&self, ctx: &Context<'_>) -> Result<bool> {
+        let loader = ctx.get_loader::<DataLoader<StocktakeByIdLoader>>();
+        let stocktake = loader
+            .load_one(self.line.line.stocktake_id.clone())
+            .await?
+            .ok_or(
+                StandardGraphqlError::InternalError(format!(
+                    "Cannot find stocktake {}",
+                    self.line.line.stocktake_id
+                ))
+                .extend(),
+            )?;
+
+        Ok(check_snapshot_hidden(&stocktake))
+    }
 }
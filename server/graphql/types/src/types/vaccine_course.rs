@@ -48,6 +48,10 @@ impl VaccineCourseNode {
         self.row().wastage_rate
     }
 
+    pub async fn open_vial_discard_hours(&self) -> Option<i32> {
+        self.row().open_vial_discard_hours
+    }
+
     pub async fn demographic(&self, ctx: &Context<'_>) -> Result<Option<DemographicNode>> {
         let demographic_id = match &self.row().demographic_id {
             Some(id) => id,
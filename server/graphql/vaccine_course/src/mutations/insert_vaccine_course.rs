@@ -57,6 +57,7 @@ pub struct InsertVaccineCourseInput {
     pub coverage_rate: f64,
     pub is_active: bool,
     pub wastage_rate: f64,
+    pub open_vial_discard_hours: Option<i32>,
 }
 
 impl From<InsertVaccineCourseInput> for InsertVaccineCourse {
@@ -71,6 +72,7 @@ impl From<InsertVaccineCourseInput> for InsertVaccineCourse {
             coverage_rate,
             is_active,
             wastage_rate,
+            open_vial_discard_hours,
         }: InsertVaccineCourseInput,
     ) -> Self {
         InsertVaccineCourse {
@@ -99,6 +101,7 @@ impl From<InsertVaccineCourseInput> for InsertVaccineCourse {
             coverage_rate,
             is_active,
             wastage_rate,
+            open_vial_discard_hours,
         }
     }
 }
@@ -71,6 +71,7 @@ pub struct UpdateVaccineCourseInput {
     pub coverage_rate: f64,
     pub is_active: bool,
     pub wastage_rate: f64,
+    pub open_vial_discard_hours: Option<i32>,
 }
 
 impl From<UpdateVaccineCourseInput> for UpdateVaccineCourse {
@@ -84,6 +85,7 @@ impl From<UpdateVaccineCourseInput> for UpdateVaccineCourse {
             coverage_rate,
             is_active,
             wastage_rate,
+            open_vial_discard_hours,
         }: UpdateVaccineCourseInput,
     ) -> Self {
         UpdateVaccineCourse {
@@ -111,6 +113,7 @@ impl From<UpdateVaccineCourseInput> for UpdateVaccineCourse {
             coverage_rate,
             is_active,
             wastage_rate,
+            open_vial_discard_hours,
         }
     }
 }
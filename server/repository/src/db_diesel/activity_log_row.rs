@@ -85,6 +85,14 @@ pub enum ActivityLogType {
     DemographicIndicatorUpdated,
     DemographicProjectionCreated,
     DemographicProjectionUpdated,
+    StocktakeStatusRecount,
+    StocktakeStatusPendingApproval,
+    StocktakeStatusApproved,
+    UserLoginFailed,
+    UserAccountLocked,
+    ClinicalWarningAcknowledged,
+    InvoicePaymentCreated,
+    InvoicePaymentCancelled,
 }
 
 #[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
@@ -0,0 +1,159 @@
+use super::{allocation_rule_row::allocation_rule::dsl::*, StorageConnection};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, Delete, RepositoryError,
+    RowActionType, Upsert,
+};
+
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    allocation_rule (id) {
+        id -> Text,
+        store_id -> Text,
+        name_tag_id -> Nullable<Text>,
+        strategy -> crate::db_diesel::allocation_rule_row::AllocationStrategyTypeMapping,
+        expiring_soon_days -> Nullable<Integer>,
+        min_remaining_shelf_life_days -> Nullable<Integer>,
+        avoid_item_variant_id -> Nullable<Text>,
+        priority -> Integer,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AllocationStrategyType {
+    /// First expiry first out
+    #[default]
+    Fefo,
+    /// First received first out
+    Fifo,
+    /// In location code order, so stock is picked walking the store once
+    PickPath,
+    /// Stock lines that can be issued without opening a pack first
+    FullPacksFirst,
+}
+
+/// How outbound shipment placeholder lines are allocated in a store, for customers with the name
+/// tag or for everyone when no name tag is set
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = allocation_rule)]
+#[diesel(treat_none_as_null = true)]
+pub struct AllocationRuleRow {
+    pub id: String,
+    pub store_id: String,
+    pub name_tag_id: Option<String>,
+    pub strategy: AllocationStrategyType,
+    /// Overrides the default expiring soon offset
+    pub expiring_soon_days: Option<i32>,
+    /// Stock expiring within this many days is not allocated
+    pub min_remaining_shelf_life_days: Option<i32>,
+    /// Stock lines of this item variant are not allocated
+    pub avoid_item_variant_id: Option<String>,
+    /// Higher priority wins when a customer has more than one matching name tag
+    pub priority: i32,
+}
+
+pub struct AllocationRuleRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> AllocationRuleRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        AllocationRuleRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &AllocationRuleRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(allocation_rule)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &AllocationRuleRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::AllocationRule,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        rule_id: &str,
+    ) -> Result<Option<AllocationRuleRow>, RepositoryError> {
+        let result = allocation_rule
+            .filter(id.eq(rule_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Highest priority first
+    pub fn find_many_by_store_id(
+        &self,
+        store: &str,
+    ) -> Result<Vec<AllocationRuleRow>, RepositoryError> {
+        let result = allocation_rule
+            .filter(store_id.eq(store))
+            .order((priority.desc(), id.asc()))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, rule_id: &str) -> Result<Option<i64>, RepositoryError> {
+        let Some(old_row) = self.find_one_by_id(rule_id)? else {
+            return Ok(None);
+        };
+        let change_log_id = self.insert_changelog(&old_row, RowActionType::Delete)?;
+
+        diesel::delete(allocation_rule.filter(id.eq(rule_id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(Some(change_log_id))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AllocationRuleRowDelete(pub String);
+impl Delete for AllocationRuleRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        AllocationRuleRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            AllocationRuleRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for AllocationRuleRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = AllocationRuleRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            AllocationRuleRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
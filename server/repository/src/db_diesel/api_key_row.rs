@@ -0,0 +1,115 @@
+use super::{api_key_row::api_key::dsl as api_key_dsl, StorageConnection};
+use crate::{repository_error::RepositoryError, Upsert};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    api_key (id) {
+        id -> Text,
+        name -> Text,
+        key_hash -> Text,
+        key_prefix -> Text,
+        user_id -> Text,
+        store_ids -> Text,
+        resources -> Text,
+        created_datetime -> Timestamp,
+        expiry_datetime -> Nullable<Timestamp>,
+        last_used_datetime -> Nullable<Timestamp>,
+        revoked_datetime -> Nullable<Timestamp>,
+    }
+}
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(table_name = api_key)]
+#[diesel(treat_none_as_null = true)]
+pub struct ApiKeyRow {
+    pub id: String,
+    pub name: String,
+    /// sha256 of the key, the key itself is only shown once when it's created
+    pub key_hash: String,
+    /// First characters of the key to help identifying it
+    pub key_prefix: String,
+    /// User the key acts on behalf of, the key never has more permissions than this user
+    pub user_id: String,
+    /// JSON array of the store ids the key is limited to
+    pub store_ids: String,
+    /// JSON array of the resources the key is limited to
+    pub resources: String,
+    pub created_datetime: NaiveDateTime,
+    pub expiry_datetime: Option<NaiveDateTime>,
+    pub last_used_datetime: Option<NaiveDateTime>,
+    pub revoked_datetime: Option<NaiveDateTime>,
+}
+
+pub struct ApiKeyRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> ApiKeyRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        ApiKeyRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &ApiKeyRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(api_key_dsl::api_key)
+            .values(row)
+            .on_conflict(api_key_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<ApiKeyRow>, RepositoryError> {
+        let result = api_key_dsl::api_key
+            .filter(api_key_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_one_by_key_hash(
+        &self,
+        key_hash: &str,
+    ) -> Result<Option<ApiKeyRow>, RepositoryError> {
+        let result = api_key_dsl::api_key
+            .filter(api_key_dsl::key_hash.eq(key_hash))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_all(&self) -> Result<Vec<ApiKeyRow>, RepositoryError> {
+        let result = api_key_dsl::api_key
+            .order(api_key_dsl::created_datetime.desc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn update_last_used_datetime(
+        &self,
+        id: &str,
+        last_used_datetime: NaiveDateTime,
+    ) -> Result<(), RepositoryError> {
+        diesel::update(api_key_dsl::api_key.filter(api_key_dsl::id.eq(id)))
+            .set(api_key_dsl::last_used_datetime.eq(last_used_datetime))
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+impl Upsert for ApiKeyRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        ApiKeyRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            ApiKeyRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
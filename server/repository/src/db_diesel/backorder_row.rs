@@ -0,0 +1,154 @@
+use super::{
+    backorder_row::backorder::dsl::*, item_link_row::item_link, name_link_row::name_link,
+    StorageConnection,
+};
+use crate::{repository_error::RepositoryError, Upsert};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    backorder (id) {
+        id -> Text,
+        store_id -> Text,
+        name_link_id -> Text,
+        item_link_id -> Text,
+        requisition_id -> Nullable<Text>,
+        quantity -> Double,
+        allocated_quantity -> Double,
+        status -> crate::db_diesel::backorder_row::BackorderStatusMapping,
+        created_datetime -> Timestamp,
+        fulfilled_datetime -> Nullable<Timestamp>,
+        cancelled_datetime -> Nullable<Timestamp>,
+        cancellation_reason -> Nullable<Text>,
+    }
+}
+
+joinable!(backorder -> name_link (name_link_id));
+joinable!(backorder -> item_link (item_link_id));
+allow_tables_to_appear_in_same_query!(backorder, name_link);
+allow_tables_to_appear_in_same_query!(backorder, item_link);
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BackorderStatus {
+    #[default]
+    Open,
+    Fulfilled,
+    Cancelled,
+}
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = backorder)]
+pub struct BackorderRow {
+    pub id: String,
+    pub store_id: String,
+    /// Customer the stock is owed to
+    pub name_link_id: String,
+    pub item_link_id: String,
+    /// Response requisition the backorder was raised from
+    pub requisition_id: Option<String>,
+    /// Units that could not be supplied when the backorder was raised
+    pub quantity: f64,
+    /// Units allocated to outbound shipments since
+    pub allocated_quantity: f64,
+    pub status: BackorderStatus,
+    pub created_datetime: NaiveDateTime,
+    pub fulfilled_datetime: Option<NaiveDateTime>,
+    pub cancelled_datetime: Option<NaiveDateTime>,
+    pub cancellation_reason: Option<String>,
+}
+
+impl BackorderRow {
+    pub fn remaining_quantity(&self) -> f64 {
+        (self.quantity - self.allocated_quantity).max(0.0)
+    }
+}
+
+pub struct BackorderRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> BackorderRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        BackorderRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &BackorderRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(backorder)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        backorder_id: &str,
+    ) -> Result<Option<BackorderRow>, RepositoryError> {
+        let result = backorder
+            .filter(id.eq(backorder_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// All backorders raised from the requisition, in any status
+    pub fn find_many_by_requisition_id(
+        &self,
+        requisition: &str,
+    ) -> Result<Vec<BackorderRow>, RepositoryError> {
+        let result = backorder
+            .filter(requisition_id.eq(requisition))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    /// Open backorders of a store, oldest first. Optionally limited to items and a customer,
+    /// including merged items and names
+    pub fn find_open(
+        &self,
+        store: &str,
+        item_ids: Option<&[String]>,
+        name_id: Option<&str>,
+    ) -> Result<Vec<BackorderRow>, RepositoryError> {
+        let mut query = backorder
+            .inner_join(item_link::table)
+            .inner_join(name_link::table)
+            .filter(store_id.eq(store).and(status.eq(BackorderStatus::Open)))
+            .select(backorder::all_columns)
+            .order(created_datetime.asc())
+            .into_boxed();
+
+        if let Some(item_ids) = item_ids {
+            query = query.filter(item_link::item_id.eq_any(item_ids.to_vec()));
+        }
+        if let Some(name_id) = name_id {
+            query = query.filter(name_link::name_id.eq(name_id.to_string()));
+        }
+
+        let result = query.load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for BackorderRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        BackorderRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            BackorderRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -111,7 +111,25 @@ pub enum ChangelogTableName {
     PackagingVariant,
     IndicatorValue,
     BundledItem,
+    LmisCodeMapping,
     Item,
+    Recall,
+    RecallBatch,
+    RecallStockLine,
+    SerialNumber,
+    SerialNumberMovement,
+    ItemOmsFields,
+    InvoicePayment,
+    PriceList,
+    PriceListLine,
+    TaxRule,
+    NamePriceCategory,
+    PrescriptionLineDirection,
+    PickList,
+    PickListLine,
+    PickPathLocation,
+    VaccineOpenVial,
+    AllocationRule,
 }
 
 pub(crate) enum ChangeLogSyncStyle {
@@ -179,6 +197,24 @@ impl ChangelogTableName {
             ChangelogTableName::PackagingVariant => ChangeLogSyncStyle::Central,
             ChangelogTableName::IndicatorValue => ChangeLogSyncStyle::Legacy,
             ChangelogTableName::BundledItem => ChangeLogSyncStyle::Central,
+            ChangelogTableName::LmisCodeMapping => ChangeLogSyncStyle::Central,
+            ChangelogTableName::Recall => ChangeLogSyncStyle::Central,
+            ChangelogTableName::RecallBatch => ChangeLogSyncStyle::Central,
+            ChangelogTableName::RecallStockLine => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::SerialNumber => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::SerialNumberMovement => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::ItemOmsFields => ChangeLogSyncStyle::Central,
+            ChangelogTableName::InvoicePayment => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::PriceList => ChangeLogSyncStyle::Central,
+            ChangelogTableName::PriceListLine => ChangeLogSyncStyle::Central,
+            ChangelogTableName::TaxRule => ChangeLogSyncStyle::Central,
+            ChangelogTableName::NamePriceCategory => ChangeLogSyncStyle::Central,
+            ChangelogTableName::PrescriptionLineDirection => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::PickList => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::PickListLine => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::PickPathLocation => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::VaccineOpenVial => ChangeLogSyncStyle::Remote,
+            ChangelogTableName::AllocationRule => ChangeLogSyncStyle::Remote,
         }
     }
 }
@@ -493,6 +529,12 @@ fn create_filtered_outgoing_sync_query(
         .select(store::id.nullable())
         .into_boxed();
 
+    let names_of_active_stores_for_site = store::table
+        .inner_join(name_link::table)
+        .filter(store::site_id.eq(sync_site_id))
+        .select(name_link::name_id.nullable())
+        .into_boxed();
+
     // Filter the query for the matching records for each type
     query = query.filter(
         changelog_deduped::table_name
@@ -500,7 +542,15 @@ fn create_filtered_outgoing_sync_query(
             .or(changelog_deduped::table_name.eq(ChangelogTableName::SyncFileReference)) // All sites get all sync file references (not necessarily files)
             .or(changelog_deduped::table_name
                 .eq_any(remote_sync_table_names)
-                .and(changelog_deduped::store_id.eq_any(active_stores_for_site))),
+                .and(changelog_deduped::store_id.eq_any(active_stores_for_site)))
+            // Serial numbers issued to a store on the site are received with the transferred shipment
+            .or(changelog_deduped::table_name
+                .eq(ChangelogTableName::SerialNumber)
+                .and(
+                    name_link::name_id
+                        .nullable()
+                        .eq_any(names_of_active_stores_for_site),
+                )),
         // Any other special cases could be handled here...
     );
 
@@ -13,7 +13,8 @@ use crate::{
     ChangelogFilter, ChangelogRepository, ChangelogRow, ChangelogTableName, CurrencyRow,
     EqualFilter, InvoiceLineRow, InvoiceLineRowRepository, InvoiceRow, InvoiceRowRepository,
     LocationRowRepository, NameRow, RequisitionLineRow, RequisitionLineRowRepository,
-    RequisitionRow, RequisitionRowRepository, RowActionType, StorageConnection, StoreRow, Upsert,
+    RequisitionRow, RequisitionRowRepository, RowActionType, SerialNumberRow, StorageConnection,
+    StoreRow, Upsert,
 };
 
 #[actix_rt::test]
@@ -701,3 +702,46 @@ async fn test_changelog_outgoing_sync_records() {
     assert_eq!(outgoing_results.len(), 1);
     assert_eq!(outgoing_results[0].record_id, asset_class_id);
 }
+
+#[actix_rt::test]
+async fn test_changelog_outgoing_transferred_serial_numbers() {
+    let (_, connection, _, _) = test_db::setup_all(
+        "test_changelog_outgoing_transferred_serial_numbers",
+        MockDataInserts::none().names().stores().units().items(),
+    )
+    .await;
+
+    let repo = ChangelogRepository::new(&connection);
+    let site1_id = mock_store_a().site_id;
+    let site2_id = mock_store_b().site_id;
+
+    // Unit of store A, issued to store B on another site
+    let row = SerialNumberRow {
+        id: "serial_number_id".to_string(),
+        store_id: mock_store_a().id,
+        item_link_id: mock_item_a().id,
+        serial: "S1".to_string(),
+        ..Default::default()
+    };
+    row.upsert(&connection).unwrap();
+
+    let outgoing_results = repo
+        .outgoing_sync_records_from_central(0, 1000, site2_id, true)
+        .unwrap();
+    assert!(outgoing_results.is_empty());
+
+    SerialNumberRow {
+        issued_name_link_id: Some(mock_store_b().name_link_id),
+        ..row
+    }
+    .upsert(&connection)
+    .unwrap();
+
+    for site_id in [site1_id, site2_id] {
+        let outgoing_results = repo
+            .outgoing_sync_records_from_central(0, 1000, site_id, true)
+            .unwrap();
+        assert_eq!(outgoing_results.len(), 1);
+        assert_eq!(outgoing_results[0].record_id, "serial_number_id");
+    }
+}
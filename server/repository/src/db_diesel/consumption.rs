@@ -1,4 +1,7 @@
-use super::{consumption::consumption::dsl as consumption_dsl, StorageConnection};
+use super::{
+    item_link_row::item_link::dsl as item_link_dsl,
+    stock_aggregate::consumption_aggregate::dsl as consumption_aggregate_dsl, StorageConnection,
+};
 
 use crate::{
     diesel_macros::{apply_date_filter, apply_equal_filter},
@@ -6,6 +9,7 @@ use crate::{
 };
 use diesel::prelude::*;
 
+// View over stock movements, queries use the daily `consumption_aggregate` table instead
 table! {
     consumption (id) {
         id -> Text,
@@ -69,7 +73,16 @@ impl<'a> ConsumptionRepository<'a> {
         filter: Option<ConsumptionFilter>,
     ) -> Result<Vec<ConsumptionRow>, RepositoryError> {
         // Query Consumption
-        let mut query = consumption_dsl::consumption.into_boxed();
+        let mut query = consumption_aggregate_dsl::consumption_aggregate
+            .inner_join(item_link_dsl::item_link)
+            .select((
+                consumption_aggregate_dsl::id,
+                item_link_dsl::item_id,
+                consumption_aggregate_dsl::store_id,
+                consumption_aggregate_dsl::quantity,
+                consumption_aggregate_dsl::date,
+            ))
+            .into_boxed();
 
         if let Some(f) = filter {
             let ConsumptionFilter {
@@ -78,9 +91,9 @@ impl<'a> ConsumptionRepository<'a> {
                 store_id,
             } = f;
 
-            apply_equal_filter!(query, item_id, consumption_dsl::item_id);
-            apply_equal_filter!(query, store_id, consumption_dsl::store_id);
-            apply_date_filter!(query, date, consumption_dsl::date);
+            apply_equal_filter!(query, item_id, item_link_dsl::item_id);
+            apply_equal_filter!(query, store_id, consumption_aggregate_dsl::store_id);
+            apply_date_filter!(query, date, consumption_aggregate_dsl::date);
         }
 
         // Debug diesel query
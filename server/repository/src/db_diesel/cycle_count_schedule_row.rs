@@ -0,0 +1,107 @@
+use super::{
+    cycle_count_schedule_row::cycle_count_schedule::dsl as cycle_count_schedule_dsl,
+    StorageConnection,
+};
+use crate::{repository_error::RepositoryError, Upsert};
+
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+
+table! {
+    cycle_count_schedule (id) {
+        id -> Text,
+        store_id -> Text,
+        is_active -> Bool,
+        cycle_length_days -> Integer,
+        abc_weighting -> Nullable<crate::db_diesel::cycle_count_schedule_row::CycleCountAbcWeightingMapping>,
+        last_generated_date -> Nullable<Date>,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum CycleCountAbcWeighting {
+    /// Classify items by the value of stock on hand
+    Value,
+    /// Classify items by consumption
+    Movement,
+}
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(table_name = cycle_count_schedule)]
+#[diesel(treat_none_as_null = true)]
+pub struct CycleCountScheduleRow {
+    pub id: String,
+    pub store_id: String,
+    pub is_active: bool,
+    /// Days between generated cycle count stocktakes
+    pub cycle_length_days: i32,
+    /// When set, high value or fast moving items are counted more often
+    pub abc_weighting: Option<CycleCountAbcWeighting>,
+    pub last_generated_date: Option<NaiveDate>,
+}
+
+pub struct CycleCountScheduleRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> CycleCountScheduleRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        CycleCountScheduleRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &CycleCountScheduleRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(cycle_count_schedule_dsl::cycle_count_schedule)
+            .values(row)
+            .on_conflict(cycle_count_schedule_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<CycleCountScheduleRow>, RepositoryError> {
+        let result = cycle_count_schedule_dsl::cycle_count_schedule
+            .filter(cycle_count_schedule_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_one_by_store_id(
+        &self,
+        store_id: &str,
+    ) -> Result<Option<CycleCountScheduleRow>, RepositoryError> {
+        let result = cycle_count_schedule_dsl::cycle_count_schedule
+            .filter(cycle_count_schedule_dsl::store_id.eq(store_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_all_active(&self) -> Result<Vec<CycleCountScheduleRow>, RepositoryError> {
+        let result = cycle_count_schedule_dsl::cycle_count_schedule
+            .filter(cycle_count_schedule_dsl::is_active.eq(true))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for CycleCountScheduleRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        CycleCountScheduleRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            CycleCountScheduleRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,344 @@
+use diesel::{
+    prelude::*,
+    sql_query,
+    sql_types::{BigInt, Text},
+};
+
+use crate::{RepositoryError, StorageConnection};
+
+#[derive(QueryableByName, Debug, PartialEq)]
+struct TableNameRow {
+    #[diesel(sql_type = Text)]
+    name: String,
+}
+
+#[derive(QueryableByName, Debug, PartialEq)]
+struct CountRow {
+    #[diesel(sql_type = BigInt)]
+    count: i64,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TableRowCount {
+    pub table_name: String,
+    pub count: i64,
+}
+
+#[cfg(not(feature = "postgres"))]
+const TABLE_NAMES_QUERY: &str = r#"
+    SELECT name FROM sqlite_master
+    WHERE type = 'table' AND name NOT LIKE 'sqlite_%'
+    ORDER BY name
+"#;
+
+#[cfg(feature = "postgres")]
+const TABLE_NAMES_QUERY: &str = r#"
+    SELECT table_name AS name FROM information_schema.tables
+    WHERE table_schema = 'public' AND table_type = 'BASE TABLE'
+    ORDER BY table_name
+"#;
+
+/// Number of rows in each table of the database, ordered by table name
+pub fn table_row_counts(
+    connection: &StorageConnection,
+) -> Result<Vec<TableRowCount>, RepositoryError> {
+    let tables =
+        sql_query(TABLE_NAMES_QUERY).load::<TableNameRow>(connection.lock().connection())?;
+
+    tables
+        .into_iter()
+        .map(|TableNameRow { name }| {
+            let CountRow { count } = sql_query(format!(
+                r#"SELECT COUNT(*) AS count FROM "{}""#,
+                name.replace('"', "\"\"")
+            ))
+            .get_result::<CountRow>(connection.lock().connection())?;
+
+            Ok(TableRowCount {
+                table_name: name,
+                count,
+            })
+        })
+        .collect()
+}
+
+/// Tables copied into the extract, rows of any other table are removed. New tables are left out
+/// of the extract until they are added here, after checking they don't hold patient data or
+/// secrets (or scrubbing them in SCRUB_EXTRACT_SQL)
+#[cfg(not(feature = "postgres"))]
+const EXTRACT_TABLES: &[&str] = &[
+    "__diesel_schema_migrations",
+    "activity_log",
+    "allocation_rule",
+    "asset",
+    "asset_catalogue_item",
+    "asset_catalogue_type",
+    "asset_category",
+    "asset_class",
+    "asset_internal_location",
+    "asset_log",
+    "asset_log_reason",
+    "asset_property",
+    "backorder",
+    "barcode",
+    "bundled_item",
+    "changelog",
+    "clinician",
+    "clinician_link",
+    "clinician_store_join",
+    "cold_storage_type",
+    "consumption_aggregate",
+    "context",
+    "currency",
+    "cycle_count_schedule",
+    "demographic",
+    "demographic_indicator",
+    "demographic_projection",
+    "document_registry",
+    "drug_ingredient",
+    "drug_interaction",
+    "form_schema",
+    "indicator_column",
+    "indicator_line",
+    "indicator_value",
+    "inventory_adjustment_reason",
+    "invoice",
+    "invoice_line",
+    "invoice_payment",
+    "item",
+    "item_link",
+    "item_variant",
+    "key_value_store",
+    "lmis_code_mapping",
+    "location",
+    "location_movement",
+    "master_list",
+    "master_list_line",
+    "master_list_name_join",
+    "migration_fragment_log",
+    "name",
+    "name_link",
+    "name_price_category",
+    "name_property",
+    "name_store_join",
+    "name_tag",
+    "name_tag_join",
+    "number",
+    "packaging_variant",
+    "period",
+    "period_schedule",
+    "pick_list",
+    "pick_list_line",
+    "pick_path_location",
+    "price_list",
+    "price_list_line",
+    "program",
+    "program_indicator",
+    "program_requisition_order_type",
+    "program_requisition_settings",
+    "property",
+    "reason_option",
+    "recall",
+    "recall_batch",
+    "recall_stock_line",
+    "report",
+    "requisition",
+    "requisition_line",
+    "return_reason",
+    "rnr_form",
+    "rnr_form_line",
+    "sensor",
+    "serial_number",
+    "serial_number_movement",
+    "stock_line",
+    "stock_on_hand_aggregate",
+    "stocktake",
+    "stocktake_line",
+    "store",
+    "store_preference",
+    "sync_log",
+    "tax_rule",
+    "temperature_breach",
+    "temperature_breach_config",
+    "temperature_log",
+    "unit",
+    "user_account",
+    "user_permission",
+    "user_store_join",
+    "vaccine_course",
+    "vaccine_course_dose",
+    "vaccine_course_item",
+    "vaccine_open_vial",
+];
+
+/// Removes patient data and secrets from the tables kept in the extract, data that only relates
+/// to the supply chain (items, stock, invoices, requisitions, etc.) is kept. Patients stay as
+/// anonymous names, so prescriptions (and recall recipients derived from them) can't be traced
+/// back to a person
+#[cfg(not(feature = "postgres"))]
+const SCRUB_EXTRACT_SQL: &str = r#"
+    CREATE TEMP TABLE patient_name_link AS
+        SELECT name_link.id FROM name_link
+        JOIN name ON name.id = name_link.name_id
+        WHERE name.type = 'PATIENT';
+    UPDATE name SET
+        name = 'Patient',
+        code = id,
+        first_name = NULL,
+        last_name = NULL,
+        gender = NULL,
+        date_of_birth = NULL,
+        date_of_death = NULL,
+        is_deceased = FALSE,
+        phone = NULL,
+        email = NULL,
+        address1 = NULL,
+        address2 = NULL,
+        comment = NULL,
+        national_health_number = NULL,
+        custom_data = NULL,
+        properties = NULL,
+        created_datetime = NULL
+    WHERE type = 'PATIENT';
+    DELETE FROM name_price_category
+    WHERE name_id IN (SELECT id FROM name WHERE type = 'PATIENT');
+    -- Prescriptions and returns from patients
+    UPDATE invoice SET comment = NULL, their_reference = NULL
+    WHERE name_link_id IN (SELECT id FROM patient_name_link);
+    UPDATE invoice_line SET note = NULL
+    WHERE invoice_id IN (
+        SELECT id FROM invoice WHERE name_link_id IN (SELECT id FROM patient_name_link)
+    );
+    UPDATE invoice_payment SET reference = NULL, comment = NULL
+    WHERE name_link_id IN (SELECT id FROM patient_name_link);
+    UPDATE serial_number SET issued_name_link_id = NULL
+    WHERE issued_name_link_id IN (SELECT id FROM patient_name_link);
+    -- Logged changes can contain patient details
+    UPDATE activity_log SET changed_to = NULL, changed_from = NULL;
+    UPDATE user_account SET hashed_password = '';
+    DELETE FROM key_value_store
+    WHERE id IN ('SETTINGS_SYNC_PASSWORD_SHA256', 'SETTINGS_TOKEN_SECRET');
+    DROP TABLE patient_name_link;
+"#;
+
+/// Writes a copy of the (sqlite) database to `path`, with patient data and secrets removed
+#[cfg(not(feature = "postgres"))]
+pub fn create_patient_free_extract(
+    connection: &StorageConnection,
+    path: &str,
+) -> Result<(), RepositoryError> {
+    use diesel::connection::SimpleConnection;
+
+    sql_query(format!("VACUUM INTO '{}'", path.replace('\'', "''")))
+        .execute(connection.lock().connection())?;
+
+    let mut extract =
+        SqliteConnection::establish(path).map_err(|error| RepositoryError::DBError {
+            msg: "Failed to open database extract".to_string(),
+            extra: format!("{:?}", error),
+        })?;
+
+    // Foreign keys would stop rows from being removed in any order
+    extract.batch_execute("PRAGMA foreign_keys = OFF;")?;
+    let tables = sql_query(TABLE_NAMES_QUERY).load::<TableNameRow>(&mut extract)?;
+    for TableNameRow { name } in tables {
+        if !EXTRACT_TABLES.contains(&name.as_str()) {
+            extract.batch_execute(&format!(r#"DELETE FROM "{}";"#, name.replace('"', "\"\"")))?;
+        }
+    }
+    extract.batch_execute(SCRUB_EXTRACT_SQL)?;
+    // Reclaim free pages, so deleted data can't be recovered from the file
+    extract.batch_execute("VACUUM;")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        mock::{mock_patient, mock_prescription_a, mock_user_account_a, MockDataInserts},
+        test_db::setup_all,
+        InvoiceRow, InvoiceRowRepository,
+    };
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn table_row_counts_include_all_tables() {
+        let (_, connection, _, _) =
+            setup_all("table_row_counts", MockDataInserts::none().names()).await;
+
+        let counts = table_row_counts(&connection).unwrap();
+        let name_count = counts
+            .iter()
+            .find(|count| count.table_name == "name")
+            .unwrap();
+        assert!(name_count.count > 0);
+        assert!(counts.iter().any(|count| count.table_name == "invoice"));
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    #[actix_rt::test]
+    async fn patient_free_extract() {
+        let (_, connection, _, _) = setup_all("patient_free_extract", MockDataInserts::all()).await;
+        InvoiceRowRepository::new(&connection)
+            .upsert_one(&InvoiceRow {
+                comment: Some("Patient address".to_string()),
+                ..mock_prescription_a()
+            })
+            .unwrap();
+
+        std::fs::create_dir_all("test_output").unwrap();
+        let path = "test_output/patient_free_extract_copy.sqlite";
+        let _ = std::fs::remove_file(path);
+        create_patient_free_extract(&connection, path).unwrap();
+
+        let mut extract = SqliteConnection::establish(path).unwrap();
+        let patient = sql_query(format!(
+            "SELECT name FROM name WHERE id = '{}'",
+            mock_patient().id
+        ))
+        .get_result::<TableNameRow>(&mut extract)
+        .unwrap();
+        assert_eq!(patient.name, "Patient");
+
+        let CountRow { count } = sql_query(format!(
+            "SELECT COUNT(*) AS count FROM user_account WHERE id = '{}' AND hashed_password = ''",
+            mock_user_account_a().id
+        ))
+        .get_result::<CountRow>(&mut extract)
+        .unwrap();
+        assert_eq!(count, 1);
+
+        let CountRow { count } = sql_query(format!(
+            "SELECT COUNT(*) AS count FROM invoice WHERE id = '{}' AND comment IS NULL",
+            mock_prescription_a().id
+        ))
+        .get_result::<CountRow>(&mut extract)
+        .unwrap();
+        assert_eq!(count, 1);
+
+        // Tables that aren't part of the extract are emptied
+        let table_names: Vec<String> = table_row_counts(&connection)
+            .unwrap()
+            .into_iter()
+            .map(|count| count.table_name)
+            .collect();
+        for table in EXTRACT_TABLES {
+            assert!(table_names.contains(&table.to_string()), "{}", table);
+        }
+        let CountRow { count } = sql_query("SELECT COUNT(*) AS count FROM document")
+            .get_result::<CountRow>(&mut extract)
+            .unwrap();
+        assert_eq!(count, 0);
+
+        // Source database is untouched
+        let source_patient = sql_query(format!(
+            "SELECT name FROM name WHERE id = '{}'",
+            mock_patient().id
+        ))
+        .get_result::<TableNameRow>(connection.lock().connection())
+        .unwrap();
+        assert_eq!(source_patient.name, mock_patient().name);
+    }
+}
@@ -0,0 +1,84 @@
+use super::{drug_ingredient_row::drug_ingredient::dsl::*, StorageConnection};
+use crate::{repository_error::RepositoryError, Upsert};
+
+use diesel::prelude::*;
+
+table! {
+    drug_ingredient (id) {
+        id -> Text,
+        item_code -> Text,
+        ingredient -> Text,
+    }
+}
+
+/// Active ingredient of an item, used to match drug interactions and duplicate therapy
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
+#[diesel(table_name = drug_ingredient)]
+pub struct DrugIngredientRow {
+    pub id: String,
+    /// Stored lower case
+    pub item_code: String,
+    /// Stored lower case
+    pub ingredient: String,
+}
+
+pub struct DrugIngredientRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> DrugIngredientRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        DrugIngredientRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &DrugIngredientRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(drug_ingredient)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        ingredient_id: &str,
+    ) -> Result<Option<DrugIngredientRow>, RepositoryError> {
+        let result = drug_ingredient
+            .filter(id.eq(ingredient_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_item_codes(
+        &self,
+        item_codes: &[String],
+    ) -> Result<Vec<DrugIngredientRow>, RepositoryError> {
+        let result = drug_ingredient
+            .filter(item_code.eq_any(item_codes))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete_all(&self) -> Result<(), RepositoryError> {
+        diesel::delete(drug_ingredient).execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+impl Upsert for DrugIngredientRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        DrugIngredientRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            DrugIngredientRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,101 @@
+use super::{drug_interaction_row::drug_interaction::dsl::*, StorageConnection};
+use crate::{repository_error::RepositoryError, Upsert};
+
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    drug_interaction (id) {
+        id -> Text,
+        subject_a -> Text,
+        subject_b -> Text,
+        severity -> crate::db_diesel::drug_interaction_row::DrugInteractionSeverityMapping,
+        description -> Text,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DrugInteractionSeverity {
+    Minor,
+    Moderate,
+    Major,
+    /// The combination must not be dispensed
+    Contraindicated,
+}
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
+#[diesel(table_name = drug_interaction)]
+pub struct DrugInteractionRow {
+    pub id: String,
+    /// Item code or ingredient name, stored lower case
+    pub subject_a: String,
+    /// Item code or ingredient name, stored lower case
+    pub subject_b: String,
+    pub severity: DrugInteractionSeverity,
+    pub description: String,
+}
+
+pub struct DrugInteractionRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> DrugInteractionRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        DrugInteractionRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &DrugInteractionRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(drug_interaction)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        interaction_id: &str,
+    ) -> Result<Option<DrugInteractionRow>, RepositoryError> {
+        let result = drug_interaction
+            .filter(id.eq(interaction_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Interactions where either subject is one of the given keys
+    pub fn find_many_by_subjects(
+        &self,
+        subjects: &[String],
+    ) -> Result<Vec<DrugInteractionRow>, RepositoryError> {
+        let result = drug_interaction
+            .filter(subject_a.eq_any(subjects).or(subject_b.eq_any(subjects)))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete_all(&self) -> Result<(), RepositoryError> {
+        diesel::delete(drug_interaction).execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+impl Upsert for DrugInteractionRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        DrugInteractionRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            DrugInteractionRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -8,7 +8,7 @@ use super::{
 use crate::repository_error::RepositoryError;
 use crate::{
     ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, InvoiceRowRepository,
-    RowActionType,
+    PrescriptionLineDirectionRowRepository, RowActionType, StockAggregateRepository,
 };
 use crate::{Delete, Upsert};
 
@@ -16,6 +16,7 @@ use diesel::prelude::*;
 
 use chrono::NaiveDate;
 use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
 
 table! {
     invoice_line (id) {
@@ -41,6 +42,10 @@ table! {
         return_reason_id -> Nullable<Text>,
         foreign_currency_price_before_tax -> Nullable<Double>,
         item_variant_id -> Nullable<Text>,
+        linked_invoice_line_id -> Nullable<Text>,
+        discrepancy_type -> Nullable<crate::db_diesel::invoice_line_row::InvoiceLineDiscrepancyTypeMapping>,
+        discrepancy_number_of_packs -> Double,
+        discrepancy_reason -> Nullable<Text>,
     }
 }
 
@@ -63,6 +68,17 @@ pub enum InvoiceLineType {
     Service,
 }
 
+/// Difference between what was shipped and what was received, recorded by the receiving store
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum InvoiceLineDiscrepancyType {
+    Short,
+    Damaged,
+    WrongBatch,
+    Excess,
+}
+
 #[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
 #[diesel(treat_none_as_null = true)]
 #[diesel(table_name = invoice_line)]
@@ -92,6 +108,12 @@ pub struct InvoiceLineRow {
     pub return_reason_id: Option<String>,
     pub foreign_currency_price_before_tax: Option<f64>,
     pub item_variant_id: Option<String>,
+    /// Outbound shipment line this inbound line was generated from by the transfer processor
+    pub linked_invoice_line_id: Option<String>,
+    pub discrepancy_type: Option<InvoiceLineDiscrepancyType>,
+    /// Packs affected by the discrepancy, in the pack size of this line
+    pub discrepancy_number_of_packs: f64,
+    pub discrepancy_reason: Option<String>,
 }
 
 pub struct InvoiceLineRowRepository<'a> {
@@ -104,12 +126,22 @@ impl<'a> InvoiceLineRowRepository<'a> {
     }
 
     pub fn upsert_one(&self, row: &InvoiceLineRow) -> Result<i64, RepositoryError> {
+        let old_row = self.find_one_by_id(&row.id)?;
         diesel::insert_into(invoice_line)
             .values(row)
             .on_conflict(id)
             .do_update()
             .set(row)
             .execute(self.connection.lock().connection())?;
+
+        let aggregates = StockAggregateRepository::new(self.connection);
+        if let Some(old_row) = old_row {
+            if old_row.item_link_id != row.item_link_id || old_row.invoice_id != row.invoice_id {
+                aggregates.refresh_invoice_line_consumption(&old_row)?;
+            }
+        }
+        aggregates.refresh_invoice_line_consumption(row)?;
+
         self.insert_changelog(row, RowActionType::Upsert)
     }
 
@@ -191,15 +223,22 @@ impl<'a> InvoiceLineRowRepository<'a> {
 
     pub fn delete(&self, invoice_line_id: &str) -> Result<Option<i64>, RepositoryError> {
         let old_row = self.find_one_by_id(invoice_line_id)?;
-        let change_log_id = match old_row {
-            Some(old_row) => self.insert_changelog(&old_row, RowActionType::Delete)?,
+        let (change_log_id, old_row) = match old_row {
+            Some(old_row) => (
+                self.insert_changelog(&old_row, RowActionType::Delete)?,
+                old_row,
+            ),
             None => {
                 return Ok(None);
             }
         };
 
+        PrescriptionLineDirectionRowRepository::new(self.connection)
+            .delete_by_invoice_line_id(invoice_line_id)?;
         diesel::delete(invoice_line.filter(id.eq(invoice_line_id)))
             .execute(self.connection.lock().connection())?;
+        StockAggregateRepository::new(self.connection)
+            .refresh_invoice_line_consumption(&old_row)?;
         Ok(Some(change_log_id))
     }
 
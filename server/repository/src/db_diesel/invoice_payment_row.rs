@@ -0,0 +1,226 @@
+use super::{
+    invoice_payment_row::invoice_payment::dsl::*, name_link_row::name_link, StorageConnection,
+};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    Upsert,
+};
+
+use chrono::NaiveDateTime;
+use diesel::{dsl::max, prelude::*};
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    invoice_payment (id) {
+        id -> Text,
+        store_id -> Text,
+        name_link_id -> Text,
+        invoice_id -> Nullable<Text>,
+        customer_return_id -> Nullable<Text>,
+        #[sql_name = "type"] type_ -> crate::db_diesel::invoice_payment_row::InvoicePaymentTypeMapping,
+        payment_method -> Nullable<crate::db_diesel::invoice_payment_row::PaymentMethodMapping>,
+        receipt_number -> BigInt,
+        amount -> Double,
+        currency_id -> Nullable<Text>,
+        currency_rate -> Double,
+        home_currency_amount -> Double,
+        reference -> Nullable<Text>,
+        comment -> Nullable<Text>,
+        user_id -> Nullable<Text>,
+        payment_datetime -> Timestamp,
+        cancelled_datetime -> Nullable<Timestamp>,
+    }
+}
+
+joinable!(invoice_payment -> name_link (name_link_id));
+allow_tables_to_appear_in_same_query!(invoice_payment, name_link);
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum InvoicePaymentType {
+    /// Money received from the customer, numbered as a receipt
+    #[default]
+    Payment,
+    /// Credit given for a customer return, numbered as a credit note
+    CreditNote,
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PaymentMethod {
+    Cash,
+    Card,
+    MobileMoney,
+    BankTransfer,
+    Cheque,
+    Insurance,
+    Other,
+}
+
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(treat_none_as_null = true)]
+#[diesel(table_name = invoice_payment)]
+pub struct InvoicePaymentRow {
+    pub id: String,
+    pub store_id: String,
+    pub name_link_id: String,
+    /// Invoice the payment or credit is applied to, a credit note can be left unapplied
+    pub invoice_id: Option<String>,
+    /// Customer return a credit note was issued for
+    pub customer_return_id: Option<String>,
+    #[diesel(column_name = type_)]
+    pub r#type: InvoicePaymentType,
+    /// Not set for credit notes
+    pub payment_method: Option<PaymentMethod>,
+    /// Receipt number for payments, credit note number for credit notes
+    pub receipt_number: i64,
+    /// Amount in the payment currency
+    pub amount: f64,
+    pub currency_id: Option<String>,
+    pub currency_rate: f64,
+    /// amount * currency_rate
+    pub home_currency_amount: f64,
+    /// e.g. card authorisation or mobile money transaction id
+    pub reference: Option<String>,
+    pub comment: Option<String>,
+    pub user_id: Option<String>,
+    pub payment_datetime: NaiveDateTime,
+    pub cancelled_datetime: Option<NaiveDateTime>,
+}
+
+impl InvoicePaymentRow {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled_datetime.is_some()
+    }
+}
+
+pub struct InvoicePaymentRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> InvoicePaymentRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        InvoicePaymentRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &InvoicePaymentRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(invoice_payment)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &InvoicePaymentRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::InvoicePayment,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        payment_id: &str,
+    ) -> Result<Option<InvoicePaymentRow>, RepositoryError> {
+        let result = invoice_payment
+            .filter(id.eq(payment_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Includes cancelled payments
+    pub fn find_many_by_invoice_ids(
+        &self,
+        invoice_ids: &[String],
+    ) -> Result<Vec<InvoicePaymentRow>, RepositoryError> {
+        let result = invoice_payment
+            .filter(invoice_id.eq_any(invoice_ids))
+            .order(payment_datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_customer_return_id(
+        &self,
+        return_id: &str,
+    ) -> Result<Vec<InvoicePaymentRow>, RepositoryError> {
+        let result = invoice_payment
+            .filter(customer_return_id.eq(return_id))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    /// Payments and credit notes of a customer in a store, including merged names
+    pub fn find_many_by_name_id(
+        &self,
+        store: &str,
+        name_id: &str,
+    ) -> Result<Vec<InvoicePaymentRow>, RepositoryError> {
+        let result = invoice_payment
+            .inner_join(name_link::table)
+            .filter(store_id.eq(store).and(name_link::name_id.eq(name_id)))
+            .select(invoice_payment::all_columns)
+            .order(payment_datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    /// Payments taken in a store between from (inclusive) and to (exclusive)
+    pub fn find_many_by_payment_datetime(
+        &self,
+        store: &str,
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+    ) -> Result<Vec<InvoicePaymentRow>, RepositoryError> {
+        let result = invoice_payment
+            .filter(store_id.eq(store))
+            .filter(payment_datetime.ge(from).and(payment_datetime.lt(to)))
+            .order(receipt_number.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_max_receipt_number(
+        &self,
+        r#type: InvoicePaymentType,
+        store: &str,
+    ) -> Result<Option<i64>, RepositoryError> {
+        let result = invoice_payment
+            .filter(type_.eq(r#type).and(store_id.eq(store)))
+            .select(max(receipt_number))
+            .first(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for InvoicePaymentRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = InvoicePaymentRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            InvoicePaymentRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
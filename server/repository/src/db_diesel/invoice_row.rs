@@ -7,7 +7,10 @@ use super::{
 };
 
 use crate::{repository_error::RepositoryError, Delete, Upsert};
-use crate::{ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RowActionType};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RowActionType,
+    StockAggregateRepository,
+};
 
 use diesel::{dsl::max, prelude::*};
 
@@ -166,12 +169,15 @@ impl<'a> InvoiceRowRepository<'a> {
     }
 
     pub fn upsert_one(&self, row: &InvoiceRow) -> Result<i64, RepositoryError> {
+        let old_row = self.find_one_by_id(&row.id)?;
         diesel::insert_into(invoice)
             .values(row)
             .on_conflict(id)
             .do_update()
             .set(row)
             .execute(self.connection.lock().connection())?;
+        StockAggregateRepository::new(self.connection)
+            .refresh_invoice_consumption(old_row.as_ref(), row)?;
         self.insert_changelog(row, RowActionType::Upsert)
     }
 
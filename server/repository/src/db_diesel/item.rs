@@ -4,7 +4,7 @@ use super::{
     master_list_line_row::master_list_line::dsl as master_list_line_dsl,
     master_list_name_join::master_list_name_join::dsl as master_list_name_join_dsl,
     master_list_row::master_list::dsl as master_list_dsl,
-    stock_on_hand::stock_on_hand::dsl as stock_on_hand_dsl,
+    stock_aggregate::stock_on_hand_aggregate::dsl as stock_on_hand_aggregate_dsl,
     store_row::store::dsl as store_dsl,
     unit_row::{unit, unit::dsl as unit_dsl},
     DBType, ItemRow, ItemType, StorageConnection, UnitRow,
@@ -251,11 +251,11 @@ fn create_filtered_query(store_id: String, filter: Option<ItemFilter>) -> BoxedI
 
         let item_ids_with_stock_on_hand = item_link_dsl::item_link
             .select(item_link_dsl::item_id)
-            .inner_join(stock_on_hand_dsl::stock_on_hand)
+            .inner_join(stock_on_hand_aggregate_dsl::stock_on_hand_aggregate)
             .filter(
-                stock_on_hand_dsl::available_stock_on_hand
+                stock_on_hand_aggregate_dsl::available_stock_on_hand
                     .gt(0.0)
-                    .and(stock_on_hand_dsl::store_id.eq(store_id.clone())),
+                    .and(stock_on_hand_aggregate_dsl::store_id.eq(store_id.clone())),
             )
             .group_by(item_link_dsl::item_id)
             .into_boxed();
@@ -1,4 +1,6 @@
-use crate::{Delete, Upsert};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, Delete, RowActionType, Upsert,
+};
 
 use super::{
     item_link_row::item_link, item_row::item::dsl::*, name_link_row::name_link, unit_row::unit,
@@ -7,6 +9,7 @@ use super::{
 
 use diesel::prelude::*;
 use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
 
 table! {
     item (id) {
@@ -26,6 +29,14 @@ table! {
     }
 }
 
+table! {
+    #[sql_name = "item"]
+    item_oms_fields (id) {
+        id -> Text,
+        is_serialised -> Bool,
+    }
+}
+
 table! {
     item_is_visible (id) {
         id -> Text,
@@ -75,6 +86,15 @@ pub struct ItemRow {
     pub vaccine_doses: i32,
 }
 
+/// Item fields only used by omSupply, they are not part of the legacy item record
+#[derive(Clone, Queryable, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[diesel(table_name = item_oms_fields)]
+pub struct ItemOmsFieldsRow {
+    pub id: String,
+    /// Units of the item are tracked by serial number
+    pub is_serialised: bool,
+}
+
 impl Default for ItemRow {
     fn default() -> Self {
         Self {
@@ -163,6 +183,35 @@ impl<'a> ItemRowRepository<'a> {
         Ok(result)
     }
 
+    pub fn find_one_oms_fields_by_id(
+        &self,
+        item_id: &str,
+    ) -> Result<Option<ItemOmsFieldsRow>, RepositoryError> {
+        let result = item_oms_fields::table
+            .filter(item_oms_fields::id.eq(item_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn update_is_serialised(
+        &self,
+        item_id: &str,
+        is_serialised: bool,
+    ) -> Result<i64, RepositoryError> {
+        diesel::update(item_oms_fields::table.find(item_id))
+            .set(item_oms_fields::is_serialised.eq(is_serialised))
+            .execute(self.connection.lock().connection())?;
+
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::ItemOmsFields,
+            record_id: item_id.to_owned(),
+            row_action: RowActionType::Upsert,
+            ..Default::default()
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
     pub fn delete(&self, item_id: &str) -> Result<(), RepositoryError> {
         diesel::update(item.filter(id.eq(item_id)))
             .set(is_active.eq(false))
@@ -204,3 +253,19 @@ impl Upsert for ItemRow {
         )
     }
 }
+
+impl Upsert for ItemOmsFieldsRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id =
+            ItemRowRepository::new(con).update_is_serialised(&self.id, self.is_serialised)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            ItemRowRepository::new(con).find_one_oms_fields_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -45,6 +45,7 @@ pub enum KeyType {
     SettingsDisplayCustomTheme,
     SettingsDisplayCustomThemeHash,
     SettingsLabelPrinter,
+    SettingsLoginSecurity,
 
     LogLevel,
     LogDirectory,
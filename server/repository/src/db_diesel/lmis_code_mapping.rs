@@ -0,0 +1,103 @@
+use super::{
+    lmis_code_mapping_row::{lmis_code_mapping, LmisCodeMappingRow, LmisCodeMappingType},
+    DBType, StorageConnection,
+};
+use crate::{diesel_macros::apply_equal_filter, repository_error::RepositoryError, EqualFilter};
+
+use diesel::{dsl::IntoBoxed, prelude::*};
+use util::inline_init;
+
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct LmisCodeMappingFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub program_id: Option<EqualFilter<String>>,
+    pub r#type: Option<EqualFilter<LmisCodeMappingType>>,
+    pub internal_id: Option<EqualFilter<String>>,
+}
+
+impl LmisCodeMappingFilter {
+    pub fn new() -> LmisCodeMappingFilter {
+        Self::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn program_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.program_id = Some(filter);
+        self
+    }
+
+    pub fn r#type(mut self, filter: EqualFilter<LmisCodeMappingType>) -> Self {
+        self.r#type = Some(filter);
+        self
+    }
+
+    pub fn internal_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.internal_id = Some(filter);
+        self
+    }
+}
+
+impl LmisCodeMappingType {
+    pub fn equal_to(&self) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_to = Some(self.clone()))
+    }
+}
+
+pub struct LmisCodeMappingRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> LmisCodeMappingRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        LmisCodeMappingRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<LmisCodeMappingFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query
+            .count()
+            .get_result(self.connection.lock().connection())?)
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: LmisCodeMappingFilter,
+    ) -> Result<Vec<LmisCodeMappingRow>, RepositoryError> {
+        let result = create_filtered_query(Some(filter))
+            .order((
+                lmis_code_mapping::type_.asc(),
+                lmis_code_mapping::internal_id.asc(),
+            ))
+            .load::<LmisCodeMappingRow>(self.connection.lock().connection())?;
+
+        Ok(result)
+    }
+}
+
+type BoxedLmisCodeMappingQuery = IntoBoxed<'static, lmis_code_mapping::table, DBType>;
+
+fn create_filtered_query(filter: Option<LmisCodeMappingFilter>) -> BoxedLmisCodeMappingQuery {
+    let mut query = lmis_code_mapping::table.into_boxed();
+    // Exclude any deleted mappings
+    query = query.filter(lmis_code_mapping::deleted_datetime.is_null());
+
+    if let Some(f) = filter {
+        let LmisCodeMappingFilter {
+            id,
+            program_id,
+            r#type,
+            internal_id,
+        } = f;
+
+        apply_equal_filter!(query, id, lmis_code_mapping::id);
+        apply_equal_filter!(query, program_id, lmis_code_mapping::program_id);
+        apply_equal_filter!(query, r#type, lmis_code_mapping::type_);
+        apply_equal_filter!(query, internal_id, lmis_code_mapping::internal_id);
+    }
+    query
+}
@@ -0,0 +1,126 @@
+use super::{lmis_code_mapping_row::lmis_code_mapping::dsl::*, program_row::program};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    StorageConnection, Upsert,
+};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    lmis_code_mapping (id) {
+        id -> Text,
+        program_id -> Text,
+        #[sql_name = "type"] type_ -> crate::db_diesel::lmis_code_mapping_row::LmisCodeMappingTypeMapping,
+        internal_id -> Text,
+        external_code -> Text,
+        deleted_datetime -> Nullable<Timestamp>,
+    }
+}
+
+joinable!(lmis_code_mapping -> program (program_id));
+allow_tables_to_appear_in_same_query!(lmis_code_mapping, program);
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum LmisCodeMappingType {
+    /// internal_id is the program id
+    #[default]
+    Program,
+    /// internal_id is the store id
+    Facility,
+    /// internal_id is the item id
+    Item,
+    /// internal_id is the R&R form line field, e.g. `initial_balance`
+    Field,
+}
+
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = lmis_code_mapping)]
+#[diesel(treat_none_as_null = true)]
+pub struct LmisCodeMappingRow {
+    pub id: String,
+    pub program_id: String,
+    #[diesel(column_name = type_)]
+    pub r#type: LmisCodeMappingType,
+    pub internal_id: String,
+    /// Code used for the record by the external LMIS
+    pub external_code: String,
+    pub deleted_datetime: Option<NaiveDateTime>,
+}
+
+pub struct LmisCodeMappingRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> LmisCodeMappingRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        LmisCodeMappingRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &LmisCodeMappingRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(lmis_code_mapping)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row.id.to_owned(), RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row_id: String,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::LmisCodeMapping,
+            record_id: row_id,
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        mapping_id: &str,
+    ) -> Result<Option<LmisCodeMappingRow>, RepositoryError> {
+        let result = lmis_code_mapping
+            .filter(id.eq(mapping_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn mark_deleted(&self, mapping_id: &str) -> Result<i64, RepositoryError> {
+        diesel::update(lmis_code_mapping.filter(id.eq(mapping_id)))
+            .set(deleted_datetime.eq(Some(chrono::Utc::now().naive_utc())))
+            .execute(self.connection.lock().connection())?;
+
+        // Upsert row action as this is a soft delete, not actual delete
+        self.insert_changelog(mapping_id.to_owned(), RowActionType::Upsert)
+    }
+}
+
+impl Upsert for LmisCodeMappingRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = LmisCodeMappingRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            LmisCodeMappingRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,103 @@
+use super::{login_lockout_row::login_lockout::dsl as login_lockout_dsl, StorageConnection};
+use crate::{repository_error::RepositoryError, Upsert};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+
+table! {
+    login_lockout (id) {
+        id -> Text,
+        identifier_type -> crate::db_diesel::login_lockout_row::LoginLockoutIdentifierTypeMapping,
+        identifier -> Text,
+        failed_attempts -> Integer,
+        last_failed_datetime -> Timestamp,
+        locked_until_datetime -> Nullable<Timestamp>,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum LoginLockoutIdentifierType {
+    #[default]
+    Username,
+    IpAddress,
+}
+
+/// Failed local login attempts for a username or a client ip address
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(table_name = login_lockout)]
+#[diesel(treat_none_as_null = true)]
+pub struct LoginLockoutRow {
+    pub id: String,
+    pub identifier_type: LoginLockoutIdentifierType,
+    /// Lower case username or the client ip address
+    pub identifier: String,
+    /// Consecutive failed attempts, reset after a successful login or once the counting window
+    /// has passed
+    pub failed_attempts: i32,
+    pub last_failed_datetime: NaiveDateTime,
+    pub locked_until_datetime: Option<NaiveDateTime>,
+}
+
+pub struct LoginLockoutRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> LoginLockoutRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        LoginLockoutRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &LoginLockoutRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(login_lockout_dsl::login_lockout)
+            .values(row)
+            .on_conflict(login_lockout_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<LoginLockoutRow>, RepositoryError> {
+        let result = login_lockout_dsl::login_lockout
+            .filter(login_lockout_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_one_by_identifier(
+        &self,
+        identifier_type: LoginLockoutIdentifierType,
+        identifier: &str,
+    ) -> Result<Option<LoginLockoutRow>, RepositoryError> {
+        let result = login_lockout_dsl::login_lockout
+            .filter(login_lockout_dsl::identifier_type.eq(identifier_type))
+            .filter(login_lockout_dsl::identifier.eq(identifier))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(login_lockout_dsl::login_lockout.filter(login_lockout_dsl::id.eq(id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+impl Upsert for LoginLockoutRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        LoginLockoutRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            LoginLockoutRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -3,7 +3,10 @@ use crate::repository_error::RepositoryError;
 pub mod activity_log;
 mod activity_log_row;
 pub mod adjustment;
+mod allocation_rule_row;
+mod api_key_row;
 pub mod assets;
+mod backorder_row;
 pub mod barcode;
 mod barcode_row;
 pub mod changelog;
@@ -19,6 +22,8 @@ pub mod contact_trace_row;
 mod context_row;
 pub mod currency;
 mod currency_row;
+mod cycle_count_schedule_row;
+mod database_diagnostics;
 pub mod demographic;
 pub mod demographic_indicator;
 pub mod demographic_indicator_row;
@@ -30,6 +35,8 @@ pub mod document;
 pub mod document_registry;
 mod document_registry_config;
 mod document_registry_row;
+mod drug_ingredient_row;
+mod drug_interaction_row;
 pub mod encounter;
 pub mod encounter_row;
 mod filter_restriction;
@@ -47,6 +54,7 @@ mod inventory_adjustment_reason_row;
 pub mod invoice;
 pub mod invoice_line;
 mod invoice_line_row;
+mod invoice_payment_row;
 mod invoice_row;
 pub mod item;
 mod item_link_row;
@@ -54,10 +62,13 @@ mod item_row;
 pub mod item_variant;
 pub mod key_value_store;
 pub mod ledger;
+pub mod lmis_code_mapping;
+mod lmis_code_mapping_row;
 pub mod location;
 pub mod location_movement;
 mod location_movement_row;
 mod location_row;
+mod login_lockout_row;
 pub mod master_list;
 pub mod master_list_line;
 mod master_list_line_row;
@@ -66,6 +77,7 @@ mod master_list_row;
 mod migration_fragment_log;
 pub mod name;
 mod name_link_row;
+mod name_price_category_row;
 pub mod name_property;
 pub mod name_property_row;
 mod name_row;
@@ -76,8 +88,14 @@ mod name_tag_row;
 mod number_row;
 mod patient;
 pub mod period;
+mod pick_list_line_row;
+mod pick_list_row;
+mod pick_path_location_row;
 pub mod plugin_data;
 mod plugin_data_row;
+mod prescription_line_direction_row;
+mod price_list_line_row;
+mod price_list_row;
 pub mod program_enrolment;
 mod program_enrolment_row;
 pub mod program_event;
@@ -89,6 +107,9 @@ pub mod property;
 pub mod property_row;
 pub mod reason_option;
 pub mod reason_option_row;
+mod recall_batch_row;
+mod recall_row;
+mod recall_stock_line_row;
 pub mod replenishment;
 pub mod report;
 mod report_query;
@@ -103,6 +124,9 @@ pub mod rnr_form_line_row;
 pub mod rnr_form_row;
 pub mod sensor;
 mod sensor_row;
+mod serial_number_movement_row;
+mod serial_number_row;
+mod stock_aggregate;
 pub mod stock_line;
 mod stock_line_row;
 pub mod stock_movement;
@@ -120,6 +144,7 @@ pub mod sync_file_reference;
 pub mod sync_file_reference_row;
 pub mod sync_log;
 mod sync_log_row;
+mod tax_rule_row;
 pub mod temperature_breach;
 pub mod temperature_breach_config;
 mod temperature_breach_config_row;
@@ -131,16 +156,22 @@ mod unit_row;
 mod user;
 pub mod user_permission;
 mod user_permission_row;
+mod user_pin_row;
 mod user_row;
 mod user_store_join_row;
 pub mod vaccination;
 pub mod vaccination_card;
 pub mod vaccination_row;
 pub mod vaccine_course;
+pub mod vaccine_open_vial;
+mod vaccine_open_vial_row;
 
 pub use activity_log_row::*;
 pub use adjustment::*;
+pub use allocation_rule_row::*;
+pub use api_key_row::*;
 pub use assets::*;
+pub use backorder_row::*;
 pub use barcode_row::*;
 pub use changelog::*;
 pub use clinician::*;
@@ -153,6 +184,8 @@ pub use consumption::*;
 pub use context_row::*;
 pub use currency::*;
 pub use currency_row::*;
+pub use cycle_count_schedule_row::*;
+pub use database_diagnostics::*;
 pub use demographic_indicator::*;
 pub use demographic_indicator_row::*;
 pub use demographic_projection_row::*;
@@ -161,6 +194,8 @@ pub use document::*;
 pub use document_registry::*;
 pub use document_registry_config::*;
 pub use document_registry_row::*;
+pub use drug_ingredient_row::*;
+pub use drug_interaction_row::*;
 pub use encounter::*;
 pub use encounter_row::*;
 pub use filter_sort_pagination::*;
@@ -173,13 +208,17 @@ pub use inventory_adjustment_reason_row::*;
 pub use invoice::*;
 pub use invoice_line::*;
 pub use invoice_line_row::*;
+pub use invoice_payment_row::*;
 pub use invoice_row::*;
 pub use item::*;
 pub use item_link_row::*;
 pub use item_row::*;
 pub use key_value_store::*;
+pub use lmis_code_mapping::*;
+pub use lmis_code_mapping_row::*;
 pub use location_movement_row::*;
 pub use location_row::*;
+pub use login_lockout_row::*;
 pub use master_list::*;
 pub use master_list_line::*;
 pub use master_list_line_row::*;
@@ -188,6 +227,7 @@ pub use master_list_row::*;
 pub(crate) use migration_fragment_log::*;
 pub use name::*;
 pub use name_link_row::*;
+pub use name_price_category_row::*;
 pub use name_property::*;
 pub use name_property_row::*;
 pub use name_row::*;
@@ -198,8 +238,14 @@ pub use name_tag_row::*;
 pub use number_row::*;
 pub use patient::*;
 pub use period::*;
+pub use pick_list_line_row::*;
+pub use pick_list_row::*;
+pub use pick_path_location_row::*;
 pub use plugin_data::*;
 pub use plugin_data_row::*;
+pub use prescription_line_direction_row::*;
+pub use price_list_line_row::*;
+pub use price_list_row::*;
 pub use program_enrolment::*;
 pub use program_enrolment_row::*;
 pub use program_event::*;
@@ -209,6 +255,9 @@ pub use program_indicator_row::*;
 pub use program_requisition::*;
 pub use property_row::*;
 pub use reason_option::*;
+pub use recall_batch_row::*;
+pub use recall_row::*;
+pub use recall_stock_line_row::*;
 pub use replenishment::*;
 pub use report::*;
 pub use report_query::*;
@@ -222,6 +271,9 @@ pub use rnr_form_line_row::*;
 pub use rnr_form_row::*;
 pub use sensor::*;
 pub use sensor_row::*;
+pub use serial_number_movement_row::*;
+pub use serial_number_row::*;
+pub use stock_aggregate::*;
 pub use stock_line::*;
 pub use stock_line_row::*;
 pub use stock_movement::*;
@@ -239,6 +291,7 @@ pub use sync_file_reference::*;
 pub use sync_file_reference_row::*;
 pub use sync_log::*;
 pub use sync_log_row::*;
+pub use tax_rule_row::*;
 pub use temperature_breach::*;
 pub use temperature_breach_config::*;
 pub use temperature_breach_config_row::*;
@@ -250,11 +303,14 @@ pub use unit_row::*;
 pub use user::*;
 pub use user_permission::*;
 pub use user_permission_row::*;
+pub use user_pin_row::*;
 pub use user_row::*;
 pub use user_store_join_row::*;
 pub use vaccination::*;
 pub use vaccination_card::*;
 pub use vaccination_row::*;
+pub use vaccine_open_vial::*;
+pub use vaccine_open_vial_row::*;
 
 use diesel::{
     prelude::*,
@@ -263,6 +319,7 @@ use diesel::{
     sql_query,
     sql_types::Text,
 };
+use util::metrics;
 
 #[cfg(not(feature = "postgres"))]
 pub type DBBackendConnection = SqliteConnection;
@@ -329,9 +386,16 @@ impl From<DieselError> for RepositoryError {
 fn get_connection(
     pool: &Pool<ConnectionManager<DBBackendConnection>>,
 ) -> Result<DBConnection, RepositoryError> {
-    pool.get().map_err(|error| RepositoryError::DBError {
-        msg: "Failed to open Connection".to_string(),
-        extra: format!("{:?}", error),
+    let start = std::time::Instant::now();
+    let result = pool.get();
+    metrics::DB_CONNECTION_WAIT.observe_since(&[], start);
+
+    result.map_err(|error| {
+        metrics::DB_CONNECTION_ERRORS.inc(&[]);
+        RepositoryError::DBError {
+            msg: "Failed to open Connection".to_string(),
+            extra: format!("{:?}", error),
+        }
     })
 }
 
@@ -0,0 +1,118 @@
+use super::{name_price_category_row::name_price_category::dsl::*, StorageConnection};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, Delete, RepositoryError,
+    RowActionType, Upsert,
+};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    name_price_category (name_id) {
+        name_id -> Text,
+        price_category -> Text,
+    }
+}
+
+/// Price category of a customer or patient (e.g. "INSURED", "EXEMPT"), used to select price lists
+/// and tax rules
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = name_price_category)]
+pub struct NamePriceCategoryRow {
+    pub name_id: String,
+    pub price_category: String,
+}
+
+pub struct NamePriceCategoryRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> NamePriceCategoryRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        NamePriceCategoryRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &NamePriceCategoryRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(name_price_category)
+            .values(row)
+            .on_conflict(name_id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    /// Identified by the name, a name has at most one price category
+    fn insert_changelog(
+        &self,
+        row: &NamePriceCategoryRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::NamePriceCategory,
+            record_id: row.name_id.clone(),
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_name_id(
+        &self,
+        for_name_id: &str,
+    ) -> Result<Option<NamePriceCategoryRow>, RepositoryError> {
+        let result = name_price_category
+            .filter(name_id.eq(for_name_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, for_name_id: &str) -> Result<Option<i64>, RepositoryError> {
+        let old_row = self.find_one_by_name_id(for_name_id)?;
+        let change_log_id = match old_row {
+            Some(old_row) => self.insert_changelog(&old_row, RowActionType::Delete)?,
+            None => {
+                return Ok(None);
+            }
+        };
+
+        diesel::delete(name_price_category.filter(name_id.eq(for_name_id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(Some(change_log_id))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NamePriceCategoryRowDelete(pub String);
+impl Delete for NamePriceCategoryRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        NamePriceCategoryRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            NamePriceCategoryRowRepository::new(con).find_one_by_name_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for NamePriceCategoryRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = NamePriceCategoryRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            NamePriceCategoryRowRepository::new(con).find_one_by_name_id(&self.name_id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -49,6 +49,18 @@ impl<'a> NameTagJoinRepository<'a> {
         Ok(result)
     }
 
+    pub fn find_many_by_name_id(
+        &self,
+        name_id: &str,
+    ) -> Result<Vec<NameTagJoinRow>, RepositoryError> {
+        let result = name_tag_join_dsl::name_tag_join
+            .inner_join(name_link::table)
+            .filter(name_link::name_id.eq(name_id))
+            .select(name_tag_join::all_columns)
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
     pub fn delete(&self, id: &str) -> Result<(), RepositoryError> {
         diesel::delete(name_tag_join_dsl::name_tag_join.filter(name_tag_join_dsl::id.eq(id)))
             .execute(self.connection.lock().connection())?;
@@ -39,6 +39,8 @@ pub enum NumberRowType {
     Prescription,
     SupplierReturn,
     CustomerReturn,
+    Receipt,
+    CreditNote,
     Program(String),
 }
 
@@ -56,6 +58,8 @@ impl fmt::Display for NumberRowType {
             NumberRowType::Prescription => write!(f, "PRESCRIPTION"),
             NumberRowType::SupplierReturn => write!(f, "SUPPLIER_RETURN"),
             NumberRowType::CustomerReturn => write!(f, "CUSTOMER_RETURN"),
+            NumberRowType::Receipt => write!(f, "RECEIPT"),
+            NumberRowType::CreditNote => write!(f, "CREDIT_NOTE"),
             NumberRowType::Program(custom_string) => write!(f, "PROGRAM_{}", custom_string),
         }
     }
@@ -76,6 +80,8 @@ impl TryFrom<String> for NumberRowType {
             "REPACK" => Ok(NumberRowType::Repack),
             "SUPPLIER_RETURN" => Ok(NumberRowType::SupplierReturn),
             "CUSTOMER_RETURN" => Ok(NumberRowType::CustomerReturn),
+            "RECEIPT" => Ok(NumberRowType::Receipt),
+            "CREDIT_NOTE" => Ok(NumberRowType::CreditNote),
             _ => match s.split_once('_') {
                 Some((prefix, custom_string)) => {
                     if prefix == "PROGRAM" {
@@ -246,6 +252,8 @@ mod number_row_mapping_test {
             NumberRowType::Program("EXAMPLE_TEST".to_string()),
             NumberRowType::SupplierReturn,
             NumberRowType::CustomerReturn,
+            NumberRowType::Receipt,
+            NumberRowType::CreditNote,
         ] {
             match number_row_type {
                 NumberRowType::InboundShipment => {
@@ -323,6 +331,14 @@ mod number_row_mapping_test {
                     NumberRowType::try_from(NumberRowType::CustomerReturn.to_string()).unwrap()
                         == NumberRowType::CustomerReturn
                 ),
+                NumberRowType::Receipt => assert!(
+                    NumberRowType::try_from(NumberRowType::Receipt.to_string()).unwrap()
+                        == NumberRowType::Receipt
+                ),
+                NumberRowType::CreditNote => assert!(
+                    NumberRowType::try_from(NumberRowType::CreditNote.to_string()).unwrap()
+                        == NumberRowType::CreditNote
+                ),
             }
         }
     }
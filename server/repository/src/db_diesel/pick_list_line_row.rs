@@ -0,0 +1,142 @@
+use super::{pick_list_line_row::pick_list_line::dsl::*, StorageConnection};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, PickListRowRepository,
+    RepositoryError, RowActionType, Upsert,
+};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    pick_list_line (id) {
+        id -> Text,
+        pick_list_id -> Text,
+        invoice_id -> Text,
+        invoice_line_id -> Text,
+        item_link_id -> Text,
+        stock_line_id -> Nullable<Text>,
+        location_id -> Nullable<Text>,
+        line_number -> Integer,
+        number_of_packs -> Double,
+        picked_number_of_packs -> Nullable<Double>,
+        picked_datetime -> Nullable<Timestamp>,
+    }
+}
+
+/// Packs of an allocated outbound shipment line to pick, an invoice line can have more than one
+/// pick list line when packs were added to it after a short pick
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = pick_list_line)]
+#[diesel(treat_none_as_null = true)]
+pub struct PickListLineRow {
+    pub id: String,
+    pub pick_list_id: String,
+    pub invoice_id: String,
+    pub invoice_line_id: String,
+    pub item_link_id: String,
+    pub stock_line_id: Option<String>,
+    pub location_id: Option<String>,
+    /// Position along the pick path
+    pub line_number: i32,
+    pub number_of_packs: f64,
+    /// Set when the picker confirms the line
+    pub picked_number_of_packs: Option<f64>,
+    pub picked_datetime: Option<NaiveDateTime>,
+}
+
+impl PickListLineRow {
+    pub fn is_confirmed(&self) -> bool {
+        self.picked_number_of_packs.is_some()
+    }
+}
+
+pub struct PickListLineRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> PickListLineRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        PickListLineRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &PickListLineRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(pick_list_line)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &PickListLineRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let pick_list = PickListRowRepository::new(self.connection)
+            .find_one_by_id(&row.pick_list_id)?
+            .ok_or(RepositoryError::NotFound)?;
+
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::PickListLine,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(pick_list.store_id),
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        line_id: &str,
+    ) -> Result<Option<PickListLineRow>, RepositoryError> {
+        let result = pick_list_line
+            .filter(id.eq(line_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// In pick path order
+    pub fn find_many_by_pick_list_id(
+        &self,
+        for_pick_list_id: &str,
+    ) -> Result<Vec<PickListLineRow>, RepositoryError> {
+        let result = pick_list_line
+            .filter(pick_list_id.eq(for_pick_list_id))
+            .order(line_number.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_invoice_ids(
+        &self,
+        invoice_ids: &[String],
+    ) -> Result<Vec<PickListLineRow>, RepositoryError> {
+        let result = pick_list_line
+            .filter(invoice_id.eq_any(invoice_ids))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for PickListLineRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = PickListLineRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PickListLineRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,131 @@
+use super::{pick_list_row::pick_list::dsl::*, StorageConnection};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    Upsert,
+};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    pick_list (id) {
+        id -> Text,
+        store_id -> Text,
+        status -> crate::db_diesel::pick_list_row::PickListStatusMapping,
+        created_datetime -> Timestamp,
+        completed_datetime -> Nullable<Timestamp>,
+        user_id -> Text,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PickListStatus {
+    #[default]
+    InProgress,
+    Completed,
+}
+
+/// Allocated lines of one or more outbound shipments to be picked together (a wave pick)
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = pick_list)]
+#[diesel(treat_none_as_null = true)]
+pub struct PickListRow {
+    pub id: String,
+    pub store_id: String,
+    pub status: PickListStatus,
+    pub created_datetime: NaiveDateTime,
+    pub completed_datetime: Option<NaiveDateTime>,
+    /// User who generated the pick list
+    pub user_id: String,
+}
+
+pub struct PickListRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> PickListRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        PickListRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &PickListRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(pick_list)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &PickListRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::PickList,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        pick_list_id: &str,
+    ) -> Result<Option<PickListRow>, RepositoryError> {
+        let result = pick_list
+            .filter(id.eq(pick_list_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_ids(&self, ids: &[String]) -> Result<Vec<PickListRow>, RepositoryError> {
+        let result = pick_list
+            .filter(id.eq_any(ids))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    /// Newest first
+    pub fn find_many_by_store_id(
+        &self,
+        store: &str,
+        status_filter: Option<PickListStatus>,
+    ) -> Result<Vec<PickListRow>, RepositoryError> {
+        let mut query = pick_list.filter(store_id.eq(store)).into_boxed();
+        if let Some(status_filter) = status_filter {
+            query = query.filter(status.eq(status_filter));
+        }
+        let result = query
+            .order(created_datetime.desc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for PickListRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = PickListRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PickListRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,137 @@
+use super::{pick_path_location_row::pick_path_location::dsl::*, StorageConnection};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, Delete, RepositoryError,
+    RowActionType, Upsert,
+};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    pick_path_location (id) {
+        id -> Text,
+        store_id -> Text,
+        location_id -> Text,
+        sort_order -> Integer,
+    }
+}
+
+/// Position of a location along the pick path of its store, locations that aren't on the path
+/// are picked last
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = pick_path_location)]
+pub struct PickPathLocationRow {
+    pub id: String,
+    pub store_id: String,
+    pub location_id: String,
+    pub sort_order: i32,
+}
+
+pub struct PickPathLocationRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> PickPathLocationRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        PickPathLocationRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &PickPathLocationRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(pick_path_location)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &PickPathLocationRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::PickPathLocation,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        row_id: &str,
+    ) -> Result<Option<PickPathLocationRow>, RepositoryError> {
+        let result = pick_path_location
+            .filter(id.eq(row_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// In pick path order
+    pub fn find_many_by_store_id(
+        &self,
+        store: &str,
+    ) -> Result<Vec<PickPathLocationRow>, RepositoryError> {
+        let result = pick_path_location
+            .filter(store_id.eq(store))
+            .order(sort_order.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, row_id: &str) -> Result<Option<i64>, RepositoryError> {
+        let Some(old_row) = self.find_one_by_id(row_id)? else {
+            return Ok(None);
+        };
+        let change_log_id = self.insert_changelog(&old_row, RowActionType::Delete)?;
+
+        diesel::delete(pick_path_location.filter(id.eq(row_id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(Some(change_log_id))
+    }
+
+    pub fn delete_by_store_id(&self, store: &str) -> Result<(), RepositoryError> {
+        for row in self.find_many_by_store_id(store)? {
+            self.delete(&row.id)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PickPathLocationRowDelete(pub String);
+impl Delete for PickPathLocationRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        PickPathLocationRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PickPathLocationRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for PickPathLocationRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = PickPathLocationRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PickPathLocationRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,193 @@
+use super::{
+    prescription_line_direction_row::prescription_line_direction::dsl as prescription_line_direction_dsl,
+    StorageConnection,
+};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, Delete, InvoiceLineRowRepository,
+    InvoiceRowRepository, RepositoryError, RowActionType, Upsert,
+};
+
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    prescription_line_direction (id) {
+        id -> Text,
+        invoice_line_id -> Text,
+        sig -> Nullable<Text>,
+        dose -> Nullable<Double>,
+        dose_unit -> Nullable<Text>,
+        frequency_per_day -> Nullable<Double>,
+        duration_days -> Nullable<Integer>,
+        route -> Nullable<crate::db_diesel::prescription_line_direction_row::AdministrationRouteMapping>,
+        directions -> Text,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AdministrationRoute {
+    Oral,
+    Sublingual,
+    Topical,
+    Inhaled,
+    Nasal,
+    Ophthalmic,
+    Otic,
+    Rectal,
+    Vaginal,
+    Intramuscular,
+    Intravenous,
+    Subcutaneous,
+}
+
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = prescription_line_direction)]
+#[diesel(treat_none_as_null = true)]
+pub struct PrescriptionLineDirectionRow {
+    pub id: String,
+    pub invoice_line_id: String,
+    /// Directions as entered by the prescriber, e.g. "1T BD x5/7"
+    pub sig: Option<String>,
+    /// Number of units (of the item) per administration
+    pub dose: Option<f64>,
+    /// Display unit of the dose, e.g. "tablet"
+    pub dose_unit: Option<String>,
+    /// None when taken as required (PRN)
+    pub frequency_per_day: Option<f64>,
+    pub duration_days: Option<i32>,
+    pub route: Option<AdministrationRoute>,
+    /// Full text directions, printed on the label
+    pub directions: String,
+}
+
+pub struct PrescriptionLineDirectionRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> PrescriptionLineDirectionRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        PrescriptionLineDirectionRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &PrescriptionLineDirectionRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(prescription_line_direction_dsl::prescription_line_direction)
+            .values(row)
+            .on_conflict(prescription_line_direction_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &PrescriptionLineDirectionRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        // Directions are synced with the store of the prescription
+        let invoice_line = InvoiceLineRowRepository::new(self.connection)
+            .find_one_by_id(&row.invoice_line_id)?
+            .ok_or(RepositoryError::NotFound)?;
+        let invoice = InvoiceRowRepository::new(self.connection)
+            .find_one_by_id(&invoice_line.invoice_id)?
+            .ok_or(RepositoryError::NotFound)?;
+
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::PrescriptionLineDirection,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(invoice.store_id),
+            name_link_id: Some(invoice.name_link_id),
+        };
+
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<PrescriptionLineDirectionRow>, RepositoryError> {
+        let result = prescription_line_direction_dsl::prescription_line_direction
+            .filter(prescription_line_direction_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_one_by_invoice_line_id(
+        &self,
+        invoice_line_id: &str,
+    ) -> Result<Option<PrescriptionLineDirectionRow>, RepositoryError> {
+        let result = prescription_line_direction_dsl::prescription_line_direction
+            .filter(prescription_line_direction_dsl::invoice_line_id.eq(invoice_line_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_invoice_line_ids(
+        &self,
+        invoice_line_ids: &[String],
+    ) -> Result<Vec<PrescriptionLineDirectionRow>, RepositoryError> {
+        let result = prescription_line_direction_dsl::prescription_line_direction
+            .filter(prescription_line_direction_dsl::invoice_line_id.eq_any(invoice_line_ids))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<Option<i64>, RepositoryError> {
+        let Some(old_row) = self.find_one_by_id(id)? else {
+            return Ok(None);
+        };
+        let change_log_id = self.insert_changelog(&old_row, RowActionType::Delete)?;
+
+        diesel::delete(
+            prescription_line_direction_dsl::prescription_line_direction
+                .filter(prescription_line_direction_dsl::id.eq(id)),
+        )
+        .execute(self.connection.lock().connection())?;
+        Ok(Some(change_log_id))
+    }
+
+    pub fn delete_by_invoice_line_id(&self, invoice_line_id: &str) -> Result<(), RepositoryError> {
+        if let Some(row) = self.find_one_by_invoice_line_id(invoice_line_id)? {
+            self.delete(&row.id)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PrescriptionLineDirectionRowDelete(pub String);
+impl Delete for PrescriptionLineDirectionRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        PrescriptionLineDirectionRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PrescriptionLineDirectionRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for PrescriptionLineDirectionRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let change_log_id = PrescriptionLineDirectionRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(change_log_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PrescriptionLineDirectionRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,158 @@
+use super::{price_list_line_row::price_list_line::dsl::*, StorageConnection};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, Delete, RepositoryError,
+    RowActionType, Upsert,
+};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    price_list_line (id) {
+        id -> Text,
+        price_list_id -> Text,
+        item_id -> Text,
+        min_quantity -> Double,
+        price_per_unit -> Nullable<Double>,
+        discount_percentage -> Nullable<Double>,
+    }
+}
+
+/// Price of an item from `min_quantity` units, an item can have a line per quantity break
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = price_list_line)]
+#[diesel(treat_none_as_null = true)]
+pub struct PriceListLineRow {
+    pub id: String,
+    pub price_list_id: String,
+    pub item_id: String,
+    /// Number of units on the line for this price to apply
+    pub min_quantity: f64,
+    /// When not set the discount is applied to the default price list price
+    pub price_per_unit: Option<f64>,
+    pub discount_percentage: Option<f64>,
+}
+
+pub struct PriceListLineRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> PriceListLineRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        PriceListLineRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &PriceListLineRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(price_list_line)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &PriceListLineRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::PriceListLine,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        line_id: &str,
+    ) -> Result<Option<PriceListLineRow>, RepositoryError> {
+        let result = price_list_line
+            .filter(id.eq(line_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_price_list_ids(
+        &self,
+        price_list_ids: &[String],
+    ) -> Result<Vec<PriceListLineRow>, RepositoryError> {
+        let result = price_list_line
+            .filter(price_list_id.eq_any(price_list_ids))
+            .order((item_id.asc(), min_quantity.asc()))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_item_id(
+        &self,
+        for_item_id: &str,
+        price_list_ids: &[String],
+    ) -> Result<Vec<PriceListLineRow>, RepositoryError> {
+        let result = price_list_line
+            .filter(item_id.eq(for_item_id))
+            .filter(price_list_id.eq_any(price_list_ids))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, line_id: &str) -> Result<Option<i64>, RepositoryError> {
+        let old_row = self.find_one_by_id(line_id)?;
+        let change_log_id = match old_row {
+            Some(old_row) => self.insert_changelog(&old_row, RowActionType::Delete)?,
+            None => {
+                return Ok(None);
+            }
+        };
+
+        diesel::delete(price_list_line.filter(id.eq(line_id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(Some(change_log_id))
+    }
+
+    pub fn delete_by_price_list_id(&self, for_price_list_id: &str) -> Result<(), RepositoryError> {
+        let lines = self.find_many_by_price_list_ids(&[for_price_list_id.to_string()])?;
+        for line in lines {
+            self.delete(&line.id)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PriceListLineRowDelete(pub String);
+impl Delete for PriceListLineRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        PriceListLineRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PriceListLineRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for PriceListLineRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = PriceListLineRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PriceListLineRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,157 @@
+use super::{price_list_row::price_list::dsl::*, StorageConnection};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, Delete, RepositoryError,
+    RowActionType, Upsert,
+};
+
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    price_list (id) {
+        id -> Text,
+        name -> Text,
+        store_id -> Nullable<Text>,
+        name_id -> Nullable<Text>,
+        name_tag_id -> Nullable<Text>,
+        price_category -> Nullable<Text>,
+        valid_from -> Nullable<Date>,
+        valid_to -> Nullable<Date>,
+        priority -> Integer,
+        is_active -> Bool,
+    }
+}
+
+/// Prices for customers matching all of the set targets (name, name tag and price category),
+/// a list without targets applies to everyone
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = price_list)]
+#[diesel(treat_none_as_null = true)]
+pub struct PriceListRow {
+    pub id: String,
+    pub name: String,
+    /// None when the list applies to all stores
+    pub store_id: Option<String>,
+    pub name_id: Option<String>,
+    pub name_tag_id: Option<String>,
+    /// Matched against the customer's `NamePriceCategoryRow`, e.g. "INSURED"
+    pub price_category: Option<String>,
+    pub valid_from: Option<NaiveDate>,
+    /// Inclusive
+    pub valid_to: Option<NaiveDate>,
+    /// Higher priority wins between lists with the same targets
+    pub priority: i32,
+    pub is_active: bool,
+}
+
+impl PriceListRow {
+    pub fn is_valid_on(&self, date: NaiveDate) -> bool {
+        self.valid_from.is_none_or(|from| from <= date) && self.valid_to.is_none_or(|to| to >= date)
+    }
+}
+
+pub struct PriceListRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> PriceListRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        PriceListRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &PriceListRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(price_list)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &PriceListRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::PriceList,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        price_list_id: &str,
+    ) -> Result<Option<PriceListRow>, RepositoryError> {
+        let result = price_list
+            .filter(id.eq(price_list_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Lists for the store, including lists that apply to all stores
+    pub fn find_many_by_store_id(
+        &self,
+        for_store_id: &str,
+    ) -> Result<Vec<PriceListRow>, RepositoryError> {
+        let result = price_list
+            .filter(store_id.eq(for_store_id).or(store_id.is_null()))
+            .order((priority.desc(), name.asc()))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, price_list_id: &str) -> Result<Option<i64>, RepositoryError> {
+        let old_row = self.find_one_by_id(price_list_id)?;
+        let change_log_id = match old_row {
+            Some(old_row) => self.insert_changelog(&old_row, RowActionType::Delete)?,
+            None => {
+                return Ok(None);
+            }
+        };
+
+        diesel::delete(price_list.filter(id.eq(price_list_id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(Some(change_log_id))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PriceListRowDelete(pub String);
+impl Delete for PriceListRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        PriceListRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PriceListRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for PriceListRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = PriceListRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            PriceListRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
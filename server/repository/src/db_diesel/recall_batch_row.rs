@@ -0,0 +1,99 @@
+use super::{recall_batch_row::recall_batch::dsl::*, StorageConnection};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    Upsert,
+};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    recall_batch (id) {
+        id -> Text,
+        recall_id -> Text,
+        batch -> Text,
+    }
+}
+
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = recall_batch)]
+pub struct RecallBatchRow {
+    pub id: String,
+    pub recall_id: String,
+    pub batch: String,
+}
+
+pub struct RecallBatchRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> RecallBatchRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        RecallBatchRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &RecallBatchRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(recall_batch)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &RecallBatchRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::RecallBatch,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        recall_batch_id: &str,
+    ) -> Result<Option<RecallBatchRow>, RepositoryError> {
+        let result = recall_batch
+            .filter(id.eq(recall_batch_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_recall_id(
+        &self,
+        for_recall_id: &str,
+    ) -> Result<Vec<RecallBatchRow>, RepositoryError> {
+        let result = recall_batch
+            .filter(recall_id.eq(for_recall_id))
+            .order(batch.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for RecallBatchRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = RecallBatchRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            RecallBatchRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,115 @@
+use super::{recall_row::recall::dsl::*, StorageConnection};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    Upsert,
+};
+
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    recall (id) {
+        id -> Text,
+        item_link_id -> Text,
+        reason -> Text,
+        recall_date -> Date,
+        created_datetime -> Timestamp,
+        store_id -> Text,
+        user_id -> Text,
+    }
+}
+
+/// Manufacturer recall of one or more batches of an item, applies to every store on the site
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = recall)]
+pub struct RecallRow {
+    pub id: String,
+    pub item_link_id: String,
+    pub reason: String,
+    /// Date of the manufacturer's recall notice
+    pub recall_date: NaiveDate,
+    pub created_datetime: NaiveDateTime,
+    /// Store the recall was entered in
+    pub store_id: String,
+    pub user_id: String,
+}
+
+pub struct RecallRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> RecallRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        RecallRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &RecallRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(recall)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &RecallRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::Recall,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(&self, recall_id: &str) -> Result<Option<RecallRow>, RepositoryError> {
+        let result = recall
+            .filter(id.eq(recall_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Newest first
+    pub fn find_all(&self) -> Result<Vec<RecallRow>, RepositoryError> {
+        let result = recall
+            .order(created_datetime.desc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_item_link_ids(
+        &self,
+        item_link_ids: &[String],
+    ) -> Result<Vec<RecallRow>, RepositoryError> {
+        let result = recall
+            .filter(item_link_id.eq_any(item_link_ids))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for RecallRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = RecallRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            RecallRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,115 @@
+use super::{recall_stock_line_row::recall_stock_line::dsl::*, StorageConnection};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    Upsert,
+};
+
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    recall_stock_line (id) {
+        id -> Text,
+        recall_id -> Text,
+        store_id -> Text,
+        stock_line_id -> Text,
+        quarantined_number_of_packs -> Double,
+    }
+}
+
+/// Stock line put on hold by a recall
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = recall_stock_line)]
+pub struct RecallStockLineRow {
+    pub id: String,
+    pub recall_id: String,
+    pub store_id: String,
+    pub stock_line_id: String,
+    /// Total packs of the stock line when the recall was entered
+    pub quarantined_number_of_packs: f64,
+}
+
+pub struct RecallStockLineRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> RecallStockLineRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        RecallStockLineRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &RecallStockLineRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(recall_stock_line)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &RecallStockLineRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::RecallStockLine,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        recall_stock_line_id: &str,
+    ) -> Result<Option<RecallStockLineRow>, RepositoryError> {
+        let result = recall_stock_line
+            .filter(id.eq(recall_stock_line_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_recall_id(
+        &self,
+        for_recall_id: &str,
+    ) -> Result<Vec<RecallStockLineRow>, RepositoryError> {
+        let result = recall_stock_line
+            .filter(recall_id.eq(for_recall_id))
+            .order(store_id.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_stock_line_id(
+        &self,
+        for_stock_line_id: &str,
+    ) -> Result<Vec<RecallStockLineRow>, RepositoryError> {
+        let result = recall_stock_line
+            .filter(stock_line_id.eq(for_stock_line_id))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+impl Upsert for RecallStockLineRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = RecallStockLineRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            RecallStockLineRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
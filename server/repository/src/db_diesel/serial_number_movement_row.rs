@@ -0,0 +1,179 @@
+use super::{serial_number_movement_row::serial_number_movement::dsl::*, StorageConnection};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, Delete, RepositoryError,
+    RowActionType, Upsert,
+};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    serial_number_movement (id) {
+        id -> Text,
+        serial_number_id -> Text,
+        store_id -> Text,
+        #[sql_name = "type"] type_ -> crate::db_diesel::serial_number_movement_row::SerialNumberMovementTypeMapping,
+        invoice_id -> Text,
+        invoice_line_id -> Text,
+        datetime -> Timestamp,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SerialNumberMovementType {
+    #[default]
+    Received,
+    Issued,
+}
+
+/// Receipt or issue of a serialised unit, used to trace where the unit went
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = serial_number_movement)]
+pub struct SerialNumberMovementRow {
+    pub id: String,
+    pub serial_number_id: String,
+    pub store_id: String,
+    #[diesel(column_name = type_)]
+    pub r#type: SerialNumberMovementType,
+    pub invoice_id: String,
+    pub invoice_line_id: String,
+    pub datetime: NaiveDateTime,
+}
+
+pub struct SerialNumberMovementRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SerialNumberMovementRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SerialNumberMovementRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &SerialNumberMovementRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(serial_number_movement)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &SerialNumberMovementRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::SerialNumberMovement,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        movement_id: &str,
+    ) -> Result<Option<SerialNumberMovementRow>, RepositoryError> {
+        let result = serial_number_movement
+            .filter(id.eq(movement_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Oldest first
+    pub fn find_many_by_serial_number_ids(
+        &self,
+        serial_number_ids: &[String],
+    ) -> Result<Vec<SerialNumberMovementRow>, RepositoryError> {
+        let result = serial_number_movement
+            .filter(serial_number_id.eq_any(serial_number_ids))
+            .order(datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, movement_id: &str) -> Result<Option<i64>, RepositoryError> {
+        let old_row = self.find_one_by_id(movement_id)?;
+        let change_log_id = match old_row {
+            Some(old_row) => self.insert_changelog(&old_row, RowActionType::Delete)?,
+            None => {
+                return Ok(None);
+            }
+        };
+
+        diesel::delete(serial_number_movement.filter(id.eq(movement_id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(Some(change_log_id))
+    }
+
+    pub fn delete_by_serial_number_id(
+        &self,
+        for_serial_number_id: &str,
+    ) -> Result<(), RepositoryError> {
+        let movements: Vec<SerialNumberMovementRow> = serial_number_movement
+            .filter(serial_number_id.eq(for_serial_number_id))
+            .load(self.connection.lock().connection())?;
+        for movement in movements {
+            self.delete(&movement.id)?;
+        }
+        Ok(())
+    }
+
+    /// Removes the issue of the unit when it is no longer picked on the invoice line
+    pub fn delete_by_serial_number_and_invoice_line(
+        &self,
+        for_serial_number_id: &str,
+        for_invoice_line_id: &str,
+    ) -> Result<(), RepositoryError> {
+        let movements: Vec<SerialNumberMovementRow> = serial_number_movement
+            .filter(serial_number_id.eq(for_serial_number_id))
+            .filter(invoice_line_id.eq(for_invoice_line_id))
+            .load(self.connection.lock().connection())?;
+        for movement in movements {
+            self.delete(&movement.id)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SerialNumberMovementRowDelete(pub String);
+impl Delete for SerialNumberMovementRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        SerialNumberMovementRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            SerialNumberMovementRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for SerialNumberMovementRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = SerialNumberMovementRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            SerialNumberMovementRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
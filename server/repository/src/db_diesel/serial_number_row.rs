@@ -0,0 +1,194 @@
+use super::{serial_number_row::serial_number::dsl::*, StorageConnection};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, Delete, RepositoryError,
+    RowActionType, Upsert,
+};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+
+table! {
+    serial_number (id) {
+        id -> Text,
+        store_id -> Text,
+        item_link_id -> Text,
+        serial -> Text,
+        gtin -> Nullable<Text>,
+        stock_line_id -> Text,
+        status -> crate::db_diesel::serial_number_row::SerialNumberStatusMapping,
+        received_invoice_line_id -> Text,
+        issued_invoice_line_id -> Nullable<Text>,
+        issued_name_link_id -> Nullable<Text>,
+        created_datetime -> Timestamp,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SerialNumberStatus {
+    #[default]
+    InStock,
+    /// Picked on an outbound shipment or prescription line
+    Issued,
+}
+
+/// A single unit of a serialised item, scanned on receipt and carried on the stock line it was
+/// received into
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = serial_number)]
+#[diesel(treat_none_as_null = true)]
+pub struct SerialNumberRow {
+    pub id: String,
+    pub store_id: String,
+    pub item_link_id: String,
+    pub serial: String,
+    pub gtin: Option<String>,
+    pub stock_line_id: String,
+    pub status: SerialNumberStatus,
+    pub received_invoice_line_id: String,
+    pub issued_invoice_line_id: Option<String>,
+    /// Other party of the invoice the unit was issued on, the unit is synced to the site of the
+    /// receiving store so the serial number can be carried to the transferred shipment
+    pub issued_name_link_id: Option<String>,
+    pub created_datetime: NaiveDateTime,
+}
+
+pub struct SerialNumberRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SerialNumberRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SerialNumberRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &SerialNumberRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(serial_number)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &SerialNumberRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::SerialNumber,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: row.issued_name_link_id.clone(),
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        serial_number_id: &str,
+    ) -> Result<Option<SerialNumberRow>, RepositoryError> {
+        let result = serial_number
+            .filter(id.eq(serial_number_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_stock_line_id(
+        &self,
+        for_stock_line_id: &str,
+    ) -> Result<Vec<SerialNumberRow>, RepositoryError> {
+        let result = serial_number
+            .filter(stock_line_id.eq(for_stock_line_id))
+            .order(serial.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_received_invoice_line_id(
+        &self,
+        invoice_line_id: &str,
+    ) -> Result<Vec<SerialNumberRow>, RepositoryError> {
+        let result = serial_number
+            .filter(received_invoice_line_id.eq(invoice_line_id))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_issued_invoice_line_id(
+        &self,
+        invoice_line_id: &str,
+    ) -> Result<Vec<SerialNumberRow>, RepositoryError> {
+        let result = serial_number
+            .filter(issued_invoice_line_id.eq(invoice_line_id))
+            .order(serial.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, serial_number_id: &str) -> Result<Option<i64>, RepositoryError> {
+        let old_row = self.find_one_by_id(serial_number_id)?;
+        let change_log_id = match old_row {
+            Some(old_row) => self.insert_changelog(&old_row, RowActionType::Delete)?,
+            None => {
+                return Ok(None);
+            }
+        };
+
+        diesel::delete(serial_number.filter(id.eq(serial_number_id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(Some(change_log_id))
+    }
+
+    /// Units with the serial number in every store, oldest receipt first
+    pub fn find_many_by_serial_number(
+        &self,
+        for_serial: &str,
+    ) -> Result<Vec<SerialNumberRow>, RepositoryError> {
+        let result = serial_number
+            .filter(serial.eq(for_serial))
+            .order(created_datetime.asc())
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SerialNumberRowDelete(pub String);
+impl Delete for SerialNumberRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        SerialNumberRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            SerialNumberRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for SerialNumberRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = SerialNumberRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            SerialNumberRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -0,0 +1,439 @@
+use super::{
+    invoice_line_row::invoice_line::dsl as invoice_line_dsl,
+    invoice_row::invoice::dsl as invoice_dsl, item_link_row::item_link,
+    stock_line_row::stock_line::dsl as stock_line_dsl, StorageConnection,
+};
+
+use crate::{
+    InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineType, InvoiceRow, InvoiceRowRepository,
+    InvoiceType, RepositoryError,
+};
+
+use chrono::{Duration, NaiveDate};
+use diesel::{dsl::sum, prelude::*, sql_query};
+
+use self::{
+    consumption_aggregate::dsl as consumption_aggregate_dsl,
+    stock_on_hand_aggregate::dsl as stock_on_hand_aggregate_dsl,
+};
+
+// Aggregate tables are maintained by the row repositories of the underlying tables (stock_line,
+// invoice_line and invoice), they are not synced and don't have changelog entries.
+// They are keyed by item_link_id so item merges don't require them to be updated.
+
+table! {
+    stock_on_hand_aggregate (id) {
+        id -> Text,
+        item_link_id -> Text,
+        store_id -> Text,
+        available_stock_on_hand -> Double,
+        total_stock_on_hand -> Double,
+    }
+}
+
+table! {
+    consumption_aggregate (id) {
+        id -> Text,
+        item_link_id -> Text,
+        store_id -> Text,
+        date -> Date,
+        quantity -> Double,
+    }
+}
+
+joinable!(stock_on_hand_aggregate -> item_link (item_link_id));
+joinable!(consumption_aggregate -> item_link (item_link_id));
+allow_tables_to_appear_in_same_query!(stock_on_hand_aggregate, item_link);
+allow_tables_to_appear_in_same_query!(consumption_aggregate, item_link);
+
+/// Current stock on hand of an item link in a store, for stock lines with positive packs
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset, Default)]
+#[diesel(table_name = stock_on_hand_aggregate)]
+pub struct StockOnHandAggregateRow {
+    pub id: String,
+    pub item_link_id: String,
+    pub store_id: String,
+    pub available_stock_on_hand: f64,
+    pub total_stock_on_hand: f64,
+}
+
+/// Quantity issued by outbound shipments and prescriptions picked on a given day
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset)]
+#[diesel(table_name = consumption_aggregate)]
+pub struct ConsumptionAggregateRow {
+    pub id: String,
+    pub item_link_id: String,
+    pub store_id: String,
+    pub date: NaiveDate,
+    pub quantity: f64,
+}
+
+const CONSUMPTION_INVOICE_TYPES: [InvoiceType; 2] =
+    [InvoiceType::OutboundShipment, InvoiceType::Prescription];
+
+// Also used by the migration that adds the aggregate tables
+pub(crate) const REBUILD_STOCK_ON_HAND: &str = r#"
+    INSERT INTO stock_on_hand_aggregate
+        SELECT
+            item_link_id || '_' || store_id,
+            item_link_id,
+            store_id,
+            SUM(pack_size * available_number_of_packs),
+            SUM(pack_size * total_number_of_packs)
+        FROM stock_line
+        WHERE available_number_of_packs > 0 OR total_number_of_packs > 0
+        GROUP BY item_link_id, store_id;
+"#;
+
+pub(crate) const REBUILD_CONSUMPTION: &str = r#"
+    INSERT INTO consumption_aggregate
+        SELECT
+            invoice_line.item_link_id || '_' || invoice.store_id || '_' || date(invoice.picked_datetime),
+            invoice_line.item_link_id,
+            invoice.store_id,
+            date(invoice.picked_datetime),
+            SUM(invoice_line.number_of_packs * invoice_line.pack_size)
+        FROM invoice_line
+        JOIN invoice ON invoice.id = invoice_line.invoice_id
+        WHERE invoice.type IN ('OUTBOUND_SHIPMENT', 'PRESCRIPTION')
+            AND invoice.picked_datetime IS NOT NULL
+            AND invoice_line.number_of_packs > 0
+            AND invoice_line.type IN ('STOCK_IN', 'STOCK_OUT')
+        GROUP BY invoice_line.item_link_id, invoice.store_id, date(invoice.picked_datetime);
+"#;
+
+/// Store and picked date an invoice contributes consumption to
+fn consumption_key(invoice: &InvoiceRow) -> Option<(String, NaiveDate)> {
+    if !CONSUMPTION_INVOICE_TYPES.contains(&invoice.r#type) {
+        return None;
+    }
+    let picked_datetime = invoice.picked_datetime?;
+    Some((invoice.store_id.clone(), picked_datetime.date()))
+}
+
+pub struct StockAggregateRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> StockAggregateRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        StockAggregateRepository { connection }
+    }
+
+    /// Recalculates stock on hand of an item link in a store from its stock lines
+    pub fn refresh_stock_on_hand(
+        &self,
+        item_link_id: &str,
+        store_id: &str,
+    ) -> Result<(), RepositoryError> {
+        let id = format!("{item_link_id}_{store_id}");
+
+        let (available_stock_on_hand, total_stock_on_hand): (Option<f64>, Option<f64>) =
+            stock_line_dsl::stock_line
+                .filter(stock_line_dsl::item_link_id.eq(item_link_id))
+                .filter(stock_line_dsl::store_id.eq(store_id))
+                .filter(
+                    stock_line_dsl::available_number_of_packs
+                        .gt(0.0)
+                        .or(stock_line_dsl::total_number_of_packs.gt(0.0)),
+                )
+                .select((
+                    sum(stock_line_dsl::pack_size * stock_line_dsl::available_number_of_packs),
+                    sum(stock_line_dsl::pack_size * stock_line_dsl::total_number_of_packs),
+                ))
+                .first(self.connection.lock().connection())?;
+
+        let (Some(available_stock_on_hand), Some(total_stock_on_hand)) =
+            (available_stock_on_hand, total_stock_on_hand)
+        else {
+            diesel::delete(stock_on_hand_aggregate_dsl::stock_on_hand_aggregate)
+                .filter(stock_on_hand_aggregate_dsl::id.eq(id))
+                .execute(self.connection.lock().connection())?;
+            return Ok(());
+        };
+
+        let row = StockOnHandAggregateRow {
+            id,
+            item_link_id: item_link_id.to_string(),
+            store_id: store_id.to_string(),
+            available_stock_on_hand,
+            total_stock_on_hand,
+        };
+        diesel::insert_into(stock_on_hand_aggregate_dsl::stock_on_hand_aggregate)
+            .values(&row)
+            .on_conflict(stock_on_hand_aggregate_dsl::id)
+            .do_update()
+            .set(&row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    /// Recalculates consumption of an item link in a store on a given day from picked outbound
+    /// shipment and prescription lines
+    pub fn refresh_consumption(
+        &self,
+        item_link_id: &str,
+        store_id: &str,
+        date: NaiveDate,
+    ) -> Result<(), RepositoryError> {
+        let id = format!("{item_link_id}_{store_id}_{date}");
+        let start_of_day = date.and_hms_opt(0, 0, 0).unwrap();
+
+        let quantity: Option<f64> = invoice_line_dsl::invoice_line
+            .inner_join(invoice_dsl::invoice)
+            .filter(invoice_line_dsl::item_link_id.eq(item_link_id))
+            .filter(invoice_dsl::store_id.eq(store_id))
+            .filter(invoice_dsl::type_.eq_any(CONSUMPTION_INVOICE_TYPES))
+            .filter(invoice_dsl::picked_datetime.ge(start_of_day))
+            .filter(invoice_dsl::picked_datetime.lt(start_of_day + Duration::days(1)))
+            .filter(invoice_line_dsl::number_of_packs.gt(0.0))
+            .filter(
+                invoice_line_dsl::type_
+                    .eq_any([InvoiceLineType::StockIn, InvoiceLineType::StockOut]),
+            )
+            .select(sum(
+                invoice_line_dsl::number_of_packs * invoice_line_dsl::pack_size
+            ))
+            .first(self.connection.lock().connection())?;
+
+        let Some(quantity) = quantity else {
+            diesel::delete(consumption_aggregate_dsl::consumption_aggregate)
+                .filter(consumption_aggregate_dsl::id.eq(id))
+                .execute(self.connection.lock().connection())?;
+            return Ok(());
+        };
+
+        let row = ConsumptionAggregateRow {
+            id,
+            item_link_id: item_link_id.to_string(),
+            store_id: store_id.to_string(),
+            date,
+            quantity,
+        };
+        diesel::insert_into(consumption_aggregate_dsl::consumption_aggregate)
+            .values(&row)
+            .on_conflict(consumption_aggregate_dsl::id)
+            .do_update()
+            .set(&row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    /// Refreshes consumption the invoice line contributes to (if any)
+    pub fn refresh_invoice_line_consumption(
+        &self,
+        line: &InvoiceLineRow,
+    ) -> Result<(), RepositoryError> {
+        let invoice =
+            InvoiceRowRepository::new(self.connection).find_one_by_id(&line.invoice_id)?;
+        match invoice.as_ref().and_then(consumption_key) {
+            Some((store_id, date)) => self.refresh_consumption(&line.item_link_id, &store_id, date),
+            None => Ok(()),
+        }
+    }
+
+    /// Refreshes consumption of all lines of an invoice, when its type, store or picked date
+    /// changed between `old_invoice` and `new_invoice`
+    pub fn refresh_invoice_consumption(
+        &self,
+        old_invoice: Option<&InvoiceRow>,
+        new_invoice: &InvoiceRow,
+    ) -> Result<(), RepositoryError> {
+        let old_key = old_invoice.and_then(consumption_key);
+        let new_key = consumption_key(new_invoice);
+        if old_key == new_key {
+            return Ok(());
+        }
+
+        let mut item_link_ids: Vec<String> = InvoiceLineRowRepository::new(self.connection)
+            .find_many_by_invoice_id(&new_invoice.id)?
+            .into_iter()
+            .map(|line| line.item_link_id)
+            .collect();
+        item_link_ids.sort();
+        item_link_ids.dedup();
+
+        for (store_id, date) in old_key.into_iter().chain(new_key) {
+            for item_link_id in item_link_ids.iter() {
+                self.refresh_consumption(item_link_id, &store_id, date)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Recalculates all aggregates from the underlying tables, e.g. after bulk updates that bypass
+    /// the row repositories
+    pub fn rebuild(&self) -> Result<(), RepositoryError> {
+        let mut connection = self.connection.lock();
+        diesel::delete(stock_on_hand_aggregate_dsl::stock_on_hand_aggregate)
+            .execute(connection.connection())?;
+        diesel::delete(consumption_aggregate_dsl::consumption_aggregate)
+            .execute(connection.connection())?;
+        sql_query(REBUILD_STOCK_ON_HAND).execute(connection.connection())?;
+        sql_query(REBUILD_CONSUMPTION).execute(connection.connection())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use chrono::{Duration, NaiveDate};
+    use diesel::prelude::*;
+
+    use crate::{
+        consumption::consumption::dsl as consumption_view_dsl,
+        mock::{
+            mock_outbound_shipment_a, mock_outbound_shipment_a_invoice_lines, mock_stock_line_a,
+            MockDataInserts,
+        },
+        stock_on_hand::stock_on_hand::dsl as stock_on_hand_view_dsl,
+        test_db, ConsumptionRepository, InvoiceLineRowRepository, InvoiceRowRepository,
+        StockAggregateRepository, StockLineRow, StockLineRowRepository, StockOnHandRepository,
+        StorageConnection,
+    };
+
+    type Totals<K> = BTreeMap<K, f64>;
+
+    fn assert_totals_eq<K: Ord + std::fmt::Debug>(aggregate: Totals<K>, view: Totals<K>) {
+        // Ignore zero totals, views include them for all item and store combinations
+        let non_zero = |totals: Totals<K>| -> Vec<(K, f64)> {
+            totals
+                .into_iter()
+                .filter(|(_, total)| total.abs() > f64::EPSILON)
+                .collect()
+        };
+        let (aggregate, view) = (non_zero(aggregate), non_zero(view));
+        assert_eq!(
+            aggregate.len(),
+            view.len(),
+            "{:#?} != {:#?}",
+            aggregate,
+            view
+        );
+        for ((aggregate_key, aggregate_total), (view_key, view_total)) in
+            aggregate.iter().zip(view.iter())
+        {
+            assert_eq!(aggregate_key, view_key);
+            assert!(
+                (aggregate_total - view_total).abs() < 1e-6,
+                "{:?}: {} != {}",
+                aggregate_key,
+                aggregate_total,
+                view_total
+            );
+        }
+    }
+
+    fn assert_consistent_with_views(connection: &StorageConnection) {
+        // Stock on hand
+        let mut aggregate = Totals::new();
+        for row in StockOnHandRepository::new(connection).query(None).unwrap() {
+            aggregate.insert(
+                (row.item_id.clone(), row.store_id.clone(), "available"),
+                row.available_stock_on_hand,
+            );
+            aggregate.insert(
+                (row.item_id, row.store_id, "total"),
+                row.total_stock_on_hand,
+            );
+        }
+
+        let view_rows = stock_on_hand_view_dsl::stock_on_hand
+            .select((
+                stock_on_hand_view_dsl::item_id,
+                stock_on_hand_view_dsl::store_id,
+                stock_on_hand_view_dsl::available_stock_on_hand,
+                stock_on_hand_view_dsl::total_stock_on_hand,
+            ))
+            .load::<(String, String, f64, f64)>(connection.lock().connection())
+            .unwrap();
+        let mut view = Totals::new();
+        for (item_id, store_id, available, total) in view_rows {
+            view.insert((item_id.clone(), store_id.clone(), "available"), available);
+            view.insert((item_id, store_id, "total"), total);
+        }
+        assert_totals_eq(aggregate, view);
+
+        // Consumption, the view has a row per invoice line
+        let mut aggregate = Totals::new();
+        for row in ConsumptionRepository::new(connection).query(None).unwrap() {
+            *aggregate
+                .entry((row.item_id, row.store_id, row.date))
+                .or_default() += row.quantity;
+        }
+
+        let view_rows = consumption_view_dsl::consumption
+            .select((
+                consumption_view_dsl::item_id,
+                consumption_view_dsl::store_id,
+                consumption_view_dsl::date,
+                consumption_view_dsl::quantity,
+            ))
+            .load::<(String, String, NaiveDate, f64)>(connection.lock().connection())
+            .unwrap();
+        let mut view = Totals::new();
+        for (item_id, store_id, date, quantity) in view_rows {
+            *view.entry((item_id, store_id, date)).or_default() += quantity;
+        }
+        assert_totals_eq(aggregate, view);
+    }
+
+    #[actix_rt::test]
+    async fn stock_aggregates_match_views() {
+        let (_, connection, _, _) =
+            test_db::setup_all("stock_aggregates_match_views", MockDataInserts::all()).await;
+
+        // Mock data is inserted through the row repositories
+        assert_consistent_with_views(&connection);
+
+        // Stock line changes
+        let stock_line_repo = StockLineRowRepository::new(&connection);
+        stock_line_repo
+            .upsert_one(&StockLineRow {
+                available_number_of_packs: 5.0,
+                total_number_of_packs: 7.0,
+                pack_size: 2.0,
+                ..mock_stock_line_a()
+            })
+            .unwrap();
+        let new_stock_line = StockLineRow {
+            id: "stock_aggregate_test_line".to_string(),
+            store_id: "store_c".to_string(),
+            ..mock_stock_line_a()
+        };
+        stock_line_repo.upsert_one(&new_stock_line).unwrap();
+        assert_consistent_with_views(&connection);
+
+        stock_line_repo.delete(&new_stock_line.id).unwrap();
+        assert_consistent_with_views(&connection);
+
+        // Invoice line changes
+        let invoice_line_repo = InvoiceLineRowRepository::new(&connection);
+        let mut lines = mock_outbound_shipment_a_invoice_lines();
+        lines[0].number_of_packs = 3.0;
+        lines[0].pack_size = 5.0;
+        invoice_line_repo.upsert_one(&lines[0]).unwrap();
+        invoice_line_repo.delete(&lines[1].id).unwrap();
+        assert_consistent_with_views(&connection);
+
+        // Picked date of invoice changes
+        let invoice = mock_outbound_shipment_a();
+        InvoiceRowRepository::new(&connection)
+            .upsert_one(&crate::InvoiceRow {
+                picked_datetime: invoice
+                    .picked_datetime
+                    .map(|picked| picked - Duration::days(3)),
+                ..invoice
+            })
+            .unwrap();
+        assert_consistent_with_views(&connection);
+
+        // Rebuild
+        StockAggregateRepository::new(&connection)
+            .rebuild()
+            .unwrap();
+        assert_consistent_with_views(&connection);
+    }
+}
@@ -4,7 +4,10 @@ use super::{
 };
 
 use crate::{db_diesel::barcode_row::barcode, repository_error::RepositoryError, Delete, Upsert};
-use crate::{ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RowActionType};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RowActionType,
+    StockAggregateRepository,
+};
 
 use diesel::prelude::*;
 
@@ -71,12 +74,22 @@ impl<'a> StockLineRowRepository<'a> {
     }
 
     pub fn upsert_one(&self, row: &StockLineRow) -> Result<i64, RepositoryError> {
+        let old_row = self.find_one_by_id(&row.id)?;
         diesel::insert_into(stock_line_dsl::stock_line)
             .values(row)
             .on_conflict(stock_line_dsl::id)
             .do_update()
             .set(row)
             .execute(self.connection.lock().connection())?;
+
+        let aggregates = StockAggregateRepository::new(self.connection);
+        if let Some(old_row) = old_row {
+            if old_row.item_link_id != row.item_link_id || old_row.store_id != row.store_id {
+                aggregates.refresh_stock_on_hand(&old_row.item_link_id, &old_row.store_id)?;
+            }
+        }
+        aggregates.refresh_stock_on_hand(&row.item_link_id, &row.store_id)?;
+
         self.insert_changelog(row, RowActionType::Upsert)
     }
 
@@ -98,8 +111,11 @@ impl<'a> StockLineRowRepository<'a> {
 
     pub fn delete(&self, id: &str) -> Result<Option<i64>, RepositoryError> {
         let old_row = self.find_one_by_id(id)?;
-        let change_log_id = match old_row {
-            Some(old_row) => self.insert_changelog(&old_row, RowActionType::Delete)?,
+        let (change_log_id, old_row) = match old_row {
+            Some(old_row) => (
+                self.insert_changelog(&old_row, RowActionType::Delete)?,
+                old_row,
+            ),
             None => {
                 return Ok(None);
             }
@@ -107,6 +123,8 @@ impl<'a> StockLineRowRepository<'a> {
 
         diesel::delete(stock_line_dsl::stock_line.filter(stock_line_dsl::id.eq(id)))
             .execute(self.connection.lock().connection())?;
+        StockAggregateRepository::new(self.connection)
+            .refresh_stock_on_hand(&old_row.item_link_id, &old_row.store_id)?;
         Ok(Some(change_log_id))
     }
 
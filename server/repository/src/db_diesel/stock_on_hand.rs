@@ -1,8 +1,15 @@
-use super::{stock_on_hand::stock_on_hand::dsl as stock_on_hand_dsl, StorageConnection};
+use std::collections::HashMap;
+
+use super::{
+    item_link_row::item_link::dsl as item_link_dsl, item_row::item::dsl as item_dsl,
+    stock_aggregate::stock_on_hand_aggregate::dsl as stock_on_hand_aggregate_dsl,
+    store_row::store::dsl as store_dsl, StorageConnection,
+};
 
 use crate::{diesel_macros::apply_equal_filter, item_link, EqualFilter, RepositoryError};
 use diesel::prelude::*;
 
+// View over stock lines, queries use the `stock_on_hand_aggregate` table instead
 table! {
     stock_on_hand (id) {
         id -> Text,
@@ -53,23 +60,67 @@ impl<'a> StockOnHandRepository<'a> {
         &self,
         filter: Option<StockOnHandFilter>,
     ) -> Result<Vec<StockOnHandRow>, RepositoryError> {
-        // Query StockOnHand
-        let mut query = stock_on_hand_dsl::stock_on_hand.into_boxed();
-
-        if let Some(f) = filter {
-            let StockOnHandFilter { item_id, store_id } = f;
-
-            apply_equal_filter!(query, item_id, stock_on_hand_dsl::item_id);
-            apply_equal_filter!(query, store_id, stock_on_hand_dsl::store_id);
+        let (item_id, store_id) = match filter {
+            Some(StockOnHandFilter { item_id, store_id }) => (item_id, store_id),
+            None => (None, None),
+        };
+
+        // Same as the view, a row for every item and store combination
+        let mut items_query = item_dsl::item
+            .select((item_dsl::id, item_dsl::name))
+            .into_boxed();
+        apply_equal_filter!(items_query, item_id.clone(), item_dsl::id);
+        let items = items_query.load::<(String, String)>(self.connection.lock().connection())?;
+
+        let mut stores_query = store_dsl::store.select(store_dsl::id).into_boxed();
+        apply_equal_filter!(stores_query, store_id.clone(), store_dsl::id);
+        let stores = stores_query.load::<String>(self.connection.lock().connection())?;
+
+        let mut aggregates_query = stock_on_hand_aggregate_dsl::stock_on_hand_aggregate
+            .inner_join(item_link_dsl::item_link)
+            .select((
+                item_link_dsl::item_id,
+                stock_on_hand_aggregate_dsl::store_id,
+                stock_on_hand_aggregate_dsl::available_stock_on_hand,
+                stock_on_hand_aggregate_dsl::total_stock_on_hand,
+            ))
+            .into_boxed();
+        apply_equal_filter!(aggregates_query, item_id, item_link_dsl::item_id);
+        apply_equal_filter!(
+            aggregates_query,
+            store_id,
+            stock_on_hand_aggregate_dsl::store_id
+        );
+        let aggregates = aggregates_query
+            .load::<(String, String, f64, f64)>(self.connection.lock().connection())?;
+
+        // Merged items have multiple item links
+        let mut stock_on_hand: HashMap<(String, String), (f64, f64)> = HashMap::new();
+        for (item_id, store_id, available, total) in aggregates {
+            let entry = stock_on_hand.entry((item_id, store_id)).or_default();
+            entry.0 += available;
+            entry.1 += total;
         }
 
-        // Debug diesel query
-        // println!(
-        //     "{}",
-        //     diesel::debug_query::<crate::DBType, _>(&query).to_string()
-        // );
+        let mut result = Vec::new();
+        for (item_id, item_name) in items {
+            for store_id in stores.iter() {
+                let (available_stock_on_hand, total_stock_on_hand) = stock_on_hand
+                    .get(&(item_id.clone(), store_id.clone()))
+                    .copied()
+                    .unwrap_or_default();
+                result.push(StockOnHandRow {
+                    id: "n/a".to_string(),
+                    item_id: item_id.clone(),
+                    item_name: item_name.clone(),
+                    store_id: store_id.clone(),
+                    available_stock_on_hand,
+                    total_stock_on_hand,
+                });
+            }
+        }
 
-        Ok(query.load::<StockOnHandRow>(self.connection.lock().connection())?)
+        Ok(result)
     }
 }
 
@@ -13,6 +13,7 @@ use crate::{
 };
 
 use diesel::{dsl::IntoBoxed, prelude::*};
+use util::inline_init;
 
 #[derive(Clone, Default)]
 pub struct StocktakeFilter {
@@ -102,6 +103,16 @@ pub enum StocktakeSortField {
 
 pub type Stocktake = StocktakeRow;
 
+impl StocktakeStatus {
+    pub fn equal_to(&self) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.equal_to = Some(self.clone()))
+    }
+
+    pub fn not_equal_to(&self) -> EqualFilter<Self> {
+        inline_init(|r: &mut EqualFilter<Self>| r.not_equal_to = Some(self.clone()))
+    }
+}
+
 pub type StocktakeSort = Sort<StocktakeSortField>;
 
 type BoxedStocktakeQuery = IntoBoxed<'static, stocktake::table, DBType>;
@@ -117,11 +128,7 @@ fn create_filtered_query(filter: Option<StocktakeFilter>) -> BoxedStocktakeQuery
         apply_string_filter!(query, f.comment, stocktake::comment);
         apply_string_filter!(query, f.description, stocktake::description);
 
-        if let Some(value) = f.status {
-            if let Some(eq) = value.equal_to {
-                query = query.filter(stocktake::status.eq(eq));
-            }
-        }
+        apply_equal_filter!(query, f.status, stocktake::status);
 
         apply_date_time_filter!(query, f.created_datetime, stocktake::created_datetime);
         apply_date_filter!(query, f.stocktake_date, stocktake::stocktake_date);
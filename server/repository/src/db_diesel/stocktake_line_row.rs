@@ -35,6 +35,7 @@ table! {
         sell_price_per_pack -> Nullable<Double>,
         note -> Nullable<Text>,
         inventory_adjustment_reason_id -> Nullable<Text>,
+        requires_recount -> Bool,
     }
 }
 
@@ -69,6 +70,8 @@ pub struct StocktakeLineRow {
     pub sell_price_per_pack: Option<f64>,
     pub note: Option<String>,
     pub inventory_adjustment_reason_id: Option<String>,
+    /// Set when a blind count variance exceeds the stocktake threshold
+    pub requires_recount: bool,
 }
 
 pub struct StocktakeLineRowRepository<'a> {
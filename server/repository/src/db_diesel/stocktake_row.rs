@@ -9,6 +9,7 @@ use crate::{ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RowActi
 use chrono::{NaiveDate, NaiveDateTime};
 use diesel::{dsl::max, prelude::*};
 use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
 use util::Defaults;
 
 table! {
@@ -26,19 +27,30 @@ table! {
         inventory_addition_id -> Nullable<Text>,
         inventory_reduction_id -> Nullable<Text>,
         is_locked -> Bool,
+        is_blind -> Bool,
+        variance_quantity_threshold -> Nullable<Double>,
+        variance_value_threshold -> Nullable<Double>,
     }
 }
 
 joinable!(stocktake -> user_account (user_id));
 
-#[derive(DbEnum, Debug, Clone, PartialEq, Eq)]
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[DbValueStyle = "SCREAMING_SNAKE_CASE"]
 pub enum StocktakeStatus {
     New,
+    /// Blind stocktake count was submitted, lines with a variance above the threshold need to
+    /// be recounted
+    Recount,
+    /// Blind stocktake count (and recount) was submitted and is waiting for approval
+    PendingApproval,
+    /// Blind stocktake was approved and can be finalised
+    Approved,
     Finalised,
 }
 
-#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Eq)]
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
 #[diesel(table_name = stocktake)]
 pub struct StocktakeRow {
     pub id: String,
@@ -55,6 +67,13 @@ pub struct StocktakeRow {
     pub inventory_addition_id: Option<String>,
     pub inventory_reduction_id: Option<String>,
     pub is_locked: bool,
+    /// Snapshot quantities are hidden from the counter until the count is submitted, the
+    /// stocktake has to be approved before it can be finalised
+    pub is_blind: bool,
+    /// Absolute variance in packs above which a blind stocktake line needs to be recounted
+    pub variance_quantity_threshold: Option<f64>,
+    /// Absolute variance in cost value above which a blind stocktake line needs to be recounted
+    pub variance_value_threshold: Option<f64>,
 }
 
 impl Default for StocktakeStatus {
@@ -80,6 +99,9 @@ impl Default for StocktakeRow {
             inventory_addition_id: Default::default(),
             inventory_reduction_id: Default::default(),
             is_locked: Default::default(),
+            is_blind: Default::default(),
+            variance_quantity_threshold: Default::default(),
+            variance_value_threshold: Default::default(),
         }
     }
 }
@@ -211,6 +211,12 @@ impl StorageConnectionManager {
         Ok(StorageConnection::new(get_connection(&self.pool)?))
     }
 
+    /// Number of (open, idle) connections in the pool
+    pub fn pool_state(&self) -> (u32, u32) {
+        let state = self.pool.state();
+        (state.connections, state.idle_connections)
+    }
+
     // Note, this method is only needed for an Android workaround to avoid adding a diesel
     // dependency to the server crate.
     pub fn execute(&self, sql: &str) -> Result<(), RepositoryError> {
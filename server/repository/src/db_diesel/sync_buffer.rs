@@ -180,6 +180,14 @@ impl<'a> SyncBufferRepository<'a> {
         self.query(Some(filter))
     }
 
+    pub fn count(&self, filter: Option<SyncBufferFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query
+            .count()
+            .get_result(self.connection.lock().connection())?)
+    }
+
     pub fn query(
         &self,
         filter: Option<SyncBufferFilter>,
@@ -0,0 +1,151 @@
+use super::{tax_rule_row::tax_rule::dsl::*, StorageConnection};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, Delete, RepositoryError,
+    RowActionType, Upsert,
+};
+
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    tax_rule (id) {
+        id -> Text,
+        item_id -> Nullable<Text>,
+        price_category -> Nullable<Text>,
+        tax_percentage -> Double,
+        valid_from -> Nullable<Date>,
+        valid_to -> Nullable<Date>,
+    }
+}
+
+/// Tax applied to invoice lines, the most specific matching rule is used (item and price category,
+/// then item, then price category, then rules without either)
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = tax_rule)]
+#[diesel(treat_none_as_null = true)]
+pub struct TaxRuleRow {
+    pub id: String,
+    /// None when the rule applies to all items
+    pub item_id: Option<String>,
+    /// None when the rule applies to all customers, e.g. "EXEMPT" with a 0% rate
+    pub price_category: Option<String>,
+    pub tax_percentage: f64,
+    pub valid_from: Option<NaiveDate>,
+    /// Inclusive
+    pub valid_to: Option<NaiveDate>,
+}
+
+impl TaxRuleRow {
+    pub fn is_valid_on(&self, date: NaiveDate) -> bool {
+        self.valid_from.is_none_or(|from| from <= date) && self.valid_to.is_none_or(|to| to >= date)
+    }
+}
+
+pub struct TaxRuleRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> TaxRuleRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        TaxRuleRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &TaxRuleRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(tax_rule)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &TaxRuleRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::TaxRule,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: None,
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(&self, rule_id: &str) -> Result<Option<TaxRuleRow>, RepositoryError> {
+        let result = tax_rule
+            .filter(id.eq(rule_id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_all(&self) -> Result<Vec<TaxRuleRow>, RepositoryError> {
+        let result = tax_rule
+            .order((item_id.asc(), price_category.asc()))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    /// Rules for the item, including rules that apply to all items
+    pub fn find_many_by_item_id(
+        &self,
+        for_item_id: &str,
+    ) -> Result<Vec<TaxRuleRow>, RepositoryError> {
+        let result = tax_rule
+            .filter(item_id.eq(for_item_id).or(item_id.is_null()))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, rule_id: &str) -> Result<Option<i64>, RepositoryError> {
+        let old_row = self.find_one_by_id(rule_id)?;
+        let change_log_id = match old_row {
+            Some(old_row) => self.insert_changelog(&old_row, RowActionType::Delete)?,
+            None => {
+                return Ok(None);
+            }
+        };
+
+        diesel::delete(tax_rule.filter(id.eq(rule_id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(Some(change_log_id))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TaxRuleRowDelete(pub String);
+impl Delete for TaxRuleRowDelete {
+    fn delete(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        TaxRuleRowRepository::new(con).delete(&self.0)
+    }
+    // Test only
+    fn assert_deleted(&self, con: &StorageConnection) {
+        assert_eq!(
+            TaxRuleRowRepository::new(con).find_one_by_id(&self.0),
+            Ok(None)
+        )
+    }
+}
+
+impl Upsert for TaxRuleRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = TaxRuleRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            TaxRuleRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -8,6 +8,7 @@ use crate::{Delete, Upsert};
 use diesel::prelude::*;
 
 use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
 
 table! {
   user_permission (id) {
@@ -19,8 +20,9 @@ table! {
     }
 }
 
-#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PermissionType {
     ServerAdmin,
 
@@ -41,6 +43,9 @@ pub enum PermissionType {
     // stocktake
     StocktakeQuery,
     StocktakeMutate,
+    /// Approve blind stocktake counts before they can be finalised, granted to users with the
+    /// legacy "Finalise inventory adjustments" permission
+    StocktakeApprove,
     // inventory adjustment
     InventoryAdjustmentMutate,
     // requisition
@@ -86,6 +91,8 @@ pub enum PermissionType {
     NamePropertiesMutate,
     // Central Server
     EditCentralData,
+    // api keys
+    ApiKeyMutate,
 }
 
 #[derive(Clone, Queryable, Insertable, Debug, PartialEq, Eq, AsChangeset)]
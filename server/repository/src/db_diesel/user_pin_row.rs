@@ -0,0 +1,106 @@
+use super::{user_pin_row::user_pin::dsl as user_pin_dsl, StorageConnection};
+use crate::{repository_error::RepositoryError, Upsert};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    user_pin (id) {
+        id -> Text,
+        user_id -> Text,
+        device_token_hash -> Text,
+        pin_hash -> Text,
+        created_datetime -> Timestamp,
+        expiry_datetime -> Timestamp,
+        failed_attempts -> Integer,
+    }
+}
+
+/// PIN a user can use to switch to their account on a known device, without a full login
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[diesel(table_name = user_pin)]
+#[diesel(treat_none_as_null = true)]
+pub struct UserPinRow {
+    pub id: String,
+    pub user_id: String,
+    /// Hash of the token the server issued to the device the PIN was set on
+    pub device_token_hash: String,
+    pub pin_hash: String,
+    pub created_datetime: NaiveDateTime,
+    /// A full password login is required again after this
+    pub expiry_datetime: NaiveDateTime,
+    pub failed_attempts: i32,
+}
+
+pub struct UserPinRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> UserPinRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        UserPinRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &UserPinRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(user_pin_dsl::user_pin)
+            .values(row)
+            .on_conflict(user_pin_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<UserPinRow>, RepositoryError> {
+        let result = user_pin_dsl::user_pin
+            .filter(user_pin_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_one_by_user_and_device(
+        &self,
+        user_id: &str,
+        device_token_hash: &str,
+    ) -> Result<Option<UserPinRow>, RepositoryError> {
+        let result = user_pin_dsl::user_pin
+            .filter(user_pin_dsl::user_id.eq(user_id))
+            .filter(user_pin_dsl::device_token_hash.eq(device_token_hash))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_one_by_device(
+        &self,
+        device_token_hash: &str,
+    ) -> Result<Option<UserPinRow>, RepositoryError> {
+        let result = user_pin_dsl::user_pin
+            .filter(user_pin_dsl::device_token_hash.eq(device_token_hash))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(user_pin_dsl::user_pin.filter(user_pin_dsl::id.eq(id)))
+            .execute(self.connection.lock().connection())?;
+        Ok(())
+    }
+}
+
+impl Upsert for UserPinRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        UserPinRowRepository::new(con).upsert_one(self)?;
+        Ok(None) // Table not in Changelog
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            UserPinRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
@@ -72,4 +72,21 @@ impl<'a> VaccinationCardRepository<'a> {
             .order(vaccination_card_dsl::min_age.asc())
             .load::<VaccinationCardRow>(self.connection.lock().connection())?)
     }
+
+    pub fn query_by_enrolment_ids(
+        &self,
+        program_enrolment_ids: Vec<String>,
+    ) -> Result<Vec<VaccinationCardRow>, RepositoryError> {
+        let mut query = vaccination_card_dsl::vaccination_card.into_boxed();
+
+        apply_equal_filter!(
+            query,
+            Some(EqualFilter::equal_any(program_enrolment_ids)),
+            vaccination_card_dsl::program_enrolment_id
+        );
+
+        Ok(query
+            .order(vaccination_card_dsl::min_age.asc())
+            .load::<VaccinationCardRow>(self.connection.lock().connection())?)
+    }
 }
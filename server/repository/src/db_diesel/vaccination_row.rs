@@ -134,6 +134,16 @@ impl<'a> VaccinationRowRepository<'a> {
         Ok(result)
     }
 
+    pub fn find_many_by_stock_line_ids(
+        &self,
+        stock_line_ids: &[String],
+    ) -> Result<Vec<VaccinationRow>, RepositoryError> {
+        let result = vaccination
+            .filter(stock_line_id.eq_any(stock_line_ids))
+            .load(self.connection.lock().connection())?;
+        Ok(result)
+    }
+
     pub fn delete(&self, vaccination_id: &str) -> Result<(), RepositoryError> {
         diesel::delete(vaccination)
             .filter(id.eq(vaccination_id))
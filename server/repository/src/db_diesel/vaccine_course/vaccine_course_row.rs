@@ -19,6 +19,7 @@ table! {
         coverage_rate -> Double,
         is_active -> Bool,
         wastage_rate -> Double,
+        open_vial_discard_hours -> Nullable<Integer>,
         deleted_datetime -> Nullable<Timestamp>,
     }
 }
@@ -36,6 +37,8 @@ pub struct VaccineCourseRow {
     pub coverage_rate: f64,
     pub is_active: bool,
     pub wastage_rate: f64,
+    /// Hours an opened multi-dose vial can be used for before remaining doses must be discarded
+    pub open_vial_discard_hours: Option<i32>,
     pub deleted_datetime: Option<chrono::NaiveDateTime>,
 }
 
@@ -0,0 +1,112 @@
+use super::{
+    vaccine_open_vial_row::{vaccine_open_vial, vaccine_open_vial::dsl as vaccine_open_vial_dsl},
+    DBType, StorageConnection, VaccineOpenVialRow,
+};
+
+use diesel::prelude::*;
+
+use crate::{diesel_macros::apply_equal_filter, repository_error::RepositoryError, EqualFilter};
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct VaccineOpenVialFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub stock_line_id: Option<EqualFilter<String>>,
+    pub vaccine_course_id: Option<EqualFilter<String>>,
+    /// Vial still has doses remaining and hasn't been discarded
+    pub is_open: Option<bool>,
+}
+
+pub struct VaccineOpenVialRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> VaccineOpenVialRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        VaccineOpenVialRepository { connection }
+    }
+
+    /// Returns vials ordered by the time they were opened, oldest first
+    pub fn query_by_filter(
+        &self,
+        filter: VaccineOpenVialFilter,
+    ) -> Result<Vec<VaccineOpenVialRow>, RepositoryError> {
+        let result = Self::create_filtered_query(Some(filter))
+            .order(vaccine_open_vial_dsl::opened_datetime.asc())
+            .load::<VaccineOpenVialRow>(self.connection.lock().connection())?;
+
+        Ok(result)
+    }
+
+    pub fn create_filtered_query(
+        filter: Option<VaccineOpenVialFilter>,
+    ) -> BoxedVaccineOpenVialQuery {
+        let mut query = vaccine_open_vial_dsl::vaccine_open_vial.into_boxed();
+
+        if let Some(f) = filter {
+            let VaccineOpenVialFilter {
+                id,
+                store_id,
+                stock_line_id,
+                vaccine_course_id,
+                is_open,
+            } = f;
+
+            apply_equal_filter!(query, id, vaccine_open_vial_dsl::id);
+            apply_equal_filter!(query, store_id, vaccine_open_vial_dsl::store_id);
+            apply_equal_filter!(query, stock_line_id, vaccine_open_vial_dsl::stock_line_id);
+            apply_equal_filter!(
+                query,
+                vaccine_course_id,
+                vaccine_open_vial_dsl::vaccine_course_id
+            );
+
+            query = match is_open {
+                Some(true) => query
+                    .filter(vaccine_open_vial_dsl::doses_remaining.gt(0))
+                    .filter(vaccine_open_vial_dsl::discarded_datetime.is_null()),
+                Some(false) => query.filter(
+                    vaccine_open_vial_dsl::doses_remaining
+                        .le(0)
+                        .or(vaccine_open_vial_dsl::discarded_datetime.is_not_null()),
+                ),
+                None => query,
+            };
+        }
+
+        query
+    }
+}
+
+type BoxedVaccineOpenVialQuery = vaccine_open_vial::BoxedQuery<'static, DBType>;
+
+impl VaccineOpenVialFilter {
+    pub fn new() -> VaccineOpenVialFilter {
+        Self::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn stock_line_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.stock_line_id = Some(filter);
+        self
+    }
+
+    pub fn vaccine_course_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.vaccine_course_id = Some(filter);
+        self
+    }
+
+    pub fn is_open(mut self, value: bool) -> Self {
+        self.is_open = Some(value);
+        self
+    }
+}
@@ -0,0 +1,107 @@
+use super::{
+    vaccine_open_vial_row::vaccine_open_vial::dsl as vaccine_open_vial_dsl, StorageConnection,
+};
+use crate::{
+    ChangeLogInsertRow, ChangelogRepository, ChangelogTableName, RepositoryError, RowActionType,
+    Upsert,
+};
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+table! {
+    vaccine_open_vial (id) {
+        id -> Text,
+        store_id -> Text,
+        stock_line_id -> Text,
+        item_link_id -> Text,
+        vaccine_course_id -> Text,
+        opened_datetime -> Timestamp,
+        discard_after_datetime -> Timestamp,
+        total_doses -> Integer,
+        doses_remaining -> Integer,
+        discarded_datetime -> Nullable<Timestamp>,
+        wastage_invoice_id -> Nullable<Text>,
+    }
+}
+
+#[derive(
+    Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default, Serialize, Deserialize,
+)]
+#[diesel(table_name = vaccine_open_vial)]
+#[diesel(treat_none_as_null = true)]
+pub struct VaccineOpenVialRow {
+    pub id: String,
+    pub store_id: String,
+    pub stock_line_id: String,
+    pub item_link_id: String,
+    pub vaccine_course_id: String,
+    pub opened_datetime: NaiveDateTime,
+    /// Open vial policy cut off, remaining doses should not be used after this time
+    pub discard_after_datetime: NaiveDateTime,
+    pub total_doses: i32,
+    pub doses_remaining: i32,
+    pub discarded_datetime: Option<NaiveDateTime>,
+    /// Inventory adjustment writing off the doses remaining when the vial was discarded
+    pub wastage_invoice_id: Option<String>,
+}
+
+pub struct VaccineOpenVialRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> VaccineOpenVialRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        VaccineOpenVialRowRepository { connection }
+    }
+
+    pub fn upsert_one(&self, row: &VaccineOpenVialRow) -> Result<i64, RepositoryError> {
+        diesel::insert_into(vaccine_open_vial_dsl::vaccine_open_vial)
+            .values(row)
+            .on_conflict(vaccine_open_vial_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(self.connection.lock().connection())?;
+
+        self.insert_changelog(row, RowActionType::Upsert)
+    }
+
+    fn insert_changelog(
+        &self,
+        row: &VaccineOpenVialRow,
+        action: RowActionType,
+    ) -> Result<i64, RepositoryError> {
+        let row = ChangeLogInsertRow {
+            table_name: ChangelogTableName::VaccineOpenVial,
+            record_id: row.id.clone(),
+            row_action: action,
+            store_id: Some(row.store_id.clone()),
+            name_link_id: None,
+        };
+        ChangelogRepository::new(self.connection).insert(&row)
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<VaccineOpenVialRow>, RepositoryError> {
+        let result = vaccine_open_vial_dsl::vaccine_open_vial
+            .filter(vaccine_open_vial_dsl::id.eq(id))
+            .first(self.connection.lock().connection())
+            .optional()?;
+        Ok(result)
+    }
+}
+
+impl Upsert for VaccineOpenVialRow {
+    fn upsert(&self, con: &StorageConnection) -> Result<Option<i64>, RepositoryError> {
+        let cursor_id = VaccineOpenVialRowRepository::new(con).upsert_one(self)?;
+        Ok(Some(cursor_id))
+    }
+
+    // Test only
+    fn assert_upserted(&self, con: &StorageConnection) {
+        assert_eq!(
+            VaccineOpenVialRowRepository::new(con).find_one_by_id(&self.id),
+            Ok(Some(self.clone()))
+        )
+    }
+}
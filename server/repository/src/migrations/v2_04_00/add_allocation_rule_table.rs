@@ -0,0 +1,51 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_allocation_rule_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE allocation_strategy_type AS ENUM (
+                'FEFO',
+                'FIFO',
+                'PICK_PATH',
+                'FULL_PACKS_FIRST'
+                );
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'allocation_rule';
+            "#
+            )?;
+        }
+
+        const STRATEGY_ENUM: &str = if cfg!(feature = "postgres") {
+            "allocation_strategy_type"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE allocation_rule (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    name_tag_id TEXT REFERENCES name_tag(id),
+                    strategy {STRATEGY_ENUM} NOT NULL,
+                    expiring_soon_days INTEGER,
+                    min_remaining_shelf_life_days INTEGER,
+                    avoid_item_variant_id TEXT REFERENCES item_variant(id),
+                    priority INTEGER NOT NULL DEFAULT 0
+                );
+                CREATE INDEX index_allocation_rule_store_id ON allocation_rule (store_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_api_key_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                ALTER TYPE permission_type ADD VALUE IF NOT EXISTS 'API_KEY_MUTATE';
+            "#
+            )?;
+        }
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE api_key (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    key_hash TEXT NOT NULL UNIQUE,
+                    key_prefix TEXT NOT NULL,
+                    user_id TEXT NOT NULL REFERENCES user_account(id),
+                    store_ids TEXT NOT NULL,
+                    resources TEXT NOT NULL,
+                    created_datetime {DATETIME} NOT NULL,
+                    expiry_datetime {DATETIME},
+                    last_used_datetime {DATETIME},
+                    revoked_datetime {DATETIME}
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
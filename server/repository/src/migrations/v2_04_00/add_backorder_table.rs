@@ -0,0 +1,54 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_backorder_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE backorder_status AS ENUM (
+                'OPEN',
+                'FULFILLED',
+                'CANCELLED'
+                );
+            "#
+            )?;
+        }
+
+        const STATUS_ENUM: &str = if cfg!(feature = "postgres") {
+            "backorder_status"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE backorder (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    name_link_id TEXT NOT NULL REFERENCES name_link(id),
+                    item_link_id TEXT NOT NULL REFERENCES item_link(id),
+                    requisition_id TEXT REFERENCES requisition(id),
+                    quantity {DOUBLE} NOT NULL,
+                    allocated_quantity {DOUBLE} NOT NULL DEFAULT 0,
+                    status {STATUS_ENUM} NOT NULL,
+                    created_datetime {DATETIME} NOT NULL,
+                    fulfilled_datetime {DATETIME},
+                    cancelled_datetime {DATETIME},
+                    cancellation_reason TEXT
+                );
+                CREATE INDEX index_backorder_store_id_status ON backorder (store_id, status);
+                CREATE INDEX index_backorder_requisition_id ON backorder (requisition_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
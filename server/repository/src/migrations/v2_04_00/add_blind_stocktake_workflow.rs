@@ -0,0 +1,46 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_blind_stocktake_workflow"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                ALTER TYPE stocktake_status ADD VALUE IF NOT EXISTS 'RECOUNT';
+                ALTER TYPE stocktake_status ADD VALUE IF NOT EXISTS 'PENDING_APPROVAL';
+                ALTER TYPE stocktake_status ADD VALUE IF NOT EXISTS 'APPROVED';
+
+                ALTER TYPE activity_log_type
+                ADD VALUE IF NOT EXISTS
+                    'STOCKTAKE_STATUS_RECOUNT' AFTER 'DEMOGRAPHIC_PROJECTION_UPDATED';
+                ALTER TYPE activity_log_type
+                ADD VALUE IF NOT EXISTS
+                    'STOCKTAKE_STATUS_PENDING_APPROVAL' AFTER 'STOCKTAKE_STATUS_RECOUNT';
+                ALTER TYPE activity_log_type
+                ADD VALUE IF NOT EXISTS
+                    'STOCKTAKE_STATUS_APPROVED' AFTER 'STOCKTAKE_STATUS_PENDING_APPROVAL';
+
+                ALTER TYPE permission_type ADD VALUE IF NOT EXISTS 'STOCKTAKE_APPROVE';
+            "#
+            )?;
+        }
+
+        sql!(
+            connection,
+            r#"
+                ALTER TABLE stocktake ADD COLUMN is_blind BOOLEAN NOT NULL DEFAULT FALSE;
+                ALTER TABLE stocktake ADD COLUMN variance_quantity_threshold {DOUBLE};
+                ALTER TABLE stocktake ADD COLUMN variance_value_threshold {DOUBLE};
+                ALTER TABLE stocktake_line ADD COLUMN requires_recount BOOLEAN NOT NULL DEFAULT FALSE;
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_cycle_count_schedule_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE cycle_count_abc_weighting AS ENUM (
+                'VALUE',
+                'MOVEMENT'
+                );
+            "#
+            )?;
+        }
+
+        const ABC_WEIGHTING_ENUM: &str = if cfg!(feature = "postgres") {
+            "cycle_count_abc_weighting"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE cycle_count_schedule (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL UNIQUE REFERENCES store(id),
+                    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+                    cycle_length_days INTEGER NOT NULL,
+                    abc_weighting {ABC_WEIGHTING_ENUM},
+                    last_generated_date {DATE}
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
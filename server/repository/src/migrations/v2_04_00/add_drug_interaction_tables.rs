@@ -0,0 +1,59 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_drug_interaction_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE drug_interaction_severity AS ENUM (
+                'MINOR',
+                'MODERATE',
+                'MAJOR',
+                'CONTRAINDICATED'
+                );
+
+                ALTER TYPE activity_log_type
+                ADD VALUE IF NOT EXISTS
+                    'CLINICAL_WARNING_ACKNOWLEDGED' AFTER 'USER_ACCOUNT_LOCKED';
+            "#
+            )?;
+        }
+
+        const SEVERITY_ENUM: &str = if cfg!(feature = "postgres") {
+            "drug_interaction_severity"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE drug_interaction (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    subject_a TEXT NOT NULL,
+                    subject_b TEXT NOT NULL,
+                    severity {SEVERITY_ENUM} NOT NULL,
+                    description TEXT NOT NULL
+                );
+                CREATE INDEX index_drug_interaction_subject_a ON drug_interaction (subject_a);
+                CREATE INDEX index_drug_interaction_subject_b ON drug_interaction (subject_b);
+
+                CREATE TABLE drug_ingredient (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    item_code TEXT NOT NULL,
+                    ingredient TEXT NOT NULL,
+                    UNIQUE (item_code, ingredient)
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
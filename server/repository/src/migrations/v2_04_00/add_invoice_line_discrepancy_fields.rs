@@ -0,0 +1,40 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_invoice_line_discrepancy_fields"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        let discrepancy_type = if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE invoice_line_discrepancy_type AS ENUM (
+                    'SHORT',
+                    'DAMAGED',
+                    'WRONG_BATCH',
+                    'EXCESS'
+                );
+            "#
+            )?;
+            "invoice_line_discrepancy_type"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                ALTER TABLE invoice_line ADD COLUMN linked_invoice_line_id TEXT;
+                ALTER TABLE invoice_line ADD COLUMN discrepancy_type {discrepancy_type};
+                ALTER TABLE invoice_line ADD COLUMN discrepancy_number_of_packs {DOUBLE} NOT NULL DEFAULT 0;
+                ALTER TABLE invoice_line ADD COLUMN discrepancy_reason TEXT;
+            "#
+        )?;
+
+        Ok(())
+    }
+}
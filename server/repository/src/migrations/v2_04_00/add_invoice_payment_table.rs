@@ -0,0 +1,92 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_invoice_payment_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE invoice_payment_type AS ENUM (
+                'PAYMENT',
+                'CREDIT_NOTE'
+                );
+
+                CREATE TYPE payment_method AS ENUM (
+                'CASH',
+                'CARD',
+                'MOBILE_MONEY',
+                'BANK_TRANSFER',
+                'CHEQUE',
+                'INSURANCE',
+                'OTHER'
+                );
+
+                ALTER TYPE activity_log_type
+                ADD VALUE IF NOT EXISTS
+                    'INVOICE_PAYMENT_CREATED' AFTER 'CLINICAL_WARNING_ACKNOWLEDGED';
+                ALTER TYPE activity_log_type
+                ADD VALUE IF NOT EXISTS
+                    'INVOICE_PAYMENT_CANCELLED' AFTER 'INVOICE_PAYMENT_CREATED';
+            "#
+            )?;
+        }
+
+        const PAYMENT_TYPE_ENUM: &str = if cfg!(feature = "postgres") {
+            "invoice_payment_type"
+        } else {
+            "TEXT"
+        };
+        const PAYMENT_METHOD_ENUM: &str = if cfg!(feature = "postgres") {
+            "payment_method"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE invoice_payment (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    name_link_id TEXT NOT NULL REFERENCES name_link(id),
+                    invoice_id TEXT,
+                    customer_return_id TEXT,
+                    type {PAYMENT_TYPE_ENUM} NOT NULL,
+                    payment_method {PAYMENT_METHOD_ENUM},
+                    receipt_number BIGINT NOT NULL,
+                    amount {DOUBLE} NOT NULL,
+                    currency_id TEXT REFERENCES currency(id),
+                    currency_rate {DOUBLE} NOT NULL DEFAULT 1.0,
+                    home_currency_amount {DOUBLE} NOT NULL,
+                    reference TEXT,
+                    comment TEXT,
+                    user_id TEXT,
+                    payment_datetime {DATETIME} NOT NULL,
+                    cancelled_datetime {DATETIME}
+                );
+                CREATE INDEX index_invoice_payment_invoice_id ON invoice_payment (invoice_id);
+                CREATE INDEX index_invoice_payment_name_link_id ON invoice_payment (name_link_id);
+                CREATE INDEX index_invoice_payment_store_id_payment_datetime
+                    ON invoice_payment (store_id, payment_datetime);
+            "#
+        )?;
+
+        if cfg!(feature = "postgres") {
+            // Postgres changelog variant
+            sql!(
+                connection,
+                r#"
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'invoice_payment';
+                "#
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_lmis_code_mapping_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE lmis_code_mapping_type AS ENUM (
+                'PROGRAM',
+                'FACILITY',
+                'ITEM',
+                'FIELD'
+                );
+            "#
+            )?;
+        }
+
+        const MAPPING_TYPE_ENUM: &str = if cfg!(feature = "postgres") {
+            "lmis_code_mapping_type"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE lmis_code_mapping (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    program_id TEXT NOT NULL REFERENCES program(id),
+                    type {MAPPING_TYPE_ENUM} NOT NULL,
+                    internal_id TEXT NOT NULL,
+                    external_code TEXT NOT NULL,
+                    deleted_datetime {DATETIME}
+                );
+            "#
+        )?;
+
+        if cfg!(feature = "postgres") {
+            // Postgres changelog variant
+            sql!(
+                connection,
+                r#"
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'lmis_code_mapping';
+                "#
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_login_lockout_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE login_lockout_identifier_type AS ENUM (
+                'USERNAME',
+                'IP_ADDRESS'
+                );
+
+                ALTER TYPE key_type ADD VALUE IF NOT EXISTS 'SETTINGS_LOGIN_SECURITY';
+
+                ALTER TYPE activity_log_type
+                ADD VALUE IF NOT EXISTS
+                    'USER_LOGIN_FAILED' AFTER 'STOCKTAKE_STATUS_APPROVED';
+                ALTER TYPE activity_log_type
+                ADD VALUE IF NOT EXISTS
+                    'USER_ACCOUNT_LOCKED' AFTER 'USER_LOGIN_FAILED';
+            "#
+            )?;
+        }
+
+        const IDENTIFIER_TYPE_ENUM: &str = if cfg!(feature = "postgres") {
+            "login_lockout_identifier_type"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE login_lockout (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    identifier_type {IDENTIFIER_TYPE_ENUM} NOT NULL,
+                    identifier TEXT NOT NULL,
+                    failed_attempts INTEGER NOT NULL,
+                    last_failed_datetime {DATETIME} NOT NULL,
+                    locked_until_datetime {DATETIME},
+                    UNIQUE (identifier_type, identifier)
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_pick_list_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE pick_list_status AS ENUM (
+                'IN_PROGRESS',
+                'COMPLETED'
+                );
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'pick_list';
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'pick_list_line';
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'pick_path_location';
+            "#
+            )?;
+        }
+
+        const STATUS_ENUM: &str = if cfg!(feature = "postgres") {
+            "pick_list_status"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE pick_list (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    status {STATUS_ENUM} NOT NULL,
+                    created_datetime {DATETIME} NOT NULL,
+                    completed_datetime {DATETIME},
+                    user_id TEXT NOT NULL
+                );
+                CREATE INDEX index_pick_list_store_id ON pick_list (store_id);
+
+                CREATE TABLE pick_list_line (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    pick_list_id TEXT NOT NULL REFERENCES pick_list(id),
+                    invoice_id TEXT NOT NULL REFERENCES invoice(id),
+                    invoice_line_id TEXT NOT NULL,
+                    item_link_id TEXT NOT NULL REFERENCES item_link(id),
+                    stock_line_id TEXT REFERENCES stock_line(id),
+                    location_id TEXT REFERENCES location(id),
+                    line_number INTEGER NOT NULL,
+                    number_of_packs {DOUBLE} NOT NULL,
+                    picked_number_of_packs {DOUBLE},
+                    picked_datetime {DATETIME}
+                );
+                CREATE INDEX index_pick_list_line_pick_list_id ON pick_list_line (pick_list_id);
+                CREATE INDEX index_pick_list_line_invoice_id ON pick_list_line (invoice_id);
+
+                CREATE TABLE pick_path_location (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    location_id TEXT NOT NULL REFERENCES location(id),
+                    sort_order INTEGER NOT NULL
+                );
+                CREATE INDEX index_pick_path_location_store_id ON pick_path_location (store_id);
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_prescription_line_direction_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE administration_route AS ENUM (
+                'ORAL',
+                'SUBLINGUAL',
+                'TOPICAL',
+                'INHALED',
+                'NASAL',
+                'OPHTHALMIC',
+                'OTIC',
+                'RECTAL',
+                'VAGINAL',
+                'INTRAMUSCULAR',
+                'INTRAVENOUS',
+                'SUBCUTANEOUS'
+                );
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'prescription_line_direction';
+            "#
+            )?;
+        }
+
+        const ADMINISTRATION_ROUTE_ENUM: &str = if cfg!(feature = "postgres") {
+            "administration_route"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE prescription_line_direction (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    invoice_line_id TEXT NOT NULL UNIQUE REFERENCES invoice_line(id),
+                    sig TEXT,
+                    dose {DOUBLE},
+                    dose_unit TEXT,
+                    frequency_per_day {DOUBLE},
+                    duration_days INTEGER,
+                    route {ADMINISTRATION_ROUTE_ENUM},
+                    directions TEXT NOT NULL
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_price_list_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE price_list (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    store_id TEXT,
+                    name_id TEXT,
+                    name_tag_id TEXT,
+                    price_category TEXT,
+                    valid_from {DATE},
+                    valid_to {DATE},
+                    priority INTEGER NOT NULL DEFAULT 0,
+                    is_active BOOLEAN NOT NULL DEFAULT TRUE
+                );
+
+                CREATE TABLE price_list_line (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    price_list_id TEXT NOT NULL REFERENCES price_list(id),
+                    item_id TEXT NOT NULL REFERENCES item(id),
+                    min_quantity {DOUBLE} NOT NULL DEFAULT 0,
+                    price_per_unit {DOUBLE},
+                    discount_percentage {DOUBLE}
+                );
+                CREATE INDEX index_price_list_line_item_id ON price_list_line (item_id);
+
+                CREATE TABLE tax_rule (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    item_id TEXT REFERENCES item(id),
+                    price_category TEXT,
+                    tax_percentage {DOUBLE} NOT NULL,
+                    valid_from {DATE},
+                    valid_to {DATE}
+                );
+
+                CREATE TABLE name_price_category (
+                    name_id TEXT NOT NULL PRIMARY KEY,
+                    price_category TEXT NOT NULL
+                );
+            "#
+        )?;
+
+        if cfg!(feature = "postgres") {
+            // Postgres changelog variants
+            sql!(
+                connection,
+                r#"
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'price_list';
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'price_list_line';
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'tax_rule';
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'name_price_category';
+                "#
+            )?;
+        }
+
+        Ok(())
+    }
+}
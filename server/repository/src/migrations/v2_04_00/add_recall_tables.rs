@@ -0,0 +1,56 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_recall_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE recall (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    item_link_id TEXT NOT NULL REFERENCES item_link(id),
+                    reason TEXT NOT NULL,
+                    recall_date {DATE} NOT NULL,
+                    created_datetime {DATETIME} NOT NULL,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    user_id TEXT NOT NULL
+                );
+
+                CREATE TABLE recall_batch (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    recall_id TEXT NOT NULL REFERENCES recall(id),
+                    batch TEXT NOT NULL
+                );
+                CREATE INDEX index_recall_batch_recall_id ON recall_batch (recall_id);
+
+                CREATE TABLE recall_stock_line (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    recall_id TEXT NOT NULL REFERENCES recall(id),
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    stock_line_id TEXT NOT NULL,
+                    quarantined_number_of_packs {DOUBLE} NOT NULL
+                );
+                CREATE INDEX index_recall_stock_line_recall_id ON recall_stock_line (recall_id);
+            "#
+        )?;
+
+        if cfg!(feature = "postgres") {
+            // Postgres changelog variants
+            sql!(
+                connection,
+                r#"
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'recall';
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'recall_batch';
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'recall_stock_line';
+                "#
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_serial_number_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                CREATE TYPE serial_number_status AS ENUM (
+                'IN_STOCK',
+                'ISSUED'
+                );
+                CREATE TYPE serial_number_movement_type AS ENUM (
+                'RECEIVED',
+                'ISSUED'
+                );
+            "#
+            )?;
+        }
+
+        const STATUS_ENUM: &str = if cfg!(feature = "postgres") {
+            "serial_number_status"
+        } else {
+            "TEXT"
+        };
+        const MOVEMENT_TYPE_ENUM: &str = if cfg!(feature = "postgres") {
+            "serial_number_movement_type"
+        } else {
+            "TEXT"
+        };
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE serial_number (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    item_link_id TEXT NOT NULL REFERENCES item_link(id),
+                    serial TEXT NOT NULL,
+                    gtin TEXT,
+                    stock_line_id TEXT NOT NULL,
+                    status {STATUS_ENUM} NOT NULL,
+                    received_invoice_line_id TEXT NOT NULL,
+                    issued_invoice_line_id TEXT,
+                    issued_name_link_id TEXT REFERENCES name_link(id),
+                    created_datetime {DATETIME} NOT NULL
+                );
+                CREATE INDEX index_serial_number_stock_line_id ON serial_number (stock_line_id);
+                CREATE INDEX index_serial_number_serial ON serial_number (serial);
+
+                CREATE TABLE serial_number_movement (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    serial_number_id TEXT NOT NULL REFERENCES serial_number(id),
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    type {MOVEMENT_TYPE_ENUM} NOT NULL,
+                    invoice_id TEXT NOT NULL,
+                    invoice_line_id TEXT NOT NULL,
+                    datetime {DATETIME} NOT NULL
+                );
+                CREATE INDEX index_serial_number_movement_serial_number_id ON serial_number_movement (serial_number_id);
+
+                ALTER TABLE item ADD COLUMN is_serialised BOOLEAN NOT NULL DEFAULT FALSE;
+            "#
+        )?;
+
+        if cfg!(feature = "postgres") {
+            // Postgres changelog variants
+            sql!(
+                connection,
+                r#"
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'serial_number';
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'serial_number_movement';
+                    ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'item_oms_fields';
+                "#
+            )?;
+        }
+
+        Ok(())
+    }
+}
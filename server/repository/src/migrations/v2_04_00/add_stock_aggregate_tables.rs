@@ -0,0 +1,45 @@
+use crate::{
+    db_diesel::{REBUILD_CONSUMPTION, REBUILD_STOCK_ON_HAND},
+    migrations::*,
+};
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_stock_aggregate_tables"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE stock_on_hand_aggregate (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    item_link_id TEXT NOT NULL REFERENCES item_link(id),
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    available_stock_on_hand {DOUBLE} NOT NULL,
+                    total_stock_on_hand {DOUBLE} NOT NULL,
+                    UNIQUE (item_link_id, store_id)
+                );
+
+                CREATE TABLE consumption_aggregate (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    item_link_id TEXT NOT NULL REFERENCES item_link(id),
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    date {DATE} NOT NULL,
+                    quantity {DOUBLE} NOT NULL,
+                    UNIQUE (item_link_id, store_id, date)
+                );
+
+                CREATE INDEX index_consumption_aggregate_store_id_date
+                    ON consumption_aggregate (store_id, date);
+            "#
+        )?;
+
+        // Existing stock lines and invoices
+        sql!(connection, "{REBUILD_STOCK_ON_HAND}{REBUILD_CONSUMPTION}")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,29 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_user_pin_table"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE user_pin (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    user_id TEXT NOT NULL REFERENCES user_account(id),
+                    device_token_hash TEXT NOT NULL,
+                    pin_hash TEXT NOT NULL,
+                    created_datetime {DATETIME} NOT NULL,
+                    expiry_datetime {DATETIME} NOT NULL,
+                    failed_attempts INTEGER NOT NULL DEFAULT 0,
+                    UNIQUE (user_id, device_token_hash)
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
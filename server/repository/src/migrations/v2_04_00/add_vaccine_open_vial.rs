@@ -0,0 +1,49 @@
+use crate::migrations::*;
+
+pub(crate) struct Migrate;
+
+impl MigrationFragment for Migrate {
+    fn identifier(&self) -> &'static str {
+        "add_vaccine_open_vial"
+    }
+
+    fn migrate(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+        if cfg!(feature = "postgres") {
+            sql!(
+                connection,
+                r#"
+                ALTER TYPE changelog_table_name ADD VALUE IF NOT EXISTS 'vaccine_open_vial';
+            "#
+            )?;
+        }
+
+        sql!(
+            connection,
+            r#"
+                ALTER TABLE vaccine_course
+                ADD COLUMN open_vial_discard_hours INTEGER;
+            "#
+        )?;
+
+        sql!(
+            connection,
+            r#"
+                CREATE TABLE vaccine_open_vial (
+                    id TEXT NOT NULL PRIMARY KEY,
+                    store_id TEXT NOT NULL REFERENCES store(id),
+                    stock_line_id TEXT NOT NULL REFERENCES stock_line(id),
+                    item_link_id TEXT NOT NULL REFERENCES item_link(id),
+                    vaccine_course_id TEXT NOT NULL REFERENCES vaccine_course(id),
+                    opened_datetime {DATETIME} NOT NULL,
+                    discard_after_datetime {DATETIME} NOT NULL,
+                    total_doses INTEGER NOT NULL,
+                    doses_remaining INTEGER NOT NULL,
+                    discarded_datetime {DATETIME},
+                    wastage_invoice_id TEXT REFERENCES invoice(id)
+                );
+            "#
+        )?;
+
+        Ok(())
+    }
+}
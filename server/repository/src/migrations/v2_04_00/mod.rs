@@ -1,13 +1,31 @@
 use super::{version::Version, Migration, MigrationFragment};
 
+mod add_allocation_rule_table;
+mod add_api_key_table;
+mod add_backorder_table;
+mod add_blind_stocktake_workflow;
 mod add_bundled_item_table;
 mod add_cold_storage_type_table;
+mod add_cycle_count_schedule_table;
 mod add_demographic_indicator_types_to_activity_log;
+mod add_drug_interaction_tables;
 mod add_expected_lifespan_to_assets;
+mod add_invoice_line_discrepancy_fields;
+mod add_invoice_payment_table;
 mod add_item_variant_id_to_stock_line_and_invoice_line;
+mod add_lmis_code_mapping_table;
+mod add_login_lockout_table;
 mod add_manual_requisition_line_fields;
+mod add_pick_list_tables;
+mod add_prescription_line_direction_table;
+mod add_price_list_tables;
 mod add_reason_option_table;
+mod add_recall_tables;
+mod add_serial_number_tables;
+mod add_stock_aggregate_tables;
 mod add_unserviceable_status_to_asset_status_enum;
+mod add_user_pin_table;
+mod add_vaccine_open_vial;
 mod delete_pack_variant;
 mod indicator_line_column_create_tables;
 mod indicator_value_create_table;
@@ -42,6 +60,24 @@ impl Migration for V2_04_00 {
             Box::new(indicator_value_create_table::Migrate),
             Box::new(add_bundled_item_table::Migrate),
             Box::new(add_demographic_indicator_types_to_activity_log::Migrate),
+            Box::new(add_vaccine_open_vial::Migrate),
+            Box::new(add_lmis_code_mapping_table::Migrate),
+            Box::new(add_cycle_count_schedule_table::Migrate),
+            Box::new(add_blind_stocktake_workflow::Migrate),
+            Box::new(add_api_key_table::Migrate),
+            Box::new(add_login_lockout_table::Migrate),
+            Box::new(add_user_pin_table::Migrate),
+            Box::new(add_stock_aggregate_tables::Migrate),
+            Box::new(add_prescription_line_direction_table::Migrate),
+            Box::new(add_drug_interaction_tables::Migrate),
+            Box::new(add_price_list_tables::Migrate),
+            Box::new(add_invoice_payment_table::Migrate),
+            Box::new(add_invoice_line_discrepancy_fields::Migrate),
+            Box::new(add_backorder_table::Migrate),
+            Box::new(add_allocation_rule_table::Migrate),
+            Box::new(add_pick_list_tables::Migrate),
+            Box::new(add_serial_number_tables::Migrate),
+            Box::new(add_recall_tables::Migrate),
         ]
     }
 }
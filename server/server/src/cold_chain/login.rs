@@ -2,7 +2,7 @@ use actix_web::{
     cookie::Cookie,
     http::header,
     web::{self, Data},
-    HttpResponse,
+    HttpRequest, HttpResponse,
 };
 use log::error;
 use mime_guess::mime;
@@ -25,11 +25,13 @@ pub struct LoginRequest {
 }
 
 pub async fn post_login(
+    request: HttpRequest,
     user_info: web::Json<LoginRequest>,
     service_provider: Data<ServiceProvider>,
     auth_data: Data<AuthData>,
 ) -> HttpResponse {
-    let cookie = match do_login(user_info, service_provider, auth_data).await {
+    let client_ip = request.peer_addr().map(|address| address.ip().to_string());
+    let cookie = match do_login(user_info, client_ip, service_provider, auth_data).await {
         Ok(cookie) => cookie,
         Err(error) => return HttpResponse::InternalServerError().body(format!("{:#?}", error)),
     };
@@ -44,6 +46,7 @@ pub async fn post_login(
 
 async fn do_login(
     user_info: web::Json<LoginRequest>,
+    client_ip: Option<String>,
     service_provider: Data<ServiceProvider>,
     auth_data: Data<AuthData>,
 ) -> Result<Option<Cookie<'static>>, RepositoryError> {
@@ -59,6 +62,7 @@ async fn do_login(
             username: user_info.username.clone(),
             password: user_info.password.clone(),
             central_server_url: sync_settings.url.clone(),
+            client_ip,
         },
         MIN_ERR_RESPONSE_TIME_SEC,
     )
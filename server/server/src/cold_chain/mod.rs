@@ -3,7 +3,7 @@ use actix_web::{
     HttpRequest, Result,
 };
 use service::{
-    auth::{validate_auth, AuthDeniedKind, AuthError, Resource, ResourceAccessRequest},
+    auth::{validate_auth_or_api_key, AuthDeniedKind, AuthError, Resource, ResourceAccessRequest},
     auth_data::AuthData,
     service_provider::{ServiceContext, ServiceProvider},
     user_account::UserAccountService,
@@ -42,13 +42,25 @@ fn validate_request(
     let service_context = service_provider
         .basic_context()
         .map_err(|err| AuthError::Denied(AuthDeniedKind::NotAuthenticated(err.to_string())))?;
-    let token = request
-        .cookie(COOKIE_NAME)
-        .map(|cookie| cookie.value().to_string());
+    // Integrations pass an API key as bearer token, the cookie is set by the login endpoint
+    let token = bearer_token(&request).or_else(|| {
+        request
+            .cookie(COOKIE_NAME)
+            .map(|cookie| cookie.value().to_string())
+    });
 
     validate_access(service_provider, &service_context, auth_data, token)
 }
 
+fn bearer_token(request: &HttpRequest) -> Option<String> {
+    request
+        .headers()
+        .get("Authorization")
+        .and_then(|header_value| header_value.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .map(|token| token.to_string())
+}
+
 /// Validates current user is authenticated and authorized
 pub fn validate_access(
     service_provider: &ServiceProvider,
@@ -57,23 +69,33 @@ pub fn validate_access(
     token: Option<String>,
 ) -> Result<(String, String), AuthError> {
     let user_service = UserAccountService::new(&service_context.connection);
-    let validated_user = validate_auth(auth_data, &token)?;
-    let store_id = match user_service.find_user_active_on_this_site(&validated_user.user_id)? {
-        Some(user) => {
-            let store_id = match user.default_store() {
-                Some(store) => store.store_row.id.clone(),
-                None => return Err(AuthError::Denied(AuthDeniedKind::NotAuthenticated(
-                    "No default store found for user, or default store is not active on current site".to_string(),
-                ))),
-            };
-            store_id
-        }
+    let validated_user = validate_auth_or_api_key(&service_context.connection, auth_data, &token)?;
+    let user = match user_service.find_user_active_on_this_site(&validated_user.user_id)? {
+        Some(user) => user,
         None => {
             return Err(AuthError::InternalError(
                 "User not found in database".to_string(),
             ))
         }
     };
+    let store_id = match &validated_user.api_key_scope {
+        // API keys act on the first store of their scope
+        Some(scope) => match scope.store_ids.first() {
+            Some(store_id) => store_id.clone(),
+            None => {
+                return Err(AuthError::Denied(AuthDeniedKind::NotAuthenticated(
+                    "Api key has no store in its scope".to_string(),
+                )))
+            }
+        },
+        None => match user.default_store() {
+            Some(store) => store.store_row.id.clone(),
+            None => return Err(AuthError::Denied(AuthDeniedKind::NotAuthenticated(
+                "No default store found for user, or default store is not active on current site"
+                    .to_string(),
+            ))),
+        },
+    };
 
     let access_request = ResourceAccessRequest {
         resource: Resource::ColdChainApi,
@@ -3,13 +3,16 @@ extern crate machine_uid;
 
 use crate::{
     certs::Certificates, cold_chain::config_cold_chain, configuration::get_or_create_token_secret,
-    cors::cors_policy, middleware::central_server_only, print::config_print,
-    serve_frontend::config_serve_frontend, static_files::config_static_files,
+    cors::cors_policy, metrics::config_metrics, middleware::central_server_only, oidc::config_oidc,
+    print::config_print, serve_frontend::config_serve_frontend, static_files::config_static_files,
     support::config_support, sync_on_central::config_sync_on_central,
     upload_fridge_tag::config_upload_fridge_tag,
 };
 
-use self::middleware::{compress as compress_middleware, logger as logger_middleware};
+use self::middleware::{
+    compress as compress_middleware, log_context as log_context_middleware,
+    logger as logger_middleware,
+};
 use actix_cors::Cors;
 use anyhow::Context;
 use extism::set_log_callback;
@@ -23,11 +26,13 @@ use repository::{get_storage_connection_manager, migrations::migrate};
 
 use service::{
     auth_data::AuthData,
+    oidc::OidcClient,
     plugin::validation::ValidatedPluginBucket,
     processors::Processors,
     service_provider::ServiceProvider,
     settings::{is_develop, ServerSettings, Settings},
     standard_reports::StandardReports,
+    stocktake::run_cycle_count_scheduler,
     sync::{
         file_sync_driver::FileSyncDriver,
         synchroniser_driver::{SiteIsInitialisedCallback, SynchroniserDriver},
@@ -45,7 +50,9 @@ pub mod configuration;
 pub mod cors;
 pub mod environment;
 mod logging;
+mod metrics;
 pub mod middleware;
+mod oidc;
 mod serve_frontend;
 pub mod static_files;
 pub mod support;
@@ -284,11 +291,21 @@ pub async fn start_server(
         force_trigger_sync_on_startup,
     );
     let file_sync_task = file_sync_driver.run(service_provider.clone().into_inner());
+    let cycle_count_task = actix_web::rt::spawn(run_cycle_count_scheduler(
+        service_provider.clone().into_inner(),
+    ));
+
+    let oidc_client = settings
+        .oidc
+        .clone()
+        .map(|oidc| Data::new(OidcClient::new(oidc)));
 
     let closure_settings = settings.clone();
     let mut http_server = HttpServer::new(move || {
         App::new()
             .app_data(Data::new(closure_settings.clone()))
+            // Inside the logger so the correlation id response header is set when it's logged
+            .wrap(log_context_middleware())
             .wrap(logger_middleware())
             .wrap(cors_policy(&closure_settings))
             .wrap(compress_middleware())
@@ -305,6 +322,8 @@ pub async fn start_server(
             .configure(config_sync_on_central)
             .configure(config_support)
             .configure(config_print)
+            .configure(config_oidc(oidc_client.clone()))
+            .configure(config_metrics(closure_settings.server.metrics_enabled))
             // Needs to be last to capture all unmatches routes
             .configure(config_serve_frontend)
     })
@@ -333,6 +352,7 @@ pub async fn start_server(
         Some(_) = off_switch.recv() => {},
         _ = synchroniser_task => unreachable!("Synchroniser unexpectedly stopped"),
         _ = file_sync_task => unreachable!("File sync unexpectedly stopped"),
+        _ = cycle_count_task => unreachable!("Cycle count scheduler unexpectedly stopped"),
         result = processors_task => unreachable!("Processor terminated ({:?})", result)
     };
 
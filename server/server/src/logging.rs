@@ -1,9 +1,24 @@
 #[cfg(not(target_os = "android"))]
 use std::env;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    sync::Mutex,
+};
 
-use service::settings::{Level, LogMode, LoggingSettings};
+use log::{Log, Metadata, Record};
+use service::{
+    log_context::StructuredLogLine,
+    settings::{Level, LogFormat, LogMode, LoggingSettings},
+};
 use simple_log::LogConfigBuilder;
 
+const DEFAULT_LOG_FILE: &str = "remote_server.log";
+const DEFAULT_LOG_DIR: &str = "log";
+const DEFAULT_MAX_FILE_COUNT: i64 = 10;
+const DEFAULT_MAX_FILE_SIZE: usize = 1;
+
 // Can use log4rs to extend logging functionality beyond what is available in current
 // log crate since simple-log is based on log4rs.
 pub fn logging_init(settings: Option<LoggingSettings>, level: Option<Level>) {
@@ -13,6 +28,11 @@ pub fn logging_init(settings: Option<LoggingSettings>, level: Option<Level>) {
     ));
 
     let log_level = level.unwrap_or(settings.level.clone());
+    if settings.format == LogFormat::Json {
+        json_logging_init(&settings, log_level);
+        return;
+    }
+
     let config = match settings.mode {
         LogMode::File => file_logger(&settings)
             .level(log_level.to_string())
@@ -28,27 +48,159 @@ pub fn logging_init(settings: Option<LoggingSettings>, level: Option<Level>) {
     simple_log::new(config).expect("Unable to initialise logger");
 }
 
-fn file_logger(settings: &LoggingSettings) -> LogConfigBuilder {
-    let default_log_file = "remote_server.log".to_string();
-    let default_log_dir = "log".to_string();
-    let default_max_file_count = 10;
-    let default_max_file_size = 1;
-
+fn log_file_path(settings: &LoggingSettings) -> PathBuf {
     // Note: the file_split will panic if the path separator isn't appended
     // and the path separator has to be unix-style, even on windows
-    let log_dir = format!("{}/", settings.directory.clone().unwrap_or(default_log_dir),);
+    let log_dir = format!(
+        "{}/",
+        settings
+            .directory
+            .clone()
+            .unwrap_or(DEFAULT_LOG_DIR.to_string()),
+    );
     #[cfg(not(target_os = "android"))]
     let log_path = env::current_dir().unwrap_or_default().join(log_dir);
     // We are given the full path when running on android
     #[cfg(target_os = "android")]
     let log_path = std::path::PathBuf::from(&log_dir);
-    let log_file = settings.filename.clone().unwrap_or(default_log_file);
-    let log_file = log_path.join(log_file).to_string_lossy().to_string();
-    let max_file_count = settings.max_file_count.unwrap_or(default_max_file_count);
-    let max_file_size = settings.max_file_size.unwrap_or(default_max_file_size);
+    let log_file = settings
+        .filename
+        .clone()
+        .unwrap_or(DEFAULT_LOG_FILE.to_string());
+    log_path.join(log_file)
+}
+
+fn file_logger(settings: &LoggingSettings) -> LogConfigBuilder {
+    let log_file = log_file_path(settings).to_string_lossy().to_string();
+    let max_file_count = settings.max_file_count.unwrap_or(DEFAULT_MAX_FILE_COUNT);
+    let max_file_size = settings.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE);
 
     LogConfigBuilder::builder()
         .path(log_file)
         .size(max_file_size as u64)
         .roll_count(max_file_count as u32)
 }
+
+fn json_logging_init(settings: &LoggingSettings, level: Level) {
+    let file = match settings.mode {
+        LogMode::Console => None,
+        LogMode::File | LogMode::All => Some(Mutex::new(
+            RollingFile::open(
+                log_file_path(settings),
+                settings.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE) as u64 * 1024 * 1024,
+                settings.max_file_count.unwrap_or(DEFAULT_MAX_FILE_COUNT) as usize,
+            )
+            .expect("Unable to open log file"),
+        )),
+    };
+    let logger = JsonLogger {
+        console: !matches!(settings.mode, LogMode::File),
+        file,
+    };
+
+    log::set_logger(Box::leak(Box::new(logger))).expect("Unable to initialise logger");
+    log::set_max_level(level.to_level_filter());
+}
+
+/// Writes each record as a `StructuredLogLine`, the max level is controlled by `log::set_max_level`
+struct JsonLogger {
+    console: bool,
+    file: Option<Mutex<RollingFile>>,
+}
+
+impl Log for JsonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line =
+            StructuredLogLine::new(record.level(), record.target(), record.args().to_string());
+        let Ok(line) = serde_json::to_string(&line) else {
+            return;
+        };
+
+        if self.console {
+            println!("{}", line);
+        }
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                // Nowhere to report a failure to write the log
+                let _ = file.write_line(&line);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                let _ = file.file.flush();
+            }
+        }
+    }
+}
+
+/// Log file that is rolled over to `<file>.1` .. `<file>.<max_file_count>` when it reaches
+/// max size, the oldest file is dropped
+struct RollingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    max_file_count: usize,
+}
+
+impl RollingFile {
+    fn open(path: PathBuf, max_size: u64, max_file_count: usize) -> std::io::Result<Self> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+
+        Ok(RollingFile {
+            path,
+            file,
+            size,
+            max_size,
+            max_file_count,
+        })
+    }
+
+    fn rolled_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", index));
+        path.into()
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 >= self.max_size {
+            self.roll()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    fn roll(&mut self) -> std::io::Result<()> {
+        if self.max_file_count > 0 {
+            for index in (1..self.max_file_count).rev() {
+                let from = self.rolled_path(index);
+                if from.exists() {
+                    fs::rename(from, self.rolled_path(index + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rolled_path(1))?;
+        }
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use actix_web::{
+    web::{self, Data},
+    HttpResponse,
+};
+use repository::{DatetimeFilter, SyncBufferFilter, SyncBufferRepository};
+use service::service_provider::ServiceProvider;
+use util::metrics::{render_metrics, Gauge};
+
+/// Metrics are opt-in (`server.metrics_enabled`), the route is not registered otherwise
+pub fn config_metrics(enabled: bool) -> impl FnOnce(&mut web::ServiceConfig) {
+    move |cfg| {
+        if enabled {
+            cfg.route("/metrics", web::get().to(get_metrics));
+        }
+    }
+}
+
+async fn get_metrics(service_provider: Data<ServiceProvider>) -> HttpResponse {
+    // Sampled before getting a connection for the sync buffer query below
+    let (connections, idle_connections) = service_provider.connection_manager.pool_state();
+    let mut gauges = vec![
+        Gauge {
+            name: "omsupply_db_pool_connections",
+            help: "Number of open connections in the DB pool",
+            value: connections as f64,
+        },
+        Gauge {
+            name: "omsupply_db_pool_idle_connections",
+            help: "Number of idle connections in the DB pool",
+            value: idle_connections as f64,
+        },
+    ];
+
+    let sync_buffer_backlog = service_provider.basic_context().ok().and_then(|ctx| {
+        SyncBufferRepository::new(&ctx.connection)
+            .count(Some(
+                SyncBufferFilter::new().integration_datetime(DatetimeFilter::is_null(true)),
+            ))
+            .ok()
+    });
+    match sync_buffer_backlog {
+        Some(count) => gauges.push(Gauge {
+            name: "omsupply_sync_buffer_pending_records",
+            help: "Number of sync buffer records waiting to be integrated",
+            value: count as f64,
+        }),
+        None => log::error!("Unable to count sync buffer records for metrics"),
+    }
+
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(render_metrics(&gauges))
+}
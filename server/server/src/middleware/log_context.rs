@@ -0,0 +1,146 @@
+use std::future::{ready, Ready};
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderName, HeaderValue},
+    Error,
+};
+use futures_util::future::LocalBoxFuture;
+use service::log_context::{LogContext, CORRELATION_ID_HEADER};
+
+/// Attaches a correlation id to log lines produced while handling a request, the id is taken from
+/// the request header if the client provided one and is returned in the response header
+#[derive(Debug, Default)]
+pub struct LogContextTransform {}
+
+impl<S, B> Transform<S, ServiceRequest> for LogContextTransform
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = LogContextMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(LogContextMiddleware { service }))
+    }
+}
+
+#[derive(Debug)]
+pub struct LogContextMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for LogContextMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let correlation_id = req
+            .headers()
+            .get(CORRELATION_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+        let context = LogContext::new(correlation_id);
+        let correlation_id = context.correlation_id.clone();
+
+        // Inner services can do work when called, not only when their future is polled
+        Box::pin(context.sync_scope(|| {
+            let fut = self.service.call(req);
+            LogContext::in_current_scope(async move {
+                let mut response = fut.await?;
+                if let Ok(value) = HeaderValue::from_str(&correlation_id) {
+                    response
+                        .headers_mut()
+                        .insert(HeaderName::from_static(CORRELATION_ID_HEADER), value);
+                }
+                Ok(response)
+            })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use actix_web::{test, web, App, HttpResponse};
+    use log::{Log, Metadata, Record};
+    use service::log_context::{StructuredLogLine, CORRELATION_ID_HEADER};
+
+    use crate::middleware::{log_context, logger};
+
+    static LINES: Mutex<Vec<StructuredLogLine>> = Mutex::new(Vec::new());
+
+    struct CaptureLogger;
+
+    impl Log for CaptureLogger {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record) {
+            LINES.lock().unwrap().push(StructuredLogLine::new(
+                record.level(),
+                record.target(),
+                record.args().to_string(),
+            ));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[actix_rt::test]
+    async fn request_log_lines() {
+        log::set_logger(&CaptureLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Info);
+
+        // Same order as the server
+        let app = test::init_service(App::new().wrap(log_context()).wrap(logger()).route(
+            "/",
+            web::get().to(|| async {
+                log::info!("handling request");
+                HttpResponse::Ok().finish()
+            }),
+        ))
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri("/")
+            .insert_header((CORRELATION_ID_HEADER, "request_a"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(
+            response.headers().get(CORRELATION_ID_HEADER).unwrap(),
+            "request_a"
+        );
+        // Request line is logged once the body is done with
+        test::read_body(response).await;
+
+        let lines = LINES.lock().unwrap().clone();
+        let handler_line = lines
+            .iter()
+            .find(|line| line.message == "handling request")
+            .unwrap();
+        assert_eq!(handler_line.correlation_id, Some("request_a".to_string()));
+
+        let request_line = lines
+            .iter()
+            .find(|line| line.target.starts_with("actix_web::middleware::logger"))
+            .unwrap();
+        assert!(request_line.message.contains("\"GET / HTTP/1.1\" 200"));
+        assert!(request_line.message.ends_with(" request_a"));
+    }
+}
@@ -1,12 +1,23 @@
-pub mod content_length_limit;
 mod central_server_only;
+pub mod content_length_limit;
+mod log_context;
 
 pub fn compress() -> actix_web::middleware::Compress {
     actix_web::middleware::Compress::default()
 }
 
+/// Default actix format with the correlation id (response header) appended. Request lines are
+/// logged once the response body has been sent, after the log context of the request has ended,
+/// so the id is part of the message rather than the structured `correlation_id` field
 pub fn logger() -> actix_web::middleware::Logger {
-    actix_web::middleware::Logger::default()
+    actix_web::middleware::Logger::new(&format!(
+        r#"%a "%r" %s %b "%{{Referer}}i" "%{{User-Agent}}i" %T %{{{}}}o"#,
+        service::log_context::CORRELATION_ID_HEADER
+    ))
+}
+
+pub fn log_context() -> log_context::LogContextTransform {
+    log_context::LogContextTransform::default()
 }
 
 pub(crate) fn central_server_only() -> central_server_only::CentralServerOnly {
@@ -15,4 +26,4 @@ pub(crate) fn central_server_only() -> central_server_only::CentralServerOnly {
 
 pub fn limit_content_length() -> content_length_limit::ContentLengthLimit {
     content_length_limit::ContentLengthLimit::default()
-}
\ No newline at end of file
+}
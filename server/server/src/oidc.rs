@@ -0,0 +1,177 @@
+use actix_web::{
+    cookie::{time::Duration, Cookie, SameSite},
+    get,
+    http::header,
+    web::{self, Data},
+    HttpRequest, HttpResponse,
+};
+use chrono::Utc;
+use log::error;
+use service::{
+    auth_data::AuthData,
+    login::{LoginError, LoginService},
+    oidc::{OidcClient, OidcError, OidcIdentity},
+    service_provider::ServiceProvider,
+};
+
+/// Binds a sign in to the browser that started it
+const STATE_COOKIE_NAME: &str = "oidc_state";
+const STATE_COOKIE_PATH: &str = "/oidc";
+
+/// Endpoints are always registered, they respond with not found if single sign-on isn't configured
+pub fn config_oidc(oidc_client: Option<Data<OidcClient>>) -> impl FnOnce(&mut web::ServiceConfig) {
+    |cfg| {
+        if let Some(oidc_client) = oidc_client {
+            cfg.app_data(oidc_client);
+        }
+        cfg.service(login).service(callback);
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct CallbackParams {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// Lax, the identity provider redirects back with a cross-site top level navigation
+fn state_cookie(state: &str, no_ssl: bool) -> Cookie<'static> {
+    Cookie::build(STATE_COOKIE_NAME, state.to_string())
+        .path(STATE_COOKIE_PATH)
+        .http_only(true)
+        .secure(!no_ssl)
+        .same_site(SameSite::Lax)
+        .max_age(Duration::minutes(10))
+        .finish()
+}
+
+fn redirect(location: &str) -> HttpResponse {
+    HttpResponse::Found()
+        .append_header((header::LOCATION, location.to_string()))
+        .finish()
+}
+
+/// Sends the user back to the frontend login page, where local credentials can still be used
+fn redirect_with_error(oidc_client: &OidcClient, sso_error: &str) -> HttpResponse {
+    let frontend_url = &oidc_client.settings().frontend_url;
+    let separator = if frontend_url.contains('?') { '&' } else { '?' };
+    redirect(&format!(
+        "{}{}sso_error={}",
+        frontend_url, separator, sso_error
+    ))
+}
+
+#[get("/oidc/login")]
+async fn login(oidc_client: Option<Data<OidcClient>>, auth_data: Data<AuthData>) -> HttpResponse {
+    let Some(oidc_client) = oidc_client else {
+        return HttpResponse::NotFound().body("Single sign-on is not configured");
+    };
+
+    match oidc_client.authorization_request().await {
+        Ok(request) => HttpResponse::Found()
+            .append_header((header::LOCATION, request.url.to_string()))
+            .cookie(state_cookie(&request.state, auth_data.no_ssl))
+            .finish(),
+        Err(err) => {
+            error!("Failed to start single sign-on: {:?}", err);
+            redirect_with_error(&oidc_client, error_code(&err))
+        }
+    }
+}
+
+#[get("/oidc/callback")]
+async fn callback(
+    request: HttpRequest,
+    params: web::Query<CallbackParams>,
+    oidc_client: Option<Data<OidcClient>>,
+    service_provider: Data<ServiceProvider>,
+    auth_data: Data<AuthData>,
+) -> HttpResponse {
+    let Some(oidc_client) = oidc_client else {
+        return HttpResponse::NotFound().body("Single sign-on is not configured");
+    };
+
+    let browser_state = request
+        .cookie(STATE_COOKIE_NAME)
+        .map(|cookie| cookie.value().to_string());
+    let identity = match exchange_code(&oidc_client, params.into_inner(), browser_state).await {
+        Ok(identity) => identity,
+        Err(err) => {
+            error!("Single sign-on failed: {:?}", err);
+            return redirect_with_error(&oidc_client, error_code(&err));
+        }
+    };
+
+    let pair = match LoginService::sso_login(&service_provider, &auth_data, &identity) {
+        Ok(pair) => pair,
+        Err(err) => {
+            error!(
+                "Single sign-on failed for user {}: {:?}",
+                identity.username, err
+            );
+            let sso_error = match err {
+                LoginError::LoginFailure(_) => "unknown_user",
+                _ => "internal_error",
+            };
+            return redirect_with_error(&oidc_client, sso_error);
+        }
+    };
+
+    // Same cookie as set by the authToken query, the frontend picks up the session by refreshing
+    let now = Utc::now().timestamp() as usize;
+    let secure = if auth_data.no_ssl { "" } else { "; Secure" };
+    let mut used_state_cookie = state_cookie("", auth_data.no_ssl);
+    used_state_cookie.make_removal();
+    HttpResponse::Found()
+        .append_header((
+            header::LOCATION,
+            oidc_client.settings().frontend_url.clone(),
+        ))
+        .append_header((
+            header::SET_COOKIE,
+            format!(
+                "refresh_token={}; Max-Age={}{}; HttpOnly; SameSite=Strict; Path=/",
+                pair.refresh,
+                pair.refresh_expiry_date.saturating_sub(now),
+                secure
+            ),
+        ))
+        .cookie(used_state_cookie)
+        .finish()
+}
+
+async fn exchange_code(
+    oidc_client: &OidcClient,
+    params: CallbackParams,
+    browser_state: Option<String>,
+) -> Result<OidcIdentity, OidcError> {
+    if let Some(error) = params.error {
+        return Err(OidcError::AuthorizationDenied(format!(
+            "{}: {}",
+            error,
+            params.error_description.unwrap_or_default()
+        )));
+    }
+    let (Some(code), Some(state)) = (params.code, params.state) else {
+        return Err(OidcError::UnknownState);
+    };
+
+    oidc_client
+        .exchange_code(&code, &state, browser_state.as_deref())
+        .await
+}
+
+fn error_code(error: &OidcError) -> &'static str {
+    match error {
+        OidcError::IdentityProviderUnreachable(_) => "provider_unreachable",
+        OidcError::AuthorizationDenied(_) => "authorization_denied",
+        OidcError::UnknownState | OidcError::StateMismatch => "unknown_state",
+        OidcError::InvalidProviderResponse(_)
+        | OidcError::TokenExchangeFailed(_)
+        | OidcError::InvalidIdToken(_)
+        | OidcError::MissingUsernameClaim(_) => "invalid_response",
+        OidcError::InternalError(_) => "internal_error",
+    }
+}
@@ -0,0 +1,193 @@
+use repository::{
+    item_variant::item_variant_row::ItemVariantRowRepository, AllocationRuleRow,
+    AllocationRuleRowRepository, AllocationStrategyType, NameLinkRowRepository,
+    NameTagJoinRepository, NameTagRowRepository, RepositoryError, StorageConnection,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UpsertAllocationRule {
+    pub id: String,
+    /// Applies to every customer of the store when not set
+    pub name_tag_id: Option<String>,
+    pub strategy: AllocationStrategyType,
+    pub expiring_soon_days: Option<i32>,
+    pub min_remaining_shelf_life_days: Option<i32>,
+    pub avoid_item_variant_id: Option<String>,
+    pub priority: i32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertAllocationRuleError {
+    NotThisStoreRule,
+    NameTagDoesNotExist,
+    ItemVariantDoesNotExist,
+    /// Day counts can't be negative
+    InvalidNumberOfDays,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DeleteAllocationRuleError {
+    RuleDoesNotExist,
+    NotThisStoreRule,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for UpsertAllocationRuleError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertAllocationRuleError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for DeleteAllocationRuleError {
+    fn from(error: RepositoryError) -> Self {
+        DeleteAllocationRuleError::DatabaseError(error)
+    }
+}
+
+pub trait AllocationRuleServiceTrait: Sync + Send {
+    /// Highest priority first
+    fn get_allocation_rules(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Vec<AllocationRuleRow>, RepositoryError> {
+        AllocationRuleRowRepository::new(&ctx.connection).find_many_by_store_id(&ctx.store_id)
+    }
+
+    fn upsert_allocation_rule(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertAllocationRule,
+    ) -> Result<AllocationRuleRow, UpsertAllocationRuleError> {
+        upsert_allocation_rule(ctx, input)
+    }
+
+    fn delete_allocation_rule(
+        &self,
+        ctx: &ServiceContext,
+        id: String,
+    ) -> Result<String, DeleteAllocationRuleError> {
+        delete_allocation_rule(ctx, id)
+    }
+}
+
+pub struct AllocationRuleService {}
+impl AllocationRuleServiceTrait for AllocationRuleService {}
+
+pub fn upsert_allocation_rule(
+    ctx: &ServiceContext,
+    input: UpsertAllocationRule,
+) -> Result<AllocationRuleRow, UpsertAllocationRuleError> {
+    let rule = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let repository = AllocationRuleRowRepository::new(connection);
+            if let Some(existing) = repository.find_one_by_id(&input.id)? {
+                if existing.store_id != ctx.store_id {
+                    return Err(UpsertAllocationRuleError::NotThisStoreRule);
+                }
+            }
+            if let Some(name_tag_id) = &input.name_tag_id {
+                if NameTagRowRepository::new(connection)
+                    .find_one_by_id(name_tag_id)?
+                    .is_none()
+                {
+                    return Err(UpsertAllocationRuleError::NameTagDoesNotExist);
+                }
+            }
+            if let Some(item_variant_id) = &input.avoid_item_variant_id {
+                if ItemVariantRowRepository::new(connection)
+                    .find_one_by_id(item_variant_id)?
+                    .is_none()
+                {
+                    return Err(UpsertAllocationRuleError::ItemVariantDoesNotExist);
+                }
+            }
+            let days = [
+                input.expiring_soon_days,
+                input.min_remaining_shelf_life_days,
+            ];
+            if days.iter().flatten().any(|days| *days < 0) {
+                return Err(UpsertAllocationRuleError::InvalidNumberOfDays);
+            }
+
+            let UpsertAllocationRule {
+                id,
+                name_tag_id,
+                strategy,
+                expiring_soon_days,
+                min_remaining_shelf_life_days,
+                avoid_item_variant_id,
+                priority,
+            } = input;
+            let rule = AllocationRuleRow {
+                id,
+                store_id: ctx.store_id.clone(),
+                name_tag_id,
+                strategy,
+                expiring_soon_days,
+                min_remaining_shelf_life_days,
+                avoid_item_variant_id,
+                priority,
+            };
+            repository.upsert_one(&rule)?;
+
+            Ok(rule)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(rule)
+}
+
+pub fn delete_allocation_rule(
+    ctx: &ServiceContext,
+    id: String,
+) -> Result<String, DeleteAllocationRuleError> {
+    let repository = AllocationRuleRowRepository::new(&ctx.connection);
+    let rule = repository
+        .find_one_by_id(&id)?
+        .ok_or(DeleteAllocationRuleError::RuleDoesNotExist)?;
+    if rule.store_id != ctx.store_id {
+        return Err(DeleteAllocationRuleError::NotThisStoreRule);
+    }
+    repository.delete(&id)?;
+
+    Ok(id)
+}
+
+/// Rule for a customer of the store: the highest priority rule for one of the customer's name
+/// tags, otherwise the store rule without a name tag. None means the default FEFO allocation
+pub(crate) fn get_allocation_rule(
+    connection: &StorageConnection,
+    store_id: &str,
+    name_link_id: &str,
+) -> Result<Option<AllocationRuleRow>, RepositoryError> {
+    let rules = AllocationRuleRowRepository::new(connection).find_many_by_store_id(store_id)?;
+    if rules.is_empty() {
+        return Ok(None);
+    }
+
+    let name_tag_ids: Vec<String> =
+        match NameLinkRowRepository::new(connection).find_one_by_id(name_link_id)? {
+            Some(name_link) => NameTagJoinRepository::new(connection)
+                .find_many_by_name_id(&name_link.name_id)?
+                .into_iter()
+                .map(|join| join.name_tag_id)
+                .collect(),
+            None => Vec::new(),
+        };
+
+    let tag_rule = rules.iter().find(|rule| {
+        rule.name_tag_id
+            .as_ref()
+            .is_some_and(|name_tag_id| name_tag_ids.contains(name_tag_id))
+    });
+    let store_rule = rules.iter().find(|rule| rule.name_tag_id.is_none());
+
+    Ok(tag_rule.or(store_rule).cloned())
+}
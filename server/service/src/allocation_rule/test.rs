@@ -0,0 +1,219 @@
+#[cfg(test)]
+mod allocation_rule {
+    use repository::{
+        mock::{
+            mock_item_a, mock_item_a_variant_1, mock_location_1, mock_location_3, mock_name_a,
+            mock_name_b, mock_name_tag_1, mock_store_a, mock_store_b, MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        AllocationRuleRow, AllocationRuleRowRepository, AllocationStrategyType, InvoiceLineRow,
+        InvoiceLineType, InvoiceRow, InvoiceType, NameTagJoinRow, StockLineRow,
+    };
+    use util::inline_init;
+
+    use crate::{
+        allocation_rule::{
+            get_allocation_rule, DeleteAllocationRuleError, UpsertAllocationRule,
+            UpsertAllocationRuleError,
+        },
+        invoice_line::outbound_shipment_unallocated_line::AllocationDecisionReason,
+        service_provider::ServiceProvider,
+        test_helpers::stock_line_expiring_in,
+    };
+
+    fn other_store_rule() -> AllocationRuleRow {
+        AllocationRuleRow {
+            id: "other_store_rule".to_string(),
+            store_id: mock_store_b().id,
+            ..Default::default()
+        }
+    }
+
+    fn invoice() -> InvoiceRow {
+        inline_init(|r: &mut InvoiceRow| {
+            r.id = "allocation_rule_invoice".to_string();
+            r.store_id = mock_store_a().id;
+            r.name_link_id = mock_name_a().id;
+            r.r#type = InvoiceType::OutboundShipment;
+        })
+    }
+
+    fn unallocated_line() -> InvoiceLineRow {
+        inline_init(|r: &mut InvoiceLineRow| {
+            r.id = "allocation_rule_line".to_string();
+            r.invoice_id = invoice().id;
+            r.item_link_id = mock_item_a().id;
+            r.r#type = InvoiceLineType::UnallocatedStock;
+            r.number_of_packs = 15.0;
+            r.pack_size = 1.0;
+        })
+    }
+
+    fn stock_line(id: &str, location_id: Option<String>, expiry_days: i64) -> StockLineRow {
+        stock_line_expiring_in(id, &mock_item_a().id, location_id, expiry_days)
+    }
+
+    #[actix_rt::test]
+    async fn allocation_rule() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "allocation_rule",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.name_tag_joins = vec![NameTagJoinRow {
+                    id: "name_a_tag_1".to_string(),
+                    name_link_id: mock_name_a().id,
+                    name_tag_id: mock_name_tag_1().id,
+                }];
+                r.invoices = vec![invoice()];
+                r.invoice_lines = vec![unallocated_line()];
+                r.stock_lines = vec![
+                    // Expires first but has a short shelf life
+                    stock_line("short_shelf_life", Some(mock_location_3().id), 20),
+                    inline_init(|r: &mut StockLineRow| {
+                        *r = stock_line("avoided_variant", Some(mock_location_1().id), 300);
+                        r.item_variant_id = Some(mock_item_a_variant_1().id);
+                    }),
+                    stock_line("location_3", Some(mock_location_3().id), 200),
+                    stock_line("location_1", Some(mock_location_1().id), 400),
+                    stock_line("no_location", None, 100),
+                ];
+            }),
+        )
+        .await;
+
+        AllocationRuleRowRepository::new(&connection)
+            .upsert_one(&other_store_rule())
+            .unwrap();
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = &service_provider.allocation_rule_service;
+
+        // Errors
+        assert_eq!(
+            service.upsert_allocation_rule(
+                &context,
+                UpsertAllocationRule {
+                    id: other_store_rule().id,
+                    ..Default::default()
+                }
+            ),
+            Err(UpsertAllocationRuleError::NotThisStoreRule)
+        );
+        assert_eq!(
+            service.upsert_allocation_rule(
+                &context,
+                UpsertAllocationRule {
+                    id: "rule".to_string(),
+                    name_tag_id: Some("invalid".to_string()),
+                    ..Default::default()
+                }
+            ),
+            Err(UpsertAllocationRuleError::NameTagDoesNotExist)
+        );
+        assert_eq!(
+            service.upsert_allocation_rule(
+                &context,
+                UpsertAllocationRule {
+                    id: "rule".to_string(),
+                    avoid_item_variant_id: Some("invalid".to_string()),
+                    ..Default::default()
+                }
+            ),
+            Err(UpsertAllocationRuleError::ItemVariantDoesNotExist)
+        );
+        assert_eq!(
+            service.upsert_allocation_rule(
+                &context,
+                UpsertAllocationRule {
+                    id: "rule".to_string(),
+                    min_remaining_shelf_life_days: Some(-1),
+                    ..Default::default()
+                }
+            ),
+            Err(UpsertAllocationRuleError::InvalidNumberOfDays)
+        );
+        assert_eq!(
+            service.delete_allocation_rule(&context, "invalid".to_string()),
+            Err(DeleteAllocationRuleError::RuleDoesNotExist)
+        );
+        assert_eq!(
+            service.delete_allocation_rule(&context, other_store_rule().id),
+            Err(DeleteAllocationRuleError::NotThisStoreRule)
+        );
+
+        // Rule resolution, name tag rule before store rule
+        service
+            .upsert_allocation_rule(
+                &context,
+                UpsertAllocationRule {
+                    id: "store_rule".to_string(),
+                    strategy: AllocationStrategyType::Fifo,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        service
+            .upsert_allocation_rule(
+                &context,
+                UpsertAllocationRule {
+                    id: "tag_rule".to_string(),
+                    name_tag_id: Some(mock_name_tag_1().id),
+                    strategy: AllocationStrategyType::PickPath,
+                    min_remaining_shelf_life_days: Some(30),
+                    avoid_item_variant_id: Some(mock_item_a_variant_1().id),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(service.get_allocation_rules(&context).unwrap().len(), 2);
+
+        let rule = |name_link_id: &str| {
+            get_allocation_rule(&connection, &mock_store_a().id, name_link_id)
+                .unwrap()
+                .map(|rule| rule.id)
+        };
+        assert_eq!(rule(&mock_name_a().id), Some("tag_rule".to_string()));
+        assert_eq!(rule(&mock_name_b().id), Some("store_rule".to_string()));
+
+        // Allocation with the pick path rule of name_a
+        let result = service_provider
+            .invoice_line_service
+            .allocate_outbound_shipment_unallocated_line(&context, unallocated_line().id)
+            .unwrap();
+        assert_eq!(result.strategy, AllocationStrategyType::PickPath);
+        assert_eq!(result.allocation_rule_id, Some("tag_rule".to_string()));
+        let decisions: Vec<(&str, AllocationDecisionReason)> = result
+            .stock_line_decisions
+            .iter()
+            .map(|decision| (decision.stock_line_id.as_str(), decision.reason))
+            .collect();
+        assert_eq!(
+            decisions[..4],
+            [
+                (
+                    "avoided_variant",
+                    AllocationDecisionReason::SkippedItemVariant
+                ),
+                ("location_1", AllocationDecisionReason::Allocated),
+                (
+                    "short_shelf_life",
+                    AllocationDecisionReason::SkippedShortShelfLife
+                ),
+                ("location_3", AllocationDecisionReason::Allocated),
+            ]
+        );
+        assert!(decisions.contains(&("no_location", AllocationDecisionReason::NotNeeded)));
+        assert_eq!(result.stock_line_decisions[3].number_of_packs, 5.0);
+        assert_eq!(result.inserts.len(), 2);
+
+        // Delete
+        assert_eq!(
+            service.delete_allocation_rule(&context, "tag_rule".to_string()),
+            Ok("tag_rule".to_string())
+        );
+        assert_eq!(rule(&mock_name_a().id), Some("store_rule".to_string()));
+    }
+}
@@ -0,0 +1,61 @@
+use chrono::{Duration, NaiveDateTime};
+use repository::{ApiKeyRow, ApiKeyRowRepository, RepositoryError, StorageConnection};
+use util::hash::sha256;
+
+use super::ApiKeyScope;
+
+/// Last used datetime is only updated once per interval, to avoid writing on every request
+const LAST_USED_UPDATE_INTERVAL_SECONDS: i64 = 60;
+
+#[derive(Debug, PartialEq)]
+pub enum ApiKeyAuthError {
+    ApiKeyNotFound,
+    ApiKeyRevoked,
+    ApiKeyExpired,
+    InvalidScope(String),
+    DatabaseError(RepositoryError),
+}
+
+pub struct AuthenticatedApiKey {
+    pub api_key: ApiKeyRow,
+    pub scope: ApiKeyScope,
+}
+
+/// Looks up an API key and checks it can still be used
+pub fn authenticate_api_key(
+    connection: &StorageConnection,
+    key: &str,
+    now: NaiveDateTime,
+) -> Result<AuthenticatedApiKey, ApiKeyAuthError> {
+    let repo = ApiKeyRowRepository::new(connection);
+    let api_key = repo
+        .find_one_by_key_hash(&sha256(key))?
+        .ok_or(ApiKeyAuthError::ApiKeyNotFound)?;
+
+    if api_key.revoked_datetime.is_some() {
+        return Err(ApiKeyAuthError::ApiKeyRevoked);
+    }
+    if let Some(expiry_datetime) = api_key.expiry_datetime {
+        if expiry_datetime <= now {
+            return Err(ApiKeyAuthError::ApiKeyExpired);
+        }
+    }
+    let scope = ApiKeyScope::from_row(&api_key)
+        .map_err(|err| ApiKeyAuthError::InvalidScope(err.to_string()))?;
+
+    let update_last_used = match api_key.last_used_datetime {
+        Some(last_used) => now - last_used >= Duration::seconds(LAST_USED_UPDATE_INTERVAL_SECONDS),
+        None => true,
+    };
+    if update_last_used {
+        repo.update_last_used_datetime(&api_key.id, now)?;
+    }
+
+    Ok(AuthenticatedApiKey { api_key, scope })
+}
+
+impl From<RepositoryError> for ApiKeyAuthError {
+    fn from(error: RepositoryError) -> Self {
+        ApiKeyAuthError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,159 @@
+use chrono::{NaiveDateTime, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use repository::{
+    ApiKeyRow, ApiKeyRowRepository, EqualFilter, PermissionType, RepositoryError,
+    StorageConnection, TransactionError, UserPermissionFilter, UserPermissionRepository,
+};
+use util::hash::sha256;
+
+use crate::{auth::Resource, service_provider::ServiceContext};
+
+use super::API_KEY_PREFIX;
+
+const KEY_LENGTH: usize = 40;
+/// Number of characters of the key that are stored in plain text to identify the key
+const KEY_PREFIX_LENGTH: usize = 12;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct CreateApiKey {
+    pub id: String,
+    pub name: String,
+    pub store_ids: Vec<String>,
+    /// Names of the `Resource`s the key can access, e.g. "QueryStocktake"
+    pub resources: Vec<String>,
+    /// Key never expires if not set
+    pub expiry_datetime: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CreatedApiKey {
+    pub api_key: ApiKeyRow,
+    /// The plain key, it's not stored and can't be retrieved later on
+    pub key: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CreateApiKeyError {
+    ApiKeyAlreadyExists,
+    NameCannotBeEmpty,
+    NoStoresSpecified,
+    NoResourcesSpecified,
+    UnknownResource(String),
+    /// The user creating the key doesn't have access to the store
+    StoreNotAccessible(String),
+    ExpiryDatetimeInThePast,
+    DatabaseError(RepositoryError),
+}
+
+pub fn create_api_key(
+    ctx: &ServiceContext,
+    input: CreateApiKey,
+) -> Result<CreatedApiKey, CreateApiKeyError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let resources = validate(connection, &ctx.user_id, &input)?;
+            let key = generate_key();
+            let api_key = generate(&ctx.user_id, &key, resources, input);
+            ApiKeyRowRepository::new(connection).upsert_one(&api_key)?;
+
+            Ok(CreatedApiKey { api_key, key })
+        })
+        .map_err(|error: TransactionError<CreateApiKeyError>| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    user_id: &str,
+    input: &CreateApiKey,
+) -> Result<Vec<Resource>, CreateApiKeyError> {
+    if ApiKeyRowRepository::new(connection)
+        .find_one_by_id(&input.id)?
+        .is_some()
+    {
+        return Err(CreateApiKeyError::ApiKeyAlreadyExists);
+    }
+    if input.name.trim().is_empty() {
+        return Err(CreateApiKeyError::NameCannotBeEmpty);
+    }
+    if input.store_ids.is_empty() {
+        return Err(CreateApiKeyError::NoStoresSpecified);
+    }
+    if input.resources.is_empty() {
+        return Err(CreateApiKeyError::NoResourcesSpecified);
+    }
+    if let Some(expiry_datetime) = input.expiry_datetime {
+        if expiry_datetime <= Utc::now().naive_utc() {
+            return Err(CreateApiKeyError::ExpiryDatetimeInThePast);
+        }
+    }
+
+    let resources = input
+        .resources
+        .iter()
+        .map(|name| {
+            Resource::from_name(name).ok_or(CreateApiKeyError::UnknownResource(name.clone()))
+        })
+        .collect::<Result<Vec<Resource>, CreateApiKeyError>>()?;
+
+    // A key can't give access to more than the creating user has access to
+    let permission_repo = UserPermissionRepository::new(connection);
+    for store_id in &input.store_ids {
+        let has_store_access = permission_repo.count(Some(
+            UserPermissionFilter::new()
+                .user_id(EqualFilter::equal_to(user_id))
+                .store_id(EqualFilter::equal_to(store_id))
+                .permission(PermissionType::StoreAccess.equal_to()),
+        ))? > 0;
+        if !has_store_access {
+            return Err(CreateApiKeyError::StoreNotAccessible(store_id.clone()));
+        }
+    }
+
+    Ok(resources)
+}
+
+fn generate_key() -> String {
+    let secret: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(KEY_LENGTH)
+        .map(char::from)
+        .collect();
+    format!("{}{}", API_KEY_PREFIX, secret)
+}
+
+fn generate(
+    user_id: &str,
+    key: &str,
+    resources: Vec<Resource>,
+    CreateApiKey {
+        id,
+        name,
+        store_ids,
+        resources: _,
+        expiry_datetime,
+    }: CreateApiKey,
+) -> ApiKeyRow {
+    ApiKeyRow {
+        id,
+        name: name.trim().to_string(),
+        key_hash: sha256(key),
+        key_prefix: key.chars().take(KEY_PREFIX_LENGTH).collect(),
+        user_id: user_id.to_string(),
+        // Serialising a list of strings or unit enum variants can't fail
+        store_ids: serde_json::to_string(&store_ids).unwrap_or_default(),
+        resources: serde_json::to_string(&resources).unwrap_or_default(),
+        created_datetime: Utc::now().naive_utc(),
+        expiry_datetime,
+        last_used_datetime: None,
+        revoked_datetime: None,
+    }
+}
+
+impl From<RepositoryError> for CreateApiKeyError {
+    fn from(error: RepositoryError) -> Self {
+        CreateApiKeyError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::HashSet;
+
+use repository::{
+    ApiKeyRow, ApiKeyRowRepository, EqualFilter, PermissionType, RepositoryError,
+    StorageConnection, UserPermissionFilter, UserPermissionRepository,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{auth::Resource, service_provider::ServiceContext};
+
+mod authenticate;
+mod create;
+mod revoke;
+
+pub use self::authenticate::*;
+pub use self::create::*;
+pub use self::revoke::*;
+
+/// Prefix of all API keys, used to tell them apart from JWT auth tokens
+pub const API_KEY_PREFIX: &str = "omsk_";
+
+/// Stores and resources an API key is limited to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiKeyScope {
+    pub store_ids: Vec<String>,
+    pub resources: Vec<Resource>,
+}
+
+impl ApiKeyScope {
+    pub fn from_row(row: &ApiKeyRow) -> Result<Self, serde_json::Error> {
+        Ok(ApiKeyScope {
+            store_ids: serde_json::from_str(&row.store_ids)?,
+            resources: serde_json::from_str(&row.resources)?,
+        })
+    }
+
+    pub fn allows_store(&self, store_id: &str) -> bool {
+        self.store_ids.iter().any(|id| id == store_id)
+    }
+
+    pub fn allows_resource(&self, resource: &Resource) -> bool {
+        self.resources.contains(resource)
+    }
+}
+
+pub fn is_api_key(token: &str) -> bool {
+    token.starts_with(API_KEY_PREFIX)
+}
+
+/// Stores the user can log in to
+fn accessible_store_ids(
+    connection: &StorageConnection,
+    user_id: &str,
+) -> Result<HashSet<String>, RepositoryError> {
+    let permissions = UserPermissionRepository::new(connection).query_by_filter(
+        UserPermissionFilter::new()
+            .user_id(EqualFilter::equal_to(user_id))
+            .permission(PermissionType::StoreAccess.equal_to()),
+    )?;
+    Ok(permissions
+        .into_iter()
+        .filter_map(|permission| permission.store_id)
+        .collect())
+}
+
+/// Users can manage the keys they created and keys limited to stores they have access to
+fn is_api_key_accessible(
+    api_key: &ApiKeyRow,
+    user_id: &str,
+    accessible_store_ids: &HashSet<String>,
+) -> bool {
+    if api_key.user_id == user_id {
+        return true;
+    }
+    match ApiKeyScope::from_row(api_key) {
+        Ok(scope) => scope
+            .store_ids
+            .iter()
+            .all(|store_id| accessible_store_ids.contains(store_id)),
+        Err(_) => false,
+    }
+}
+
+pub(crate) fn check_api_key_accessible(
+    connection: &StorageConnection,
+    user_id: &str,
+    api_key: &ApiKeyRow,
+) -> Result<bool, RepositoryError> {
+    let store_ids = accessible_store_ids(connection, user_id)?;
+    Ok(is_api_key_accessible(api_key, user_id, &store_ids))
+}
+
+/// Keys the user can manage, see `is_api_key_accessible`
+pub fn get_api_keys(ctx: &ServiceContext) -> Result<Vec<ApiKeyRow>, RepositoryError> {
+    let store_ids = accessible_store_ids(&ctx.connection, &ctx.user_id)?;
+    let api_keys = ApiKeyRowRepository::new(&ctx.connection).find_all()?;
+    Ok(api_keys
+        .into_iter()
+        .filter(|api_key| is_api_key_accessible(api_key, &ctx.user_id, &store_ids))
+        .collect())
+}
+
+pub trait ApiKeyServiceTrait: Sync + Send {
+    fn get_api_keys(&self, ctx: &ServiceContext) -> Result<Vec<ApiKeyRow>, RepositoryError> {
+        get_api_keys(ctx)
+    }
+
+    fn create_api_key(
+        &self,
+        ctx: &ServiceContext,
+        input: CreateApiKey,
+    ) -> Result<CreatedApiKey, CreateApiKeyError> {
+        create_api_key(ctx, input)
+    }
+
+    fn revoke_api_key(
+        &self,
+        ctx: &ServiceContext,
+        id: &str,
+    ) -> Result<ApiKeyRow, RevokeApiKeyError> {
+        revoke_api_key(ctx, id)
+    }
+}
+
+pub struct ApiKeyService {}
+impl ApiKeyServiceTrait for ApiKeyService {}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, RwLock};
+
+    use chrono::{Duration, Utc};
+    use repository::{
+        mock::{
+            mock_store_a, mock_store_b, mock_user_account_a, mock_user_account_b, MockData,
+            MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        ApiKeyRowRepository, PermissionType, UserPermissionRow,
+    };
+    use util::inline_init;
+
+    use crate::{
+        api_key::{CreateApiKey, CreateApiKeyError, RevokeApiKeyError, API_KEY_PREFIX},
+        auth::{validate_auth_or_api_key, AuthError, Resource, ResourceAccessRequest},
+        auth_data::AuthData,
+        service_provider::ServiceProvider,
+        token_bucket::TokenBucket,
+    };
+
+    #[actix_rt::test]
+    async fn api_key_lifecycle() {
+        fn permissions() -> Vec<UserPermissionRow> {
+            let mut permissions = Vec::new();
+            for store_id in [mock_store_a().id, mock_store_b().id] {
+                for permission in [PermissionType::StoreAccess, PermissionType::StocktakeQuery] {
+                    permissions.push(UserPermissionRow {
+                        id: format!("{:?}_{}", permission, store_id),
+                        user_id: mock_user_account_a().id,
+                        store_id: Some(store_id.clone()),
+                        permission,
+                        context_id: None,
+                    });
+                }
+            }
+            // Other user with access to store b only
+            permissions.push(UserPermissionRow {
+                id: "user_b_store_b".to_string(),
+                user_id: mock_user_account_b().id,
+                store_id: Some(mock_store_b().id),
+                permission: PermissionType::StoreAccess,
+                context_id: None,
+            });
+            permissions
+        }
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "api_key_lifecycle",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| r.user_permissions = permissions()),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context("".to_string(), mock_user_account_a().id)
+            .unwrap();
+        let service = &service_provider.api_key_service;
+        let auth_data = AuthData {
+            auth_token_secret: "some secret".to_string(),
+            token_bucket: Arc::new(RwLock::new(TokenBucket::new())),
+            no_ssl: true,
+            debug_no_access_control: false,
+        };
+
+        let input = CreateApiKey {
+            id: "hmis_key".to_string(),
+            name: "HMIS".to_string(),
+            store_ids: vec![mock_store_a().id],
+            resources: vec!["QueryStocktake".to_string()],
+            expiry_datetime: None,
+        };
+
+        // Errors
+        assert_eq!(
+            service.create_api_key(
+                &context,
+                CreateApiKey {
+                    resources: vec!["NotAResource".to_string()],
+                    ..input.clone()
+                }
+            ),
+            Err(CreateApiKeyError::UnknownResource(
+                "NotAResource".to_string()
+            ))
+        );
+        assert_eq!(
+            service.create_api_key(
+                &context,
+                CreateApiKey {
+                    store_ids: vec!["store_c".to_string()],
+                    ..input.clone()
+                }
+            ),
+            Err(CreateApiKeyError::StoreNotAccessible("store_c".to_string()))
+        );
+        assert_eq!(
+            service.create_api_key(
+                &context,
+                CreateApiKey {
+                    expiry_datetime: Some(Utc::now().naive_utc() - Duration::days(1)),
+                    ..input.clone()
+                }
+            ),
+            Err(CreateApiKeyError::ExpiryDatetimeInThePast)
+        );
+
+        // Success, only the hash of the key is stored
+        let created = service.create_api_key(&context, input.clone()).unwrap();
+        assert!(created.key.starts_with(API_KEY_PREFIX));
+        assert!(!created.api_key.key_hash.contains(&created.key));
+        assert_eq!(
+            service.create_api_key(&context, input),
+            Err(CreateApiKeyError::ApiKeyAlreadyExists)
+        );
+
+        // Key acts on behalf of the creating user, within its scope
+        let key = Some(created.key.clone());
+        let validate = |resource: Resource, store_id: &str| {
+            service_provider.validation_service.validate(
+                &context,
+                &auth_data,
+                &key,
+                &ResourceAccessRequest {
+                    resource,
+                    store_id: Some(store_id.to_string()),
+                },
+            )
+        };
+        let validated = validate(Resource::QueryStocktake, &mock_store_a().id).unwrap();
+        assert_eq!(validated.user_id, mock_user_account_a().id);
+        assert_eq!(validated.claims.exp, usize::MAX);
+        assert!(matches!(
+            validate(Resource::QueryStocktake, &mock_store_b().id),
+            Err(AuthError::Denied(_))
+        ));
+        assert!(matches!(
+            validate(Resource::MutateStocktake, &mock_store_a().id),
+            Err(AuthError::Denied(_))
+        ));
+        assert!(ApiKeyRowRepository::new(&connection)
+            .find_one_by_id("hmis_key")
+            .unwrap()
+            .unwrap()
+            .last_used_datetime
+            .is_some());
+
+        // Keys are only listed and revoked for users with access to all their stores
+        let context_b = service_provider
+            .context("".to_string(), mock_user_account_b().id)
+            .unwrap();
+        assert_eq!(service.get_api_keys(&context).unwrap().len(), 1);
+        assert_eq!(service.get_api_keys(&context_b).unwrap(), vec![]);
+        assert_eq!(
+            service.revoke_api_key(&context_b, "hmis_key"),
+            Err(RevokeApiKeyError::ApiKeyDoesNotExist)
+        );
+
+        // Revoked keys can't be used
+        service.revoke_api_key(&context, "hmis_key").unwrap();
+        assert_eq!(
+            service.revoke_api_key(&context, "hmis_key"),
+            Err(RevokeApiKeyError::ApiKeyAlreadyRevoked)
+        );
+        assert!(matches!(
+            validate_auth_or_api_key(&connection, &auth_data, &key),
+            Err(AuthError::Denied(_))
+        ));
+    }
+}
@@ -0,0 +1,49 @@
+use chrono::Utc;
+use repository::{ApiKeyRow, ApiKeyRowRepository, RepositoryError};
+
+use crate::service_provider::ServiceContext;
+
+use super::check_api_key_accessible;
+
+#[derive(Debug, PartialEq)]
+pub enum RevokeApiKeyError {
+    ApiKeyDoesNotExist,
+    ApiKeyAlreadyRevoked,
+    DatabaseError(RepositoryError),
+}
+
+/// Revoked keys are kept for auditing but can't be used anymore. Keys the user can't manage are
+/// reported as not existing
+pub fn revoke_api_key(ctx: &ServiceContext, id: &str) -> Result<ApiKeyRow, RevokeApiKeyError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let repo = ApiKeyRowRepository::new(connection);
+            let api_key = repo
+                .find_one_by_id(id)?
+                .ok_or(RevokeApiKeyError::ApiKeyDoesNotExist)?;
+            if !check_api_key_accessible(connection, &ctx.user_id, &api_key)? {
+                return Err(RevokeApiKeyError::ApiKeyDoesNotExist);
+            }
+            if api_key.revoked_datetime.is_some() {
+                return Err(RevokeApiKeyError::ApiKeyAlreadyRevoked);
+            }
+
+            let api_key = ApiKeyRow {
+                revoked_datetime: Some(Utc::now().naive_utc()),
+                ..api_key
+            };
+            repo.upsert_one(&api_key)?;
+
+            Ok(api_key)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+impl From<RepositoryError> for RevokeApiKeyError {
+    fn from(error: RepositoryError) -> Self {
+        RevokeApiKeyError::DatabaseError(error)
+    }
+}
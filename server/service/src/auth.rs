@@ -1,12 +1,15 @@
 use std::collections::HashMap;
 
+use chrono::Utc;
 use repository::{
-    EqualFilter, Pagination, PermissionType, RepositoryError, UserPermissionFilter,
-    UserPermissionRepository, UserPermissionRow,
+    EqualFilter, Pagination, PermissionType, RepositoryError, StorageConnection,
+    UserPermissionFilter, UserPermissionRepository, UserPermissionRow,
 };
+use serde::{Deserialize, Serialize};
 use util::{constants::PATIENT_CONTEXT_ID, uuid::uuid};
 
 use crate::{
+    api_key::{authenticate_api_key, is_api_key, ApiKeyAuthError, ApiKeyScope},
     auth_data::AuthData,
     service_provider::ServiceContext,
     settings::is_develop,
@@ -26,7 +29,7 @@ pub enum PermissionDSL {
 }
 
 /// Resources for permission checks
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Resource {
     RouteMe,
     // name
@@ -59,6 +62,7 @@ pub enum Resource {
     // stocktake
     QueryStocktake,
     MutateStocktake,
+    ApproveStocktake,
     // inventory adjustment
     MutateInventoryAdjustment,
     // requisition
@@ -84,9 +88,13 @@ pub enum Resource {
     MutateCustomerReturn,
     // prescription
     MutatePrescription,
+    // invoice payment
+    QueryInvoicePayment,
+    MutateInvoicePayment,
     // reporting
     Report,
     ReportDev,
+    QueryDashboardKpis,
     QueryLog,
     // view/edit server setting
     ServerAdmin,
@@ -113,6 +121,9 @@ pub enum Resource {
     // RnR
     QueryRnRForms,
     MutateRnRForms,
+    MutateLmisCodeMapping,
+    // pricing
+    MutatePricing,
 
     SyncInfo,
     ManualSync,
@@ -130,6 +141,16 @@ pub enum Resource {
     MutateVaccineCourse,
     QueryVaccineCourse,
     MutateImmunisationProgram,
+    // api keys
+    QueryApiKey,
+    MutateApiKey,
+}
+
+impl Resource {
+    /// Parses the variant name, e.g. "QueryStocktake"
+    pub fn from_name(name: &str) -> Option<Resource> {
+        serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+    }
 }
 
 fn all_permissions() -> HashMap<Resource, PermissionDSL> {
@@ -274,6 +295,13 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
             PermissionDSL::HasPermission(PermissionType::StocktakeMutate),
         ]),
     );
+    map.insert(
+        Resource::ApproveStocktake,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(PermissionType::StocktakeApprove),
+        ]),
+    );
     // stock take line
     map.insert(
         Resource::InsertStocktakeLine,
@@ -347,6 +375,15 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
             PermissionDSL::HasPermission(PermissionType::RnrFormMutate),
         ]),
     );
+    map.insert(
+        Resource::MutateLmisCodeMapping,
+        PermissionDSL::HasPermission(PermissionType::EditCentralData),
+    );
+    // pricing
+    map.insert(
+        Resource::MutatePricing,
+        PermissionDSL::HasPermission(PermissionType::EditCentralData),
+    );
     // invoice
     map.insert(
         Resource::QueryInvoice,
@@ -407,6 +444,27 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
             PermissionDSL::HasPermission(PermissionType::PrescriptionMutate),
         ]),
     );
+    // invoice payment
+    map.insert(
+        Resource::QueryInvoicePayment,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::Any(vec![
+                PermissionDSL::HasPermission(PermissionType::OutboundShipmentQuery),
+                PermissionDSL::HasPermission(PermissionType::PrescriptionQuery),
+            ]),
+        ]),
+    );
+    map.insert(
+        Resource::MutateInvoicePayment,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::Any(vec![
+                PermissionDSL::HasPermission(PermissionType::OutboundShipmentMutate),
+                PermissionDSL::HasPermission(PermissionType::PrescriptionMutate),
+            ]),
+        ]),
+    );
 
     // report
     map.insert(
@@ -416,6 +474,13 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
             PermissionDSL::HasPermission(PermissionType::Report),
         ]),
     );
+    map.insert(
+        Resource::QueryDashboardKpis,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(PermissionType::Report),
+        ]),
+    );
     // report development
     map.insert(
         Resource::ReportDev,
@@ -579,6 +644,15 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
         PermissionDSL::NoPermissionRequired,
     );
 
+    map.insert(
+        Resource::QueryApiKey,
+        PermissionDSL::HasPermission(PermissionType::ApiKeyMutate),
+    );
+    map.insert(
+        Resource::MutateApiKey,
+        PermissionDSL::HasPermission(PermissionType::ApiKeyMutate),
+    );
+
     map
 }
 
@@ -600,6 +674,8 @@ pub enum AuthError {
 pub struct ValidatedUserAuth {
     pub user_id: String,
     pub claims: OmSupplyClaim,
+    /// Set if authenticated with an API key, access is limited to the key scope
+    pub api_key_scope: Option<ApiKeyScope>,
 }
 
 fn dummy_user_auth() -> ValidatedUserAuth {
@@ -613,6 +689,7 @@ fn dummy_user_auth() -> ValidatedUserAuth {
             iss: "omSupply-debug".to_string(),
             sub: user_id.to_string(),
         },
+        api_key_scope: None,
     }
 }
 
@@ -663,7 +740,64 @@ pub fn validate_auth(
         }
     };
     let user_id = claims.sub.to_owned();
-    Ok(ValidatedUserAuth { user_id, claims })
+    Ok(ValidatedUserAuth {
+        user_id,
+        claims,
+        api_key_scope: None,
+    })
+}
+
+/// Validates user is auth, either through a JWT auth token or an API key (no permissions checked)
+pub fn validate_auth_or_api_key(
+    connection: &StorageConnection,
+    auth_data: &AuthData,
+    auth_token: &Option<String>,
+) -> Result<ValidatedUserAuth, AuthError> {
+    let api_key = match auth_token {
+        Some(token) if is_api_key(token) => token,
+        _ => return validate_auth(auth_data, auth_token),
+    };
+
+    let now = Utc::now().naive_utc();
+    let authenticated = match authenticate_api_key(connection, api_key, now) {
+        Ok(authenticated) => authenticated,
+        Err(err) => {
+            let e = match err {
+                ApiKeyAuthError::ApiKeyNotFound => AuthError::Denied(
+                    AuthDeniedKind::NotAuthenticated("Invalid api key".to_string()),
+                ),
+                ApiKeyAuthError::ApiKeyRevoked => AuthError::Denied(
+                    AuthDeniedKind::NotAuthenticated("Api key has been revoked".to_string()),
+                ),
+                ApiKeyAuthError::ApiKeyExpired => AuthError::Denied(
+                    AuthDeniedKind::NotAuthenticated("Api key has expired".to_string()),
+                ),
+                ApiKeyAuthError::InvalidScope(err) => {
+                    AuthError::InternalError(format!("Invalid api key scope: {}", err))
+                }
+                ApiKeyAuthError::DatabaseError(err) => AuthError::from(err),
+            };
+            return Err(e);
+        }
+    };
+
+    let user_id = authenticated.api_key.user_id;
+    Ok(ValidatedUserAuth {
+        claims: OmSupplyClaim {
+            exp: authenticated
+                .api_key
+                .expiry_datetime
+                .map(|expiry| expiry.and_utc().timestamp() as usize)
+                // Key never expires
+                .unwrap_or(usize::MAX),
+            aud: crate::token::Audience::Api,
+            iat: authenticated.api_key.created_datetime.and_utc().timestamp() as usize,
+            iss: "om-supply-api-key".to_string(),
+            sub: user_id.clone(),
+        },
+        user_id,
+        api_key_scope: Some(authenticated.scope),
+    })
 }
 
 pub struct ValidatedUser {
@@ -786,6 +920,24 @@ fn validate_resource_permissions(
     Ok(())
 }
 
+fn validate_api_key_scope(
+    scope: &ApiKeyScope,
+    resource_request: &ResourceAccessRequest,
+) -> Result<(), String> {
+    if !scope.allows_resource(&resource_request.resource) {
+        return Err(format!(
+            "Api key has no access to resource: {:?}",
+            resource_request.resource
+        ));
+    }
+    if let Some(store_id) = &resource_request.store_id {
+        if !scope.allows_store(store_id) {
+            return Err(format!("Api key has no access to store: {}", store_id));
+        }
+    }
+    Ok(())
+}
+
 pub trait AuthServiceTrait: Send + Sync {
     fn validate(
         &self,
@@ -822,8 +974,8 @@ impl AuthServiceTrait for AuthService {
         auth_token: &Option<String>,
         resource_request: &ResourceAccessRequest,
     ) -> Result<ValidatedUser, AuthError> {
-        let validated_auth = validate_auth(auth_data, auth_token)?;
         let connection = &context.connection;
+        let validated_auth = validate_auth_or_api_key(connection, auth_data, auth_token)?;
 
         let mut permission_filter =
             UserPermissionFilter::new().user_id(EqualFilter::equal_to(&validated_auth.user_id));
@@ -874,6 +1026,21 @@ impl AuthServiceTrait for AuthService {
             }
         };
 
+        // API keys are limited to their stores and resources, on top of the permissions of the
+        // user the key belongs to
+        if let Some(scope) = &validated_auth.api_key_scope {
+            if let Err(msg) = validate_api_key_scope(scope, resource_request) {
+                return Err(AuthError::Denied(AuthDeniedKind::InsufficientPermission {
+                    msg,
+                    required_permissions: required_permissions.clone(),
+                }));
+            }
+            user_permissions.retain(|permission| match &permission.store_id {
+                Some(store_id) => scope.allows_store(store_id),
+                None => true,
+            });
+        }
+
         let mut dynamic_permissions = Vec::new();
         match validate_resource_permissions(
             &validated_auth.user_id,
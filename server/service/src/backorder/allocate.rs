@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use repository::{
+    ActivityLogType, BackorderRow, BackorderRowRepository, BackorderStatus, CurrencyFilter,
+    CurrencyRepository, InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineType, InvoiceRow,
+    InvoiceRowRepository, InvoiceStatus, InvoiceType, ItemLinkRowRepository, ItemRow,
+    ItemRowRepository, NameLinkRowRepository, NumberRowType, RepositoryError,
+};
+use util::uuid::uuid;
+
+use crate::{
+    activity_log::activity_log_entry,
+    invoice_line::outbound_shipment_unallocated_line::{
+        allocate_outbound_shipment_unallocated_line, AllocateOutboundShipmentUnallocatedLineError,
+    },
+    number::next_number,
+    service_provider::ServiceContext,
+    validate::get_other_party,
+};
+
+use super::get_available_quantity;
+
+#[derive(Debug, PartialEq)]
+pub enum AllocateBackordersError {
+    ProblemGettingOtherParty,
+    ProblemFindingItem,
+    AllocateLine(AllocateOutboundShipmentUnallocatedLineError),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for AllocateBackordersError {
+    fn from(error: RepositoryError) -> Self {
+        AllocateBackordersError::DatabaseError(error)
+    }
+}
+
+/// Customer name link and requisition, each gets its own outbound shipment
+type ShipmentKey = (String, Option<String>);
+
+struct BackorderToAllocate {
+    backorder: BackorderRow,
+    item_row: ItemRow,
+    quantity: f64,
+}
+
+/// Allocates available stock of the items to open backorders of the ctx store, oldest first.
+/// A new outbound shipment is created per customer and requisition, stock that can't be
+/// allocated stays on the backorder. Returns the backorders that were allocated to
+pub fn allocate_backorders(
+    ctx: &ServiceContext,
+    item_ids: &[String],
+) -> Result<Vec<BackorderRow>, AllocateBackordersError> {
+    let connection = &ctx.connection;
+    let open_backorders =
+        BackorderRowRepository::new(connection).find_open(&ctx.store_id, Some(item_ids), None)?;
+
+    // Keep oldest first order within and across groups
+    let mut groups: Vec<(ShipmentKey, Vec<BackorderRow>)> = Vec::new();
+    for backorder in open_backorders {
+        let key = (
+            backorder.name_link_id.clone(),
+            backorder.requisition_id.clone(),
+        );
+        match groups.iter_mut().find(|(group_key, _)| *group_key == key) {
+            Some((_, backorders)) => backorders.push(backorder),
+            None => groups.push((key, vec![backorder])),
+        }
+    }
+
+    let mut available_by_item: HashMap<String, f64> = HashMap::new();
+    let mut result = Vec::new();
+
+    for ((name_link_id, requisition_id), backorders) in groups {
+        let mut to_allocate = Vec::new();
+        for backorder in backorders {
+            let item_row = get_item(ctx, &backorder.item_link_id)?;
+            let available = match available_by_item.get(&item_row.id) {
+                Some(available) => *available,
+                None => get_available_quantity(connection, &ctx.store_id, &item_row.id)?,
+            };
+            available_by_item.insert(item_row.id.clone(), available);
+
+            let quantity = backorder.remaining_quantity().min(available);
+            if quantity > 0.0 {
+                // Reserve for this group, corrected after allocation
+                available_by_item.insert(item_row.id.clone(), available - quantity);
+                to_allocate.push(BackorderToAllocate {
+                    backorder,
+                    item_row,
+                    quantity,
+                });
+            }
+        }
+
+        if to_allocate.is_empty() {
+            continue;
+        }
+
+        let invoice = generate_invoice(ctx, &name_link_id, requisition_id)?;
+        InvoiceRowRepository::new(connection).upsert_one(&invoice)?;
+
+        let mut allocated_any = false;
+        for BackorderToAllocate {
+            mut backorder,
+            item_row,
+            quantity,
+        } in to_allocate
+        {
+            let line = generate_unallocated_line(&invoice.id, item_row.clone(), quantity);
+            InvoiceLineRowRepository::new(connection).upsert_one(&line)?;
+
+            allocate_outbound_shipment_unallocated_line(ctx, line.id.clone())
+                .map_err(AllocateBackordersError::AllocateLine)?;
+
+            // Anything left on the unallocated line stays on the backorder
+            let not_allocated =
+                match InvoiceLineRowRepository::new(connection).find_one_by_id(&line.id)? {
+                    Some(remaining_line) => {
+                        InvoiceLineRowRepository::new(connection).delete(&line.id)?;
+                        remaining_line.number_of_packs.max(0.0)
+                    }
+                    None => 0.0,
+                };
+            let allocated = (quantity - not_allocated).max(0.0);
+            if not_allocated > 0.0 {
+                available_by_item.insert(item_row.id.clone(), 0.0);
+            }
+            if allocated <= 0.0 {
+                continue;
+            }
+
+            allocated_any = true;
+            backorder.allocated_quantity += allocated;
+            if backorder.remaining_quantity() <= 0.0 {
+                backorder.status = BackorderStatus::Fulfilled;
+                backorder.fulfilled_datetime = Some(Utc::now().naive_utc());
+            }
+            BackorderRowRepository::new(connection).upsert_one(&backorder)?;
+            result.push(backorder);
+        }
+
+        if !allocated_any {
+            InvoiceRowRepository::new(connection).delete(&invoice.id)?;
+            continue;
+        }
+
+        activity_log_entry(
+            ctx,
+            ActivityLogType::InvoiceCreated,
+            Some(invoice.id.to_owned()),
+            None,
+            None,
+        )?;
+    }
+
+    Ok(result)
+}
+
+fn get_item(ctx: &ServiceContext, item_link_id: &str) -> Result<ItemRow, AllocateBackordersError> {
+    let item_link = ItemLinkRowRepository::new(&ctx.connection)
+        .find_one_by_id(item_link_id)?
+        .ok_or(AllocateBackordersError::ProblemFindingItem)?;
+    ItemRowRepository::new(&ctx.connection)
+        .find_active_by_id(&item_link.item_id)?
+        .ok_or(AllocateBackordersError::ProblemFindingItem)
+}
+
+fn generate_invoice(
+    ctx: &ServiceContext,
+    name_link_id: &str,
+    requisition_id: Option<String>,
+) -> Result<InvoiceRow, AllocateBackordersError> {
+    let connection = &ctx.connection;
+    let name_link = NameLinkRowRepository::new(connection)
+        .find_one_by_id(name_link_id)?
+        .ok_or(AllocateBackordersError::ProblemGettingOtherParty)?;
+    let other_party = get_other_party(connection, &ctx.store_id, &name_link.name_id)?
+        .ok_or(AllocateBackordersError::ProblemGettingOtherParty)?;
+    let currency = CurrencyRepository::new(connection)
+        .query_by_filter(CurrencyFilter::new().is_home_currency(true))?
+        .pop()
+        .ok_or(RepositoryError::NotFound)?;
+
+    Ok(InvoiceRow {
+        id: uuid(),
+        user_id: Some(ctx.user_id.clone()),
+        name_link_id: name_link_id.to_string(),
+        name_store_id: other_party.store_id().map(|id| id.to_string()),
+        store_id: ctx.store_id.clone(),
+        invoice_number: next_number(connection, &NumberRowType::OutboundShipment, &ctx.store_id)?,
+        r#type: InvoiceType::OutboundShipment,
+        status: InvoiceStatus::New,
+        created_datetime: Utc::now().naive_utc(),
+        requisition_id,
+        comment: Some("Backorder".to_string()),
+
+        // Default
+        currency_id: Some(currency.currency_row.id),
+        currency_rate: 1.0,
+        on_hold: false,
+        their_reference: None,
+        transport_reference: None,
+        allocated_datetime: None,
+        picked_datetime: None,
+        shipped_datetime: None,
+        delivered_datetime: None,
+        verified_datetime: None,
+        colour: None,
+        linked_invoice_id: None,
+        tax_percentage: None,
+        clinician_link_id: None,
+        original_shipment_id: None,
+        backdated_datetime: None,
+    })
+}
+
+fn generate_unallocated_line(invoice_id: &str, item_row: ItemRow, quantity: f64) -> InvoiceLineRow {
+    InvoiceLineRow {
+        id: uuid(),
+        invoice_id: invoice_id.to_string(),
+        pack_size: 1.0,
+        number_of_packs: quantity,
+        item_link_id: item_row.id,
+        item_code: item_row.code,
+        item_name: item_row.name,
+        r#type: InvoiceLineType::UnallocatedStock,
+
+        // Default
+        total_before_tax: 0.0,
+        total_after_tax: 0.0,
+        tax_percentage: None,
+        note: None,
+        location_id: None,
+        batch: None,
+        expiry_date: None,
+        sell_price_per_pack: 0.0,
+        cost_price_per_pack: 0.0,
+        stock_line_id: None,
+        inventory_adjustment_reason_id: None,
+        return_reason_id: None,
+        foreign_currency_price_before_tax: None,
+        item_variant_id: None,
+        linked_invoice_line_id: None,
+        discrepancy_type: None,
+        discrepancy_number_of_packs: 0.0,
+        discrepancy_reason: None,
+    }
+}
@@ -0,0 +1,62 @@
+use chrono::Utc;
+use repository::{BackorderRow, BackorderRowRepository, BackorderStatus, RepositoryError};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct CancelBackorder {
+    pub id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CancelBackorderError {
+    BackorderDoesNotExist,
+    NotThisStoreBackorder,
+    BackorderNotOpen,
+    ReasonIsRequired,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for CancelBackorderError {
+    fn from(error: RepositoryError) -> Self {
+        CancelBackorderError::DatabaseError(error)
+    }
+}
+
+/// Stops any further allocation to the backorder, what was already allocated stays on the
+/// outbound shipments
+pub fn cancel_backorder(
+    ctx: &ServiceContext,
+    input: CancelBackorder,
+) -> Result<BackorderRow, CancelBackorderError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let repository = BackorderRowRepository::new(connection);
+            let backorder = repository
+                .find_one_by_id(&input.id)?
+                .ok_or(CancelBackorderError::BackorderDoesNotExist)?;
+
+            if backorder.store_id != ctx.store_id {
+                return Err(CancelBackorderError::NotThisStoreBackorder);
+            }
+            if backorder.status != BackorderStatus::Open {
+                return Err(CancelBackorderError::BackorderNotOpen);
+            }
+            let reason = input.reason.trim();
+            if reason.is_empty() {
+                return Err(CancelBackorderError::ReasonIsRequired);
+            }
+
+            let cancelled = BackorderRow {
+                status: BackorderStatus::Cancelled,
+                cancelled_datetime: Some(Utc::now().naive_utc()),
+                cancellation_reason: Some(reason.to_string()),
+                ..backorder
+            };
+            repository.upsert_one(&cancelled)?;
+
+            Ok(cancelled)
+        })
+        .map_err(|error| error.to_inner_error())
+}
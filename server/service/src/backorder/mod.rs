@@ -0,0 +1,64 @@
+use repository::{
+    BackorderRow, EqualFilter, RepositoryError, StockLineFilter, StockLineRepository,
+    StorageConnection,
+};
+use util::date_now;
+
+use crate::service_provider::ServiceContext;
+
+pub mod allocate;
+pub use self::allocate::*;
+pub mod cancel;
+pub use self::cancel::*;
+pub mod query;
+pub use self::query::*;
+
+#[cfg(test)]
+mod test;
+
+pub trait BackorderServiceTrait: Sync + Send {
+    fn get_outstanding_backorders(
+        &self,
+        ctx: &ServiceContext,
+        input: OutstandingBackordersInput,
+    ) -> Result<Vec<OutstandingBackorder>, RepositoryError> {
+        get_outstanding_backorders(ctx, input)
+    }
+
+    fn cancel_backorder(
+        &self,
+        ctx: &ServiceContext,
+        input: CancelBackorder,
+    ) -> Result<BackorderRow, CancelBackorderError> {
+        cancel_backorder(ctx, input)
+    }
+}
+
+pub struct BackorderService {}
+impl BackorderServiceTrait for BackorderService {}
+
+/// Units of the item that can be issued from the store now, skipping stock that is on hold or
+/// expired (the same stock outbound shipment allocation would use)
+pub(crate) fn get_available_quantity(
+    connection: &StorageConnection,
+    store_id: &str,
+    item_id: &str,
+) -> Result<f64, RepositoryError> {
+    let stock_lines = StockLineRepository::new(connection).query_by_filter(
+        StockLineFilter::new()
+            .store_id(EqualFilter::equal_to(store_id))
+            .item_id(EqualFilter::equal_to(item_id))
+            .is_available(true),
+        Some(store_id.to_string()),
+    )?;
+
+    let today = date_now();
+    let quantity = stock_lines
+        .into_iter()
+        .map(|stock_line| stock_line.stock_line_row)
+        .filter(|row| !row.on_hold && row.expiry_date.is_none_or(|expiry| expiry >= today))
+        .map(|row| row.available_number_of_packs * row.pack_size)
+        .sum();
+
+    Ok(quantity)
+}
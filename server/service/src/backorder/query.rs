@@ -0,0 +1,74 @@
+use chrono::{Local, NaiveDate};
+use repository::{
+    BackorderRow, BackorderRowRepository, ItemLinkRowRepository, ItemRowRepository,
+    NameLinkRowRepository, NameRowRepository, RepositoryError,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct OutstandingBackordersInput {
+    pub name_id: Option<String>,
+    pub item_id: Option<String>,
+    /// Date backorder age is calculated from, defaults to today
+    pub as_of: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutstandingBackorder {
+    pub backorder: BackorderRow,
+    pub item_id: String,
+    pub item_code: String,
+    pub item_name: String,
+    pub name_id: String,
+    pub name: String,
+    pub days_outstanding: i64,
+}
+
+/// Open backorders of the ctx store, oldest first
+pub fn get_outstanding_backorders(
+    ctx: &ServiceContext,
+    input: OutstandingBackordersInput,
+) -> Result<Vec<OutstandingBackorder>, RepositoryError> {
+    let connection = &ctx.connection;
+    let as_of = input.as_of.unwrap_or_else(|| Local::now().date_naive());
+    let item_ids = input.item_id.map(|item_id| vec![item_id]);
+
+    let backorders = BackorderRowRepository::new(connection).find_open(
+        &ctx.store_id,
+        item_ids.as_deref(),
+        input.name_id.as_deref(),
+    )?;
+
+    let mut result = Vec::new();
+    for backorder in backorders {
+        let item_link = ItemLinkRowRepository::new(connection)
+            .find_one_by_id(&backorder.item_link_id)?
+            .ok_or(RepositoryError::NotFound)?;
+        let item_row = ItemRowRepository::new(connection)
+            .find_one_by_id(&item_link.item_id)?
+            .ok_or(RepositoryError::NotFound)?;
+        let name_link = NameLinkRowRepository::new(connection)
+            .find_one_by_id(&backorder.name_link_id)?
+            .ok_or(RepositoryError::NotFound)?;
+        let name_row = NameRowRepository::new(connection)
+            .find_one_by_id(&name_link.name_id)?
+            .ok_or(RepositoryError::NotFound)?;
+
+        let days_outstanding = (as_of - backorder.created_datetime.date())
+            .num_days()
+            .max(0);
+
+        result.push(OutstandingBackorder {
+            backorder,
+            item_id: item_row.id,
+            item_code: item_row.code,
+            item_name: item_row.name,
+            name_id: name_row.id,
+            name: name_row.name,
+            days_outstanding,
+        });
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,219 @@
+#[cfg(test)]
+mod backorder {
+    use chrono::{Duration, Utc};
+    use repository::{
+        mock::{mock_name_a, mock_name_store_b, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        BackorderRowRepository, BackorderStatus, EqualFilter, InvoiceFilter, InvoiceLineFilter,
+        InvoiceLineRepository, InvoiceLineRow, InvoiceLineType, InvoiceRepository, InvoiceRow,
+        InvoiceStatus, InvoiceType, ItemRow, ItemType, RequisitionLineRow, RequisitionRow,
+        RequisitionStatus, RequisitionType,
+    };
+    use util::inline_init;
+
+    use crate::{
+        backorder::{CancelBackorder, CancelBackorderError, OutstandingBackordersInput},
+        invoice::inbound_shipment::{UpdateInboundShipment, UpdateInboundShipmentStatus},
+        requisition::response_requisition::{
+            CreateRequisitionShipment, CreateRequisitionShipmentError,
+        },
+        service_provider::ServiceProvider,
+    };
+
+    fn item() -> ItemRow {
+        inline_init(|r: &mut ItemRow| {
+            r.id = "backorder_item".to_string();
+            r.code = "backorder_item".to_string();
+            r.name = "Backorder item".to_string();
+            r.r#type = ItemType::Stock;
+        })
+    }
+
+    fn requisition() -> RequisitionRow {
+        inline_init(|r: &mut RequisitionRow| {
+            r.id = "backorder_requisition".to_string();
+            r.name_link_id = mock_name_a().id;
+            r.store_id = mock_store_a().id;
+            r.r#type = RequisitionType::Response;
+            r.status = RequisitionStatus::New;
+            r.created_datetime = Utc::now().naive_utc();
+        })
+    }
+
+    fn inbound_shipment() -> InvoiceRow {
+        inline_init(|r: &mut InvoiceRow| {
+            r.id = "backorder_inbound".to_string();
+            r.name_link_id = mock_name_store_b().id;
+            r.store_id = mock_store_a().id;
+            r.r#type = InvoiceType::InboundShipment;
+            r.status = InvoiceStatus::New;
+            r.created_datetime = Utc::now().naive_utc();
+        })
+    }
+
+    #[actix_rt::test]
+    async fn backorder() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "backorder",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.items = vec![item()];
+                r.requisitions = vec![requisition()];
+                r.requisition_lines = vec![inline_init(|r: &mut RequisitionLineRow| {
+                    r.id = "backorder_requisition_line".to_string();
+                    r.requisition_id = requisition().id;
+                    r.item_link_id = item().id;
+                    r.requested_quantity = 30.0;
+                    r.supply_quantity = 30.0;
+                })];
+                r.invoices = vec![inbound_shipment()];
+                r.invoice_lines = vec![inline_init(|r: &mut InvoiceLineRow| {
+                    r.id = "backorder_inbound_line".to_string();
+                    r.invoice_id = inbound_shipment().id;
+                    r.item_link_id = item().id;
+                    r.item_code = item().code;
+                    r.item_name = item().name;
+                    r.r#type = InvoiceLineType::StockIn;
+                    r.pack_size = 10.0;
+                    r.number_of_packs = 2.0;
+                })];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = &service_provider.backorder_service;
+
+        // Nothing in stock, everything is backordered
+        let shipment = service_provider
+            .requisition_service
+            .create_requisition_shipment(
+                &context,
+                CreateRequisitionShipment {
+                    response_requisition_id: requisition().id,
+                    create_backorders: true,
+                },
+            )
+            .unwrap();
+        let lines = InvoiceLineRepository::new(&connection)
+            .query_by_filter(
+                InvoiceLineFilter::new()
+                    .invoice_id(EqualFilter::equal_to(&shipment.invoice_row.id)),
+            )
+            .unwrap();
+        assert!(lines.is_empty());
+
+        let outstanding = service
+            .get_outstanding_backorders(
+                &context,
+                OutstandingBackordersInput {
+                    as_of: Some(Utc::now().date_naive() + Duration::days(3)),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(outstanding.len(), 1);
+        assert_eq!(outstanding[0].item_id, item().id);
+        assert_eq!(outstanding[0].name_id, mock_name_a().id);
+        assert_eq!(outstanding[0].days_outstanding, 3);
+        let backorder_id = outstanding[0].backorder.id.clone();
+        assert_eq!(outstanding[0].backorder.quantity, 30.0);
+
+        // Backordered quantity isn't supplied again
+        assert_eq!(
+            service_provider
+                .requisition_service
+                .create_requisition_shipment(
+                    &context,
+                    CreateRequisitionShipment {
+                        response_requisition_id: requisition().id,
+                        create_backorders: false,
+                    },
+                ),
+            Err(CreateRequisitionShipmentError::NothingRemainingToSupply)
+        );
+
+        // Receiving 20 units allocates them to a new outbound shipment
+        service_provider
+            .invoice_service
+            .update_inbound_shipment(
+                &context,
+                UpdateInboundShipment {
+                    id: inbound_shipment().id,
+                    status: Some(UpdateInboundShipmentStatus::Delivered),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let backorder = BackorderRowRepository::new(&connection)
+            .find_one_by_id(&backorder_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(backorder.allocated_quantity, 20.0);
+        assert_eq!(backorder.remaining_quantity(), 10.0);
+        assert_eq!(backorder.status, BackorderStatus::Open);
+
+        let shipments = InvoiceRepository::new(&connection)
+            .query_by_filter(
+                InvoiceFilter::new()
+                    .requisition_id(EqualFilter::equal_to(&requisition().id))
+                    .r#type(InvoiceType::OutboundShipment.equal_to()),
+            )
+            .unwrap();
+        assert_eq!(shipments.len(), 2);
+        let backorder_shipment = shipments
+            .iter()
+            .find(|backorder_shipment| backorder_shipment.invoice_row.id != shipment.invoice_row.id)
+            .unwrap();
+        let lines = InvoiceLineRepository::new(&connection)
+            .query_by_filter(
+                InvoiceLineFilter::new()
+                    .invoice_id(EqualFilter::equal_to(&backorder_shipment.invoice_row.id)),
+            )
+            .unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].invoice_line_row.r#type, InvoiceLineType::StockOut);
+        assert_eq!(lines[0].invoice_line_row.number_of_packs, 2.0);
+
+        // Cancel
+        assert_eq!(
+            service.cancel_backorder(
+                &context,
+                CancelBackorder {
+                    id: backorder_id.clone(),
+                    reason: " ".to_string(),
+                }
+            ),
+            Err(CancelBackorderError::ReasonIsRequired)
+        );
+        let cancelled = service
+            .cancel_backorder(
+                &context,
+                CancelBackorder {
+                    id: backorder_id.clone(),
+                    reason: "Customer sourced elsewhere".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(cancelled.status, BackorderStatus::Cancelled);
+        assert_eq!(cancelled.allocated_quantity, 20.0);
+        assert_eq!(
+            service.cancel_backorder(
+                &context,
+                CancelBackorder {
+                    id: backorder_id,
+                    reason: "Again".to_string(),
+                }
+            ),
+            Err(CancelBackorderError::BackorderNotOpen)
+        );
+        assert!(service
+            .get_outstanding_backorders(&context, Default::default())
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,76 @@
+use repository::{ActivityLogType, RepositoryError};
+
+use crate::{activity_log::activity_log_entry, service_provider::ServiceContext};
+
+use super::{get_prescription_warnings, ClinicalCheckError, ClinicalWarning};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AcknowledgeClinicalWarning {
+    pub invoice_id: String,
+    /// Item the warnings were checked for, as passed to `get_prescription_warnings`
+    pub item_id: Option<String>,
+    pub warning_id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AcknowledgeClinicalWarningError {
+    InvoiceDoesNotExist,
+    NotThisStoreInvoice,
+    NotAPrescriptionInvoice,
+    ItemDoesNotExist,
+    /// Warning no longer applies to the prescription
+    WarningDoesNotExist,
+    ReasonCannotBeEmpty,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for AcknowledgeClinicalWarningError {
+    fn from(error: RepositoryError) -> Self {
+        AcknowledgeClinicalWarningError::DatabaseError(error)
+    }
+}
+
+impl From<ClinicalCheckError> for AcknowledgeClinicalWarningError {
+    fn from(error: ClinicalCheckError) -> Self {
+        use AcknowledgeClinicalWarningError as to;
+        use ClinicalCheckError as from;
+        match error {
+            from::InvoiceDoesNotExist => to::InvoiceDoesNotExist,
+            from::NotThisStoreInvoice => to::NotThisStoreInvoice,
+            from::NotAPrescriptionInvoice => to::NotAPrescriptionInvoice,
+            from::ItemDoesNotExist => to::ItemDoesNotExist,
+            from::DatabaseError(error) => to::DatabaseError(error),
+        }
+    }
+}
+
+/// Records in the activity log of the prescription that the warning was reviewed and why the item
+/// is still being dispensed
+pub fn acknowledge_clinical_warning(
+    ctx: &ServiceContext,
+    input: AcknowledgeClinicalWarning,
+) -> Result<ClinicalWarning, AcknowledgeClinicalWarningError> {
+    let reason = input.reason.trim();
+    if reason.is_empty() {
+        return Err(AcknowledgeClinicalWarningError::ReasonCannotBeEmpty);
+    }
+
+    let warning = get_prescription_warnings(ctx, &input.invoice_id, input.item_id.clone())?
+        .into_iter()
+        .find(|warning| warning.id == input.warning_id)
+        .ok_or(AcknowledgeClinicalWarningError::WarningDoesNotExist)?;
+
+    activity_log_entry(
+        ctx,
+        ActivityLogType::ClinicalWarningAcknowledged,
+        Some(input.invoice_id),
+        None,
+        Some(format!(
+            "{:?} {:?} - {}: {}",
+            warning.severity, warning.r#type, warning.description, reason
+        )),
+    )?;
+
+    Ok(warning)
+}
@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+
+use repository::{
+    DrugIngredientRow, DrugIngredientRowRepository, DrugInteractionRow,
+    DrugInteractionRowRepository, DrugInteractionSeverity, RepositoryError,
+};
+use serde::Deserialize;
+use util::uuid::uuid;
+
+use crate::service_provider::ServiceContext;
+
+/// Drug interaction dataset, as imported from a json file
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DrugInteractionData {
+    #[serde(default)]
+    pub ingredients: Vec<DrugIngredientInput>,
+    #[serde(default)]
+    pub interactions: Vec<DrugInteractionInput>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DrugIngredientInput {
+    pub item_code: String,
+    pub ingredient: String,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DrugInteractionInput {
+    /// Item code or ingredient name
+    pub subject_a: String,
+    /// Item code or ingredient name
+    pub subject_b: String,
+    pub severity: DrugInteractionSeverity,
+    pub description: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ImportDrugInteractionsResult {
+    pub ingredient_count: usize,
+    pub interaction_count: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ImportDrugInteractionsError {
+    /// Index of the ingredient entry with an empty item code or ingredient
+    EmptyIngredient(usize),
+    /// Index of the interaction entry with an empty subject
+    EmptyInteractionSubject(usize),
+    /// Index of the interaction entry where both subjects are the same
+    InteractionWithItself(usize),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for ImportDrugInteractionsError {
+    fn from(error: RepositoryError) -> Self {
+        ImportDrugInteractionsError::DatabaseError(error)
+    }
+}
+
+/// Item codes and ingredients are matched case insensitively
+pub(crate) fn normalise_key(key: &str) -> String {
+    key.trim().to_lowercase()
+}
+
+pub fn import_drug_interactions(
+    ctx: &ServiceContext,
+    data: DrugInteractionData,
+) -> Result<ImportDrugInteractionsResult, ImportDrugInteractionsError> {
+    let (ingredients, interactions) = generate(data)?;
+
+    ctx.connection
+        .transaction_sync(|connection| -> Result<(), RepositoryError> {
+            let ingredient_repo = DrugIngredientRowRepository::new(connection);
+            ingredient_repo.delete_all()?;
+            for row in &ingredients {
+                ingredient_repo.upsert_one(row)?;
+            }
+
+            let interaction_repo = DrugInteractionRowRepository::new(connection);
+            interaction_repo.delete_all()?;
+            for row in &interactions {
+                interaction_repo.upsert_one(row)?;
+            }
+
+            Ok(())
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(ImportDrugInteractionsResult {
+        ingredient_count: ingredients.len(),
+        interaction_count: interactions.len(),
+    })
+}
+
+fn generate(
+    DrugInteractionData {
+        ingredients,
+        interactions,
+    }: DrugInteractionData,
+) -> Result<(Vec<DrugIngredientRow>, Vec<DrugInteractionRow>), ImportDrugInteractionsError> {
+    let mut seen = HashSet::new();
+    let mut ingredient_rows = Vec::new();
+    for (index, input) in ingredients.into_iter().enumerate() {
+        let item_code = normalise_key(&input.item_code);
+        let ingredient = normalise_key(&input.ingredient);
+        if item_code.is_empty() || ingredient.is_empty() {
+            return Err(ImportDrugInteractionsError::EmptyIngredient(index));
+        }
+        // Same ingredient can be listed more than once for an item
+        if seen.insert((item_code.clone(), ingredient.clone())) {
+            ingredient_rows.push(DrugIngredientRow {
+                id: uuid(),
+                item_code,
+                ingredient,
+            });
+        }
+    }
+
+    let mut interaction_rows = Vec::new();
+    for (index, input) in interactions.into_iter().enumerate() {
+        let subject_a = normalise_key(&input.subject_a);
+        let subject_b = normalise_key(&input.subject_b);
+        if subject_a.is_empty() || subject_b.is_empty() {
+            return Err(ImportDrugInteractionsError::EmptyInteractionSubject(index));
+        }
+        if subject_a == subject_b {
+            return Err(ImportDrugInteractionsError::InteractionWithItself(index));
+        }
+        interaction_rows.push(DrugInteractionRow {
+            id: uuid(),
+            subject_a,
+            subject_b,
+            severity: input.severity,
+            description: input.description.trim().to_string(),
+        });
+    }
+
+    Ok((ingredient_rows, interaction_rows))
+}
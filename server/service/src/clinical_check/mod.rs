@@ -0,0 +1,85 @@
+use repository::{DrugInteractionSeverity, RepositoryError};
+
+use crate::service_provider::ServiceContext;
+
+pub mod acknowledge;
+pub use self::acknowledge::*;
+pub mod import;
+pub use self::import::*;
+pub mod warnings;
+pub use self::warnings::*;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClinicalWarningType {
+    /// Interaction or contraindication from the drug interaction dataset
+    Interaction,
+    /// Same item, or an item with the same active ingredient, is already being taken
+    DuplicateTherapy,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClinicalWarning {
+    /// Stable for the same pair of items, used to acknowledge the warning
+    pub id: String,
+    pub r#type: ClinicalWarningType,
+    pub severity: DrugInteractionSeverity,
+    pub item_id: String,
+    pub item_name: String,
+    pub interacting_item_id: String,
+    pub interacting_item_name: String,
+    /// Prescription the interacting item was dispensed in, None when it is on the prescription
+    /// being checked
+    pub interacting_invoice_id: Option<String>,
+    pub description: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ClinicalCheckError {
+    InvoiceDoesNotExist,
+    NotThisStoreInvoice,
+    NotAPrescriptionInvoice,
+    ItemDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for ClinicalCheckError {
+    fn from(error: RepositoryError) -> Self {
+        ClinicalCheckError::DatabaseError(error)
+    }
+}
+
+pub trait ClinicalCheckServiceTrait: Sync + Send {
+    /// Replaces the drug interaction and ingredient dataset
+    fn import_drug_interactions(
+        &self,
+        ctx: &ServiceContext,
+        data: DrugInteractionData,
+    ) -> Result<ImportDrugInteractionsResult, ImportDrugInteractionsError> {
+        import_drug_interactions(ctx, data)
+    }
+
+    /// Checks `item_id` (or every item on the prescription when not provided) against the other
+    /// items on the prescription and the patient's active medication
+    fn get_prescription_warnings(
+        &self,
+        ctx: &ServiceContext,
+        invoice_id: &str,
+        item_id: Option<String>,
+    ) -> Result<Vec<ClinicalWarning>, ClinicalCheckError> {
+        get_prescription_warnings(ctx, invoice_id, item_id)
+    }
+
+    fn acknowledge_clinical_warning(
+        &self,
+        ctx: &ServiceContext,
+        input: AcknowledgeClinicalWarning,
+    ) -> Result<ClinicalWarning, AcknowledgeClinicalWarningError> {
+        acknowledge_clinical_warning(ctx, input)
+    }
+}
+
+pub struct ClinicalCheckService {}
+impl ClinicalCheckServiceTrait for ClinicalCheckService {}
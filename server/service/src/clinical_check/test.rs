@@ -0,0 +1,285 @@
+#[cfg(test)]
+mod clinical_check {
+    use chrono::Utc;
+    use repository::{
+        activity_log::{ActivityLogFilter, ActivityLogRepository},
+        mock::{
+            mock_item_a, mock_item_b, mock_item_c, mock_item_d, mock_patient, mock_prescription_a,
+            mock_store_a, MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        ActivityLogType, DrugInteractionSeverity, InvoiceLineRow, InvoiceLineType, InvoiceRow,
+        InvoiceStatus, InvoiceType,
+    };
+    use util::inline_init;
+
+    use crate::{
+        clinical_check::{
+            AcknowledgeClinicalWarning, AcknowledgeClinicalWarningError, ClinicalCheckError,
+            ClinicalWarningType, DrugInteractionData, DrugInteractionInput,
+            ImportDrugInteractionsError, ImportDrugInteractionsResult,
+        },
+        invoice_line::stock_out_line::InsertStockOutLine,
+        service_provider::ServiceProvider,
+        test_helpers::stock_line_expiring_in,
+    };
+
+    fn picked_prescription() -> InvoiceRow {
+        inline_init(|r: &mut InvoiceRow| {
+            r.id = "clinical_check_picked".to_string();
+            r.name_link_id = mock_patient().id;
+            r.store_id = mock_store_a().id;
+            r.r#type = InvoiceType::Prescription;
+            r.status = InvoiceStatus::Picked;
+            r.created_datetime = Utc::now().naive_utc();
+            r.picked_datetime = Some(Utc::now().naive_utc());
+        })
+    }
+
+    fn picked_prescription_line() -> InvoiceLineRow {
+        inline_init(|r: &mut InvoiceLineRow| {
+            r.id = "clinical_check_picked_line".to_string();
+            r.invoice_id = picked_prescription().id;
+            r.item_link_id = mock_item_c().id;
+            r.pack_size = 1.0;
+            r.number_of_packs = 10.0;
+            r.r#type = InvoiceLineType::StockOut;
+        })
+    }
+
+    fn interaction(
+        subject_a: &str,
+        subject_b: &str,
+        severity: DrugInteractionSeverity,
+    ) -> DrugInteractionInput {
+        DrugInteractionInput {
+            subject_a: subject_a.to_string(),
+            subject_b: subject_b.to_string(),
+            severity,
+            description: format!("{} with {}", subject_a, subject_b),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn clinical_check() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "clinical_check",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.invoices = vec![picked_prescription()];
+                r.invoice_lines = vec![picked_prescription_line()];
+                r.stock_lines = vec![stock_line_expiring_in(
+                    "clinical_check_item_d_line",
+                    &mock_item_d().id,
+                    None,
+                    30,
+                )];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = &service_provider.clinical_check_service;
+
+        // Import
+        assert_eq!(
+            service.import_drug_interactions(
+                &context,
+                DrugInteractionData {
+                    interactions: vec![interaction(
+                        "Warfarin",
+                        " warfarin ",
+                        DrugInteractionSeverity::Major
+                    )],
+                    ..Default::default()
+                },
+            ),
+            Err(ImportDrugInteractionsError::InteractionWithItself(0))
+        );
+
+        let data: DrugInteractionData = serde_json::from_str(
+            r#"{
+                "ingredients": [
+                    { "itemCode": "ITEM_C_CODE", "ingredient": "Warfarin" },
+                    { "itemCode": "item_c_code", "ingredient": "warfarin" },
+                    { "itemCode": "item_d_code", "ingredient": "Warfarin" }
+                ],
+                "interactions": [
+                    { "subjectA": "item_a_code", "subjectB": "item_b_code", "severity": "MAJOR", "description": "a with b" },
+                    { "subjectA": "item_b_code", "subjectB": "warfarin", "severity": "CONTRAINDICATED", "description": "b with warfarin" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            service.import_drug_interactions(&context, data),
+            Ok(ImportDrugInteractionsResult {
+                ingredient_count: 2,
+                interaction_count: 2
+            })
+        );
+
+        // Errors
+        assert_eq!(
+            service.get_prescription_warnings(&context, "invalid", None),
+            Err(ClinicalCheckError::InvoiceDoesNotExist)
+        );
+        assert_eq!(
+            service.get_prescription_warnings(
+                &context,
+                &mock_prescription_a().id,
+                Some("invalid".to_string())
+            ),
+            Err(ClinicalCheckError::ItemDoesNotExist)
+        );
+
+        // Lines on the prescription (items a and b) checked against each other and against item c
+        // dispensed today
+        let summary = |warnings: Vec<crate::clinical_check::ClinicalWarning>| {
+            warnings
+                .into_iter()
+                .map(|warning| {
+                    (
+                        warning.r#type,
+                        warning.severity,
+                        warning.item_id,
+                        warning.interacting_item_id,
+                        warning.interacting_invoice_id,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let warnings = service
+            .get_prescription_warnings(&context, &mock_prescription_a().id, None)
+            .unwrap();
+        assert_eq!(
+            summary(warnings),
+            vec![
+                (
+                    ClinicalWarningType::Interaction,
+                    DrugInteractionSeverity::Contraindicated,
+                    mock_item_b().id,
+                    mock_item_c().id,
+                    Some(picked_prescription().id)
+                ),
+                (
+                    ClinicalWarningType::Interaction,
+                    DrugInteractionSeverity::Major,
+                    mock_item_a().id,
+                    mock_item_b().id,
+                    None
+                ),
+            ]
+        );
+
+        // Item d being added, has the same ingredient as item c and interacts with item b
+        let warnings = service
+            .get_prescription_warnings(&context, &mock_prescription_a().id, Some(mock_item_d().id))
+            .unwrap();
+        let duplicate_id = warnings[1].id.clone();
+        assert_eq!(
+            summary(warnings),
+            vec![
+                (
+                    ClinicalWarningType::Interaction,
+                    DrugInteractionSeverity::Contraindicated,
+                    mock_item_d().id,
+                    mock_item_b().id,
+                    None
+                ),
+                (
+                    ClinicalWarningType::DuplicateTherapy,
+                    DrugInteractionSeverity::Moderate,
+                    mock_item_d().id,
+                    mock_item_c().id,
+                    Some(picked_prescription().id)
+                ),
+            ]
+        );
+
+        // Acknowledge
+        let input = AcknowledgeClinicalWarning {
+            invoice_id: mock_prescription_a().id,
+            item_id: Some(mock_item_d().id),
+            warning_id: duplicate_id.clone(),
+            reason: " ".to_string(),
+        };
+        assert_eq!(
+            service.acknowledge_clinical_warning(&context, input.clone()),
+            Err(AcknowledgeClinicalWarningError::ReasonCannotBeEmpty)
+        );
+        assert_eq!(
+            service.acknowledge_clinical_warning(
+                &context,
+                AcknowledgeClinicalWarning {
+                    warning_id: "invalid".to_string(),
+                    reason: "Reviewed".to_string(),
+                    ..input.clone()
+                }
+            ),
+            Err(AcknowledgeClinicalWarningError::WarningDoesNotExist)
+        );
+
+        let warning = service
+            .acknowledge_clinical_warning(
+                &context,
+                AcknowledgeClinicalWarning {
+                    reason: "Item c is being stopped".to_string(),
+                    ..input
+                },
+            )
+            .unwrap();
+        assert_eq!(warning.id, duplicate_id);
+
+        let logs: Vec<_> = ActivityLogRepository::new(&connection)
+            .query_by_filter(ActivityLogFilter::new())
+            .unwrap()
+            .into_iter()
+            .map(|log| log.activity_log_row)
+            .filter(|log| log.r#type == ActivityLogType::ClinicalWarningAcknowledged)
+            .collect();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].record_id, Some(mock_prescription_a().id));
+        assert!(logs[0]
+            .changed_to
+            .as_ref()
+            .is_some_and(|changed_to| changed_to.ends_with("Item c is being stopped")));
+
+        // Warnings are returned when the line is added to the prescription
+        let result = service_provider
+            .invoice_line_service
+            .insert_prescription_line(
+                &context,
+                inline_init(|r: &mut InsertStockOutLine| {
+                    r.id = "clinical_check_item_d".to_string();
+                    r.invoice_id = mock_prescription_a().id;
+                    r.stock_line_id = "clinical_check_item_d_line".to_string();
+                    r.number_of_packs = 1.0;
+                }),
+            )
+            .unwrap();
+        assert_eq!(result.line.item_row.id, mock_item_d().id);
+        assert_eq!(
+            summary(result.clinical_warnings),
+            vec![
+                (
+                    ClinicalWarningType::Interaction,
+                    DrugInteractionSeverity::Contraindicated,
+                    mock_item_d().id,
+                    mock_item_b().id,
+                    None
+                ),
+                (
+                    ClinicalWarningType::DuplicateTherapy,
+                    DrugInteractionSeverity::Moderate,
+                    mock_item_d().id,
+                    mock_item_c().id,
+                    Some(picked_prescription().id)
+                ),
+            ]
+        );
+    }
+}
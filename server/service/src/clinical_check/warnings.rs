@@ -0,0 +1,232 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Duration, Local, NaiveDate};
+use repository::{
+    DrugIngredientRowRepository, DrugInteractionRow, DrugInteractionRowRepository,
+    DrugInteractionSeverity, EqualFilter, InvoiceFilter, InvoiceLineFilter, InvoiceLineRepository,
+    InvoiceLineType, InvoiceRepository, InvoiceType, ItemRow, ItemRowRepository,
+};
+
+use crate::{
+    invoice::{
+        check_invoice_type, check_store,
+        prescription::{get_medication_history, MedicationHistoryEntry},
+    },
+    service_provider::ServiceContext,
+};
+
+use super::{normalise_key, ClinicalCheckError, ClinicalWarning, ClinicalWarningType};
+
+/// Dispensed medication without a known days of supply is considered active for this many days
+const DEFAULT_ACTIVE_DAYS: i64 = 30;
+
+#[derive(Clone)]
+struct Medication {
+    item_id: String,
+    item_name: String,
+    item_code: String,
+    /// Prescription the item was dispensed in, None when on the prescription being checked
+    invoice_id: Option<String>,
+}
+
+impl Medication {
+    fn new(item: ItemRow, invoice_id: Option<String>) -> Self {
+        Medication {
+            item_code: normalise_key(&item.code),
+            item_id: item.id,
+            item_name: item.name,
+            invoice_id,
+        }
+    }
+}
+
+pub fn get_prescription_warnings(
+    ctx: &ServiceContext,
+    invoice_id: &str,
+    item_id: Option<String>,
+) -> Result<Vec<ClinicalWarning>, ClinicalCheckError> {
+    let invoice = InvoiceRepository::new(&ctx.connection)
+        .query_one(InvoiceFilter::by_id(invoice_id))?
+        .ok_or(ClinicalCheckError::InvoiceDoesNotExist)?;
+    if !check_store(&invoice.invoice_row, &ctx.store_id) {
+        return Err(ClinicalCheckError::NotThisStoreInvoice);
+    }
+    if !check_invoice_type(&invoice.invoice_row, InvoiceType::Prescription) {
+        return Err(ClinicalCheckError::NotAPrescriptionInvoice);
+    }
+
+    let mut on_prescription: Vec<Medication> = Vec::new();
+    let lines = InvoiceLineRepository::new(&ctx.connection).query_by_filter(
+        InvoiceLineFilter::new()
+            .invoice_id(EqualFilter::equal_to(invoice_id))
+            .r#type(InvoiceLineType::StockOut.equal_to()),
+    )?;
+    for line in lines {
+        // An item can be on more than one line (different batches)
+        if !on_prescription
+            .iter()
+            .any(|medication| medication.item_id == line.item_row.id)
+        {
+            on_prescription.push(Medication::new(line.item_row, None));
+        }
+    }
+
+    let to_check = match item_id {
+        Some(item_id) => {
+            let item = ItemRowRepository::new(&ctx.connection)
+                .find_active_by_id(&item_id)?
+                .ok_or(ClinicalCheckError::ItemDoesNotExist)?;
+            vec![Medication::new(item, None)]
+        }
+        None => on_prescription.clone(),
+    };
+
+    let today = Local::now().date_naive();
+    let active = get_medication_history(ctx, &invoice.name_row.id)?
+        .into_iter()
+        .filter(|entry| entry.invoice_line.invoice_row.id != invoice_id && is_active(entry, today))
+        .map(|entry| {
+            Medication::new(
+                entry.invoice_line.item_row,
+                Some(entry.invoice_line.invoice_row.id),
+            )
+        });
+    let current: Vec<Medication> = on_prescription.into_iter().chain(active).collect();
+
+    let item_codes: Vec<String> = to_check
+        .iter()
+        .chain(current.iter())
+        .map(|medication| medication.item_code.clone())
+        .collect();
+    let mut ingredients: HashMap<String, HashSet<String>> = HashMap::new();
+    for row in
+        DrugIngredientRowRepository::new(&ctx.connection).find_many_by_item_codes(&item_codes)?
+    {
+        ingredients
+            .entry(row.item_code)
+            .or_default()
+            .insert(row.ingredient);
+    }
+
+    let mut subjects = item_codes;
+    subjects.extend(ingredients.values().flatten().cloned());
+    let interactions =
+        DrugInteractionRowRepository::new(&ctx.connection).find_many_by_subjects(&subjects)?;
+
+    let mut warnings: Vec<ClinicalWarning> = Vec::new();
+    for medication in &to_check {
+        for other in &current {
+            // Another batch of the same item on this prescription
+            if other.invoice_id.is_none() && other.item_id == medication.item_id {
+                continue;
+            }
+            for warning in check_pair(medication, other, &ingredients, &interactions) {
+                if !warnings.iter().any(|existing| existing.id == warning.id) {
+                    warnings.push(warning);
+                }
+            }
+        }
+    }
+    warnings.sort_by(|a, b| b.severity.cmp(&a.severity));
+
+    Ok(warnings)
+}
+
+fn is_active(entry: &MedicationHistoryEntry, date: NaiveDate) -> bool {
+    if !entry.is_latest_for_item {
+        return false;
+    }
+    match entry.refill_due_date {
+        Some(refill_due_date) => refill_due_date >= date,
+        None => entry.prescription_date + Duration::days(DEFAULT_ACTIVE_DAYS) >= date,
+    }
+}
+
+fn check_pair(
+    medication: &Medication,
+    other: &Medication,
+    ingredients: &HashMap<String, HashSet<String>>,
+    interactions: &[DrugInteractionRow],
+) -> Vec<ClinicalWarning> {
+    let empty = HashSet::new();
+    let medication_ingredients = ingredients.get(&medication.item_code).unwrap_or(&empty);
+    let other_ingredients = ingredients.get(&other.item_code).unwrap_or(&empty);
+
+    // Order of the pair doesn't matter, the same warning is raised when checking either item
+    let (first_id, second_id) = if medication.item_id <= other.item_id {
+        (&medication.item_id, &other.item_id)
+    } else {
+        (&other.item_id, &medication.item_id)
+    };
+    let warning = |id: String, r#type, severity, description| ClinicalWarning {
+        id,
+        r#type,
+        severity,
+        item_id: medication.item_id.clone(),
+        item_name: medication.item_name.clone(),
+        interacting_item_id: other.item_id.clone(),
+        interacting_item_name: other.item_name.clone(),
+        interacting_invoice_id: other.invoice_id.clone(),
+        description,
+    };
+
+    let mut warnings = Vec::new();
+
+    let duplicate_description = if medication.item_id == other.item_id {
+        Some(format!(
+            "{} has already been dispensed to the patient",
+            medication.item_name
+        ))
+    } else {
+        let mut shared: Vec<&str> = medication_ingredients
+            .intersection(other_ingredients)
+            .map(String::as_str)
+            .collect();
+        shared.sort();
+        (!shared.is_empty()).then(|| {
+            format!(
+                "{} and {} both contain {}",
+                medication.item_name,
+                other.item_name,
+                shared.join(", ")
+            )
+        })
+    };
+    if let Some(description) = duplicate_description {
+        warnings.push(warning(
+            format!("duplicate_{}_{}", first_id, second_id),
+            ClinicalWarningType::DuplicateTherapy,
+            DrugInteractionSeverity::Moderate,
+            description,
+        ));
+    }
+
+    let matches = |subject: &str, medication: &Medication, ingredients: &HashSet<String>| {
+        medication.item_code == subject || ingredients.contains(subject)
+    };
+    for interaction in interactions {
+        let is_match = (matches(&interaction.subject_a, medication, medication_ingredients)
+            && matches(&interaction.subject_b, other, other_ingredients))
+            || (matches(&interaction.subject_b, medication, medication_ingredients)
+                && matches(&interaction.subject_a, other, other_ingredients));
+        if !is_match {
+            continue;
+        }
+        let description = if interaction.description.is_empty() {
+            format!(
+                "Interaction between {} and {}",
+                medication.item_name, other.item_name
+            )
+        } else {
+            interaction.description.clone()
+        };
+        warnings.push(warning(
+            format!("interaction_{}_{}_{}", interaction.id, first_id, second_id),
+            ClinicalWarningType::Interaction,
+            interaction.severity.clone(),
+            description,
+        ));
+    }
+
+    warnings
+}
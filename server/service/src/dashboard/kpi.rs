@@ -0,0 +1,602 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, Duration, Months, NaiveDate, NaiveDateTime};
+use repository::{
+    DatetimeFilter, EqualFilter, InvoiceFilter, InvoiceLineFilter, InvoiceLineRepository,
+    InvoiceLineType, InvoiceRepository, InvoiceType, RepositoryError, RequisitionFilter,
+    RequisitionLineFilter, RequisitionLineRepository, RequisitionRepository, RequisitionType,
+    StockLineRow, StockLineRowRepository, StockMovementFilter, StockMovementRepository,
+    StorageConnection,
+};
+
+use crate::service_provider::ServiceContext;
+
+pub const MAX_NUMBER_OF_KPI_PERIODS: u32 = 36;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KpiPeriodLength {
+    /// Monday to Sunday
+    Week,
+    Month,
+    Quarter,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KpiInput {
+    /// KPIs are calculated separately for each store, to allow comparison between stores
+    pub store_ids: Vec<String>,
+    pub period_length: KpiPeriodLength,
+    pub number_of_periods: u32,
+    /// The last period is the one containing this date
+    pub end_date: NaiveDate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KpiPeriod {
+    pub start_date: NaiveDate,
+    /// Inclusive
+    pub end_date: NaiveDate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KpiPeriodValues {
+    pub period: KpiPeriod,
+    /// Share of stocked items with no stock at the end of the period, None if no items are stocked
+    pub stockout_rate: Option<f64>,
+    /// Quantity supplied by inbound shipments over quantity requested, for request requisitions
+    /// sent in the period. None if nothing was requested
+    pub order_fill_rate: Option<f64>,
+    /// Average days between shipped and delivered datetime of inbound shipments delivered in the
+    /// period. None if no shipments were delivered
+    pub average_lead_time_days: Option<f64>,
+    /// Cost value of stock on hand at the end of the period
+    pub stock_value: f64,
+    /// Cost value of stock that was on hand when it expired during the period
+    pub expiry_wastage_value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoreKpis {
+    pub store_id: String,
+    pub periods: Vec<KpiPeriodValues>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum KpiError {
+    NoStoresSelected,
+    InvalidNumberOfPeriods,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for KpiError {
+    fn from(error: RepositoryError) -> Self {
+        KpiError::DatabaseError(error)
+    }
+}
+
+pub trait KpiServiceTrait: Send + Sync {
+    fn get_kpis(&self, ctx: &ServiceContext, input: KpiInput) -> Result<Vec<StoreKpis>, KpiError> {
+        get_kpis(ctx, input)
+    }
+}
+
+pub struct KpiService {}
+impl KpiServiceTrait for KpiService {}
+
+pub fn get_kpis(ctx: &ServiceContext, input: KpiInput) -> Result<Vec<StoreKpis>, KpiError> {
+    let KpiInput {
+        store_ids,
+        period_length,
+        number_of_periods,
+        end_date,
+    } = input;
+
+    if store_ids.is_empty() {
+        return Err(KpiError::NoStoresSelected);
+    }
+    if number_of_periods == 0 || number_of_periods > MAX_NUMBER_OF_KPI_PERIODS {
+        return Err(KpiError::InvalidNumberOfPeriods);
+    }
+
+    let periods = generate_periods(period_length, number_of_periods, end_date)
+        .ok_or(KpiError::InvalidNumberOfPeriods)?;
+
+    let mut result = Vec::new();
+    for store_id in store_ids {
+        let periods = store_kpis(&ctx.connection, &store_id, &periods)?;
+        result.push(StoreKpis { store_id, periods });
+    }
+    Ok(result)
+}
+
+fn period_start(date: NaiveDate, period_length: KpiPeriodLength) -> Option<NaiveDate> {
+    match period_length {
+        KpiPeriodLength::Week => {
+            Some(date - Duration::days(date.weekday().num_days_from_monday() as i64))
+        }
+        KpiPeriodLength::Month => date.with_day(1),
+        KpiPeriodLength::Quarter => {
+            NaiveDate::from_ymd_opt(date.year(), (date.month0() / 3) * 3 + 1, 1)
+        }
+    }
+}
+
+fn add_periods(date: NaiveDate, period_length: KpiPeriodLength, count: i64) -> Option<NaiveDate> {
+    let months = match period_length {
+        KpiPeriodLength::Week => return Some(date + Duration::weeks(count)),
+        KpiPeriodLength::Month => count,
+        KpiPeriodLength::Quarter => count * 3,
+    };
+    if months >= 0 {
+        date.checked_add_months(Months::new(months as u32))
+    } else {
+        date.checked_sub_months(Months::new(months.unsigned_abs() as u32))
+    }
+}
+
+/// Consecutive periods in chronological order, the last one containing `end_date`
+fn generate_periods(
+    period_length: KpiPeriodLength,
+    number_of_periods: u32,
+    end_date: NaiveDate,
+) -> Option<Vec<KpiPeriod>> {
+    let last_start = period_start(end_date, period_length)?;
+    let mut start_date = add_periods(last_start, period_length, -(number_of_periods as i64 - 1))?;
+
+    let mut periods = Vec::new();
+    for _ in 0..number_of_periods {
+        let next_start = add_periods(start_date, period_length, 1)?;
+        periods.push(KpiPeriod {
+            start_date,
+            end_date: next_start - Duration::days(1),
+        });
+        start_date = next_start;
+    }
+    Some(periods)
+}
+
+fn start_of_day(date: NaiveDate) -> NaiveDateTime {
+    date.and_hms_opt(0, 0, 0).unwrap()
+}
+
+fn period_index(periods: &[KpiPeriod], date: NaiveDate) -> Option<usize> {
+    periods
+        .iter()
+        .position(|period| period.start_date <= date && date <= period.end_date)
+}
+
+/// Stock line with the movements since the start of the first period, used to work out historic
+/// stock on hand the same way as the ledger: current stock minus movements after a point in time
+struct StockLineHistory {
+    row: StockLineRow,
+    movements: Vec<(NaiveDateTime, f64)>,
+}
+
+impl StockLineHistory {
+    fn units_at(&self, datetime: NaiveDateTime) -> f64 {
+        let current_units = self.row.total_number_of_packs * self.row.pack_size;
+        let units_since: f64 = self
+            .movements
+            .iter()
+            .filter(|(movement_datetime, _)| *movement_datetime >= datetime)
+            .map(|(_, quantity)| quantity)
+            .sum();
+        current_units - units_since
+    }
+
+    /// Stock lines without movements since the first period existed before it
+    fn existed_at(&self, datetime: NaiveDateTime) -> bool {
+        self.movements.is_empty()
+            || self
+                .movements
+                .iter()
+                .any(|(movement_datetime, _)| *movement_datetime < datetime)
+    }
+
+    fn value_at(&self, datetime: NaiveDateTime) -> f64 {
+        if self.row.pack_size <= 0.0 {
+            return 0.0;
+        }
+        self.units_at(datetime).max(0.0) / self.row.pack_size * self.row.cost_price_per_pack
+    }
+}
+
+fn stock_line_histories(
+    connection: &StorageConnection,
+    store_id: &str,
+    from: NaiveDateTime,
+) -> Result<Vec<StockLineHistory>, RepositoryError> {
+    let movements = StockMovementRepository::new(connection).query(Some(StockMovementFilter {
+        store_id: Some(EqualFilter::equal_to(store_id)),
+        datetime: Some(DatetimeFilter::after_or_equal_to(from)),
+        ..Default::default()
+    }))?;
+
+    let mut movements_by_stock_line: HashMap<String, Vec<(NaiveDateTime, f64)>> = HashMap::new();
+    for movement in movements {
+        // Non stock lines (services) don't have a stock line
+        if let Some(stock_line_id) = movement.stock_line_id {
+            movements_by_stock_line
+                .entry(stock_line_id)
+                .or_default()
+                .push((movement.datetime, movement.quantity));
+        }
+    }
+
+    let histories = StockLineRowRepository::new(connection)
+        .find_by_store_id(store_id)?
+        .into_iter()
+        .map(|row| StockLineHistory {
+            movements: movements_by_stock_line.remove(&row.id).unwrap_or_default(),
+            row,
+        })
+        .collect();
+    Ok(histories)
+}
+
+fn stockout_rate(histories: &[StockLineHistory], datetime: NaiveDateTime) -> Option<f64> {
+    let mut units_by_item: HashMap<&str, f64> = HashMap::new();
+    for history in histories
+        .iter()
+        .filter(|history| history.existed_at(datetime))
+    {
+        *units_by_item.entry(&history.row.item_link_id).or_default() += history.units_at(datetime);
+    }
+    if units_by_item.is_empty() {
+        return None;
+    }
+
+    let stocked_out = units_by_item
+        .values()
+        .filter(|units| **units <= 0.0)
+        .count();
+    Some(stocked_out as f64 / units_by_item.len() as f64)
+}
+
+fn expiry_wastage_value(histories: &[StockLineHistory], period: &KpiPeriod) -> f64 {
+    histories
+        .iter()
+        .filter_map(|history| {
+            let expiry_date = history.row.expiry_date?;
+            (period.start_date <= expiry_date && expiry_date <= period.end_date)
+                .then(|| history.value_at(start_of_day(expiry_date)))
+        })
+        .sum()
+}
+
+fn order_fill_rates(
+    connection: &StorageConnection,
+    store_id: &str,
+    periods: &[KpiPeriod],
+    from: NaiveDateTime,
+) -> Result<Vec<Option<f64>>, RepositoryError> {
+    let requisitions = RequisitionRepository::new(connection).query_by_filter(
+        RequisitionFilter::new()
+            .store_id(EqualFilter::equal_to(store_id))
+            .r#type(RequisitionType::Request.equal_to())
+            .sent_datetime(DatetimeFilter::after_or_equal_to(from)),
+    )?;
+    let requisition_periods: HashMap<String, usize> = requisitions
+        .into_iter()
+        .filter_map(|requisition| {
+            let sent_date = requisition.requisition_row.sent_datetime?.date();
+            let index = period_index(periods, sent_date)?;
+            Some((requisition.requisition_row.id, index))
+        })
+        .collect();
+    let requisition_ids: Vec<String> = requisition_periods.keys().cloned().collect();
+
+    let mut supplied: HashMap<(String, String), f64> = HashMap::new();
+    let inbound_lines = InvoiceLineRepository::new(connection).query_by_filter(
+        InvoiceLineFilter::new()
+            .requisition_id(EqualFilter::equal_any(requisition_ids.clone()))
+            .invoice_type(InvoiceType::InboundShipment.equal_to())
+            .r#type(InvoiceLineType::StockIn.equal_to()),
+    )?;
+    for line in inbound_lines {
+        let Some(requisition_id) = line.invoice_row.requisition_id else {
+            continue;
+        };
+        let line = line.invoice_line_row;
+        *supplied
+            .entry((requisition_id, line.item_link_id))
+            .or_default() += line.number_of_packs * line.pack_size;
+    }
+
+    let mut totals = vec![(0.0, 0.0); periods.len()];
+    let requisition_lines = RequisitionLineRepository::new(connection).query_by_filter(
+        RequisitionLineFilter::new().requisition_id(EqualFilter::equal_any(requisition_ids)),
+    )?;
+    for line in requisition_lines {
+        let line = line.requisition_line_row;
+        let Some(index) = requisition_periods.get(&line.requisition_id) else {
+            continue;
+        };
+        let supplied_quantity = supplied
+            .get(&(line.requisition_id, line.item_link_id))
+            .copied()
+            .unwrap_or_default();
+        let (requested, filled) = &mut totals[*index];
+        *requested += line.requested_quantity;
+        // Over supply doesn't make up for other lines
+        *filled += supplied_quantity.min(line.requested_quantity);
+    }
+
+    Ok(totals
+        .into_iter()
+        .map(|(requested, filled)| (requested > 0.0).then(|| filled / requested))
+        .collect())
+}
+
+fn average_lead_times(
+    connection: &StorageConnection,
+    store_id: &str,
+    periods: &[KpiPeriod],
+    from: NaiveDateTime,
+) -> Result<Vec<Option<f64>>, RepositoryError> {
+    let invoices = InvoiceRepository::new(connection).query_by_filter(
+        InvoiceFilter::new()
+            .store_id(EqualFilter::equal_to(store_id))
+            .r#type(InvoiceType::InboundShipment.equal_to())
+            .delivered_datetime(DatetimeFilter::after_or_equal_to(from)),
+    )?;
+
+    let mut lead_times = vec![Vec::new(); periods.len()];
+    for invoice in invoices {
+        let invoice = invoice.invoice_row;
+        // Manually entered inbound shipments are never shipped
+        let (Some(shipped_datetime), Some(delivered_datetime)) =
+            (invoice.shipped_datetime, invoice.delivered_datetime)
+        else {
+            continue;
+        };
+        if let Some(index) = period_index(periods, delivered_datetime.date()) {
+            let days = (delivered_datetime - shipped_datetime).num_seconds() as f64 / 86_400.0;
+            lead_times[index].push(days);
+        }
+    }
+
+    Ok(lead_times
+        .into_iter()
+        .map(|days| (!days.is_empty()).then(|| days.iter().sum::<f64>() / days.len() as f64))
+        .collect())
+}
+
+fn store_kpis(
+    connection: &StorageConnection,
+    store_id: &str,
+    periods: &[KpiPeriod],
+) -> Result<Vec<KpiPeriodValues>, RepositoryError> {
+    let Some(first_period) = periods.first() else {
+        return Ok(Vec::new());
+    };
+    let from = start_of_day(first_period.start_date);
+
+    let histories = stock_line_histories(connection, store_id, from)?;
+    let order_fill_rates = order_fill_rates(connection, store_id, periods, from)?;
+    let average_lead_times = average_lead_times(connection, store_id, periods, from)?;
+
+    let result = periods
+        .iter()
+        .zip(order_fill_rates)
+        .zip(average_lead_times)
+        .map(|((period, order_fill_rate), average_lead_time_days)| {
+            let end_of_period = start_of_day(period.end_date + Duration::days(1));
+            KpiPeriodValues {
+                stockout_rate: stockout_rate(&histories, end_of_period),
+                order_fill_rate,
+                average_lead_time_days,
+                stock_value: histories
+                    .iter()
+                    .map(|history| history.value_at(end_of_period))
+                    .sum(),
+                expiry_wastage_value: expiry_wastage_value(&histories, period),
+                period: period.clone(),
+            }
+        })
+        .collect();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{mock_item_a, mock_name_a, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        InvoiceLineRow, InvoiceLineType, InvoiceRow, InvoiceStatus, InvoiceType,
+        RequisitionLineRow, RequisitionRow, RequisitionStatus, RequisitionType, StockLineRow,
+    };
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn datetime(month: u32, day: u32) -> NaiveDateTime {
+        date(month, day).and_hms_opt(10, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn kpi_periods() {
+        let periods = generate_periods(KpiPeriodLength::Quarter, 2, date(2, 15)).unwrap();
+        assert_eq!(
+            periods,
+            vec![
+                KpiPeriod {
+                    start_date: NaiveDate::from_ymd_opt(2023, 10, 1).unwrap(),
+                    end_date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+                },
+                KpiPeriod {
+                    start_date: date(1, 1),
+                    end_date: date(3, 31),
+                },
+            ]
+        );
+
+        // 2024-02-15 is a Thursday
+        let periods = generate_periods(KpiPeriodLength::Week, 1, date(2, 15)).unwrap();
+        assert_eq!(
+            periods,
+            vec![KpiPeriod {
+                start_date: date(2, 12),
+                end_date: date(2, 18),
+            }]
+        );
+
+        let periods = generate_periods(KpiPeriodLength::Month, 2, date(3, 31)).unwrap();
+        assert_eq!(periods[0].start_date, date(2, 1));
+        assert_eq!(periods[0].end_date, date(2, 29));
+        assert_eq!(periods[1].end_date, date(3, 31));
+    }
+
+    #[actix_rt::test]
+    async fn kpi_service() {
+        let stock_line = StockLineRow {
+            id: "kpi_stock_line".to_string(),
+            item_link_id: mock_item_a().id,
+            store_id: mock_store_a().id,
+            pack_size: 1.0,
+            cost_price_per_pack: 2.0,
+            total_number_of_packs: 10.0,
+            available_number_of_packs: 10.0,
+            expiry_date: Some(date(2, 10)),
+            ..Default::default()
+        };
+        let request_requisition = RequisitionRow {
+            id: "kpi_request_requisition".to_string(),
+            name_link_id: mock_name_a().id,
+            store_id: mock_store_a().id,
+            r#type: RequisitionType::Request,
+            status: RequisitionStatus::Sent,
+            created_datetime: datetime(1, 1),
+            sent_datetime: Some(datetime(1, 2)),
+            ..Default::default()
+        };
+        let request_requisition_line = RequisitionLineRow {
+            id: "kpi_request_requisition_line".to_string(),
+            requisition_id: request_requisition.id.clone(),
+            item_link_id: mock_item_a().id,
+            requested_quantity: 40.0,
+            ..Default::default()
+        };
+        let inbound_shipment = InvoiceRow {
+            id: "kpi_inbound_shipment".to_string(),
+            name_link_id: mock_name_a().id,
+            store_id: mock_store_a().id,
+            r#type: InvoiceType::InboundShipment,
+            status: InvoiceStatus::Delivered,
+            requisition_id: Some(request_requisition.id.clone()),
+            created_datetime: datetime(1, 6),
+            shipped_datetime: Some(datetime(1, 6)),
+            delivered_datetime: Some(datetime(1, 10)),
+            ..Default::default()
+        };
+        let outbound_shipment = InvoiceRow {
+            id: "kpi_outbound_shipment".to_string(),
+            name_link_id: mock_name_a().id,
+            store_id: mock_store_a().id,
+            r#type: InvoiceType::OutboundShipment,
+            status: InvoiceStatus::Picked,
+            created_datetime: datetime(3, 5),
+            picked_datetime: Some(datetime(3, 5)),
+            ..Default::default()
+        };
+        let line = |id: &str, invoice: &InvoiceRow, r#type, number_of_packs| InvoiceLineRow {
+            id: id.to_string(),
+            invoice_id: invoice.id.clone(),
+            item_link_id: mock_item_a().id,
+            stock_line_id: Some(stock_line.id.clone()),
+            pack_size: 1.0,
+            r#type,
+            number_of_packs,
+            ..Default::default()
+        };
+        let invoice_lines = vec![
+            line(
+                "kpi_inbound_line",
+                &inbound_shipment,
+                InvoiceLineType::StockIn,
+                30.0,
+            ),
+            line(
+                "kpi_outbound_line",
+                &outbound_shipment,
+                InvoiceLineType::StockOut,
+                20.0,
+            ),
+        ];
+
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "kpi_service",
+            MockDataInserts::none().items().names().stores().units(),
+            MockData {
+                stock_lines: vec![stock_line],
+                invoices: vec![inbound_shipment, outbound_shipment],
+                invoice_lines,
+                requisitions: vec![request_requisition],
+                requisition_lines: vec![request_requisition_line],
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.basic_context().unwrap();
+        let service = service_provider.kpi_service;
+
+        let input = KpiInput {
+            store_ids: vec![mock_store_a().id],
+            period_length: KpiPeriodLength::Month,
+            number_of_periods: 3,
+            end_date: date(3, 15),
+        };
+
+        assert_eq!(
+            service.get_kpis(
+                &context,
+                KpiInput {
+                    store_ids: Vec::new(),
+                    ..input.clone()
+                }
+            ),
+            Err(KpiError::NoStoresSelected)
+        );
+        assert_eq!(
+            service.get_kpis(
+                &context,
+                KpiInput {
+                    number_of_periods: MAX_NUMBER_OF_KPI_PERIODS + 1,
+                    ..input.clone()
+                }
+            ),
+            Err(KpiError::InvalidNumberOfPeriods)
+        );
+
+        let result = service.get_kpis(&context, input).unwrap();
+        assert_eq!(result.len(), 1);
+        let periods = &result[0].periods;
+
+        // January: 30 received of 40 requested, 4 days after shipping
+        assert_eq!(periods[0].period.start_date, date(1, 1));
+        assert_eq!(periods[0].order_fill_rate, Some(0.75));
+        assert_eq!(periods[0].average_lead_time_days, Some(4.0));
+        assert_eq!(periods[0].stock_value, 60.0);
+        assert_eq!(periods[0].stockout_rate, Some(0.0));
+        assert_eq!(periods[0].expiry_wastage_value, 0.0);
+
+        // February: all 30 units expired on the shelf
+        assert_eq!(periods[1].order_fill_rate, None);
+        assert_eq!(periods[1].average_lead_time_days, None);
+        assert_eq!(periods[1].stock_value, 60.0);
+        assert_eq!(periods[1].expiry_wastage_value, 60.0);
+
+        // March: 20 units issued
+        assert_eq!(periods[2].stock_value, 20.0);
+        assert_eq!(periods[2].stockout_rate, Some(0.0));
+        assert_eq!(periods[2].expiry_wastage_value, 0.0);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod invoice_count;
 pub mod item_count;
+pub mod kpi;
 pub mod requisition_count;
 pub mod stock_expiry_count;
@@ -1,10 +1,8 @@
 use crate::activity_log::{activity_log_entry, log_type_from_invoice_status};
+use crate::recall::upsert_received_stock_line;
 use crate::{invoice::query::get_invoice, service_provider::ServiceContext, WithDBError};
 use repository::Invoice;
-use repository::{
-    InvoiceLineRowRepository, InvoiceRowRepository, InvoiceStatus, RepositoryError,
-    StockLineRowRepository,
-};
+use repository::{InvoiceLineRowRepository, InvoiceRowRepository, InvoiceStatus, RepositoryError};
 
 mod generate;
 mod validate;
@@ -57,10 +55,8 @@ pub fn update_customer_return(
             let invoice_line_repository = InvoiceLineRowRepository::new(connection);
 
             if let Some(lines_and_invoice_lines) = batches_to_update {
-                let stock_line_repository = StockLineRowRepository::new(connection);
-
                 for LineAndStockLine { line, stock_line } in lines_and_invoice_lines.into_iter() {
-                    stock_line_repository.upsert_one(&stock_line)?;
+                    upsert_received_stock_line(connection, &stock_line)?;
                     invoice_line_repository.upsert_one(&line)?;
                 }
             }
@@ -0,0 +1,134 @@
+use repository::{
+    InvoiceLineDiscrepancyType, InvoiceLineRow, InvoiceLineRowRepository, InvoiceRow, InvoiceType,
+    RepositoryError,
+};
+
+use crate::service_provider::ServiceContext;
+
+use super::{check_invoice_exists, check_store};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscrepancyReportLine {
+    pub line: InvoiceLineRow,
+    /// In the pack size of the line. For inbound lines this comes from the outbound line, which
+    /// is only known once the outbound shipment has been synced to this site
+    pub shipped_number_of_packs: Option<f64>,
+    /// Discrepancy packs at the price per pack of the line (cost price for inbound shipments,
+    /// sell price for outbound shipments)
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscrepancyTypeTotal {
+    pub r#type: InvoiceLineDiscrepancyType,
+    pub line_count: u32,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscrepancyReport {
+    pub invoice: InvoiceRow,
+    pub lines: Vec<DiscrepancyReportLine>,
+    /// Only types with at least one line
+    pub totals: Vec<DiscrepancyTypeTotal>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum DiscrepancyReportError {
+    InvoiceDoesNotExist,
+    NotThisStoreInvoice,
+    NotAShipment,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for DiscrepancyReportError {
+    fn from(error: RepositoryError) -> Self {
+        DiscrepancyReportError::DatabaseError(error)
+    }
+}
+
+/// Discrepancies recorded by the receiving store. For an inbound shipment these are the
+/// discrepancies as recorded, for an outbound shipment they're the discrepancies sent back
+/// when the inbound shipment was verified
+pub fn get_discrepancy_report(
+    ctx: &ServiceContext,
+    invoice_id: &str,
+) -> Result<DiscrepancyReport, DiscrepancyReportError> {
+    let connection = &ctx.connection;
+    let invoice = check_invoice_exists(invoice_id, connection)?
+        .ok_or(DiscrepancyReportError::InvoiceDoesNotExist)?;
+    if !check_store(&invoice, &ctx.store_id) {
+        return Err(DiscrepancyReportError::NotThisStoreInvoice);
+    }
+    let is_inbound = match invoice.r#type {
+        InvoiceType::InboundShipment => true,
+        InvoiceType::OutboundShipment => false,
+        _ => return Err(DiscrepancyReportError::NotAShipment),
+    };
+
+    let line_repository = InvoiceLineRowRepository::new(connection);
+    let mut lines = line_repository.find_many_by_invoice_id(&invoice.id)?;
+    lines.retain(|line| line.discrepancy_type.is_some());
+
+    let outbound_lines = match is_inbound {
+        true => line_repository.find_many_by_id(
+            &lines
+                .iter()
+                .filter_map(|line| line.linked_invoice_line_id.clone())
+                .collect::<Vec<String>>(),
+        )?,
+        false => Vec::new(),
+    };
+
+    let mut report = DiscrepancyReport {
+        invoice,
+        lines: Vec::new(),
+        totals: Vec::new(),
+    };
+
+    for line in lines {
+        let Some(r#type) = line.discrepancy_type.clone() else {
+            continue;
+        };
+        let (shipped_number_of_packs, price_per_pack) = match is_inbound {
+            true => (
+                outbound_lines
+                    .iter()
+                    .find(|outbound_line| {
+                        Some(&outbound_line.id) == line.linked_invoice_line_id.as_ref()
+                    })
+                    .filter(|_| line.pack_size > 0.0)
+                    .map(|outbound_line| {
+                        outbound_line.number_of_packs * outbound_line.pack_size / line.pack_size
+                    }),
+                line.cost_price_per_pack,
+            ),
+            false => (Some(line.number_of_packs), line.sell_price_per_pack),
+        };
+        let value = line.discrepancy_number_of_packs * price_per_pack;
+
+        match report
+            .totals
+            .iter_mut()
+            .find(|total| total.r#type == r#type)
+        {
+            Some(total) => {
+                total.line_count += 1;
+                total.value += value;
+            }
+            None => report.totals.push(DiscrepancyTypeTotal {
+                r#type,
+                line_count: 1,
+                value,
+            }),
+        }
+
+        report.lines.push(DiscrepancyReportLine {
+            line,
+            shipped_number_of_packs,
+            value,
+        });
+    }
+
+    Ok(report)
+}
@@ -329,6 +329,10 @@ pub fn generate_lines_and_stock_lines(
             return_reason_id: _,
             foreign_currency_price_before_tax: _,
             item_variant_id,
+            linked_invoice_line_id: _,
+            discrepancy_type: _,
+            discrepancy_number_of_packs: _,
+            discrepancy_reason: _,
         }: InvoiceLineRow = invoice_lines;
 
         if number_of_packs > 0.0 {
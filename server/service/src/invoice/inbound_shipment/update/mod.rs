@@ -1,11 +1,11 @@
 use crate::activity_log::{activity_log_entry, log_type_from_invoice_status};
+use crate::backorder::allocate_backorders;
 use crate::invoice_line::ShipmentTaxUpdate;
+use crate::recall::upsert_received_stock_line;
+use crate::serial_number::receive_transferred_serial_numbers;
 use crate::{invoice::query::get_invoice, service_provider::ServiceContext, WithDBError};
-use repository::{Invoice, LocationMovementRowRepository};
-use repository::{
-    InvoiceLineRowRepository, InvoiceRowRepository, InvoiceStatus, RepositoryError,
-    StockLineRowRepository,
-};
+use repository::{Invoice, ItemLinkRowRepository, LocationMovementRowRepository};
+use repository::{InvoiceLineRowRepository, InvoiceRowRepository, InvoiceStatus, RepositoryError};
 
 mod generate;
 mod validate;
@@ -42,7 +42,7 @@ pub fn update_inbound_shipment(
     ctx: &ServiceContext,
     patch: UpdateInboundShipment,
 ) -> Result<Invoice, OutError> {
-    let invoice = ctx
+    let (invoice, received_item_ids) = ctx
         .connection
         .transaction_sync(|connection| {
             let (invoice, other_party, status_changed) =
@@ -66,12 +66,18 @@ pub fn update_inbound_shipment(
             InvoiceRowRepository::new(connection).upsert_one(&update_invoice)?;
             let invoice_line_repository = InvoiceLineRowRepository::new(connection);
 
+            let mut received_item_ids = Vec::new();
             if let Some(lines_and_invoice_lines) = batches_to_update {
-                let stock_line_repository = StockLineRowRepository::new(connection);
-
+                let item_link_repository = ItemLinkRowRepository::new(connection);
                 for LineAndStockLine { line, stock_line } in lines_and_invoice_lines.into_iter() {
-                    stock_line_repository.upsert_one(&stock_line)?;
+                    upsert_received_stock_line(connection, &stock_line)?;
                     invoice_line_repository.upsert_one(&line)?;
+                    receive_transferred_serial_numbers(connection, &ctx.store_id, &line)?;
+                    if let Some(item_link) =
+                        item_link_repository.find_one_by_id(&stock_line.item_link_id)?
+                    {
+                        received_item_ids.push(item_link.item_id);
+                    }
                 }
             }
 
@@ -117,12 +123,28 @@ pub fn update_inbound_shipment(
                 )?;
             }
 
-            get_invoice(ctx, None, &update_invoice.id)
+            let invoice = get_invoice(ctx, None, &update_invoice.id)
                 .map_err(OutError::DatabaseError)?
-                .ok_or(OutError::UpdatedInvoiceDoesNotExist)
+                .ok_or(OutError::UpdatedInvoiceDoesNotExist)?;
+            Ok::<_, OutError>((invoice, received_item_ids))
         })
         .map_err(|error| error.to_inner_error())?;
 
+    // Received stock goes to open backorders first. The shipment is received regardless, backorders
+    // that couldn't be allocated stay open and are allocated on the next receipt of the item
+    if !received_item_ids.is_empty() {
+        let result = ctx
+            .connection
+            .transaction_sync(|_| allocate_backorders(ctx, &received_item_ids))
+            .map_err(|error| error.to_inner_error());
+        if let Err(error) = result {
+            log::error!(
+                "Problem allocating received stock to backorders {:#?}",
+                error
+            );
+        }
+    }
+
     ctx.processors_trigger.trigger_invoice_transfer_processors();
 
     Ok(invoice)
@@ -137,6 +159,8 @@ pub enum UpdateInboundShipmentError {
     CannotEditFinalised,
     CannotChangeStatusOfInvoiceOnHold,
     CannotIssueForeignCurrencyForInternalSuppliers,
+    /// Holds the ids of lines of serialised items without a serial number for every unit
+    SerialNumbersNotRecorded(Vec<String>),
     // Name validation
     OtherPartyDoesNotExist,
     OtherPartyNotVisible,
@@ -3,8 +3,9 @@ use crate::invoice::{
     check_invoice_exists, check_invoice_is_editable, check_invoice_status, check_invoice_type,
     check_status_change, check_store, InvoiceRowStatusError,
 };
+use crate::serial_number::lines_missing_serial_numbers;
 use crate::validate::{check_other_party, CheckOtherPartyType, OtherPartyErrors};
-use repository::{InvoiceRow, InvoiceType, Name, StorageConnection};
+use repository::{InvoiceRow, InvoiceStatus, InvoiceType, Name, StorageConnection};
 
 use super::{UpdateInboundShipment, UpdateInboundShipmentError};
 
@@ -37,6 +38,12 @@ pub fn validate(
                 InvoiceRowStatusError::CannotReverseInvoiceStatus => CannotReverseInvoiceStatus,
             },
         )?;
+        if patch.full_status() == Some(InvoiceStatus::Verified) {
+            let line_ids = lines_missing_serial_numbers(connection, &invoice.id)?;
+            if !line_ids.is_empty() {
+                return Err(SerialNumbersNotRecorded(line_ids));
+            }
+        }
     }
 
     // Other party check
@@ -4,6 +4,7 @@ use repository::InvoiceLine;
 use repository::InvoiceSort;
 use repository::InvoiceType;
 use repository::PaginationOption;
+use repository::PrescriptionLineDirectionRow;
 use repository::RepositoryError;
 use repository::StockLine;
 
@@ -53,6 +54,9 @@ pub use self::prescription::*;
 
 pub mod common;
 
+pub mod discrepancy_report;
+pub use self::discrepancy_report::*;
+
 pub trait InvoiceServiceTrait: Sync + Send {
     fn get_invoices(
         &self,
@@ -206,6 +210,22 @@ pub trait InvoiceServiceTrait: Sync + Send {
         batch_prescription(ctx, input)
     }
 
+    fn set_prescription_line_directions(
+        &self,
+        ctx: &ServiceContext,
+        input: SetPrescriptionLineDirections,
+    ) -> Result<PrescriptionLineDirectionRow, SetPrescriptionLineDirectionsError> {
+        set_prescription_line_directions(ctx, input)
+    }
+
+    fn get_medication_history(
+        &self,
+        ctx: &ServiceContext,
+        patient_id: &str,
+    ) -> Result<Vec<MedicationHistoryEntry>, RepositoryError> {
+        get_medication_history(ctx, patient_id)
+    }
+
     fn generate_supplier_return_lines(
         &self,
         ctx: &ServiceContext,
@@ -311,6 +331,14 @@ pub trait InvoiceServiceTrait: Sync + Send {
     ) -> Result<StockLine, AddNewStockLineError> {
         add_new_stock_line(ctx, input)
     }
+
+    fn get_discrepancy_report(
+        &self,
+        ctx: &ServiceContext,
+        invoice_id: &str,
+    ) -> Result<DiscrepancyReport, DiscrepancyReportError> {
+        get_discrepancy_report(ctx, invoice_id)
+    }
 }
 
 pub struct InvoiceService;
@@ -47,6 +47,8 @@ pub enum UpdateOutboundShipmentError {
     OtherPartyDoesNotExist,
     // Error applies to unallocated lines with above zero quantity
     CanOnlyChangeToAllocatedWhenNoUnallocatedLines(Vec<InvoiceLine>),
+    /// Holds the ids of lines of serialised items without a serial number for every unit
+    SerialNumbersNotRecorded(Vec<String>),
     // Internal
     UpdatedInvoiceDoesNotExist,
     DatabaseError(RepositoryError),
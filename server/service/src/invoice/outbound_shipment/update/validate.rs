@@ -3,6 +3,7 @@ use crate::invoice::{
     check_invoice_exists, check_invoice_is_editable, check_invoice_status, check_invoice_type,
     check_status_change, check_store, InvoiceRowStatusError,
 };
+use crate::serial_number::lines_missing_serial_numbers;
 use crate::validate::get_other_party;
 use repository::{EqualFilter, NameLinkRowRepository};
 use repository::{
@@ -55,6 +56,12 @@ pub fn validate(
             },
         )?;
         check_can_change_status_to_allocated(connection, &invoice, patch.full_status())?;
+        if patch.full_status() == Some(InvoiceStatus::Shipped) {
+            let line_ids = lines_missing_serial_numbers(connection, &invoice.id)?;
+            if !line_ids.is_empty() {
+                return Err(SerialNumbersNotRecorded(line_ids));
+            }
+        }
     }
     Ok((invoice, status_changed))
 }
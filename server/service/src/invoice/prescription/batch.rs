@@ -2,9 +2,9 @@ use repository::{Invoice, InvoiceLine, RepositoryError};
 
 use crate::{
     invoice_line::stock_out_line::{
-        delete_stock_out_line, insert_stock_out_line, update_stock_out_line, DeleteStockOutLine,
-        DeleteStockOutLineError, InsertStockOutLine, InsertStockOutLineError, UpdateStockOutLine,
-        UpdateStockOutLineError,
+        delete_stock_out_line, insert_prescription_line, update_stock_out_line, DeleteStockOutLine,
+        DeleteStockOutLineError, InsertPrescriptionLineResult, InsertStockOutLine,
+        InsertStockOutLineError, UpdateStockOutLine, UpdateStockOutLineError,
     },
     service_provider::ServiceContext,
     BatchMutationsProcessor, InputWithResult, WithDBError,
@@ -28,8 +28,12 @@ pub struct BatchPrescription {
 
 pub type InsertPrescriptionsResult =
     Vec<InputWithResult<InsertPrescription, Result<Invoice, InsertPrescriptionError>>>;
-pub type InsertLinesResult =
-    Vec<InputWithResult<InsertStockOutLine, Result<InvoiceLine, InsertStockOutLineError>>>;
+pub type InsertLinesResult = Vec<
+    InputWithResult<
+        InsertStockOutLine,
+        Result<InsertPrescriptionLineResult, InsertStockOutLineError>,
+    >,
+>;
 pub type UpdateLinesResult =
     Vec<InputWithResult<UpdateStockOutLine, Result<InvoiceLine, UpdateStockOutLineError>>>;
 pub type DeleteLinesResult =
@@ -71,7 +75,7 @@ pub fn batch_prescription(
 
             // Normal Line
             let (has_errors, result) =
-                mutations_processor.do_mutations(input.insert_line, insert_stock_out_line);
+                mutations_processor.do_mutations(input.insert_line, insert_prescription_line);
             results.insert_line = result;
             if has_errors && !continue_on_error {
                 return Err(WithDBError::err(results));
@@ -0,0 +1,354 @@
+use repository::{
+    AdministrationRoute, EqualFilter, InvoiceLineFilter, InvoiceLineRepository, InvoiceType,
+    PrescriptionLineDirectionRow, PrescriptionLineDirectionRowRepository, RepositoryError,
+    StorageConnection, UnitRowRepository,
+};
+
+use crate::{
+    invoice::{check_invoice_exists, check_invoice_is_editable, check_invoice_type, check_store},
+    invoice_line::validate::check_line_row_exists,
+    service_provider::ServiceContext,
+};
+
+pub mod sig;
+pub use self::sig::*;
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct SetPrescriptionLineDirections {
+    pub invoice_line_id: String,
+    /// Sig codes as written by the prescriber, expanded to fill in any field not provided
+    pub sig: Option<String>,
+    pub dose: Option<f64>,
+    pub dose_unit: Option<String>,
+    pub frequency_per_day: Option<f64>,
+    pub duration_days: Option<i32>,
+    pub route: Option<AdministrationRoute>,
+    /// Overrides the generated full text directions
+    pub directions: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SetPrescriptionLineDirectionsError {
+    LineDoesNotExist,
+    InvoiceDoesNotExist,
+    NotThisStoreInvoice,
+    NotAPrescriptionInvoice,
+    CannotEditFinalised,
+    DoseMustBePositive,
+    FrequencyMustBePositive,
+    DurationMustBePositive,
+    /// Neither sig, dose nor directions were provided
+    NoDirections,
+    DatabaseError(RepositoryError),
+}
+
+type OutError = SetPrescriptionLineDirectionsError;
+
+impl From<RepositoryError> for SetPrescriptionLineDirectionsError {
+    fn from(error: RepositoryError) -> Self {
+        SetPrescriptionLineDirectionsError::DatabaseError(error)
+    }
+}
+
+/// Dispensed quantities are in item units, a dose in another unit (e.g. mL of a tablet) can't be
+/// compared. Doses or items without a unit are assumed to match
+pub(crate) fn is_dose_in_item_unit(
+    direction: &PrescriptionLineDirectionRow,
+    item_unit: Option<&str>,
+) -> bool {
+    match (&direction.dose_unit, item_unit) {
+        (Some(dose_unit), Some(item_unit)) => is_same_unit(dose_unit, item_unit),
+        _ => true,
+    }
+}
+
+/// Number of item units needed to follow the directions for the full duration, None when dose,
+/// frequency or duration is not known or the dose is not in the item unit
+pub fn quantity_to_dispense(
+    direction: &PrescriptionLineDirectionRow,
+    item_unit: Option<&str>,
+) -> Option<f64> {
+    if !is_dose_in_item_unit(direction, item_unit) {
+        return None;
+    }
+    match (
+        direction.dose,
+        direction.frequency_per_day,
+        direction.duration_days,
+    ) {
+        (Some(dose), Some(frequency_per_day), Some(duration_days)) => {
+            Some((dose * frequency_per_day * duration_days as f64).ceil())
+        }
+        _ => None,
+    }
+}
+
+/// Same as `quantity_to_dispense`, with the unit of the item of the directions' line
+pub fn get_quantity_to_dispense(
+    connection: &StorageConnection,
+    direction: &PrescriptionLineDirectionRow,
+) -> Result<Option<f64>, RepositoryError> {
+    let line = InvoiceLineRepository::new(connection)
+        .query_by_filter(
+            InvoiceLineFilter::new().id(EqualFilter::equal_to(&direction.invoice_line_id)),
+        )?
+        .pop()
+        .ok_or(RepositoryError::NotFound)?;
+    let item_unit = match &line.item_row.unit_id {
+        Some(unit_id) => UnitRowRepository::new(connection)
+            .find_one_by_id(unit_id)?
+            .map(|unit| unit.name),
+        None => None,
+    };
+
+    Ok(quantity_to_dispense(direction, item_unit.as_deref()))
+}
+
+pub fn set_prescription_line_directions(
+    ctx: &ServiceContext,
+    input: SetPrescriptionLineDirections,
+) -> Result<PrescriptionLineDirectionRow, SetPrescriptionLineDirectionsError> {
+    let direction = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let line = check_line_row_exists(connection, &input.invoice_line_id)?
+                .ok_or(OutError::LineDoesNotExist)?;
+            let invoice = check_invoice_exists(&line.invoice_id, connection)?
+                .ok_or(OutError::InvoiceDoesNotExist)?;
+            if !check_store(&invoice, &ctx.store_id) {
+                return Err(OutError::NotThisStoreInvoice);
+            }
+            if !check_invoice_type(&invoice, InvoiceType::Prescription) {
+                return Err(OutError::NotAPrescriptionInvoice);
+            }
+            if !check_invoice_is_editable(&invoice) {
+                return Err(OutError::CannotEditFinalised);
+            }
+
+            let repo = PrescriptionLineDirectionRowRepository::new(connection);
+            let existing = repo.find_one_by_invoice_line_id(&input.invoice_line_id)?;
+            let direction = generate(existing, input)?;
+            repo.upsert_one(&direction)?;
+
+            Ok(direction)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(direction)
+}
+
+fn generate(
+    existing: Option<PrescriptionLineDirectionRow>,
+    SetPrescriptionLineDirections {
+        invoice_line_id,
+        sig,
+        dose,
+        dose_unit,
+        frequency_per_day,
+        duration_days,
+        route,
+        directions,
+    }: SetPrescriptionLineDirections,
+) -> Result<PrescriptionLineDirectionRow, OutError> {
+    let sig = sig.filter(|sig| !sig.trim().is_empty());
+    let directions = directions.filter(|directions| !directions.trim().is_empty());
+    if sig.is_none() && dose.is_none() && directions.is_none() {
+        return Err(OutError::NoDirections);
+    }
+
+    let expanded = sig.as_deref().map(expand_sig).unwrap_or_default();
+    let merged = ExpandedSig {
+        dose: dose.or(expanded.dose),
+        dose_unit: dose_unit.or(expanded.dose_unit),
+        // Description from the sig only applies when the frequency came from the sig
+        frequency_description: match frequency_per_day {
+            Some(_) => None,
+            None => expanded.frequency_description,
+        },
+        frequency_per_day: frequency_per_day.or(expanded.frequency_per_day),
+        duration_days: duration_days.or(expanded.duration_days),
+        route: route.or(expanded.route),
+        instructions: expanded.instructions,
+    };
+
+    if merged.dose.is_some_and(|dose| dose <= 0.0) {
+        return Err(OutError::DoseMustBePositive);
+    }
+    if merged
+        .frequency_per_day
+        .is_some_and(|frequency| frequency <= 0.0)
+    {
+        return Err(OutError::FrequencyMustBePositive);
+    }
+    if merged.duration_days.is_some_and(|duration| duration <= 0) {
+        return Err(OutError::DurationMustBePositive);
+    }
+
+    Ok(PrescriptionLineDirectionRow {
+        id: existing
+            .map(|existing| existing.id)
+            .unwrap_or_else(util::uuid::uuid),
+        invoice_line_id,
+        directions: directions.unwrap_or_else(|| merged.directions()),
+        sig,
+        dose: merged.dose,
+        dose_unit: merged.dose_unit,
+        frequency_per_day: merged.frequency_per_day,
+        duration_days: merged.duration_days,
+        route: merged.route,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{
+            mock_inbound_shipment_a_invoice_lines, mock_prescription_a_invoice_line_a,
+            mock_store_a, mock_store_b, MockDataInserts,
+        },
+        test_db::setup_all,
+        AdministrationRoute, InvoiceLineRowRepository, PrescriptionLineDirectionRowRepository,
+    };
+
+    use crate::{
+        invoice::prescription::{
+            get_quantity_to_dispense, quantity_to_dispense, SetPrescriptionLineDirections,
+            SetPrescriptionLineDirectionsError as ServiceError,
+        },
+        service_provider::ServiceProvider,
+    };
+
+    #[actix_rt::test]
+    async fn set_prescription_line_directions() {
+        let (_, connection, connection_manager, _) =
+            setup_all("set_prescription_line_directions", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = &service_provider.invoice_service;
+        let line_id = mock_prescription_a_invoice_line_a().id;
+
+        // LineDoesNotExist
+        assert_eq!(
+            service.set_prescription_line_directions(
+                &context,
+                SetPrescriptionLineDirections {
+                    invoice_line_id: "invalid".to_string(),
+                    sig: Some("1T BD".to_string()),
+                    ..Default::default()
+                }
+            ),
+            Err(ServiceError::LineDoesNotExist)
+        );
+        // NotAPrescriptionInvoice
+        assert_eq!(
+            service.set_prescription_line_directions(
+                &context,
+                SetPrescriptionLineDirections {
+                    invoice_line_id: mock_inbound_shipment_a_invoice_lines()[0].id.clone(),
+                    sig: Some("1T BD".to_string()),
+                    ..Default::default()
+                }
+            ),
+            Err(ServiceError::NotAPrescriptionInvoice)
+        );
+        // NoDirections
+        assert_eq!(
+            service.set_prescription_line_directions(
+                &context,
+                SetPrescriptionLineDirections {
+                    invoice_line_id: line_id.clone(),
+                    sig: Some("  ".to_string()),
+                    ..Default::default()
+                }
+            ),
+            Err(ServiceError::NoDirections)
+        );
+        // DurationMustBePositive
+        assert_eq!(
+            service.set_prescription_line_directions(
+                &context,
+                SetPrescriptionLineDirections {
+                    invoice_line_id: line_id.clone(),
+                    dose: Some(1.0),
+                    duration_days: Some(0),
+                    ..Default::default()
+                }
+            ),
+            Err(ServiceError::DurationMustBePositive)
+        );
+        // NotThisStoreInvoice
+        let context_b = service_provider
+            .context(mock_store_b().id, "".to_string())
+            .unwrap();
+        assert_eq!(
+            service.set_prescription_line_directions(
+                &context_b,
+                SetPrescriptionLineDirections {
+                    invoice_line_id: line_id.clone(),
+                    sig: Some("1T BD".to_string()),
+                    ..Default::default()
+                }
+            ),
+            Err(ServiceError::NotThisStoreInvoice)
+        );
+
+        // Success, expanded from sig
+        let direction = service
+            .set_prescription_line_directions(
+                &context,
+                SetPrescriptionLineDirections {
+                    invoice_line_id: line_id.clone(),
+                    sig: Some("1T PO BD x5/7".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(direction.dose, Some(1.0));
+        assert_eq!(direction.frequency_per_day, Some(2.0));
+        assert_eq!(direction.duration_days, Some(5));
+        assert_eq!(direction.route, Some(AdministrationRoute::Oral));
+        assert_eq!(
+            direction.directions,
+            "Take 1 tablet by mouth twice a day for 5 days"
+        );
+        assert_eq!(
+            get_quantity_to_dispense(&connection, &direction),
+            Ok(Some(10.0))
+        );
+        // Only when the dose is in the item unit
+        assert_eq!(quantity_to_dispense(&direction, Some("Tabs")), Some(10.0));
+        assert_eq!(quantity_to_dispense(&direction, Some("mL")), None);
+
+        // Structured fields override the sig, existing row is updated
+        let updated = service
+            .set_prescription_line_directions(
+                &context,
+                SetPrescriptionLineDirections {
+                    invoice_line_id: line_id.clone(),
+                    sig: Some("1T PO BD x5/7".to_string()),
+                    frequency_per_day: Some(3.0),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(updated.id, direction.id);
+        assert_eq!(
+            updated.directions,
+            "Take 1 tablet by mouth three times a day for 5 days"
+        );
+        assert_eq!(quantity_to_dispense(&updated, None), Some(15.0));
+
+        // Directions are removed with the line
+        InvoiceLineRowRepository::new(&connection)
+            .delete(&line_id)
+            .unwrap();
+        assert_eq!(
+            PrescriptionLineDirectionRowRepository::new(&connection)
+                .find_one_by_invoice_line_id(&line_id),
+            Ok(None)
+        );
+    }
+}
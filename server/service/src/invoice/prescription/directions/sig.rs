@@ -0,0 +1,344 @@
+use repository::AdministrationRoute;
+
+/// Prescription directions parsed from sig codes, e.g. "1T PO BD PC x5/7"
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ExpandedSig {
+    pub dose: Option<f64>,
+    pub dose_unit: Option<String>,
+    pub frequency_per_day: Option<f64>,
+    /// Frequency as written out on the label, e.g. "at night"
+    pub frequency_description: Option<String>,
+    pub duration_days: Option<i32>,
+    pub route: Option<AdministrationRoute>,
+    /// Extra instructions (e.g. "after food") and any words that are not sig codes
+    pub instructions: Vec<String>,
+}
+
+fn dose_unit(code: &str) -> Option<&'static str> {
+    let unit = match code {
+        "T" | "TAB" | "TABS" => "tablet",
+        "C" | "CAP" | "CAPS" => "capsule",
+        "ML" => "mL",
+        "MG" => "mg",
+        "G" => "g",
+        "GTT" | "DROP" | "DROPS" => "drop",
+        "PUFF" | "PUFFS" => "puff",
+        "SACHET" | "SACHETS" => "sachet",
+        "SUPP" => "suppository",
+        "U" | "UNIT" | "UNITS" => "unit",
+        _ => return None,
+    };
+    Some(unit)
+}
+
+/// Compares units ignoring case, plurals and sig abbreviations, e.g. "Tabs" is the same as "tablet"
+pub(crate) fn is_same_unit(a: &str, b: &str) -> bool {
+    let normalise = |unit: &str| {
+        let unit = unit.trim().to_uppercase();
+        let unit = dose_unit(&unit).map(str::to_uppercase).unwrap_or(unit);
+        match unit.strip_suffix('S') {
+            Some(singular) if !singular.is_empty() => singular.to_string(),
+            _ => unit,
+        }
+    };
+    normalise(a) == normalise(b)
+}
+
+/// Frequency per day (None when taken as required) and description
+fn frequency(code: &str) -> Option<(Option<f64>, String)> {
+    let frequency = match code {
+        "OD" | "QD" | "DAILY" => (Some(1.0), "once a day"),
+        "BD" | "BID" => (Some(2.0), "twice a day"),
+        "TDS" | "TID" => (Some(3.0), "three times a day"),
+        "QDS" | "QID" => (Some(4.0), "four times a day"),
+        "MANE" | "OM" => (Some(1.0), "in the morning"),
+        "NOCTE" | "QHS" => (Some(1.0), "at night"),
+        "WEEKLY" => (Some(1.0 / 7.0), "once a week"),
+        "STAT" => (Some(1.0), "immediately"),
+        "PRN" => (None, "when required"),
+        _ => {
+            // Every n hours, e.g. Q6H
+            let hours = code.strip_prefix('Q')?.strip_suffix('H')?;
+            let hours: f64 = hours.parse().ok().filter(|hours| *hours > 0.0)?;
+            return Some((Some(24.0 / hours), format!("every {} hours", hours)));
+        }
+    };
+    Some((frequency.0, frequency.1.to_string()))
+}
+
+fn route(code: &str) -> Option<AdministrationRoute> {
+    let route = match code {
+        "PO" | "ORAL" => AdministrationRoute::Oral,
+        "SL" => AdministrationRoute::Sublingual,
+        "TOP" => AdministrationRoute::Topical,
+        "INH" => AdministrationRoute::Inhaled,
+        "NAS" => AdministrationRoute::Nasal,
+        "EYE" | "OPH" => AdministrationRoute::Ophthalmic,
+        "EAR" | "OTIC" => AdministrationRoute::Otic,
+        "PR" => AdministrationRoute::Rectal,
+        "PV" => AdministrationRoute::Vaginal,
+        "IM" => AdministrationRoute::Intramuscular,
+        "IV" => AdministrationRoute::Intravenous,
+        "SC" | "SUBCUT" => AdministrationRoute::Subcutaneous,
+        _ => return None,
+    };
+    Some(route)
+}
+
+fn instruction(code: &str) -> Option<&'static str> {
+    let instruction = match code {
+        "AC" => "before food",
+        "PC" => "after food",
+        "CC" => "with food",
+        "SOS" => "if needed",
+        _ => return None,
+    };
+    Some(instruction)
+}
+
+/// Duration in days, e.g. 5/7 (days), 2/52 (weeks), 1/12 (months), 5D, 2W or 1M
+fn duration(code: &str) -> Option<i32> {
+    let (count, days_per_count) = if let Some((count, period)) = code.split_once('/') {
+        let days_per_count = match period {
+            "7" => 1,
+            "52" => 7,
+            "12" => 30,
+            _ => return None,
+        };
+        (count, days_per_count)
+    } else {
+        let days_per_count = match code.chars().last()? {
+            'D' => 1,
+            'W' => 7,
+            'M' => 30,
+            _ => return None,
+        };
+        (&code[..code.len() - 1], days_per_count)
+    };
+
+    let count: i32 = count.parse().ok().filter(|count| *count > 0)?;
+    Some(count * days_per_count)
+}
+
+/// Splits a dose like "1", "0.5", "1/2" or "2T" into quantity and unit code
+fn dose(code: &str) -> Option<(f64, &str)> {
+    let unit_start = code
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '/'))
+        .unwrap_or(code.len());
+    let (quantity, unit) = code.split_at(unit_start);
+
+    let quantity = match quantity.split_once('/') {
+        Some((numerator, denominator)) => {
+            numerator.parse::<f64>().ok()? / denominator.parse::<f64>().ok()?
+        }
+        None => quantity.parse().ok()?,
+    };
+    if !quantity.is_finite() || quantity <= 0.0 {
+        return None;
+    }
+    if !unit.is_empty() && dose_unit(unit).is_none() {
+        return None;
+    }
+
+    Some((quantity, unit))
+}
+
+/// Expands sig codes into structured directions, words that are not recognised are kept
+/// as instructions so nothing the prescriber wrote is lost
+pub fn expand_sig(sig: &str) -> ExpandedSig {
+    let mut result = ExpandedSig::default();
+
+    let mut words = sig.split_whitespace().peekable();
+    while let Some(word) = words.next() {
+        let code = word.to_uppercase();
+
+        if code == "FOR" {
+            if let Some(days) = words.peek().and_then(|next| duration(&next.to_uppercase())) {
+                result.duration_days = Some(days);
+                words.next();
+                continue;
+            }
+        }
+        if let Some(days) = code.strip_prefix('X').and_then(duration) {
+            result.duration_days = Some(days);
+            continue;
+        }
+
+        if result.dose.is_none() {
+            if let Some((quantity, unit)) = dose(&code) {
+                result.dose = Some(quantity);
+                if let Some(unit) = dose_unit(unit) {
+                    result.dose_unit = Some(unit.to_string());
+                }
+                continue;
+            }
+        } else if let Some(days) = duration(&code) {
+            result.duration_days = Some(days);
+            continue;
+        }
+
+        if result.dose_unit.is_none() {
+            if let Some(unit) = dose_unit(&code) {
+                result.dose_unit = Some(unit.to_string());
+                continue;
+            }
+        }
+
+        if let Some((frequency_per_day, description)) = frequency(&code) {
+            result.frequency_per_day = frequency_per_day;
+            result.frequency_description = Some(description);
+            continue;
+        }
+
+        if let Some(route) = route(&code) {
+            result.route = Some(route);
+            continue;
+        }
+
+        if let Some(instruction) = instruction(&code) {
+            result.instructions.push(instruction.to_string());
+            continue;
+        }
+
+        result.instructions.push(word.to_string());
+    }
+
+    result
+}
+
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", (value * 100.0).round() / 100.0)
+    }
+}
+
+fn describe_frequency(frequency_per_day: f64) -> String {
+    let times = ["once", "twice", "three times", "four times"];
+    if frequency_per_day < 1.0 {
+        format!("every {} days", format_number(1.0 / frequency_per_day))
+    } else if frequency_per_day.fract() == 0.0 && frequency_per_day <= times.len() as f64 {
+        format!("{} a day", times[frequency_per_day as usize - 1])
+    } else {
+        format!("{} times a day", format_number(frequency_per_day))
+    }
+}
+
+fn verb(route: &Option<AdministrationRoute>) -> &'static str {
+    match route {
+        Some(AdministrationRoute::Topical) => "Apply",
+        Some(AdministrationRoute::Inhaled) => "Inhale",
+        Some(AdministrationRoute::Nasal)
+        | Some(AdministrationRoute::Ophthalmic)
+        | Some(AdministrationRoute::Otic) => "Use",
+        Some(AdministrationRoute::Rectal) | Some(AdministrationRoute::Vaginal) => "Insert",
+        Some(AdministrationRoute::Intramuscular)
+        | Some(AdministrationRoute::Intravenous)
+        | Some(AdministrationRoute::Subcutaneous) => "Inject",
+        Some(AdministrationRoute::Oral) | Some(AdministrationRoute::Sublingual) | None => "Take",
+    }
+}
+
+fn describe_route(route: &AdministrationRoute) -> &'static str {
+    match route {
+        AdministrationRoute::Oral => "by mouth",
+        AdministrationRoute::Sublingual => "under the tongue",
+        AdministrationRoute::Topical => "to the skin",
+        AdministrationRoute::Inhaled => "by inhalation",
+        AdministrationRoute::Nasal => "in the nose",
+        AdministrationRoute::Ophthalmic => "in the eye",
+        AdministrationRoute::Otic => "in the ear",
+        AdministrationRoute::Rectal => "rectally",
+        AdministrationRoute::Vaginal => "vaginally",
+        AdministrationRoute::Intramuscular => "into the muscle",
+        AdministrationRoute::Intravenous => "into the vein",
+        AdministrationRoute::Subcutaneous => "under the skin",
+    }
+}
+
+impl ExpandedSig {
+    /// Full text directions for the label, e.g. "Take 1 tablet by mouth twice a day after food
+    /// for 5 days"
+    pub fn directions(&self) -> String {
+        let mut parts = vec![verb(&self.route).to_string()];
+
+        if let Some(dose) = self.dose {
+            parts.push(format_number(dose));
+        }
+        if let Some(unit) = &self.dose_unit {
+            let plural = self.dose.is_some_and(|dose| dose > 1.0) && unit != "mL";
+            parts.push(if plural {
+                format!("{}s", unit)
+            } else {
+                unit.clone()
+            });
+        }
+        if let Some(route) = &self.route {
+            parts.push(describe_route(route).to_string());
+        }
+        match (&self.frequency_description, self.frequency_per_day) {
+            (Some(description), _) => parts.push(description.clone()),
+            (None, Some(frequency_per_day)) => parts.push(describe_frequency(frequency_per_day)),
+            (None, None) => {}
+        }
+        parts.extend(self.instructions.iter().cloned());
+        if let Some(duration_days) = self.duration_days {
+            parts.push(match duration_days {
+                1 => "for 1 day".to_string(),
+                days => format!("for {} days", days),
+            });
+        }
+
+        parts.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::AdministrationRoute;
+
+    use super::{expand_sig, ExpandedSig};
+
+    #[test]
+    fn expand_sig_codes() {
+        let expanded = expand_sig("1T PO BD pc x5/7");
+        assert_eq!(
+            expanded,
+            ExpandedSig {
+                dose: Some(1.0),
+                dose_unit: Some("tablet".to_string()),
+                frequency_per_day: Some(2.0),
+                frequency_description: Some("twice a day".to_string()),
+                duration_days: Some(5),
+                route: Some(AdministrationRoute::Oral),
+                instructions: vec!["after food".to_string()],
+            }
+        );
+        assert_eq!(
+            expanded.directions(),
+            "Take 1 tablet by mouth twice a day after food for 5 days"
+        );
+
+        let expanded = expand_sig("2 caps q6h x2/52");
+        assert_eq!(expanded.dose, Some(2.0));
+        assert_eq!(expanded.frequency_per_day, Some(4.0));
+        assert_eq!(expanded.duration_days, Some(14));
+        assert_eq!(
+            expanded.directions(),
+            "Take 2 capsules every 6 hours for 14 days"
+        );
+
+        let expanded = expand_sig("1 puff INH PRN when wheezy");
+        assert_eq!(expanded.frequency_per_day, None);
+        assert_eq!(
+            expanded.directions(),
+            "Inhale 1 puff by inhalation when required when wheezy"
+        );
+
+        let expanded = expand_sig("1/2 tab nocte 7d");
+        assert_eq!(expanded.dose, Some(0.5));
+        assert_eq!(expanded.duration_days, Some(7));
+        assert_eq!(expanded.directions(), "Take 0.5 tablet at night for 7 days");
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate};
+use repository::{
+    EqualFilter, InvoiceFilter, InvoiceLine, InvoiceLineFilter, InvoiceLineRepository,
+    InvoiceLineType, InvoiceRepository, InvoiceStatus, InvoiceType, PrescriptionLineDirectionRow,
+    PrescriptionLineDirectionRowRepository, RepositoryError, UnitRowRepository,
+};
+
+use super::directions::is_dose_in_item_unit;
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MedicationHistoryEntry {
+    pub invoice_line: InvoiceLine,
+    pub direction: Option<PrescriptionLineDirectionRow>,
+    pub prescription_date: NaiveDate,
+    /// Number of days the dispensed quantity lasts when following the directions
+    pub days_of_supply: Option<i32>,
+    pub refill_due_date: Option<NaiveDate>,
+    /// False when the same item has been dispensed to the patient again since
+    pub is_latest_for_item: bool,
+}
+
+impl MedicationHistoryEntry {
+    /// Only the latest dispense of an item can be due for a refill
+    pub fn is_due_for_refill(&self, date: NaiveDate) -> bool {
+        self.is_latest_for_item
+            && self
+                .refill_due_date
+                .is_some_and(|refill_due_date| refill_due_date <= date)
+    }
+}
+
+fn days_of_supply(
+    line: &InvoiceLine,
+    direction: &Option<PrescriptionLineDirectionRow>,
+    item_unit: Option<&str>,
+) -> Option<i32> {
+    let direction = direction.as_ref()?;
+    let quantity = line.invoice_line_row.number_of_packs * line.invoice_line_row.pack_size;
+    let is_dose_in_item_unit = is_dose_in_item_unit(direction, item_unit);
+
+    match (direction.dose, direction.frequency_per_day) {
+        (Some(dose), Some(frequency_per_day)) if quantity > 0.0 && is_dose_in_item_unit => {
+            Some((quantity / (dose * frequency_per_day)).floor() as i32)
+        }
+        // Without a daily dose (e.g. taken as required) rely on the prescribed duration
+        _ => direction.duration_days,
+    }
+}
+
+/// Medication dispensed to a patient in picked or verified prescriptions, most recent first
+pub fn get_medication_history(
+    ctx: &ServiceContext,
+    patient_id: &str,
+) -> Result<Vec<MedicationHistoryEntry>, RepositoryError> {
+    let prescriptions = InvoiceRepository::new(&ctx.connection).query_by_filter(
+        InvoiceFilter::new()
+            .name_id(EqualFilter::equal_to(patient_id))
+            .r#type(InvoiceType::Prescription.equal_to())
+            .status(InvoiceStatus::equal_any(vec![
+                InvoiceStatus::Picked,
+                InvoiceStatus::Verified,
+            ])),
+    )?;
+    let invoice_ids: Vec<String> = prescriptions
+        .into_iter()
+        .map(|invoice| invoice.invoice_row.id)
+        .collect();
+
+    let lines = InvoiceLineRepository::new(&ctx.connection).query_by_filter(
+        InvoiceLineFilter::new()
+            .invoice_id(EqualFilter::equal_any(invoice_ids))
+            .r#type(InvoiceLineType::StockOut.equal_to()),
+    )?;
+
+    let line_ids: Vec<String> = lines
+        .iter()
+        .map(|line| line.invoice_line_row.id.clone())
+        .collect();
+    let mut directions: HashMap<String, PrescriptionLineDirectionRow> =
+        PrescriptionLineDirectionRowRepository::new(&ctx.connection)
+            .find_many_by_invoice_line_ids(&line_ids)?
+            .into_iter()
+            .map(|direction| (direction.invoice_line_id.clone(), direction))
+            .collect();
+
+    // Unit id -> unit name
+    let unit_repository = UnitRowRepository::new(&ctx.connection);
+    let mut units: HashMap<String, String> = HashMap::new();
+    for unit_id in lines
+        .iter()
+        .filter_map(|line| line.item_row.unit_id.as_ref())
+    {
+        if units.contains_key(unit_id) {
+            continue;
+        }
+        if let Some(unit) = unit_repository.find_one_by_id(unit_id)? {
+            units.insert(unit.id, unit.name);
+        }
+    }
+
+    let mut entries: Vec<MedicationHistoryEntry> = lines
+        .into_iter()
+        .map(|line| {
+            let invoice = &line.invoice_row;
+            let prescription_date = invoice
+                .backdated_datetime
+                .or(invoice.picked_datetime)
+                .unwrap_or(invoice.created_datetime)
+                .date();
+            let direction = directions.remove(&line.invoice_line_row.id);
+            let item_unit = line
+                .item_row
+                .unit_id
+                .as_ref()
+                .and_then(|unit_id| units.get(unit_id))
+                .map(String::as_str);
+            let days_of_supply = days_of_supply(&line, &direction, item_unit);
+
+            MedicationHistoryEntry {
+                refill_due_date: days_of_supply
+                    .map(|days| prescription_date + Duration::days(days as i64)),
+                days_of_supply,
+                prescription_date,
+                direction,
+                invoice_line: line,
+                is_latest_for_item: false,
+            }
+        })
+        .collect();
+
+    entries.sort_by(|a, b| {
+        b.prescription_date
+            .cmp(&a.prescription_date)
+            .then_with(|| {
+                b.invoice_line
+                    .invoice_row
+                    .created_datetime
+                    .cmp(&a.invoice_line.invoice_row.created_datetime)
+            })
+            .then_with(|| {
+                a.invoice_line
+                    .invoice_line_row
+                    .item_name
+                    .cmp(&b.invoice_line.invoice_line_row.item_name)
+            })
+    });
+
+    // Item id -> invoice id of the latest prescription of the item
+    let mut latest_prescriptions: HashMap<String, String> = HashMap::new();
+    for entry in entries.iter_mut() {
+        let item_id = &entry.invoice_line.item_row.id;
+        let invoice_id = &entry.invoice_line.invoice_row.id;
+        // Lines of the same item in the latest prescription are all current
+        entry.is_latest_for_item = match latest_prescriptions.get(item_id) {
+            Some(latest_invoice_id) => latest_invoice_id == invoice_id,
+            None => {
+                latest_prescriptions.insert(item_id.clone(), invoice_id.clone());
+                true
+            }
+        };
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{mock_item_a, mock_item_b, mock_patient, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        InvoiceLineRow, InvoiceLineType, InvoiceRow, InvoiceStatus, InvoiceType, ItemRow,
+        PrescriptionLineDirectionRow, PrescriptionLineDirectionRowRepository, UnitRow,
+    };
+    use util::inline_init;
+
+    use crate::service_provider::ServiceProvider;
+
+    fn prescription(id: &str, status: InvoiceStatus, date: NaiveDate) -> InvoiceRow {
+        inline_init(|r: &mut InvoiceRow| {
+            r.id = id.to_string();
+            r.name_link_id = mock_patient().id;
+            r.store_id = mock_store_a().id;
+            r.r#type = InvoiceType::Prescription;
+            r.status = status;
+            r.created_datetime = date.and_hms_opt(10, 0, 0).unwrap();
+            r.picked_datetime = Some(date.and_hms_opt(11, 0, 0).unwrap());
+        })
+    }
+
+    fn line(id: &str, invoice_id: &str, item_id: &str, number_of_packs: f64) -> InvoiceLineRow {
+        inline_init(|r: &mut InvoiceLineRow| {
+            r.id = id.to_string();
+            r.invoice_id = invoice_id.to_string();
+            r.item_link_id = item_id.to_string();
+            r.pack_size = 1.0;
+            r.number_of_packs = number_of_packs;
+            r.r#type = InvoiceLineType::StockOut;
+        })
+    }
+
+    fn tablet_item(id: &str) -> ItemRow {
+        inline_init(|r: &mut ItemRow| {
+            r.id = id.to_string();
+            r.name = id.to_string();
+            r.code = id.to_string();
+            r.unit_id = Some("history_unit_tab".to_string());
+        })
+    }
+
+    #[actix_rt::test]
+    async fn medication_history() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "medication_history",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.units = vec![UnitRow {
+                    id: "history_unit_tab".to_string(),
+                    name: "Tab".to_string(),
+                    description: None,
+                    index: 0,
+                    is_active: true,
+                }];
+                r.items = vec![tablet_item("history_item_c"), tablet_item("history_item_d")];
+                r.invoices = vec![
+                    prescription("history_1", InvoiceStatus::Picked, date(1)),
+                    prescription("history_2", InvoiceStatus::Verified, date(8)),
+                    prescription("history_3", InvoiceStatus::Picked, date(9)),
+                    prescription("history_4", InvoiceStatus::Picked, date(5)),
+                    prescription("history_5", InvoiceStatus::Picked, date(6)),
+                    prescription("history_new", InvoiceStatus::New, date(10)),
+                ];
+                r.invoice_lines = vec![
+                    line("history_1_a", "history_1", &mock_item_a().id, 20.0),
+                    line("history_2_b", "history_2", &mock_item_b().id, 30.0),
+                    line("history_3_a", "history_3", &mock_item_a().id, 10.0),
+                    line("history_4_c", "history_4", "history_item_c", 20.0),
+                    line("history_5_d", "history_5", "history_item_d", 20.0),
+                    line("history_new_a", "history_new", &mock_item_a().id, 10.0),
+                ];
+            }),
+        )
+        .await;
+
+        let direction_repo = PrescriptionLineDirectionRowRepository::new(&connection);
+        direction_repo
+            .upsert_one(&PrescriptionLineDirectionRow {
+                id: "history_1_a_direction".to_string(),
+                invoice_line_id: "history_1_a".to_string(),
+                dose: Some(1.0),
+                frequency_per_day: Some(2.0),
+                directions: "Take 1 twice a day".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        direction_repo
+            .upsert_one(&PrescriptionLineDirectionRow {
+                id: "history_2_b_direction".to_string(),
+                invoice_line_id: "history_2_b".to_string(),
+                dose: Some(1.0),
+                duration_days: Some(7),
+                directions: "Take 1 when required for 7 days".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        // Dose unit matches the item unit "Tab"
+        direction_repo
+            .upsert_one(&PrescriptionLineDirectionRow {
+                id: "history_4_c_direction".to_string(),
+                invoice_line_id: "history_4_c".to_string(),
+                dose: Some(2.0),
+                dose_unit: Some("tablets".to_string()),
+                frequency_per_day: Some(2.0),
+                directions: "Take 2 tablets twice a day".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        // Dose in mL can't be compared with the number of tablets, prescribed duration is used
+        direction_repo
+            .upsert_one(&PrescriptionLineDirectionRow {
+                id: "history_5_d_direction".to_string(),
+                invoice_line_id: "history_5_d".to_string(),
+                dose: Some(5.0),
+                dose_unit: Some("mL".to_string()),
+                frequency_per_day: Some(2.0),
+                duration_days: Some(3),
+                directions: "Take 5 mL twice a day for 3 days".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+
+        let history = service_provider
+            .invoice_service
+            .get_medication_history(&context, &mock_patient().id)
+            .unwrap();
+
+        let summary: Vec<_> = history
+            .iter()
+            .map(|entry| {
+                (
+                    entry.invoice_line.invoice_line_row.id.as_str(),
+                    entry.prescription_date,
+                    entry.days_of_supply,
+                    entry.refill_due_date,
+                    entry.is_latest_for_item,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("history_3_a", date(9), None, None, true),
+                ("history_2_b", date(8), Some(7), Some(date(15)), true),
+                ("history_5_d", date(6), Some(3), Some(date(9)), true),
+                ("history_4_c", date(5), Some(20 / 4), Some(date(10)), true),
+                ("history_1_a", date(1), Some(20 / 2), Some(date(11)), false),
+            ]
+        );
+
+        // Superseded dispense of item a is not due, even though its refill date has passed
+        let due: Vec<bool> = history
+            .iter()
+            .map(|entry| entry.is_due_for_refill(date(16)))
+            .collect();
+        assert_eq!(due, vec![false, true, true, true, false]);
+    }
+}
@@ -9,3 +9,9 @@ pub use self::delete::*;
 
 pub mod batch;
 pub use self::batch::*;
+
+pub mod directions;
+pub use self::directions::*;
+
+pub mod medication_history;
+pub use self::medication_history::*;
@@ -42,6 +42,8 @@ pub enum UpdatePrescriptionError {
     NotThisStoreInvoice,
     ClinicianDoesNotExist,
     PatientDoesNotExist,
+    /// Holds the ids of lines of serialised items without a serial number for every unit
+    SerialNumbersNotRecorded(Vec<String>),
     // Internal
     UpdatedInvoiceDoesNotExist,
     DatabaseError(RepositoryError),
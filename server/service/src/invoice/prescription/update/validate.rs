@@ -2,11 +2,12 @@ use crate::invoice::{
     check_invoice_exists, check_invoice_is_editable, check_invoice_type, check_status_change,
     check_store,
 };
+use crate::serial_number::lines_missing_serial_numbers;
 use crate::validate::check_patient_exists;
 use repository::{
     ClinicianRowRepository, EqualFilter, InvoiceLineFilter, InvoiceLineRepository, RepositoryError,
 };
-use repository::{InvoiceRow, InvoiceType, StorageConnection};
+use repository::{InvoiceRow, InvoiceStatus, InvoiceType, StorageConnection};
 
 use super::{UpdatePrescription, UpdatePrescriptionError};
 
@@ -32,6 +33,12 @@ pub fn validate(
     }
     // Status check
     let status_changed = check_status_change(&invoice, patch.full_status());
+    if status_changed && patch.full_status() == Some(InvoiceStatus::Verified) {
+        let line_ids = lines_missing_serial_numbers(connection, &invoice.id)?;
+        if !line_ids.is_empty() {
+            return Err(SerialNumbersNotRecorded(line_ids));
+        }
+    }
 
     if let Some(patient_id) = &patch.patient_id {
         check_patient_exists(connection, patient_id)?.ok_or(PatientDoesNotExist)?;
@@ -48,5 +48,9 @@ pub fn generate(
         inventory_adjustment_reason_id: None,
         return_reason_id: None,
         item_variant_id: None,
+        linked_invoice_line_id: None,
+        discrepancy_type: None,
+        discrepancy_number_of_packs: 0.0,
+        discrepancy_reason: None,
     })
 }
@@ -30,6 +30,9 @@ use self::stock_in_line::*;
 pub mod update_return_reason_id;
 use self::update_return_reason_id::*;
 
+pub mod update_discrepancy;
+use self::update_discrepancy::*;
+
 pub trait InvoiceLineServiceTrait: Sync + Send {
     fn get_invoice_line(
         &self,
@@ -60,6 +63,14 @@ pub trait InvoiceLineServiceTrait: Sync + Send {
         insert_stock_out_line(ctx, input)
     }
 
+    fn insert_prescription_line(
+        &self,
+        ctx: &ServiceContext,
+        input: InsertStockOutLine,
+    ) -> Result<InsertPrescriptionLineResult, InsertStockOutLineError> {
+        insert_prescription_line(ctx, input)
+    }
+
     fn update_stock_out_line(
         &self,
         ctx: &ServiceContext,
@@ -190,6 +201,14 @@ pub trait InvoiceLineServiceTrait: Sync + Send {
     ) -> Result<InvoiceLine, UpdateLineReturnReasonError> {
         update_return_reason_id(ctx, input)
     }
+
+    fn update_line_discrepancy(
+        &self,
+        ctx: &ServiceContext,
+        input: UpdateLineDiscrepancy,
+    ) -> Result<InvoiceLine, UpdateLineDiscrepancyError> {
+        update_line_discrepancy(ctx, input)
+    }
 }
 
 pub struct InvoiceLineService {}
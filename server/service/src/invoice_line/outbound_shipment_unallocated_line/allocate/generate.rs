@@ -1,22 +1,28 @@
 use std::cmp::Ordering;
 
+use chrono::Duration;
 use repository::{
-    EqualFilter, InvoiceLine, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineType,
-    Pagination, RepositoryError, StockLine, StockLineFilter, StockLineRepository, StockLineSort,
-    StockLineSortField, StorageConnection,
+    AllocationRuleRow, AllocationStrategyType, EqualFilter, InvoiceLine, InvoiceLineFilter,
+    InvoiceLineRepository, InvoiceLineType, Pagination, RepositoryError, StockLine,
+    StockLineFilter, StockLineRepository, StockLineSort, StockLineSortField, StorageConnection,
 };
 use util::{
     constants::stock_line_expiring_soon_offset, date_now, date_now_with_offset,
     fraction_is_integer, uuid,
 };
 
-use crate::invoice_line::{
-    outbound_shipment_unallocated_line::{
-        DeleteOutboundShipmentUnallocatedLine, UpdateOutboundShipmentUnallocatedLine,
+use crate::{
+    allocation_rule::get_allocation_rule,
+    invoice_line::{
+        outbound_shipment_unallocated_line::{
+            DeleteOutboundShipmentUnallocatedLine, UpdateOutboundShipmentUnallocatedLine,
+        },
+        stock_out_line::{InsertStockOutLine, StockOutType, UpdateStockOutLine},
     },
-    stock_out_line::{InsertStockOutLine, StockOutType, UpdateStockOutLine},
 };
 
+use super::{strategy::allocation_strategy, AllocationDecisionReason, StockLineAllocationDecision};
+
 #[derive(Default)]
 pub struct GenerateOutput {
     pub update_lines: Vec<UpdateStockOutLine>,
@@ -26,12 +32,17 @@ pub struct GenerateOutput {
     pub skipped_expired_stock_lines: Vec<StockLine>,
     pub skipped_on_hold_stock_lines: Vec<StockLine>,
     pub issued_expiring_soon_stock_lines: Vec<StockLine>,
+    pub strategy: AllocationStrategyType,
+    pub allocation_rule_id: Option<String>,
+    pub stock_line_decisions: Vec<StockLineAllocationDecision>,
 }
 
+/// Stock lines in `excluded_stock_line_ids` are not considered, e.g. ones that were short picked
 pub fn generate(
     connection: &StorageConnection,
     store_id: &str,
     unallocated_line: InvoiceLine,
+    excluded_stock_line_ids: &[String],
 ) -> Result<GenerateOutput, RepositoryError> {
     let mut result = GenerateOutput::default();
     let allocated_lines = get_allocated_lines(connection, &unallocated_line)?;
@@ -44,31 +55,71 @@ pub fn generate(
         });
         return Ok(result);
     }
+
+    let rule = get_allocation_rule(
+        connection,
+        store_id,
+        &unallocated_line.invoice_row.name_link_id,
+    )?
+    .unwrap_or_default();
+    result.strategy = rule.strategy;
+    result.allocation_rule_id = (!rule.id.is_empty()).then(|| rule.id.clone());
+
     // Asc, by expiry date, nulls last
-    let sorted_available_stock_lines =
+    let mut sorted_available_stock_lines =
         get_sorted_available_stock_lines(connection, store_id, &unallocated_line)?;
-    // Use FEFO to allocate
+    sorted_available_stock_lines
+        .retain(|stock_line| !excluded_stock_line_ids.contains(&stock_line.stock_line_row.id));
+    allocation_strategy(rule.strategy).order_stock_lines(
+        connection,
+        &mut sorted_available_stock_lines,
+        remaining_to_allocate,
+    )?;
+
     for stock_line in sorted_available_stock_lines {
-        let can_use = get_stock_line_eligibility(&stock_line)
-            .map(|eligibility| match eligibility {
-                StockLineAlert::OnHold => {
-                    result.skipped_on_hold_stock_lines.push(stock_line.clone());
-                    false
-                }
-                StockLineAlert::Expired => {
-                    result.skipped_expired_stock_lines.push(stock_line.clone());
-                    false
-                }
-                StockLineAlert::ExpiringSoon => {
-                    result
-                        .issued_expiring_soon_stock_lines
-                        .push(stock_line.clone());
-                    true
-                }
-            })
-            .unwrap_or(true);
-
-        if !can_use {
+        let stock_line_id = stock_line.stock_line_row.id.clone();
+        if remaining_to_allocate <= 0.0 {
+            result
+                .stock_line_decisions
+                .push(StockLineAllocationDecision {
+                    stock_line_id,
+                    reason: AllocationDecisionReason::NotNeeded,
+                    number_of_packs: 0.0,
+                });
+            continue;
+        }
+
+        let alert = get_stock_line_eligibility(&stock_line, &rule);
+        let skipped_reason = match alert {
+            Some(StockLineAlert::OnHold) => {
+                result.skipped_on_hold_stock_lines.push(stock_line.clone());
+                Some(AllocationDecisionReason::SkippedOnHold)
+            }
+            Some(StockLineAlert::Expired) => {
+                result.skipped_expired_stock_lines.push(stock_line.clone());
+                Some(AllocationDecisionReason::SkippedExpired)
+            }
+            Some(StockLineAlert::ShortShelfLife) => {
+                Some(AllocationDecisionReason::SkippedShortShelfLife)
+            }
+            Some(StockLineAlert::ItemVariant) => Some(AllocationDecisionReason::SkippedItemVariant),
+            Some(StockLineAlert::ExpiringSoon) => {
+                result
+                    .issued_expiring_soon_stock_lines
+                    .push(stock_line.clone());
+                None
+            }
+            None => None,
+        };
+
+        if let Some(reason) = skipped_reason {
+            result
+                .stock_line_decisions
+                .push(StockLineAllocationDecision {
+                    stock_line_id,
+                    reason,
+                    number_of_packs: 0.0,
+                });
             continue;
         }
 
@@ -89,11 +140,20 @@ pub fn generate(
             )),
         }
 
-        remaining_to_allocate -= stock_line.stock_line_row.pack_size * packs_to_allocate;
+        result
+            .stock_line_decisions
+            .push(StockLineAllocationDecision {
+                stock_line_id,
+                reason: match alert {
+                    Some(StockLineAlert::ExpiringSoon) => {
+                        AllocationDecisionReason::AllocatedExpiringSoon
+                    }
+                    _ => AllocationDecisionReason::Allocated,
+                },
+                number_of_packs: packs_to_allocate,
+            });
 
-        if remaining_to_allocate <= 0.0 {
-            break;
-        }
+        remaining_to_allocate -= stock_line.stock_line_row.pack_size * packs_to_allocate;
     }
 
     // If nothing remaining to alloacted just remove the line, otherwise update
@@ -114,10 +174,15 @@ pub fn generate(
 enum StockLineAlert {
     OnHold,
     Expired,
+    ShortShelfLife,
+    ItemVariant,
     ExpiringSoon,
 }
 
-fn get_stock_line_eligibility(stock_line: &StockLine) -> Option<StockLineAlert> {
+fn get_stock_line_eligibility(
+    stock_line: &StockLine,
+    rule: &AllocationRuleRow,
+) -> Option<StockLineAlert> {
     use StockLineAlert::*;
     let stock_line_row = &stock_line.stock_line_row;
     // Expired
@@ -125,6 +190,12 @@ fn get_stock_line_eligibility(stock_line: &StockLine) -> Option<StockLineAlert>
         return Some(OnHold);
     }
 
+    if rule.avoid_item_variant_id.is_some()
+        && stock_line_row.item_variant_id == rule.avoid_item_variant_id
+    {
+        return Some(ItemVariant);
+    }
+
     let expiry_date = match &stock_line_row.expiry_date {
         Some(expiry_date) => expiry_date,
         None => return None,
@@ -134,9 +205,19 @@ fn get_stock_line_eligibility(stock_line: &StockLine) -> Option<StockLineAlert>
         return Some(Expired);
     }
 
-    if let Ordering::Less =
-        expiry_date.cmp(&date_now_with_offset(stock_line_expiring_soon_offset()))
-    {
+    if let Some(min_remaining_shelf_life_days) = rule.min_remaining_shelf_life_days {
+        let min_expiry_date =
+            date_now_with_offset(Duration::days(min_remaining_shelf_life_days.into()));
+        if let Ordering::Less = expiry_date.cmp(&min_expiry_date) {
+            return Some(ShortShelfLife);
+        }
+    }
+
+    let expiring_soon_offset = rule
+        .expiring_soon_days
+        .map(|days| Duration::days(days.into()))
+        .unwrap_or_else(stock_line_expiring_soon_offset);
+    if let Ordering::Less = expiry_date.cmp(&date_now_with_offset(expiring_soon_offset)) {
         return Some(ExpiringSoon);
     }
 
@@ -8,7 +8,10 @@ use crate::{
     },
     service_provider::ServiceContext,
 };
-use repository::{InvoiceLine, InvoiceLineType, RepositoryError, StockLine, StorageConnection};
+use repository::{
+    AllocationStrategyType, InvoiceLine, InvoiceLineType, RepositoryError, StockLine,
+    StorageConnection,
+};
 
 use super::{
     delete_outbound_shipment_unallocated_line, update_outbound_shipment_unallocated_line,
@@ -17,6 +20,7 @@ use super::{
 };
 
 mod generate;
+mod strategy;
 mod test;
 use generate::{generate, GenerateOutput};
 
@@ -63,6 +67,32 @@ pub struct AllocateLineResult {
     pub skipped_expired_stock_lines: Vec<StockLine>,
     pub skipped_on_hold_stock_lines: Vec<StockLine>,
     pub issued_expiring_soon_stock_lines: Vec<StockLine>,
+    /// Strategy of the allocation rule used, FEFO when the store has no rule for the customer
+    pub strategy: AllocationStrategyType,
+    pub allocation_rule_id: Option<String>,
+    /// Why each available stock line was or wasn't allocated, in the order they were considered
+    pub stock_line_decisions: Vec<StockLineAllocationDecision>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AllocationDecisionReason {
+    Allocated,
+    AllocatedExpiringSoon,
+    SkippedOnHold,
+    SkippedExpired,
+    /// Expires before the minimum remaining shelf life of the allocation rule
+    SkippedShortShelfLife,
+    /// Stock line is of the item variant the allocation rule avoids
+    SkippedItemVariant,
+    /// Quantity was already fully allocated from earlier stock lines
+    NotNeeded,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StockLineAllocationDecision {
+    pub stock_line_id: String,
+    pub reason: AllocationDecisionReason,
+    pub number_of_packs: f64,
 }
 
 type ServiceResult = AllocateLineResult;
@@ -83,7 +113,10 @@ pub fn allocate_outbound_shipment_unallocated_line(
                 skipped_expired_stock_lines,
                 skipped_on_hold_stock_lines,
                 issued_expiring_soon_stock_lines,
-            } = generate(connection, &ctx.store_id, unallocated_line)?;
+                strategy,
+                allocation_rule_id,
+                stock_line_decisions,
+            } = generate(connection, &ctx.store_id, unallocated_line, &[])?;
 
             let mut result = ServiceResult {
                 inserts: vec![],
@@ -92,6 +125,9 @@ pub fn allocate_outbound_shipment_unallocated_line(
                 skipped_expired_stock_lines,
                 skipped_on_hold_stock_lines,
                 issued_expiring_soon_stock_lines,
+                strategy,
+                allocation_rule_id,
+                stock_line_decisions,
             };
 
             for input in update_lines.into_iter() {
@@ -142,9 +178,68 @@ pub fn allocate_outbound_shipment_unallocated_line(
     Ok(line)
 }
 
+#[derive(Default, Debug, PartialEq)]
+pub struct ShortPickAllocation {
+    pub inserts: Vec<InvoiceLine>,
+    pub updates: Vec<InvoiceLine>,
+    /// Units that couldn't be allocated from other stock lines
+    pub unallocated_quantity: f64,
+}
+
+/// Allocates `quantity` units of the item of an outbound shipment line from other stock lines,
+/// after the line was short picked. Placeholder lines can only be added to new shipments, so
+/// this allocates as if there was one without creating it
+pub(crate) fn allocate_short_pick(
+    ctx: &ServiceContext,
+    short_picked_line: &InvoiceLine,
+    quantity: f64,
+    excluded_stock_line_ids: &[String],
+) -> Result<ShortPickAllocation, OutError> {
+    let mut unallocated_line = short_picked_line.clone();
+    unallocated_line.invoice_line_row.r#type = InvoiceLineType::UnallocatedStock;
+    unallocated_line.invoice_line_row.pack_size = 1.0;
+    unallocated_line.invoice_line_row.number_of_packs = quantity;
+
+    let GenerateOutput {
+        update_lines,
+        insert_lines,
+        update_unallocated_line,
+        ..
+    } = generate(
+        &ctx.connection,
+        &ctx.store_id,
+        unallocated_line,
+        excluded_stock_line_ids,
+    )?;
+
+    let mut result = ShortPickAllocation {
+        unallocated_quantity: update_unallocated_line
+            .map(|line| line.quantity)
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+
+    for input in update_lines.into_iter() {
+        result
+            .updates
+            .push(update_stock_out_line(ctx, input.clone()).map_err(|error| {
+                OutError::UpdateOutboundShipmentLine(InputWithError { input, error })
+            })?);
+    }
+
+    for input in insert_lines.into_iter() {
+        result
+            .inserts
+            .push(insert_stock_out_line(ctx, input.clone()).map_err(|error| {
+                OutError::InsertOutboundShipmentLine(InputWithError { input, error })
+            })?);
+    }
+
+    Ok(result)
+}
+
 fn validate(connection: &StorageConnection, line_id: &str) -> Result<InvoiceLine, OutError> {
-    let invoice_line =
-        check_line_exists(connection, line_id)?.ok_or(OutError::LineDoesNotExist)?;
+    let invoice_line = check_line_exists(connection, line_id)?.ok_or(OutError::LineDoesNotExist)?;
 
     if invoice_line.invoice_line_row.r#type != InvoiceLineType::UnallocatedStock {
         return Err(OutError::LineIsNotUnallocatedLine);
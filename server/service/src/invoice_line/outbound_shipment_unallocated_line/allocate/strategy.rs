@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use repository::{
+    AllocationStrategyType, EqualFilter, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineType,
+    RepositoryError, StockLine, StorageConnection,
+};
+use util::fraction_is_integer;
+
+use crate::pick_list::{get_pick_path_positions, location_pick_order};
+
+/// Orders the available stock lines of an item before they are allocated. Stock lines arrive in
+/// FEFO order and sorts are stable, so expiry date breaks ties for every strategy
+pub trait AllocationStrategy {
+    fn order_stock_lines(
+        &self,
+        connection: &StorageConnection,
+        stock_lines: &mut [StockLine],
+        quantity: f64,
+    ) -> Result<(), RepositoryError>;
+}
+
+pub fn allocation_strategy(r#type: AllocationStrategyType) -> Box<dyn AllocationStrategy> {
+    match r#type {
+        AllocationStrategyType::Fefo => Box::new(Fefo),
+        AllocationStrategyType::Fifo => Box::new(Fifo),
+        AllocationStrategyType::PickPath => Box::new(PickPath),
+        AllocationStrategyType::FullPacksFirst => Box::new(FullPacksFirst),
+    }
+}
+
+pub struct Fefo;
+
+impl AllocationStrategy for Fefo {
+    fn order_stock_lines(
+        &self,
+        _: &StorageConnection,
+        _: &mut [StockLine],
+        _: f64,
+    ) -> Result<(), RepositoryError> {
+        Ok(())
+    }
+}
+
+/// Oldest received first, stock lines without a receipt (e.g. from a stocktake) last
+pub struct Fifo;
+
+impl AllocationStrategy for Fifo {
+    fn order_stock_lines(
+        &self,
+        connection: &StorageConnection,
+        stock_lines: &mut [StockLine],
+        _: f64,
+    ) -> Result<(), RepositoryError> {
+        let received = get_received_datetimes(connection, stock_lines)?;
+        stock_lines.sort_by_key(|line| {
+            let received = received.get(&line.stock_line_row.id);
+            (received.is_none(), received.copied())
+        });
+        Ok(())
+    }
+}
+
+/// Along the pick path of the store, then by location code, stock lines without a location last
+pub struct PickPath;
+
+impl AllocationStrategy for PickPath {
+    fn order_stock_lines(
+        &self,
+        connection: &StorageConnection,
+        stock_lines: &mut [StockLine],
+        _: f64,
+    ) -> Result<(), RepositoryError> {
+        let Some(store_id) = stock_lines
+            .first()
+            .map(|line| line.stock_line_row.store_id.clone())
+        else {
+            return Ok(());
+        };
+        let pick_path = get_pick_path_positions(connection, &store_id)?;
+
+        stock_lines.sort_by_key(|line| {
+            let location = line.location_row.as_ref();
+            location_pick_order(
+                &pick_path,
+                location.map(|location| location.id.as_str()),
+                location.map(|location| location.code.as_str()),
+            )
+        });
+        Ok(())
+    }
+}
+
+/// Unopened stock lines with packs no bigger than the quantity first, so packs are only opened
+/// when nothing else is left
+pub struct FullPacksFirst;
+
+impl AllocationStrategy for FullPacksFirst {
+    fn order_stock_lines(
+        &self,
+        _: &StorageConnection,
+        stock_lines: &mut [StockLine],
+        quantity: f64,
+    ) -> Result<(), RepositoryError> {
+        stock_lines.sort_by_key(|line| {
+            let row = &line.stock_line_row;
+            (
+                !fraction_is_integer(row.available_number_of_packs),
+                row.pack_size > quantity,
+            )
+        });
+        Ok(())
+    }
+}
+
+/// Earliest receipt of each stock line, from the stock in lines that introduced it
+fn get_received_datetimes(
+    connection: &StorageConnection,
+    stock_lines: &[StockLine],
+) -> Result<HashMap<String, NaiveDateTime>, RepositoryError> {
+    let stock_line_ids = stock_lines
+        .iter()
+        .map(|line| line.stock_line_row.id.clone())
+        .collect();
+    let stock_in_lines = InvoiceLineRepository::new(connection).query_by_filter(
+        InvoiceLineFilter::new()
+            .stock_line_id(EqualFilter::equal_any(stock_line_ids))
+            .r#type(InvoiceLineType::StockIn.equal_to()),
+    )?;
+
+    let mut received: HashMap<String, NaiveDateTime> = HashMap::new();
+    for line in stock_in_lines {
+        let Some(stock_line_id) = line.invoice_line_row.stock_line_id else {
+            continue;
+        };
+        let invoice = line.invoice_row;
+        let datetime = invoice
+            .delivered_datetime
+            .unwrap_or(invoice.created_datetime);
+        received
+            .entry(stock_line_id)
+            .and_modify(|existing| *existing = (*existing).min(datetime))
+            .or_insert(datetime);
+    }
+
+    Ok(received)
+}
@@ -125,6 +125,10 @@ fn generate(
         return_reason_id: None,
         foreign_currency_price_before_tax: None,
         item_variant_id: None,
+        linked_invoice_line_id: None,
+        discrepancy_type: None,
+        discrepancy_number_of_packs: 0.0,
+        discrepancy_reason: None,
     };
 
     Ok(new_line)
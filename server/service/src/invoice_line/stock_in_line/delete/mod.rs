@@ -1,5 +1,6 @@
 use crate::{
-    invoice::common::generate_invoice_user_id_update, service_provider::ServiceContext, WithDBError,
+    invoice::common::generate_invoice_user_id_update,
+    serial_number::delete_received_serial_numbers, service_provider::ServiceContext, WithDBError,
 };
 use repository::{
     InvoiceLineRowRepository, InvoiceRowRepository, RepositoryError, StockLineRowRepository,
@@ -30,6 +31,7 @@ pub fn delete_stock_in_line(
 
             let delete_batch_id_option = line.stock_line_id.clone();
 
+            delete_received_serial_numbers(connection, &line.id)?;
             InvoiceLineRowRepository::new(connection).delete(&line.id)?;
 
             if let Some(id) = delete_batch_id_option {
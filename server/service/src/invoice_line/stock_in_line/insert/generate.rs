@@ -123,6 +123,10 @@ fn generate_line(
         inventory_adjustment_reason_id: None,
         return_reason_id: None,
         foreign_currency_price_before_tax: None,
+        linked_invoice_line_id: None,
+        discrepancy_type: None,
+        discrepancy_number_of_packs: 0.0,
+        discrepancy_reason: None,
     }
 }
 
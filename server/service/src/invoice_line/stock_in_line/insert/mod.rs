@@ -1,11 +1,11 @@
 use crate::{
-    invoice_line::query::get_invoice_line, service_provider::ServiceContext, NullableUpdate,
-    WithDBError,
+    invoice_line::query::get_invoice_line, recall::upsert_received_stock_line,
+    service_provider::ServiceContext, NullableUpdate, WithDBError,
 };
 use chrono::NaiveDate;
 use repository::{
     BarcodeRowRepository, InvoiceLine, InvoiceLineRowRepository, InvoiceRowRepository,
-    RepositoryError, StockLineRowRepository,
+    RepositoryError,
 };
 
 mod generate;
@@ -67,7 +67,7 @@ pub fn insert_stock_in_line(
             }
 
             if let Some(stock_line_row) = stock_line {
-                StockLineRowRepository::new(connection).upsert_one(&stock_line_row)?;
+                upsert_received_stock_line(connection, &stock_line_row)?;
             }
             InvoiceLineRowRepository::new(connection).upsert_one(&invoice_line)?;
 
@@ -1,5 +1,6 @@
 use crate::{
     invoice_line::{query::get_invoice_line, ShipmentTaxUpdate},
+    recall::upsert_received_stock_line,
     service_provider::ServiceContext,
     NullableUpdate, WithDBError,
 };
@@ -56,7 +57,7 @@ pub fn update_stock_in_line(
             let stock_line_repository = StockLineRowRepository::new(connection);
 
             if let Some(upsert_batch) = upsert_batch_option {
-                stock_line_repository.upsert_one(&upsert_batch)?;
+                upsert_received_stock_line(connection, &upsert_batch)?;
             }
 
             InvoiceLineRowRepository::new(connection).upsert_one(&updated_line)?;
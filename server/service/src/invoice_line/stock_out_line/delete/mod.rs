@@ -1,4 +1,4 @@
-use crate::service_provider::ServiceContext;
+use crate::{serial_number::release_serial_numbers, service_provider::ServiceContext};
 use repository::{
     InvoiceLineRowRepository, InvoiceRowRepository, InvoiceStatus, RepositoryError,
     StockLineRowRepository,
@@ -27,6 +27,7 @@ pub fn delete_stock_out_line(
             let line = validate(&input, &ctx.store_id, connection)?;
             let stock_line_id_option = line.stock_line_id.clone();
 
+            release_serial_numbers(connection, &line.id)?;
             InvoiceLineRowRepository::new(connection).delete(&line.id)?;
 
             if let Some(stock_line_id) = stock_line_id_option {
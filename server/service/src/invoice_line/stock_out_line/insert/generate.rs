@@ -8,7 +8,8 @@ use crate::{
     invoice_line::StockOutType,
     pricing::{
         calculate_sell_price::calculate_sell_price,
-        item_price::{get_pricing_for_item, ItemPrice, ItemPriceLookup},
+        item_price::ItemPrice,
+        resolve_price::{resolve_price, ResolvePrice, ResolvedPrice},
     },
     service_provider::ServiceContext,
 };
@@ -31,12 +32,15 @@ pub fn generate(
         adjust_total_number_of_packs,
     );
 
-    // Check if we need to override the pricing with a default
-    let pricing = get_pricing_for_item(
+    // Check if we need to override the pricing with a price list or default, and the tax with a
+    // tax rule
+    let pricing = resolve_price(
         ctx,
-        ItemPriceLookup {
+        ResolvePrice {
             item_id: item_row.id.clone(),
-            customer_name_id: Some(invoice.name_link_id.clone()),
+            name_id: Some(invoice.name_link_id.clone()),
+            quantity: input.number_of_packs * update_batch.pack_size,
+            date: None,
         },
     )?;
     let new_line = generate_line(
@@ -132,12 +136,21 @@ fn generate_line(
         currency_rate,
         ..
     }: InvoiceRow,
-    default_pricing: ItemPrice,
+    pricing: ResolvedPrice,
 ) -> Result<InvoiceLineRow, RepositoryError> {
     let cost_price_per_pack = stock_line_cost_price_per_pack; // For now, we just get the cost price from the stock line
 
-    let sell_price_per_pack =
-        calculate_sell_price(stock_line_sell_price_per_pack, pack_size, default_pricing);
+    let sell_price_per_pack = calculate_sell_price(
+        stock_line_sell_price_per_pack,
+        pack_size,
+        ItemPrice::from(&pricing),
+    );
+    // Item level tax rule takes precedence over the invoice tax, which takes precedence over
+    // general tax rules
+    let tax_percentage = pricing
+        .item_tax_percentage()
+        .or(tax_percentage)
+        .or(pricing.tax_percentage());
 
     let total_before_tax = total_before_tax.unwrap_or(sell_price_per_pack * number_of_packs);
     let total_after_tax = calculate_total_after_tax(total_before_tax, tax_percentage);
@@ -171,6 +184,10 @@ fn generate_line(
         return_reason_id: None,
         foreign_currency_price_before_tax,
         item_variant_id,
+        linked_invoice_line_id: None,
+        discrepancy_type: None,
+        discrepancy_number_of_packs: 0.0,
+        discrepancy_reason: None,
     })
 }
 
@@ -1,4 +1,9 @@
-use crate::{invoice_line::query::get_invoice_line, service_provider::ServiceContext, WithDBError};
+use crate::{
+    clinical_check::{get_prescription_warnings, ClinicalCheckError, ClinicalWarning},
+    invoice_line::query::get_invoice_line,
+    service_provider::ServiceContext,
+    WithDBError,
+};
 use chrono::NaiveDate;
 use repository::{InvoiceLine, InvoiceLineRowRepository, RepositoryError, StockLineRowRepository};
 
@@ -63,6 +68,28 @@ where
     }
 }
 
+impl From<ClinicalCheckError> for InsertStockOutLineError {
+    fn from(error: ClinicalCheckError) -> Self {
+        use ClinicalCheckError as from;
+        use InsertStockOutLineError as to;
+        match error {
+            from::InvoiceDoesNotExist => to::InvoiceDoesNotExist,
+            from::NotThisStoreInvoice => to::NotThisStoreInvoice,
+            from::NotAPrescriptionInvoice => to::InvoiceTypeDoesNotMatch,
+            // Item of the stock line is no longer available
+            from::ItemDoesNotExist => to::StockLineNotFound,
+            from::DatabaseError(error) => to::DatabaseError(error),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InsertPrescriptionLineResult {
+    pub line: InvoiceLine,
+    /// Clinical warnings for the item of the new line, see `get_prescription_warnings`
+    pub clinical_warnings: Vec<ClinicalWarning>,
+}
+
 type OutError = InsertStockOutLineError;
 
 pub fn insert_stock_out_line(
@@ -84,6 +111,37 @@ pub fn insert_stock_out_line(
     Ok(new_line)
 }
 
+/// Inserts a prescription line and checks its item against the rest of the prescription and the
+/// patient's active medication. Warnings don't stop the line from being added, they are
+/// returned to be shown (and acknowledged) when dispensing.
+pub fn insert_prescription_line(
+    ctx: &ServiceContext,
+    input: InsertStockOutLine,
+) -> Result<InsertPrescriptionLineResult, OutError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|_| {
+            let line = insert_stock_out_line(
+                ctx,
+                InsertStockOutLine {
+                    r#type: StockOutType::Prescription,
+                    ..input
+                },
+            )?;
+            let clinical_warnings = get_prescription_warnings(
+                ctx,
+                &line.invoice_row.id,
+                Some(line.item_row.id.clone()),
+            )?;
+            Ok(InsertPrescriptionLineResult {
+                line,
+                clinical_warnings,
+            }) as Result<InsertPrescriptionLineResult, OutError>
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
 #[cfg(test)]
 mod test {
     use repository::{
@@ -4,7 +4,7 @@ use crate::{
     invoice::{check_invoice_exists, check_invoice_is_editable, check_invoice_type, check_store},
     invoice_line::{
         check_batch_exists, check_batch_on_hold, check_existing_stock_line, check_location_on_hold,
-        invoice_backdated_date,
+        check_recalled_batch_can_be_removed, invoice_backdated_date,
         validate::{check_line_exists, check_number_of_packs},
         LocationIsOnHoldError,
     },
@@ -57,7 +57,9 @@ pub fn validate(
     if !check_invoice_is_editable(&invoice) {
         return Err(CannotEditFinalised);
     }
-    if !check_batch_on_hold(&batch) {
+    if !check_batch_on_hold(&batch)
+        && !check_recalled_batch_can_be_removed(connection, &input.r#type, &batch)?
+    {
         return Err(BatchIsOnHold);
     }
     check_location_on_hold(&batch).map_err(|e| match e {
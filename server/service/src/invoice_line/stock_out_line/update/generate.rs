@@ -1,10 +1,19 @@
 use repository::{InvoiceLineRow, InvoiceRow, InvoiceStatus, ItemRow, StockLine, StockLineRow};
 
-use crate::invoice::common::calculate_total_after_tax;
+use crate::{
+    invoice::common::calculate_total_after_tax,
+    pricing::{
+        calculate_sell_price::calculate_sell_price,
+        item_price::ItemPrice,
+        resolve_price::{resolve_price, ResolvePrice, ResolvedPrice},
+    },
+    service_provider::ServiceContext,
+};
 
 use super::{BatchPair, UpdateStockOutLine, UpdateStockOutLineError};
 
 pub fn generate(
+    ctx: &ServiceContext,
     input: UpdateStockOutLine,
     existing_line: InvoiceLineRow,
     item_row: ItemRow,
@@ -27,11 +36,27 @@ pub fn generate(
         ),
     };
 
+    // Price list quantity breaks depend on the number of units on the line
+    let pricing = match (input.number_of_packs, input.total_before_tax) {
+        (Some(number_of_packs), None) => Some(resolve_price(
+            ctx,
+            ResolvePrice {
+                item_id: item_row.id.clone(),
+                name_id: Some(invoice.name_link_id.clone()),
+                quantity: number_of_packs * batch_pair.main_batch.stock_line_row.pack_size,
+                date: None,
+            },
+        )?),
+        _ => None,
+    }
+    .filter(|pricing| pricing.price_list_line.is_some() && pricing.base_price_per_unit.is_some());
+
     let new_line = generate_line(
         input,
         existing_line,
         item_row,
         batch_pair.main_batch.stock_line_row.clone(),
+        pricing,
     );
 
     Ok((new_line, batch_pair))
@@ -103,10 +128,19 @@ fn generate_line(
         item_variant_id,
         ..
     }: StockLineRow,
+    pricing: Option<ResolvedPrice>,
 ) -> InvoiceLineRow {
-    // Cost & sell prices shouldn't need adjusting when the invoice line is being updated
+    // Cost price shouldn't need adjusting when the invoice line is being updated, sell price only
+    // changes when a price list applies to the new quantity
     let cost_price_per_pack = invoice_line_cost_price_per_pack;
-    let sell_price_per_pack = invoice_line_sell_price_per_pack;
+    let sell_price_per_pack = match pricing {
+        Some(pricing) => calculate_sell_price(
+            invoice_line_sell_price_per_pack,
+            pack_size,
+            ItemPrice::from(&pricing),
+        ),
+        None => invoice_line_sell_price_per_pack,
+    };
 
     let mut update_line = InvoiceLineRow {
         id,
@@ -131,6 +165,10 @@ fn generate_line(
         return_reason_id: None,
         foreign_currency_price_before_tax,
         item_variant_id,
+        linked_invoice_line_id: None,
+        discrepancy_type: None,
+        discrepancy_number_of_packs: 0.0,
+        discrepancy_reason: None,
     };
 
     if let Some(number_of_packs) = input.number_of_packs {
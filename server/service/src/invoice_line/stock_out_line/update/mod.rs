@@ -5,6 +5,7 @@ use repository::{
 
 use crate::{
     invoice_line::{query::get_invoice_line, ShipmentTaxUpdate},
+    serial_number::reconcile_picked_serial_numbers,
     service_provider::ServiceContext,
 };
 
@@ -63,8 +64,9 @@ pub fn update_stock_out_line(
         .transaction_sync(|connection| {
             let (line, item, batch_pair, invoice) = validate(ctx, &input, &ctx.store_id)?;
 
-            let (update_line, batch_pair) = generate(input, line, item, batch_pair, invoice)?;
+            let (update_line, batch_pair) = generate(ctx, input, line, item, batch_pair, invoice)?;
             InvoiceLineRowRepository::new(connection).upsert_one(&update_line)?;
+            reconcile_picked_serial_numbers(connection, &update_line)?;
 
             let stock_line_repo = StockLineRowRepository::new(connection);
             stock_line_repo.upsert_one(&batch_pair.main_batch.stock_line_row)?;
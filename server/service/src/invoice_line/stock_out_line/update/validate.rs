@@ -4,7 +4,7 @@ use crate::{
     invoice::{check_invoice_exists, check_invoice_is_editable, check_invoice_type, check_store},
     invoice_line::{
         check_batch_exists, check_batch_on_hold, check_existing_stock_line, check_location_on_hold,
-        invoice_backdated_date,
+        check_recalled_batch_can_be_removed, invoice_backdated_date,
         stock_out_line::BatchPair,
         validate::{check_line_belongs_to_invoice, check_line_exists, check_number_of_packs},
         LocationIsOnHoldError,
@@ -40,12 +40,11 @@ pub fn validate(
         return Err(StockLineAlreadyExistsInInvoice(existing_stock.id));
     }
 
-    if let Some(r#type) = &input.r#type {
-        if !check_invoice_type(&invoice, r#type.to_domain()) {
-            return Err(InvoiceTypeDoesNotMatch);
-        }
-    } else {
+    let Some(r#type) = &input.r#type else {
         return Err(NoInvoiceType);
+    };
+    if !check_invoice_type(&invoice, r#type.to_domain()) {
+        return Err(InvoiceTypeDoesNotMatch);
     }
     if !check_invoice_is_editable(&invoice) {
         return Err(CannotEditFinalised);
@@ -61,7 +60,9 @@ pub fn validate(
 
     let item = line.item_row.clone();
 
-    if !check_batch_on_hold(&batch_pair.main_batch) {
+    if !check_batch_on_hold(&batch_pair.main_batch)
+        && !check_recalled_batch_can_be_removed(connection, r#type, &batch_pair.main_batch)?
+    {
         return Err(BatchIsOnHold);
     }
     check_location_on_hold(&batch_pair.main_batch).map_err(|e| match e {
@@ -3,6 +3,10 @@ use repository::{
     StockLineFilter, StockLineRepository, StorageConnection,
 };
 
+use crate::recall::is_recalled_stock_line;
+
+use super::StockOutType;
+
 pub fn check_batch_exists(
     store_id: &str,
     batch_id: &str,
@@ -57,6 +61,20 @@ pub fn check_batch_on_hold(batch: &StockLine) -> bool {
     true
 }
 
+/// Stock put on hold by a recall can still be returned to the supplier or written off
+pub fn check_recalled_batch_can_be_removed(
+    connection: &StorageConnection,
+    r#type: &StockOutType,
+    batch: &StockLine,
+) -> Result<bool, RepositoryError> {
+    match r#type {
+        StockOutType::SupplierReturn | StockOutType::InventoryReduction => {
+            is_recalled_stock_line(connection, &batch.stock_line_row.id)
+        }
+        StockOutType::OutboundShipment | StockOutType::Prescription => Ok(false),
+    }
+}
+
 pub enum LocationIsOnHoldError {
     LocationIsOnHold,
 }
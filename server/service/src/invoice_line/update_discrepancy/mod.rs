@@ -0,0 +1,234 @@
+mod validate;
+use repository::{
+    InvoiceLine, InvoiceLineDiscrepancyType, InvoiceLineRow, InvoiceLineRowRepository,
+    RepositoryError, TransactionError,
+};
+use validate::validate;
+
+use crate::service_provider::ServiceContext;
+
+use super::get_invoice_line;
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct UpdateLineDiscrepancy {
+    pub line_id: String,
+    /// Clears the discrepancy when not set
+    pub r#type: Option<InvoiceLineDiscrepancyType>,
+    /// Packs short, damaged, from the wrong batch or in excess of what was shipped
+    pub number_of_packs: f64,
+    pub reason: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum UpdateLineDiscrepancyError {
+    LineDoesNotExist,
+    InvoiceDoesNotExist,
+    NotThisStoreInvoice,
+    NotAnInboundShipment,
+    CannotEditFinalised,
+    /// Line was added by the receiving store rather than generated from the outbound shipment
+    NotATransferLine,
+    NumberOfPacksMustBePositive,
+    ReasonIsRequired,
+    UpdatedLineDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+/// Records a shortage, damage, wrong batch or excess against an inbound transfer line. The
+/// discrepancy is sent back to the outbound shipment when the inbound shipment is verified
+pub fn update_line_discrepancy(
+    ctx: &ServiceContext,
+    input: UpdateLineDiscrepancy,
+) -> Result<InvoiceLine, UpdateLineDiscrepancyError> {
+    let new_line = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let line = validate(connection, &ctx.store_id, &input)?;
+            InvoiceLineRowRepository::new(connection).upsert_one(&generate(line, input.clone()))?;
+
+            get_invoice_line(ctx, &input.line_id)
+                .map_err(UpdateLineDiscrepancyError::DatabaseError)?
+                .ok_or(UpdateLineDiscrepancyError::UpdatedLineDoesNotExist)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(new_line)
+}
+
+fn generate(
+    line: InvoiceLineRow,
+    UpdateLineDiscrepancy {
+        line_id: _,
+        r#type,
+        number_of_packs,
+        reason,
+    }: UpdateLineDiscrepancy,
+) -> InvoiceLineRow {
+    match r#type {
+        Some(r#type) => InvoiceLineRow {
+            discrepancy_type: Some(r#type),
+            discrepancy_number_of_packs: number_of_packs,
+            discrepancy_reason: reason,
+            ..line
+        },
+        None => InvoiceLineRow {
+            discrepancy_type: None,
+            discrepancy_number_of_packs: 0.0,
+            discrepancy_reason: None,
+            ..line
+        },
+    }
+}
+
+impl From<RepositoryError> for UpdateLineDiscrepancyError {
+    fn from(error: RepositoryError) -> Self {
+        UpdateLineDiscrepancyError::DatabaseError(error)
+    }
+}
+
+impl From<TransactionError<UpdateLineDiscrepancyError>> for UpdateLineDiscrepancyError {
+    fn from(error: TransactionError<UpdateLineDiscrepancyError>) -> Self {
+        match error {
+            TransactionError::Transaction { msg, level } => {
+                UpdateLineDiscrepancyError::DatabaseError(RepositoryError::TransactionError {
+                    msg,
+                    level,
+                })
+            }
+            TransactionError::Inner(e) => e,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{
+            mock_inbound_shipment_a, mock_inbound_shipment_a_invoice_lines,
+            mock_inbound_shipment_b, mock_item_a, mock_outbound_shipment_a_invoice_lines,
+            mock_store_a, MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        InvoiceLineDiscrepancyType, InvoiceLineRow, InvoiceLineType,
+    };
+
+    use crate::{
+        invoice_line::{UpdateLineDiscrepancy, UpdateLineDiscrepancyError},
+        service_provider::ServiceProvider,
+    };
+
+    fn transfer_line(id: &str, invoice_id: &str) -> InvoiceLineRow {
+        InvoiceLineRow {
+            id: id.to_string(),
+            invoice_id: invoice_id.to_string(),
+            item_link_id: mock_item_a().id,
+            r#type: InvoiceLineType::StockIn,
+            pack_size: 10.0,
+            number_of_packs: 5.0,
+            linked_invoice_line_id: Some("outbound_line".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn short(line_id: &str) -> UpdateLineDiscrepancy {
+        UpdateLineDiscrepancy {
+            line_id: line_id.to_string(),
+            r#type: Some(InvoiceLineDiscrepancyType::Short),
+            number_of_packs: 2.0,
+            reason: Some("Two boxes missing from the pallet".to_string()),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn update_line_discrepancy() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "update_line_discrepancy",
+            MockDataInserts::all(),
+            MockData {
+                invoice_lines: vec![
+                    transfer_line("delivered_line", &mock_inbound_shipment_a().id),
+                    transfer_line("verified_line", &mock_inbound_shipment_b().id),
+                ],
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.invoice_line_service;
+
+        // Errors
+        assert_eq!(
+            service.update_line_discrepancy(&context, short("does_not_exist")),
+            Err(UpdateLineDiscrepancyError::LineDoesNotExist)
+        );
+        assert_eq!(
+            service.update_line_discrepancy(
+                &context,
+                short(&mock_outbound_shipment_a_invoice_lines()[0].id)
+            ),
+            Err(UpdateLineDiscrepancyError::NotThisStoreInvoice)
+        );
+        assert_eq!(
+            service.update_line_discrepancy(&context, short("verified_line")),
+            Err(UpdateLineDiscrepancyError::CannotEditFinalised)
+        );
+        assert_eq!(
+            service.update_line_discrepancy(
+                &context,
+                short(&mock_inbound_shipment_a_invoice_lines()[0].id)
+            ),
+            Err(UpdateLineDiscrepancyError::NotATransferLine)
+        );
+        assert_eq!(
+            service.update_line_discrepancy(
+                &context,
+                UpdateLineDiscrepancy {
+                    number_of_packs: 0.0,
+                    ..short("delivered_line")
+                }
+            ),
+            Err(UpdateLineDiscrepancyError::NumberOfPacksMustBePositive)
+        );
+        assert_eq!(
+            service.update_line_discrepancy(
+                &context,
+                UpdateLineDiscrepancy {
+                    reason: Some(" ".to_string()),
+                    ..short("delivered_line")
+                }
+            ),
+            Err(UpdateLineDiscrepancyError::ReasonIsRequired)
+        );
+
+        // Success
+        let line = service
+            .update_line_discrepancy(&context, short("delivered_line"))
+            .unwrap()
+            .invoice_line_row;
+        assert_eq!(
+            line.discrepancy_type,
+            Some(InvoiceLineDiscrepancyType::Short)
+        );
+        assert_eq!(line.discrepancy_number_of_packs, 2.0);
+        // Received quantity is left for the receiver to edit
+        assert_eq!(line.number_of_packs, 5.0);
+
+        // Clear
+        let line = service
+            .update_line_discrepancy(
+                &context,
+                UpdateLineDiscrepancy {
+                    line_id: "delivered_line".to_string(),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .invoice_line_row;
+        assert_eq!(line.discrepancy_type, None);
+        assert_eq!(line.discrepancy_number_of_packs, 0.0);
+        assert_eq!(line.discrepancy_reason, None);
+    }
+}
@@ -0,0 +1,49 @@
+use repository::{InvoiceLineRow, InvoiceType, StorageConnection};
+
+use crate::{
+    invoice::{check_invoice_exists, check_invoice_is_editable, check_invoice_type, check_store},
+    invoice_line::validate::check_line_row_exists,
+};
+
+use super::{UpdateLineDiscrepancy, UpdateLineDiscrepancyError};
+
+pub fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &UpdateLineDiscrepancy,
+) -> Result<InvoiceLineRow, UpdateLineDiscrepancyError> {
+    use UpdateLineDiscrepancyError::*;
+
+    let line = check_line_row_exists(connection, &input.line_id)?.ok_or(LineDoesNotExist)?;
+    let invoice = check_invoice_exists(&line.invoice_id, connection)?.ok_or(InvoiceDoesNotExist)?;
+
+    if !check_store(&invoice, store_id) {
+        return Err(NotThisStoreInvoice);
+    }
+    if !check_invoice_type(&invoice, InvoiceType::InboundShipment) {
+        return Err(NotAnInboundShipment);
+    }
+    if !check_invoice_is_editable(&invoice) {
+        return Err(CannotEditFinalised);
+    }
+    // Discrepancies are reported back against the outbound line, so only lines that came
+    // from a transfer can have one
+    if line.linked_invoice_line_id.is_none() {
+        return Err(NotATransferLine);
+    }
+
+    if input.r#type.is_some() {
+        if input.number_of_packs <= 0.0 {
+            return Err(NumberOfPacksMustBePositive);
+        }
+        if input
+            .reason
+            .as_ref()
+            .is_none_or(|reason| reason.trim().is_empty())
+        {
+            return Err(ReasonIsRequired);
+        }
+    }
+
+    Ok(line)
+}
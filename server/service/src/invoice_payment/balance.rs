@@ -0,0 +1,164 @@
+use chrono::{Local, NaiveDate};
+use repository::{
+    EqualFilter, InvoiceFilter, InvoicePaymentRowRepository, InvoicePaymentType, InvoiceRepository,
+    InvoiceRow, InvoiceStatus, InvoiceType, NameRowRepository, RepositoryError,
+};
+
+use crate::service_provider::ServiceContext;
+
+use super::{get_payment_summaries, InvoicePaymentSummary, AMOUNT_TOLERANCE};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CustomerBalanceInput {
+    pub name_id: String,
+    /// Date invoice age is calculated from, defaults to today
+    pub as_of: Option<NaiveDate>,
+}
+
+/// Outstanding amounts by age of the invoice
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AgingBuckets {
+    /// Up to 30 days
+    pub current: f64,
+    pub days_31_to_60: f64,
+    pub days_61_to_90: f64,
+    pub over_90_days: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutstandingInvoice {
+    pub invoice_row: InvoiceRow,
+    pub summary: InvoicePaymentSummary,
+    /// Days since the invoice was picked
+    pub days_outstanding: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CustomerBalance {
+    pub name_id: String,
+    /// Shipped, delivered or verified outbound shipments and verified prescriptions
+    pub total_invoiced: f64,
+    pub total_paid: f64,
+    pub total_credited: f64,
+    /// Credit notes not applied to an invoice
+    pub unapplied_credit: f64,
+    /// Amount owed by the customer, negative if the customer is in credit
+    pub balance: f64,
+    pub aging: AgingBuckets,
+    pub outstanding_invoices: Vec<OutstandingInvoice>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CustomerBalanceError {
+    NameDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for CustomerBalanceError {
+    fn from(error: RepositoryError) -> Self {
+        CustomerBalanceError::DatabaseError(error)
+    }
+}
+
+/// Balance of a customer in the ctx store, with outstanding invoices grouped by age
+pub fn get_customer_balance(
+    ctx: &ServiceContext,
+    input: CustomerBalanceInput,
+) -> Result<CustomerBalance, CustomerBalanceError> {
+    let connection = &ctx.connection;
+    if NameRowRepository::new(connection)
+        .find_one_by_id(&input.name_id)?
+        .is_none()
+    {
+        return Err(CustomerBalanceError::NameDoesNotExist);
+    }
+    let as_of = input.as_of.unwrap_or_else(|| Local::now().date_naive());
+
+    // Invoices are owed once their total can no longer change, same as when they can be paid
+    // (see `check_invoice_is_editable`)
+    let invoices = InvoiceRepository::new(connection).query_by_filter(
+        InvoiceFilter::new()
+            .store_id(EqualFilter::equal_to(&ctx.store_id))
+            .name_id(EqualFilter::equal_to(&input.name_id))
+            .r#type(EqualFilter {
+                equal_any: Some(vec![
+                    InvoiceType::OutboundShipment,
+                    InvoiceType::Prescription,
+                ]),
+                ..Default::default()
+            })
+            .status(EqualFilter {
+                equal_any: Some(vec![
+                    InvoiceStatus::Shipped,
+                    InvoiceStatus::Delivered,
+                    InvoiceStatus::Verified,
+                ]),
+                ..Default::default()
+            }),
+    )?;
+    let invoice_ids: Vec<String> = invoices
+        .iter()
+        .map(|invoice| invoice.invoice_row.id.clone())
+        .collect();
+    let mut summaries = get_payment_summaries(connection, &invoice_ids)?;
+
+    let mut balance = CustomerBalance {
+        name_id: input.name_id.clone(),
+        ..Default::default()
+    };
+
+    for payment in InvoicePaymentRowRepository::new(connection)
+        .find_many_by_name_id(&ctx.store_id, &input.name_id)?
+        .into_iter()
+        .filter(|payment| !payment.is_cancelled())
+    {
+        match payment.r#type {
+            InvoicePaymentType::Payment => balance.total_paid += payment.home_currency_amount,
+            InvoicePaymentType::CreditNote => {
+                balance.total_credited += payment.home_currency_amount;
+                if payment.invoice_id.is_none() {
+                    balance.unapplied_credit += payment.home_currency_amount;
+                }
+            }
+        }
+    }
+
+    for invoice in invoices {
+        let invoice_row = invoice.invoice_row;
+        let Some(summary) = summaries.remove(&invoice_row.id) else {
+            continue;
+        };
+        balance.total_invoiced += summary.total;
+
+        let outstanding = summary.outstanding();
+        if outstanding <= AMOUNT_TOLERANCE {
+            continue;
+        }
+
+        let invoice_date = invoice_row
+            .picked_datetime
+            .unwrap_or(invoice_row.created_datetime)
+            .date();
+        let days_outstanding = (as_of - invoice_date).num_days().max(0);
+        let bucket = match days_outstanding {
+            0..=30 => &mut balance.aging.current,
+            31..=60 => &mut balance.aging.days_31_to_60,
+            61..=90 => &mut balance.aging.days_61_to_90,
+            _ => &mut balance.aging.over_90_days,
+        };
+        *bucket += outstanding;
+
+        balance.outstanding_invoices.push(OutstandingInvoice {
+            invoice_row,
+            summary,
+            days_outstanding,
+        });
+    }
+
+    balance
+        .outstanding_invoices
+        .sort_by_key(|invoice| std::cmp::Reverse(invoice.days_outstanding));
+    balance.balance = balance.total_invoiced - balance.total_paid - balance.total_credited;
+
+    Ok(balance)
+}
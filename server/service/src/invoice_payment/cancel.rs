@@ -0,0 +1,67 @@
+use chrono::Utc;
+use repository::{
+    ActivityLogType, InvoicePaymentRow, InvoicePaymentRowRepository, RepositoryError,
+};
+
+use crate::{activity_log::activity_log_entry, service_provider::ServiceContext};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CancelInvoicePayment {
+    pub id: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CancelInvoicePaymentError {
+    PaymentDoesNotExist,
+    NotThisStorePayment,
+    PaymentAlreadyCancelled,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for CancelInvoicePaymentError {
+    fn from(error: RepositoryError) -> Self {
+        CancelInvoicePaymentError::DatabaseError(error)
+    }
+}
+
+/// Payments are never deleted so receipt numbers stay accounted for, cancelled payments are
+/// excluded from balances and listed separately in the reconciliation
+pub fn cancel_invoice_payment(
+    ctx: &ServiceContext,
+    input: CancelInvoicePayment,
+) -> Result<InvoicePaymentRow, CancelInvoicePaymentError> {
+    let payment = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let repo = InvoicePaymentRowRepository::new(connection);
+            let mut payment = repo
+                .find_one_by_id(&input.id)?
+                .ok_or(CancelInvoicePaymentError::PaymentDoesNotExist)?;
+            if payment.store_id != ctx.store_id {
+                return Err(CancelInvoicePaymentError::NotThisStorePayment);
+            }
+            if payment.is_cancelled() {
+                return Err(CancelInvoicePaymentError::PaymentAlreadyCancelled);
+            }
+
+            payment.cancelled_datetime = Some(Utc::now().naive_utc());
+            repo.upsert_one(&payment)?;
+
+            activity_log_entry(
+                ctx,
+                ActivityLogType::InvoicePaymentCancelled,
+                payment
+                    .invoice_id
+                    .clone()
+                    .or_else(|| payment.customer_return_id.clone()),
+                Some(format!("{:?} {}", payment.r#type, payment.receipt_number)),
+                input.reason,
+            )?;
+
+            Ok(payment)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(payment)
+}
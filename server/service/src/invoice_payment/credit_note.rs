@@ -0,0 +1,209 @@
+use chrono::{NaiveDateTime, Utc};
+use repository::{
+    ActivityLogType, InvoicePaymentRow, InvoicePaymentRowRepository, InvoicePaymentType,
+    InvoiceRow, InvoiceRowRepository, InvoiceStatus, InvoiceType, NameLinkRowRepository,
+    NumberRowType, RepositoryError, StorageConnection, TransactionError,
+};
+
+use crate::{
+    activity_log::activity_log_entry, invoice::check_invoice_is_editable, number::next_number,
+    service_provider::ServiceContext,
+};
+
+use super::{get_payment_summaries, AMOUNT_TOLERANCE};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InsertCreditNote {
+    pub id: String,
+    pub customer_return_id: String,
+    /// Invoice of the same customer to apply the credit to, credit stays on the customer's
+    /// account if not set
+    pub invoice_id: Option<String>,
+    /// Defaults to the return total that hasn't been credited yet
+    pub amount: Option<f64>,
+    pub comment: Option<String>,
+    /// Defaults to now
+    pub payment_datetime: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InsertCreditNoteError {
+    CreditNoteAlreadyExists,
+    ReturnDoesNotExist,
+    NotThisStoreReturn,
+    NotACustomerReturn,
+    /// Return needs to be delivered or verified before credit is given
+    ReturnNotReceived,
+    AmountMustBePositive,
+    /// Amount that can still be credited for the return
+    AmountExceedsReturnTotal(f64),
+    InvoiceDoesNotExist,
+    NotThisStoreInvoice,
+    CannotApplyToInvoiceType,
+    /// Credit can only be applied once the invoice total can no longer change
+    InvoiceNotFinalised,
+    InvoiceDoesNotBelongToCustomer,
+    /// Amount outstanding on the invoice
+    AmountExceedsOutstanding(f64),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for InsertCreditNoteError {
+    fn from(error: RepositoryError) -> Self {
+        InsertCreditNoteError::DatabaseError(error)
+    }
+}
+
+/// Issues a credit note, in home currency, for goods returned by a customer
+pub fn insert_credit_note(
+    ctx: &ServiceContext,
+    input: InsertCreditNote,
+) -> Result<InvoicePaymentRow, InsertCreditNoteError> {
+    let credit_note = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let (customer_return, amount) = validate(connection, &ctx.store_id, &input)?;
+            let credit_note = generate(connection, ctx, customer_return, amount, input)?;
+            InvoicePaymentRowRepository::new(connection).upsert_one(&credit_note)?;
+
+            activity_log_entry(
+                ctx,
+                ActivityLogType::InvoicePaymentCreated,
+                credit_note.customer_return_id.clone(),
+                None,
+                Some(format!(
+                    "Credit note {}: {:.2}",
+                    credit_note.receipt_number, credit_note.home_currency_amount
+                )),
+            )?;
+
+            Ok(credit_note)
+        })
+        .map_err(|error: TransactionError<InsertCreditNoteError>| error.to_inner_error())?;
+
+    Ok(credit_note)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &InsertCreditNote,
+) -> Result<(InvoiceRow, f64), InsertCreditNoteError> {
+    use InsertCreditNoteError as Error;
+
+    let payment_repo = InvoicePaymentRowRepository::new(connection);
+    if payment_repo.find_one_by_id(&input.id)?.is_some() {
+        return Err(Error::CreditNoteAlreadyExists);
+    }
+
+    let invoice_repo = InvoiceRowRepository::new(connection);
+    let customer_return = invoice_repo
+        .find_one_by_id(&input.customer_return_id)?
+        .ok_or(Error::ReturnDoesNotExist)?;
+    if customer_return.store_id != store_id {
+        return Err(Error::NotThisStoreReturn);
+    }
+    if customer_return.r#type != InvoiceType::CustomerReturn {
+        return Err(Error::NotACustomerReturn);
+    }
+    if !matches!(
+        customer_return.status,
+        InvoiceStatus::Delivered | InvoiceStatus::Verified
+    ) {
+        return Err(Error::ReturnNotReceived);
+    }
+
+    let return_total =
+        get_payment_summaries(connection, std::slice::from_ref(&customer_return.id))?
+            .remove(&customer_return.id)
+            .map(|summary| summary.total)
+            .unwrap_or_default();
+    let already_credited: f64 = payment_repo
+        .find_many_by_customer_return_id(&customer_return.id)?
+        .iter()
+        .filter(|credit_note| !credit_note.is_cancelled())
+        .map(|credit_note| credit_note.home_currency_amount)
+        .sum();
+    let remaining = return_total - already_credited;
+
+    let amount = input.amount.unwrap_or(remaining);
+    if amount <= 0.0 {
+        return Err(Error::AmountMustBePositive);
+    }
+    if amount > remaining + AMOUNT_TOLERANCE {
+        return Err(Error::AmountExceedsReturnTotal(remaining));
+    }
+
+    if let Some(invoice_id) = &input.invoice_id {
+        let invoice = invoice_repo
+            .find_one_by_id(invoice_id)?
+            .ok_or(Error::InvoiceDoesNotExist)?;
+        if invoice.store_id != store_id {
+            return Err(Error::NotThisStoreInvoice);
+        }
+        if !matches!(
+            invoice.r#type,
+            InvoiceType::OutboundShipment | InvoiceType::Prescription
+        ) {
+            return Err(Error::CannotApplyToInvoiceType);
+        }
+        if check_invoice_is_editable(&invoice) {
+            return Err(Error::InvoiceNotFinalised);
+        }
+
+        let name_link_repo = NameLinkRowRepository::new(connection);
+        let name_id = |name_link_id: &str| -> Result<Option<String>, RepositoryError> {
+            Ok(name_link_repo
+                .find_one_by_id(name_link_id)?
+                .map(|name_link| name_link.name_id))
+        };
+        if name_id(&invoice.name_link_id)? != name_id(&customer_return.name_link_id)? {
+            return Err(Error::InvoiceDoesNotBelongToCustomer);
+        }
+
+        let outstanding = get_payment_summaries(connection, std::slice::from_ref(&invoice.id))?
+            .remove(&invoice.id)
+            .map(|summary| summary.outstanding())
+            .unwrap_or_default();
+        if amount > outstanding + AMOUNT_TOLERANCE {
+            return Err(Error::AmountExceedsOutstanding(outstanding));
+        }
+    }
+
+    Ok((customer_return, amount))
+}
+
+fn generate(
+    connection: &StorageConnection,
+    ctx: &ServiceContext,
+    customer_return: InvoiceRow,
+    amount: f64,
+    InsertCreditNote {
+        id,
+        customer_return_id,
+        invoice_id,
+        amount: _,
+        comment,
+        payment_datetime,
+    }: InsertCreditNote,
+) -> Result<InvoicePaymentRow, RepositoryError> {
+    Ok(InvoicePaymentRow {
+        id,
+        store_id: ctx.store_id.clone(),
+        name_link_id: customer_return.name_link_id,
+        invoice_id,
+        customer_return_id: Some(customer_return_id),
+        r#type: InvoicePaymentType::CreditNote,
+        payment_method: None,
+        receipt_number: next_number(connection, &NumberRowType::CreditNote, &ctx.store_id)?,
+        amount,
+        currency_id: None,
+        currency_rate: 1.0,
+        home_currency_amount: amount,
+        reference: None,
+        comment,
+        user_id: Some(ctx.user_id.clone()),
+        payment_datetime: payment_datetime.unwrap_or_else(|| Utc::now().naive_utc()),
+        cancelled_datetime: None,
+    })
+}
@@ -0,0 +1,174 @@
+use chrono::{NaiveDateTime, Utc};
+use repository::{
+    ActivityLogType, CurrencyRowRepository, InvoicePaymentRow, InvoicePaymentRowRepository,
+    InvoicePaymentType, InvoiceRow, InvoiceRowRepository, InvoiceType, NumberRowType,
+    PaymentMethod, RepositoryError, StorageConnection, TransactionError,
+};
+
+use crate::{
+    activity_log::activity_log_entry, invoice::check_invoice_is_editable, number::next_number,
+    service_provider::ServiceContext,
+};
+
+use super::{get_payment_summaries, AMOUNT_TOLERANCE};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InsertInvoicePayment {
+    pub id: String,
+    pub invoice_id: String,
+    /// In the payment currency
+    pub amount: f64,
+    pub payment_method: PaymentMethod,
+    /// Home currency if not set
+    pub currency_id: Option<String>,
+    pub reference: Option<String>,
+    pub comment: Option<String>,
+    /// Defaults to now
+    pub payment_datetime: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InsertInvoicePaymentError {
+    PaymentAlreadyExists,
+    InvoiceDoesNotExist,
+    NotThisStoreInvoice,
+    /// Only outbound shipments and prescriptions can be paid
+    CannotPayInvoiceType,
+    /// Invoice total can still change, i.e. outbound shipment not shipped or prescription not
+    /// verified
+    InvoiceNotFinalised,
+    AmountMustBePositive,
+    CurrencyDoesNotExist,
+    /// Payment is more than is still owed on the invoice, amount outstanding in home currency
+    AmountExceedsOutstanding(f64),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for InsertInvoicePaymentError {
+    fn from(error: RepositoryError) -> Self {
+        InsertInvoicePaymentError::DatabaseError(error)
+    }
+}
+
+/// Records a full or partial payment against an invoice and gives it the next receipt number
+pub fn insert_invoice_payment(
+    ctx: &ServiceContext,
+    input: InsertInvoicePayment,
+) -> Result<InvoicePaymentRow, InsertInvoicePaymentError> {
+    let payment = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let (invoice, currency_rate) = validate(connection, &ctx.store_id, &input)?;
+            let payment = generate(connection, ctx, invoice, currency_rate, input)?;
+            InvoicePaymentRowRepository::new(connection).upsert_one(&payment)?;
+
+            activity_log_entry(
+                ctx,
+                ActivityLogType::InvoicePaymentCreated,
+                payment.invoice_id.clone(),
+                None,
+                Some(format!(
+                    "Receipt {}: {:.2}",
+                    payment.receipt_number, payment.home_currency_amount
+                )),
+            )?;
+
+            Ok(payment)
+        })
+        .map_err(|error: TransactionError<InsertInvoicePaymentError>| error.to_inner_error())?;
+
+    Ok(payment)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &InsertInvoicePayment,
+) -> Result<(InvoiceRow, f64), InsertInvoicePaymentError> {
+    use InsertInvoicePaymentError as Error;
+
+    if InvoicePaymentRowRepository::new(connection)
+        .find_one_by_id(&input.id)?
+        .is_some()
+    {
+        return Err(Error::PaymentAlreadyExists);
+    }
+
+    let invoice = InvoiceRowRepository::new(connection)
+        .find_one_by_id(&input.invoice_id)?
+        .ok_or(Error::InvoiceDoesNotExist)?;
+    if invoice.store_id != store_id {
+        return Err(Error::NotThisStoreInvoice);
+    }
+    if !matches!(
+        invoice.r#type,
+        InvoiceType::OutboundShipment | InvoiceType::Prescription
+    ) {
+        return Err(Error::CannotPayInvoiceType);
+    }
+    if check_invoice_is_editable(&invoice) {
+        return Err(Error::InvoiceNotFinalised);
+    }
+
+    if input.amount <= 0.0 {
+        return Err(Error::AmountMustBePositive);
+    }
+
+    let currency_rate = match &input.currency_id {
+        Some(currency_id) => {
+            CurrencyRowRepository::new(connection)
+                .find_one_by_id(currency_id)?
+                .filter(|currency| currency.is_active)
+                .ok_or(Error::CurrencyDoesNotExist)?
+                .rate
+        }
+        None => 1.0,
+    };
+
+    let outstanding = get_payment_summaries(connection, std::slice::from_ref(&invoice.id))?
+        .remove(&invoice.id)
+        .map(|summary| summary.outstanding())
+        .unwrap_or_default();
+    if input.amount * currency_rate > outstanding + AMOUNT_TOLERANCE {
+        return Err(Error::AmountExceedsOutstanding(outstanding));
+    }
+
+    Ok((invoice, currency_rate))
+}
+
+fn generate(
+    connection: &StorageConnection,
+    ctx: &ServiceContext,
+    invoice: InvoiceRow,
+    currency_rate: f64,
+    InsertInvoicePayment {
+        id,
+        invoice_id,
+        amount,
+        payment_method,
+        currency_id,
+        reference,
+        comment,
+        payment_datetime,
+    }: InsertInvoicePayment,
+) -> Result<InvoicePaymentRow, RepositoryError> {
+    Ok(InvoicePaymentRow {
+        id,
+        store_id: ctx.store_id.clone(),
+        name_link_id: invoice.name_link_id,
+        invoice_id: Some(invoice_id),
+        customer_return_id: None,
+        r#type: InvoicePaymentType::Payment,
+        payment_method: Some(payment_method),
+        receipt_number: next_number(connection, &NumberRowType::Receipt, &ctx.store_id)?,
+        amount,
+        currency_id,
+        currency_rate,
+        home_currency_amount: amount * currency_rate,
+        reference,
+        comment,
+        user_id: Some(ctx.user_id.clone()),
+        payment_datetime: payment_datetime.unwrap_or_else(|| Utc::now().naive_utc()),
+        cancelled_datetime: None,
+    })
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+
+use repository::{
+    InvoiceLineRepository, InvoicePaymentRow, InvoicePaymentRowRepository, InvoicePaymentType,
+    RepositoryError, StorageConnection,
+};
+
+use crate::service_provider::ServiceContext;
+
+pub mod balance;
+pub use self::balance::*;
+pub mod cancel;
+pub use self::cancel::*;
+pub mod credit_note;
+pub use self::credit_note::*;
+pub mod insert;
+pub use self::insert::*;
+pub mod reconciliation;
+pub use self::reconciliation::*;
+
+#[cfg(test)]
+mod test;
+
+/// Amounts within half a cent are treated as equal when checking for over payment
+pub(crate) const AMOUNT_TOLERANCE: f64 = 0.005;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InvoicePaymentSummary {
+    pub invoice_id: String,
+    /// Invoice total after tax
+    pub total: f64,
+    /// Payments in home currency, excluding cancelled payments
+    pub paid: f64,
+    /// Credit notes applied to the invoice, excluding cancelled credit notes
+    pub credited: f64,
+    /// Includes cancelled payments and credit notes
+    pub payments: Vec<InvoicePaymentRow>,
+}
+
+impl InvoicePaymentSummary {
+    pub fn outstanding(&self) -> f64 {
+        self.total - self.paid - self.credited
+    }
+}
+
+pub trait InvoicePaymentServiceTrait: Sync + Send {
+    fn get_invoice_payment_summary(
+        &self,
+        ctx: &ServiceContext,
+        invoice_id: &str,
+    ) -> Result<InvoicePaymentSummary, RepositoryError> {
+        let mut summaries = get_payment_summaries(&ctx.connection, &[invoice_id.to_string()])?;
+        Ok(summaries.remove(invoice_id).unwrap_or_default())
+    }
+
+    fn insert_invoice_payment(
+        &self,
+        ctx: &ServiceContext,
+        input: InsertInvoicePayment,
+    ) -> Result<InvoicePaymentRow, InsertInvoicePaymentError> {
+        insert_invoice_payment(ctx, input)
+    }
+
+    fn insert_credit_note(
+        &self,
+        ctx: &ServiceContext,
+        input: InsertCreditNote,
+    ) -> Result<InvoicePaymentRow, InsertCreditNoteError> {
+        insert_credit_note(ctx, input)
+    }
+
+    fn cancel_invoice_payment(
+        &self,
+        ctx: &ServiceContext,
+        input: CancelInvoicePayment,
+    ) -> Result<InvoicePaymentRow, CancelInvoicePaymentError> {
+        cancel_invoice_payment(ctx, input)
+    }
+
+    fn get_customer_balance(
+        &self,
+        ctx: &ServiceContext,
+        input: CustomerBalanceInput,
+    ) -> Result<CustomerBalance, CustomerBalanceError> {
+        get_customer_balance(ctx, input)
+    }
+
+    fn get_cashier_reconciliation(
+        &self,
+        ctx: &ServiceContext,
+        input: CashierReconciliationInput,
+    ) -> Result<CashierReconciliation, RepositoryError> {
+        get_cashier_reconciliation(ctx, input)
+    }
+}
+
+pub struct InvoicePaymentService {}
+impl InvoicePaymentServiceTrait for InvoicePaymentService {}
+
+/// Totals, payments and credit notes of the invoices, keyed by invoice id
+pub(crate) fn get_payment_summaries(
+    connection: &StorageConnection,
+    invoice_ids: &[String],
+) -> Result<HashMap<String, InvoicePaymentSummary>, RepositoryError> {
+    let mut summaries: HashMap<String, InvoicePaymentSummary> = invoice_ids
+        .iter()
+        .map(|invoice_id| {
+            (
+                invoice_id.clone(),
+                InvoicePaymentSummary {
+                    invoice_id: invoice_id.clone(),
+                    ..Default::default()
+                },
+            )
+        })
+        .collect();
+
+    for stats in InvoiceLineRepository::new(connection).stats(invoice_ids)? {
+        if let Some(summary) = summaries.get_mut(&stats.invoice_id) {
+            summary.total = stats.total_after_tax;
+        }
+    }
+
+    for payment in
+        InvoicePaymentRowRepository::new(connection).find_many_by_invoice_ids(invoice_ids)?
+    {
+        let Some(summary) = payment
+            .invoice_id
+            .as_ref()
+            .and_then(|invoice_id| summaries.get_mut(invoice_id))
+        else {
+            continue;
+        };
+        if !payment.is_cancelled() {
+            match payment.r#type {
+                InvoicePaymentType::Payment => summary.paid += payment.home_currency_amount,
+                InvoicePaymentType::CreditNote => summary.credited += payment.home_currency_amount,
+            }
+        }
+        summary.payments.push(payment);
+    }
+
+    Ok(summaries)
+}
@@ -0,0 +1,100 @@
+use chrono::NaiveDateTime;
+use repository::{
+    InvoicePaymentRow, InvoicePaymentRowRepository, InvoicePaymentType, PaymentMethod,
+    RepositoryError,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CashierReconciliationInput {
+    /// Start of the cashier's day, inclusive
+    pub from_datetime: NaiveDateTime,
+    /// End of the cashier's day, exclusive
+    pub to_datetime: NaiveDateTime,
+    /// Payments taken by this user only, all users if not set
+    pub user_id: Option<String>,
+}
+
+/// Payments taken with one method in one currency, e.g. cash in USD
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentMethodTotal {
+    pub payment_method: PaymentMethod,
+    pub currency_id: Option<String>,
+    pub count: u32,
+    /// In the payment currency, the amount the cashier should have counted
+    pub amount: f64,
+    pub home_currency_amount: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CashierReconciliation {
+    pub totals: Vec<PaymentMethodTotal>,
+    /// All payments, excluding cancelled payments
+    pub total_home_currency_amount: f64,
+    /// Credit notes issued, these don't affect the cash drawer
+    pub credit_note_count: u32,
+    pub credit_note_total: f64,
+    pub cancelled_count: u32,
+    pub cancelled_home_currency_amount: f64,
+    /// Payments and credit notes in receipt number order, including cancelled
+    pub payments: Vec<InvoicePaymentRow>,
+}
+
+/// End of day summary of the payments taken in the ctx store
+pub fn get_cashier_reconciliation(
+    ctx: &ServiceContext,
+    input: CashierReconciliationInput,
+) -> Result<CashierReconciliation, RepositoryError> {
+    let payments: Vec<InvoicePaymentRow> = InvoicePaymentRowRepository::new(&ctx.connection)
+        .find_many_by_payment_datetime(&ctx.store_id, input.from_datetime, input.to_datetime)?
+        .into_iter()
+        .filter(|payment| {
+            input
+                .user_id
+                .as_ref()
+                .is_none_or(|user_id| payment.user_id.as_ref() == Some(user_id))
+        })
+        .collect();
+
+    let mut reconciliation = CashierReconciliation::default();
+    for payment in &payments {
+        if payment.is_cancelled() {
+            reconciliation.cancelled_count += 1;
+            reconciliation.cancelled_home_currency_amount += payment.home_currency_amount;
+            continue;
+        }
+
+        let payment_method = match (&payment.r#type, &payment.payment_method) {
+            (InvoicePaymentType::Payment, Some(payment_method)) => payment_method,
+            _ => {
+                reconciliation.credit_note_count += 1;
+                reconciliation.credit_note_total += payment.home_currency_amount;
+                continue;
+            }
+        };
+
+        reconciliation.total_home_currency_amount += payment.home_currency_amount;
+        let total = match reconciliation.totals.iter_mut().find(|total| {
+            &total.payment_method == payment_method && total.currency_id == payment.currency_id
+        }) {
+            Some(total) => total,
+            None => {
+                reconciliation.totals.push(PaymentMethodTotal {
+                    payment_method: payment_method.clone(),
+                    currency_id: payment.currency_id.clone(),
+                    count: 0,
+                    amount: 0.0,
+                    home_currency_amount: 0.0,
+                });
+                reconciliation.totals.last_mut().unwrap()
+            }
+        };
+        total.count += 1;
+        total.amount += payment.amount;
+        total.home_currency_amount += payment.home_currency_amount;
+    }
+    reconciliation.payments = payments;
+
+    Ok(reconciliation)
+}
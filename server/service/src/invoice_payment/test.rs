@@ -0,0 +1,330 @@
+#[cfg(test)]
+mod invoice_payment {
+    use chrono::{Duration, NaiveDate, Utc};
+    use repository::{
+        mock::{
+            currency_b, mock_item_a, mock_outbound_shipment_a, mock_patient, mock_store_a,
+            MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        InvoiceLineRow, InvoiceLineType, InvoiceRow, InvoiceStatus, InvoiceType, PaymentMethod,
+    };
+    use util::inline_init;
+
+    use crate::{
+        invoice_payment::{
+            CancelInvoicePayment, CancelInvoicePaymentError, CashierReconciliationInput,
+            CustomerBalanceInput, InsertCreditNote, InsertCreditNoteError, InsertInvoicePayment,
+            InsertInvoicePaymentError,
+        },
+        service_provider::ServiceProvider,
+    };
+
+    fn as_of() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, 30).unwrap()
+    }
+
+    fn invoice(id: &str, r#type: InvoiceType, status: InvoiceStatus, age: i64) -> InvoiceRow {
+        let datetime = (as_of() - Duration::days(age))
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        inline_init(|r: &mut InvoiceRow| {
+            r.id = id.to_string();
+            r.name_link_id = mock_patient().id;
+            r.store_id = mock_store_a().id;
+            r.r#type = r#type;
+            r.status = status;
+            r.created_datetime = datetime;
+            r.picked_datetime = Some(datetime);
+            r.currency_rate = 1.0;
+        })
+    }
+
+    fn line(invoice_id: &str, total: f64) -> InvoiceLineRow {
+        inline_init(|r: &mut InvoiceLineRow| {
+            r.id = format!("{}_line", invoice_id);
+            r.invoice_id = invoice_id.to_string();
+            r.item_link_id = mock_item_a().id;
+            r.pack_size = 1.0;
+            r.number_of_packs = 1.0;
+            r.total_before_tax = total;
+            r.total_after_tax = total;
+            r.r#type = InvoiceLineType::StockOut;
+        })
+    }
+
+    fn payment(id: &str, invoice_id: &str, amount: f64) -> InsertInvoicePayment {
+        InsertInvoicePayment {
+            id: id.to_string(),
+            invoice_id: invoice_id.to_string(),
+            amount,
+            payment_method: PaymentMethod::Cash,
+            currency_id: None,
+            reference: None,
+            comment: None,
+            payment_datetime: None,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn invoice_payment() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "invoice_payment",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.invoices = vec![
+                    invoice(
+                        "old",
+                        InvoiceType::Prescription,
+                        InvoiceStatus::Verified,
+                        45,
+                    ),
+                    invoice(
+                        "recent",
+                        InvoiceType::Prescription,
+                        InvoiceStatus::Verified,
+                        5,
+                    ),
+                    invoice(
+                        "not_picked",
+                        InvoiceType::Prescription,
+                        InvoiceStatus::New,
+                        1,
+                    ),
+                    invoice(
+                        "not_verified",
+                        InvoiceType::Prescription,
+                        InvoiceStatus::Picked,
+                        3,
+                    ),
+                    invoice(
+                        "return",
+                        InvoiceType::CustomerReturn,
+                        InvoiceStatus::Delivered,
+                        2,
+                    ),
+                ];
+                r.invoice_lines = vec![
+                    line("old", 100.0),
+                    line("recent", 40.0),
+                    line("not_picked", 25.0),
+                    line("not_verified", 15.0),
+                    InvoiceLineRow {
+                        r#type: InvoiceLineType::StockIn,
+                        ..line("return", 30.0)
+                    },
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "cashier".to_string())
+            .unwrap();
+        let service = &service_provider.invoice_payment_service;
+
+        // Payment errors
+        assert_eq!(
+            service.insert_invoice_payment(&context, payment("p", "old", 0.0)),
+            Err(InsertInvoicePaymentError::AmountMustBePositive)
+        );
+        assert_eq!(
+            service.insert_invoice_payment(
+                &context,
+                payment("p", &mock_outbound_shipment_a().id, 1.0)
+            ),
+            Err(InsertInvoicePaymentError::NotThisStoreInvoice)
+        );
+        assert_eq!(
+            service.insert_invoice_payment(&context, payment("p", "return", 1.0)),
+            Err(InsertInvoicePaymentError::CannotPayInvoiceType)
+        );
+        assert_eq!(
+            service.insert_invoice_payment(&context, payment("p", "not_picked", 1.0)),
+            Err(InsertInvoicePaymentError::InvoiceNotFinalised)
+        );
+        assert_eq!(
+            service.insert_invoice_payment(&context, payment("p", "not_verified", 1.0)),
+            Err(InsertInvoicePaymentError::InvoiceNotFinalised)
+        );
+        assert_eq!(
+            service.insert_invoice_payment(&context, payment("p", "old", 100.5)),
+            Err(InsertInvoicePaymentError::AmountExceedsOutstanding(100.0))
+        );
+
+        // Partial payments, receipt numbers increment
+        let first = service
+            .insert_invoice_payment(&context, payment("p1", "old", 60.0))
+            .unwrap();
+        let second = service
+            .insert_invoice_payment(
+                &context,
+                InsertInvoicePayment {
+                    currency_id: Some(currency_b().id),
+                    payment_method: PaymentMethod::Card,
+                    ..payment("p2", "old", 10.0)
+                },
+            )
+            .unwrap();
+        assert_eq!(second.receipt_number, first.receipt_number + 1);
+        assert_eq!(second.home_currency_amount, 10.0 * currency_b().rate);
+        assert_eq!(
+            service.insert_invoice_payment(&context, payment("p1", "old", 1.0)),
+            Err(InsertInvoicePaymentError::PaymentAlreadyExists)
+        );
+
+        let summary = service
+            .get_invoice_payment_summary(&context, "old")
+            .unwrap();
+        assert_eq!(summary.total, 100.0);
+        assert_eq!(summary.outstanding(), 31.0);
+        assert_eq!(summary.payments.len(), 2);
+
+        // Credit notes
+        assert_eq!(
+            service.insert_credit_note(
+                &context,
+                InsertCreditNote {
+                    id: "c".to_string(),
+                    customer_return_id: "old".to_string(),
+                    ..Default::default()
+                }
+            ),
+            Err(InsertCreditNoteError::NotACustomerReturn)
+        );
+        assert_eq!(
+            service.insert_credit_note(
+                &context,
+                InsertCreditNote {
+                    id: "c".to_string(),
+                    customer_return_id: "return".to_string(),
+                    amount: Some(31.0),
+                    ..Default::default()
+                }
+            ),
+            Err(InsertCreditNoteError::AmountExceedsReturnTotal(30.0))
+        );
+        assert_eq!(
+            service.insert_credit_note(
+                &context,
+                InsertCreditNote {
+                    id: "c".to_string(),
+                    customer_return_id: "return".to_string(),
+                    invoice_id: Some("not_picked".to_string()),
+                    ..Default::default()
+                }
+            ),
+            Err(InsertCreditNoteError::InvoiceNotFinalised)
+        );
+        let credit_note = service
+            .insert_credit_note(
+                &context,
+                InsertCreditNote {
+                    id: "c1".to_string(),
+                    customer_return_id: "return".to_string(),
+                    invoice_id: Some("recent".to_string()),
+                    amount: Some(20.0),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(credit_note.payment_method, None);
+        // Remaining 10 of the return total is left on the account
+        let unapplied = service
+            .insert_credit_note(
+                &context,
+                InsertCreditNote {
+                    id: "c2".to_string(),
+                    customer_return_id: "return".to_string(),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(unapplied.amount, 10.0);
+        assert_eq!(unapplied.receipt_number, credit_note.receipt_number + 1);
+
+        // Balance: 140 invoiced, 69 paid, 30 credited. Invoices that can't be paid yet aren't owed
+        let balance = service
+            .get_customer_balance(
+                &context,
+                CustomerBalanceInput {
+                    name_id: mock_patient().id,
+                    as_of: Some(as_of()),
+                },
+            )
+            .unwrap();
+        assert_eq!(balance.total_invoiced, 140.0);
+        assert_eq!(balance.total_paid, 69.0);
+        assert_eq!(balance.total_credited, 30.0);
+        assert_eq!(balance.unapplied_credit, 10.0);
+        assert_eq!(balance.balance, 41.0);
+        assert_eq!(balance.aging.days_31_to_60, 31.0);
+        assert_eq!(balance.aging.current, 20.0);
+        assert_eq!(balance.outstanding_invoices.len(), 2);
+        assert_eq!(balance.outstanding_invoices[0].invoice_row.id, "old");
+
+        // Cancelling a payment puts the amount back on the invoice
+        let cancelled = service
+            .cancel_invoice_payment(
+                &context,
+                CancelInvoicePayment {
+                    id: "p1".to_string(),
+                    reason: Some("Wrong patient".to_string()),
+                },
+            )
+            .unwrap();
+        assert!(cancelled.is_cancelled());
+        assert_eq!(
+            service.cancel_invoice_payment(
+                &context,
+                CancelInvoicePayment {
+                    id: "p1".to_string(),
+                    reason: None,
+                }
+            ),
+            Err(CancelInvoicePaymentError::PaymentAlreadyCancelled)
+        );
+        assert_eq!(
+            service
+                .get_invoice_payment_summary(&context, "old")
+                .unwrap()
+                .outstanding(),
+            91.0
+        );
+
+        // Reconciliation
+        let now = Utc::now().naive_utc();
+        let reconciliation = service
+            .get_cashier_reconciliation(
+                &context,
+                CashierReconciliationInput {
+                    from_datetime: now - Duration::hours(1),
+                    to_datetime: now + Duration::hours(1),
+                    user_id: Some("cashier".to_string()),
+                },
+            )
+            .unwrap();
+        assert_eq!(reconciliation.payments.len(), 4);
+        assert_eq!(reconciliation.totals.len(), 1);
+        assert_eq!(reconciliation.totals[0].payment_method, PaymentMethod::Card);
+        assert_eq!(reconciliation.totals[0].amount, 10.0);
+        assert_eq!(reconciliation.total_home_currency_amount, 9.0);
+        assert_eq!(reconciliation.credit_note_count, 2);
+        assert_eq!(reconciliation.credit_note_total, 30.0);
+        assert_eq!(reconciliation.cancelled_count, 1);
+        assert_eq!(reconciliation.cancelled_home_currency_amount, 60.0);
+
+        let other_cashier = service
+            .get_cashier_reconciliation(
+                &context,
+                CashierReconciliationInput {
+                    from_datetime: now - Duration::hours(1),
+                    to_datetime: now + Duration::hours(1),
+                    user_id: Some("other".to_string()),
+                },
+            )
+            .unwrap();
+        assert!(other_cashier.payments.is_empty());
+    }
+}
@@ -8,14 +8,18 @@ use service_provider::ServiceContext;
 use std::convert::TryInto;
 
 pub mod activity_log;
+pub mod allocation_rule;
+pub mod api_key;
 pub mod apis;
 pub mod app_data;
 
 pub mod asset;
 pub mod auth;
 pub mod auth_data;
+pub mod backorder;
 pub mod barcode;
 pub mod catalogue;
+pub mod clinical_check;
 pub mod clinician;
 pub mod cold_chain;
 pub mod cold_storage_type;
@@ -29,19 +33,24 @@ pub mod document;
 pub mod inventory_adjustment_reason;
 pub mod invoice;
 pub mod invoice_line;
+pub mod invoice_payment;
 pub mod item;
 pub mod item_stats;
 pub mod label_printer_settings_service;
 pub mod ledger;
 pub mod localisations;
 pub mod location;
+pub mod log_context;
 pub mod log_service;
 pub mod login;
+pub mod login_security;
 pub mod master_list;
 pub mod name;
 pub mod name_property;
 pub mod number;
+pub mod oidc;
 pub mod permission;
+pub mod pick_list;
 pub mod plugin;
 pub mod plugin_data;
 pub mod pricing;
@@ -50,6 +59,7 @@ pub mod processors;
 pub mod program;
 pub mod programs;
 pub mod reason_option;
+pub mod recall;
 pub mod repack;
 pub mod report;
 pub mod requisition;
@@ -57,6 +67,7 @@ pub mod requisition_line;
 pub mod return_reason;
 pub mod rnr_form;
 pub mod sensor;
+pub mod serial_number;
 pub mod service_provider;
 pub mod settings;
 pub mod settings_service;
@@ -67,11 +78,13 @@ pub mod stocktake;
 pub mod stocktake_line;
 pub mod store;
 pub mod store_preference;
+pub mod support_bundle;
 pub mod sync;
 pub mod temperature_excursion;
 pub mod token;
 pub mod token_bucket;
 pub mod user_account;
+pub mod user_pin;
 pub mod vaccination;
 pub mod vaccine_course;
 pub mod validate;
@@ -0,0 +1,266 @@
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Level;
+
+/// Header used to pass a correlation id in and out of http requests
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+
+/// Identifies the work (http request or sync run) log lines are produced for
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LogContext {
+    pub correlation_id: String,
+    pub store_id: Option<String>,
+    pub user_id: Option<String>,
+}
+
+tokio::task_local! {
+    static LOG_CONTEXT: Arc<Mutex<LogContext>>;
+}
+
+impl LogContext {
+    pub fn new(correlation_id: Option<String>) -> Self {
+        LogContext {
+            correlation_id: correlation_id.unwrap_or_else(util::uuid::uuid),
+            store_id: None,
+            user_id: None,
+        }
+    }
+
+    /// Run future with this context attached to every log line it produces
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        LOG_CONTEXT.scope(Arc::new(Mutex::new(self)), future).await
+    }
+
+    /// Run closure with this context attached to every log line it produces
+    pub fn sync_scope<R>(self, f: impl FnOnce() -> R) -> R {
+        LOG_CONTEXT.sync_scope(Arc::new(Mutex::new(self)), f)
+    }
+
+    /// The context is task local and isn't inherited by spawned tasks (`tokio::spawn`, other
+    /// executors), wrap the future before spawning it to keep the context of the current task
+    pub fn in_current_scope<F: Future>(future: F) -> impl Future<Output = F::Output> {
+        let context = LOG_CONTEXT.try_with(Arc::clone).ok();
+        async move {
+            match context {
+                Some(context) => LOG_CONTEXT.scope(context, future).await,
+                None => future.await,
+            }
+        }
+    }
+
+    /// Same as `in_current_scope` for closures passed to `spawn_blocking`
+    pub fn in_current_sync_scope<R>(f: impl FnOnce() -> R) -> impl FnOnce() -> R {
+        let context = LOG_CONTEXT.try_with(Arc::clone).ok();
+        move || match context {
+            Some(context) => LOG_CONTEXT.sync_scope(context, f),
+            None => f(),
+        }
+    }
+
+    /// Context of the currently running task, if any
+    pub fn current() -> Option<LogContext> {
+        LOG_CONTEXT
+            .try_with(|context| context.lock().ok().map(|context| context.clone()))
+            .ok()
+            .flatten()
+    }
+
+    /// Record user and store on the context of the currently running task, called once the user
+    /// is authenticated. Does nothing outside of a log context
+    pub fn set_user(user_id: &str, store_id: Option<&str>) {
+        let _ = LOG_CONTEXT.try_with(|context| {
+            if let Ok(mut context) = context.lock() {
+                context.user_id = Some(user_id.to_string());
+                if let Some(store_id) = store_id {
+                    context.store_id = Some(store_id.to_string());
+                }
+            }
+        });
+    }
+}
+
+/// A line of the structured (JSON) log file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructuredLogLine {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub store_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+}
+
+impl StructuredLogLine {
+    pub fn new(level: log::Level, target: &str, message: String) -> Self {
+        let context = LogContext::current().unwrap_or_default();
+        StructuredLogLine {
+            timestamp: Utc::now(),
+            level: level.to_string().to_lowercase(),
+            target: target.to_string(),
+            message,
+            correlation_id: Some(context.correlation_id).filter(|id| !id.is_empty()),
+            store_id: context.store_id,
+            user_id: context.user_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LogLineFilter {
+    /// Include lines of this level and more severe levels
+    pub level: Option<Level>,
+    pub from_datetime: Option<DateTime<Utc>>,
+    pub to_datetime: Option<DateTime<Utc>>,
+    pub correlation_id: Option<String>,
+}
+
+fn severity(level: &str) -> u8 {
+    match level {
+        "error" => 1,
+        "warn" => 2,
+        "info" => 3,
+        "debug" => 4,
+        _ => 5,
+    }
+}
+
+impl LogLineFilter {
+    /// Only structured log lines can be matched, plain text lines never match a filter
+    pub fn matches(&self, line: &str) -> bool {
+        let Ok(line) = serde_json::from_str::<StructuredLogLine>(line) else {
+            return false;
+        };
+
+        if let Some(level) = &self.level {
+            if severity(&line.level) > severity(&level.to_string()) {
+                return false;
+            }
+        }
+        if let Some(from_datetime) = self.from_datetime {
+            if line.timestamp < from_datetime {
+                return false;
+            }
+        }
+        if let Some(to_datetime) = self.to_datetime {
+            if line.timestamp > to_datetime {
+                return false;
+            }
+        }
+        if let Some(correlation_id) = &self.correlation_id {
+            if line.correlation_id.as_ref() != Some(correlation_id) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn log_context_scope() {
+        assert_eq!(LogContext::current(), None);
+
+        let context = LogContext::new(Some("correlation_a".to_string()));
+        let current = context
+            .scope(async {
+                LogContext::set_user("user_a", Some("store_a"));
+                // Store is kept when a later auth check is not store specific
+                LogContext::set_user("user_a", None);
+                LogContext::current()
+            })
+            .await;
+
+        assert_eq!(
+            current,
+            Some(LogContext {
+                correlation_id: "correlation_a".to_string(),
+                store_id: Some("store_a".to_string()),
+                user_id: Some("user_a".to_string()),
+            })
+        );
+    }
+
+    #[actix_rt::test]
+    async fn log_context_spawned_tasks() {
+        let context = LogContext::new(Some("correlation_a".to_string()));
+        let (spawned, blocking) = context
+            .scope(async {
+                let spawned = tokio::spawn(LogContext::in_current_scope(async {
+                    LogContext::set_user("user_a", Some("store_a"));
+                    LogContext::current()
+                }));
+                let blocking =
+                    tokio::task::spawn_blocking(LogContext::in_current_sync_scope(|| {
+                        LogContext::current()
+                    }));
+                (spawned.await.unwrap(), blocking.await.unwrap())
+            })
+            .await;
+
+        assert_eq!(
+            spawned,
+            Some(LogContext {
+                correlation_id: "correlation_a".to_string(),
+                store_id: Some("store_a".to_string()),
+                user_id: Some("user_a".to_string()),
+            })
+        );
+        assert_eq!(
+            blocking.map(|context| context.correlation_id),
+            Some("correlation_a".to_string())
+        );
+        // Not propagated unless wrapped
+        assert_eq!(
+            tokio::spawn(async { LogContext::current() }).await.unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn log_line_filter() {
+        let line = |level: &str, hour: u32, correlation_id: &str| {
+            serde_json::to_string(&StructuredLogLine {
+                timestamp: Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap(),
+                level: level.to_string(),
+                target: "service".to_string(),
+                message: "message".to_string(),
+                correlation_id: Some(correlation_id.to_string()),
+                store_id: None,
+                user_id: None,
+            })
+            .unwrap()
+        };
+
+        let filter = LogLineFilter {
+            level: Some(Level::Warn),
+            from_datetime: Some(Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap()),
+            to_datetime: Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()),
+            correlation_id: Some("a".to_string()),
+        };
+
+        assert!(filter.matches(&line("error", 10, "a")));
+        assert!(filter.matches(&line("warn", 12, "a")));
+        assert!(!filter.matches(&line("info", 11, "a")));
+        assert!(!filter.matches(&line("error", 9, "a")));
+        assert!(!filter.matches(&line("error", 13, "a")));
+        assert!(!filter.matches(&line("error", 11, "b")));
+        assert!(!filter.matches("2024-01-01 11:00:00 [ERROR] plain text"));
+        assert!(LogLineFilter::default().matches(&line("trace", 0, "c")));
+    }
+}
@@ -7,7 +7,7 @@ use std::{
     path::Path,
 };
 
-use crate::{service_provider::ServiceContext, settings::Level};
+use crate::{log_context::LogLineFilter, service_provider::ServiceContext, settings::Level};
 
 pub trait LogServiceTrait: Send + Sync {
     fn get_log_file_names(&self, ctx: &ServiceContext) -> Result<Vec<String>, Error> {
@@ -27,6 +27,7 @@ pub trait LogServiceTrait: Send + Sync {
         &self,
         ctx: &ServiceContext,
         file_name: Option<String>,
+        filter: Option<LogLineFilter>,
     ) -> Result<(String, Vec<String>), Error> {
         let log_dir = self.get_log_directory(ctx)?;
         let log_dir_path = Path::new(&log_dir);
@@ -50,6 +51,7 @@ pub trait LogServiceTrait: Send + Sync {
 
         let log_file_content = log_file_content
             .split('\n')
+            .filter(|line| filter.as_ref().is_none_or(|filter| filter.matches(line)))
             .map(|s| s.to_string())
             .collect::<Vec<String>>();
 
@@ -83,6 +85,7 @@ pub trait LogServiceTrait: Send + Sync {
     ) -> Result<(), RepositoryError> {
         let key_value_store = KeyValueStoreRepository::new(&ctx.connection);
 
+        log::set_max_level(log_level.to_level_filter());
         let log_level = match log_level {
             Level::Error => "error",
             Level::Warn => "warn",
@@ -92,7 +95,10 @@ pub trait LogServiceTrait: Send + Sync {
         };
 
         key_value_store.set_string(KeyType::LogLevel, Some(log_level.to_string()))?;
-        simple_log::update_log_level(log_level).expect("Couldn't update log level");
+        // Structured (JSON) logging doesn't use simple_log, it only relies on the max level above
+        if let Err(error) = simple_log::update_log_level(log_level) {
+            log::debug!("simple_log level not updated: {}", error);
+        }
 
         Ok(())
     }
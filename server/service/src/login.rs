@@ -7,8 +7,9 @@ use bcrypt::BcryptError;
 use chrono::Utc;
 use log::info;
 use repository::{
-    ActivityLogType, LanguageType, PermissionType, RepositoryError, UserAccountRow,
-    UserPermissionRow, UserStoreJoinRow,
+    ActivityLogType, EqualFilter, LanguageType, PermissionType, RepositoryError, StorageConnection,
+    User, UserAccountRow, UserAccountRowRepository, UserPermissionFilter, UserPermissionRepository,
+    UserPermissionRow, UserPermissionRowRepository, UserStoreJoinRow,
 };
 use reqwest::{ClientBuilder, Url};
 use serde::{Deserialize, Serialize};
@@ -23,10 +24,16 @@ use crate::{
         permissions::{map_api_permissions, Permissions},
     },
     auth_data::AuthData,
+    login_security::{
+        get_login_security_settings, login_lockout_remaining, record_failed_login,
+        reset_failed_logins,
+    },
+    oidc::OidcIdentity,
     service_provider::{ServiceContext, ServiceProvider},
     settings::is_develop,
     token::{JWTIssuingError, TokenPair, TokenService},
     user_account::{StorePermissions, UserAccountService, VerifyPasswordError},
+    user_pin::{verify_user_pin, VerifyUserPinError},
 };
 
 const CONNECTION_TIMEOUT_SEC: u64 = 10;
@@ -55,6 +62,9 @@ pub enum LoginFailure {
     AccountBlocked(u64),
     /// User account does not have login rights to any stores on this site
     NoSiteAccess,
+    /// No valid PIN for the user on this device or the user hasn't logged in with their password
+    /// since the server started, a full login is required
+    PinNotAvailable,
 }
 
 #[derive(Debug)]
@@ -74,6 +84,18 @@ pub struct LoginInput {
     pub password: String,
     /// Central server url needed to fetch user details during login
     pub central_server_url: String,
+    /// Address of the client, failed login attempts are limited per username and per client ip
+    #[serde(default)]
+    pub client_ip: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PinLoginInput {
+    pub username: String,
+    /// Token the server issued to the device when the PIN was set
+    pub device_token: String,
+    pub pin: String,
+    pub client_ip: Option<String>,
 }
 
 impl LoginService {
@@ -96,18 +118,36 @@ impl LoginService {
         min_err_response_time_sec: u64,
     ) -> Result<TokenPair, LoginError> {
         let now = SystemTime::now();
-        match LoginService::do_login(service_provider, auth_data, input).await {
-            Ok(result) => Ok(result),
-            Err(err) => {
-                let elapsed = now.elapsed().unwrap_or(Duration::from_secs(0));
-                let minimum = Duration::from_secs(min_err_response_time_sec);
-                if elapsed < minimum {
-                    tokio::time::sleep(minimum - elapsed).await;
-                }
+        let result = LoginService::do_login(service_provider, auth_data, input).await;
+        LoginService::delay_error(result, now, min_err_response_time_sec).await
+    }
+
+    /// Switches to the user that set the PIN on the device, without contacting central.
+    /// Same as for `login`, errors are only returned after `min_err_response_time_sec`.
+    pub async fn pin_login(
+        service_provider: &ServiceProvider,
+        auth_data: &AuthData,
+        input: PinLoginInput,
+        min_err_response_time_sec: u64,
+    ) -> Result<TokenPair, LoginError> {
+        let now = SystemTime::now();
+        let result = LoginService::do_pin_login(service_provider, auth_data, input);
+        LoginService::delay_error(result, now, min_err_response_time_sec).await
+    }
 
-                Err(err)
+    async fn delay_error(
+        result: Result<TokenPair, LoginError>,
+        start: SystemTime,
+        min_err_response_time_sec: u64,
+    ) -> Result<TokenPair, LoginError> {
+        if result.is_err() {
+            let elapsed = start.elapsed().unwrap_or(Duration::from_secs(0));
+            let minimum = Duration::from_secs(min_err_response_time_sec);
+            if elapsed < minimum {
+                tokio::time::sleep(minimum - elapsed).await;
             }
         }
+        result
     }
 
     async fn do_login(
@@ -117,6 +157,19 @@ impl LoginService {
     ) -> Result<TokenPair, LoginError> {
         let mut username = input.username.clone();
         let mut connection_failure = false;
+        {
+            let service_ctx = service_provider.basic_context()?;
+            if let Some(timeout_remaining) = login_lockout_remaining(
+                &service_ctx.connection,
+                &input.username,
+                input.client_ip.as_deref(),
+                Utc::now().naive_utc(),
+            )? {
+                return Err(LoginError::LoginFailure(LoginFailure::AccountBlocked(
+                    timeout_remaining,
+                )));
+            }
+        }
         match LoginService::fetch_user_from_central(&input).await {
             Ok(user_info) => {
                 let service_ctx =
@@ -127,7 +180,11 @@ impl LoginService {
             }
             Err(err) => match err {
                 FetchUserError::Unauthenticated => {
-                    return Err(LoginError::LoginFailure(LoginFailure::InvalidCredentials))
+                    return Err(LoginService::failed_login(
+                        service_provider,
+                        &input.username,
+                        input.client_ip.as_deref(),
+                    ))
                 }
                 FetchUserError::AccountBlocked(timeout_remaining) => {
                     return Err(LoginError::LoginFailure(LoginFailure::AccountBlocked(
@@ -147,12 +204,16 @@ impl LoginService {
             Ok(user) => user,
             Err(err) => {
                 return Err(match err {
-                    VerifyPasswordError::UsernameDoesNotExist => {
-                        LoginError::LoginFailure(LoginFailure::InvalidCredentials)
-                    }
-                    VerifyPasswordError::InvalidCredentials => {
-                        LoginError::LoginFailure(LoginFailure::InvalidCredentials)
-                    }
+                    VerifyPasswordError::UsernameDoesNotExist => LoginService::failed_login(
+                        service_provider,
+                        &input.username,
+                        input.client_ip.as_deref(),
+                    ),
+                    VerifyPasswordError::InvalidCredentials => LoginService::failed_login(
+                        service_provider,
+                        &input.username,
+                        input.client_ip.as_deref(),
+                    ),
                     VerifyPasswordError::InvalidCredentialsBackend(_) => {
                         LoginError::InternalError("Failed to read credentials".to_string())
                     }
@@ -177,6 +238,119 @@ impl LoginService {
 
         service_ctx.user_id.clone_from(&user_account.id);
 
+        reset_failed_logins(&service_ctx.connection, &input.username)?;
+        activity_log_entry(
+            &service_ctx,
+            ActivityLogType::UserLoggedIn,
+            None,
+            None,
+            None,
+        )?;
+
+        LoginService::issue_token_pair(auth_data, &user_account.id, &input.password)
+    }
+
+    fn do_pin_login(
+        service_provider: &ServiceProvider,
+        auth_data: &AuthData,
+        input: PinLoginInput,
+    ) -> Result<TokenPair, LoginError> {
+        let mut service_ctx = service_provider.basic_context()?;
+        let now = Utc::now().naive_utc();
+        if let Some(timeout_remaining) = login_lockout_remaining(
+            &service_ctx.connection,
+            &input.username,
+            input.client_ip.as_deref(),
+            now,
+        )? {
+            return Err(LoginError::LoginFailure(LoginFailure::AccountBlocked(
+                timeout_remaining,
+            )));
+        }
+
+        let settings = get_login_security_settings(&service_ctx.connection)?;
+        let user_account = match verify_user_pin(
+            &service_ctx.connection,
+            &settings,
+            &input.username,
+            &input.device_token,
+            &input.pin,
+            now,
+        ) {
+            Ok(user) => user,
+            Err(err) => {
+                return Err(match err {
+                    VerifyUserPinError::InvalidCredentials => LoginService::failed_login(
+                        service_provider,
+                        &input.username,
+                        input.client_ip.as_deref(),
+                    ),
+                    VerifyUserPinError::PinNotAvailable => {
+                        LoginError::LoginFailure(LoginFailure::PinNotAvailable)
+                    }
+                    VerifyUserPinError::PinHashError(_) => {
+                        LoginError::InternalError("Failed to read PIN".to_string())
+                    }
+                    VerifyUserPinError::DatabaseError(e) => LoginError::DatabaseError(e),
+                })
+            }
+        };
+
+        let user_service = UserAccountService::new(&service_ctx.connection);
+        match user_service.find_user_active_on_this_site(&user_account.id) {
+            Ok(Some(_)) => (),
+            Ok(None) => return Err(LoginError::LoginFailure(LoginFailure::NoSiteAccess)),
+            Err(err) => return Err(err.into()),
+        };
+
+        // The password isn't known when switching with a PIN, keep the one of the last full login.
+        // Without it (e.g. after a restart) the user can't be refreshed from central, so a full
+        // login is required
+        let password = LoginService::known_password(auth_data, &user_account.id)?
+            .ok_or(LoginError::LoginFailure(LoginFailure::PinNotAvailable))?;
+
+        service_ctx.user_id.clone_from(&user_account.id);
+
+        reset_failed_logins(&service_ctx.connection, &input.username)?;
+        activity_log_entry(
+            &service_ctx,
+            ActivityLogType::UserLoggedIn,
+            None,
+            None,
+            None,
+        )?;
+
+        LoginService::issue_token_pair(auth_data, &user_account.id, &password)
+    }
+
+    /// Signs in a user authenticated by the single sign-on identity provider. The user account
+    /// and its stores need to be on this site already, e.g. synced from central. Permissions
+    /// mapped from the identity provider groups replace the user's permissions in those stores.
+    pub fn sso_login(
+        service_provider: &ServiceProvider,
+        auth_data: &AuthData,
+        identity: &OidcIdentity,
+    ) -> Result<TokenPair, LoginError> {
+        let username = identity.username.as_str();
+        let mut service_ctx = service_provider.basic_context()?;
+        let user_account = UserAccountRowRepository::new(&service_ctx.connection)
+            .find_one_by_user_name(username)?
+            .ok_or(LoginError::LoginFailure(LoginFailure::InvalidCredentials))?;
+
+        let user_service = UserAccountService::new(&service_ctx.connection);
+        let user = match user_service.find_user_active_on_this_site(&user_account.id) {
+            Ok(Some(user)) => user,
+            Ok(None) => return Err(LoginError::LoginFailure(LoginFailure::NoSiteAccess)),
+            Err(err) => return Err(err.into()),
+        };
+
+        service_ctx.user_id.clone_from(&user_account.id);
+
+        if let Some(permissions) = &identity.store_permissions {
+            set_store_permissions(&service_ctx.connection, &user, permissions)?;
+        }
+
+        reset_failed_logins(&service_ctx.connection, username)?;
         activity_log_entry(
             &service_ctx,
             ActivityLogType::UserLoggedIn,
@@ -185,6 +359,30 @@ impl LoginService {
             None,
         )?;
 
+        // SSO users don't sign in with a password. Keep the one of the last full login if there
+        // was one, otherwise the session has no password: its permissions come from the identity
+        // provider and it can't be refreshed from central (`SyncUser::update_user` returns
+        // `MissingCredentials`)
+        let password =
+            LoginService::known_password(auth_data, &user_account.id)?.unwrap_or_default();
+        LoginService::issue_token_pair(auth_data, &user_account.id, &password)
+    }
+
+    /// Password of the last full login of the user, if the server has been running since
+    fn known_password(auth_data: &AuthData, user_id: &str) -> Result<Option<String>, LoginError> {
+        let password = auth_data
+            .token_bucket
+            .read()
+            .map_err(|_| LoginError::InternalError("Concurrent error".to_string()))?
+            .get_password(user_id);
+        Ok(Some(password).filter(|password| !password.is_empty()))
+    }
+
+    fn issue_token_pair(
+        auth_data: &AuthData,
+        user_id: &str,
+        password: &str,
+    ) -> Result<TokenPair, LoginError> {
         let mut token_service = TokenService::new(
             &auth_data.token_bucket,
             auth_data.auth_token_secret.as_bytes(),
@@ -193,18 +391,46 @@ impl LoginService {
         let max_age_token = chrono::Duration::minutes(60).num_seconds() as usize;
         let max_age_refresh = chrono::Duration::hours(6).num_seconds() as usize;
 
-        let pair = match token_service.jwt_token(
-            &user_account.id,
-            &input.password,
-            max_age_token,
-            max_age_refresh,
-        ) {
+        let pair = match token_service.jwt_token(user_id, password, max_age_token, max_age_refresh)
+        {
             Ok(pair) => pair,
             Err(err) => return Err(LoginError::FailedToGenerateToken(err)),
         };
         Ok(pair)
     }
 
+    /// Records the failed attempt and returns the error to report to the user
+    fn failed_login(
+        service_provider: &ServiceProvider,
+        username: &str,
+        client_ip: Option<&str>,
+    ) -> LoginError {
+        let result = (|| -> Result<Option<u64>, RepositoryError> {
+            let mut service_ctx = service_provider.basic_context()?;
+            if let Some(user) = UserAccountRowRepository::new(&service_ctx.connection)
+                .find_one_by_user_name(username)?
+            {
+                service_ctx.user_id = user.id;
+            }
+            let settings = get_login_security_settings(&service_ctx.connection)?;
+            record_failed_login(
+                &service_ctx,
+                &settings,
+                username,
+                client_ip,
+                Utc::now().naive_utc(),
+            )
+        })();
+
+        match result {
+            Ok(Some(timeout_remaining)) => {
+                LoginError::LoginFailure(LoginFailure::AccountBlocked(timeout_remaining))
+            }
+            Ok(None) => LoginError::LoginFailure(LoginFailure::InvalidCredentials),
+            Err(err) => LoginError::DatabaseError(err),
+        }
+    }
+
     pub async fn fetch_user_from_central(
         input: &LoginInput,
     ) -> Result<LoginUserInfoV4, FetchUserError> {
@@ -347,6 +573,42 @@ impl LoginService {
     }
 }
 
+/// Replaces the user's permissions in each of their stores on this site, context permissions
+/// (programs) are kept
+fn set_store_permissions(
+    connection: &StorageConnection,
+    user: &User,
+    permissions: &HashSet<PermissionType>,
+) -> Result<(), RepositoryError> {
+    connection
+        .transaction_sync(|connection| {
+            let permission_repo = UserPermissionRowRepository::new(connection);
+            for store in &user.stores {
+                let store_id = &store.store_row.id;
+                let existing = UserPermissionRepository::new(connection).query_by_filter(
+                    UserPermissionFilter::new()
+                        .user_id(EqualFilter::equal_to(&user.user_row.id))
+                        .store_id(EqualFilter::equal_to(store_id))
+                        .has_context(false),
+                )?;
+                for permission in existing {
+                    permission_repo.delete(&permission.id)?;
+                }
+                for permission in permissions {
+                    permission_repo.upsert_one(&UserPermissionRow {
+                        id: uuid(),
+                        user_id: user.user_row.id.clone(),
+                        store_id: Some(store_id.clone()),
+                        permission: permission.clone(),
+                        context_id: None,
+                    })?;
+                }
+            }
+            Ok(())
+        })
+        .map_err(RepositoryError::from)
+}
+
 impl From<RepositoryError> for LoginError {
     fn from(err: RepositoryError) -> Self {
         LoginError::InternalError(format!("{:?}", err))
@@ -360,6 +622,7 @@ fn permissions_to_domain(permissions: Vec<Permissions>) -> HashSet<PermissionTyp
             // admin
             Permissions::AccessServerAdministration => {
                 output.insert(PermissionType::ServerAdmin);
+                output.insert(PermissionType::ApiKeyMutate);
             }
             // location
             Permissions::ManageLocations => {
@@ -404,6 +667,12 @@ fn permissions_to_domain(permissions: Vec<Permissions>) -> HashSet<PermissionTyp
             Permissions::EnterInventoryAdjustments => {
                 output.insert(PermissionType::InventoryAdjustmentMutate);
             }
+            // Legacy mSupply has no permission to approve stocktakes. Approving a blind stocktake
+            // signs off the inventory adjustments it is finalised into, so users allowed to
+            // finalise inventory adjustments can approve blind stocktake counts
+            Permissions::FinaliseInventoryAdjustments => {
+                output.insert(PermissionType::StocktakeApprove);
+            }
             // customer invoices
             Permissions::ViewCustomerInvoices => {
                 output.insert(PermissionType::OutboundShipmentQuery);
@@ -503,11 +772,14 @@ fn permissions_to_domain(permissions: Vec<Permissions>) -> HashSet<PermissionTyp
 mod test {
     use std::sync::{Arc, RwLock};
 
+    use chrono::{Duration, Utc};
     use httpmock::{Method::POST, MockServer};
     use repository::{
+        activity_log::{ActivityLogFilter, ActivityLogRepository},
         mock::{mock_store_a, mock_user_empty_hashed_password, MockDataInserts},
         test_db::setup_all,
-        EqualFilter, KeyType, KeyValueStoreRepository, UserFilter, UserPermissionFilter,
+        ActivityLogType, EqualFilter, KeyType, KeyValueStoreRepository, LoginLockoutIdentifierType,
+        LoginLockoutRow, LoginLockoutRowRepository, UserFilter, UserPermissionFilter,
         UserPermissionRepository, UserRepository,
     };
     use util::{assert_matches, assert_variant};
@@ -567,6 +839,7 @@ mod test {
                     username: "Gryffindor".to_string(),
                     password: "password".to_string(),
                     central_server_url,
+                    client_ip: None,
                 },
                 0,
             )
@@ -608,6 +881,7 @@ mod test {
                     username: "Gryffindor".to_string(),
                     password: "password2".to_string(),
                     central_server_url,
+                    client_ip: None,
                 },
                 0,
             )
@@ -635,6 +909,7 @@ mod test {
                     username: "Gryffindor".to_string(),
                     password: "password".to_string(),
                     central_server_url,
+                    client_ip: None,
                 },
                 0,
             )
@@ -659,6 +934,7 @@ mod test {
                     username: mock_user_empty_hashed_password().username,
                     password: "password".to_string(),
                     central_server_url,
+                    client_ip: None,
                 },
                 0,
             )
@@ -689,6 +965,7 @@ mod test {
                     username: mock_user_empty_hashed_password().username,
                     password: "password".to_string(),
                     central_server_url,
+                    client_ip: None,
                 },
                 0,
             )
@@ -717,6 +994,7 @@ mod test {
                     username: "Gryffindor".to_string(),
                     password: "password2".to_string(),
                     central_server_url,
+                    client_ip: None,
                 },
                 0,
             )
@@ -749,6 +1027,7 @@ mod test {
                     username: "Gryffindor".to_string(),
                     password: "password".to_string(),
                     central_server_url,
+                    client_ip: None,
                 },
                 0,
             )
@@ -789,4 +1068,121 @@ mod test {
         //     );
         // }
     }
+
+    #[actix_rt::test]
+    async fn offline_login_lockout_test() {
+        let (_, _, connection_manager, _) = setup_all(
+            "offline_login_lockout_test",
+            MockDataInserts::none().names().stores().user_accounts(),
+        )
+        .await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.basic_context().unwrap();
+        let auth_data = AuthData {
+            auth_token_secret: "secret".to_string(),
+            token_bucket: Arc::new(RwLock::new(TokenBucket::new())),
+            no_ssl: true,
+            debug_no_access_control: false,
+        };
+        KeyValueStoreRepository::new(&context.connection)
+            .set_i32(KeyType::SettingsSyncSiteId, Some(mock_store_a().site_id))
+            .unwrap();
+
+        let online_server = MockServer::start();
+        online_server.mock(|when, then| {
+            when.method(POST).path("/api/v4/login".to_string());
+            then.status(200).body(LOGIN_V4_RESPONSE_1);
+        });
+        let offline_server = MockServer::start();
+        offline_server.mock(|when, then| {
+            when.method(POST).path("/api/v4/login".to_string());
+            then.status(500);
+        });
+        let login = |password: &str, central_server_url: String| {
+            LoginService::login(
+                &service_provider,
+                &auth_data,
+                LoginInput {
+                    username: "Gryffindor".to_string(),
+                    password: password.to_string(),
+                    central_server_url,
+                    client_ip: Some("10.0.0.1".to_string()),
+                },
+                0,
+            )
+        };
+
+        // Cache the user credentials on this site
+        login("password", online_server.base_url()).await.unwrap();
+
+        // Default settings lock the username after 5 failed attempts
+        for _ in 0..4 {
+            assert_matches!(
+                login("wrong", offline_server.base_url()).await,
+                Err(LoginError::LoginFailure(LoginFailure::InvalidCredentials))
+            );
+        }
+        assert_matches!(
+            login("wrong", offline_server.base_url()).await,
+            Err(LoginError::LoginFailure(LoginFailure::AccountBlocked(60)))
+        );
+        // Correct password is rejected while locked, without asking central
+        assert_matches!(
+            login("password", online_server.base_url()).await,
+            Err(LoginError::LoginFailure(LoginFailure::AccountBlocked(_)))
+        );
+
+        let log_types: Vec<ActivityLogType> = ActivityLogRepository::new(&context.connection)
+            .query_by_filter(ActivityLogFilter::new())
+            .unwrap()
+            .into_iter()
+            .map(|log| log.activity_log_row.r#type)
+            .collect();
+        assert_eq!(
+            log_types
+                .iter()
+                .filter(|t| **t == ActivityLogType::UserLoginFailed)
+                .count(),
+            5
+        );
+        assert!(log_types.contains(&ActivityLogType::UserAccountLocked));
+
+        // Once the lockout has expired the next failure locks the username for twice as long
+        let lockout_repo = LoginLockoutRowRepository::new(&context.connection);
+        let expire_lockout = || {
+            let row = lockout_repo
+                .find_one_by_identifier(LoginLockoutIdentifierType::Username, "gryffindor")
+                .unwrap()
+                .unwrap();
+            lockout_repo
+                .upsert_one(&LoginLockoutRow {
+                    locked_until_datetime: Some(Utc::now().naive_utc() - Duration::seconds(1)),
+                    ..row
+                })
+                .unwrap();
+        };
+        expire_lockout();
+        assert_matches!(
+            login("wrong", offline_server.base_url()).await,
+            Err(LoginError::LoginFailure(LoginFailure::AccountBlocked(120)))
+        );
+
+        // A successful login clears the username failures but not the client ip failures
+        expire_lockout();
+        login("password", offline_server.base_url()).await.unwrap();
+        assert_eq!(
+            lockout_repo
+                .find_one_by_identifier(LoginLockoutIdentifierType::Username, "gryffindor")
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            lockout_repo
+                .find_one_by_identifier(LoginLockoutIdentifierType::IpAddress, "10.0.0.1")
+                .unwrap()
+                .unwrap()
+                .failed_attempts,
+            6
+        );
+    }
 }
@@ -0,0 +1,159 @@
+use chrono::{Duration, NaiveDateTime};
+use repository::{
+    ActivityLogType, LoginLockoutIdentifierType, LoginLockoutRow, LoginLockoutRowRepository,
+    RepositoryError, StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::{activity_log::activity_log_entry, service_provider::ServiceContext};
+
+use super::LoginSecuritySettings;
+
+fn identifiers(
+    username: &str,
+    client_ip: Option<&str>,
+) -> Vec<(LoginLockoutIdentifierType, String)> {
+    let mut identifiers = vec![(
+        LoginLockoutIdentifierType::Username,
+        username.to_lowercase(),
+    )];
+    if let Some(client_ip) = client_ip {
+        identifiers.push((LoginLockoutIdentifierType::IpAddress, client_ip.to_string()));
+    }
+    identifiers
+}
+
+/// Returns the remaining lockout in seconds if either the username or the client ip is locked
+pub fn login_lockout_remaining(
+    connection: &StorageConnection,
+    username: &str,
+    client_ip: Option<&str>,
+    now: NaiveDateTime,
+) -> Result<Option<u64>, RepositoryError> {
+    let repo = LoginLockoutRowRepository::new(connection);
+    let mut remaining = None;
+    for (identifier_type, identifier) in identifiers(username, client_ip) {
+        let locked_until = repo
+            .find_one_by_identifier(identifier_type, &identifier)?
+            .and_then(|row| row.locked_until_datetime);
+        if let Some(locked_until) = locked_until {
+            if locked_until > now {
+                let seconds = (locked_until - now).num_seconds().max(1) as u64;
+                remaining = Some(remaining.unwrap_or(0).max(seconds));
+            }
+        }
+    }
+
+    Ok(remaining)
+}
+
+/// Duration of the lockout after the given number of consecutive failed attempts, doubles for
+/// every attempt past the maximum
+pub fn lockout_seconds(failed_attempts: u32, max_failed_attempts: u32, base: u64, max: u64) -> u64 {
+    if failed_attempts < max_failed_attempts {
+        return 0;
+    }
+    let exponent = (failed_attempts - max_failed_attempts).min(32);
+    base.saturating_mul(1u64 << exponent).min(max)
+}
+
+/// Counts a failed login attempt against the username and the client ip and locks them once the
+/// configured maximum is reached.
+/// The ctx user should be the user the username belongs to, if it's known on this site.
+/// Returns the lockout in seconds if the attempt got the username or client ip locked.
+pub fn record_failed_login(
+    ctx: &ServiceContext,
+    settings: &LoginSecuritySettings,
+    username: &str,
+    client_ip: Option<&str>,
+    now: NaiveDateTime,
+) -> Result<Option<u64>, RepositoryError> {
+    let repo = LoginLockoutRowRepository::new(&ctx.connection);
+    let window = Duration::seconds(settings.failed_attempts_window_seconds as i64);
+
+    activity_log_entry(
+        ctx,
+        ActivityLogType::UserLoginFailed,
+        None,
+        None,
+        Some(describe(username, client_ip)),
+    )?;
+
+    let mut locked = None;
+    for (identifier_type, identifier) in identifiers(username, client_ip) {
+        let max_failed_attempts = match identifier_type {
+            LoginLockoutIdentifierType::Username => settings.max_failed_attempts_per_username,
+            LoginLockoutIdentifierType::IpAddress => settings.max_failed_attempts_per_ip,
+        };
+
+        let mut row = match repo.find_one_by_identifier(identifier_type.clone(), &identifier)? {
+            Some(row) if now - row.last_failed_datetime <= window => row,
+            Some(row) => LoginLockoutRow {
+                failed_attempts: 0,
+                locked_until_datetime: None,
+                ..row
+            },
+            None => LoginLockoutRow {
+                id: uuid(),
+                identifier_type: identifier_type.clone(),
+                identifier: identifier.clone(),
+                failed_attempts: 0,
+                last_failed_datetime: now,
+                locked_until_datetime: None,
+            },
+        };
+        row.failed_attempts += 1;
+        row.last_failed_datetime = now;
+
+        let seconds = lockout_seconds(
+            row.failed_attempts as u32,
+            max_failed_attempts,
+            settings.lockout_base_seconds,
+            settings.lockout_max_seconds,
+        );
+        if seconds > 0 {
+            let locked_until = now + Duration::seconds(seconds as i64);
+            row.locked_until_datetime = Some(locked_until);
+            locked = Some(locked.unwrap_or(0).max(seconds));
+
+            let locked_identifier = match identifier_type {
+                LoginLockoutIdentifierType::Username => describe(username, None),
+                LoginLockoutIdentifierType::IpAddress => format!("ip {}", identifier),
+            };
+            activity_log_entry(
+                ctx,
+                ActivityLogType::UserAccountLocked,
+                None,
+                Some(locked_identifier),
+                Some(locked_until.to_string()),
+            )?;
+        }
+
+        repo.upsert_one(&row)?;
+    }
+
+    Ok(locked)
+}
+
+/// Clears the failed attempts of a username after a successful login. Failed attempts of the
+/// client ip are kept so that one known password can't be used to reset them.
+pub fn reset_failed_logins(
+    connection: &StorageConnection,
+    username: &str,
+) -> Result<(), RepositoryError> {
+    let repo = LoginLockoutRowRepository::new(connection);
+    if let Some(row) = repo.find_one_by_identifier(
+        LoginLockoutIdentifierType::Username,
+        &username.to_lowercase(),
+    )? {
+        repo.delete(&row.id)?;
+    }
+    Ok(())
+}
+
+fn describe(username: &str, client_ip: Option<&str>) -> String {
+    match client_ip {
+        Some(client_ip) => format!("user {} from {}", username, client_ip),
+        None => format!("user {}", username),
+    }
+}
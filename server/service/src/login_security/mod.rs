@@ -0,0 +1,242 @@
+use repository::{KeyType, KeyValueStoreRepository, RepositoryError, StorageConnection};
+use serde::{Deserialize, Serialize};
+
+use crate::service_provider::ServiceContext;
+
+mod lockout;
+mod password_policy;
+
+pub use self::lockout::*;
+pub use self::password_policy::*;
+
+/// Local login security settings, applied when logging in against the locally cached user
+/// credentials and when creating or updating user passwords on this site
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoginSecuritySettings {
+    /// Number of consecutive failed attempts for a username before it is locked
+    pub max_failed_attempts_per_username: u32,
+    /// Number of consecutive failed attempts from a client ip address before it is locked
+    pub max_failed_attempts_per_ip: u32,
+    /// Duration of the first lockout, doubled for every further failed attempt
+    pub lockout_base_seconds: u64,
+    pub lockout_max_seconds: u64,
+    /// Failed attempts older than this are forgotten
+    pub failed_attempts_window_seconds: u64,
+    pub password_policy: PasswordPolicy,
+    /// How long a user PIN can be used for quick user switching before a full login is required
+    pub pin_validity_seconds: u64,
+    /// Number of wrong PIN entries after which the PIN is removed
+    pub max_failed_pin_attempts: u32,
+}
+
+impl Default for LoginSecuritySettings {
+    fn default() -> Self {
+        LoginSecuritySettings {
+            max_failed_attempts_per_username: 5,
+            max_failed_attempts_per_ip: 20,
+            lockout_base_seconds: 60,
+            lockout_max_seconds: 60 * 60,
+            failed_attempts_window_seconds: 24 * 60 * 60,
+            password_policy: PasswordPolicy::default(),
+            pin_validity_seconds: 12 * 60 * 60,
+            max_failed_pin_attempts: 3,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpdateLoginSecuritySettingsError {
+    MaxFailedAttemptsMustBePositive,
+    LockoutBaseSecondsMustBePositive,
+    LockoutMaxSecondsLessThanBaseSeconds,
+    PasswordMinLengthMustBePositive,
+    PinValiditySecondsMustBePositive,
+    MaxFailedPinAttemptsMustBePositive,
+    DatabaseError(RepositoryError),
+}
+
+/// Loads the login security settings, falls back to the defaults if none are stored
+pub fn get_login_security_settings(
+    connection: &StorageConnection,
+) -> Result<LoginSecuritySettings, RepositoryError> {
+    let settings = KeyValueStoreRepository::new(connection)
+        .get_string(KeyType::SettingsLoginSecurity)?
+        .and_then(|value| serde_json::from_str::<LoginSecuritySettings>(&value).ok())
+        .unwrap_or_default();
+
+    Ok(settings)
+}
+
+pub trait LoginSecurityServiceTrait: Sync + Send {
+    fn get_login_security_settings(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<LoginSecuritySettings, RepositoryError> {
+        get_login_security_settings(&ctx.connection)
+    }
+
+    fn update_login_security_settings(
+        &self,
+        ctx: &ServiceContext,
+        settings: LoginSecuritySettings,
+    ) -> Result<LoginSecuritySettings, UpdateLoginSecuritySettingsError> {
+        update_login_security_settings(ctx, settings)
+    }
+}
+
+pub struct LoginSecurityService {}
+impl LoginSecurityServiceTrait for LoginSecurityService {}
+
+fn update_login_security_settings(
+    ctx: &ServiceContext,
+    settings: LoginSecuritySettings,
+) -> Result<LoginSecuritySettings, UpdateLoginSecuritySettingsError> {
+    use UpdateLoginSecuritySettingsError::*;
+
+    if settings.max_failed_attempts_per_username == 0 || settings.max_failed_attempts_per_ip == 0 {
+        return Err(MaxFailedAttemptsMustBePositive);
+    }
+    if settings.lockout_base_seconds == 0 {
+        return Err(LockoutBaseSecondsMustBePositive);
+    }
+    if settings.lockout_max_seconds < settings.lockout_base_seconds {
+        return Err(LockoutMaxSecondsLessThanBaseSeconds);
+    }
+    if settings.password_policy.min_length == 0 {
+        return Err(PasswordMinLengthMustBePositive);
+    }
+    if settings.pin_validity_seconds == 0 {
+        return Err(PinValiditySecondsMustBePositive);
+    }
+    if settings.max_failed_pin_attempts == 0 {
+        return Err(MaxFailedPinAttemptsMustBePositive);
+    }
+
+    // Serialising a struct of plain values can't fail
+    let serialised = serde_json::to_string(&settings).unwrap_or_default();
+    KeyValueStoreRepository::new(&ctx.connection)
+        .set_string(KeyType::SettingsLoginSecurity, Some(serialised))?;
+
+    Ok(settings)
+}
+
+impl From<RepositoryError> for UpdateLoginSecuritySettingsError {
+    fn from(error: RepositoryError) -> Self {
+        UpdateLoginSecuritySettingsError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{mock::MockDataInserts, test_db::setup_all};
+    use util::{assert_matches, assert_variant};
+
+    use crate::{
+        login_security::{
+            lockout_seconds, LoginSecuritySettings, PasswordPolicy, PasswordPolicyViolation,
+            UpdateLoginSecuritySettingsError,
+        },
+        service_provider::ServiceProvider,
+        user_account::{
+            CreateUserAccount, CreateUserAccountError, UpdatePasswordError, UserAccountService,
+        },
+    };
+
+    #[actix_rt::test]
+    async fn login_security_settings() {
+        let (_, connection, connection_manager, _) =
+            setup_all("login_security_settings", MockDataInserts::none()).await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.basic_context().unwrap();
+        let service = &service_provider.login_security_service;
+
+        // Defaults when nothing is stored
+        assert_eq!(
+            service.get_login_security_settings(&context),
+            Ok(LoginSecuritySettings::default())
+        );
+
+        // Errors
+        let settings = LoginSecuritySettings {
+            password_policy: PasswordPolicy {
+                min_length: 10,
+                require_uppercase: true,
+                require_lowercase: true,
+                require_digit: true,
+                require_symbol: true,
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            service.update_login_security_settings(
+                &context,
+                LoginSecuritySettings {
+                    max_failed_attempts_per_ip: 0,
+                    ..settings.clone()
+                }
+            ),
+            Err(UpdateLoginSecuritySettingsError::MaxFailedAttemptsMustBePositive)
+        );
+        assert_eq!(
+            service.update_login_security_settings(
+                &context,
+                LoginSecuritySettings {
+                    lockout_max_seconds: 30,
+                    ..settings.clone()
+                }
+            ),
+            Err(UpdateLoginSecuritySettingsError::LockoutMaxSecondsLessThanBaseSeconds)
+        );
+
+        // Success
+        service
+            .update_login_security_settings(&context, settings.clone())
+            .unwrap();
+        assert_eq!(service.get_login_security_settings(&context), Ok(settings));
+
+        // Lockout doubles up to the maximum
+        assert_eq!(lockout_seconds(4, 5, 60, 3600), 0);
+        assert_eq!(lockout_seconds(5, 5, 60, 3600), 60);
+        assert_eq!(lockout_seconds(7, 5, 60, 3600), 240);
+        assert_eq!(lockout_seconds(100, 5, 60, 3600), 3600);
+
+        // Locally created users must comply with the password policy
+        let user_service = UserAccountService::new(&connection);
+        let result = user_service.create_user(CreateUserAccount {
+            username: "local_user".to_string(),
+            password: "password".to_string(),
+            email: None,
+        });
+        let violations = assert_variant!(
+            result,
+            Err(CreateUserAccountError::PasswordPolicyViolation(violations)) => violations
+        );
+        assert_eq!(
+            violations,
+            vec![
+                PasswordPolicyViolation::TooShort(10),
+                PasswordPolicyViolation::MissingUppercase,
+                PasswordPolicyViolation::MissingDigit,
+                PasswordPolicyViolation::MissingSymbol,
+            ]
+        );
+        let user = user_service
+            .create_user(CreateUserAccount {
+                username: "local_user".to_string(),
+                password: "Passw0rd!23".to_string(),
+                email: None,
+            })
+            .unwrap();
+        assert_matches!(
+            user_service.update_password(&user.id, "short"),
+            Err(UpdatePasswordError::PasswordPolicyViolation(_))
+        );
+        user_service
+            .update_password(&user.id, "N3w-Passw0rd")
+            .unwrap();
+        user_service
+            .verify_password("local_user", "N3w-Passw0rd")
+            .unwrap();
+    }
+}
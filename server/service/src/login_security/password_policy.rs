@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PasswordPolicy {
+    pub min_length: u32,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    /// Requires at least one character that is neither a letter nor a digit
+    pub require_symbol: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        PasswordPolicy {
+            min_length: 8,
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PasswordPolicyViolation {
+    TooShort(u32),
+    MissingUppercase,
+    MissingLowercase,
+    MissingDigit,
+    MissingSymbol,
+}
+
+impl PasswordPolicy {
+    /// Returns all the rules the password doesn't comply with
+    pub fn validate(&self, password: &str) -> Vec<PasswordPolicyViolation> {
+        use PasswordPolicyViolation::*;
+
+        let mut violations = Vec::new();
+        if (password.chars().count() as u32) < self.min_length {
+            violations.push(TooShort(self.min_length));
+        }
+        if self.require_uppercase && !password.chars().any(char::is_uppercase) {
+            violations.push(MissingUppercase);
+        }
+        if self.require_lowercase && !password.chars().any(char::is_lowercase) {
+            violations.push(MissingLowercase);
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            violations.push(MissingDigit);
+        }
+        if self.require_symbol && password.chars().all(char::is_alphanumeric) {
+            violations.push(MissingSymbol);
+        }
+
+        violations
+    }
+}
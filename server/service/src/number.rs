@@ -1,6 +1,7 @@
 use repository::{
-    InvoiceRowRepository, InvoiceType, NumberRowRepository, NumberRowType, RepositoryError,
-    RequisitionRowRepository, RequisitionType, StocktakeRowRepository, StorageConnection,
+    InvoicePaymentRowRepository, InvoicePaymentType, InvoiceRowRepository, InvoiceType,
+    NumberRowRepository, NumberRowType, RepositoryError, RequisitionRowRepository, RequisitionType,
+    StocktakeRowRepository, StorageConnection,
 };
 
 /// Get next number for record type and store
@@ -45,6 +46,10 @@ pub fn next_number(
                 .find_max_invoice_number(InvoiceType::CustomerReturn, store_id)?,
             NumberRowType::SupplierReturn => InvoiceRowRepository::new(connection_tx)
                 .find_max_invoice_number(InvoiceType::SupplierReturn, store_id)?,
+            NumberRowType::Receipt => InvoicePaymentRowRepository::new(connection_tx)
+                .find_max_receipt_number(InvoicePaymentType::Payment, store_id)?,
+            NumberRowType::CreditNote => InvoicePaymentRowRepository::new(connection_tx)
+                .find_max_receipt_number(InvoicePaymentType::CreditNote, store_id)?,
             NumberRowType::Program(_) => {
                 let next_number =
                     repo.get_next_number_for_type_and_store(r#type, store_id, None)?;
@@ -0,0 +1,633 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    time::Duration,
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{NaiveDateTime, Utc};
+use jsonwebtoken::{
+    decode, decode_header,
+    jwk::{Jwk, JwkSet},
+    Algorithm, DecodingKey, Validation,
+};
+use rand::{distributions::Alphanumeric, Rng};
+use repository::PermissionType;
+use reqwest::{Client, ClientBuilder, Url};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+#[cfg(test)]
+mod test_data;
+
+const CONNECTION_TIMEOUT_SEC: u64 = 10;
+/// Time the user has to sign in with the identity provider
+const PENDING_LOGIN_EXPIRY_SEC: i64 = 10 * 60;
+const CODE_VERIFIER_LENGTH: usize = 64;
+const STATE_LENGTH: usize = 32;
+
+fn default_scopes() -> Vec<String> {
+    vec![
+        "openid".to_string(),
+        "profile".to_string(),
+        "email".to_string(),
+    ]
+}
+
+fn default_username_claim() -> String {
+    "preferred_username".to_string()
+}
+
+fn default_frontend_url() -> String {
+    "/".to_string()
+}
+
+/// OpenID Connect identity provider used for single sign-on
+#[derive(serde::Deserialize, Clone, Debug)]
+pub struct OidcSettings {
+    /// The provider metadata is discovered from `{issuer_url}/.well-known/openid-configuration`
+    pub issuer_url: String,
+    pub client_id: String,
+    /// Only needed for confidential clients, PKCE is always used
+    pub client_secret: Option<String>,
+    /// Url of the `/oidc/callback` endpoint of this server, as registered with the provider
+    pub redirect_url: String,
+    #[serde(default = "default_scopes")]
+    pub scopes: Vec<String>,
+    /// ID token claim matched against the username of the `user_account`
+    #[serde(default = "default_username_claim")]
+    pub username_claim: String,
+    /// Where the browser is sent after the sign in
+    #[serde(default = "default_frontend_url")]
+    pub frontend_url: String,
+    /// ID token claim listing the user's groups or roles. When set, the user's permissions in
+    /// each of their stores on this site are replaced with the ones of their groups on sign in
+    pub groups_claim: Option<String>,
+    /// Store permissions given for each group of the groups claim, store access is always given
+    #[serde(default)]
+    pub group_permissions: HashMap<String, Vec<PermissionType>>,
+}
+
+/// User signed in with the identity provider
+#[derive(Debug, PartialEq)]
+pub struct OidcIdentity {
+    pub username: String,
+    /// Permissions of the user's groups, `None` if groups aren't mapped and the permissions
+    /// synced from central apply
+    pub store_permissions: Option<HashSet<PermissionType>>,
+}
+
+/// Sign in started with the identity provider
+#[derive(Debug)]
+pub struct AuthorizationRequest {
+    /// Identity provider url the browser needs to be sent to
+    pub url: Url,
+    /// Needs to be kept by the browser that started the sign in, e.g. in a cookie, and checked
+    /// when the provider redirects back
+    pub state: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum OidcError {
+    /// Identity provider could not be reached, users can still sign in with local credentials
+    IdentityProviderUnreachable(String),
+    InvalidProviderResponse(String),
+    /// Callback doesn't belong to a sign in started on this server or the sign in took too long
+    UnknownState,
+    /// Callback doesn't come from the browser that started the sign in
+    StateMismatch,
+    /// Identity provider returned an error instead of an authorization code
+    AuthorizationDenied(String),
+    TokenExchangeFailed(String),
+    InvalidIdToken(String),
+    MissingUsernameClaim(String),
+    InternalError(String),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct TokenResponse {
+    id_token: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct TokenErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+struct PendingLogin {
+    code_verifier: String,
+    nonce: String,
+    created_datetime: NaiveDateTime,
+}
+
+/// Authorization code flow with PKCE against the configured identity provider.
+/// Sign ins that have been started are kept in memory until the provider redirects back.
+pub struct OidcClient {
+    settings: OidcSettings,
+    client: Client,
+    pending_logins: Mutex<HashMap<String, PendingLogin>>,
+}
+
+fn random_string(length: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+/// S256 code challenge for the code verifier, see RFC 7636
+pub fn code_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+fn connection_error(error: reqwest::Error) -> OidcError {
+    OidcError::IdentityProviderUnreachable(format!("{:?}", error))
+}
+
+fn lock_error<T>(_: T) -> OidcError {
+    OidcError::InternalError("Concurrent error".to_string())
+}
+
+impl OidcClient {
+    pub fn new(settings: OidcSettings) -> Self {
+        let client = ClientBuilder::new()
+            .connect_timeout(Duration::from_secs(CONNECTION_TIMEOUT_SEC))
+            .build()
+            .unwrap_or_default();
+
+        OidcClient {
+            settings,
+            client,
+            pending_logins: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn settings(&self) -> &OidcSettings {
+        &self.settings
+    }
+
+    async fn provider_metadata(&self) -> Result<ProviderMetadata, OidcError> {
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            self.settings.issuer_url.trim_end_matches('/')
+        );
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(connection_error)?;
+        if response.status().is_server_error() {
+            return Err(OidcError::IdentityProviderUnreachable(format!(
+                "Discovery returned {}",
+                response.status()
+            )));
+        }
+
+        response
+            .json::<ProviderMetadata>()
+            .await
+            .map_err(|error| OidcError::InvalidProviderResponse(format!("{:?}", error)))
+    }
+
+    /// Starts a sign in
+    pub async fn authorization_request(&self) -> Result<AuthorizationRequest, OidcError> {
+        let metadata = self.provider_metadata().await?;
+
+        let state = random_string(STATE_LENGTH);
+        let pending = PendingLogin {
+            code_verifier: random_string(CODE_VERIFIER_LENGTH),
+            nonce: random_string(STATE_LENGTH),
+            created_datetime: Utc::now().naive_utc(),
+        };
+
+        let mut url = Url::parse(&metadata.authorization_endpoint)
+            .map_err(|error| OidcError::InvalidProviderResponse(format!("{:?}", error)))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.settings.client_id)
+            .append_pair("redirect_uri", &self.settings.redirect_url)
+            .append_pair("scope", &self.settings.scopes.join(" "))
+            .append_pair("state", &state)
+            .append_pair("nonce", &pending.nonce)
+            .append_pair("code_challenge", &code_challenge(&pending.code_verifier))
+            .append_pair("code_challenge_method", "S256");
+
+        let mut pending_logins = self.pending_logins.lock().map_err(lock_error)?;
+        let now = Utc::now().naive_utc();
+        pending_logins.retain(|_, login| {
+            (now - login.created_datetime).num_seconds() < PENDING_LOGIN_EXPIRY_SEC
+        });
+        pending_logins.insert(state.clone(), pending);
+
+        Ok(AuthorizationRequest { url, state })
+    }
+
+    /// Completes a sign in with the authorization code the identity provider redirected back
+    /// with. `browser_state` is the state kept by the browser the callback came from, it has to
+    /// match so a sign in started by someone else can't be completed in the user's browser.
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        state: &str,
+        browser_state: Option<&str>,
+    ) -> Result<OidcIdentity, OidcError> {
+        if browser_state != Some(state) {
+            return Err(OidcError::StateMismatch);
+        }
+        let pending = self
+            .pending_logins
+            .lock()
+            .map_err(lock_error)?
+            .remove(state)
+            .ok_or(OidcError::UnknownState)?;
+        if (Utc::now().naive_utc() - pending.created_datetime).num_seconds()
+            >= PENDING_LOGIN_EXPIRY_SEC
+        {
+            return Err(OidcError::UnknownState);
+        }
+
+        let metadata = self.provider_metadata().await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.settings.redirect_url.as_str()),
+            ("client_id", self.settings.client_id.as_str()),
+            ("code_verifier", pending.code_verifier.as_str()),
+        ];
+        if let Some(client_secret) = &self.settings.client_secret {
+            form.push(("client_secret", client_secret.as_str()));
+        }
+        let response = self
+            .client
+            .post(&metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .map_err(connection_error)?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = match response.json::<TokenErrorResponse>().await {
+                Ok(error) => format!(
+                    "{}: {}",
+                    error.error,
+                    error.error_description.unwrap_or_default()
+                ),
+                Err(_) => status.to_string(),
+            };
+            return Err(OidcError::TokenExchangeFailed(message));
+        }
+        let id_token = response
+            .json::<TokenResponse>()
+            .await
+            .map_err(|error| OidcError::InvalidProviderResponse(format!("{:?}", error)))?
+            .id_token
+            .ok_or(OidcError::InvalidProviderResponse(
+                "Token response has no id_token".to_string(),
+            ))?;
+
+        let jwks = self
+            .client
+            .get(&metadata.jwks_uri)
+            .send()
+            .await
+            .map_err(connection_error)?
+            .json::<JwkSet>()
+            .await
+            .map_err(|error| OidcError::InvalidProviderResponse(format!("{:?}", error)))?;
+
+        let claims = validate_id_token(
+            &id_token,
+            &jwks,
+            &metadata.issuer,
+            &self.settings.client_id,
+            &pending.nonce,
+        )?;
+
+        let username = match claims.get(&self.settings.username_claim) {
+            Some(Value::String(username)) if !username.is_empty() => username.clone(),
+            _ => {
+                return Err(OidcError::MissingUsernameClaim(
+                    self.settings.username_claim.clone(),
+                ))
+            }
+        };
+
+        Ok(OidcIdentity {
+            username,
+            store_permissions: self.store_permissions(&claims),
+        })
+    }
+
+    fn store_permissions(
+        &self,
+        claims: &HashMap<String, Value>,
+    ) -> Option<HashSet<PermissionType>> {
+        let groups_claim = self.settings.groups_claim.as_ref()?;
+        let groups: Vec<&str> = match claims.get(groups_claim) {
+            Some(Value::Array(groups)) => groups.iter().filter_map(Value::as_str).collect(),
+            Some(Value::String(group)) => vec![group.as_str()],
+            _ => Vec::new(),
+        };
+
+        let mut permissions: HashSet<PermissionType> = groups
+            .into_iter()
+            .filter_map(|group| self.settings.group_permissions.get(group))
+            .flatten()
+            .cloned()
+            .collect();
+        permissions.insert(PermissionType::StoreAccess);
+        Some(permissions)
+    }
+}
+
+/// Checks the signature against the provider keys as well as issuer, audience, expiry and nonce
+fn validate_id_token(
+    id_token: &str,
+    jwks: &JwkSet,
+    issuer: &str,
+    client_id: &str,
+    nonce: &str,
+) -> Result<HashMap<String, Value>, OidcError> {
+    let invalid = |error: jsonwebtoken::errors::Error| OidcError::InvalidIdToken(error.to_string());
+
+    let header = decode_header(id_token).map_err(invalid)?;
+    // Only signatures with the provider's public keys are accepted
+    if matches!(
+        header.alg,
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+    ) {
+        return Err(OidcError::InvalidIdToken(format!(
+            "Unsupported algorithm {:?}",
+            header.alg
+        )));
+    }
+    let jwk: &Jwk = match &header.kid {
+        Some(kid) => jwks.find(kid),
+        None => jwks.keys.first(),
+    }
+    .ok_or(OidcError::InvalidIdToken("Unknown signing key".to_string()))?;
+    let key = DecodingKey::from_jwk(jwk).map_err(invalid)?;
+
+    let mut validation = Validation::new(header.alg);
+    validation.set_issuer(&[issuer]);
+    validation.set_audience(&[client_id]);
+    validation.required_spec_claims =
+        HashSet::from(["exp", "iss", "aud", "sub"].map(|claim| claim.to_string()));
+
+    let claims = decode::<HashMap<String, Value>>(id_token, &key, &validation)
+        .map_err(invalid)?
+        .claims;
+    if claims.get("nonce") != Some(&Value::String(nonce.to_string())) {
+        return Err(OidcError::InvalidIdToken("Nonce mismatch".to_string()));
+    }
+
+    Ok(claims)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, RwLock},
+    };
+
+    use chrono::Utc;
+    use httpmock::{
+        Method::{GET, POST},
+        MockServer,
+    };
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use repository::{
+        mock::{mock_store_a, mock_user_account_b, MockDataInserts},
+        test_db::setup_all,
+        EqualFilter, KeyType, KeyValueStoreRepository, PermissionType, UserPermissionFilter,
+        UserPermissionRepository,
+    };
+    use serde_json::json;
+    use util::assert_matches;
+
+    use super::{
+        code_challenge,
+        test_data::{PRIVATE_KEY_PEM, PUBLIC_KEY_EXPONENT, PUBLIC_KEY_MODULUS},
+        OidcClient, OidcError, OidcIdentity, OidcSettings,
+    };
+    use crate::{
+        auth_data::AuthData,
+        login::{LoginError, LoginFailure, LoginService},
+        service_provider::ServiceProvider,
+        token_bucket::TokenBucket,
+    };
+
+    fn settings(issuer_url: &str) -> OidcSettings {
+        OidcSettings {
+            issuer_url: issuer_url.to_string(),
+            client_id: "omsupply".to_string(),
+            client_secret: None,
+            redirect_url: "http://localhost:8000/oidc/callback".to_string(),
+            scopes: vec!["openid".to_string()],
+            username_claim: "preferred_username".to_string(),
+            frontend_url: "/".to_string(),
+            groups_claim: None,
+            group_permissions: HashMap::new(),
+        }
+    }
+
+    fn id_token(issuer: &str, audience: &str, nonce: &str, username: &str) -> String {
+        let mut header = Header::new(jsonwebtoken::Algorithm::RS256);
+        header.kid = Some("key_1".to_string());
+        let claims = json!({
+            "iss": issuer,
+            "aud": audience,
+            "sub": "subject_1",
+            "exp": Utc::now().timestamp() + 300,
+            "nonce": nonce,
+            "preferred_username": username,
+            "groups": ["pharmacist"],
+        });
+        encode(
+            &header,
+            &claims,
+            &EncodingKey::from_rsa_pem(PRIVATE_KEY_PEM.as_bytes()).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[actix_rt::test]
+    async fn oidc_login() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "oidc_login",
+            MockDataInserts::none()
+                .names()
+                .stores()
+                .user_accounts()
+                .user_store_joins(),
+        )
+        .await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let auth_data = AuthData {
+            auth_token_secret: "secret".to_string(),
+            token_bucket: Arc::new(RwLock::new(TokenBucket::new())),
+            no_ssl: true,
+            debug_no_access_control: false,
+        };
+        KeyValueStoreRepository::new(&connection)
+            .set_i32(KeyType::SettingsSyncSiteId, Some(mock_store_a().site_id))
+            .unwrap();
+
+        // Identity provider unreachable, users need to fall back to local credentials
+        let client = OidcClient::new(settings("http://127.0.0.1:1"));
+        assert_matches!(
+            client.authorization_request().await,
+            Err(OidcError::IdentityProviderUnreachable(_))
+        );
+
+        // Mock identity provider
+        let server = MockServer::start();
+        let issuer = server.base_url();
+        server.mock(|when, then| {
+            when.method(GET).path("/.well-known/openid-configuration");
+            then.status(200).json_body(json!({
+                "issuer": issuer,
+                "authorization_endpoint": server.url("/authorize"),
+                "token_endpoint": server.url("/token"),
+                "jwks_uri": server.url("/jwks"),
+            }));
+        });
+        server.mock(|when, then| {
+            when.method(GET).path("/jwks");
+            then.status(200).json_body(json!({
+                "keys": [{
+                    "kty": "RSA",
+                    "kid": "key_1",
+                    "alg": "RS256",
+                    "use": "sig",
+                    "n": PUBLIC_KEY_MODULUS,
+                    "e": PUBLIC_KEY_EXPONENT,
+                }]
+            }));
+        });
+        let client = OidcClient::new(OidcSettings {
+            groups_claim: Some("groups".to_string()),
+            group_permissions: HashMap::from([(
+                "pharmacist".to_string(),
+                vec![PermissionType::PrescriptionQuery],
+            )]),
+            ..settings(&issuer)
+        });
+
+        let start_login = || async {
+            let request = client.authorization_request().await.unwrap();
+            let url = request.url;
+            let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+            assert!(url.as_str().starts_with(&server.url("/authorize")));
+            assert_eq!(query["code_challenge_method"], "S256");
+            assert_eq!(query["code_challenge"].len(), code_challenge("").len());
+            assert_eq!(query["state"], request.state);
+            (request.state, query["nonce"].clone())
+        };
+
+        // Success, the username claim is mapped to the user account
+        let (state, nonce) = start_login().await;
+        let token = id_token(&issuer, "omsupply", &nonce, &mock_user_account_b().username);
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/token")
+                .x_www_form_urlencoded_tuple("code", "code_1")
+                .x_www_form_urlencoded_key_exists("code_verifier");
+            then.status(200).json_body(json!({ "id_token": token }));
+        });
+        // Callback in a browser that didn't start the sign in, e.g. login CSRF
+        assert_eq!(
+            client.exchange_code("code_1", &state, None).await,
+            Err(OidcError::StateMismatch)
+        );
+        assert_eq!(
+            client
+                .exchange_code("code_1", &state, Some("other_state"))
+                .await,
+            Err(OidcError::StateMismatch)
+        );
+        let identity = client
+            .exchange_code("code_1", &state, Some(&state))
+            .await
+            .unwrap();
+        assert_eq!(identity.username, mock_user_account_b().username);
+        LoginService::sso_login(&service_provider, &auth_data, &identity).unwrap();
+        // No full login happened, the session is issued without a password
+        assert_eq!(
+            auth_data
+                .token_bucket
+                .read()
+                .unwrap()
+                .get_password(&mock_user_account_b().id),
+            ""
+        );
+
+        // Groups are mapped to the user's permissions in each of their stores
+        let permissions = UserPermissionRepository::new(&connection)
+            .query_by_filter(
+                UserPermissionFilter::new()
+                    .user_id(EqualFilter::equal_to(&mock_user_account_b().id))
+                    .has_context(false),
+            )
+            .unwrap();
+        assert!(!permissions.is_empty());
+        assert!(permissions.iter().all(|permission| matches!(
+            permission.permission,
+            PermissionType::StoreAccess | PermissionType::PrescriptionQuery
+        )));
+
+        // A sign in can only be completed once
+        assert_eq!(
+            client.exchange_code("code_1", &state, Some(&state)).await,
+            Err(OidcError::UnknownState)
+        );
+
+        // ID token issued for another client
+        let (state, nonce) = start_login().await;
+        let token = id_token(&issuer, "other_client", &nonce, "user_b");
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/token")
+                .x_www_form_urlencoded_tuple("code", "code_2");
+            then.status(200).json_body(json!({ "id_token": token }));
+        });
+        assert_matches!(
+            client.exchange_code("code_2", &state, Some(&state)).await,
+            Err(OidcError::InvalidIdToken(_))
+        );
+
+        // ID token replayed for another sign in
+        let (state, _) = start_login().await;
+        assert_matches!(
+            client.exchange_code("code_1", &state, Some(&state)).await,
+            Err(OidcError::InvalidIdToken(_))
+        );
+
+        // Users unknown on this site can't sign in
+        assert_matches!(
+            LoginService::sso_login(
+                &service_provider,
+                &auth_data,
+                &OidcIdentity {
+                    username: "unknown_user".to_string(),
+                    store_permissions: None,
+                }
+            ),
+            Err(LoginError::LoginFailure(LoginFailure::InvalidCredentials))
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::Utc;
+use repository::{
+    InvoiceRowRepository, InvoiceStatus, PickListLineRow, PickListLineRowRepository,
+    PickListRowRepository, PickListStatus, RepositoryError,
+};
+
+use crate::{
+    invoice::outbound_shipment::update::{
+        update_outbound_shipment, UpdateOutboundShipment, UpdateOutboundShipmentError,
+        UpdateOutboundShipmentStatus,
+    },
+    service_provider::ServiceContext,
+};
+
+use super::PickList;
+
+#[derive(Debug, PartialEq)]
+pub enum CompletePickListError {
+    PickListDoesNotExist,
+    NotThisStorePickList,
+    PickListIsCompleted,
+    LinesNotConfirmed(Vec<PickListLineRow>),
+    UpdateOutboundShipment {
+        invoice_id: String,
+        error: UpdateOutboundShipmentError,
+    },
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for CompletePickListError {
+    fn from(error: RepositoryError) -> Self {
+        CompletePickListError::DatabaseError(error)
+    }
+}
+
+/// Completes a pick list once every line is confirmed, moving its allocated shipments to Picked
+pub fn complete_pick_list(
+    ctx: &ServiceContext,
+    id: String,
+) -> Result<PickList, CompletePickListError> {
+    let pick_list = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let repository = PickListRowRepository::new(connection);
+            let mut pick_list = repository
+                .find_one_by_id(&id)?
+                .ok_or(CompletePickListError::PickListDoesNotExist)?;
+            if pick_list.store_id != ctx.store_id {
+                return Err(CompletePickListError::NotThisStorePickList);
+            }
+            if pick_list.status == PickListStatus::Completed {
+                return Err(CompletePickListError::PickListIsCompleted);
+            }
+
+            let lines =
+                PickListLineRowRepository::new(connection).find_many_by_pick_list_id(&id)?;
+            let not_confirmed: Vec<PickListLineRow> = lines
+                .iter()
+                .filter(|line| !line.is_confirmed())
+                .cloned()
+                .collect();
+            if !not_confirmed.is_empty() {
+                return Err(CompletePickListError::LinesNotConfirmed(not_confirmed));
+            }
+
+            let mut invoice_ids: Vec<String> =
+                lines.iter().map(|line| line.invoice_id.clone()).collect();
+            invoice_ids.sort();
+            invoice_ids.dedup();
+            for invoice_id in invoice_ids {
+                let is_allocated = InvoiceRowRepository::new(connection)
+                    .find_one_by_id(&invoice_id)?
+                    .is_some_and(|invoice| invoice.status == InvoiceStatus::Allocated);
+                if !is_allocated {
+                    continue;
+                }
+                update_outbound_shipment(
+                    ctx,
+                    UpdateOutboundShipment {
+                        id: invoice_id.clone(),
+                        status: Some(UpdateOutboundShipmentStatus::Picked),
+                        ..Default::default()
+                    },
+                )
+                .map_err(|error| {
+                    CompletePickListError::UpdateOutboundShipment { invoice_id, error }
+                })?;
+            }
+
+            pick_list.status = PickListStatus::Completed;
+            pick_list.completed_datetime = Some(Utc::now().naive_utc());
+            repository.upsert_one(&pick_list)?;
+
+            Ok(PickList { pick_list, lines })
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(pick_list)
+}
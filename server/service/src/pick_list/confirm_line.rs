@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use repository::{
+    ActivityLogType, EqualFilter, InvoiceLine, InvoiceLineFilter, InvoiceLineRepository,
+    InvoiceLineType, PickListLineRow, PickListLineRowRepository, PickListRowRepository,
+    PickListStatus, RepositoryError, StockLineRowRepository,
+};
+use util::uuid::uuid;
+
+use crate::{
+    activity_log::activity_log_entry,
+    invoice_line::{
+        outbound_shipment_unallocated_line::{
+            allocate_short_pick, AllocateOutboundShipmentUnallocatedLineError,
+        },
+        stock_out_line::{
+            delete_stock_out_line, update_stock_out_line, DeleteStockOutLine,
+            DeleteStockOutLineError, StockOutType, UpdateStockOutLine, UpdateStockOutLineError,
+        },
+    },
+    service_provider::ServiceContext,
+};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConfirmPickListLine {
+    pub id: String,
+    pub picked_number_of_packs: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConfirmPickListLineResult {
+    pub line: PickListLineRow,
+    /// Lines added to the end of the pick list for stock allocated in place of a short pick
+    pub reallocated_lines: Vec<PickListLineRow>,
+    /// Units of a short pick that couldn't be allocated from other stock
+    pub unallocated_quantity: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ConfirmPickListLineError {
+    PickListLineDoesNotExist,
+    NotThisStorePickList,
+    PickListIsCompleted,
+    LineAlreadyConfirmed,
+    PickedNumberOfPacksBelowZero,
+    PickedMoreThanAllocated,
+    /// The shipment line was removed after the pick list was generated
+    InvoiceLineDoesNotExist,
+    UpdateStockOutLine(UpdateStockOutLineError),
+    DeleteStockOutLine(DeleteStockOutLineError),
+    AllocateShortPick(AllocateOutboundShipmentUnallocatedLineError),
+    DatabaseError(RepositoryError),
+}
+
+type OutError = ConfirmPickListLineError;
+
+impl From<RepositoryError> for ConfirmPickListLineError {
+    fn from(error: RepositoryError) -> Self {
+        ConfirmPickListLineError::DatabaseError(error)
+    }
+}
+
+/// Records the packs picked for a line. A short pick reduces the shipment line to the picked
+/// packs, puts the stock line on hold and allocates the rest from other stock lines, adding them
+/// to the pick list
+pub fn confirm_pick_list_line(
+    ctx: &ServiceContext,
+    input: ConfirmPickListLine,
+) -> Result<ConfirmPickListLineResult, OutError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let line_repository = PickListLineRowRepository::new(connection);
+            let mut line = line_repository
+                .find_one_by_id(&input.id)?
+                .ok_or(OutError::PickListLineDoesNotExist)?;
+            let pick_list = PickListRowRepository::new(connection)
+                .find_one_by_id(&line.pick_list_id)?
+                .ok_or(OutError::PickListLineDoesNotExist)?;
+            if pick_list.store_id != ctx.store_id {
+                return Err(OutError::NotThisStorePickList);
+            }
+            if pick_list.status == PickListStatus::Completed {
+                return Err(OutError::PickListIsCompleted);
+            }
+            if line.is_confirmed() {
+                return Err(OutError::LineAlreadyConfirmed);
+            }
+            if input.picked_number_of_packs < 0.0 {
+                return Err(OutError::PickedNumberOfPacksBelowZero);
+            }
+            if input.picked_number_of_packs > line.number_of_packs {
+                return Err(OutError::PickedMoreThanAllocated);
+            }
+
+            let mut result = ConfirmPickListLineResult::default();
+            let short_packs = line.number_of_packs - input.picked_number_of_packs;
+            if short_packs > 0.0 {
+                let pick_list_lines = line_repository.find_many_by_pick_list_id(&pick_list.id)?;
+                (result.reallocated_lines, result.unallocated_quantity) =
+                    reallocate_short_pick(ctx, &line, short_packs, &pick_list_lines)?;
+                for reallocated_line in result.reallocated_lines.iter() {
+                    line_repository.upsert_one(reallocated_line)?;
+                }
+            }
+
+            line.picked_number_of_packs = Some(input.picked_number_of_packs);
+            line.picked_datetime = Some(Utc::now().naive_utc());
+            line_repository.upsert_one(&line)?;
+            result.line = line;
+
+            Ok(result)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+fn reallocate_short_pick(
+    ctx: &ServiceContext,
+    line: &PickListLineRow,
+    short_packs: f64,
+    pick_list_lines: &[PickListLineRow],
+) -> Result<(Vec<PickListLineRow>, f64), OutError> {
+    let connection = &ctx.connection;
+    let invoice_line = InvoiceLineRepository::new(connection)
+        .query_one(InvoiceLineFilter::new().id(EqualFilter::equal_to(&line.invoice_line_id)))?
+        .ok_or(OutError::InvoiceLineDoesNotExist)?;
+    let invoice_line_row = &invoice_line.invoice_line_row;
+
+    let remaining_packs = invoice_line_row.number_of_packs - short_packs;
+    if remaining_packs > 0.0 {
+        update_stock_out_line(
+            ctx,
+            UpdateStockOutLine {
+                id: invoice_line_row.id.clone(),
+                r#type: Some(StockOutType::OutboundShipment),
+                number_of_packs: Some(remaining_packs),
+                ..Default::default()
+            },
+        )
+        .map_err(OutError::UpdateStockOutLine)?;
+    } else {
+        delete_stock_out_line(
+            ctx,
+            DeleteStockOutLine {
+                id: invoice_line_row.id.clone(),
+                r#type: Some(StockOutType::OutboundShipment),
+            },
+        )
+        .map_err(OutError::DeleteStockOutLine)?;
+    }
+    if let Some(stock_line_id) = &line.stock_line_id {
+        hold_short_picked_stock_line(ctx, stock_line_id)?;
+    }
+
+    // Stock lines short picked on this pick list (including this one) aren't allocated again,
+    // even if they are taken off hold before the pick list is completed
+    let mut excluded_stock_line_ids: Vec<String> = pick_list_lines
+        .iter()
+        .filter(|line| {
+            line.picked_number_of_packs
+                .is_some_and(|picked| picked < line.number_of_packs)
+        })
+        .filter_map(|line| line.stock_line_id.clone())
+        .collect();
+    excluded_stock_line_ids.extend(line.stock_line_id.clone());
+
+    let packs_before = get_item_lines(ctx, &invoice_line)?
+        .into_iter()
+        .map(|line| {
+            (
+                line.invoice_line_row.id,
+                line.invoice_line_row.number_of_packs,
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    let allocation = allocate_short_pick(
+        ctx,
+        &invoice_line,
+        short_packs * invoice_line_row.pack_size,
+        &excluded_stock_line_ids,
+    )
+    .map_err(OutError::AllocateShortPick)?;
+
+    let mut line_number = pick_list_lines
+        .iter()
+        .map(|line| line.line_number)
+        .max()
+        .unwrap_or_default();
+    let mut reallocated_lines = Vec::new();
+    for allocated_line in allocation.inserts.into_iter().chain(allocation.updates) {
+        let row = allocated_line.invoice_line_row;
+        let number_of_packs = row.number_of_packs - packs_before.get(&row.id).unwrap_or(&0.0);
+        if number_of_packs <= 0.0 {
+            continue;
+        }
+        line_number += 1;
+        reallocated_lines.push(PickListLineRow {
+            id: uuid(),
+            pick_list_id: line.pick_list_id.clone(),
+            invoice_id: row.invoice_id,
+            invoice_line_id: row.id,
+            item_link_id: row.item_link_id,
+            stock_line_id: row.stock_line_id,
+            location_id: row.location_id,
+            line_number,
+            number_of_packs,
+            picked_number_of_packs: None,
+            picked_datetime: None,
+        });
+    }
+
+    Ok((reallocated_lines, allocation.unallocated_quantity))
+}
+
+/// Packs that couldn't be found would otherwise be returned to available stock, the stock line is
+/// held until it's counted (e.g. by a stocktake) and taken off hold
+fn hold_short_picked_stock_line(
+    ctx: &ServiceContext,
+    stock_line_id: &str,
+) -> Result<(), RepositoryError> {
+    let repository = StockLineRowRepository::new(&ctx.connection);
+    let Some(mut stock_line) = repository.find_one_by_id(stock_line_id)? else {
+        return Ok(());
+    };
+    if stock_line.on_hold {
+        return Ok(());
+    }
+
+    stock_line.on_hold = true;
+    repository.upsert_one(&stock_line)?;
+    activity_log_entry(
+        ctx,
+        ActivityLogType::StockOnHold,
+        Some(stock_line.id),
+        None,
+        None,
+    )
+}
+
+fn get_item_lines(
+    ctx: &ServiceContext,
+    invoice_line: &InvoiceLine,
+) -> Result<Vec<InvoiceLine>, RepositoryError> {
+    InvoiceLineRepository::new(&ctx.connection).query_by_filter(
+        InvoiceLineFilter::new()
+            .invoice_id(EqualFilter::equal_to(
+                &invoice_line.invoice_line_row.invoice_id,
+            ))
+            .item_id(EqualFilter::equal_to(&invoice_line.item_row.id))
+            .r#type(InvoiceLineType::StockOut.equal_to()),
+    )
+}
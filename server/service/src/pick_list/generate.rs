@@ -0,0 +1,195 @@
+use std::collections::HashSet;
+
+use chrono::Utc;
+use repository::{
+    EqualFilter, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineType, InvoiceRowRepository,
+    InvoiceStatus, InvoiceType, PickListLineRow, PickListLineRowRepository, PickListRow,
+    PickListRowRepository, PickListStatus, RepositoryError, StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::service_provider::ServiceContext;
+
+use super::{get_pick_path_positions, location_pick_order, PickList};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GeneratePickList {
+    pub id: String,
+    /// Outbound shipments to pick together, more than one makes a wave pick
+    pub invoice_ids: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GeneratePickListError {
+    PickListAlreadyExists,
+    NoShipmentsSelected,
+    InvoiceDoesNotExist(String),
+    NotThisStoreInvoice(String),
+    NotAnOutboundShipment(String),
+    /// Only allocated shipments can be picked
+    InvoiceIsNotAllocated(String),
+    InvoiceIsOnHold(String),
+    InvoiceAlreadyOnPickList(String),
+    /// Shipments have no allocated lines
+    NothingToPick,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for GeneratePickListError {
+    fn from(error: RepositoryError) -> Self {
+        GeneratePickListError::DatabaseError(error)
+    }
+}
+
+/// Pick list of the allocated lines of the shipments, grouped by location along the pick path
+/// of the store
+pub fn generate_pick_list(
+    ctx: &ServiceContext,
+    input: GeneratePickList,
+) -> Result<PickList, GeneratePickListError> {
+    let pick_list = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let invoice_ids = validate(connection, &ctx.store_id, &input)?;
+            let (pick_list, lines) = generate(ctx, input.id, &invoice_ids)?;
+
+            PickListRowRepository::new(connection).upsert_one(&pick_list)?;
+            let repository = PickListLineRowRepository::new(connection);
+            for line in lines.iter() {
+                repository.upsert_one(line)?;
+            }
+
+            Ok(PickList { pick_list, lines }) as Result<PickList, GeneratePickListError>
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(pick_list)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &GeneratePickList,
+) -> Result<Vec<String>, GeneratePickListError> {
+    use GeneratePickListError::*;
+
+    if PickListRowRepository::new(connection)
+        .find_one_by_id(&input.id)?
+        .is_some()
+    {
+        return Err(PickListAlreadyExists);
+    }
+
+    let mut seen = HashSet::new();
+    let invoice_ids: Vec<String> = input
+        .invoice_ids
+        .iter()
+        .filter(|invoice_id| seen.insert(invoice_id.as_str()))
+        .cloned()
+        .collect();
+    if invoice_ids.is_empty() {
+        return Err(NoShipmentsSelected);
+    }
+
+    for invoice_id in &invoice_ids {
+        let invoice = InvoiceRowRepository::new(connection)
+            .find_one_by_id(invoice_id)?
+            .ok_or_else(|| InvoiceDoesNotExist(invoice_id.clone()))?;
+        if invoice.store_id != store_id {
+            return Err(NotThisStoreInvoice(invoice_id.clone()));
+        }
+        if invoice.r#type != InvoiceType::OutboundShipment {
+            return Err(NotAnOutboundShipment(invoice_id.clone()));
+        }
+        if invoice.status != InvoiceStatus::Allocated {
+            return Err(InvoiceIsNotAllocated(invoice_id.clone()));
+        }
+        if invoice.on_hold {
+            return Err(InvoiceIsOnHold(invoice_id.clone()));
+        }
+    }
+
+    let existing_lines =
+        PickListLineRowRepository::new(connection).find_many_by_invoice_ids(&invoice_ids)?;
+    let pick_list_ids: Vec<String> = existing_lines
+        .iter()
+        .map(|line| line.pick_list_id.clone())
+        .collect();
+    let in_progress: Vec<String> = PickListRowRepository::new(connection)
+        .find_many_by_ids(&pick_list_ids)?
+        .into_iter()
+        .filter(|pick_list| pick_list.status == PickListStatus::InProgress)
+        .map(|pick_list| pick_list.id)
+        .collect();
+    if let Some(line) = existing_lines
+        .into_iter()
+        .find(|line| in_progress.contains(&line.pick_list_id))
+    {
+        return Err(InvoiceAlreadyOnPickList(line.invoice_id));
+    }
+
+    Ok(invoice_ids)
+}
+
+fn generate(
+    ctx: &ServiceContext,
+    id: String,
+    invoice_ids: &[String],
+) -> Result<(PickListRow, Vec<PickListLineRow>), GeneratePickListError> {
+    let connection = &ctx.connection;
+    let mut invoice_lines = InvoiceLineRepository::new(connection).query_by_filter(
+        InvoiceLineFilter::new()
+            .invoice_id(EqualFilter::equal_any(invoice_ids.to_vec()))
+            .r#type(InvoiceLineType::StockOut.equal_to()),
+    )?;
+    invoice_lines.retain(|line| line.invoice_line_row.number_of_packs > 0.0);
+    if invoice_lines.is_empty() {
+        return Err(GeneratePickListError::NothingToPick);
+    }
+
+    let pick_path = get_pick_path_positions(connection, &ctx.store_id)?;
+    invoice_lines.sort_by_cached_key(|line| {
+        let location = line.location_row_option.as_ref();
+        (
+            location_pick_order(
+                &pick_path,
+                location.map(|location| location.id.as_str()),
+                location.map(|location| location.code.as_str()),
+            ),
+            line.item_row.name.clone(),
+            line.invoice_row.invoice_number,
+        )
+    });
+
+    let lines = invoice_lines
+        .into_iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let row = line.invoice_line_row;
+            PickListLineRow {
+                id: uuid(),
+                pick_list_id: id.clone(),
+                invoice_id: row.invoice_id,
+                invoice_line_id: row.id,
+                item_link_id: row.item_link_id,
+                stock_line_id: row.stock_line_id,
+                location_id: row.location_id,
+                line_number: index as i32 + 1,
+                number_of_packs: row.number_of_packs,
+                picked_number_of_packs: None,
+                picked_datetime: None,
+            }
+        })
+        .collect();
+
+    let pick_list = PickListRow {
+        id,
+        store_id: ctx.store_id.clone(),
+        status: PickListStatus::InProgress,
+        created_datetime: Utc::now().naive_utc(),
+        completed_datetime: None,
+        user_id: ctx.user_id.clone(),
+    };
+
+    Ok((pick_list, lines))
+}
@@ -0,0 +1,116 @@
+use repository::{
+    PickListLineRow, PickListLineRowRepository, PickListRow, PickListRowRepository, PickListStatus,
+    PickPathLocationRow, PickPathLocationRowRepository, RepositoryError,
+};
+
+use crate::service_provider::ServiceContext;
+
+pub mod complete;
+pub use self::complete::*;
+pub mod confirm_line;
+pub use self::confirm_line::*;
+pub mod generate;
+pub use self::generate::*;
+pub mod pick_path;
+pub use self::pick_path::*;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickList {
+    pub pick_list: PickListRow,
+    /// In pick path order
+    pub lines: Vec<PickListLineRow>,
+}
+
+pub trait PickListServiceTrait: Sync + Send {
+    fn get_pick_list(
+        &self,
+        ctx: &ServiceContext,
+        id: &str,
+    ) -> Result<Option<PickList>, RepositoryError> {
+        get_pick_list(ctx, id)
+    }
+
+    /// Newest first
+    fn get_pick_lists(
+        &self,
+        ctx: &ServiceContext,
+        status: Option<PickListStatus>,
+    ) -> Result<Vec<PickList>, RepositoryError> {
+        get_pick_lists(ctx, status)
+    }
+
+    fn generate_pick_list(
+        &self,
+        ctx: &ServiceContext,
+        input: GeneratePickList,
+    ) -> Result<PickList, GeneratePickListError> {
+        generate_pick_list(ctx, input)
+    }
+
+    fn confirm_pick_list_line(
+        &self,
+        ctx: &ServiceContext,
+        input: ConfirmPickListLine,
+    ) -> Result<ConfirmPickListLineResult, ConfirmPickListLineError> {
+        confirm_pick_list_line(ctx, input)
+    }
+
+    fn complete_pick_list(
+        &self,
+        ctx: &ServiceContext,
+        id: String,
+    ) -> Result<PickList, CompletePickListError> {
+        complete_pick_list(ctx, id)
+    }
+
+    fn get_pick_path(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Vec<PickPathLocationRow>, RepositoryError> {
+        PickPathLocationRowRepository::new(&ctx.connection).find_many_by_store_id(&ctx.store_id)
+    }
+
+    fn set_pick_path(
+        &self,
+        ctx: &ServiceContext,
+        input: SetPickPath,
+    ) -> Result<Vec<PickPathLocationRow>, SetPickPathError> {
+        set_pick_path(ctx, input)
+    }
+}
+
+pub struct PickListService {}
+impl PickListServiceTrait for PickListService {}
+
+/// Pick list of the ctx store
+pub fn get_pick_list(ctx: &ServiceContext, id: &str) -> Result<Option<PickList>, RepositoryError> {
+    let Some(pick_list) = PickListRowRepository::new(&ctx.connection).find_one_by_id(id)? else {
+        return Ok(None);
+    };
+    if pick_list.store_id != ctx.store_id {
+        return Ok(None);
+    }
+    let lines = PickListLineRowRepository::new(&ctx.connection).find_many_by_pick_list_id(id)?;
+
+    Ok(Some(PickList { pick_list, lines }))
+}
+
+pub fn get_pick_lists(
+    ctx: &ServiceContext,
+    status: Option<PickListStatus>,
+) -> Result<Vec<PickList>, RepositoryError> {
+    let pick_lists =
+        PickListRowRepository::new(&ctx.connection).find_many_by_store_id(&ctx.store_id, status)?;
+    let line_repository = PickListLineRowRepository::new(&ctx.connection);
+
+    pick_lists
+        .into_iter()
+        .map(|pick_list| {
+            let lines = line_repository.find_many_by_pick_list_id(&pick_list.id)?;
+            Ok(PickList { pick_list, lines })
+        })
+        .collect()
+}
@@ -0,0 +1,101 @@
+use std::collections::{HashMap, HashSet};
+
+use repository::{
+    LocationRowRepository, PickPathLocationRow, PickPathLocationRowRepository, RepositoryError,
+    StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SetPickPath {
+    /// Locations of the store in the order they are walked past, replaces the existing path
+    pub location_ids: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SetPickPathError {
+    LocationDoesNotExist(String),
+    NotThisStoreLocation(String),
+    LocationIsRepeated(String),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for SetPickPathError {
+    fn from(error: RepositoryError) -> Self {
+        SetPickPathError::DatabaseError(error)
+    }
+}
+
+pub fn set_pick_path(
+    ctx: &ServiceContext,
+    input: SetPickPath,
+) -> Result<Vec<PickPathLocationRow>, SetPickPathError> {
+    let pick_path = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let mut seen = HashSet::new();
+            for location_id in &input.location_ids {
+                let location = LocationRowRepository::new(connection)
+                    .find_one_by_id(location_id)?
+                    .ok_or_else(|| SetPickPathError::LocationDoesNotExist(location_id.clone()))?;
+                if location.store_id != ctx.store_id {
+                    return Err(SetPickPathError::NotThisStoreLocation(location_id.clone()));
+                }
+                if !seen.insert(location_id) {
+                    return Err(SetPickPathError::LocationIsRepeated(location_id.clone()));
+                }
+            }
+
+            let repository = PickPathLocationRowRepository::new(connection);
+            repository.delete_by_store_id(&ctx.store_id)?;
+
+            let mut pick_path = Vec::new();
+            for (sort_order, location_id) in input.location_ids.into_iter().enumerate() {
+                let row = PickPathLocationRow {
+                    id: uuid(),
+                    store_id: ctx.store_id.clone(),
+                    location_id,
+                    sort_order: sort_order as i32,
+                };
+                repository.upsert_one(&row)?;
+                pick_path.push(row);
+            }
+
+            Ok(pick_path)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(pick_path)
+}
+
+type PickOrder = (bool, Option<i32>, bool, Option<String>);
+
+/// Sort key of a location along the pick path: locations on the path in path order, then other
+/// locations by code, then no location
+pub(crate) fn location_pick_order(
+    pick_path: &HashMap<String, i32>,
+    location_id: Option<&str>,
+    location_code: Option<&str>,
+) -> PickOrder {
+    let position = location_id.and_then(|location_id| pick_path.get(location_id).copied());
+    (
+        position.is_none(),
+        position,
+        location_code.is_none(),
+        location_code.map(str::to_string),
+    )
+}
+
+/// Sort order of each location on the pick path of the store
+pub(crate) fn get_pick_path_positions(
+    connection: &StorageConnection,
+    store_id: &str,
+) -> Result<HashMap<String, i32>, RepositoryError> {
+    Ok(PickPathLocationRowRepository::new(connection)
+        .find_many_by_store_id(store_id)?
+        .into_iter()
+        .map(|row| (row.location_id, row.sort_order))
+        .collect())
+}
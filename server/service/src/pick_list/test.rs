@@ -0,0 +1,298 @@
+#[cfg(test)]
+mod pick_list {
+    use repository::{
+        mock::{
+            mock_location_1, mock_location_3, mock_location_in_another_store, mock_name_a,
+            mock_store_a, MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        EqualFilter, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineRow, InvoiceLineType,
+        InvoiceRow, InvoiceRowRepository, InvoiceStatus, InvoiceType, ItemRow, ItemType,
+        PickListStatus, StockLineRow, StockLineRowRepository,
+    };
+    use util::inline_init;
+
+    use crate::{
+        pick_list::{
+            CompletePickListError, ConfirmPickListLine, ConfirmPickListLineError, GeneratePickList,
+            GeneratePickListError, SetPickPath, SetPickPathError,
+        },
+        service_provider::ServiceProvider,
+        test_helpers::stock_line_expiring_in,
+    };
+
+    fn item() -> ItemRow {
+        inline_init(|r: &mut ItemRow| {
+            r.id = "pick_item".to_string();
+            r.code = "pick_item".to_string();
+            r.name = "Pick item".to_string();
+            r.r#type = ItemType::Stock;
+        })
+    }
+
+    fn stock_line(id: &str, location_id: Option<String>, expiry_days: i64) -> StockLineRow {
+        // Half of the packs are allocated to a shipment
+        inline_init(|r: &mut StockLineRow| {
+            *r = stock_line_expiring_in(id, &item().id, location_id, expiry_days);
+            r.available_number_of_packs = 5.0;
+        })
+    }
+
+    fn shipment(id: &str, status: InvoiceStatus) -> InvoiceRow {
+        inline_init(|r: &mut InvoiceRow| {
+            r.id = id.to_string();
+            r.name_link_id = mock_name_a().id;
+            r.store_id = mock_store_a().id;
+            r.r#type = InvoiceType::OutboundShipment;
+            r.status = status;
+        })
+    }
+
+    fn shipment_line(invoice_id: &str, stock_line: StockLineRow) -> InvoiceLineRow {
+        inline_init(|r: &mut InvoiceLineRow| {
+            r.id = format!("{invoice_id}_line");
+            r.invoice_id = invoice_id.to_string();
+            r.item_link_id = item().id;
+            r.item_code = item().code;
+            r.item_name = item().name;
+            r.r#type = InvoiceLineType::StockOut;
+            r.location_id = stock_line.location_id;
+            r.stock_line_id = Some(stock_line.id);
+            r.pack_size = 1.0;
+            r.number_of_packs = 5.0;
+        })
+    }
+
+    #[actix_rt::test]
+    async fn pick_list() {
+        let location_1_stock = stock_line("location_1_stock", Some(mock_location_1().id), 100);
+        let location_3_stock = stock_line("location_3_stock", Some(mock_location_3().id), 200);
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "pick_list",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.items = vec![item()];
+                r.stock_lines = vec![
+                    location_1_stock.clone(),
+                    location_3_stock.clone(),
+                    stock_line_expiring_in("no_location_stock", &item().id, None, 300),
+                ];
+                r.invoices = vec![
+                    shipment("shipment_a", InvoiceStatus::Allocated),
+                    shipment("shipment_b", InvoiceStatus::Allocated),
+                    shipment("shipment_new", InvoiceStatus::New),
+                ];
+                r.invoice_lines = vec![
+                    shipment_line("shipment_a", location_1_stock.clone()),
+                    shipment_line("shipment_b", location_3_stock.clone()),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = &service_provider.pick_list_service;
+
+        // Pick path
+        assert_eq!(
+            service.set_pick_path(
+                &context,
+                SetPickPath {
+                    location_ids: vec![mock_location_in_another_store().id],
+                }
+            ),
+            Err(SetPickPathError::NotThisStoreLocation(
+                mock_location_in_another_store().id
+            ))
+        );
+        assert_eq!(
+            service.set_pick_path(
+                &context,
+                SetPickPath {
+                    location_ids: vec![mock_location_3().id, mock_location_3().id],
+                }
+            ),
+            Err(SetPickPathError::LocationIsRepeated(mock_location_3().id))
+        );
+        service
+            .set_pick_path(
+                &context,
+                SetPickPath {
+                    location_ids: vec![mock_location_3().id, mock_location_1().id],
+                },
+            )
+            .unwrap();
+        assert_eq!(service.get_pick_path(&context).unwrap().len(), 2);
+
+        // Generate
+        assert_eq!(
+            service.generate_pick_list(
+                &context,
+                GeneratePickList {
+                    id: "pick_list".to_string(),
+                    invoice_ids: vec![],
+                }
+            ),
+            Err(GeneratePickListError::NoShipmentsSelected)
+        );
+        assert_eq!(
+            service.generate_pick_list(
+                &context,
+                GeneratePickList {
+                    id: "pick_list".to_string(),
+                    invoice_ids: vec!["shipment_new".to_string()],
+                }
+            ),
+            Err(GeneratePickListError::InvoiceIsNotAllocated(
+                "shipment_new".to_string()
+            ))
+        );
+
+        // Wave pick, in pick path order
+        let pick_list = service
+            .generate_pick_list(
+                &context,
+                GeneratePickList {
+                    id: "pick_list".to_string(),
+                    invoice_ids: vec!["shipment_a".to_string(), "shipment_b".to_string()],
+                },
+            )
+            .unwrap();
+        let invoice_ids: Vec<&str> = pick_list
+            .lines
+            .iter()
+            .map(|line| line.invoice_id.as_str())
+            .collect();
+        assert_eq!(invoice_ids, vec!["shipment_b", "shipment_a"]);
+        assert_eq!(
+            service.generate_pick_list(
+                &context,
+                GeneratePickList {
+                    id: "another_pick_list".to_string(),
+                    invoice_ids: vec!["shipment_a".to_string()],
+                }
+            ),
+            Err(GeneratePickListError::InvoiceAlreadyOnPickList(
+                "shipment_a".to_string()
+            ))
+        );
+
+        // Confirm
+        let shipment_b_line = pick_list.lines[0].clone();
+        let shipment_a_line = pick_list.lines[1].clone();
+        assert_eq!(
+            service.confirm_pick_list_line(
+                &context,
+                ConfirmPickListLine {
+                    id: shipment_b_line.id.clone(),
+                    picked_number_of_packs: 6.0,
+                }
+            ),
+            Err(ConfirmPickListLineError::PickedMoreThanAllocated)
+        );
+        service
+            .confirm_pick_list_line(
+                &context,
+                ConfirmPickListLine {
+                    id: shipment_b_line.id.clone(),
+                    picked_number_of_packs: 5.0,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            service.confirm_pick_list_line(
+                &context,
+                ConfirmPickListLine {
+                    id: shipment_b_line.id,
+                    picked_number_of_packs: 5.0,
+                }
+            ),
+            Err(ConfirmPickListLineError::LineAlreadyConfirmed)
+        );
+
+        // Short pick is reallocated from the next stock line by expiry
+        let result = service
+            .confirm_pick_list_line(
+                &context,
+                ConfirmPickListLine {
+                    id: shipment_a_line.id,
+                    picked_number_of_packs: 2.0,
+                },
+            )
+            .unwrap();
+        assert_eq!(result.unallocated_quantity, 0.0);
+        assert_eq!(result.reallocated_lines.len(), 1);
+        let reallocated_line = &result.reallocated_lines[0];
+        assert_eq!(reallocated_line.line_number, 3);
+        assert_eq!(reallocated_line.number_of_packs, 3.0);
+        assert_eq!(
+            reallocated_line.stock_line_id,
+            Some(location_3_stock.id.clone())
+        );
+
+        let shipment_a_lines = InvoiceLineRepository::new(&connection)
+            .query_by_filter(
+                InvoiceLineFilter::new().invoice_id(EqualFilter::equal_to("shipment_a")),
+            )
+            .unwrap();
+        let packs = |stock_line_id: &str| {
+            shipment_a_lines
+                .iter()
+                .find(|line| line.invoice_line_row.stock_line_id.as_deref() == Some(stock_line_id))
+                .map(|line| line.invoice_line_row.number_of_packs)
+        };
+        assert_eq!(packs(&location_1_stock.id), Some(2.0));
+        assert_eq!(packs(&location_3_stock.id), Some(3.0));
+        // Short picked packs aren't returned to available stock
+        let short_picked_stock = StockLineRowRepository::new(&connection)
+            .find_one_by_id(&location_1_stock.id)
+            .unwrap()
+            .unwrap();
+        assert!(short_picked_stock.on_hold);
+
+        // Complete
+        assert!(matches!(
+            service.complete_pick_list(&context, "pick_list".to_string()),
+            Err(CompletePickListError::LinesNotConfirmed(lines)) if lines.len() == 1
+        ));
+        service
+            .confirm_pick_list_line(
+                &context,
+                ConfirmPickListLine {
+                    id: reallocated_line.id.clone(),
+                    picked_number_of_packs: 3.0,
+                },
+            )
+            .unwrap();
+        let completed = service
+            .complete_pick_list(&context, "pick_list".to_string())
+            .unwrap();
+        assert_eq!(completed.pick_list.status, PickListStatus::Completed);
+        for invoice_id in ["shipment_a", "shipment_b"] {
+            let invoice = InvoiceRowRepository::new(&connection)
+                .find_one_by_id(invoice_id)
+                .unwrap()
+                .unwrap();
+            assert_eq!(invoice.status, InvoiceStatus::Picked);
+        }
+        assert_eq!(
+            service.complete_pick_list(&context, "pick_list".to_string()),
+            Err(CompletePickListError::PickListIsCompleted)
+        );
+
+        // Shipments can be picked again once their pick list is completed
+        assert!(matches!(
+            service.generate_pick_list(
+                &context,
+                GeneratePickList {
+                    id: "another_pick_list".to_string(),
+                    invoice_ids: vec!["shipment_a".to_string()],
+                }
+            ),
+            Err(GeneratePickListError::InvoiceIsNotAllocated(_))
+        ));
+    }
+}
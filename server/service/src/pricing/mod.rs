@@ -1,9 +1,18 @@
 use crate::service_provider::ServiceContext;
 use item_price::{get_pricing_for_item, ItemPrice, ItemPriceLookup};
-use repository::RepositoryError;
+use price_list::{
+    delete_price_list, get_price_lists, set_name_price_category, upsert_price_list, PriceList,
+    SetNamePriceCategoryError, UpsertPriceList, UpsertPriceListError,
+};
+use repository::{NamePriceCategoryRow, RepositoryError, TaxRuleRow, TaxRuleRowRepository};
+use resolve_price::{resolve_price, ResolvePrice, ResolvedPrice};
+use tax_rule::{delete_tax_rule, upsert_tax_rule, UpsertTaxRule, UpsertTaxRuleError};
 
 pub mod calculate_sell_price;
 pub mod item_price;
+pub mod price_list;
+pub mod resolve_price;
+pub mod tax_rule;
 
 pub trait PricingServiceTrait: Sync + Send {
     fn get_pricing_for_item(
@@ -13,6 +22,61 @@ pub trait PricingServiceTrait: Sync + Send {
     ) -> Result<ItemPrice, RepositoryError> {
         get_pricing_for_item(ctx, input)
     }
+
+    /// Price and tax for a line, from price lists, tax rules and the default price list
+    fn resolve_price(
+        &self,
+        ctx: &ServiceContext,
+        input: ResolvePrice,
+    ) -> Result<ResolvedPrice, RepositoryError> {
+        resolve_price(ctx, input)
+    }
+
+    /// Price lists of the ctx store, including lists for all stores
+    fn get_price_lists(&self, ctx: &ServiceContext) -> Result<Vec<PriceList>, RepositoryError> {
+        get_price_lists(ctx)
+    }
+
+    fn upsert_price_list(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertPriceList,
+    ) -> Result<PriceList, UpsertPriceListError> {
+        upsert_price_list(ctx, input)
+    }
+
+    fn delete_price_list(
+        &self,
+        ctx: &ServiceContext,
+        id: String,
+    ) -> Result<String, RepositoryError> {
+        delete_price_list(ctx, id)
+    }
+
+    fn get_tax_rules(&self, ctx: &ServiceContext) -> Result<Vec<TaxRuleRow>, RepositoryError> {
+        TaxRuleRowRepository::new(&ctx.connection).find_all()
+    }
+
+    fn upsert_tax_rule(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertTaxRule,
+    ) -> Result<TaxRuleRow, UpsertTaxRuleError> {
+        upsert_tax_rule(ctx, input)
+    }
+
+    fn delete_tax_rule(&self, ctx: &ServiceContext, id: String) -> Result<String, RepositoryError> {
+        delete_tax_rule(ctx, id)
+    }
+
+    fn set_name_price_category(
+        &self,
+        ctx: &ServiceContext,
+        name_id: String,
+        price_category: Option<String>,
+    ) -> Result<Option<NamePriceCategoryRow>, SetNamePriceCategoryError> {
+        set_name_price_category(ctx, name_id, price_category)
+    }
 }
 
 pub struct PricingService {}
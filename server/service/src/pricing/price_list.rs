@@ -0,0 +1,314 @@
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+use repository::{
+    ItemRowRepository, NamePriceCategoryRow, NamePriceCategoryRowRepository, NameRowRepository,
+    NameTagRowRepository, PriceListLineRow, PriceListLineRowRepository, PriceListRow,
+    PriceListRowRepository, RepositoryError, StorageConnection, StoreRowRepository,
+};
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceList {
+    pub price_list_row: PriceListRow,
+    pub lines: Vec<PriceListLineRow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UpsertPriceListLine {
+    pub id: String,
+    pub item_id: String,
+    pub min_quantity: f64,
+    pub price_per_unit: Option<f64>,
+    pub discount_percentage: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UpsertPriceList {
+    pub id: String,
+    pub name: String,
+    pub store_id: Option<String>,
+    pub name_id: Option<String>,
+    pub name_tag_id: Option<String>,
+    pub price_category: Option<String>,
+    pub valid_from: Option<NaiveDate>,
+    pub valid_to: Option<NaiveDate>,
+    pub priority: i32,
+    pub is_active: bool,
+    /// Replaces the existing lines of the price list
+    pub lines: Vec<UpsertPriceListLine>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertPriceListError {
+    NameCannotBeEmpty,
+    StoreDoesNotExist,
+    NameDoesNotExist,
+    NameTagDoesNotExist,
+    ValidToBeforeValidFrom,
+    /// Line id
+    ItemDoesNotExist(String),
+    /// Line id, a line needs a price or a discount
+    LineHasNoPrice(String),
+    /// Line id
+    PriceCannotBeNegative(String),
+    /// Line id, discount must be between 0 and 100
+    InvalidDiscountPercentage(String),
+    /// Line id
+    MinQuantityCannotBeNegative(String),
+    /// Line id, an item can only have one line per minimum quantity
+    DuplicateQuantityBreak(String),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for UpsertPriceListError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertPriceListError::DatabaseError(error)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SetNamePriceCategoryError {
+    NameDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for SetNamePriceCategoryError {
+    fn from(error: RepositoryError) -> Self {
+        SetNamePriceCategoryError::DatabaseError(error)
+    }
+}
+
+/// Price categories are matched case insensitively, empty is the same as not set
+pub(crate) fn normalise_price_category(price_category: Option<String>) -> Option<String> {
+    price_category
+        .map(|category| category.trim().to_uppercase())
+        .filter(|category| !category.is_empty())
+}
+
+pub fn get_price_lists(ctx: &ServiceContext) -> Result<Vec<PriceList>, RepositoryError> {
+    let price_lists =
+        PriceListRowRepository::new(&ctx.connection).find_many_by_store_id(&ctx.store_id)?;
+    let ids: Vec<String> = price_lists.iter().map(|list| list.id.clone()).collect();
+    let mut lines =
+        PriceListLineRowRepository::new(&ctx.connection).find_many_by_price_list_ids(&ids)?;
+
+    Ok(price_lists
+        .into_iter()
+        .map(|price_list_row| {
+            let (list_lines, other_lines) = lines
+                .drain(..)
+                .partition(|line| line.price_list_id == price_list_row.id);
+            lines = other_lines;
+            PriceList {
+                price_list_row,
+                lines: list_lines,
+            }
+        })
+        .collect())
+}
+
+pub fn upsert_price_list(
+    ctx: &ServiceContext,
+    input: UpsertPriceList,
+) -> Result<PriceList, UpsertPriceListError> {
+    let price_list = ctx
+        .connection
+        .transaction_sync(|connection| -> Result<PriceList, UpsertPriceListError> {
+            validate(connection, &input)?;
+            let price_list = generate(input);
+
+            PriceListRowRepository::new(connection).upsert_one(&price_list.price_list_row)?;
+            // Only lines removed from the list are deleted, so unchanged lines aren't re-synced as
+            // a delete and an upsert
+            let line_repo = PriceListLineRowRepository::new(connection);
+            let existing_lines = line_repo
+                .find_many_by_price_list_ids(std::slice::from_ref(&price_list.price_list_row.id))?;
+            for existing_line in existing_lines {
+                if !price_list
+                    .lines
+                    .iter()
+                    .any(|line| line.id == existing_line.id)
+                {
+                    line_repo.delete(&existing_line.id)?;
+                }
+            }
+            for line in &price_list.lines {
+                line_repo.upsert_one(line)?;
+            }
+
+            Ok(price_list)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(price_list)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    input: &UpsertPriceList,
+) -> Result<(), UpsertPriceListError> {
+    use UpsertPriceListError as Error;
+
+    if input.name.trim().is_empty() {
+        return Err(Error::NameCannotBeEmpty);
+    }
+    if let Some(store_id) = &input.store_id {
+        if StoreRowRepository::new(connection)
+            .find_one_by_id(store_id)?
+            .is_none()
+        {
+            return Err(Error::StoreDoesNotExist);
+        }
+    }
+    if let Some(name_id) = &input.name_id {
+        if NameRowRepository::new(connection)
+            .find_one_by_id(name_id)?
+            .is_none()
+        {
+            return Err(Error::NameDoesNotExist);
+        }
+    }
+    if let Some(name_tag_id) = &input.name_tag_id {
+        if NameTagRowRepository::new(connection)
+            .find_one_by_id(name_tag_id)?
+            .is_none()
+        {
+            return Err(Error::NameTagDoesNotExist);
+        }
+    }
+    if let (Some(valid_from), Some(valid_to)) = (input.valid_from, input.valid_to) {
+        if valid_to < valid_from {
+            return Err(Error::ValidToBeforeValidFrom);
+        }
+    }
+
+    let item_repo = ItemRowRepository::new(connection);
+    let mut quantity_breaks = HashSet::new();
+    for line in &input.lines {
+        if item_repo.find_active_by_id(&line.item_id)?.is_none() {
+            return Err(Error::ItemDoesNotExist(line.id.clone()));
+        }
+        if line.price_per_unit.is_none() && line.discount_percentage.is_none() {
+            return Err(Error::LineHasNoPrice(line.id.clone()));
+        }
+        if line.price_per_unit.is_some_and(|price| price < 0.0) {
+            return Err(Error::PriceCannotBeNegative(line.id.clone()));
+        }
+        if line
+            .discount_percentage
+            .is_some_and(|discount| !(0.0..=100.0).contains(&discount))
+        {
+            return Err(Error::InvalidDiscountPercentage(line.id.clone()));
+        }
+        if line.min_quantity < 0.0 {
+            return Err(Error::MinQuantityCannotBeNegative(line.id.clone()));
+        }
+        if !quantity_breaks.insert((line.item_id.clone(), line.min_quantity.to_bits())) {
+            return Err(Error::DuplicateQuantityBreak(line.id.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+fn generate(
+    UpsertPriceList {
+        id,
+        name,
+        store_id,
+        name_id,
+        name_tag_id,
+        price_category,
+        valid_from,
+        valid_to,
+        priority,
+        is_active,
+        lines,
+    }: UpsertPriceList,
+) -> PriceList {
+    PriceList {
+        lines: lines
+            .into_iter()
+            .map(
+                |UpsertPriceListLine {
+                     id: line_id,
+                     item_id,
+                     min_quantity,
+                     price_per_unit,
+                     discount_percentage,
+                 }| PriceListLineRow {
+                    id: line_id,
+                    price_list_id: id.clone(),
+                    item_id,
+                    min_quantity,
+                    price_per_unit,
+                    discount_percentage,
+                },
+            )
+            .collect(),
+        price_list_row: PriceListRow {
+            id,
+            name: name.trim().to_string(),
+            store_id,
+            name_id,
+            name_tag_id,
+            price_category: normalise_price_category(price_category),
+            valid_from,
+            valid_to,
+            priority,
+            is_active,
+        },
+    }
+}
+
+pub fn delete_price_list(ctx: &ServiceContext, id: String) -> Result<String, RepositoryError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            PriceListLineRowRepository::new(connection).delete_by_price_list_id(&id)?;
+            PriceListRowRepository::new(connection).delete(&id)?;
+            Ok::<(), RepositoryError>(())
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(id)
+}
+
+/// Sets or clears (when None) the price category of a customer or patient
+pub fn set_name_price_category(
+    ctx: &ServiceContext,
+    name_id: String,
+    price_category: Option<String>,
+) -> Result<Option<NamePriceCategoryRow>, SetNamePriceCategoryError> {
+    let row = ctx
+        .connection
+        .transaction_sync(|connection| {
+            if NameRowRepository::new(connection)
+                .find_one_by_id(&name_id)?
+                .is_none()
+            {
+                return Err(SetNamePriceCategoryError::NameDoesNotExist);
+            }
+
+            let repo = NamePriceCategoryRowRepository::new(connection);
+            let row = match normalise_price_category(price_category) {
+                Some(price_category) => {
+                    let row = NamePriceCategoryRow {
+                        name_id,
+                        price_category,
+                    };
+                    repo.upsert_one(&row)?;
+                    Some(row)
+                }
+                None => {
+                    repo.delete(&name_id)?;
+                    None
+                }
+            };
+
+            Ok(row)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(row)
+}
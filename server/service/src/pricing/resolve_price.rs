@@ -0,0 +1,251 @@
+use chrono::{Local, NaiveDate};
+use repository::{
+    NamePriceCategoryRowRepository, NameTagJoinRepository, PriceListLineRow,
+    PriceListLineRowRepository, PriceListRow, PriceListRowRepository, RepositoryError, TaxRuleRow,
+    TaxRuleRowRepository,
+};
+
+use crate::service_provider::ServiceContext;
+
+use super::item_price::{get_pricing_for_item, ItemPrice, ItemPriceLookup};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResolvePrice {
+    pub item_id: String,
+    pub name_id: Option<String>,
+    /// Number of units on the line, used to select quantity breaks
+    pub quantity: f64,
+    /// Defaults to today
+    pub date: Option<NaiveDate>,
+}
+
+/// Rule that set the price, in order of precedence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PriceRuleType {
+    /// Price list for this customer
+    CustomerPriceList,
+    /// Price list for a name tag of the customer
+    NameTagPriceList,
+    /// Price list for the price category of the customer, e.g. insured patients
+    PriceCategoryPriceList,
+    /// Price list without a customer, name tag or price category
+    GeneralPriceList,
+    /// Default price list master list, with the largest discount list discount
+    DefaultPriceList,
+    /// Price should be taken from the stock line
+    NoPrice,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedPrice {
+    pub item_id: String,
+    pub rule: PriceRuleType,
+    pub price_list: Option<PriceListRow>,
+    /// Includes the quantity break that applied
+    pub price_list_line: Option<PriceListLineRow>,
+    /// Price before discount
+    pub base_price_per_unit: Option<f64>,
+    pub discount_percentage: Option<f64>,
+    /// Only populated if there is a base price
+    pub price_per_unit: Option<f64>,
+    pub price_category: Option<String>,
+    pub tax_rule: Option<TaxRuleRow>,
+}
+
+impl ResolvedPrice {
+    pub fn tax_percentage(&self) -> Option<f64> {
+        self.tax_rule.as_ref().map(|rule| rule.tax_percentage)
+    }
+
+    /// Tax of a rule set for the item, general rules (price category only or neither) don't
+    /// override tax set on an invoice
+    pub fn item_tax_percentage(&self) -> Option<f64> {
+        self.tax_rule
+            .as_ref()
+            .filter(|rule| rule.item_id.is_some())
+            .map(|rule| rule.tax_percentage)
+    }
+}
+
+impl From<&ResolvedPrice> for ItemPrice {
+    fn from(resolved: &ResolvedPrice) -> Self {
+        ItemPrice {
+            item_id: resolved.item_id.clone(),
+            default_price_per_unit: resolved.base_price_per_unit,
+            discount_percentage: resolved.discount_percentage,
+            calculated_price_per_unit: resolved.price_per_unit,
+        }
+    }
+}
+
+pub fn resolve_price(
+    ctx: &ServiceContext,
+    input: ResolvePrice,
+) -> Result<ResolvedPrice, RepositoryError> {
+    let date = input.date.unwrap_or_else(|| Local::now().date_naive());
+
+    let (price_category, name_tag_ids) = match &input.name_id {
+        Some(name_id) => (
+            NamePriceCategoryRowRepository::new(&ctx.connection)
+                .find_one_by_name_id(name_id)?
+                .map(|row| row.price_category),
+            NameTagJoinRepository::new(&ctx.connection)
+                .find_many_by_name_id(name_id)?
+                .into_iter()
+                .map(|join| join.name_tag_id)
+                .collect(),
+        ),
+        None => (None, Vec::new()),
+    };
+
+    let tax_rule = find_tax_rule(ctx, &input.item_id, &price_category, date)?;
+    let default_pricing = get_pricing_for_item(
+        ctx,
+        ItemPriceLookup {
+            item_id: input.item_id.clone(),
+            customer_name_id: input.name_id.clone(),
+        },
+    )?;
+
+    // Repository returns lists by priority, sort is stable so priority is kept within each rule
+    let mut price_lists: Vec<(PriceRuleType, PriceListRow)> =
+        PriceListRowRepository::new(&ctx.connection)
+            .find_many_by_store_id(&ctx.store_id)?
+            .into_iter()
+            .filter(|list| list.is_active && list.is_valid_on(date))
+            .filter_map(|list| {
+                match_price_list(
+                    &list,
+                    input.name_id.as_deref(),
+                    &name_tag_ids,
+                    price_category.as_deref(),
+                )
+                .map(|rule| (rule, list))
+            })
+            .collect();
+    price_lists.sort_by_key(|(rule, _)| *rule);
+
+    let price_list_ids: Vec<String> = price_lists
+        .iter()
+        .map(|(_, list)| list.id.clone())
+        .collect();
+    let lines = PriceListLineRowRepository::new(&ctx.connection)
+        .find_many_by_item_id(&input.item_id, &price_list_ids)?;
+
+    for (rule, price_list) in price_lists {
+        // Largest quantity break the line qualifies for
+        let line = lines
+            .iter()
+            .filter(|line| {
+                line.price_list_id == price_list.id && line.min_quantity <= input.quantity
+            })
+            .max_by(|a, b| a.min_quantity.total_cmp(&b.min_quantity));
+        let Some(line) = line else {
+            continue;
+        };
+
+        let base_price_per_unit = line
+            .price_per_unit
+            .or(default_pricing.default_price_per_unit);
+        let discount_percentage = line.discount_percentage;
+
+        return Ok(ResolvedPrice {
+            item_id: input.item_id,
+            rule,
+            price_per_unit: base_price_per_unit
+                .map(|price| apply_discount(price, discount_percentage)),
+            base_price_per_unit,
+            discount_percentage,
+            price_list_line: Some(line.clone()),
+            price_list: Some(price_list),
+            price_category,
+            tax_rule,
+        });
+    }
+
+    let ItemPrice {
+        item_id,
+        default_price_per_unit,
+        discount_percentage,
+        calculated_price_per_unit,
+    } = default_pricing;
+    let rule = if default_price_per_unit.is_some() || discount_percentage.is_some() {
+        PriceRuleType::DefaultPriceList
+    } else {
+        PriceRuleType::NoPrice
+    };
+
+    Ok(ResolvedPrice {
+        item_id,
+        rule,
+        price_list: None,
+        price_list_line: None,
+        base_price_per_unit: default_price_per_unit,
+        discount_percentage,
+        price_per_unit: calculated_price_per_unit,
+        price_category,
+        tax_rule,
+    })
+}
+
+fn apply_discount(price: f64, discount_percentage: Option<f64>) -> f64 {
+    price * (1.0 - discount_percentage.unwrap_or(0.0) / 100.0)
+}
+
+/// All targets set on the list need to match the customer
+fn match_price_list(
+    list: &PriceListRow,
+    name_id: Option<&str>,
+    name_tag_ids: &[String],
+    price_category: Option<&str>,
+) -> Option<PriceRuleType> {
+    if list
+        .name_id
+        .as_ref()
+        .is_some_and(|list_name_id| Some(list_name_id.as_str()) != name_id)
+    {
+        return None;
+    }
+    if list
+        .name_tag_id
+        .as_ref()
+        .is_some_and(|name_tag_id| !name_tag_ids.contains(name_tag_id))
+    {
+        return None;
+    }
+    if list
+        .price_category
+        .as_ref()
+        .is_some_and(|category| Some(category.as_str()) != price_category)
+    {
+        return None;
+    }
+
+    let rule = if list.name_id.is_some() {
+        PriceRuleType::CustomerPriceList
+    } else if list.name_tag_id.is_some() {
+        PriceRuleType::NameTagPriceList
+    } else if list.price_category.is_some() {
+        PriceRuleType::PriceCategoryPriceList
+    } else {
+        PriceRuleType::GeneralPriceList
+    };
+    Some(rule)
+}
+
+/// Most specific rule wins: item and price category, item, price category, then neither
+fn find_tax_rule(
+    ctx: &ServiceContext,
+    item_id: &str,
+    price_category: &Option<String>,
+    date: NaiveDate,
+) -> Result<Option<TaxRuleRow>, RepositoryError> {
+    let rule = TaxRuleRowRepository::new(&ctx.connection)
+        .find_many_by_item_id(item_id)?
+        .into_iter()
+        .filter(|rule| rule.is_valid_on(date))
+        .filter(|rule| rule.price_category.is_none() || &rule.price_category == price_category)
+        .max_by_key(|rule| (rule.item_id.is_some(), rule.price_category.is_some()));
+
+    Ok(rule)
+}
@@ -0,0 +1,85 @@
+use chrono::NaiveDate;
+use repository::{ItemRowRepository, RepositoryError, TaxRuleRow, TaxRuleRowRepository};
+
+use crate::service_provider::ServiceContext;
+
+use super::price_list::normalise_price_category;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UpsertTaxRule {
+    pub id: String,
+    pub item_id: Option<String>,
+    pub price_category: Option<String>,
+    pub tax_percentage: f64,
+    pub valid_from: Option<NaiveDate>,
+    pub valid_to: Option<NaiveDate>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertTaxRuleError {
+    ItemDoesNotExist,
+    /// Tax must be between 0 and 100
+    InvalidTaxPercentage,
+    ValidToBeforeValidFrom,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for UpsertTaxRuleError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertTaxRuleError::DatabaseError(error)
+    }
+}
+
+pub fn upsert_tax_rule(
+    ctx: &ServiceContext,
+    input: UpsertTaxRule,
+) -> Result<TaxRuleRow, UpsertTaxRuleError> {
+    let rule = ctx
+        .connection
+        .transaction_sync(|connection| {
+            if let Some(item_id) = &input.item_id {
+                if ItemRowRepository::new(connection)
+                    .find_active_by_id(item_id)?
+                    .is_none()
+                {
+                    return Err(UpsertTaxRuleError::ItemDoesNotExist);
+                }
+            }
+            if !(0.0..=100.0).contains(&input.tax_percentage) {
+                return Err(UpsertTaxRuleError::InvalidTaxPercentage);
+            }
+            if let (Some(valid_from), Some(valid_to)) = (input.valid_from, input.valid_to) {
+                if valid_to < valid_from {
+                    return Err(UpsertTaxRuleError::ValidToBeforeValidFrom);
+                }
+            }
+
+            let UpsertTaxRule {
+                id,
+                item_id,
+                price_category,
+                tax_percentage,
+                valid_from,
+                valid_to,
+            } = input;
+            let rule = TaxRuleRow {
+                id,
+                item_id,
+                price_category: normalise_price_category(price_category),
+                tax_percentage,
+                valid_from,
+                valid_to,
+            };
+            TaxRuleRowRepository::new(connection).upsert_one(&rule)?;
+
+            Ok(rule)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(rule)
+}
+
+pub fn delete_tax_rule(ctx: &ServiceContext, id: String) -> Result<String, RepositoryError> {
+    TaxRuleRowRepository::new(&ctx.connection).delete(&id)?;
+    Ok(id)
+}
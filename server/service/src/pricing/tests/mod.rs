@@ -1,2 +1,3 @@
 #[cfg(test)]
 mod item_price;
+mod resolve_price;
@@ -0,0 +1,410 @@
+#[cfg(test)]
+mod query {
+    use chrono::NaiveDate;
+    use repository::mock::{
+        mock_item_a, mock_item_b, mock_name_a, mock_name_b, mock_name_tag_1, mock_stock_line_a,
+        mock_stock_line_b, mock_store_a, mock_user_account_a,
+    };
+    use repository::{mock::MockDataInserts, test_db::setup_all};
+    use repository::{
+        InvoiceRow, InvoiceRowRepository, InvoiceStatus, InvoiceType, NameTagJoinRepository,
+        NameTagJoinRow,
+    };
+
+    use crate::invoice_line::stock_out_line::{
+        InsertStockOutLine, StockOutType, UpdateStockOutLine,
+    };
+    use crate::pricing::price_list::{UpsertPriceList, UpsertPriceListError, UpsertPriceListLine};
+    use crate::pricing::resolve_price::{PriceRuleType, ResolvePrice};
+    use crate::pricing::tax_rule::UpsertTaxRule;
+    use crate::service_provider::ServiceProvider;
+
+    fn price_list(id: &str, lines: Vec<UpsertPriceListLine>) -> UpsertPriceList {
+        UpsertPriceList {
+            id: id.to_string(),
+            name: id.to_string(),
+            is_active: true,
+            lines,
+            ..Default::default()
+        }
+    }
+
+    fn line(id: &str, item_id: &str, min_quantity: f64, price: f64) -> UpsertPriceListLine {
+        UpsertPriceListLine {
+            id: id.to_string(),
+            item_id: item_id.to_string(),
+            min_quantity,
+            price_per_unit: Some(price),
+            discount_percentage: None,
+        }
+    }
+
+    fn date(day: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(2024, 1, day)
+    }
+
+    #[actix_rt::test]
+    async fn resolve_price_precedence() {
+        let (_, _, connection_manager, _) =
+            setup_all("resolve_price_precedence", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.basic_context().unwrap();
+        let service = service_provider.pricing_service;
+
+        let resolve = |name_id: &str, quantity: f64| {
+            service
+                .resolve_price(
+                    &context,
+                    ResolvePrice {
+                        item_id: mock_item_a().id,
+                        name_id: Some(name_id.to_string()),
+                        quantity,
+                        date: date(15),
+                    },
+                )
+                .unwrap()
+        };
+
+        // No price lists
+        let resolved = resolve(&mock_name_a().id, 1.0);
+        assert_eq!(resolved.rule, PriceRuleType::NoPrice);
+        assert_eq!(resolved.price_per_unit, None);
+
+        // General list, with a quantity break from 10 units
+        service
+            .upsert_price_list(
+                &context,
+                price_list(
+                    "general",
+                    vec![
+                        line("general_1", &mock_item_a().id, 0.0, 10.0),
+                        line("general_10", &mock_item_a().id, 10.0, 8.0),
+                    ],
+                ),
+            )
+            .unwrap();
+        let resolved = resolve(&mock_name_a().id, 5.0);
+        assert_eq!(resolved.rule, PriceRuleType::GeneralPriceList);
+        assert_eq!(resolved.price_per_unit, Some(10.0));
+        let resolved = resolve(&mock_name_a().id, 12.0);
+        assert_eq!(resolved.price_per_unit, Some(8.0));
+        assert_eq!(resolved.price_list_line.unwrap().id, "general_10");
+
+        // Price category list applies to names in the category only
+        service
+            .upsert_price_list(
+                &context,
+                UpsertPriceList {
+                    price_category: Some("insured".to_string()),
+                    ..price_list(
+                        "category",
+                        vec![line("category_1", &mock_item_a().id, 0.0, 6.0)],
+                    )
+                },
+            )
+            .unwrap();
+        service
+            .set_name_price_category(&context, mock_name_a().id, Some(" Insured ".to_string()))
+            .unwrap();
+        let resolved = resolve(&mock_name_a().id, 1.0);
+        assert_eq!(resolved.rule, PriceRuleType::PriceCategoryPriceList);
+        assert_eq!(resolved.price_category, Some("INSURED".to_string()));
+        assert_eq!(resolved.price_per_unit, Some(6.0));
+        assert_eq!(
+            resolve(&mock_name_b().id, 1.0).rule,
+            PriceRuleType::GeneralPriceList
+        );
+
+        // Name tag list wins over price category
+        NameTagJoinRepository::new(&context.connection)
+            .upsert_one(&NameTagJoinRow {
+                id: "name_a_tag".to_string(),
+                name_link_id: mock_name_a().id,
+                name_tag_id: mock_name_tag_1().id,
+            })
+            .unwrap();
+        service
+            .upsert_price_list(
+                &context,
+                UpsertPriceList {
+                    name_tag_id: Some(mock_name_tag_1().id),
+                    ..price_list(
+                        "tag",
+                        vec![UpsertPriceListLine {
+                            discount_percentage: Some(50.0),
+                            ..line("tag_1", &mock_item_a().id, 0.0, 4.0)
+                        }],
+                    )
+                },
+            )
+            .unwrap();
+        let resolved = resolve(&mock_name_a().id, 1.0);
+        assert_eq!(resolved.rule, PriceRuleType::NameTagPriceList);
+        assert_eq!(resolved.base_price_per_unit, Some(4.0));
+        assert_eq!(resolved.price_per_unit, Some(2.0));
+
+        // Customer list wins over everything, but only while it's valid
+        service
+            .upsert_price_list(
+                &context,
+                UpsertPriceList {
+                    name_id: Some(mock_name_a().id),
+                    valid_from: date(10),
+                    valid_to: date(20),
+                    ..price_list(
+                        "customer",
+                        vec![line("customer_1", &mock_item_a().id, 0.0, 1.0)],
+                    )
+                },
+            )
+            .unwrap();
+        let resolved = resolve(&mock_name_a().id, 1.0);
+        assert_eq!(resolved.rule, PriceRuleType::CustomerPriceList);
+        assert_eq!(resolved.price_list.unwrap().id, "customer");
+        assert_eq!(resolved.price_per_unit, Some(1.0));
+
+        let resolved = service
+            .resolve_price(
+                &context,
+                ResolvePrice {
+                    item_id: mock_item_a().id,
+                    name_id: Some(mock_name_a().id),
+                    quantity: 1.0,
+                    date: date(21),
+                },
+            )
+            .unwrap();
+        assert_eq!(resolved.rule, PriceRuleType::NameTagPriceList);
+
+        // Lists without a line for the item are skipped
+        let resolved = service
+            .resolve_price(
+                &context,
+                ResolvePrice {
+                    item_id: mock_item_b().id,
+                    name_id: Some(mock_name_a().id),
+                    quantity: 1.0,
+                    date: date(15),
+                },
+            )
+            .unwrap();
+        assert_eq!(resolved.rule, PriceRuleType::NoPrice);
+    }
+
+    #[actix_rt::test]
+    async fn resolve_tax_rule() {
+        let (_, _, connection_manager, _) =
+            setup_all("resolve_tax_rule", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.basic_context().unwrap();
+        let service = service_provider.pricing_service;
+
+        let tax_rule = |id: &str, item_id: Option<String>, category: Option<&str>, tax: f64| {
+            service
+                .upsert_tax_rule(
+                    &context,
+                    UpsertTaxRule {
+                        id: id.to_string(),
+                        item_id,
+                        price_category: category.map(str::to_string),
+                        tax_percentage: tax,
+                        ..Default::default()
+                    },
+                )
+                .unwrap();
+        };
+        let tax_for = |item_id: String| {
+            service
+                .resolve_price(
+                    &context,
+                    ResolvePrice {
+                        item_id,
+                        name_id: Some(mock_name_a().id),
+                        quantity: 1.0,
+                        date: date(15),
+                    },
+                )
+                .unwrap()
+                .tax_percentage()
+        };
+
+        assert_eq!(tax_for(mock_item_a().id), None);
+
+        tax_rule("all", None, None, 15.0);
+        tax_rule("category", None, Some("exempt"), 0.0);
+        tax_rule("item_a", Some(mock_item_a().id), None, 5.0);
+        assert_eq!(tax_for(mock_item_a().id), Some(5.0));
+        assert_eq!(tax_for(mock_item_b().id), Some(15.0));
+
+        // Item rule is more specific than the price category rule
+        service
+            .set_name_price_category(&context, mock_name_a().id, Some("exempt".to_string()))
+            .unwrap();
+        assert_eq!(tax_for(mock_item_a().id), Some(5.0));
+        assert_eq!(tax_for(mock_item_b().id), Some(0.0));
+
+        tax_rule("item_a_exempt", Some(mock_item_a().id), Some("EXEMPT"), 1.0);
+        assert_eq!(tax_for(mock_item_a().id), Some(1.0));
+    }
+
+    #[actix_rt::test]
+    async fn upsert_price_list_errors() {
+        let (_, _, connection_manager, _) =
+            setup_all("upsert_price_list_errors", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.basic_context().unwrap();
+        let service = service_provider.pricing_service;
+
+        assert_eq!(
+            service.upsert_price_list(
+                &context,
+                UpsertPriceList {
+                    valid_from: date(10),
+                    valid_to: date(9),
+                    ..price_list("list", vec![])
+                },
+            ),
+            Err(UpsertPriceListError::ValidToBeforeValidFrom)
+        );
+        assert_eq!(
+            service.upsert_price_list(
+                &context,
+                price_list(
+                    "list",
+                    vec![
+                        line("line_1", &mock_item_a().id, 5.0, 1.0),
+                        line("line_2", &mock_item_a().id, 5.0, 2.0),
+                    ],
+                ),
+            ),
+            Err(UpsertPriceListError::DuplicateQuantityBreak(
+                "line_2".to_string()
+            ))
+        );
+        assert_eq!(
+            service.upsert_price_list(
+                &context,
+                price_list(
+                    "list",
+                    vec![UpsertPriceListLine {
+                        price_per_unit: None,
+                        ..line("line_1", &mock_item_a().id, 0.0, 1.0)
+                    }],
+                ),
+            ),
+            Err(UpsertPriceListError::LineHasNoPrice("line_1".to_string()))
+        );
+    }
+
+    #[actix_rt::test]
+    async fn stock_out_line_pricing() {
+        let (_, connection, connection_manager, _) =
+            setup_all("stock_out_line_pricing", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = &service_provider.pricing_service;
+        let invoice_line_service = &service_provider.invoice_line_service;
+
+        InvoiceRowRepository::new(&connection)
+            .upsert_one(&InvoiceRow {
+                id: "pricing_outbound".to_string(),
+                name_link_id: mock_name_a().id,
+                store_id: mock_store_a().id,
+                r#type: InvoiceType::OutboundShipment,
+                status: InvoiceStatus::New,
+                tax_percentage: Some(12.0),
+                ..Default::default()
+            })
+            .unwrap();
+        service
+            .upsert_price_list(
+                &context,
+                price_list(
+                    "general",
+                    vec![
+                        line("general_1", &mock_item_a().id, 0.0, 10.0),
+                        line("general_10", &mock_item_a().id, 10.0, 8.0),
+                    ],
+                ),
+            )
+            .unwrap();
+        service
+            .upsert_tax_rule(
+                &context,
+                UpsertTaxRule {
+                    id: "all".to_string(),
+                    tax_percentage: 15.0,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        // General tax rule doesn't override the invoice tax
+        let line = invoice_line_service
+            .insert_stock_out_line(
+                &context,
+                InsertStockOutLine {
+                    id: "pricing_line".to_string(),
+                    r#type: StockOutType::OutboundShipment,
+                    invoice_id: "pricing_outbound".to_string(),
+                    stock_line_id: mock_stock_line_a().id,
+                    number_of_packs: 2.0,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .invoice_line_row;
+        assert_eq!(line.sell_price_per_pack, 10.0);
+        assert_eq!(line.tax_percentage, Some(12.0));
+
+        // Quantity break applies when the line is updated
+        let line = invoice_line_service
+            .update_stock_out_line(
+                &context,
+                UpdateStockOutLine {
+                    id: "pricing_line".to_string(),
+                    r#type: Some(StockOutType::OutboundShipment),
+                    number_of_packs: Some(12.0),
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .invoice_line_row;
+        assert_eq!(line.sell_price_per_pack, 8.0);
+        assert_eq!(line.total_before_tax, 96.0);
+
+        // Item tax rule overrides the invoice tax
+        service
+            .upsert_tax_rule(
+                &context,
+                UpsertTaxRule {
+                    id: "item_a".to_string(),
+                    item_id: Some(mock_item_a().id),
+                    tax_percentage: 5.0,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let line = invoice_line_service
+            .insert_stock_out_line(
+                &context,
+                InsertStockOutLine {
+                    id: "pricing_line_b".to_string(),
+                    r#type: StockOutType::OutboundShipment,
+                    invoice_id: "pricing_outbound".to_string(),
+                    stock_line_id: mock_stock_line_b().id,
+                    number_of_packs: 1.0,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .invoice_line_row;
+        assert_eq!(line.tax_percentage, Some(5.0));
+    }
+}
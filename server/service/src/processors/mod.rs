@@ -1,8 +1,10 @@
 use std::sync::Arc;
+use std::time::Instant;
 use thiserror::Error;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
+use util::metrics;
 
 use crate::service_provider::ServiceProvider;
 
@@ -81,10 +83,10 @@ impl Processors {
                 let result = tokio::select! {
                     biased;
                     Some(_) = requisition_transfer.recv() => {
-                        process_requisition_transfers(&service_provider).map_err(ProcessorsError::RequisitionTransfer)
+                        record_processor_run("requisition_transfer", || process_requisition_transfers(&service_provider)).map_err(ProcessorsError::RequisitionTransfer)
                     },
                     Some(_) = invoice_transfer.recv() => {
-                        process_invoice_transfers(&service_provider).map_err(ProcessorsError::InvoiceTransfer)
+                        record_processor_run("invoice_transfer", || process_invoice_transfers(&service_provider)).map_err(ProcessorsError::InvoiceTransfer)
                     },
                     Some(sender) = await_process_queue.recv() => {
                         sender.send(()).map_err(ProcessorsError::AwaitProcessQueue)
@@ -101,6 +103,18 @@ impl Processors {
     }
 }
 
+fn record_processor_run<T, E>(
+    processor: &'static str,
+    process: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    let start = Instant::now();
+    let result = process();
+    metrics::PROCESSOR_DURATION.observe_since(&[("processor", processor)], start);
+    let status = if result.is_ok() { "success" } else { "error" };
+    metrics::PROCESSOR_RUNS.inc(&[("processor", processor), ("result", status)]);
+    result
+}
+
 impl ProcessorsTrigger {
     pub(crate) fn trigger_requisition_transfer_processors(&self) {
         if let Err(error) = self.requisition_transfer.try_send(()) {
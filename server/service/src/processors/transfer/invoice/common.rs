@@ -22,7 +22,7 @@ pub(crate) fn generate_inbound_lines(
         .map(|l| l.invoice_line_row)
         .map(
             |InvoiceLineRow {
-                 id: _,
+                 id: outbound_line_id,
                  invoice_id: _,
                  item_link_id,
                  item_name,
@@ -44,6 +44,10 @@ pub(crate) fn generate_inbound_lines(
                  return_reason_id,
                  foreign_currency_price_before_tax,
                  item_variant_id,
+                 linked_invoice_line_id: _,
+                 discrepancy_type: _,
+                 discrepancy_number_of_packs: _,
+                 discrepancy_reason: _,
              }| {
                 let cost_price_per_pack = sell_price_per_pack;
 
@@ -76,10 +80,14 @@ pub(crate) fn generate_inbound_lines(
                     foreign_currency_price_before_tax,
                     return_reason_id,
                     item_variant_id,
+                    linked_invoice_line_id: Some(outbound_line_id),
                     // Default
                     stock_line_id: None,
                     location_id: None,
                     inventory_adjustment_reason_id: None,
+                    discrepancy_type: None,
+                    discrepancy_number_of_packs: 0.0,
+                    discrepancy_reason: None,
                 }
             },
         )
@@ -8,6 +8,7 @@ use crate::{
             delete_inbound_invoice::DeleteInboundInvoiceProcessor,
             link_outbound_invoice::LinkOutboundInvoiceProcessor,
             update_inbound_invoice::UpdateInboundInvoiceProcessor,
+            update_outbound_invoice_discrepancies::UpdateOutboundInvoiceDiscrepanciesProcessor,
             update_outbound_invoice_status::UpdateOutboundInvoiceStatusProcessor,
         },
     },
@@ -29,6 +30,7 @@ pub(crate) mod create_inbound_invoice;
 pub(crate) mod delete_inbound_invoice;
 pub(crate) mod link_outbound_invoice;
 pub(crate) mod update_inbound_invoice;
+pub(crate) mod update_outbound_invoice_discrepancies;
 pub(crate) mod update_outbound_invoice_status;
 
 #[cfg(test)]
@@ -102,6 +104,7 @@ pub(crate) fn process_invoice_transfers(
         Box::new(LinkOutboundInvoiceProcessor),
         Box::new(UpdateInboundInvoiceProcessor),
         Box::new(UpdateOutboundInvoiceStatusProcessor),
+        Box::new(UpdateOutboundInvoiceDiscrepanciesProcessor),
         Box::new(DeleteInboundInvoiceProcessor),
         Box::new(AssignInvoiceNumberProcessor),
     ];
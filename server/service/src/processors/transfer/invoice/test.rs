@@ -1,11 +1,12 @@
 use chrono::NaiveDate;
 use repository::{
     mock::{insert_extra_mock_data, MockData, MockDataInserts},
-    EqualFilter, InvoiceFilter, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineRow,
-    InvoiceLineRowRepository, InvoiceLineType, InvoiceRepository, InvoiceRow, InvoiceRowRepository,
-    InvoiceStatus, InvoiceType, ItemRow, KeyType, KeyValueStoreRow, LocationRow, NameLinkRow,
-    NameRow, RequisitionFilter, RequisitionRepository, RequisitionRow, RequisitionRowRepository,
-    RequisitionStatus, RequisitionType, StockLineRow, StorageConnection, StoreRow,
+    EqualFilter, InvoiceFilter, InvoiceLineDiscrepancyType, InvoiceLineFilter,
+    InvoiceLineRepository, InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineType,
+    InvoiceRepository, InvoiceRow, InvoiceRowRepository, InvoiceStatus, InvoiceType, ItemRow,
+    KeyType, KeyValueStoreRow, LocationRow, NameLinkRow, NameRow, RequisitionFilter,
+    RequisitionRepository, RequisitionRow, RequisitionRowRepository, RequisitionStatus,
+    RequisitionType, StockLineRow, StorageConnection, StoreRow,
 };
 use util::{inline_edit, inline_init, uuid::uuid};
 
@@ -16,7 +17,10 @@ use crate::{
         outbound_shipment::update::{UpdateOutboundShipment, UpdateOutboundShipmentStatus},
         supplier_return::update::{UpdateSupplierReturn, UpdateSupplierReturnStatus},
     },
-    invoice_line::stock_out_line::{StockOutType, UpdateStockOutLine},
+    invoice_line::{
+        stock_out_line::{StockOutType, UpdateStockOutLine},
+        update_discrepancy::UpdateLineDiscrepancy,
+    },
     processors::test_helpers::exec_concurrent,
     requisition::request_requisition::{UpdateRequestRequisition, UpdateRequestRequisitionStatus},
     service_provider::ServiceProvider,
@@ -156,9 +160,11 @@ async fn invoice_transfers() {
             tester.update_inbound_shipment_to_delivered(&service_provider);
             ctx.processors_trigger.await_events_processed().await;
             tester.check_outbound_shipment_status_matches_inbound_shipment(&ctx.connection);
+            tester.record_inbound_shipment_discrepancy(&service_provider);
             tester.update_inbound_shipment_to_verified(&service_provider);
             ctx.processors_trigger.await_events_processed().await;
             tester.check_outbound_shipment_status_matches_inbound_shipment(&ctx.connection);
+            tester.check_outbound_shipment_discrepancy(&service_provider);
 
             // RETURN
             tester.insert_supplier_return(&ctx.connection);
@@ -989,6 +995,34 @@ impl InvoiceTransferTester {
         self.inbound_shipment = Some(inbound_shipment.invoice_row);
     }
 
+    pub(crate) fn record_inbound_shipment_discrepancy(&self, service_provider: &ServiceProvider) {
+        let ctx = service_provider
+            .context(self.inbound_store.id.clone(), "".to_string())
+            .unwrap();
+
+        let inbound_line = InvoiceLineRowRepository::new(&ctx.connection)
+            .find_many_by_invoice_id(&self.inbound_shipment.clone().map(|r| r.id).unwrap())
+            .unwrap()
+            .into_iter()
+            .find(|line| {
+                line.linked_invoice_line_id == Some(self.outbound_shipment_line2.id.clone())
+            })
+            .unwrap();
+
+        service_provider
+            .invoice_line_service
+            .update_line_discrepancy(
+                &ctx,
+                UpdateLineDiscrepancy {
+                    line_id: inbound_line.id,
+                    r#type: Some(InvoiceLineDiscrepancyType::Damaged),
+                    number_of_packs: 3.0,
+                    reason: Some("Water damage".to_string()),
+                },
+            )
+            .unwrap();
+    }
+
     pub(crate) fn update_inbound_shipment_to_verified(
         &mut self,
         service_provider: &ServiceProvider,
@@ -1026,6 +1060,31 @@ impl InvoiceTransferTester {
         )
     }
 
+    pub(crate) fn check_outbound_shipment_discrepancy(&self, service_provider: &ServiceProvider) {
+        let ctx = service_provider
+            .context(self.outbound_store.id.clone(), "".to_string())
+            .unwrap();
+
+        let report = service_provider
+            .invoice_service
+            .get_discrepancy_report(&ctx, &self.outbound_shipment.id)
+            .unwrap();
+
+        assert_eq!(report.lines.len(), 1);
+        let line = &report.lines[0].line;
+        assert_eq!(line.id, self.outbound_shipment_line2.id);
+        assert_eq!(
+            line.discrepancy_type,
+            Some(InvoiceLineDiscrepancyType::Damaged)
+        );
+        assert_eq!(line.discrepancy_number_of_packs, 3.0);
+        assert_eq!(line.discrepancy_reason, Some("Water damage".to_string()));
+        assert_eq!(
+            report.lines[0].shipped_number_of_packs,
+            Some(self.outbound_shipment_line2.number_of_packs)
+        );
+    }
+
     pub(crate) fn insert_supplier_return(&self, connection: &StorageConnection) {
         let inbound_shipment_id = self.inbound_shipment.clone().map(|r| r.id);
         insert_extra_mock_data(
@@ -1323,6 +1382,10 @@ fn check_line(connection: &StorageConnection, inbound_id: &str, outbound_line: &
     assert_eq!(inbound_line.pack_size, outbound_line.pack_size);
     assert_eq!(inbound_line.number_of_packs, outbound_line.number_of_packs);
     assert_eq!(inbound_line.note, outbound_line.note);
+    assert_eq!(
+        inbound_line.linked_invoice_line_id,
+        Some(outbound_line.id.clone())
+    );
 
     match outbound_line.r#type {
         InvoiceLineType::Service => {
@@ -0,0 +1,117 @@
+use repository::{
+    InvoiceLineRow, InvoiceLineRowRepository, InvoiceStatus, InvoiceType, RepositoryError,
+    StorageConnection,
+};
+
+use crate::processors::transfer::invoice::Operation;
+
+use super::{InvoiceTransferProcessor, InvoiceTransferProcessorRecord};
+
+const DESCRIPTION: &str = "Update outbound invoice line discrepancies from inbound invoice";
+
+pub(crate) struct UpdateOutboundInvoiceDiscrepanciesProcessor;
+
+impl InvoiceTransferProcessor for UpdateOutboundInvoiceDiscrepanciesProcessor {
+    fn get_description(&self) -> String {
+        DESCRIPTION.to_string()
+    }
+
+    /// Outbound invoice lines will be updated when all below conditions are met:
+    ///
+    /// 1. Source invoice name_id is for a store that is active on current site (transfer processor driver guarantees this)
+    /// 2. Source invoice is Inbound shipment
+    /// 3. Linked invoice exists (the outbound invoice)
+    /// 4. Source inbound invoice is Verified (discrepancies can't change after this)
+    /// 5. Source inbound invoice has lines generated from outbound lines, where the discrepancy
+    ///    doesn't match the outbound line
+    ///
+    /// Only runs once:
+    /// 6. Because linked outbound lines will be updated to match the inbound lines and `5.` will never be true again
+    fn try_process_record(
+        &self,
+        connection: &StorageConnection,
+        record_for_processing: &InvoiceTransferProcessorRecord,
+    ) -> Result<Option<String>, RepositoryError> {
+        // Check can execute
+        let (inbound_invoice, linked_invoice) = match &record_for_processing.operation {
+            Operation::Upsert {
+                invoice,
+                linked_invoice,
+                ..
+            } => (invoice, linked_invoice),
+            _ => return Ok(None),
+        };
+        // 2.
+        if inbound_invoice.invoice_row.r#type != InvoiceType::InboundShipment {
+            return Ok(None);
+        }
+        // 3.
+        let outbound_invoice = match &linked_invoice {
+            Some(linked_invoice) => linked_invoice,
+            None => return Ok(None),
+        };
+        // 4.
+        if inbound_invoice.invoice_row.status != InvoiceStatus::Verified {
+            return Ok(None);
+        }
+        // 5.
+        let line_repository = InvoiceLineRowRepository::new(connection);
+        let outbound_lines =
+            line_repository.find_many_by_invoice_id(&outbound_invoice.invoice_row.id)?;
+        let updated_outbound_lines: Vec<InvoiceLineRow> = line_repository
+            .find_many_by_invoice_id(&inbound_invoice.invoice_row.id)?
+            .iter()
+            .filter_map(|inbound_line| {
+                let outbound_line = outbound_lines.iter().find(|outbound_line| {
+                    Some(&outbound_line.id) == inbound_line.linked_invoice_line_id.as_ref()
+                })?;
+                let updated_line = generate_outbound_line(inbound_line, outbound_line);
+                (&updated_line != outbound_line).then_some(updated_line)
+            })
+            .collect();
+
+        if updated_outbound_lines.is_empty() {
+            return Ok(None);
+        }
+
+        // Execute
+        for line in updated_outbound_lines.iter() {
+            // 6.
+            line_repository.upsert_one(line)?;
+        }
+
+        let result = format!(
+            "invoice ({}) source invoice ({}) updated lines ({:?})",
+            outbound_invoice.invoice_row.id,
+            inbound_invoice.invoice_row.id,
+            updated_outbound_lines
+                .into_iter()
+                .map(|line| line.id)
+                .collect::<Vec<String>>()
+        );
+
+        Ok(Some(result))
+    }
+}
+
+/// Inbound lines can be in a different pack size to the outbound line (pack to one store
+/// preference), the discrepancy is converted to the pack size of the outbound line
+fn generate_outbound_line(
+    inbound_line: &InvoiceLineRow,
+    outbound_line: &InvoiceLineRow,
+) -> InvoiceLineRow {
+    let discrepancy_number_of_packs = match inbound_line.discrepancy_type {
+        Some(_) if outbound_line.pack_size > 0.0 => {
+            inbound_line.discrepancy_number_of_packs * inbound_line.pack_size
+                / outbound_line.pack_size
+        }
+        _ => 0.0,
+    };
+
+    InvoiceLineRow {
+        discrepancy_type: inbound_line.discrepancy_type.clone(),
+        discrepancy_number_of_packs,
+        discrepancy_reason: inbound_line.discrepancy_reason.clone(),
+        ..outbound_line.clone()
+    }
+}
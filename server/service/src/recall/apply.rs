@@ -0,0 +1,173 @@
+use repository::{
+    EqualFilter, ItemLinkRowRepository, RecallBatchRowRepository, RecallRow, RecallRowRepository,
+    RecallStockLineRow, RecallStockLineRowRepository, RepositoryError, StockLineFilter,
+    StockLineRepository, StockLineRow, StockLineRowRepository, StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::sync::{ActiveStoresOnSite, GetActiveStoresOnSiteError};
+
+fn is_recalled_batch(batches: &[String], batch: &Option<String>) -> bool {
+    batch
+        .as_ref()
+        .is_some_and(|batch| batches.contains(&batch.trim().to_string()))
+}
+
+/// Puts stock of the recalled batches on hold in every store on the site, skipping stock lines
+/// already quarantined by the recall
+pub(crate) fn apply_recall_to_site(
+    connection: &StorageConnection,
+    recall: &RecallRow,
+    batches: &[String],
+) -> Result<(), GetActiveStoresOnSiteError> {
+    let store_ids = ActiveStoresOnSite::get(connection)?.store_ids();
+    let stock_lines = StockLineRepository::new(connection).query_by_filter(
+        StockLineFilter::new()
+            .item_id(EqualFilter::equal_to(&recall.item_link_id))
+            .store_id(EqualFilter::equal_any(store_ids)),
+        None,
+    )?;
+    let stock_line_repository = StockLineRowRepository::new(connection);
+    let recall_stock_line_repository = RecallStockLineRowRepository::new(connection);
+    for stock_line in stock_lines {
+        let mut stock_line = stock_line.stock_line_row;
+        if !is_recalled_batch(batches, &stock_line.batch) || stock_line.total_number_of_packs <= 0.0
+        {
+            continue;
+        }
+        let is_quarantined = recall_stock_line_repository
+            .find_many_by_stock_line_id(&stock_line.id)?
+            .iter()
+            .any(|row| row.recall_id == recall.id);
+        if is_quarantined {
+            continue;
+        }
+
+        recall_stock_line_repository.upsert_one(&RecallStockLineRow {
+            id: uuid(),
+            recall_id: recall.id.clone(),
+            store_id: stock_line.store_id.clone(),
+            stock_line_id: stock_line.id.clone(),
+            quarantined_number_of_packs: stock_line.total_number_of_packs,
+        })?;
+        stock_line.on_hold = true;
+        stock_line_repository.upsert_one(&stock_line)?;
+    }
+
+    Ok(())
+}
+
+/// Recalls are entered on the central server, remote sites apply them once they are synced
+pub(crate) fn apply_recalls_to_site(
+    connection: &StorageConnection,
+) -> Result<(), GetActiveStoresOnSiteError> {
+    connection
+        .transaction_sync(|connection| {
+            let batch_repository = RecallBatchRowRepository::new(connection);
+            for recall in RecallRowRepository::new(connection).find_all()? {
+                let batches: Vec<String> = batch_repository
+                    .find_many_by_recall_id(&recall.id)?
+                    .into_iter()
+                    .map(|row| row.batch)
+                    .collect();
+                apply_recall_to_site(connection, &recall, &batches)?;
+            }
+            Ok(())
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+fn find_recalls_for_batch(
+    connection: &StorageConnection,
+    item_link_id: &str,
+    batch: &Option<String>,
+) -> Result<Vec<RecallRow>, RepositoryError> {
+    if batch.as_ref().is_none_or(|batch| batch.trim().is_empty()) {
+        return Ok(Vec::new());
+    }
+    let item_link_repository = ItemLinkRowRepository::new(connection);
+    let Some(item_link) = item_link_repository.find_one_by_id(item_link_id)? else {
+        return Ok(Vec::new());
+    };
+    let item_link_ids: Vec<String> = item_link_repository
+        .find_many_by_item_id(&item_link.item_id)?
+        .into_iter()
+        .map(|link| link.id)
+        .collect();
+
+    let batch_repository = RecallBatchRowRepository::new(connection);
+    let mut result = Vec::new();
+    for recall in RecallRowRepository::new(connection).find_many_by_item_link_ids(&item_link_ids)? {
+        let batches: Vec<String> = batch_repository
+            .find_many_by_recall_id(&recall.id)?
+            .into_iter()
+            .map(|row| row.batch)
+            .collect();
+        if is_recalled_batch(&batches, batch) {
+            result.push(recall);
+        }
+    }
+    Ok(result)
+}
+
+/// Upserts received stock (inbound shipment, customer return or inventory addition), stock of a
+/// recalled batch is put on hold and the received packs are added to the quarantined quantity
+pub(crate) fn upsert_received_stock_line(
+    connection: &StorageConnection,
+    stock_line: &StockLineRow,
+) -> Result<(), RepositoryError> {
+    let stock_line_repository = StockLineRowRepository::new(connection);
+    let recalls = find_recalls_for_batch(connection, &stock_line.item_link_id, &stock_line.batch)?;
+    if recalls.is_empty() {
+        stock_line_repository.upsert_one(stock_line)?;
+        return Ok(());
+    }
+
+    let previous_total_number_of_packs = stock_line_repository
+        .find_one_by_id(&stock_line.id)?
+        .map_or(0.0, |stock_line| stock_line.total_number_of_packs);
+    let received_number_of_packs =
+        stock_line.total_number_of_packs - previous_total_number_of_packs;
+
+    stock_line_repository.upsert_one(&StockLineRow {
+        on_hold: true,
+        ..stock_line.clone()
+    })?;
+
+    let recall_stock_line_repository = RecallStockLineRowRepository::new(connection);
+    let recall_stock_lines =
+        recall_stock_line_repository.find_many_by_stock_line_id(&stock_line.id)?;
+    for recall in recalls {
+        let recall_stock_line = match recall_stock_lines
+            .iter()
+            .find(|row| row.recall_id == recall.id)
+        {
+            Some(row) => RecallStockLineRow {
+                quarantined_number_of_packs: (row.quarantined_number_of_packs
+                    + received_number_of_packs)
+                    .max(0.0),
+                ..row.clone()
+            },
+            None => RecallStockLineRow {
+                id: uuid(),
+                recall_id: recall.id,
+                store_id: stock_line.store_id.clone(),
+                stock_line_id: stock_line.id.clone(),
+                quarantined_number_of_packs: stock_line.total_number_of_packs,
+            },
+        };
+        recall_stock_line_repository.upsert_one(&recall_stock_line)?;
+    }
+
+    Ok(())
+}
+
+/// Quarantined stock can still leave the store through a supplier return or inventory reduction
+pub(crate) fn is_recalled_stock_line(
+    connection: &StorageConnection,
+    stock_line_id: &str,
+) -> Result<bool, RepositoryError> {
+    Ok(!RecallStockLineRowRepository::new(connection)
+        .find_many_by_stock_line_id(stock_line_id)?
+        .is_empty())
+}
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+
+use repository::{
+    EqualFilter, Invoice, InvoiceFilter, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineType,
+    InvoiceRepository, InvoiceStatus, InvoiceType, NameLinkRowRepository, NameRow,
+    NameRowRepository, ProgramEnrolmentRowRepository, RecallStockLineRow,
+    RecallStockLineRowRepository, RepositoryError, StockLineRow, StockLineRowRepository,
+    VaccinationRowRepository,
+};
+
+use crate::service_provider::ServiceContext;
+
+use super::{get_batches, Recall};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecalledStockLine {
+    pub recall_stock_line: RecallStockLineRow,
+    pub stock_line: StockLineRow,
+    /// Packs sent back to the supplier on supplier returns
+    pub returned_number_of_packs: f64,
+    /// Packs written off with inventory adjustments
+    pub destroyed_number_of_packs: f64,
+}
+
+impl RecalledStockLine {
+    /// Quarantined packs not yet returned or destroyed
+    pub fn remaining_number_of_packs(&self) -> f64 {
+        (self.recall_stock_line.quarantined_number_of_packs
+            - self.returned_number_of_packs
+            - self.destroyed_number_of_packs)
+            .max(0.0)
+    }
+}
+
+/// Customer or patient who received a recalled batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecallRecipient {
+    pub name: NameRow,
+    /// Outbound shipments or prescriptions with the recalled batches
+    pub invoice_ids: Vec<String>,
+    pub vaccination_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecallImpact {
+    pub recall: Recall,
+    pub stock_lines: Vec<RecalledStockLine>,
+    pub outbound_shipments: Vec<Invoice>,
+    pub customers: Vec<RecallRecipient>,
+    pub patients: Vec<RecallRecipient>,
+}
+
+impl RecallImpact {
+    /// True once every quarantined pack has been returned or destroyed
+    pub fn is_resolved(&self) -> bool {
+        self.stock_lines
+            .iter()
+            .all(|line| line.remaining_number_of_packs() <= 0.0)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GetRecallImpactError {
+    RecallDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for GetRecallImpactError {
+    fn from(error: RepositoryError) -> Self {
+        GetRecallImpactError::DatabaseError(error)
+    }
+}
+
+pub fn get_recall_impact(
+    ctx: &ServiceContext,
+    id: &str,
+) -> Result<RecallImpact, GetRecallImpactError> {
+    let connection = &ctx.connection;
+    let recall = repository::RecallRowRepository::new(connection)
+        .find_one_by_id(id)?
+        .ok_or(GetRecallImpactError::RecallDoesNotExist)?;
+    let recall = get_batches(ctx, recall)?;
+    let is_recalled_batch = |batch: &Option<String>| {
+        batch
+            .as_ref()
+            .is_some_and(|batch| recall.batches.contains(&batch.trim().to_string()))
+    };
+
+    // Issues of the recalled batches, from any store
+    let issued_lines: Vec<_> = InvoiceLineRepository::new(connection)
+        .query_by_filter(
+            InvoiceLineFilter::new()
+                .item_id(EqualFilter::equal_to(&recall.recall.item_link_id))
+                .r#type(InvoiceLineType::StockOut.equal_to()),
+        )?
+        .into_iter()
+        .filter(|line| is_recalled_batch(&line.invoice_line_row.batch))
+        .collect();
+
+    // Returns and write offs of the quarantined stock
+    let recall_stock_lines =
+        RecallStockLineRowRepository::new(connection).find_many_by_recall_id(id)?;
+    let stock_line_ids: Vec<String> = recall_stock_lines
+        .iter()
+        .map(|line| line.stock_line_id.clone())
+        .collect();
+    let stock_line_rows =
+        StockLineRowRepository::new(connection).find_many_by_ids(&stock_line_ids)?;
+    let mut stock_lines = Vec::new();
+    for recall_stock_line in recall_stock_lines {
+        let Some(stock_line) = stock_line_rows
+            .iter()
+            .find(|row| row.id == recall_stock_line.stock_line_id)
+        else {
+            continue;
+        };
+        let removed_number_of_packs = |invoice_type: InvoiceType| {
+            issued_lines
+                .iter()
+                .filter(|line| {
+                    line.invoice_row.r#type == invoice_type
+                        && line.invoice_line_row.stock_line_id.as_ref() == Some(&stock_line.id)
+                        && line.invoice_row.created_datetime >= recall.recall.created_datetime
+                        && line.invoice_row.status != InvoiceStatus::New
+                })
+                .map(|line| line.invoice_line_row.number_of_packs)
+                .sum()
+        };
+        stock_lines.push(RecalledStockLine {
+            returned_number_of_packs: removed_number_of_packs(InvoiceType::SupplierReturn),
+            destroyed_number_of_packs: removed_number_of_packs(InvoiceType::InventoryReduction),
+            stock_line: stock_line.clone(),
+            recall_stock_line,
+        });
+    }
+
+    // Outbound shipments, prescriptions and vaccinations that gave out the recalled batches
+    let invoice_ids = |invoice_type: InvoiceType| {
+        let mut ids: Vec<String> = issued_lines
+            .iter()
+            .filter(|line| line.invoice_row.r#type == invoice_type)
+            .map(|line| line.invoice_row.id.clone())
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    };
+    let invoice_repository = InvoiceRepository::new(connection);
+    let outbound_shipments = invoice_repository.query_by_filter(InvoiceFilter::new().id(
+        EqualFilter::equal_any(invoice_ids(InvoiceType::OutboundShipment)),
+    ))?;
+    let prescriptions = invoice_repository.query_by_filter(InvoiceFilter::new().id(
+        EqualFilter::equal_any(invoice_ids(InvoiceType::Prescription)),
+    ))?;
+
+    let mut customers: BTreeMap<String, RecallRecipient> = BTreeMap::new();
+    for invoice in &outbound_shipments {
+        recipient(&mut customers, &invoice.name_row)
+            .invoice_ids
+            .push(invoice.invoice_row.id.clone());
+    }
+
+    let mut patients: BTreeMap<String, RecallRecipient> = BTreeMap::new();
+    for invoice in &prescriptions {
+        recipient(&mut patients, &invoice.name_row)
+            .invoice_ids
+            .push(invoice.invoice_row.id.clone());
+    }
+    let batch_stock_line_ids: Vec<String> = issued_lines
+        .iter()
+        .filter_map(|line| line.invoice_line_row.stock_line_id.clone())
+        .chain(stock_line_ids)
+        .collect();
+    let vaccinations = VaccinationRowRepository::new(connection)
+        .find_many_by_stock_line_ids(&batch_stock_line_ids)?;
+    let enrolment_repository = ProgramEnrolmentRowRepository::new(connection);
+    let name_link_repository = NameLinkRowRepository::new(connection);
+    let name_repository = NameRowRepository::new(connection);
+    for vaccination in vaccinations
+        .into_iter()
+        .filter(|vaccination| vaccination.given)
+    {
+        let Some(enrolment) =
+            enrolment_repository.find_one_by_id(&vaccination.program_enrolment_id)?
+        else {
+            continue;
+        };
+        let Some(name_link) = name_link_repository.find_one_by_id(&enrolment.patient_link_id)?
+        else {
+            continue;
+        };
+        let Some(patient) = name_repository.find_one_by_id(&name_link.name_id)? else {
+            continue;
+        };
+        recipient(&mut patients, &patient)
+            .vaccination_ids
+            .push(vaccination.id);
+    }
+
+    Ok(RecallImpact {
+        recall,
+        stock_lines,
+        outbound_shipments,
+        customers: customers.into_values().collect(),
+        patients: patients.into_values().collect(),
+    })
+}
+
+fn recipient<'a>(
+    recipients: &'a mut BTreeMap<String, RecallRecipient>,
+    name: &NameRow,
+) -> &'a mut RecallRecipient {
+    recipients
+        .entry(name.id.clone())
+        .or_insert_with(|| RecallRecipient {
+            name: name.clone(),
+            invoice_ids: Vec::new(),
+            vaccination_ids: Vec::new(),
+        })
+}
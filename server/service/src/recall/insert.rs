@@ -0,0 +1,104 @@
+use chrono::{NaiveDate, Utc};
+use repository::{
+    ItemRowRepository, RecallBatchRow, RecallBatchRowRepository, RecallRow, RecallRowRepository,
+    RepositoryError,
+};
+use util::uuid::uuid;
+
+use crate::{service_provider::ServiceContext, sync::GetActiveStoresOnSiteError};
+
+use super::{apply::apply_recall_to_site, Recall};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InsertRecall {
+    pub id: String,
+    pub item_id: String,
+    pub batches: Vec<String>,
+    pub reason: String,
+    pub recall_date: NaiveDate,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InsertRecallError {
+    RecallAlreadyExists,
+    ItemDoesNotExist,
+    NoBatches,
+    /// Stores on the site are not known until the site has synced
+    SiteIdNotSet,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for InsertRecallError {
+    fn from(error: RepositoryError) -> Self {
+        InsertRecallError::DatabaseError(error)
+    }
+}
+
+impl From<GetActiveStoresOnSiteError> for InsertRecallError {
+    fn from(error: GetActiveStoresOnSiteError) -> Self {
+        match error {
+            GetActiveStoresOnSiteError::DatabaseError(error) => {
+                InsertRecallError::DatabaseError(error)
+            }
+            GetActiveStoresOnSiteError::SiteIdNotSet => InsertRecallError::SiteIdNotSet,
+        }
+    }
+}
+
+type OutError = InsertRecallError;
+
+/// Records the recall and puts stock of the recalled batches on hold in every store on the site,
+/// recalls are entered on the central server and remote sites apply them when they are synced
+pub fn insert_recall(ctx: &ServiceContext, input: InsertRecall) -> Result<Recall, OutError> {
+    let recall = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let recall_repository = RecallRowRepository::new(connection);
+            if recall_repository.find_one_by_id(&input.id)?.is_some() {
+                return Err(OutError::RecallAlreadyExists);
+            }
+            if ItemRowRepository::new(connection)
+                .find_active_by_id(&input.item_id)?
+                .is_none()
+            {
+                return Err(OutError::ItemDoesNotExist);
+            }
+            let mut batches: Vec<String> = input
+                .batches
+                .iter()
+                .map(|batch| batch.trim().to_string())
+                .filter(|batch| !batch.is_empty())
+                .collect();
+            batches.sort();
+            batches.dedup();
+            if batches.is_empty() {
+                return Err(OutError::NoBatches);
+            }
+
+            let recall = RecallRow {
+                id: input.id.clone(),
+                item_link_id: input.item_id.clone(),
+                reason: input.reason.clone(),
+                recall_date: input.recall_date,
+                created_datetime: Utc::now().naive_utc(),
+                store_id: ctx.store_id.clone(),
+                user_id: ctx.user_id.clone(),
+            };
+            recall_repository.upsert_one(&recall)?;
+            let batch_repository = RecallBatchRowRepository::new(connection);
+            for batch in &batches {
+                batch_repository.upsert_one(&RecallBatchRow {
+                    id: uuid(),
+                    recall_id: recall.id.clone(),
+                    batch: batch.clone(),
+                })?;
+            }
+
+            apply_recall_to_site(connection, &recall, &batches)?;
+
+            Ok(Recall { recall, batches }) as Result<Recall, OutError>
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(recall)
+}
@@ -0,0 +1,69 @@
+use repository::{RecallBatchRowRepository, RecallRow, RecallRowRepository, RepositoryError};
+
+use crate::service_provider::ServiceContext;
+
+pub(crate) mod apply;
+pub(crate) use self::apply::*;
+pub mod impact;
+pub use self::impact::*;
+pub mod insert;
+pub use self::insert::*;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recall {
+    pub recall: RecallRow,
+    pub batches: Vec<String>,
+}
+
+pub trait RecallServiceTrait: Sync + Send {
+    /// Recalls apply to every store on the site, newest first
+    fn get_recalls(&self, ctx: &ServiceContext) -> Result<Vec<Recall>, RepositoryError> {
+        let recalls = RecallRowRepository::new(&ctx.connection).find_all()?;
+        recalls
+            .into_iter()
+            .map(|recall| get_batches(ctx, recall))
+            .collect()
+    }
+
+    fn get_recall(
+        &self,
+        ctx: &ServiceContext,
+        id: &str,
+    ) -> Result<Option<Recall>, RepositoryError> {
+        RecallRowRepository::new(&ctx.connection)
+            .find_one_by_id(id)?
+            .map(|recall| get_batches(ctx, recall))
+            .transpose()
+    }
+
+    fn insert_recall(
+        &self,
+        ctx: &ServiceContext,
+        input: InsertRecall,
+    ) -> Result<Recall, InsertRecallError> {
+        insert_recall(ctx, input)
+    }
+
+    fn get_recall_impact(
+        &self,
+        ctx: &ServiceContext,
+        id: &str,
+    ) -> Result<RecallImpact, GetRecallImpactError> {
+        get_recall_impact(ctx, id)
+    }
+}
+
+pub struct RecallService {}
+impl RecallServiceTrait for RecallService {}
+
+fn get_batches(ctx: &ServiceContext, recall: RecallRow) -> Result<Recall, RepositoryError> {
+    let batches = RecallBatchRowRepository::new(&ctx.connection)
+        .find_many_by_recall_id(&recall.id)?
+        .into_iter()
+        .map(|row| row.batch)
+        .collect();
+    Ok(Recall { recall, batches })
+}
@@ -0,0 +1,377 @@
+#[cfg(test)]
+mod recall {
+    use chrono::NaiveDate;
+    use chrono::Utc;
+    use repository::{
+        mock::{
+            mock_item_a, mock_name_a, mock_patient, mock_store_a, mock_store_b, MockData,
+            MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        InvoiceLineRow, InvoiceLineType, InvoiceRow, InvoiceRowRepository, InvoiceStatus,
+        InvoiceType, KeyType, KeyValueStoreRepository, KeyValueStoreRow, RecallBatchRow,
+        RecallBatchRowRepository, RecallRow, RecallRowRepository, RecallStockLineRowRepository,
+        StockLineRow, StockLineRowRepository,
+    };
+    use util::inline_init;
+
+    use crate::{
+        invoice::{
+            inventory_adjustment::{
+                add_new_stock_line::AddNewStockLine,
+                adjust_existing_stock::{AdjustmentType, InsertInventoryAdjustment},
+            },
+            supplier_return::{
+                insert::InsertSupplierReturn,
+                update::{UpdateSupplierReturn, UpdateSupplierReturnStatus},
+                SupplierReturnLineInput,
+            },
+        },
+        invoice_line::stock_out_line::{InsertStockOutLine, InsertStockOutLineError, StockOutType},
+        recall::{apply_recalls_to_site, InsertRecall, InsertRecallError},
+        service_provider::ServiceProvider,
+    };
+
+    fn stock_line(
+        id: &str,
+        store_id: String,
+        batch: &str,
+        total_number_of_packs: f64,
+    ) -> StockLineRow {
+        inline_init(|r: &mut StockLineRow| {
+            r.id = id.to_string();
+            r.store_id = store_id;
+            r.item_link_id = mock_item_a().id;
+            r.batch = Some(batch.to_string());
+            r.pack_size = 1.0;
+            r.available_number_of_packs = total_number_of_packs;
+            r.total_number_of_packs = total_number_of_packs;
+        })
+    }
+
+    fn invoice(
+        id: &str,
+        r#type: InvoiceType,
+        status: InvoiceStatus,
+        name_id: String,
+    ) -> InvoiceRow {
+        inline_init(|r: &mut InvoiceRow| {
+            r.id = id.to_string();
+            r.name_link_id = name_id;
+            r.store_id = mock_store_a().id;
+            r.r#type = r#type;
+            r.status = status;
+        })
+    }
+
+    fn stock_out_line(
+        invoice_id: &str,
+        stock_line_id: &str,
+        number_of_packs: f64,
+    ) -> InvoiceLineRow {
+        inline_init(|r: &mut InvoiceLineRow| {
+            r.id = format!("{invoice_id}_line");
+            r.invoice_id = invoice_id.to_string();
+            r.item_link_id = mock_item_a().id;
+            r.r#type = InvoiceLineType::StockOut;
+            r.stock_line_id = Some(stock_line_id.to_string());
+            r.batch = Some("B1".to_string());
+            r.pack_size = 1.0;
+            r.number_of_packs = number_of_packs;
+        })
+    }
+
+    fn input() -> InsertRecall {
+        InsertRecall {
+            id: "recall".to_string(),
+            item_id: mock_item_a().id,
+            batches: vec!["B1".to_string(), " B1 ".to_string()],
+            reason: "Contamination".to_string(),
+            recall_date: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn recall() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "recall",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![
+                    stock_line("recalled", mock_store_a().id, "B1", 10.0),
+                    stock_line("other_batch", mock_store_a().id, "B2", 10.0),
+                    stock_line("issued", mock_store_a().id, "B1", 0.0),
+                    // Store of another site
+                    stock_line("other_site", mock_store_b().id, "B1", 10.0),
+                ];
+                r.invoices = vec![
+                    invoice(
+                        "recall_outbound",
+                        InvoiceType::OutboundShipment,
+                        InvoiceStatus::Shipped,
+                        mock_name_a().id,
+                    ),
+                    invoice(
+                        "recall_prescription",
+                        InvoiceType::Prescription,
+                        InvoiceStatus::Verified,
+                        mock_patient().id,
+                    ),
+                ];
+                r.invoice_lines = vec![
+                    stock_out_line("recall_outbound", "issued", 5.0),
+                    stock_out_line("recall_prescription", "issued", 1.0),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = &service_provider.recall_service;
+
+        // Errors
+        assert_eq!(
+            service.insert_recall(
+                &context,
+                InsertRecall {
+                    item_id: "invalid".to_string(),
+                    ..input()
+                }
+            ),
+            Err(InsertRecallError::ItemDoesNotExist)
+        );
+        assert_eq!(
+            service.insert_recall(
+                &context,
+                InsertRecall {
+                    batches: vec![" ".to_string()],
+                    ..input()
+                }
+            ),
+            Err(InsertRecallError::NoBatches)
+        );
+        assert_eq!(
+            service.insert_recall(&context, input()),
+            Err(InsertRecallError::SiteIdNotSet)
+        );
+
+        // Recalled batches are put on hold in stores of the site
+        KeyValueStoreRepository::new(&connection)
+            .set_i32(KeyType::SettingsSyncSiteId, Some(mock_store_a().site_id))
+            .unwrap();
+        let recall = service.insert_recall(&context, input()).unwrap();
+        assert_eq!(recall.batches, vec!["B1".to_string()]);
+        assert_eq!(
+            service.insert_recall(&context, input()),
+            Err(InsertRecallError::RecallAlreadyExists)
+        );
+        let on_hold = |id: &str| {
+            StockLineRowRepository::new(&connection)
+                .find_one_by_id(id)
+                .unwrap()
+                .unwrap()
+                .on_hold
+        };
+        assert!(on_hold("recalled"));
+        assert!(!on_hold("other_batch"));
+        assert!(!on_hold("issued"));
+        assert!(!on_hold("other_site"));
+
+        // Impact
+        let impact = service.get_recall_impact(&context, "recall").unwrap();
+        assert_eq!(impact.stock_lines.len(), 1);
+        assert_eq!(impact.stock_lines[0].remaining_number_of_packs(), 10.0);
+        assert!(!impact.is_resolved());
+        assert_eq!(impact.outbound_shipments.len(), 1);
+        assert_eq!(impact.customers.len(), 1);
+        assert_eq!(impact.customers[0].name.id, mock_name_a().id);
+        assert_eq!(impact.patients.len(), 1);
+        assert_eq!(impact.patients[0].name.id, mock_patient().id);
+        assert_eq!(
+            impact.patients[0].invoice_ids,
+            vec!["recall_prescription".to_string()]
+        );
+
+        // Progress through supplier returns and inventory adjustments
+        // Recalled stock on hold can't be issued
+        let outbound_line = |id: &str, r#type: StockOutType, invoice_id: &str| InsertStockOutLine {
+            id: id.to_string(),
+            r#type,
+            invoice_id: invoice_id.to_string(),
+            stock_line_id: "recalled".to_string(),
+            number_of_packs: 1.0,
+            ..Default::default()
+        };
+        InvoiceRowRepository::new(&connection)
+            .upsert_one(&invoice(
+                "recall_new_outbound",
+                InvoiceType::OutboundShipment,
+                InvoiceStatus::New,
+                mock_name_a().id,
+            ))
+            .unwrap();
+        assert_eq!(
+            service_provider.invoice_line_service.insert_stock_out_line(
+                &context,
+                outbound_line(
+                    "recall_new_outbound_line",
+                    StockOutType::OutboundShipment,
+                    "recall_new_outbound"
+                )
+            ),
+            Err(InsertStockOutLineError::BatchIsOnHold)
+        );
+
+        // But can be returned to the supplier and written off
+        let invoice_service = &service_provider.invoice_service;
+        invoice_service
+            .insert_supplier_return(
+                &context,
+                InsertSupplierReturn {
+                    id: "recall_return".to_string(),
+                    other_party_id: mock_name_a().id,
+                    supplier_return_lines: vec![SupplierReturnLineInput {
+                        id: "recall_return_line".to_string(),
+                        stock_line_id: "recalled".to_string(),
+                        number_of_packs: 4.0,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        // Not counted until the return is shipped
+        let impact = service.get_recall_impact(&context, "recall").unwrap();
+        assert_eq!(impact.stock_lines[0].returned_number_of_packs, 0.0);
+        invoice_service
+            .update_supplier_return(
+                &context,
+                UpdateSupplierReturn {
+                    supplier_return_id: "recall_return".to_string(),
+                    status: Some(UpdateSupplierReturnStatus::Shipped),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        invoice_service
+            .insert_inventory_adjustment(
+                &context,
+                InsertInventoryAdjustment {
+                    stock_line_id: "recalled".to_string(),
+                    adjustment: 6.0,
+                    adjustment_type: AdjustmentType::Reduction,
+                    inventory_adjustment_reason_id: None,
+                },
+            )
+            .unwrap();
+        let impact = service.get_recall_impact(&context, "recall").unwrap();
+        assert_eq!(impact.stock_lines[0].returned_number_of_packs, 4.0);
+        assert_eq!(impact.stock_lines[0].destroyed_number_of_packs, 6.0);
+        assert!(impact.is_resolved());
+        assert_eq!(impact.outbound_shipments.len(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn recall_applied_to_later_stock() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "recall_applied_to_later_stock",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.key_value_store_rows = vec![inline_init(|r: &mut KeyValueStoreRow| {
+                    r.id = KeyType::SettingsSyncSiteId;
+                    r.value_int = Some(mock_store_a().site_id);
+                })];
+                r.stock_lines = vec![stock_line("synced", mock_store_a().id, "B1", 10.0)];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let stock_line_repository = StockLineRowRepository::new(&connection);
+        let recall_stock_lines = |stock_line_id: &str| {
+            RecallStockLineRowRepository::new(&connection)
+                .find_many_by_stock_line_id(stock_line_id)
+                .unwrap()
+        };
+
+        // Recall entered on central and synced
+        RecallRowRepository::new(&connection)
+            .upsert_one(&inline_init(|r: &mut RecallRow| {
+                r.id = "recall".to_string();
+                r.item_link_id = mock_item_a().id;
+                r.store_id = mock_store_b().id;
+                r.created_datetime = Utc::now().naive_utc();
+            }))
+            .unwrap();
+        RecallBatchRowRepository::new(&connection)
+            .upsert_one(&RecallBatchRow {
+                id: "recall_batch".to_string(),
+                recall_id: "recall".to_string(),
+                batch: "B1".to_string(),
+            })
+            .unwrap();
+        apply_recalls_to_site(&connection).unwrap();
+        assert!(
+            stock_line_repository
+                .find_one_by_id("synced")
+                .unwrap()
+                .unwrap()
+                .on_hold
+        );
+        assert_eq!(
+            recall_stock_lines("synced")[0].quarantined_number_of_packs,
+            10.0
+        );
+        // Applying again doesn't quarantine twice
+        apply_recalls_to_site(&connection).unwrap();
+        assert_eq!(recall_stock_lines("synced").len(), 1);
+
+        // Stock of the recalled batch received later is quarantined
+        let invoice_service = &service_provider.invoice_service;
+        let received = |id: &str, batch: &str| {
+            invoice_service
+                .add_new_stock_line(
+                    &context,
+                    AddNewStockLine {
+                        stock_line_id: id.to_string(),
+                        item_id: mock_item_a().id,
+                        number_of_packs: 5.0,
+                        pack_size: 1.0,
+                        batch: Some(batch.to_string()),
+                        ..Default::default()
+                    },
+                )
+                .unwrap()
+        };
+        assert!(received("received", " B1").stock_line_row.on_hold);
+        assert_eq!(
+            recall_stock_lines("received")[0].quarantined_number_of_packs,
+            5.0
+        );
+        assert!(!received("received_other", "B2").stock_line_row.on_hold);
+        assert!(recall_stock_lines("received_other").is_empty());
+
+        // Added stock adds to the quarantined quantity
+        invoice_service
+            .insert_inventory_adjustment(
+                &context,
+                InsertInventoryAdjustment {
+                    stock_line_id: "received".to_string(),
+                    adjustment: 2.0,
+                    adjustment_type: AdjustmentType::Addition,
+                    inventory_adjustment_reason_id: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            recall_stock_lines("received")[0].quarantined_number_of_packs,
+            7.0
+        );
+    }
+}
@@ -1,12 +1,10 @@
-use super::OutError;
-use crate::{
-    number::next_number, requisition::requisition_supply_status::RequisitionLineSupplyStatus,
-    validate::get_other_party,
-};
+use super::{validate::LineToSupply, OutError};
+use crate::{backorder::get_available_quantity, number::next_number, validate::get_other_party};
 use chrono::Utc;
 use repository::{
-    CurrencyFilter, CurrencyRepository, InvoiceLineRow, InvoiceLineType, InvoiceRow, InvoiceStatus,
-    InvoiceType, ItemRowRepository, NumberRowType, Requisition, StorageConnection,
+    BackorderRow, BackorderStatus, CurrencyFilter, CurrencyRepository, InvoiceLineRow,
+    InvoiceLineType, InvoiceRow, InvoiceStatus, InvoiceType, ItemRowRepository, NumberRowType,
+    Requisition, StorageConnection,
 };
 use util::uuid::uuid;
 
@@ -15,8 +13,9 @@ pub fn generate(
     store_id: &str,
     user_id: &str,
     requisition: Requisition,
-    fulfillments: Vec<RequisitionLineSupplyStatus>,
-) -> Result<(InvoiceRow, Vec<InvoiceLineRow>), OutError> {
+    fulfillments: Vec<LineToSupply>,
+    create_backorders: bool,
+) -> Result<(InvoiceRow, Vec<InvoiceLineRow>, Vec<BackorderRow>), OutError> {
     let other_party = get_other_party(connection, store_id, &requisition.name_row.id)?
         .ok_or(OutError::ProblemGettingOtherParty)?;
     let requisition_row = requisition.requisition_row;
@@ -59,27 +58,80 @@ pub fn generate(
         backdated_datetime: None,
     };
 
+    let (fulfillments, backorder_rows) = if create_backorders {
+        generate_backorders(connection, store_id, &new_invoice, fulfillments)?
+    } else {
+        (fulfillments, Vec::new())
+    };
+
     let invoice_line_rows = generate_invoice_lines(connection, &new_invoice.id, fulfillments)?;
-    Ok((new_invoice, invoice_line_rows))
+    Ok((new_invoice, invoice_line_rows, backorder_rows))
+}
+
+/// Limits the lines to what can be supplied from available stock, and backorders the rest
+fn generate_backorders(
+    connection: &StorageConnection,
+    store_id: &str,
+    invoice: &InvoiceRow,
+    fulfillments: Vec<LineToSupply>,
+) -> Result<(Vec<LineToSupply>, Vec<BackorderRow>), OutError> {
+    let mut lines_to_supply = Vec::new();
+    let mut backorder_rows = Vec::new();
+
+    for LineToSupply {
+        supply_status,
+        quantity,
+    } in fulfillments
+    {
+        let available = get_available_quantity(connection, store_id, supply_status.item_id())?;
+        let to_supply = quantity.min(available.max(0.0));
+        let to_backorder = quantity - to_supply;
+
+        if to_backorder > 0.0 {
+            backorder_rows.push(BackorderRow {
+                id: uuid(),
+                store_id: store_id.to_string(),
+                name_link_id: invoice.name_link_id.clone(),
+                item_link_id: supply_status.item_id().to_string(),
+                requisition_id: invoice.requisition_id.clone(),
+                quantity: to_backorder,
+                status: BackorderStatus::Open,
+                created_datetime: invoice.created_datetime,
+                ..Default::default()
+            });
+        }
+        if to_supply > 0.0 {
+            lines_to_supply.push(LineToSupply {
+                supply_status,
+                quantity: to_supply,
+            });
+        }
+    }
+
+    Ok((lines_to_supply, backorder_rows))
 }
 
 pub fn generate_invoice_lines(
     connection: &StorageConnection,
     invoice_id: &str,
-    requisition_line_supply_statuses: Vec<RequisitionLineSupplyStatus>,
+    lines_to_supply: Vec<LineToSupply>,
 ) -> Result<Vec<InvoiceLineRow>, OutError> {
     let mut invoice_line_rows = vec![];
 
-    for requisition_line_supply_status in requisition_line_supply_statuses.into_iter() {
+    for LineToSupply {
+        supply_status,
+        quantity,
+    } in lines_to_supply.into_iter()
+    {
         let item_row = ItemRowRepository::new(connection)
-            .find_active_by_id(requisition_line_supply_status.item_id())?
+            .find_active_by_id(supply_status.item_id())?
             .ok_or(OutError::ProblemFindingItem)?;
 
         invoice_line_rows.push(InvoiceLineRow {
             id: uuid(),
             invoice_id: invoice_id.to_owned(),
             pack_size: 1.0,
-            number_of_packs: requisition_line_supply_status.remaining_quantity(),
+            number_of_packs: quantity,
             item_link_id: item_row.id,
             item_code: item_row.code,
             item_name: item_row.name,
@@ -100,6 +152,10 @@ pub fn generate_invoice_lines(
             return_reason_id: None,
             foreign_currency_price_before_tax: None,
             item_variant_id: None,
+            linked_invoice_line_id: None,
+            discrepancy_type: None,
+            discrepancy_number_of_packs: 0.0,
+            discrepancy_reason: None,
         });
     }
 
@@ -2,8 +2,8 @@ use crate::activity_log::activity_log_entry;
 use crate::service_provider::ServiceContext;
 use repository::{ActivityLogType, EqualFilter};
 use repository::{
-    Invoice, InvoiceFilter, InvoiceLineRowRepository, InvoiceRepository, InvoiceRowRepository,
-    RepositoryError,
+    BackorderRowRepository, Invoice, InvoiceFilter, InvoiceLineRowRepository, InvoiceRepository,
+    InvoiceRowRepository, RepositoryError,
 };
 
 mod generate;
@@ -13,9 +13,11 @@ mod validate;
 use generate::*;
 use validate::*;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Default)]
 pub struct CreateRequisitionShipment {
     pub response_requisition_id: String,
+    /// Only supply what is available in stock, and backorder the rest
+    pub create_backorders: bool,
 }
 
 #[derive(Debug, PartialEq)]
@@ -41,12 +43,13 @@ pub fn create_requisition_shipment(
         .connection
         .transaction_sync(|connection| {
             let (requisition_row, fulfillments) = validate(connection, &ctx.store_id, &input)?;
-            let (invoice_row, invoice_line_rows) = generate(
+            let (invoice_row, invoice_line_rows, backorder_rows) = generate(
                 connection,
                 &ctx.store_id,
                 &ctx.user_id,
                 requisition_row,
                 fulfillments,
+                input.create_backorders,
             )?;
 
             InvoiceRowRepository::new(connection).upsert_one(&invoice_row)?;
@@ -56,6 +59,11 @@ pub fn create_requisition_shipment(
                 invoice_line_repository.upsert_one(&row)?;
             }
 
+            let backorder_repository = BackorderRowRepository::new(connection);
+            for row in backorder_rows {
+                backorder_repository.upsert_one(&row)?;
+            }
+
             activity_log_entry(
                 ctx,
                 ActivityLogType::InvoiceCreated,
@@ -37,6 +37,7 @@ mod test_update {
                 &context,
                 CreateRequisitionShipment {
                     response_requisition_id: "invalid".to_owned(),
+                    create_backorders: false,
                 },
             ),
             Err(ServiceError::RequisitionDoesNotExist)
@@ -48,6 +49,7 @@ mod test_update {
                 &context,
                 CreateRequisitionShipment {
                     response_requisition_id: mock_finalised_response_requisition().id,
+                    create_backorders: false,
                 },
             ),
             Err(ServiceError::CannotEditRequisition)
@@ -59,6 +61,7 @@ mod test_update {
                 &context,
                 CreateRequisitionShipment {
                     response_requisition_id: mock_sent_request_requisition().id,
+                    create_backorders: false,
                 },
             ),
             Err(ServiceError::NotAResponseRequisition)
@@ -71,6 +74,7 @@ mod test_update {
                 &context,
                 CreateRequisitionShipment {
                     response_requisition_id: mock_new_response_requisition_for_update_test().id,
+                    create_backorders: false,
                 },
             ),
             Err(ServiceError::NotThisStoreRequisition)
@@ -97,6 +101,7 @@ mod test_update {
                 &context,
                 CreateRequisitionShipment {
                     response_requisition_id: mock_new_response_requisition_test().requisition.id,
+                    create_backorders: false,
                 },
             )
             .unwrap();
@@ -134,6 +139,7 @@ mod test_update {
                 &context,
                 CreateRequisitionShipment {
                     response_requisition_id: mock_new_response_requisition_test().requisition.id,
+                    create_backorders: false,
                 },
             ),
             Err(ServiceError::NothingRemainingToSupply)
@@ -156,6 +162,7 @@ mod test_update {
                 &context,
                 CreateRequisitionShipment {
                     response_requisition_id: mock_new_response_requisition_test().requisition.id,
+                    create_backorders: false,
                 },
             )
             .unwrap();
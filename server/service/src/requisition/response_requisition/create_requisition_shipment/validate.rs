@@ -1,6 +1,8 @@
+use std::collections::HashMap;
+
 use repository::{
     requisition_row::{RequisitionStatus, RequisitionType},
-    Requisition, StorageConnection,
+    BackorderRowRepository, ItemLinkRowRepository, Requisition, StorageConnection,
 };
 
 use crate::requisition::requisition_supply_status::RequisitionLineSupplyStatus;
@@ -10,11 +12,17 @@ use crate::requisition::{
 
 use super::{CreateRequisitionShipment, OutError};
 
+pub struct LineToSupply {
+    pub supply_status: RequisitionLineSupplyStatus,
+    /// Remaining quantity that isn't already backordered
+    pub quantity: f64,
+}
+
 pub fn validate(
     connection: &StorageConnection,
     store_id: &str,
     input: &CreateRequisitionShipment,
-) -> Result<(Requisition, Vec<RequisitionLineSupplyStatus>), OutError> {
+) -> Result<(Requisition, Vec<LineToSupply>), OutError> {
     let requisition = check_requisition_exists(connection, &input.response_requisition_id)?
         .ok_or(OutError::RequisitionDoesNotExist)?;
     let requisition_row = &requisition.requisition_row;
@@ -33,8 +41,34 @@ pub fn validate(
     let supply_statuses =
         get_requisitions_supply_statuses(connection, vec![requisition_row.id.clone()])?;
 
-    let remaining_to_supply =
-        RequisitionLineSupplyStatus::lines_remaining_to_supply(supply_statuses);
+    // Quantities still on (or cancelled from) a backorder are not supplied again
+    let item_link_repository = ItemLinkRowRepository::new(connection);
+    let mut backordered_by_item: HashMap<String, f64> = HashMap::new();
+    for backorder in
+        BackorderRowRepository::new(connection).find_many_by_requisition_id(&requisition_row.id)?
+    {
+        let Some(item_link) = item_link_repository.find_one_by_id(&backorder.item_link_id)? else {
+            continue;
+        };
+        *backordered_by_item.entry(item_link.item_id).or_default() +=
+            backorder.remaining_quantity();
+    }
+
+    let remaining_to_supply: Vec<LineToSupply> =
+        RequisitionLineSupplyStatus::lines_remaining_to_supply(supply_statuses)
+            .into_iter()
+            .map(|supply_status| {
+                let backordered = backordered_by_item
+                    .get(supply_status.item_id())
+                    .copied()
+                    .unwrap_or_default();
+                LineToSupply {
+                    quantity: supply_status.remaining_quantity() - backordered,
+                    supply_status,
+                }
+            })
+            .filter(|line| line.quantity > 0.0)
+            .collect();
 
     if remaining_to_supply.is_empty() {
         return Err(OutError::NothingRemainingToSupply);
@@ -0,0 +1,119 @@
+use repository::{
+    EqualFilter, LmisCodeMappingFilter, LmisCodeMappingRepository, LmisCodeMappingRow,
+    LmisCodeMappingRowRepository, LmisCodeMappingType, RepositoryError, StorageConnection,
+};
+
+use crate::service_provider::ServiceContext;
+
+use super::validate::check_program_exists;
+
+#[derive(PartialEq, Debug)]
+pub enum UpsertLmisCodeMappingError {
+    CreatedRecordNotFound,
+    ProgramDoesNotExist,
+    ExternalCodeCannotBeEmpty,
+    DuplicateMapping,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Default, Clone)]
+pub struct UpsertLmisCodeMapping {
+    pub id: String,
+    pub program_id: String,
+    pub r#type: LmisCodeMappingType,
+    pub internal_id: String,
+    pub external_code: String,
+}
+
+pub fn upsert_lmis_code_mapping(
+    ctx: &ServiceContext,
+    input: UpsertLmisCodeMapping,
+) -> Result<LmisCodeMappingRow, UpsertLmisCodeMappingError> {
+    let mapping = ctx
+        .connection
+        .transaction_sync(|connection| {
+            validate(connection, &input)?;
+            let new_mapping = generate(input.clone());
+            let repo = LmisCodeMappingRowRepository::new(connection);
+
+            repo.upsert_one(&new_mapping)?;
+
+            repo.find_one_by_id(&new_mapping.id)?
+                .ok_or(UpsertLmisCodeMappingError::CreatedRecordNotFound)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(mapping)
+}
+
+fn generate(
+    UpsertLmisCodeMapping {
+        id,
+        program_id,
+        r#type,
+        internal_id,
+        external_code,
+    }: UpsertLmisCodeMapping,
+) -> LmisCodeMappingRow {
+    LmisCodeMappingRow {
+        id,
+        program_id,
+        r#type,
+        internal_id,
+        external_code: external_code.trim().to_string(),
+        deleted_datetime: None,
+    }
+}
+
+fn validate(
+    connection: &StorageConnection,
+    input: &UpsertLmisCodeMapping,
+) -> Result<(), UpsertLmisCodeMappingError> {
+    if check_program_exists(connection, &input.program_id)?.is_none() {
+        return Err(UpsertLmisCodeMappingError::ProgramDoesNotExist);
+    }
+
+    if input.external_code.trim().is_empty() {
+        return Err(UpsertLmisCodeMappingError::ExternalCodeCannotBeEmpty);
+    }
+
+    // Each program/store/item/field can only be mapped once per program
+    let count = LmisCodeMappingRepository::new(connection).count(Some(
+        LmisCodeMappingFilter::new()
+            .program_id(EqualFilter::equal_to(&input.program_id))
+            .r#type(input.r#type.equal_to())
+            .internal_id(EqualFilter::equal_to(&input.internal_id))
+            .id(EqualFilter::not_equal_to(&input.id)),
+    ))?;
+
+    if count > 0 {
+        return Err(UpsertLmisCodeMappingError::DuplicateMapping);
+    }
+
+    Ok(())
+}
+
+impl From<RepositoryError> for UpsertLmisCodeMappingError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertLmisCodeMappingError::DatabaseError(error)
+    }
+}
+
+pub fn delete_lmis_code_mapping(
+    ctx: &ServiceContext,
+    id: String,
+) -> Result<String, RepositoryError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            // Soft delete, deleting an already deleted mapping is fine
+            LmisCodeMappingRowRepository::new(connection).mark_deleted(&id)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(id)
+}
+
+pub fn get_lmis_code_mappings(
+    connection: &StorageConnection,
+    filter: LmisCodeMappingFilter,
+) -> Result<Vec<LmisCodeMappingRow>, RepositoryError> {
+    LmisCodeMappingRepository::new(connection).query_by_filter(filter)
+}
@@ -0,0 +1,318 @@
+use chrono::{Local, NaiveDate};
+use repository::{
+    EqualFilter, LmisCodeMappingFilter, LmisCodeMappingRepository, LmisCodeMappingRow,
+    LmisCodeMappingType, RepositoryError, RnRForm, RnRFormLine, RnRFormLineFilter,
+    RnRFormLineRepository, RnRFormStatus,
+};
+use serde_json::{json, Value};
+use util::escape_csv_value;
+
+use crate::{
+    service_provider::ServiceContext,
+    static_files::{StaticFileCategory, StaticFileService},
+};
+
+use super::validate::check_rnr_form_exists;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LmisExportFormat {
+    /// OpenLMIS requisition JSON, one entry in `products` per R&R form line
+    OpenLmisJson,
+    /// DHIS2 dataValueSet JSON, item codes are used as data elements and field codes as
+    /// category option combos
+    Dhis2DataValueSet,
+    /// One row per R&R form line, one column per field
+    Csv,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LmisExportError {
+    RnRFormDoesNotExist,
+    RnRFormDoesNotBelongToStore,
+    RnRFormNotFinalised,
+    ProgramCodeNotMapped,
+    FacilityCodeNotMapped,
+    /// Contains the codes of items on the form without an external code
+    ItemCodesNotMapped(Vec<String>),
+    FileError(String),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for LmisExportError {
+    fn from(error: RepositoryError) -> Self {
+        LmisExportError::DatabaseError(error)
+    }
+}
+
+/// Keys of the exported R&R line fields, in export order. Field code mappings use these
+/// keys as their internal id.
+pub const LMIS_EXPORT_FIELDS: [&str; 8] = [
+    "beginningBalance",
+    "quantityReceived",
+    "quantityDispensed",
+    "adjustments",
+    "losses",
+    "stockInHand",
+    "stockOutDays",
+    "requestedQuantity",
+];
+const REMARKS_FIELD: &str = "remarks";
+
+struct ExportLine {
+    product_code: String,
+    values: [f64; 8],
+    remarks: Option<String>,
+}
+
+struct ExportData {
+    program_code: String,
+    facility_code: String,
+    period_start: NaiveDate,
+    period_end: NaiveDate,
+    field_codes: Vec<String>,
+    remarks_code: String,
+    lines: Vec<ExportLine>,
+}
+
+pub fn generate_lmis_export(
+    ctx: &ServiceContext,
+    store_id: &str,
+    rnr_form_id: &str,
+    format: LmisExportFormat,
+) -> Result<String, LmisExportError> {
+    let rnr_form = validate(ctx, store_id, rnr_form_id)?;
+    let data = get_export_data(ctx, rnr_form)?;
+
+    Ok(match format {
+        LmisExportFormat::OpenLmisJson => to_open_lmis_json(&data).to_string(),
+        LmisExportFormat::Dhis2DataValueSet => to_dhis2_data_value_set(&data).to_string(),
+        LmisExportFormat::Csv => to_csv(&data),
+    })
+}
+
+/// Generates the export and stores it as a temporary file, returns the static file id
+pub fn export_rnr_form_to_lmis(
+    ctx: &ServiceContext,
+    base_dir: &Option<String>,
+    store_id: &str,
+    rnr_form_id: &str,
+    format: LmisExportFormat,
+) -> Result<String, LmisExportError> {
+    let content = generate_lmis_export(ctx, store_id, rnr_form_id, format)?;
+
+    let extension = match format {
+        LmisExportFormat::Csv => "csv",
+        LmisExportFormat::OpenLmisJson | LmisExportFormat::Dhis2DataValueSet => "json",
+    };
+
+    let file_service = StaticFileService::new(base_dir)
+        .map_err(|err| LmisExportError::FileError(format!("{}", err)))?;
+    let file = file_service
+        .store_file(
+            &format!(
+                "{}_rnr_form_{}.{}",
+                Local::now().format("%Y%m%d_%H%M%S"),
+                rnr_form_id,
+                extension
+            ),
+            StaticFileCategory::Temporary,
+            content.as_bytes(),
+        )
+        .map_err(|err| LmisExportError::FileError(format!("{}", err)))?;
+
+    Ok(file.id)
+}
+
+fn validate(
+    ctx: &ServiceContext,
+    store_id: &str,
+    rnr_form_id: &str,
+) -> Result<RnRForm, LmisExportError> {
+    let rnr_form = check_rnr_form_exists(&ctx.connection, rnr_form_id)?
+        .ok_or(LmisExportError::RnRFormDoesNotExist)?;
+
+    if rnr_form.rnr_form_row.store_id != store_id {
+        return Err(LmisExportError::RnRFormDoesNotBelongToStore);
+    }
+
+    if rnr_form.rnr_form_row.status != RnRFormStatus::Finalised {
+        return Err(LmisExportError::RnRFormNotFinalised);
+    }
+
+    Ok(rnr_form)
+}
+
+fn get_export_data(ctx: &ServiceContext, rnr_form: RnRForm) -> Result<ExportData, LmisExportError> {
+    let RnRForm {
+        rnr_form_row,
+        period_row,
+        ..
+    } = rnr_form;
+
+    let mappings = LmisCodeMappingRepository::new(&ctx.connection).query_by_filter(
+        LmisCodeMappingFilter::new().program_id(EqualFilter::equal_to(&rnr_form_row.program_id)),
+    )?;
+    let code_for = |r#type: LmisCodeMappingType, internal_id: &str| {
+        mappings
+            .iter()
+            .find(|m: &&LmisCodeMappingRow| m.r#type == r#type && m.internal_id == internal_id)
+            .map(|m| m.external_code.clone())
+    };
+
+    let program_code = code_for(LmisCodeMappingType::Program, &rnr_form_row.program_id)
+        .ok_or(LmisExportError::ProgramCodeNotMapped)?;
+    let facility_code = code_for(LmisCodeMappingType::Facility, &rnr_form_row.store_id)
+        .ok_or(LmisExportError::FacilityCodeNotMapped)?;
+
+    // Fields without a mapping are exported with their default key
+    let field_codes = LMIS_EXPORT_FIELDS
+        .iter()
+        .map(|field| code_for(LmisCodeMappingType::Field, field).unwrap_or(field.to_string()))
+        .collect();
+    let remarks_code =
+        code_for(LmisCodeMappingType::Field, REMARKS_FIELD).unwrap_or(REMARKS_FIELD.to_string());
+
+    let form_lines = RnRFormLineRepository::new(&ctx.connection).query_by_filter(
+        RnRFormLineFilter::new().rnr_form_id(EqualFilter::equal_to(&rnr_form_row.id)),
+    )?;
+
+    let mut unmapped_items = Vec::new();
+    let mut lines = Vec::new();
+    for RnRFormLine {
+        rnr_form_line_row: line,
+        item_row,
+        ..
+    } in form_lines
+    {
+        let Some(product_code) = code_for(LmisCodeMappingType::Item, &item_row.id) else {
+            unmapped_items.push(item_row.code);
+            continue;
+        };
+
+        lines.push(ExportLine {
+            product_code,
+            values: [
+                line.initial_balance,
+                line.entered_quantity_received
+                    .unwrap_or(line.snapshot_quantity_received),
+                line.entered_quantity_consumed
+                    .unwrap_or(line.snapshot_quantity_consumed),
+                line.entered_adjustments
+                    .unwrap_or(line.snapshot_adjustments),
+                line.entered_losses.unwrap_or_default(),
+                line.final_balance,
+                line.stock_out_duration as f64,
+                line.entered_requested_quantity
+                    .unwrap_or(line.calculated_requested_quantity),
+            ],
+            remarks: line.comment,
+        });
+    }
+
+    if !unmapped_items.is_empty() {
+        unmapped_items.sort();
+        return Err(LmisExportError::ItemCodesNotMapped(unmapped_items));
+    }
+
+    Ok(ExportData {
+        program_code,
+        facility_code,
+        period_start: period_row.start_date,
+        period_end: period_row.end_date,
+        field_codes,
+        remarks_code,
+        lines,
+    })
+}
+
+fn to_open_lmis_json(data: &ExportData) -> Value {
+    let products: Vec<Value> = data
+        .lines
+        .iter()
+        .map(|line| {
+            let mut product = serde_json::Map::new();
+            product.insert("productCode".to_string(), json!(line.product_code));
+            for (code, value) in data.field_codes.iter().zip(line.values.iter()) {
+                product.insert(code.clone(), json!(value));
+            }
+            if let Some(remarks) = &line.remarks {
+                product.insert(data.remarks_code.clone(), json!(remarks));
+            }
+            Value::Object(product)
+        })
+        .collect();
+
+    json!({
+        "programCode": data.program_code,
+        "facilityCode": data.facility_code,
+        "periodStartDate": data.period_start.format("%Y-%m-%d").to_string(),
+        "periodEndDate": data.period_end.format("%Y-%m-%d").to_string(),
+        "emergency": false,
+        "products": products,
+    })
+}
+
+fn to_dhis2_data_value_set(data: &ExportData) -> Value {
+    // DHIS2 periods are identified as yyyyMM for monthly periods, R&R periods are monthly
+    let period = data.period_start.format("%Y%m").to_string();
+
+    let data_values: Vec<Value> = data
+        .lines
+        .iter()
+        .flat_map(|line| {
+            data.field_codes
+                .iter()
+                .zip(line.values.iter())
+                .map(move |(code, value)| {
+                    json!({
+                        "dataElement": line.product_code,
+                        "categoryOptionCombo": code,
+                        "value": value.to_string(),
+                    })
+                })
+        })
+        .collect();
+
+    json!({
+        "dataSet": data.program_code,
+        "orgUnit": data.facility_code,
+        "period": period,
+        "dataValues": data_values,
+    })
+}
+
+fn to_csv(data: &ExportData) -> String {
+    let mut header = vec![
+        "programCode".to_string(),
+        "facilityCode".to_string(),
+        "periodStartDate".to_string(),
+        "periodEndDate".to_string(),
+        "productCode".to_string(),
+    ];
+    header.extend(data.field_codes.iter().cloned());
+    header.push(data.remarks_code.clone());
+
+    let mut rows = vec![header];
+    for line in &data.lines {
+        let mut row = vec![
+            data.program_code.clone(),
+            data.facility_code.clone(),
+            data.period_start.format("%Y-%m-%d").to_string(),
+            data.period_end.format("%Y-%m-%d").to_string(),
+            line.product_code.clone(),
+        ];
+        row.extend(line.values.iter().map(|value| value.to_string()));
+        row.push(line.remarks.clone().unwrap_or_default());
+        rows.push(row);
+    }
+
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .map(|value| escape_csv_value(value))
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -1,11 +1,17 @@
 use crate::{service_provider::ServiceContext, ListError, ListResult};
 
 use repository::{
-    PaginationOption, PeriodRow, RepositoryError, RnRForm, RnRFormFilter, RnRFormSort,
+    LmisCodeMappingFilter, LmisCodeMappingRow, PaginationOption, PeriodRow, RepositoryError,
+    RnRForm, RnRFormFilter, RnRFormSort,
 };
 
 use self::finalise::{finalise_rnr_form, FinaliseRnRForm, FinaliseRnRFormError};
 use self::insert::{insert_rnr_form, InsertRnRForm, InsertRnRFormError};
+use self::lmis_code_mapping::{
+    delete_lmis_code_mapping, get_lmis_code_mappings, upsert_lmis_code_mapping,
+    UpsertLmisCodeMapping, UpsertLmisCodeMappingError,
+};
+use self::lmis_export::{export_rnr_form_to_lmis, LmisExportError, LmisExportFormat};
 use self::query::{get_rnr_form, get_rnr_forms};
 use self::schedules_with_periods::{get_schedules_with_periods_by_program, PeriodSchedule};
 use self::update::{update_rnr_form, UpdateRnRForm, UpdateRnRFormError};
@@ -13,6 +19,8 @@ use self::update::{update_rnr_form, UpdateRnRForm, UpdateRnRFormError};
 pub mod finalise;
 mod generate_rnr_form_lines;
 pub mod insert;
+pub mod lmis_code_mapping;
+pub mod lmis_export;
 pub mod query;
 pub mod schedules_with_periods;
 mod tests;
@@ -75,6 +83,41 @@ pub trait RnRFormServiceTrait: Sync + Send {
     ) -> Result<RnRForm, FinaliseRnRFormError> {
         finalise_rnr_form(ctx, store_id, input)
     }
+
+    fn get_lmis_code_mappings(
+        &self,
+        ctx: &ServiceContext,
+        filter: LmisCodeMappingFilter,
+    ) -> Result<Vec<LmisCodeMappingRow>, RepositoryError> {
+        get_lmis_code_mappings(&ctx.connection, filter)
+    }
+
+    fn upsert_lmis_code_mapping(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertLmisCodeMapping,
+    ) -> Result<LmisCodeMappingRow, UpsertLmisCodeMappingError> {
+        upsert_lmis_code_mapping(ctx, input)
+    }
+
+    fn delete_lmis_code_mapping(
+        &self,
+        ctx: &ServiceContext,
+        id: String,
+    ) -> Result<String, RepositoryError> {
+        delete_lmis_code_mapping(ctx, id)
+    }
+
+    fn export_rnr_form_to_lmis(
+        &self,
+        ctx: &ServiceContext,
+        base_dir: &Option<String>,
+        store_id: &str,
+        rnr_form_id: &str,
+        format: LmisExportFormat,
+    ) -> Result<String, LmisExportError> {
+        export_rnr_form_to_lmis(ctx, base_dir, store_id, rnr_form_id, format)
+    }
 }
 
 pub struct RnRFormService;
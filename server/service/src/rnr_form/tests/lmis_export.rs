@@ -0,0 +1,267 @@
+#[cfg(test)]
+mod lmis_export {
+    use repository::mock::MockDataInserts;
+    use repository::mock::{
+        item_query_test1, mock_program_b, mock_rnr_form_a, mock_rnr_form_b, mock_store_a,
+        mock_store_b,
+    };
+    use repository::test_db::setup_all;
+    use repository::LmisCodeMappingType;
+    use serde_json::{json, Value};
+
+    use crate::rnr_form::lmis_code_mapping::{UpsertLmisCodeMapping, UpsertLmisCodeMappingError};
+    use crate::rnr_form::lmis_export::{generate_lmis_export, LmisExportError, LmisExportFormat};
+    use crate::service_provider::ServiceProvider;
+
+    #[actix_rt::test]
+    async fn lmis_export_errors() {
+        let (_, _, connection_manager, _) =
+            setup_all("lmis_export_errors", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.rnr_form_service;
+        let store_id = mock_store_a().id;
+        let format = LmisExportFormat::OpenLmisJson;
+
+        // RnRFormDoesNotExist
+        assert_eq!(
+            generate_lmis_export(&context, &store_id, "invalid", format),
+            Err(LmisExportError::RnRFormDoesNotExist)
+        );
+
+        // RnRFormDoesNotBelongToStore
+        assert_eq!(
+            generate_lmis_export(&context, &mock_store_b().id, &mock_rnr_form_a().id, format),
+            Err(LmisExportError::RnRFormDoesNotBelongToStore)
+        );
+
+        // RnRFormNotFinalised
+        assert_eq!(
+            generate_lmis_export(&context, &store_id, &mock_rnr_form_b().id, format),
+            Err(LmisExportError::RnRFormNotFinalised)
+        );
+
+        // ProgramCodeNotMapped
+        assert_eq!(
+            generate_lmis_export(&context, &store_id, &mock_rnr_form_a().id, format),
+            Err(LmisExportError::ProgramCodeNotMapped)
+        );
+
+        service
+            .upsert_lmis_code_mapping(
+                &context,
+                UpsertLmisCodeMapping {
+                    id: "program_mapping".to_string(),
+                    program_id: mock_program_b().id,
+                    r#type: LmisCodeMappingType::Program,
+                    internal_id: mock_program_b().id,
+                    external_code: "ESS_MEDS".to_string(),
+                },
+            )
+            .unwrap();
+
+        // FacilityCodeNotMapped
+        assert_eq!(
+            generate_lmis_export(&context, &store_id, &mock_rnr_form_a().id, format),
+            Err(LmisExportError::FacilityCodeNotMapped)
+        );
+
+        service
+            .upsert_lmis_code_mapping(
+                &context,
+                UpsertLmisCodeMapping {
+                    id: "facility_mapping".to_string(),
+                    program_id: mock_program_b().id,
+                    r#type: LmisCodeMappingType::Facility,
+                    internal_id: store_id.clone(),
+                    external_code: "HC01".to_string(),
+                },
+            )
+            .unwrap();
+
+        // ItemCodesNotMapped
+        assert_eq!(
+            generate_lmis_export(&context, &store_id, &mock_rnr_form_a().id, format),
+            Err(LmisExportError::ItemCodesNotMapped(vec![
+                item_query_test1().code
+            ]))
+        );
+    }
+
+    #[actix_rt::test]
+    async fn upsert_lmis_code_mapping_errors() {
+        let (_, _, connection_manager, _) =
+            setup_all("upsert_lmis_code_mapping_errors", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.rnr_form_service;
+
+        let mapping = UpsertLmisCodeMapping {
+            id: "item_mapping".to_string(),
+            program_id: mock_program_b().id,
+            r#type: LmisCodeMappingType::Item,
+            internal_id: item_query_test1().id,
+            external_code: "C100".to_string(),
+        };
+
+        // ProgramDoesNotExist
+        assert_eq!(
+            service.upsert_lmis_code_mapping(
+                &context,
+                UpsertLmisCodeMapping {
+                    program_id: "invalid".to_string(),
+                    ..mapping.clone()
+                }
+            ),
+            Err(UpsertLmisCodeMappingError::ProgramDoesNotExist)
+        );
+
+        // ExternalCodeCannotBeEmpty
+        assert_eq!(
+            service.upsert_lmis_code_mapping(
+                &context,
+                UpsertLmisCodeMapping {
+                    external_code: " ".to_string(),
+                    ..mapping.clone()
+                }
+            ),
+            Err(UpsertLmisCodeMappingError::ExternalCodeCannotBeEmpty)
+        );
+
+        service
+            .upsert_lmis_code_mapping(&context, mapping.clone())
+            .unwrap();
+
+        // DuplicateMapping
+        assert_eq!(
+            service.upsert_lmis_code_mapping(
+                &context,
+                UpsertLmisCodeMapping {
+                    id: "item_mapping_2".to_string(),
+                    ..mapping.clone()
+                }
+            ),
+            Err(UpsertLmisCodeMappingError::DuplicateMapping)
+        );
+
+        // Can map again once the first mapping is deleted
+        service
+            .delete_lmis_code_mapping(&context, mapping.id.clone())
+            .unwrap();
+        assert!(service
+            .upsert_lmis_code_mapping(
+                &context,
+                UpsertLmisCodeMapping {
+                    id: "item_mapping_2".to_string(),
+                    ..mapping
+                }
+            )
+            .is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn lmis_export_success() {
+        let (_, _, connection_manager, _) =
+            setup_all("lmis_export_success", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.rnr_form_service;
+        let store_id = mock_store_a().id;
+
+        let mappings = vec![
+            (
+                LmisCodeMappingType::Program,
+                mock_program_b().id,
+                "ESS_MEDS",
+            ),
+            (LmisCodeMappingType::Facility, store_id.clone(), "HC01"),
+            (LmisCodeMappingType::Item, item_query_test1().id, "C100"),
+            (
+                LmisCodeMappingType::Field,
+                "stockInHand".to_string(),
+                "SOH_COC",
+            ),
+        ];
+        for (index, (r#type, internal_id, external_code)) in mappings.into_iter().enumerate() {
+            service
+                .upsert_lmis_code_mapping(
+                    &context,
+                    UpsertLmisCodeMapping {
+                        id: format!("mapping_{}", index),
+                        program_id: mock_program_b().id,
+                        r#type,
+                        internal_id,
+                        external_code: external_code.to_string(),
+                    },
+                )
+                .unwrap();
+        }
+
+        // OpenLMIS
+        let result: Value = serde_json::from_str(
+            &generate_lmis_export(
+                &context,
+                &store_id,
+                &mock_rnr_form_a().id,
+                LmisExportFormat::OpenLmisJson,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(result["programCode"], json!("ESS_MEDS"));
+        assert_eq!(result["facilityCode"], json!("HC01"));
+        assert_eq!(result["periodStartDate"], json!("2024-01-01"));
+        assert_eq!(result["products"][0]["productCode"], json!("C100"));
+        // Mapped field uses the external code as its key
+        assert_eq!(result["products"][0]["SOH_COC"], json!(5.0));
+        assert_eq!(result["products"][0]["beginningBalance"], json!(0.0));
+
+        // DHIS2
+        let result: Value = serde_json::from_str(
+            &generate_lmis_export(
+                &context,
+                &store_id,
+                &mock_rnr_form_a().id,
+                LmisExportFormat::Dhis2DataValueSet,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(result["dataSet"], json!("ESS_MEDS"));
+        assert_eq!(result["orgUnit"], json!("HC01"));
+        assert_eq!(result["period"], json!("202401"));
+        assert!(result["dataValues"].as_array().unwrap().contains(&json!({
+            "dataElement": "C100",
+            "categoryOptionCombo": "SOH_COC",
+            "value": "5",
+        })));
+
+        // CSV
+        let result = generate_lmis_export(
+            &context,
+            &store_id,
+            &mock_rnr_form_a().id,
+            LmisExportFormat::Csv,
+        )
+        .unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            "programCode,facilityCode,periodStartDate,periodEndDate,productCode,beginningBalance,quantityReceived,quantityDispensed,adjustments,losses,SOH_COC,stockOutDays,requestedQuantity,remarks"
+        );
+        assert_eq!(
+            lines[1],
+            "ESS_MEDS,HC01,2024-01-01,2024-01-31,C100,0,0,0,0,0,5,0,0,"
+        );
+    }
+}
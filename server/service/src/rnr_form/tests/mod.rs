@@ -12,3 +12,6 @@ mod update;
 
 #[cfg(test)]
 mod finalise;
+
+#[cfg(test)]
+mod lmis_export;
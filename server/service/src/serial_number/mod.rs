@@ -0,0 +1,229 @@
+use repository::{
+    InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineType, ItemLinkRowRepository,
+    ItemOmsFieldsRow, ItemRowRepository, RepositoryError, SerialNumberMovementRow,
+    SerialNumberMovementRowRepository, SerialNumberRow, SerialNumberRowRepository,
+    SerialNumberStatus, StorageConnection,
+};
+
+use crate::service_provider::ServiceContext;
+
+pub mod pick;
+pub use self::pick::*;
+pub mod receive;
+pub use self::receive::*;
+
+#[cfg(test)]
+mod test;
+
+#[derive(Debug, PartialEq)]
+pub enum UpdateItemIsSerialisedError {
+    ItemDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for UpdateItemIsSerialisedError {
+    fn from(error: RepositoryError) -> Self {
+        UpdateItemIsSerialisedError::DatabaseError(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SerialNumberTrace {
+    pub serial_number: SerialNumberRow,
+    /// Oldest first
+    pub movements: Vec<SerialNumberMovementRow>,
+}
+
+pub trait SerialNumberServiceTrait: Sync + Send {
+    /// Units received into the stock line, including units already issued
+    fn get_stock_line_serial_numbers(
+        &self,
+        ctx: &ServiceContext,
+        stock_line_id: &str,
+    ) -> Result<Vec<SerialNumberRow>, RepositoryError> {
+        let serial_numbers = SerialNumberRowRepository::new(&ctx.connection)
+            .find_many_by_stock_line_id(stock_line_id)?;
+        Ok(serial_numbers
+            .into_iter()
+            .filter(|serial_number| serial_number.store_id == ctx.store_id)
+            .collect())
+    }
+
+    fn receive_serial_numbers(
+        &self,
+        ctx: &ServiceContext,
+        input: ReceiveSerialNumbers,
+    ) -> Result<Vec<SerialNumberRow>, ReceiveSerialNumbersError> {
+        receive_serial_numbers(ctx, input)
+    }
+
+    fn pick_serial_numbers(
+        &self,
+        ctx: &ServiceContext,
+        input: PickSerialNumbers,
+    ) -> Result<Vec<SerialNumberRow>, PickSerialNumbersError> {
+        pick_serial_numbers(ctx, input)
+    }
+
+    /// Every unit with the serial number, in any store of this site, with its receipts and issues
+    fn trace_serial_number(
+        &self,
+        ctx: &ServiceContext,
+        serial: &str,
+    ) -> Result<Vec<SerialNumberTrace>, RepositoryError> {
+        trace_serial_number(&ctx.connection, serial)
+    }
+
+    /// Serialised items need a serial number for every unit before a shipment or prescription is
+    /// finalised, set on the central server
+    fn update_item_is_serialised(
+        &self,
+        ctx: &ServiceContext,
+        item_id: &str,
+        is_serialised: bool,
+    ) -> Result<ItemOmsFieldsRow, UpdateItemIsSerialisedError> {
+        let repository = ItemRowRepository::new(&ctx.connection);
+        repository
+            .find_one_by_id(item_id)?
+            .ok_or(UpdateItemIsSerialisedError::ItemDoesNotExist)?;
+        repository.update_is_serialised(item_id, is_serialised)?;
+        repository
+            .find_one_oms_fields_by_id(item_id)?
+            .ok_or(UpdateItemIsSerialisedError::ItemDoesNotExist)
+    }
+}
+
+pub struct SerialNumberService {}
+impl SerialNumberServiceTrait for SerialNumberService {}
+
+pub fn trace_serial_number(
+    connection: &StorageConnection,
+    serial: &str,
+) -> Result<Vec<SerialNumberTrace>, RepositoryError> {
+    let serial_numbers =
+        SerialNumberRowRepository::new(connection).find_many_by_serial_number(serial)?;
+    let ids: Vec<String> = serial_numbers.iter().map(|row| row.id.clone()).collect();
+    let movements =
+        SerialNumberMovementRowRepository::new(connection).find_many_by_serial_number_ids(&ids)?;
+
+    Ok(serial_numbers
+        .into_iter()
+        .map(|serial_number| SerialNumberTrace {
+            movements: movements
+                .iter()
+                .filter(|movement| movement.serial_number_id == serial_number.id)
+                .cloned()
+                .collect(),
+            serial_number,
+        })
+        .collect())
+}
+
+/// Returns units picked on a stock out line to stock, for when the line is deleted
+pub(crate) fn release_serial_numbers(
+    connection: &StorageConnection,
+    invoice_line_id: &str,
+) -> Result<(), RepositoryError> {
+    for serial_number in SerialNumberRowRepository::new(connection)
+        .find_many_by_issued_invoice_line_id(invoice_line_id)?
+    {
+        return_to_stock(connection, serial_number, invoice_line_id)?;
+    }
+    Ok(())
+}
+
+/// Returns units picked on a stock out line to stock when they no longer match the line, i.e. the
+/// line was changed to another stock line or its quantity was reduced below the picked units
+pub(crate) fn reconcile_picked_serial_numbers(
+    connection: &StorageConnection,
+    line: &InvoiceLineRow,
+) -> Result<(), RepositoryError> {
+    let mut units = number_of_units(line.number_of_packs, line.pack_size);
+    for serial_number in
+        SerialNumberRowRepository::new(connection).find_many_by_issued_invoice_line_id(&line.id)?
+    {
+        if Some(&serial_number.stock_line_id) == line.stock_line_id.as_ref() && units > 0 {
+            units -= 1;
+            continue;
+        }
+        return_to_stock(connection, serial_number, &line.id)?;
+    }
+    Ok(())
+}
+
+fn return_to_stock(
+    connection: &StorageConnection,
+    mut serial_number: SerialNumberRow,
+    invoice_line_id: &str,
+) -> Result<(), RepositoryError> {
+    SerialNumberMovementRowRepository::new(connection)
+        .delete_by_serial_number_and_invoice_line(&serial_number.id, invoice_line_id)?;
+    serial_number.status = SerialNumberStatus::InStock;
+    serial_number.issued_invoice_line_id = None;
+    serial_number.issued_name_link_id = None;
+    SerialNumberRowRepository::new(connection).upsert_one(&serial_number)?;
+    Ok(())
+}
+
+/// Removes units received on a stock in line, for when the line and its stock line are deleted
+pub(crate) fn delete_received_serial_numbers(
+    connection: &StorageConnection,
+    invoice_line_id: &str,
+) -> Result<(), RepositoryError> {
+    let repository = SerialNumberRowRepository::new(connection);
+    let movement_repository = SerialNumberMovementRowRepository::new(connection);
+    for serial_number in repository.find_many_by_received_invoice_line_id(invoice_line_id)? {
+        movement_repository.delete_by_serial_number_id(&serial_number.id)?;
+        repository.delete(&serial_number.id)?;
+    }
+    Ok(())
+}
+
+/// Lines of serialised items without a serial number for every unit, received units for stock in
+/// lines and picked units for stock out lines
+pub(crate) fn lines_missing_serial_numbers(
+    connection: &StorageConnection,
+    invoice_id: &str,
+) -> Result<Vec<String>, RepositoryError> {
+    let item_link_repository = ItemLinkRowRepository::new(connection);
+    let item_repository = ItemRowRepository::new(connection);
+    let serial_number_repository = SerialNumberRowRepository::new(connection);
+    let mut result = Vec::new();
+    for line in InvoiceLineRowRepository::new(connection).find_many_by_invoice_id(invoice_id)? {
+        let Some(item_link) = item_link_repository.find_one_by_id(&line.item_link_id)? else {
+            continue;
+        };
+        let is_serialised = item_repository
+            .find_one_oms_fields_by_id(&item_link.item_id)?
+            .is_some_and(|fields| fields.is_serialised);
+        if !is_serialised {
+            continue;
+        }
+        let recorded = match line.r#type {
+            InvoiceLineType::StockIn => serial_number_repository
+                .find_many_by_received_invoice_line_id(&line.id)?
+                .len(),
+            InvoiceLineType::StockOut => serial_number_repository
+                .find_many_by_issued_invoice_line_id(&line.id)?
+                .len(),
+            _ => continue,
+        };
+        if recorded < number_of_units(line.number_of_packs, line.pack_size) {
+            result.push(line.id);
+        }
+    }
+    Ok(result)
+}
+
+/// Number of units on an invoice line, serial numbers are recorded per unit rather than per pack
+fn number_of_units(number_of_packs: f64, pack_size: f64) -> usize {
+    (number_of_packs * pack_size).floor().max(0.0) as usize
+}
+
+fn find_repeated(serials: &[String]) -> Option<String> {
+    serials
+        .iter()
+        .enumerate()
+        .find(|(index, serial)| serials[..*index].contains(serial))
+        .map(|(_, serial)| serial.clone())
+}
@@ -0,0 +1,124 @@
+use chrono::Utc;
+use repository::{
+    InvoiceLineRowRepository, InvoiceLineType, InvoiceRowRepository, InvoiceType, RepositoryError,
+    SerialNumberMovementRow, SerialNumberMovementRowRepository, SerialNumberMovementType,
+    SerialNumberRow, SerialNumberRowRepository, SerialNumberStatus,
+};
+use util::uuid::uuid;
+
+use crate::{invoice::check_invoice_is_editable, service_provider::ServiceContext};
+
+use super::{find_repeated, number_of_units, return_to_stock};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PickSerialNumbers {
+    /// Stock out line of an outbound shipment or prescription
+    pub invoice_line_id: String,
+    /// Replaces the units previously picked on the line
+    pub serials: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PickSerialNumbersError {
+    InvoiceLineDoesNotExist,
+    NotThisStoreInvoice,
+    NotAStockOutLine,
+    CannotEditInvoice,
+    SerialNumberIsRepeated(String),
+    SerialNumberNotOnStockLine(String),
+    SerialNumberAlreadyIssued(String),
+    MoreSerialNumbersThanUnits,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for PickSerialNumbersError {
+    fn from(error: RepositoryError) -> Self {
+        PickSerialNumbersError::DatabaseError(error)
+    }
+}
+
+type OutError = PickSerialNumbersError;
+
+/// Picks units of the line's stock line by serial number, returns the units picked on the line
+pub fn pick_serial_numbers(
+    ctx: &ServiceContext,
+    input: PickSerialNumbers,
+) -> Result<Vec<SerialNumberRow>, OutError> {
+    let serial_numbers = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let line = InvoiceLineRowRepository::new(connection)
+                .find_one_by_id(&input.invoice_line_id)?
+                .ok_or(OutError::InvoiceLineDoesNotExist)?;
+            let invoice = InvoiceRowRepository::new(connection)
+                .find_one_by_id(&line.invoice_id)?
+                .ok_or(OutError::InvoiceLineDoesNotExist)?;
+            if invoice.store_id != ctx.store_id {
+                return Err(OutError::NotThisStoreInvoice);
+            }
+            if !matches!(
+                invoice.r#type,
+                InvoiceType::OutboundShipment | InvoiceType::Prescription
+            ) || line.r#type != InvoiceLineType::StockOut
+            {
+                return Err(OutError::NotAStockOutLine);
+            }
+            if !check_invoice_is_editable(&invoice) {
+                return Err(OutError::CannotEditInvoice);
+            }
+            let stock_line_id = line.stock_line_id.clone().unwrap_or_default();
+
+            if let Some(serial) = find_repeated(&input.serials) {
+                return Err(OutError::SerialNumberIsRepeated(serial));
+            }
+            if input.serials.len() > number_of_units(line.number_of_packs, line.pack_size) {
+                return Err(OutError::MoreSerialNumbersThanUnits);
+            }
+
+            let repository = SerialNumberRowRepository::new(connection);
+            let stock_line_serial_numbers =
+                repository.find_many_by_stock_line_id(&stock_line_id)?;
+            let mut to_pick = Vec::new();
+            for serial in &input.serials {
+                let serial_number = stock_line_serial_numbers
+                    .iter()
+                    .find(|serial_number| &serial_number.serial == serial)
+                    .ok_or(OutError::SerialNumberNotOnStockLine(serial.clone()))?;
+                match &serial_number.issued_invoice_line_id {
+                    Some(issued_line_id) if issued_line_id == &line.id => {}
+                    Some(_) => return Err(OutError::SerialNumberAlreadyIssued(serial.clone())),
+                    None => to_pick.push(serial_number.clone()),
+                }
+            }
+
+            let movement_repository = SerialNumberMovementRowRepository::new(connection);
+            for serial_number in repository.find_many_by_issued_invoice_line_id(&line.id)? {
+                if !input.serials.contains(&serial_number.serial) {
+                    return_to_stock(connection, serial_number, &line.id)?;
+                }
+            }
+
+            let now = Utc::now().naive_utc();
+            for mut serial_number in to_pick {
+                serial_number.status = SerialNumberStatus::Issued;
+                serial_number.issued_invoice_line_id = Some(line.id.clone());
+                serial_number.issued_name_link_id = Some(invoice.name_link_id.clone());
+                repository.upsert_one(&serial_number)?;
+                movement_repository.upsert_one(&SerialNumberMovementRow {
+                    id: uuid(),
+                    serial_number_id: serial_number.id,
+                    store_id: ctx.store_id.clone(),
+                    r#type: SerialNumberMovementType::Issued,
+                    invoice_id: invoice.id.clone(),
+                    invoice_line_id: line.id.clone(),
+                    datetime: now,
+                })?;
+            }
+
+            let serial_numbers = repository.find_many_by_issued_invoice_line_id(&line.id)?;
+            Ok(serial_numbers) as Result<Vec<SerialNumberRow>, OutError>
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(serial_numbers)
+}
@@ -0,0 +1,224 @@
+use chrono::Utc;
+use repository::{
+    barcode::{BarcodeFilter, BarcodeRepository},
+    EqualFilter, InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineType, InvoiceRowRepository,
+    InvoiceType, ItemLinkRowRepository, RepositoryError, SerialNumberMovementRow,
+    SerialNumberMovementRowRepository, SerialNumberMovementType, SerialNumberRow,
+    SerialNumberRowRepository, SerialNumberStatus, StorageConnection,
+};
+use util::{uuid::uuid, GS1ParseError, GS1};
+
+use crate::{invoice::check_invoice_is_editable, service_provider::ServiceContext};
+
+use super::{find_repeated, number_of_units};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReceiveSerialNumbers {
+    /// Stock in line of an inbound shipment or customer return
+    pub invoice_line_id: String,
+    /// Scanned GS1 DataMatrix strings, one per unit
+    pub scanned_data: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReceiveSerialNumbersError {
+    InvoiceLineDoesNotExist,
+    NotThisStoreInvoice,
+    NotAStockInLine,
+    CannotEditInvoice,
+    /// Serial numbers are recorded once the shipment is delivered and the stock line exists
+    StockNotYetReceived,
+    NotGs1Data(String),
+    NoSerialNumber(String),
+    GtinIsForAnotherItem(String),
+    SerialNumberIsRepeated(String),
+    SerialNumberAlreadyInStock(String),
+    MoreSerialNumbersThanUnits,
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for ReceiveSerialNumbersError {
+    fn from(error: RepositoryError) -> Self {
+        ReceiveSerialNumbersError::DatabaseError(error)
+    }
+}
+
+type OutError = ReceiveSerialNumbersError;
+
+/// Records the serial numbers of units received on the line, returns all units of the line
+pub fn receive_serial_numbers(
+    ctx: &ServiceContext,
+    input: ReceiveSerialNumbers,
+) -> Result<Vec<SerialNumberRow>, OutError> {
+    let serial_numbers = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let line = InvoiceLineRowRepository::new(connection)
+                .find_one_by_id(&input.invoice_line_id)?
+                .ok_or(OutError::InvoiceLineDoesNotExist)?;
+            let invoice = InvoiceRowRepository::new(connection)
+                .find_one_by_id(&line.invoice_id)?
+                .ok_or(OutError::InvoiceLineDoesNotExist)?;
+            if invoice.store_id != ctx.store_id {
+                return Err(OutError::NotThisStoreInvoice);
+            }
+            if !matches!(
+                invoice.r#type,
+                InvoiceType::InboundShipment | InvoiceType::CustomerReturn
+            ) || line.r#type != InvoiceLineType::StockIn
+            {
+                return Err(OutError::NotAStockInLine);
+            }
+            if !check_invoice_is_editable(&invoice) {
+                return Err(OutError::CannotEditInvoice);
+            }
+            let stock_line_id = line
+                .stock_line_id
+                .clone()
+                .ok_or(OutError::StockNotYetReceived)?;
+
+            let item_id = ItemLinkRowRepository::new(connection)
+                .find_one_by_id(&line.item_link_id)?
+                .map(|item_link| item_link.item_id)
+                .unwrap_or(line.item_link_id.clone());
+
+            let mut scanned = Vec::new();
+            for data in &input.scanned_data {
+                let gs1 = match GS1::parse(data.clone()) {
+                    Ok(gs1) => gs1,
+                    Err(GS1ParseError::InvalidFormat) => {
+                        return Err(OutError::NotGs1Data(data.clone()))
+                    }
+                };
+                let serial = gs1
+                    .serial_number()
+                    .ok_or(OutError::NoSerialNumber(data.clone()))?;
+                let gtin = gs1.gtin();
+                if let Some(gtin) = &gtin {
+                    let barcodes = BarcodeRepository::new(connection)
+                        .query_by_filter(BarcodeFilter::new().gtin(EqualFilter::equal_to(gtin)))?;
+                    if barcodes
+                        .iter()
+                        .any(|barcode| barcode.barcode_row.item_id != item_id)
+                    {
+                        return Err(OutError::GtinIsForAnotherItem(data.clone()));
+                    }
+                }
+                scanned.push((serial, gtin));
+            }
+
+            let serials: Vec<String> = scanned.iter().map(|(serial, _)| serial.clone()).collect();
+            if let Some(serial) = find_repeated(&serials) {
+                return Err(OutError::SerialNumberIsRepeated(serial));
+            }
+
+            let repository = SerialNumberRowRepository::new(connection);
+            for serial in &serials {
+                let in_stock = repository
+                    .find_many_by_serial_number(serial)?
+                    .into_iter()
+                    .any(|existing| {
+                        existing.store_id == ctx.store_id
+                            && existing.item_link_id == line.item_link_id
+                            && existing.status == SerialNumberStatus::InStock
+                    });
+                if in_stock {
+                    return Err(OutError::SerialNumberAlreadyInStock(serial.clone()));
+                }
+            }
+
+            let received = repository.find_many_by_received_invoice_line_id(&line.id)?;
+            if received.len() + serials.len()
+                > number_of_units(line.number_of_packs, line.pack_size)
+            {
+                return Err(OutError::MoreSerialNumbersThanUnits);
+            }
+
+            let movement_repository = SerialNumberMovementRowRepository::new(connection);
+            let now = Utc::now().naive_utc();
+            for (serial, gtin) in scanned {
+                let serial_number = SerialNumberRow {
+                    id: uuid(),
+                    store_id: ctx.store_id.clone(),
+                    item_link_id: line.item_link_id.clone(),
+                    serial,
+                    gtin,
+                    stock_line_id: stock_line_id.clone(),
+                    status: SerialNumberStatus::InStock,
+                    received_invoice_line_id: line.id.clone(),
+                    issued_invoice_line_id: None,
+                    issued_name_link_id: None,
+                    created_datetime: now,
+                };
+                repository.upsert_one(&serial_number)?;
+                movement_repository.upsert_one(&SerialNumberMovementRow {
+                    id: uuid(),
+                    serial_number_id: serial_number.id,
+                    store_id: ctx.store_id.clone(),
+                    r#type: SerialNumberMovementType::Received,
+                    invoice_id: invoice.id.clone(),
+                    invoice_line_id: line.id.clone(),
+                    datetime: now,
+                })?;
+            }
+
+            let serial_numbers = repository.find_many_by_received_invoice_line_id(&line.id)?;
+            Ok(serial_numbers) as Result<Vec<SerialNumberRow>, OutError>
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(serial_numbers)
+}
+
+/// Receives the units issued on the outbound shipment line of a transfer into the stock line of
+/// the inbound shipment line. Units issued to a store on another site are synced to this site
+pub(crate) fn receive_transferred_serial_numbers(
+    connection: &StorageConnection,
+    store_id: &str,
+    line: &InvoiceLineRow,
+) -> Result<(), RepositoryError> {
+    let (Some(outbound_line_id), Some(stock_line_id)) =
+        (&line.linked_invoice_line_id, &line.stock_line_id)
+    else {
+        return Ok(());
+    };
+
+    let repository = SerialNumberRowRepository::new(connection);
+    let movement_repository = SerialNumberMovementRowRepository::new(connection);
+    let received: Vec<String> = repository
+        .find_many_by_received_invoice_line_id(&line.id)?
+        .into_iter()
+        .map(|serial_number| serial_number.serial)
+        .collect();
+    let now = Utc::now().naive_utc();
+    for issued in repository.find_many_by_issued_invoice_line_id(outbound_line_id)? {
+        if received.contains(&issued.serial) {
+            continue;
+        }
+        let serial_number = SerialNumberRow {
+            id: uuid(),
+            store_id: store_id.to_string(),
+            item_link_id: line.item_link_id.clone(),
+            serial: issued.serial,
+            gtin: issued.gtin,
+            stock_line_id: stock_line_id.clone(),
+            status: SerialNumberStatus::InStock,
+            received_invoice_line_id: line.id.clone(),
+            issued_invoice_line_id: None,
+            issued_name_link_id: None,
+            created_datetime: now,
+        };
+        repository.upsert_one(&serial_number)?;
+        movement_repository.upsert_one(&SerialNumberMovementRow {
+            id: uuid(),
+            serial_number_id: serial_number.id,
+            store_id: store_id.to_string(),
+            r#type: SerialNumberMovementType::Received,
+            invoice_id: line.invoice_id.clone(),
+            invoice_line_id: line.id.clone(),
+            datetime: now,
+        })?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,476 @@
+#[cfg(test)]
+mod serial_number {
+    use repository::{
+        mock::{
+            mock_item_a, mock_item_b, mock_name_a, mock_name_store_a, mock_store_a, mock_store_b,
+            MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        BarcodeRow, InvoiceLineRow, InvoiceLineType, InvoiceRow, InvoiceStatus, InvoiceType,
+        SerialNumberMovementType, SerialNumberStatus, StockLineRow,
+    };
+    use util::inline_init;
+
+    use crate::{
+        invoice::{
+            inbound_shipment::{
+                UpdateInboundShipment, UpdateInboundShipmentError, UpdateInboundShipmentStatus,
+            },
+            prescription::{UpdatePrescription, UpdatePrescriptionError, UpdatePrescriptionStatus},
+        },
+        invoice_line::{
+            stock_in_line::{DeleteStockInLine, StockInType},
+            stock_out_line::{DeleteStockOutLine, StockOutType, UpdateStockOutLine},
+        },
+        serial_number::{
+            PickSerialNumbers, PickSerialNumbersError, ReceiveSerialNumbers,
+            ReceiveSerialNumbersError,
+        },
+        service_provider::ServiceProvider,
+    };
+
+    fn barcode(gtin: &str, item_id: String) -> BarcodeRow {
+        BarcodeRow {
+            id: gtin.to_string(),
+            gtin: gtin.to_string(),
+            item_id,
+            ..Default::default()
+        }
+    }
+
+    fn stock_line() -> StockLineRow {
+        inline_init(|r: &mut StockLineRow| {
+            r.id = "serial_stock_line".to_string();
+            r.store_id = mock_store_a().id;
+            r.item_link_id = mock_item_a().id;
+            r.pack_size = 1.0;
+            // Reserved by the outbound shipment and prescription lines
+            r.available_number_of_packs = 0.0;
+            r.total_number_of_packs = 3.0;
+        })
+    }
+
+    fn invoice(id: &str, r#type: InvoiceType, status: InvoiceStatus) -> InvoiceRow {
+        inline_init(|r: &mut InvoiceRow| {
+            r.id = id.to_string();
+            r.name_link_id = mock_name_a().id;
+            r.store_id = mock_store_a().id;
+            r.r#type = r#type;
+            r.status = status;
+        })
+    }
+
+    fn line(
+        invoice_id: &str,
+        r#type: InvoiceLineType,
+        stock_line_id: Option<String>,
+        number_of_packs: f64,
+    ) -> InvoiceLineRow {
+        inline_init(|r: &mut InvoiceLineRow| {
+            r.id = format!("{invoice_id}_line");
+            r.invoice_id = invoice_id.to_string();
+            r.item_link_id = mock_item_a().id;
+            r.r#type = r#type;
+            r.stock_line_id = stock_line_id;
+            r.pack_size = 1.0;
+            r.number_of_packs = number_of_packs;
+        })
+    }
+
+    #[actix_rt::test]
+    async fn serial_number() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "serial_number",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.barcodes = vec![
+                    barcode("0123456789", mock_item_a().id),
+                    barcode("9876543210", mock_item_b().id),
+                ];
+                r.stock_lines = vec![stock_line()];
+                r.invoices = vec![
+                    invoice(
+                        "serial_inbound",
+                        InvoiceType::InboundShipment,
+                        InvoiceStatus::Delivered,
+                    ),
+                    invoice(
+                        "serial_inbound_new",
+                        InvoiceType::InboundShipment,
+                        InvoiceStatus::New,
+                    ),
+                    invoice(
+                        "serial_outbound",
+                        InvoiceType::OutboundShipment,
+                        InvoiceStatus::Allocated,
+                    ),
+                    invoice(
+                        "serial_prescription",
+                        InvoiceType::Prescription,
+                        InvoiceStatus::New,
+                    ),
+                    // Transfer of the outbound shipment to store B
+                    InvoiceRow {
+                        store_id: mock_store_b().id,
+                        name_link_id: mock_name_store_a().id,
+                        ..invoice(
+                            "serial_transfer",
+                            InvoiceType::InboundShipment,
+                            InvoiceStatus::Shipped,
+                        )
+                    },
+                ];
+                r.invoice_lines = vec![
+                    line(
+                        "serial_inbound",
+                        InvoiceLineType::StockIn,
+                        Some(stock_line().id),
+                        3.0,
+                    ),
+                    line("serial_inbound_new", InvoiceLineType::StockIn, None, 3.0),
+                    line(
+                        "serial_outbound",
+                        InvoiceLineType::StockOut,
+                        Some(stock_line().id),
+                        2.0,
+                    ),
+                    line(
+                        "serial_prescription",
+                        InvoiceLineType::StockOut,
+                        Some(stock_line().id),
+                        1.0,
+                    ),
+                    InvoiceLineRow {
+                        linked_invoice_line_id: Some("serial_outbound_line".to_string()),
+                        ..line("serial_transfer", InvoiceLineType::StockIn, None, 2.0)
+                    },
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = &service_provider.serial_number_service;
+
+        let receive = |scanned_data: &[&str]| {
+            service.receive_serial_numbers(
+                &context,
+                ReceiveSerialNumbers {
+                    invoice_line_id: "serial_inbound_line".to_string(),
+                    scanned_data: scanned_data.iter().map(|data| data.to_string()).collect(),
+                },
+            )
+        };
+        let pick = |invoice_line_id: &str, serials: &[&str]| {
+            service.pick_serial_numbers(
+                &context,
+                PickSerialNumbers {
+                    invoice_line_id: invoice_line_id.to_string(),
+                    serials: serials.iter().map(|serial| serial.to_string()).collect(),
+                },
+            )
+        };
+
+        // Receive errors
+        assert_eq!(
+            service.receive_serial_numbers(
+                &context,
+                ReceiveSerialNumbers {
+                    invoice_line_id: "serial_inbound_new_line".to_string(),
+                    scanned_data: vec!["(01)0123456789(21)S1".to_string()],
+                }
+            ),
+            Err(ReceiveSerialNumbersError::StockNotYetReceived)
+        );
+        assert_eq!(
+            receive(&["S1"]),
+            Err(ReceiveSerialNumbersError::NotGs1Data("S1".to_string()))
+        );
+        assert_eq!(
+            receive(&["(01)0123456789"]),
+            Err(ReceiveSerialNumbersError::NoSerialNumber(
+                "(01)0123456789".to_string()
+            ))
+        );
+        // GTIN of item_b
+        assert_eq!(
+            receive(&["(01)9876543210(21)S1"]),
+            Err(ReceiveSerialNumbersError::GtinIsForAnotherItem(
+                "(01)9876543210(21)S1".to_string()
+            ))
+        );
+        assert_eq!(
+            receive(&["(21)S1", "(01)0123456789(21)S1"]),
+            Err(ReceiveSerialNumbersError::SerialNumberIsRepeated(
+                "S1".to_string()
+            ))
+        );
+        assert_eq!(
+            receive(&["(21)S1", "(21)S2", "(21)S3", "(21)S4"]),
+            Err(ReceiveSerialNumbersError::MoreSerialNumbersThanUnits)
+        );
+
+        // Receive
+        let received = receive(&["(01)0123456789(21)S1", "(21)S2"]).unwrap();
+        assert_eq!(received.len(), 2);
+        assert!(received
+            .iter()
+            .all(|serial_number| serial_number.stock_line_id == stock_line().id));
+        assert_eq!(
+            receive(&["(21)S2"]),
+            Err(ReceiveSerialNumbersError::SerialNumberAlreadyInStock(
+                "S2".to_string()
+            ))
+        );
+        assert_eq!(
+            service
+                .get_stock_line_serial_numbers(&context, &stock_line().id)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // Pick
+        assert_eq!(
+            pick("serial_inbound_line", &["S1"]),
+            Err(PickSerialNumbersError::NotAStockOutLine)
+        );
+        assert_eq!(
+            pick("serial_outbound_line", &["S3"]),
+            Err(PickSerialNumbersError::SerialNumberNotOnStockLine(
+                "S3".to_string()
+            ))
+        );
+        let picked = pick("serial_outbound_line", &["S1", "S2"]).unwrap();
+        assert!(picked
+            .iter()
+            .all(|serial_number| serial_number.status == SerialNumberStatus::Issued));
+        assert_eq!(
+            pick("serial_prescription_line", &["S1"]),
+            Err(PickSerialNumbersError::SerialNumberAlreadyIssued(
+                "S1".to_string()
+            ))
+        );
+
+        // Picking again replaces the units of the line
+        let picked = pick("serial_outbound_line", &["S1"]).unwrap();
+        assert_eq!(picked.len(), 1);
+        let picked = pick("serial_prescription_line", &["S2"]).unwrap();
+        assert_eq!(picked[0].serial, "S2");
+
+        // Trace
+        let trace = service.trace_serial_number(&context, "S1").unwrap();
+        assert_eq!(trace.len(), 1);
+        let movements: Vec<(SerialNumberMovementType, &str)> = trace[0]
+            .movements
+            .iter()
+            .map(|movement| (movement.r#type.clone(), movement.invoice_id.as_str()))
+            .collect();
+        assert_eq!(
+            movements,
+            vec![
+                (SerialNumberMovementType::Received, "serial_inbound"),
+                (SerialNumberMovementType::Issued, "serial_outbound"),
+            ]
+        );
+
+        // Units picked on the outbound shipment are received with the transferred shipment
+        let store_b_context = service_provider
+            .context(mock_store_b().id, "".to_string())
+            .unwrap();
+        service_provider
+            .invoice_service
+            .update_inbound_shipment(
+                &store_b_context,
+                UpdateInboundShipment {
+                    id: "serial_transfer".to_string(),
+                    status: Some(UpdateInboundShipmentStatus::Delivered),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let trace = service.trace_serial_number(&context, "S1").unwrap();
+        assert_eq!(trace.len(), 2);
+        assert_eq!(trace[1].serial_number.store_id, mock_store_b().id);
+        assert_eq!(trace[1].serial_number.status, SerialNumberStatus::InStock);
+        assert_eq!(
+            trace[1].serial_number.received_invoice_line_id,
+            "serial_transfer_line"
+        );
+
+        // Reducing the quantity below the picked units returns the extra units to stock
+        service_provider
+            .invoice_line_service
+            .update_stock_out_line(
+                &context,
+                UpdateStockOutLine {
+                    id: "serial_prescription_line".to_string(),
+                    r#type: Some(StockOutType::Prescription),
+                    number_of_packs: Some(0.0),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let trace = service.trace_serial_number(&context, "S2").unwrap();
+        assert_eq!(trace[0].serial_number.status, SerialNumberStatus::InStock);
+        assert_eq!(trace[0].serial_number.issued_invoice_line_id, None);
+
+        // Deleting the stock out line returns its units to stock
+        service_provider
+            .invoice_line_service
+            .delete_stock_out_line(
+                &context,
+                DeleteStockOutLine {
+                    id: "serial_outbound_line".to_string(),
+                    r#type: Some(StockOutType::OutboundShipment),
+                },
+            )
+            .unwrap();
+        let trace = service.trace_serial_number(&context, "S1").unwrap();
+        assert_eq!(trace[0].serial_number.status, SerialNumberStatus::InStock);
+        assert_eq!(trace[0].movements.len(), 1);
+
+        // Deleting the stock in line removes the units received on it
+        service_provider
+            .invoice_line_service
+            .delete_stock_out_line(
+                &context,
+                DeleteStockOutLine {
+                    id: "serial_prescription_line".to_string(),
+                    r#type: Some(StockOutType::Prescription),
+                },
+            )
+            .unwrap();
+        service_provider
+            .invoice_line_service
+            .delete_stock_in_line(
+                &context,
+                DeleteStockInLine {
+                    id: "serial_inbound_line".to_string(),
+                    r#type: StockInType::InboundShipment,
+                },
+            )
+            .unwrap();
+        let trace = service.trace_serial_number(&context, "S1").unwrap();
+        assert!(trace
+            .iter()
+            .all(|trace| trace.serial_number.store_id != mock_store_a().id));
+    }
+
+    #[actix_rt::test]
+    async fn serialised_item_finalise() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "serialised_item_finalise",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![stock_line()];
+                r.invoices = vec![
+                    invoice(
+                        "serial_inbound",
+                        InvoiceType::InboundShipment,
+                        InvoiceStatus::Delivered,
+                    ),
+                    invoice(
+                        "serial_prescription",
+                        InvoiceType::Prescription,
+                        InvoiceStatus::Picked,
+                    ),
+                ];
+                r.invoice_lines = vec![
+                    line(
+                        "serial_inbound",
+                        InvoiceLineType::StockIn,
+                        Some(stock_line().id),
+                        2.0,
+                    ),
+                    line(
+                        "serial_prescription",
+                        InvoiceLineType::StockOut,
+                        Some(stock_line().id),
+                        1.0,
+                    ),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = &service_provider.serial_number_service;
+        let invoice_service = &service_provider.invoice_service;
+        let verify_inbound = || {
+            invoice_service.update_inbound_shipment(
+                &context,
+                UpdateInboundShipment {
+                    id: "serial_inbound".to_string(),
+                    status: Some(UpdateInboundShipmentStatus::Verified),
+                    ..Default::default()
+                },
+            )
+        };
+        let verify_prescription = || {
+            invoice_service.update_prescription(
+                &context,
+                UpdatePrescription {
+                    id: "serial_prescription".to_string(),
+                    status: Some(UpdatePrescriptionStatus::Verified),
+                    ..Default::default()
+                },
+            )
+        };
+
+        service
+            .update_item_is_serialised(&context, &mock_item_a().id, true)
+            .unwrap();
+
+        // Every received unit needs a serial number
+        service
+            .receive_serial_numbers(
+                &context,
+                ReceiveSerialNumbers {
+                    invoice_line_id: "serial_inbound_line".to_string(),
+                    scanned_data: vec!["(21)S1".to_string()],
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            verify_inbound(),
+            Err(UpdateInboundShipmentError::SerialNumbersNotRecorded(vec![
+                "serial_inbound_line".to_string()
+            ]))
+        );
+        service
+            .receive_serial_numbers(
+                &context,
+                ReceiveSerialNumbers {
+                    invoice_line_id: "serial_inbound_line".to_string(),
+                    scanned_data: vec!["(21)S2".to_string()],
+                },
+            )
+            .unwrap();
+        assert!(verify_inbound().is_ok());
+
+        // Every issued unit needs a serial number
+        assert_eq!(
+            verify_prescription(),
+            Err(UpdatePrescriptionError::SerialNumbersNotRecorded(vec![
+                "serial_prescription_line".to_string()
+            ]))
+        );
+        service
+            .pick_serial_numbers(
+                &context,
+                PickSerialNumbers {
+                    invoice_line_id: "serial_prescription_line".to_string(),
+                    serials: vec!["S1".to_string()],
+                },
+            )
+            .unwrap();
+        assert!(verify_prescription().is_ok());
+    }
+}
@@ -1,15 +1,20 @@
 use crate::{
+    allocation_rule::{AllocationRuleService, AllocationRuleServiceTrait},
+    api_key::{ApiKeyService, ApiKeyServiceTrait},
     app_data::{AppDataService, AppDataServiceTrait},
     asset::AssetServiceTrait,
     auth::{AuthService, AuthServiceTrait},
+    backorder::{BackorderService, BackorderServiceTrait},
     barcode::{BarcodeService, BarcodeServiceTrait},
     catalogue::{AssetCatalogueServiceTrait, CatalogueService},
+    clinical_check::{ClinicalCheckService, ClinicalCheckServiceTrait},
     clinician::{ClinicianService, ClinicianServiceTrait},
     cold_chain::{ColdChainService, ColdChainServiceTrait},
     currency::{CurrencyService, CurrencyServiceTrait},
     dashboard::{
         invoice_count::{InvoiceCountService, InvoiceCountServiceTrait},
         item_count::{ItemCountServiceTrait, ItemServiceCount},
+        kpi::{KpiService, KpiServiceTrait},
         requisition_count::{RequisitionCountService, RequisitionCountServiceTrait},
         stock_expiry_count::{StockExpiryCountServiceTrait, StockExpiryServiceCount},
     },
@@ -22,14 +27,17 @@ use crate::{
     },
     invoice::{InvoiceService, InvoiceServiceTrait},
     invoice_line::{InvoiceLineService, InvoiceLineServiceTrait},
+    invoice_payment::{InvoicePaymentService, InvoicePaymentServiceTrait},
     item::ItemServiceTrait,
     item_stats::{ItemStatsService, ItemStatsServiceTrait},
     label_printer_settings_service::LabelPrinterSettingsServiceTrait,
     localisations::Localisations,
     location::{LocationService, LocationServiceTrait},
     log_service::{LogService, LogServiceTrait},
+    login_security::{LoginSecurityService, LoginSecurityServiceTrait},
     master_list::{MasterListService, MasterListServiceTrait},
     name::{NameService, NameServiceTrait},
+    pick_list::{PickListService, PickListServiceTrait},
     plugin_data::{PluginDataService, PluginDataServiceTrait},
     pricing::{PricingService, PricingServiceTrait},
     processors::ProcessorsTrigger,
@@ -41,24 +49,28 @@ use crate::{
         program_enrolment::{ProgramEnrolmentService, ProgramEnrolmentServiceTrait},
         program_event::{ProgramEventService, ProgramEventServiceTrait},
     },
+    recall::{RecallService, RecallServiceTrait},
     repack::{RepackService, RepackServiceTrait},
     report::report_service::{ReportService, ReportServiceTrait},
     requisition::{RequisitionService, RequisitionServiceTrait},
     requisition_line::{RequisitionLineService, RequisitionLineServiceTrait},
     rnr_form::{RnRFormService, RnRFormServiceTrait},
     sensor::{SensorService, SensorServiceTrait},
+    serial_number::{SerialNumberService, SerialNumberServiceTrait},
     settings_service::{SettingsService, SettingsServiceTrait},
     standard_reports::StandardReports,
     stock_line::{StockLineService, StockLineServiceTrait},
     stocktake::{StocktakeService, StocktakeServiceTrait},
     stocktake_line::{StocktakeLineService, StocktakeLineServiceTrait},
     store::{get_store, get_stores},
+    support_bundle::{SupportBundleService, SupportBundleServiceTrait},
     sync::{
         site_info::{SiteInfoService, SiteInfoTrait},
         sync_status::status::{SyncStatusService, SyncStatusTrait},
         synchroniser_driver::{SiteIsInitialisedTrigger, SyncTrigger},
     },
     temperature_excursion::{TemperatureExcursionService, TemperatureExcursionServiceTrait},
+    user_pin::{UserPinService, UserPinServiceTrait},
     vaccination::{VaccinationService, VaccinationServiceTrait},
     vaccine_course::VaccineCourseServiceTrait,
     ListError, ListResult,
@@ -71,6 +83,9 @@ use repository::{
 pub struct ServiceProvider {
     pub connection_manager: StorageConnectionManager,
     pub validation_service: Box<dyn AuthServiceTrait>,
+    pub api_key_service: Box<dyn ApiKeyServiceTrait>,
+    pub login_security_service: Box<dyn LoginSecurityServiceTrait>,
+    pub user_pin_service: Box<dyn UserPinServiceTrait>,
 
     pub location_service: Box<dyn LocationServiceTrait>,
 
@@ -85,10 +100,14 @@ pub struct ServiceProvider {
     pub stocktake_service: Box<dyn StocktakeServiceTrait>,
     pub stocktake_line_service: Box<dyn StocktakeLineServiceTrait>,
     pub invoice_line_service: Box<dyn InvoiceLineServiceTrait>,
+    pub invoice_payment_service: Box<dyn InvoicePaymentServiceTrait>,
     pub requisition_service: Box<dyn RequisitionServiceTrait>,
     pub requisition_line_service: Box<dyn RequisitionLineServiceTrait>,
+    pub backorder_service: Box<dyn BackorderServiceTrait>,
+    pub allocation_rule_service: Box<dyn AllocationRuleServiceTrait>,
     pub general_service: Box<dyn GeneralServiceTrait>,
     pub clinician_service: Box<dyn ClinicianServiceTrait>,
+    pub clinical_check_service: Box<dyn ClinicalCheckServiceTrait>,
     pub rnr_form_service: Box<dyn RnRFormServiceTrait>,
     // Dashboard:
     pub invoice_count_service: Box<dyn InvoiceCountServiceTrait>,
@@ -96,6 +115,7 @@ pub struct ServiceProvider {
     pub item_service: Box<dyn ItemServiceTrait>,
     pub item_count_service: Box<dyn ItemCountServiceTrait>,
     pub requisition_count_service: Box<dyn RequisitionCountServiceTrait>,
+    pub kpi_service: Box<dyn KpiServiceTrait>,
     // Stock stats
     pub item_stats_service: Box<dyn ItemStatsServiceTrait>,
     // Stock
@@ -130,6 +150,7 @@ pub struct ServiceProvider {
     pub barcode_service: Box<dyn BarcodeServiceTrait>,
     // Log
     pub log_service: Box<dyn LogServiceTrait>,
+    pub support_bundle_service: Box<dyn SupportBundleServiceTrait>,
     // Plugin
     pub plugin_data_service: Box<dyn PluginDataServiceTrait>,
     // Currency
@@ -149,6 +170,9 @@ pub struct ServiceProvider {
     // Programs
     pub program_service: Box<dyn ProgramServiceTrait>,
     pub pricing_service: Box<dyn PricingServiceTrait>,
+    pub pick_list_service: Box<dyn PickListServiceTrait>,
+    pub serial_number_service: Box<dyn SerialNumberServiceTrait>,
+    pub recall_service: Box<dyn RecallServiceTrait>,
     // Translations
     pub translations_service: Box<Localisations>,
     // Standard Reports
@@ -186,22 +210,30 @@ impl ServiceProvider {
         ServiceProvider {
             connection_manager: connection_manager.clone(),
             validation_service: Box::new(AuthService::new()),
+            api_key_service: Box::new(ApiKeyService {}),
+            login_security_service: Box::new(LoginSecurityService {}),
+            user_pin_service: Box::new(UserPinService {}),
             location_service: Box::new(LocationService {}),
             sensor_service: Box::new(SensorService {}),
             cold_chain_service: Box::new(ColdChainService {}),
             master_list_service: Box::new(MasterListService {}),
             invoice_line_service: Box::new(InvoiceLineService {}),
+            invoice_payment_service: Box::new(InvoicePaymentService {}),
             invoice_count_service: Box::new(InvoiceCountService {}),
             requisition_count_service: Box::new(RequisitionCountService {}),
+            kpi_service: Box::new(KpiService {}),
             invoice_service: Box::new(InvoiceService {}),
             stock_expiry_count_service: Box::new(StockExpiryServiceCount {}),
             stocktake_service: Box::new(StocktakeService {}),
             stocktake_line_service: Box::new(StocktakeLineService {}),
             requisition_service: Box::new(RequisitionService {}),
             requisition_line_service: Box::new(RequisitionLineService {}),
+            backorder_service: Box::new(BackorderService {}),
+            allocation_rule_service: Box::new(AllocationRuleService {}),
             item_service: Box::new(crate::item::ItemService {}),
             item_stats_service: Box::new(ItemStatsService {}),
             clinician_service: Box::new(ClinicianService {}),
+            clinical_check_service: Box::new(ClinicalCheckService {}),
             general_service: Box::new(GeneralService {}),
             report_service: Box::new(ReportService {}),
             settings: Box::new(SettingsService),
@@ -225,6 +257,7 @@ impl ServiceProvider {
             barcode_service: Box::new(BarcodeService {}),
             repack_service: Box::new(RepackService {}),
             log_service: Box::new(LogService {}),
+            support_bundle_service: Box::new(SupportBundleService {}),
             plugin_data_service: Box::new(PluginDataService {}),
             temperature_excursion_service: Box::new(TemperatureExcursionService {}),
             currency_service: Box::new(CurrencyService {}),
@@ -238,6 +271,9 @@ impl ServiceProvider {
             vaccine_course_service: Box::new(crate::vaccine_course::VaccineCourseService {}),
             program_service: Box::new(crate::program::ProgramService {}),
             pricing_service: Box::new(PricingService {}),
+            pick_list_service: Box::new(PickListService {}),
+            serial_number_service: Box::new(SerialNumberService {}),
+            recall_service: Box::new(RecallService {}),
             rnr_form_service: Box::new(RnRFormService {}),
             vaccination_service: Box::new(VaccinationService {}),
             translations_service: Box::new(Localisations::new()),
@@ -2,7 +2,7 @@ use std::fmt::{Display, Formatter, Result};
 
 use repository::database_settings::DatabaseSettings;
 
-use crate::sync::settings::SyncSettings;
+use crate::{oidc::OidcSettings, sync::settings::SyncSettings};
 
 #[derive(serde::Deserialize, Clone)]
 pub struct Settings {
@@ -11,6 +11,9 @@ pub struct Settings {
     pub sync: Option<SyncSettings>,
     pub logging: Option<LoggingSettings>,
     pub backup: Option<BackupSettings>,
+    /// Single sign-on with an OpenID Connect identity provider
+    #[serde(default)]
+    pub oidc: Option<OidcSettings>,
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -28,6 +31,9 @@ pub struct ServerSettings {
     pub base_dir: Option<String>,
     /// Option to set the machine id of the device for an OS that isn't supported by machine_uid
     pub machine_uid: Option<String>,
+    /// Expose server health metrics at /metrics, in Prometheus text exposition format
+    #[serde(default)]
+    pub metrics_enabled: bool,
 }
 
 impl ServerSettings {
@@ -57,13 +63,21 @@ pub fn is_develop() -> bool {
     cfg!(debug_assertions)
 }
 
-#[derive(serde::Deserialize, Clone)]
+#[derive(serde::Deserialize, Clone, Debug)]
 pub enum LogMode {
     All,
     Console,
     File,
 }
 
+#[derive(serde::Deserialize, Clone, Default, PartialEq, Debug)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    /// One JSON object per line, with correlation id, store and user of the request or sync run
+    Json,
+}
+
 #[derive(serde::Deserialize, Clone, Debug)]
 pub enum Level {
     Error,
@@ -73,6 +87,18 @@ pub enum Level {
     Trace,
 }
 
+impl Level {
+    pub fn to_level_filter(&self) -> log::LevelFilter {
+        match self {
+            Level::Error => log::LevelFilter::Error,
+            Level::Warn => log::LevelFilter::Warn,
+            Level::Info => log::LevelFilter::Info,
+            Level::Debug => log::LevelFilter::Debug,
+            Level::Trace => log::LevelFilter::Trace,
+        }
+    }
+}
+
 impl Display for Level {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         let level = match self {
@@ -98,6 +124,9 @@ pub struct LoggingSettings {
     pub max_file_count: Option<i64>,
     /// Max logfile size in MB
     pub max_file_size: Option<usize>,
+    /// Text (default) | Json
+    #[serde(default)]
+    pub format: LogFormat,
 }
 
 impl LoggingSettings {
@@ -109,6 +138,7 @@ impl LoggingSettings {
             filename: None,
             max_file_count: None,
             max_file_size: None,
+            format: LogFormat::Text,
         }
     }
 
@@ -0,0 +1,812 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::{Local, NaiveDate, Utc};
+use repository::{
+    ActivityLogType, ConsumptionFilter, ConsumptionRepository, CycleCountAbcWeighting,
+    CycleCountScheduleRow, CycleCountScheduleRowRepository, DateFilter, EqualFilter, NumberRowType,
+    Pagination, RepositoryError, StockLineFilter, StockLineRepository, Stocktake, StocktakeFilter,
+    StocktakeLineFilter, StocktakeLineRepository, StocktakeLineRowRepository, StocktakeRepository,
+    StocktakeRow, StocktakeRowRepository, StocktakeStatus, StorageConnection,
+};
+use util::{constants::SYSTEM_USER_ID, uuid::uuid};
+
+use crate::{
+    activity_log::activity_log_entry,
+    number::next_number,
+    service_provider::{ServiceContext, ServiceProvider},
+    store_preference::get_store_preferences,
+    sync::is_initialised,
+    validate::check_store_exists,
+};
+
+use super::{generate_lines_for_items, query::get_stocktake};
+
+pub const CYCLE_COUNT_DESCRIPTION: &str = "Cycle count";
+pub const DEFAULT_CYCLE_LENGTH_DAYS: i32 = 7;
+/// Used when the store preference has no stocktake frequency, matches the preference default
+const DEFAULT_STOCKTAKE_FREQUENCY_MONTHS: f64 = 1.0;
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AbcClass {
+    A,
+    B,
+    C,
+}
+
+impl AbcClass {
+    /// How many times an item of this class is counted in each stocktake frequency period
+    pub fn counts_per_period(&self) -> i64 {
+        match self {
+            AbcClass::A => 4,
+            AbcClass::B => 2,
+            AbcClass::C => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleCountItem {
+    pub item_id: String,
+    pub item_name: String,
+    /// Only set when the schedule is ABC weighted
+    pub abc_class: Option<AbcClass>,
+    pub count_interval_days: i64,
+    pub last_counted_date: Option<NaiveDate>,
+    /// None if the item has never been counted
+    pub next_due_date: Option<NaiveDate>,
+}
+
+impl CycleCountItem {
+    pub fn is_overdue(&self, today: &NaiveDate) -> bool {
+        match self.next_due_date {
+            Some(next_due_date) => next_due_date < *today,
+            None => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleCountPlan {
+    /// Length of the store stocktake frequency in days, every item is counted at least once per period
+    pub period_days: i64,
+    pub cycle_length_days: i64,
+    pub items_per_cycle: usize,
+    /// Items with stock in the store, most overdue first
+    pub items: Vec<CycleCountItem>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleCountCompliance {
+    pub total_items: usize,
+    /// Items counted within the last stocktake frequency period
+    pub counted_items: usize,
+    pub overdue_items: usize,
+}
+
+impl CycleCountCompliance {
+    /// Fraction of items counted within the stocktake frequency period
+    pub fn coverage(&self) -> f64 {
+        if self.total_items == 0 {
+            return 1.0;
+        }
+        self.counted_items as f64 / self.total_items as f64
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UpsertCycleCountSchedule {
+    pub is_active: bool,
+    pub cycle_length_days: i32,
+    pub abc_weighting: Option<CycleCountAbcWeighting>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertCycleCountScheduleError {
+    InvalidStore,
+    CycleLengthMustBePositive,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GenerateCycleCountError {
+    ScheduleDoesNotExist,
+    ScheduleNotActive,
+    InternalError(String),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for UpsertCycleCountScheduleError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertCycleCountScheduleError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for GenerateCycleCountError {
+    fn from(error: RepositoryError) -> Self {
+        GenerateCycleCountError::DatabaseError(error)
+    }
+}
+
+pub fn get_cycle_count_schedule(
+    ctx: &ServiceContext,
+    store_id: &str,
+) -> Result<Option<CycleCountScheduleRow>, RepositoryError> {
+    CycleCountScheduleRowRepository::new(&ctx.connection).find_one_by_store_id(store_id)
+}
+
+pub fn upsert_cycle_count_schedule(
+    ctx: &ServiceContext,
+    input: UpsertCycleCountSchedule,
+) -> Result<CycleCountScheduleRow, UpsertCycleCountScheduleError> {
+    let schedule = ctx
+        .connection
+        .transaction_sync(|connection| {
+            if !check_store_exists(connection, &ctx.store_id)? {
+                return Err(UpsertCycleCountScheduleError::InvalidStore);
+            }
+            if input.cycle_length_days < 1 {
+                return Err(UpsertCycleCountScheduleError::CycleLengthMustBePositive);
+            }
+
+            let repo = CycleCountScheduleRowRepository::new(connection);
+            let existing = repo.find_one_by_store_id(&ctx.store_id)?;
+            let schedule = CycleCountScheduleRow {
+                id: existing.as_ref().map(|r| r.id.clone()).unwrap_or_else(uuid),
+                store_id: ctx.store_id.clone(),
+                is_active: input.is_active,
+                cycle_length_days: input.cycle_length_days,
+                abc_weighting: input.abc_weighting,
+                last_generated_date: existing.and_then(|r| r.last_generated_date),
+            };
+            repo.upsert_one(&schedule)?;
+
+            Ok(schedule)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(schedule)
+}
+
+/// Number of days in the store stocktake frequency period (stocktake frequency is in months)
+fn get_period_days(connection: &StorageConnection, store_id: &str) -> Result<i64, RepositoryError> {
+    let frequency_months = match get_store_preferences(connection, store_id)?.stocktake_frequency {
+        months if months > 0.0 => months,
+        _ => DEFAULT_STOCKTAKE_FREQUENCY_MONTHS,
+    };
+    Ok(((frequency_months * 365.25 / 12.0).round() as i64).max(1))
+}
+
+/// Date each item was last counted in a finalised stocktake
+fn get_last_counted_dates(
+    connection: &StorageConnection,
+    store_id: &str,
+) -> Result<HashMap<String, NaiveDate>, RepositoryError> {
+    let stocktakes = StocktakeRepository::new(connection).query(
+        Pagination::all(),
+        Some(
+            StocktakeFilter::new()
+                .store_id(EqualFilter::equal_to(store_id))
+                .status(StocktakeStatus::Finalised.equal_to()),
+        ),
+        None,
+    )?;
+    if stocktakes.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let stocktake_dates: HashMap<String, NaiveDate> = stocktakes
+        .into_iter()
+        .map(|stocktake| {
+            let date = stocktake.stocktake_date.unwrap_or(
+                stocktake
+                    .finalised_datetime
+                    .unwrap_or(stocktake.created_datetime)
+                    .date(),
+            );
+            (stocktake.id, date)
+        })
+        .collect();
+
+    let lines = StocktakeLineRepository::new(connection).query_by_filter(
+        StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_any(
+            stocktake_dates.keys().cloned().collect(),
+        )),
+        None,
+    )?;
+
+    let mut last_counted = HashMap::new();
+    for line in lines {
+        if line.line.counted_number_of_packs.is_none() {
+            continue;
+        }
+        let Some(date) = stocktake_dates.get(&line.line.stocktake_id) else {
+            continue;
+        };
+        let entry = last_counted.entry(line.item.id).or_insert(*date);
+        if *date > *entry {
+            *entry = *date;
+        }
+    }
+
+    Ok(last_counted)
+}
+
+/// Items on stocktakes that haven't been finalised yet, these are skipped when planning a cycle
+fn get_items_in_open_stocktakes(
+    connection: &StorageConnection,
+    store_id: &str,
+) -> Result<HashSet<String>, RepositoryError> {
+    let stocktake_ids: Vec<String> = StocktakeRepository::new(connection)
+        .query(
+            Pagination::all(),
+            Some(
+                StocktakeFilter::new()
+                    .store_id(EqualFilter::equal_to(store_id))
+                    .status(StocktakeStatus::Finalised.not_equal_to()),
+            ),
+            None,
+        )?
+        .into_iter()
+        .map(|stocktake| stocktake.id)
+        .collect();
+    if stocktake_ids.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    Ok(StocktakeLineRepository::new(connection)
+        .query_by_filter(
+            StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_any(stocktake_ids)),
+            None,
+        )?
+        .into_iter()
+        .map(|line| line.item.id)
+        .collect())
+}
+
+/// Splits items into A (first 80% of the total), B (next 15%) and C (remaining) classes
+fn classify_abc(metrics: &HashMap<String, f64>) -> HashMap<String, AbcClass> {
+    let total: f64 = metrics.values().sum();
+    let mut sorted: Vec<(&String, &f64)> = metrics.iter().collect();
+    sorted.sort_by(|(a_id, a), (b_id, b)| b.total_cmp(a).then(a_id.cmp(b_id)));
+
+    let mut cumulative = 0.0;
+    sorted
+        .into_iter()
+        .map(|(item_id, metric)| {
+            let class = if total <= 0.0 {
+                AbcClass::C
+            } else if cumulative / total < 0.8 {
+                AbcClass::A
+            } else if cumulative / total < 0.95 {
+                AbcClass::B
+            } else {
+                AbcClass::C
+            };
+            cumulative += metric;
+            (item_id.clone(), class)
+        })
+        .collect()
+}
+
+pub fn get_cycle_count_plan(
+    ctx: &ServiceContext,
+    store_id: &str,
+    today: NaiveDate,
+) -> Result<CycleCountPlan, RepositoryError> {
+    let connection = &ctx.connection;
+    let schedule =
+        CycleCountScheduleRowRepository::new(connection).find_one_by_store_id(store_id)?;
+    let cycle_length_days = schedule
+        .as_ref()
+        .map(|s| s.cycle_length_days)
+        .unwrap_or(DEFAULT_CYCLE_LENGTH_DAYS) as i64;
+    let abc_weighting = schedule.and_then(|s| s.abc_weighting);
+    let period_days = get_period_days(connection, store_id)?;
+
+    let stock_lines = StockLineRepository::new(connection).query_by_filter(
+        StockLineFilter::new()
+            .store_id(EqualFilter::equal_to(store_id))
+            .has_packs_in_store(true),
+        Some(store_id.to_string()),
+    )?;
+
+    let mut item_names = HashMap::new();
+    let mut item_values = HashMap::new();
+    for line in stock_lines {
+        let row = &line.stock_line_row;
+        *item_values.entry(line.item_row.id.clone()).or_insert(0.0) +=
+            row.total_number_of_packs * row.cost_price_per_pack;
+        item_names.insert(line.item_row.id, line.item_row.name);
+    }
+
+    let abc_classes = match abc_weighting {
+        Some(CycleCountAbcWeighting::Value) => Some(classify_abc(&item_values)),
+        Some(CycleCountAbcWeighting::Movement) => {
+            let consumption = ConsumptionRepository::new(connection).query(Some(
+                ConsumptionFilter::new()
+                    .store_id(EqualFilter::equal_to(store_id))
+                    .item_id(EqualFilter::equal_any(item_names.keys().cloned().collect()))
+                    .date(DateFilter::date_range(
+                        &(today - chrono::Duration::days(period_days)),
+                        &today,
+                    )),
+            ))?;
+            let mut movement: HashMap<String, f64> =
+                item_names.keys().map(|id| (id.clone(), 0.0)).collect();
+            for row in consumption {
+                *movement.entry(row.item_id).or_insert(0.0) += row.quantity;
+            }
+            Some(classify_abc(&movement))
+        }
+        None => None,
+    };
+
+    let last_counted_dates = get_last_counted_dates(connection, store_id)?;
+
+    let mut counts_per_period = 0;
+    let mut items: Vec<CycleCountItem> = item_names
+        .into_iter()
+        .map(|(item_id, item_name)| {
+            let abc_class = abc_classes
+                .as_ref()
+                .map(|classes| *classes.get(&item_id).unwrap_or(&AbcClass::C));
+            let counts = abc_class.map(|c| c.counts_per_period()).unwrap_or(1);
+            counts_per_period += counts;
+
+            let count_interval_days = (period_days / counts).max(1);
+            let last_counted_date = last_counted_dates.get(&item_id).copied();
+            CycleCountItem {
+                next_due_date: last_counted_date
+                    .map(|date| date + chrono::Duration::days(count_interval_days)),
+                item_id,
+                item_name,
+                abc_class,
+                count_interval_days,
+                last_counted_date,
+            }
+        })
+        .collect();
+
+    // Never counted items first, then most overdue
+    items.sort_by(|a, b| {
+        a.next_due_date
+            .cmp(&b.next_due_date)
+            .then(a.item_name.cmp(&b.item_name))
+    });
+
+    // Spread the counts over the cycles in the period
+    let cycles_per_period = (period_days / cycle_length_days).max(1);
+    let items_per_cycle =
+        ((counts_per_period + cycles_per_period - 1) / cycles_per_period) as usize;
+
+    Ok(CycleCountPlan {
+        period_days,
+        cycle_length_days,
+        items_per_cycle,
+        items,
+    })
+}
+
+pub fn get_cycle_count_compliance(
+    ctx: &ServiceContext,
+    store_id: &str,
+    today: NaiveDate,
+) -> Result<CycleCountCompliance, RepositoryError> {
+    let plan = get_cycle_count_plan(ctx, store_id, today)?;
+    let period_start = today - chrono::Duration::days(plan.period_days);
+
+    Ok(CycleCountCompliance {
+        total_items: plan.items.len(),
+        counted_items: plan
+            .items
+            .iter()
+            .filter(|item| matches!(item.last_counted_date, Some(date) if date >= period_start))
+            .count(),
+        overdue_items: plan
+            .items
+            .iter()
+            .filter(|item| item.is_overdue(&today))
+            .count(),
+    })
+}
+
+/// Creates a draft stocktake with the items due in the current cycle, if a new cycle has started.
+/// Returns None when the current cycle has already been generated or no items are due.
+pub fn generate_cycle_count_stocktake(
+    ctx: &ServiceContext,
+    today: NaiveDate,
+) -> Result<Option<Stocktake>, GenerateCycleCountError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let schedule_repo = CycleCountScheduleRowRepository::new(connection);
+            let schedule = schedule_repo
+                .find_one_by_store_id(&ctx.store_id)?
+                .ok_or(GenerateCycleCountError::ScheduleDoesNotExist)?;
+            if !schedule.is_active {
+                return Err(GenerateCycleCountError::ScheduleNotActive);
+            }
+            if let Some(last_generated_date) = schedule.last_generated_date {
+                let next_cycle_date =
+                    last_generated_date + chrono::Duration::days(schedule.cycle_length_days as i64);
+                if today < next_cycle_date {
+                    return Ok(None);
+                }
+            }
+
+            let plan = get_cycle_count_plan(ctx, &ctx.store_id, today)?;
+            let items_being_counted = get_items_in_open_stocktakes(connection, &ctx.store_id)?;
+            let cycle_end = today + chrono::Duration::days(plan.cycle_length_days);
+            let item_ids: Vec<String> = plan
+                .items
+                .into_iter()
+                .filter(|item| !items_being_counted.contains(&item.item_id))
+                .filter(|item| match item.next_due_date {
+                    Some(next_due_date) => next_due_date < cycle_end,
+                    None => true,
+                })
+                .take(plan.items_per_cycle)
+                .map(|item| item.item_id)
+                .collect();
+
+            schedule_repo.upsert_one(&CycleCountScheduleRow {
+                last_generated_date: Some(today),
+                ..schedule
+            })?;
+
+            if item_ids.is_empty() {
+                return Ok(None);
+            }
+
+            let stocktake = StocktakeRow {
+                id: uuid(),
+                store_id: ctx.store_id.clone(),
+                user_id: ctx.user_id.clone(),
+                stocktake_number: next_number(
+                    connection,
+                    &NumberRowType::Stocktake,
+                    &ctx.store_id,
+                )?,
+                comment: None,
+                description: Some(CYCLE_COUNT_DESCRIPTION.to_string()),
+                status: StocktakeStatus::New,
+                created_datetime: Utc::now().naive_utc(),
+                stocktake_date: Some(today),
+                is_locked: false,
+                is_blind: false,
+                variance_quantity_threshold: None,
+                variance_value_threshold: None,
+                finalised_datetime: None,
+                inventory_addition_id: None,
+                inventory_reduction_id: None,
+            };
+            StocktakeRowRepository::new(connection).upsert_one(&stocktake)?;
+
+            let line_repo = StocktakeLineRowRepository::new(connection);
+            for line in
+                generate_lines_for_items(connection, &ctx.store_id, &stocktake.id, &item_ids)?
+            {
+                line_repo.upsert_one(&line)?;
+            }
+
+            activity_log_entry(
+                ctx,
+                ActivityLogType::StocktakeCreated,
+                Some(stocktake.id.clone()),
+                None,
+                None,
+            )?;
+
+            get_stocktake(ctx, stocktake.id)?
+                .ok_or(GenerateCycleCountError::InternalError(
+                    "Failed to read the just inserted stocktake!".to_string(),
+                ))
+                .map(Some)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+/// Generates cycle count stocktakes for all active schedules on this site
+pub fn generate_scheduled_cycle_counts(
+    service_provider: &ServiceProvider,
+    today: NaiveDate,
+) -> Result<Vec<Stocktake>, RepositoryError> {
+    let schedules =
+        CycleCountScheduleRowRepository::new(&service_provider.connection()?).find_all_active()?;
+
+    let mut stocktakes = Vec::new();
+    for schedule in schedules {
+        // Scheduled stocktakes are created by the system user
+        let ctx =
+            service_provider.context(schedule.store_id.clone(), SYSTEM_USER_ID.to_string())?;
+        match generate_cycle_count_stocktake(&ctx, today) {
+            Ok(Some(stocktake)) => stocktakes.push(stocktake),
+            Ok(None) => {}
+            Err(error) => log::error!(
+                "Failed to generate cycle count for store {}: {:?}",
+                schedule.store_id,
+                error
+            ),
+        }
+    }
+
+    Ok(stocktakes)
+}
+
+/// Periodically checks the cycle count schedules and generates any stocktakes that are due, once
+/// the site is initialised
+pub async fn run_cycle_count_scheduler(service_provider: Arc<ServiceProvider>) {
+    let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+    loop {
+        interval.tick().await;
+        if !is_initialised(&service_provider) {
+            continue;
+        }
+        let today = Local::now().date_naive();
+        if let Err(error) = generate_scheduled_cycle_counts(&service_provider, today) {
+            log::error!("Error generating scheduled cycle counts: {:?}", error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{
+            mock_item_a, mock_item_b, mock_item_c, mock_store_a, mock_user_account_a, MockData,
+            MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        CycleCountAbcWeighting, EqualFilter, StockLineRow, StocktakeLineFilter,
+        StocktakeLineRepository, StocktakeLineRow, StocktakeRow, StocktakeStatus,
+    };
+    use util::{constants::SYSTEM_USER_ID, inline_init};
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::{
+        classify_abc, generate_scheduled_cycle_counts, AbcClass, GenerateCycleCountError,
+        UpsertCycleCountSchedule, UpsertCycleCountScheduleError, CYCLE_COUNT_DESCRIPTION,
+    };
+
+    fn stock_line(id: &str, item_id: &str, packs: f64, cost_price: f64) -> StockLineRow {
+        inline_init(|s: &mut StockLineRow| {
+            s.id = id.to_string();
+            s.store_id = mock_store_a().id;
+            s.item_link_id = item_id.to_string();
+            s.pack_size = 1.0;
+            s.total_number_of_packs = packs;
+            s.cost_price_per_pack = cost_price;
+        })
+    }
+
+    #[test]
+    fn cycle_count_abc_classes() {
+        let metrics = HashMap::from([
+            ("a".to_string(), 100.0),
+            ("b".to_string(), 15.0),
+            ("c".to_string(), 5.0),
+        ]);
+        let classes = classify_abc(&metrics);
+        assert_eq!(classes["a"], AbcClass::A);
+        assert_eq!(classes["b"], AbcClass::B);
+        assert_eq!(classes["c"], AbcClass::C);
+
+        // No movement, everything is counted at the base frequency
+        let metrics = HashMap::from([("a".to_string(), 0.0), ("b".to_string(), 0.0)]);
+        let classes = classify_abc(&metrics);
+        assert_eq!(classes["a"], AbcClass::C);
+        assert_eq!(classes["b"], AbcClass::C);
+    }
+
+    #[actix_rt::test]
+    async fn cycle_count_schedule() {
+        let counted_date = NaiveDate::from_ymd_opt(2024, 6, 20).unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "cycle_count_schedule",
+            MockDataInserts::none()
+                .names()
+                .stores()
+                .name_store_joins()
+                .user_accounts()
+                .contexts()
+                .user_permissions()
+                .user_store_joins()
+                .items()
+                .units(),
+            inline_init(|m: &mut MockData| {
+                m.stock_lines = vec![
+                    stock_line("cycle_count_a", &mock_item_a().id, 100.0, 10.0),
+                    stock_line("cycle_count_b", &mock_item_b().id, 10.0, 1.0),
+                    stock_line("cycle_count_c", &mock_item_c().id, 1.0, 1.0),
+                ];
+                m.stocktakes = vec![inline_init(|r: &mut StocktakeRow| {
+                    r.id = "counted_stocktake".to_string();
+                    r.store_id = mock_store_a().id;
+                    r.status = StocktakeStatus::Finalised;
+                    r.stocktake_date = Some(counted_date);
+                })];
+                m.stocktake_lines = vec![inline_init(|r: &mut StocktakeLineRow| {
+                    r.id = "counted_stocktake_line".to_string();
+                    r.stocktake_id = "counted_stocktake".to_string();
+                    r.item_link_id = mock_item_b().id;
+                    r.counted_number_of_packs = Some(10.0);
+                })];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let mut context = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = &service_provider.stocktake_service;
+
+        // Default one month stocktake frequency, split into weekly cycles
+        let plan = service
+            .get_cycle_count_plan(&context, &mock_store_a().id, today)
+            .unwrap();
+        assert_eq!(plan.period_days, 30);
+        assert_eq!(plan.cycle_length_days, 7);
+        assert_eq!(plan.items_per_cycle, 1);
+        let item_ids: Vec<String> = plan.items.iter().map(|i| i.item_id.clone()).collect();
+        assert_eq!(
+            item_ids,
+            vec![mock_item_a().id, mock_item_c().id, mock_item_b().id]
+        );
+        assert_eq!(
+            plan.items[2].next_due_date,
+            NaiveDate::from_ymd_opt(2024, 7, 20)
+        );
+
+        let compliance = service
+            .get_cycle_count_compliance(&context, &mock_store_a().id, today)
+            .unwrap();
+        assert_eq!(compliance.total_items, 3);
+        assert_eq!(compliance.counted_items, 1);
+        assert_eq!(compliance.overdue_items, 2);
+
+        // Schedule errors
+        assert_eq!(
+            service.generate_cycle_count_stocktake(&context, today),
+            Err(GenerateCycleCountError::ScheduleDoesNotExist)
+        );
+        assert_eq!(
+            service.upsert_cycle_count_schedule(
+                &context,
+                UpsertCycleCountSchedule {
+                    is_active: true,
+                    cycle_length_days: 0,
+                    abc_weighting: None,
+                }
+            ),
+            Err(UpsertCycleCountScheduleError::CycleLengthMustBePositive)
+        );
+        context.store_id = "invalid".to_string();
+        assert_eq!(
+            service.upsert_cycle_count_schedule(
+                &context,
+                UpsertCycleCountSchedule {
+                    is_active: true,
+                    cycle_length_days: 7,
+                    abc_weighting: None,
+                }
+            ),
+            Err(UpsertCycleCountScheduleError::InvalidStore)
+        );
+        context.store_id = mock_store_a().id;
+
+        service
+            .upsert_cycle_count_schedule(
+                &context,
+                UpsertCycleCountSchedule {
+                    is_active: false,
+                    cycle_length_days: 7,
+                    abc_weighting: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            service.generate_cycle_count_stocktake(&context, today),
+            Err(GenerateCycleCountError::ScheduleNotActive)
+        );
+
+        let schedule = service
+            .upsert_cycle_count_schedule(
+                &context,
+                UpsertCycleCountSchedule {
+                    is_active: true,
+                    cycle_length_days: 7,
+                    abc_weighting: None,
+                },
+            )
+            .unwrap();
+
+        // First cycle counts the first never counted item
+        let stocktake = service
+            .generate_cycle_count_stocktake(&context, today)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            stocktake.description,
+            Some(CYCLE_COUNT_DESCRIPTION.to_string())
+        );
+        assert_eq!(stocktake.status, StocktakeStatus::New);
+        let lines = StocktakeLineRepository::new(&connection)
+            .query_by_filter(
+                StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(&stocktake.id)),
+                None,
+            )
+            .unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].item.id, mock_item_a().id);
+        assert_eq!(lines[0].line.snapshot_number_of_packs, 100.0);
+
+        // Cycle already generated
+        assert_eq!(
+            service.generate_cycle_count_stocktake(&context, today + chrono::Duration::days(6)),
+            Ok(None)
+        );
+
+        // Next cycle skips item a, it's still being counted in the open stocktake
+        let next_stocktake = service
+            .generate_cycle_count_stocktake(&context, today + chrono::Duration::days(7))
+            .unwrap()
+            .unwrap();
+        let lines = StocktakeLineRepository::new(&connection)
+            .query_by_filter(
+                StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(&next_stocktake.id)),
+                None,
+            )
+            .unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].item.id, mock_item_c().id);
+
+        // Schedule id is kept when updating
+        let updated_schedule = service
+            .upsert_cycle_count_schedule(
+                &context,
+                UpsertCycleCountSchedule {
+                    is_active: true,
+                    cycle_length_days: 14,
+                    abc_weighting: Some(CycleCountAbcWeighting::Value),
+                },
+            )
+            .unwrap();
+        assert_eq!(updated_schedule.id, schedule.id);
+        assert_eq!(
+            updated_schedule.last_generated_date,
+            Some(today + chrono::Duration::days(7))
+        );
+
+        // Item a holds most of the stock value so is counted four times a period
+        let plan = service
+            .get_cycle_count_plan(&context, &mock_store_a().id, today)
+            .unwrap();
+        let item_a = plan
+            .items
+            .iter()
+            .find(|i| i.item_id == mock_item_a().id)
+            .unwrap();
+        assert_eq!(item_a.abc_class, Some(AbcClass::A));
+        assert_eq!(item_a.count_interval_days, 7);
+
+        // Scheduled cycle counts are created by the system user
+        let scheduled =
+            generate_scheduled_cycle_counts(&service_provider, today + chrono::Duration::days(21))
+                .unwrap();
+        assert_eq!(scheduled.len(), 1);
+        assert_eq!(scheduled[0].user_id, SYSTEM_USER_ID);
+    }
+}
@@ -24,6 +24,9 @@ pub fn generate(
         master_list_id,
         items_have_stock,
         expires_before,
+        is_blind,
+        variance_quantity_threshold,
+        variance_value_threshold,
     }: InsertStocktake,
 ) -> Result<(StocktakeRow, Vec<StocktakeLineRow>), RepositoryError> {
     let stocktake_number = next_number(connection, &NumberRowType::Stocktake, store_id)?;
@@ -77,6 +80,9 @@ pub fn generate(
             user_id: user_id.to_string(),
             store_id: store_id.to_string(),
             is_locked: is_locked.unwrap_or(false),
+            is_blind: is_blind.unwrap_or(false),
+            variance_quantity_threshold,
+            variance_value_threshold,
             // Default
             finalised_datetime: None,
             inventory_addition_id: None,
@@ -102,6 +108,16 @@ pub fn generate_lines_from_master_list(
         .map(|r| r.item_id)
         .collect();
 
+    generate_lines_for_items(connection, store_id, stocktake_id, &item_ids)
+}
+
+/// Lines for each stock line of the items in the store, or an empty line for items without stock
+pub fn generate_lines_for_items(
+    connection: &StorageConnection,
+    store_id: &str,
+    stocktake_id: &str,
+    item_ids: &[String],
+) -> Result<Vec<StocktakeLineRow>, RepositoryError> {
     let mut result = Vec::<StocktakeLineRow>::new();
 
     item_ids.iter().for_each(|item_id| {
@@ -138,6 +154,7 @@ pub fn generate_lines_from_master_list(
                 comment: None,
                 counted_number_of_packs: None,
                 inventory_adjustment_reason_id: None,
+                requires_recount: false,
             });
         } else {
             stock_lines.into_iter().for_each(|line| {
@@ -177,6 +194,7 @@ pub fn generate_lines_from_master_list(
                     comment: None,
                     counted_number_of_packs: None,
                     inventory_adjustment_reason_id: None,
+                    requires_recount: false,
                 });
             });
         }
@@ -238,6 +256,7 @@ pub fn generate_lines_from_location(
                 comment: None,
                 counted_number_of_packs: None,
                 inventory_adjustment_reason_id: None,
+                requires_recount: false,
             }
         })
         .collect();
@@ -295,6 +314,7 @@ pub fn generate_lines_with_stock(
                 comment: None,
                 counted_number_of_packs: None,
                 inventory_adjustment_reason_id: None,
+                requires_recount: false,
             }
         })
         .collect();
@@ -352,6 +372,7 @@ pub fn generate_lines_expiring_before(
                 comment: None,
                 counted_number_of_packs: None,
                 inventory_adjustment_reason_id: None,
+                requires_recount: false,
                 item_name: line.item_row.name,
             }
         })
@@ -3,6 +3,7 @@ use validate::validate;
 
 mod generate;
 use generate::generate;
+pub(crate) use generate::generate_lines_for_items;
 
 use chrono::NaiveDate;
 use repository::{
@@ -24,6 +25,9 @@ pub struct InsertStocktake {
     pub location: Option<NullableUpdate<String>>,
     pub items_have_stock: Option<bool>,
     pub expires_before: Option<NaiveDate>,
+    pub is_blind: Option<bool>,
+    pub variance_quantity_threshold: Option<f64>,
+    pub variance_value_threshold: Option<f64>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -149,6 +153,9 @@ mod test {
                     master_list_id: None,
                     items_have_stock: None,
                     expires_before: None,
+                    is_blind: None,
+                    variance_quantity_threshold: None,
+                    variance_value_threshold: None,
                 },
             )
             .unwrap();
@@ -205,6 +212,9 @@ mod test {
                 master_list_id: Some("invalid".to_string()),
                 items_have_stock: None,
                 expires_before: None,
+                is_blind: None,
+                variance_quantity_threshold: None,
+                variance_value_threshold: None,
             },
         );
         assert!(invalid_result.is_err());
@@ -231,6 +241,9 @@ mod test {
                     master_list_id: Some(master_list_id.clone()),
                     items_have_stock: None,
                     expires_before: None,
+                    is_blind: None,
+                    variance_quantity_threshold: None,
+                    variance_value_threshold: None,
                 },
             )
             .unwrap();
@@ -282,6 +295,9 @@ mod test {
                     master_list_id: Some(master_list_id.clone()),
                     items_have_stock: None,
                     expires_before: None,
+                    is_blind: None,
+                    variance_quantity_threshold: None,
+                    variance_value_threshold: None,
                 },
             )
             .unwrap();
@@ -333,6 +349,9 @@ mod test {
                     master_list_id: None,
                     items_have_stock: None,
                     expires_before: None,
+                    is_blind: None,
+                    variance_quantity_threshold: None,
+                    variance_value_threshold: None,
                 },
             )
             .unwrap();
@@ -374,6 +393,9 @@ mod test {
                     master_list_id: None,
                     items_have_stock: None,
                     expires_before: None,
+                    is_blind: None,
+                    variance_quantity_threshold: None,
+                    variance_value_threshold: None,
                 },
             )
             .unwrap();
@@ -463,6 +485,9 @@ mod test {
                     master_list_id: None,
                     items_have_stock: None,
                     expires_before: None,
+                    is_blind: None,
+                    variance_quantity_threshold: None,
+                    variance_value_threshold: None,
                 },
             )
             .unwrap();
@@ -489,6 +514,9 @@ mod test {
                     master_list_id: None,
                     items_have_stock: Some(true),
                     expires_before: None,
+                    is_blind: None,
+                    variance_quantity_threshold: None,
+                    variance_value_threshold: None,
                 },
             )
             .unwrap();
@@ -527,6 +555,9 @@ mod test {
                     master_list_id: None,
                     items_have_stock: None,
                     expires_before: Some(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap()),
+                    is_blind: None,
+                    variance_quantity_threshold: None,
+                    variance_value_threshold: None,
                 },
             )
             .unwrap();
@@ -556,6 +587,9 @@ mod test {
                     master_list_id: None,
                     items_have_stock: None,
                     expires_before: Some(NaiveDate::from_ymd_opt(2020, 4, 22).unwrap()),
+                    is_blind: None,
+                    variance_quantity_threshold: None,
+                    variance_value_threshold: None,
                 },
             )
             .unwrap();
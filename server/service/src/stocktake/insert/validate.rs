@@ -3,7 +3,9 @@ use repository::{
     StocktakeRepository, StorageConnection,
 };
 
-use crate::{check_location_exists, validate::check_store_exists};
+use crate::{
+    check_location_exists, stocktake::check_variance_thresholds, validate::check_store_exists,
+};
 
 use super::{InsertStocktake, InsertStocktakeError};
 
@@ -31,6 +33,13 @@ pub fn validate(
         return Err(InsertStocktakeError::InvalidLocation);
     }
 
+    if !check_variance_thresholds(
+        &stocktake.variance_quantity_threshold,
+        &stocktake.variance_value_threshold,
+    ) {
+        return Err(InsertStocktakeError::InvalidArguments);
+    }
+
     Ok(())
 }
 
@@ -1,6 +1,9 @@
 use crate::{service_provider::ServiceContext, ListError, ListResult};
+use chrono::NaiveDate;
 use repository::PaginationOption;
-use repository::{RepositoryError, Stocktake, StocktakeFilter, StocktakeSort};
+use repository::{
+    CycleCountScheduleRow, RepositoryError, Stocktake, StocktakeFilter, StocktakeSort,
+};
 
 pub mod query;
 use self::query::{get_stocktake, get_stocktakes};
@@ -17,6 +20,12 @@ pub use self::update::*;
 mod batch;
 pub use self::batch::*;
 
+mod cycle_count;
+pub use self::cycle_count::*;
+
+mod variance;
+pub use self::variance::*;
+
 mod validate;
 pub use self::validate::*;
 
@@ -76,6 +85,74 @@ pub trait StocktakeServiceTrait: Sync + Send {
     ) -> Result<BatchStocktakeResult, RepositoryError> {
         batch_stocktake(ctx, input)
     }
+
+    fn get_cycle_count_schedule(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+    ) -> Result<Option<CycleCountScheduleRow>, RepositoryError> {
+        get_cycle_count_schedule(ctx, store_id)
+    }
+
+    fn upsert_cycle_count_schedule(
+        &self,
+        ctx: &ServiceContext,
+        input: UpsertCycleCountSchedule,
+    ) -> Result<CycleCountScheduleRow, UpsertCycleCountScheduleError> {
+        upsert_cycle_count_schedule(ctx, input)
+    }
+
+    fn get_cycle_count_plan(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        today: NaiveDate,
+    ) -> Result<CycleCountPlan, RepositoryError> {
+        get_cycle_count_plan(ctx, store_id, today)
+    }
+
+    fn get_cycle_count_compliance(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        today: NaiveDate,
+    ) -> Result<CycleCountCompliance, RepositoryError> {
+        get_cycle_count_compliance(ctx, store_id, today)
+    }
+
+    /// Generates the stocktake for the current cycle of the store in the context
+    fn generate_cycle_count_stocktake(
+        &self,
+        ctx: &ServiceContext,
+        today: NaiveDate,
+    ) -> Result<Option<Stocktake>, GenerateCycleCountError> {
+        generate_cycle_count_stocktake(ctx, today)
+    }
+
+    fn get_stocktake_variance_summary(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        stocktake_id: &str,
+    ) -> Result<StocktakeVarianceSummary, StocktakeVarianceSummaryError> {
+        get_stocktake_variance_summary(ctx, store_id, stocktake_id)
+    }
+
+    fn submit_stocktake_count(
+        &self,
+        ctx: &ServiceContext,
+        stocktake_id: &str,
+    ) -> Result<Stocktake, SubmitStocktakeCountError> {
+        submit_stocktake_count(ctx, stocktake_id)
+    }
+
+    fn approve_stocktake(
+        &self,
+        ctx: &ServiceContext,
+        stocktake_id: &str,
+    ) -> Result<Stocktake, ApproveStocktakeError> {
+        approve_stocktake(ctx, stocktake_id)
+    }
 }
 
 pub struct StocktakeService {}
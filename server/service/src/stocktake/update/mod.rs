@@ -50,6 +50,8 @@ pub enum UpdateStocktakeError {
     StocktakeDoesNotExist,
     CannotEditFinalised,
     StocktakeIsLocked,
+    /// Blind stocktakes have to be approved before they can be finalised
+    StocktakeNotApproved,
     InsertStockInLineError {
         line_id: String,
         error: InsertStockInLineError,
@@ -1,6 +1,6 @@
 use repository::{
     EqualFilter, RepositoryError, StockLine, StockLineFilter, StockLineRepository, StocktakeLine,
-    StocktakeLineFilter, StocktakeLineRepository, StocktakeRow, StorageConnection,
+    StocktakeLineFilter, StocktakeLineRepository, StocktakeRow, StocktakeStatus, StorageConnection,
 };
 
 use crate::{
@@ -34,6 +34,10 @@ pub fn validate(
 
     let status_changed = input.status.is_some();
     if status_changed {
+        if existing.is_blind && existing.status != StocktakeStatus::Approved {
+            return Err(UpdateStocktakeError::StocktakeNotApproved);
+        }
+
         if stocktake_lines.is_empty() {
             return Err(UpdateStocktakeError::NoLines);
         }
@@ -12,3 +12,23 @@ pub fn check_stocktake_exist(
 pub fn check_stocktake_not_finalised(status: &StocktakeStatus) -> bool {
     *status != StocktakeStatus::Finalised
 }
+
+pub fn check_variance_thresholds(
+    variance_quantity_threshold: &Option<f64>,
+    variance_value_threshold: &Option<f64>,
+) -> bool {
+    variance_quantity_threshold.unwrap_or_default() >= 0.0
+        && variance_value_threshold.unwrap_or_default() >= 0.0
+}
+
+/// Snapshot quantities (and anything derived from them, like the variance) are hidden from the
+/// counter until the count and recount of a blind stocktake have been submitted. Hiding is only
+/// applied to the stocktake views, stock on hand is still available through stock lines and item
+/// stats to users that can view them
+pub fn check_snapshot_hidden(stocktake: &StocktakeRow) -> bool {
+    stocktake.is_blind
+        && matches!(
+            stocktake.status,
+            StocktakeStatus::New | StocktakeStatus::Recount
+        )
+}
@@ -0,0 +1,473 @@
+use repository::{
+    ActivityLogType, EqualFilter, RepositoryError, Stocktake, StocktakeLineFilter,
+    StocktakeLineRepository, StocktakeLineRow, StocktakeLineRowRepository, StocktakeRow,
+    StocktakeRowRepository, StocktakeStatus, StorageConnection,
+};
+
+use crate::{
+    activity_log::activity_log_entry, service_provider::ServiceContext,
+    validate::check_store_id_matches,
+};
+
+use super::{check_snapshot_hidden, check_stocktake_exist, query::get_stocktake};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StocktakeLineVariance {
+    pub line: StocktakeLineRow,
+    /// Counted minus snapshot number of packs, None if the line hasn't been counted
+    pub quantity_variance: Option<f64>,
+    /// Quantity variance valued at the line cost price
+    pub value_variance: Option<f64>,
+    pub exceeds_threshold: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StocktakeVarianceSummary {
+    pub lines: Vec<StocktakeLineVariance>,
+    pub total_value_variance: f64,
+    pub lines_exceeding_threshold: usize,
+    pub uncounted_lines: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum StocktakeVarianceSummaryError {
+    StocktakeDoesNotExist,
+    InvalidStore,
+    /// Variance of a blind stocktake is only available once the count has been submitted
+    CountNotSubmitted,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SubmitStocktakeCountError {
+    StocktakeDoesNotExist,
+    InvalidStore,
+    NotABlindStocktake,
+    StocktakeIsLocked,
+    CountAlreadySubmitted,
+    NoLines,
+    InternalError(String),
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ApproveStocktakeError {
+    StocktakeDoesNotExist,
+    InvalidStore,
+    NotABlindStocktake,
+    StocktakeIsLocked,
+    StocktakeNotPendingApproval,
+    InternalError(String),
+    DatabaseError(RepositoryError),
+}
+
+pub fn get_stocktake_variance_summary(
+    ctx: &ServiceContext,
+    store_id: &str,
+    stocktake_id: &str,
+) -> Result<StocktakeVarianceSummary, StocktakeVarianceSummaryError> {
+    let stocktake = check_stocktake_exist(&ctx.connection, stocktake_id)?
+        .ok_or(StocktakeVarianceSummaryError::StocktakeDoesNotExist)?;
+    if !check_store_id_matches(store_id, &stocktake.store_id) {
+        return Err(StocktakeVarianceSummaryError::InvalidStore);
+    }
+    if check_snapshot_hidden(&stocktake) {
+        return Err(StocktakeVarianceSummaryError::CountNotSubmitted);
+    }
+
+    let lines = load_stocktake_lines(&ctx.connection, &stocktake)?;
+    Ok(generate_variance_summary(&stocktake, lines))
+}
+
+/// Submits the count (or the recount) of a blind stocktake. Lines with a variance above the
+/// stocktake thresholds are flagged for a recount when the first count is submitted.
+pub fn submit_stocktake_count(
+    ctx: &ServiceContext,
+    stocktake_id: &str,
+) -> Result<Stocktake, SubmitStocktakeCountError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let stocktake = check_stocktake_exist(connection, stocktake_id)?
+                .ok_or(SubmitStocktakeCountError::StocktakeDoesNotExist)?;
+            if !check_store_id_matches(&ctx.store_id, &stocktake.store_id) {
+                return Err(SubmitStocktakeCountError::InvalidStore);
+            }
+            if !stocktake.is_blind {
+                return Err(SubmitStocktakeCountError::NotABlindStocktake);
+            }
+            if stocktake.is_locked {
+                return Err(SubmitStocktakeCountError::StocktakeIsLocked);
+            }
+
+            let lines = load_stocktake_lines(connection, &stocktake)?;
+            if lines.is_empty() {
+                return Err(SubmitStocktakeCountError::NoLines);
+            }
+
+            let status = match stocktake.status {
+                StocktakeStatus::New => {
+                    let summary = generate_variance_summary(&stocktake, lines);
+                    let line_repo = StocktakeLineRowRepository::new(connection);
+                    for variance in summary.lines.iter().filter(|l| l.exceeds_threshold) {
+                        line_repo.upsert_one(&StocktakeLineRow {
+                            requires_recount: true,
+                            ..variance.line.clone()
+                        })?;
+                    }
+
+                    if summary.lines_exceeding_threshold > 0 {
+                        StocktakeStatus::Recount
+                    } else {
+                        StocktakeStatus::PendingApproval
+                    }
+                }
+                StocktakeStatus::Recount => StocktakeStatus::PendingApproval,
+                _ => return Err(SubmitStocktakeCountError::CountAlreadySubmitted),
+            };
+
+            let log_type = match status {
+                StocktakeStatus::Recount => ActivityLogType::StocktakeStatusRecount,
+                _ => ActivityLogType::StocktakeStatusPendingApproval,
+            };
+            StocktakeRowRepository::new(connection).upsert_one(&StocktakeRow {
+                status,
+                ..stocktake
+            })?;
+            activity_log_entry(ctx, log_type, Some(stocktake_id.to_string()), None, None)?;
+
+            get_stocktake(ctx, stocktake_id.to_string())?.ok_or(
+                SubmitStocktakeCountError::InternalError(
+                    "Failed to read the just submitted stocktake!".to_string(),
+                ),
+            )
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+/// Approves a submitted blind stocktake so it can be finalised
+pub fn approve_stocktake(
+    ctx: &ServiceContext,
+    stocktake_id: &str,
+) -> Result<Stocktake, ApproveStocktakeError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let stocktake = check_stocktake_exist(connection, stocktake_id)?
+                .ok_or(ApproveStocktakeError::StocktakeDoesNotExist)?;
+            if !check_store_id_matches(&ctx.store_id, &stocktake.store_id) {
+                return Err(ApproveStocktakeError::InvalidStore);
+            }
+            if !stocktake.is_blind {
+                return Err(ApproveStocktakeError::NotABlindStocktake);
+            }
+            if stocktake.is_locked {
+                return Err(ApproveStocktakeError::StocktakeIsLocked);
+            }
+            if stocktake.status != StocktakeStatus::PendingApproval {
+                return Err(ApproveStocktakeError::StocktakeNotPendingApproval);
+            }
+
+            StocktakeRowRepository::new(connection).upsert_one(&StocktakeRow {
+                status: StocktakeStatus::Approved,
+                ..stocktake
+            })?;
+            activity_log_entry(
+                ctx,
+                ActivityLogType::StocktakeStatusApproved,
+                Some(stocktake_id.to_string()),
+                None,
+                None,
+            )?;
+
+            get_stocktake(ctx, stocktake_id.to_string())?.ok_or(
+                ApproveStocktakeError::InternalError(
+                    "Failed to read the just approved stocktake!".to_string(),
+                ),
+            )
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+fn load_stocktake_lines(
+    connection: &StorageConnection,
+    stocktake: &StocktakeRow,
+) -> Result<Vec<StocktakeLineRow>, RepositoryError> {
+    Ok(StocktakeLineRepository::new(connection)
+        .query_by_filter(
+            StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(&stocktake.id)),
+            Some(stocktake.store_id.clone()),
+        )?
+        .into_iter()
+        .map(|line| line.line)
+        .collect())
+}
+
+fn generate_variance_summary(
+    stocktake: &StocktakeRow,
+    lines: Vec<StocktakeLineRow>,
+) -> StocktakeVarianceSummary {
+    let lines: Vec<StocktakeLineVariance> = lines
+        .into_iter()
+        .map(|line| {
+            let quantity_variance = line
+                .counted_number_of_packs
+                .map(|counted| counted - line.snapshot_number_of_packs);
+            let value_variance = quantity_variance
+                .map(|variance| variance * line.cost_price_per_pack.unwrap_or_default());
+
+            let exceeds =
+                |variance: Option<f64>, threshold: Option<f64>| match (variance, threshold) {
+                    (Some(variance), Some(threshold)) => variance.abs() > threshold,
+                    _ => false,
+                };
+            let exceeds_threshold =
+                exceeds(quantity_variance, stocktake.variance_quantity_threshold)
+                    || exceeds(value_variance, stocktake.variance_value_threshold);
+
+            StocktakeLineVariance {
+                line,
+                quantity_variance,
+                value_variance,
+                exceeds_threshold,
+            }
+        })
+        .collect();
+
+    StocktakeVarianceSummary {
+        total_value_variance: lines.iter().filter_map(|l| l.value_variance).sum(),
+        lines_exceeding_threshold: lines.iter().filter(|l| l.exceeds_threshold).count(),
+        uncounted_lines: lines
+            .iter()
+            .filter(|l| l.quantity_variance.is_none())
+            .count(),
+        lines,
+    }
+}
+
+impl From<RepositoryError> for StocktakeVarianceSummaryError {
+    fn from(error: RepositoryError) -> Self {
+        StocktakeVarianceSummaryError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for SubmitStocktakeCountError {
+    fn from(error: RepositoryError) -> Self {
+        SubmitStocktakeCountError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for ApproveStocktakeError {
+    fn from(error: RepositoryError) -> Self {
+        ApproveStocktakeError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        activity_log::{ActivityLogFilter, ActivityLogRepository},
+        mock::{mock_item_a, mock_item_b, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        ActivityLogType, EqualFilter, StocktakeLineRow, StocktakeLineRowRepository, StocktakeRow,
+        StocktakeStatus,
+    };
+    use util::inline_init;
+
+    use crate::{
+        service_provider::ServiceProvider,
+        stocktake::{
+            ApproveStocktakeError, StocktakeVarianceSummaryError, SubmitStocktakeCountError,
+            UpdateStocktake, UpdateStocktakeError, UpdateStocktakeStatus,
+        },
+        stocktake_line::{UpdateStocktakeLine, UpdateStocktakeLineError},
+    };
+
+    #[actix_rt::test]
+    async fn blind_stocktake_workflow() {
+        fn blind_stocktake() -> StocktakeRow {
+            inline_init(|r: &mut StocktakeRow| {
+                r.id = "blind_stocktake".to_string();
+                r.store_id = mock_store_a().id;
+                r.stocktake_number = 30;
+                r.status = StocktakeStatus::New;
+                r.is_blind = true;
+                r.variance_quantity_threshold = Some(5.0);
+                r.variance_value_threshold = Some(50.0);
+            })
+        }
+
+        fn line_within_threshold() -> StocktakeLineRow {
+            inline_init(|r: &mut StocktakeLineRow| {
+                r.id = "line_within_threshold".to_string();
+                r.stocktake_id = blind_stocktake().id;
+                r.item_link_id = mock_item_a().id;
+                r.item_name = mock_item_a().name;
+                r.snapshot_number_of_packs = 20.0;
+                r.counted_number_of_packs = Some(18.0);
+                r.cost_price_per_pack = Some(1.0);
+            })
+        }
+
+        fn line_above_value_threshold() -> StocktakeLineRow {
+            inline_init(|r: &mut StocktakeLineRow| {
+                r.id = "line_above_value_threshold".to_string();
+                r.stocktake_id = blind_stocktake().id;
+                r.item_link_id = mock_item_b().id;
+                r.item_name = mock_item_b().name;
+                r.snapshot_number_of_packs = 10.0;
+                r.counted_number_of_packs = Some(7.0);
+                r.cost_price_per_pack = Some(20.0);
+            })
+        }
+
+        fn not_blind_stocktake() -> StocktakeRow {
+            inline_init(|r: &mut StocktakeRow| {
+                r.id = "not_blind_stocktake".to_string();
+                r.store_id = mock_store_a().id;
+                r.stocktake_number = 31;
+                r.status = StocktakeStatus::New;
+            })
+        }
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "blind_stocktake_workflow",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.stocktakes = vec![blind_stocktake(), not_blind_stocktake()];
+                r.stocktake_lines = vec![line_within_threshold(), line_above_value_threshold()];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, "".to_string())
+            .unwrap();
+        let service = service_provider.stocktake_service;
+        let line_service = service_provider.stocktake_line_service;
+
+        // variance is hidden until the count is submitted
+        assert_eq!(
+            service.get_stocktake_variance_summary(&context, &mock_store_a().id, "blind_stocktake"),
+            Err(StocktakeVarianceSummaryError::CountNotSubmitted)
+        );
+        assert_eq!(
+            service.submit_stocktake_count(&context, "not_blind_stocktake"),
+            Err(SubmitStocktakeCountError::NotABlindStocktake)
+        );
+        assert_eq!(
+            service.approve_stocktake(&context, "blind_stocktake"),
+            Err(ApproveStocktakeError::StocktakeNotPendingApproval)
+        );
+
+        // submit count, value variance of line b (3 packs * 20) is above the threshold
+        let stocktake = service
+            .submit_stocktake_count(&context, "blind_stocktake")
+            .unwrap();
+        assert_eq!(stocktake.status, StocktakeStatus::Recount);
+        let line_repo = StocktakeLineRowRepository::new(&connection);
+        assert!(
+            !line_repo
+                .find_one_by_id("line_within_threshold")
+                .unwrap()
+                .unwrap()
+                .requires_recount
+        );
+        assert!(
+            line_repo
+                .find_one_by_id("line_above_value_threshold")
+                .unwrap()
+                .unwrap()
+                .requires_recount
+        );
+
+        // only flagged lines can be recounted
+        assert!(matches!(
+            line_service.update_stocktake_line(
+                &context,
+                inline_init(|r: &mut UpdateStocktakeLine| {
+                    r.id = "line_within_threshold".to_string();
+                    r.counted_number_of_packs = Some(20.0);
+                }),
+            ),
+            Err(UpdateStocktakeLineError::CountAlreadySubmitted)
+        ));
+        line_service
+            .update_stocktake_line(
+                &context,
+                inline_init(|r: &mut UpdateStocktakeLine| {
+                    r.id = "line_above_value_threshold".to_string();
+                    r.counted_number_of_packs = Some(9.0);
+                }),
+            )
+            .unwrap();
+
+        // submit recount
+        let stocktake = service
+            .submit_stocktake_count(&context, "blind_stocktake")
+            .unwrap();
+        assert_eq!(stocktake.status, StocktakeStatus::PendingApproval);
+        assert_eq!(
+            service.submit_stocktake_count(&context, "blind_stocktake"),
+            Err(SubmitStocktakeCountError::CountAlreadySubmitted)
+        );
+
+        // lines are locked once the count is submitted
+        assert!(matches!(
+            line_service.update_stocktake_line(
+                &context,
+                inline_init(|r: &mut UpdateStocktakeLine| {
+                    r.id = "line_within_threshold".to_string();
+                    r.comment = Some("changed after submission".to_string());
+                }),
+            ),
+            Err(UpdateStocktakeLineError::CountAlreadySubmitted)
+        ));
+
+        let summary = service
+            .get_stocktake_variance_summary(&context, &mock_store_a().id, "blind_stocktake")
+            .unwrap();
+        assert_eq!(summary.lines_exceeding_threshold, 0);
+        assert_eq!(summary.uncounted_lines, 0);
+        assert_eq!(summary.total_value_variance, -2.0 - 20.0);
+
+        // can't be finalised before approval
+        assert_eq!(
+            service.update_stocktake(
+                &context,
+                inline_init(|r: &mut UpdateStocktake| {
+                    r.id = "blind_stocktake".to_string();
+                    r.status = Some(UpdateStocktakeStatus::Finalised);
+                }),
+            ),
+            Err(UpdateStocktakeError::StocktakeNotApproved)
+        );
+
+        let stocktake = service
+            .approve_stocktake(&context, "blind_stocktake")
+            .unwrap();
+        assert_eq!(stocktake.status, StocktakeStatus::Approved);
+
+        let logs: Vec<ActivityLogType> = ActivityLogRepository::new(&connection)
+            .query_by_filter(
+                ActivityLogFilter::new().record_id(EqualFilter::equal_to("blind_stocktake")),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|log| log.activity_log_row.r#type)
+            .collect();
+        assert_eq!(
+            logs,
+            vec![
+                ActivityLogType::StocktakeStatusRecount,
+                ActivityLogType::StocktakeStatusPendingApproval,
+                ActivityLogType::StocktakeStatusApproved,
+            ]
+        );
+    }
+}
@@ -12,6 +12,8 @@ pub enum DeleteStocktakeLineError {
     InvalidStore,
     StocktakeLineDoesNotExist,
     CannotEditFinalised,
+    /// Lines can't be removed after a blind stocktake count has been submitted
+    CountAlreadySubmitted,
     StocktakeIsLocked,
 }
 
@@ -1,4 +1,4 @@
-use repository::StorageConnection;
+use repository::{StocktakeStatus, StorageConnection};
 
 use crate::{
     stocktake::{check_stocktake_exist, check_stocktake_not_finalised},
@@ -34,6 +34,9 @@ pub fn validate(
     if !check_stocktake_not_finalised(&stocktake.status) {
         return Err(DeleteStocktakeLineError::CannotEditFinalised);
     }
+    if stocktake.is_blind && stocktake.status != StocktakeStatus::New {
+        return Err(DeleteStocktakeLineError::CountAlreadySubmitted);
+    }
     if !check_store_id_matches(store_id, &stocktake.store_id) {
         return Err(DeleteStocktakeLineError::InvalidStore);
     }
@@ -45,5 +45,6 @@ pub fn generate(
         sell_price_per_pack,
         note,
         inventory_adjustment_reason_id,
+        requires_recount: false,
     }
 }
@@ -38,6 +38,8 @@ pub enum InsertStocktakeLineError {
     StockLineAlreadyExistsInStocktake,
     LocationDoesNotExist,
     CannotEditFinalised,
+    /// Lines can't be added after a blind stocktake count has been submitted
+    CountAlreadySubmitted,
     /// Either stock line xor item must be set (not both)
     StockLineXOrItem,
     ItemDoesNotExist,
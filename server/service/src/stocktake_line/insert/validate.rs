@@ -1,12 +1,12 @@
 use repository::{
     EqualFilter, ItemFilter, ItemRepository, RepositoryError, StockLine, StocktakeLineFilter,
-    StocktakeLineRepository, StorageConnection,
+    StocktakeLineRepository, StocktakeStatus, StorageConnection,
 };
 
 use crate::{
     check_location_exists,
     common_stock::{check_stock_line_exists, CommonStockLineError},
-    stocktake::{check_stocktake_exist, check_stocktake_not_finalised},
+    stocktake::{check_snapshot_hidden, check_stocktake_exist, check_stocktake_not_finalised},
     stocktake_line::validate::{
         check_active_adjustment_reasons, check_reason_is_valid, check_stock_line_reduced_below_zero,
     },
@@ -35,6 +35,9 @@ pub fn validate(
     if !check_stocktake_not_finalised(&stocktake.status) {
         return Err(CannotEditFinalised);
     }
+    if stocktake.is_blind && stocktake.status != StocktakeStatus::New {
+        return Err(CountAlreadySubmitted);
+    }
     if !check_store_id_matches(store_id, &stocktake.store_id) {
         return Err(InvalidStore);
     }
@@ -86,7 +89,9 @@ pub fn validate(
 
     let stocktake_reduction_amount =
         stocktake_reduction_amount(&input.counted_number_of_packs, &stock_line);
-    if check_active_adjustment_reasons(connection, stocktake_reduction_amount)?.is_some()
+    // Requiring a reason would reveal the variance to a blind counter
+    if !check_snapshot_hidden(&stocktake)
+        && check_active_adjustment_reasons(connection, stocktake_reduction_amount)?.is_some()
         && input.inventory_adjustment_reason_id.is_none()
         && stocktake_reduction_amount != 0.0
     {
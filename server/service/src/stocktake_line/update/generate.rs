@@ -43,5 +43,6 @@ pub fn generate(
         note: note.or(existing_line.note),
         inventory_adjustment_reason_id: inventory_adjustment_reason_id
             .or(existing_line.inventory_adjustment_reason_id),
+        requires_recount: existing_line.requires_recount,
     })
 }
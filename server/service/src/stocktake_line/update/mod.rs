@@ -35,6 +35,9 @@ pub enum UpdateStocktakeLineError {
     StockLineDoesNotExist,
     LocationDoesNotExist,
     CannotEditFinalised,
+    /// Counted quantity can't be changed after a blind stocktake count has been submitted, only
+    /// lines flagged for recount can be updated during the recount
+    CountAlreadySubmitted,
     StocktakeIsLocked,
     AdjustmentReasonNotProvided,
     AdjustmentReasonNotValid,
@@ -352,6 +355,7 @@ mod stocktake_line_test {
                 pack_size: None,
                 note: None,
                 inventory_adjustment_reason_id: None,
+                requires_recount: false,
             }
         );
 
@@ -1,9 +1,12 @@
-use repository::{RepositoryError, StocktakeLine, StorageConnection};
+use repository::{
+    RepositoryError, StocktakeLine, StocktakeLineRow, StocktakeRow, StocktakeStatus,
+    StorageConnection,
+};
 
 use crate::{
     check_location_exists,
     common_stock::{check_stock_line_exists, CommonStockLineError},
-    stocktake::{check_stocktake_exist, check_stocktake_not_finalised},
+    stocktake::{check_snapshot_hidden, check_stocktake_exist, check_stocktake_not_finalised},
     stocktake_line::validate::{
         check_active_adjustment_reasons, check_reason_is_valid,
         check_snapshot_matches_current_count, check_stock_line_reduced_below_zero,
@@ -38,6 +41,10 @@ pub fn validate(
         return Err(StocktakeIsLocked);
     }
 
+    if !check_blind_count_editable(&stocktake, stocktake_line_row, input) {
+        return Err(CountAlreadySubmitted);
+    }
+
     if !check_store_id_matches(store_id, &stocktake.store_id) {
         return Err(InvalidStore);
     }
@@ -48,7 +55,9 @@ pub fn validate(
 
     let stocktake_reduction_amount =
         stocktake_reduction_amount(&input.counted_number_of_packs, stocktake_line_row);
-    if check_active_adjustment_reasons(connection, stocktake_reduction_amount)?.is_some()
+    // Requiring a reason would reveal the variance to a blind counter
+    if !check_snapshot_hidden(&stocktake)
+        && check_active_adjustment_reasons(connection, stocktake_reduction_amount)?.is_some()
         && input.inventory_adjustment_reason_id.is_none()
         && stocktake_reduction_amount != 0.0
     {
@@ -95,6 +104,28 @@ pub fn validate(
     Ok(stocktake_line)
 }
 
+fn check_blind_count_editable(
+    stocktake: &StocktakeRow,
+    line: &StocktakeLineRow,
+    input: &UpdateStocktakeLine,
+) -> bool {
+    if !stocktake.is_blind {
+        return true;
+    }
+    let count_changed = input
+        .counted_number_of_packs
+        .is_some_and(|counted| Some(counted) != line.counted_number_of_packs);
+
+    match stocktake.status {
+        StocktakeStatus::New => true,
+        StocktakeStatus::Recount => !count_changed || line.requires_recount,
+        // The approver signs off on the submitted lines, nothing can change after submission
+        StocktakeStatus::PendingApproval
+        | StocktakeStatus::Approved
+        | StocktakeStatus::Finalised => false,
+    }
+}
+
 impl From<RepositoryError> for UpdateStocktakeLineError {
     fn from(error: RepositoryError) -> Self {
         UpdateStocktakeLineError::DatabaseError(error)
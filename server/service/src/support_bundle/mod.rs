@@ -0,0 +1,538 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{Cursor, Write},
+    path::Path,
+    time::SystemTime,
+};
+
+use chrono::{Datelike, Local, Timelike, Utc};
+use repository::{
+    migrations::Version, table_row_counts, EqualFilter, KeyType, KeyValueStoreRepository,
+    Pagination, RepositoryError, StoreFilter, StoreRepository, SyncBufferFilter,
+    SyncBufferRepository, SyncLogRepository, SyncLogSort, SyncLogSortField,
+};
+use serde_json::{json, Value};
+use zip::{
+    result::ZipError,
+    write::{SimpleFileOptions, ZipWriter},
+    CompressionMethod, DateTime,
+};
+
+use crate::{
+    service_provider::{ServiceContext, ServiceProvider},
+    settings::Settings,
+    static_files::{StaticFileCategory, StaticFileService},
+};
+
+/// Number of most recently modified log files to include
+const MAX_LOG_FILES: usize = 5;
+const MAX_SYNC_LOGS: u32 = 100;
+
+#[derive(Debug, Clone, Default)]
+pub struct SupportBundleInput {
+    /// Include a copy of the database with patient data and secrets removed (sqlite only)
+    pub include_database_extract: bool,
+}
+
+#[derive(Debug)]
+pub enum SupportBundleError {
+    DatabaseExtractNotSupported,
+    DatabaseError(RepositoryError),
+    FileError(String),
+}
+
+impl From<RepositoryError> for SupportBundleError {
+    fn from(error: RepositoryError) -> Self {
+        SupportBundleError::DatabaseError(error)
+    }
+}
+
+impl From<std::io::Error> for SupportBundleError {
+    fn from(error: std::io::Error) -> Self {
+        SupportBundleError::FileError(format!("{}", error))
+    }
+}
+
+impl From<ZipError> for SupportBundleError {
+    fn from(error: ZipError) -> Self {
+        SupportBundleError::FileError(format!("{}", error))
+    }
+}
+
+pub trait SupportBundleServiceTrait: Send + Sync {
+    /// Returns the zip file content
+    fn generate_support_bundle(
+        &self,
+        ctx: &ServiceContext,
+        service_provider: &ServiceProvider,
+        settings: &Settings,
+        input: SupportBundleInput,
+    ) -> Result<Vec<u8>, SupportBundleError> {
+        generate_support_bundle(ctx, service_provider, settings, input)
+    }
+
+    /// Generates the bundle and returns the static file id, the zip can be fetched using the
+    /// /files?id={id} endpoint
+    fn export_support_bundle(
+        &self,
+        ctx: &ServiceContext,
+        service_provider: &ServiceProvider,
+        settings: &Settings,
+        input: SupportBundleInput,
+    ) -> Result<String, SupportBundleError> {
+        let bundle = self.generate_support_bundle(ctx, service_provider, settings, input)?;
+
+        let file = StaticFileService::new(&settings.server.base_dir)
+            .and_then(|file_service| {
+                file_service.store_file(
+                    &support_bundle_file_name(),
+                    StaticFileCategory::Temporary,
+                    &bundle,
+                )
+            })
+            .map_err(|error| SupportBundleError::FileError(format!("{}", error)))?;
+
+        Ok(file.id)
+    }
+}
+
+pub struct SupportBundleService {}
+impl SupportBundleServiceTrait for SupportBundleService {}
+
+pub fn support_bundle_file_name() -> String {
+    format!(
+        "{}_support_bundle.zip",
+        Local::now().format("%Y%m%d_%H%M%S")
+    )
+}
+
+pub fn generate_support_bundle(
+    ctx: &ServiceContext,
+    service_provider: &ServiceProvider,
+    settings: &Settings,
+    input: SupportBundleInput,
+) -> Result<Vec<u8>, SupportBundleError> {
+    if input.include_database_extract && cfg!(feature = "postgres") {
+        return Err(SupportBundleError::DatabaseExtractNotSupported);
+    }
+
+    let mut zip = BundleZip::new();
+
+    zip.add_file(
+        "summary.json",
+        &to_json_bytes(&summary(ctx, service_provider)?),
+    )?;
+    zip.add_file(
+        "settings.json",
+        &to_json_bytes(&sanitised_settings(ctx, service_provider, settings)?),
+    )?;
+    zip.add_file("sync_logs.txt", sync_logs(ctx)?.as_bytes())?;
+    zip.add_file(
+        "sync_buffer_errors.json",
+        &to_json_bytes(&sync_buffer_errors(ctx)?),
+    )?;
+    zip.add_file("table_row_counts.csv", table_counts(ctx)?.as_bytes())?;
+    add_log_files(&mut zip, ctx, service_provider)?;
+
+    #[cfg(not(feature = "postgres"))]
+    if input.include_database_extract {
+        add_database_extract(&mut zip, ctx)?;
+    }
+
+    zip.finish()
+}
+
+/// In memory zip archive, all files are deflated and stamped with the bundle creation time
+struct BundleZip {
+    writer: ZipWriter<Cursor<Vec<u8>>>,
+    options: SimpleFileOptions,
+}
+
+impl BundleZip {
+    fn new() -> Self {
+        let now = Local::now().naive_local();
+        let modified = DateTime::from_date_and_time(
+            now.year() as u16,
+            now.month() as u8,
+            now.day() as u8,
+            now.hour() as u8,
+            now.minute() as u8,
+            now.second() as u8,
+        )
+        .unwrap_or_default();
+
+        BundleZip {
+            writer: ZipWriter::new(Cursor::new(Vec::new())),
+            options: SimpleFileOptions::default()
+                .compression_method(CompressionMethod::Deflated)
+                .last_modified_time(modified),
+        }
+    }
+
+    fn add_file(&mut self, name: &str, content: &[u8]) -> Result<(), SupportBundleError> {
+        self.writer.start_file(name, self.options)?;
+        self.writer.write_all(content)?;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Vec<u8>, SupportBundleError> {
+        Ok(self.writer.finish()?.into_inner())
+    }
+}
+
+fn to_json_bytes(value: &Value) -> Vec<u8> {
+    serde_json::to_vec_pretty(value).unwrap_or_default()
+}
+
+fn summary(
+    ctx: &ServiceContext,
+    service_provider: &ServiceProvider,
+) -> Result<Value, SupportBundleError> {
+    let key_value_store = KeyValueStoreRepository::new(&ctx.connection);
+    let site_id = key_value_store.get_i32(KeyType::SettingsSyncSiteId)?;
+    let stores = match site_id {
+        Some(site_id) => StoreRepository::new(&ctx.connection)
+            .query_by_filter(StoreFilter::new().site_id(EqualFilter::equal_to_i32(site_id)))?,
+        None => Vec::new(),
+    };
+
+    Ok(json!({
+        "generated_datetime": Utc::now().to_rfc3339(),
+        "app_version": Version::from_package_json().to_string(),
+        "database_version": key_value_store.get_string(KeyType::DatabaseVersion)?,
+        "hardware_id": service_provider.app_data_service.get_hardware_id().ok(),
+        "site_id": site_id,
+        "site_uuid": key_value_store.get_string(KeyType::SettingsSyncSiteUuid)?,
+        "central_server_site_id": key_value_store.get_i32(KeyType::SettingsSyncCentralServerSiteId)?,
+        "is_sync_disabled": service_provider.settings.is_sync_disabled(ctx)?,
+        "stores": stores.iter().map(|store| json!({
+            "id": store.store_row.id,
+            "code": store.store_row.code,
+            "name": store.name_row.name,
+        })).collect::<Vec<_>>(),
+    }))
+}
+
+/// Settings without passwords, secrets or other credentials
+fn sanitised_settings(
+    ctx: &ServiceContext,
+    service_provider: &ServiceProvider,
+    settings: &Settings,
+) -> Result<Value, SupportBundleError> {
+    let Settings {
+        server,
+        database,
+        sync,
+        logging,
+        backup,
+        oidc,
+    } = settings;
+    let sync_in_database = service_provider.settings.sync_settings(ctx)?;
+
+    Ok(json!({
+        "server": {
+            "port": server.port,
+            "danger_allow_http": server.danger_allow_http,
+            "debug_no_access_control": server.debug_no_access_control,
+            "cors_origins": server.cors_origins,
+            "base_dir": server.base_dir,
+            "machine_uid": server.machine_uid,
+            "metrics_enabled": server.metrics_enabled,
+        },
+        "database": {
+            "host": database.host,
+            "port": database.port,
+            "database_name": database.database_name,
+            "database_path": database.database_path,
+        },
+        "sync": sync.as_ref().map(|sync| json!({
+            "url": sync.url,
+            "username": sync.username,
+            "interval_seconds": sync.interval_seconds,
+        })),
+        "sync_in_database": sync_in_database.map(|sync| json!({
+            "url": sync.url,
+            "username": sync.username,
+            "interval_seconds": sync.interval_seconds,
+        })),
+        "logging": logging.as_ref().map(|logging| json!({
+            "mode": format!("{:?}", logging.mode),
+            "level": logging.level.to_string(),
+            "directory": logging.directory,
+            "filename": logging.filename,
+            "max_file_count": logging.max_file_count,
+            "max_file_size": logging.max_file_size,
+            "format": format!("{:?}", logging.format),
+        })),
+        "backup": backup.as_ref().map(|backup| json!({
+            "backup_dir": backup.backup_dir,
+            "max_number_of_backups": backup.max_number_of_backups,
+        })),
+        "oidc": oidc.as_ref().map(|oidc| json!({
+            "issuer_url": oidc.issuer_url,
+            "client_id": oidc.client_id,
+            "redirect_url": oidc.redirect_url,
+            "scopes": oidc.scopes,
+        })),
+    }))
+}
+
+fn sync_logs(ctx: &ServiceContext) -> Result<String, SupportBundleError> {
+    let sync_logs = SyncLogRepository::new(&ctx.connection).query(
+        Pagination {
+            limit: MAX_SYNC_LOGS,
+            offset: 0,
+        },
+        None,
+        Some(SyncLogSort {
+            key: SyncLogSortField::StartedDatetime,
+            desc: Some(true),
+        }),
+    )?;
+
+    Ok(sync_logs
+        .iter()
+        .map(|sync_log| format!("{:#?}", sync_log.sync_log_row))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Sync buffer records that failed to integrate, grouped by table, action and error
+fn sync_buffer_errors(ctx: &ServiceContext) -> Result<Value, SupportBundleError> {
+    let rows = SyncBufferRepository::new(&ctx.connection).query_by_filter(
+        SyncBufferFilter::new().integration_error(EqualFilter {
+            is_null: Some(false),
+            ..Default::default()
+        }),
+    )?;
+
+    let mut summaries: BTreeMap<(String, String, String), (i64, String)> = BTreeMap::new();
+    for row in rows {
+        let key = (
+            row.table_name,
+            format!("{:?}", row.action),
+            row.integration_error.unwrap_or_default(),
+        );
+        summaries.entry(key).or_insert((0, row.record_id)).0 += 1;
+    }
+
+    Ok(Value::Array(
+        summaries
+            .into_iter()
+            .map(
+                |((table_name, action, error), (count, example_record_id))| {
+                    json!({
+                        "table_name": table_name,
+                        "action": action,
+                        "integration_error": error,
+                        "count": count,
+                        "example_record_id": example_record_id,
+                    })
+                },
+            )
+            .collect(),
+    ))
+}
+
+fn table_counts(ctx: &ServiceContext) -> Result<String, SupportBundleError> {
+    let mut csv = "table_name,count\n".to_string();
+    for row in table_row_counts(&ctx.connection)? {
+        csv.push_str(&format!("{},{}\n", row.table_name, row.count));
+    }
+    Ok(csv)
+}
+
+/// Log files are best effort, the bundle is still useful when they can't be read
+fn add_log_files(
+    zip: &mut BundleZip,
+    ctx: &ServiceContext,
+    service_provider: &ServiceProvider,
+) -> Result<(), SupportBundleError> {
+    let log_service = &service_provider.log_service;
+    let log_dir = log_service.get_log_directory(ctx)?;
+    let mut file_names = match log_service.get_log_file_names(ctx) {
+        Ok(file_names) => file_names,
+        Err(error) => {
+            zip.add_file(
+                "logs/error.txt",
+                format!("Unable to list log files: {}", error).as_bytes(),
+            )?;
+            return Ok(());
+        }
+    };
+
+    let modified = |file_name: &String| {
+        fs::metadata(Path::new(&log_dir).join(file_name))
+            .and_then(|metadata| metadata.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH)
+    };
+    file_names.sort_by_key(|file_name| std::cmp::Reverse(modified(file_name)));
+
+    for file_name in file_names.into_iter().take(MAX_LOG_FILES) {
+        // Compressed logs are decompressed by the log service
+        let zip_file_name = format!("logs/{}", file_name.trim_end_matches(".gz"));
+        match log_service.get_log_content(ctx, Some(file_name.clone()), None) {
+            Ok((_, lines)) => zip.add_file(&zip_file_name, lines.join("\n").as_bytes())?,
+            Err(error) => zip.add_file(
+                &format!("{}.error.txt", zip_file_name),
+                format!("Unable to read log file: {}", error).as_bytes(),
+            )?,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(feature = "postgres"))]
+fn add_database_extract(
+    zip: &mut BundleZip,
+    ctx: &ServiceContext,
+) -> Result<(), SupportBundleError> {
+    let directory = tempfile::tempdir()?;
+    let path = directory.path().join("database_extract.sqlite");
+    repository::create_patient_free_extract(&ctx.connection, &path.to_string_lossy())?;
+    zip.add_file("database_extract.sqlite", &fs::read(&path)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+
+    use repository::{
+        database_settings::DatabaseSettings,
+        mock::MockDataInserts,
+        test_db::{get_test_db_settings, setup_all},
+        SyncAction, SyncBufferRow, SyncBufferRowRepository,
+    };
+
+    use crate::{
+        settings::ServerSettings,
+        sync::settings::SyncSettings,
+        test_helpers::{setup_all_and_service_provider, ServiceTestContext},
+    };
+
+    use super::*;
+
+    fn unzip(bytes: &[u8]) -> BTreeMap<String, String> {
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut files = BTreeMap::new();
+        for index in 0..archive.len() {
+            let mut file = archive.by_index(index).unwrap();
+            let mut content = String::new();
+            file.read_to_string(&mut content).unwrap();
+            files.insert(file.name().to_string(), content);
+        }
+        files
+    }
+
+    #[actix_rt::test]
+    async fn support_bundle() {
+        let ServiceTestContext {
+            service_provider,
+            service_context,
+            ..
+        } = setup_all_and_service_provider("support_bundle", MockDataInserts::none().names()).await;
+
+        let settings = Settings {
+            server: ServerSettings {
+                port: 8000,
+                danger_allow_http: false,
+                debug_no_access_control: false,
+                cors_origins: vec![],
+                base_dir: None,
+                machine_uid: None,
+                metrics_enabled: false,
+            },
+            database: DatabaseSettings {
+                password: "secret_database_password".to_string(),
+                ..get_test_db_settings("support_bundle")
+            },
+            sync: Some(SyncSettings {
+                url: "http://central".to_string(),
+                username: "site_a".to_string(),
+                password_sha256: "secret_password_hash".to_string(),
+                interval_seconds: 60,
+                batch_size: Default::default(),
+            }),
+            logging: None,
+            backup: None,
+            oidc: None,
+        };
+
+        let bundle = service_provider
+            .support_bundle_service
+            .generate_support_bundle(
+                &service_context,
+                &service_provider,
+                &settings,
+                SupportBundleInput::default(),
+            )
+            .unwrap();
+        // End of central directory record, with no comment
+        assert_eq!(
+            &bundle[bundle.len() - 22..bundle.len() - 18],
+            &[0x50, 0x4b, 0x05, 0x06]
+        );
+
+        let files = unzip(&bundle);
+        assert!(files.contains_key("summary.json"));
+        assert!(files.contains_key("sync_logs.txt"));
+        assert!(files.contains_key("sync_buffer_errors.json"));
+        assert!(!files.contains_key("database_extract.sqlite"));
+
+        let settings_json = &files["settings.json"];
+        assert!(settings_json.contains("http://central"));
+        assert!(!settings_json.contains("secret_password_hash"));
+        assert!(!settings_json.contains("secret_database_password"));
+
+        assert!(files["table_row_counts.csv"].starts_with("table_name,count\n"));
+        assert!(files["table_row_counts.csv"].contains("\nname,"));
+    }
+
+    #[actix_rt::test]
+    async fn sync_buffer_error_summary() {
+        let (_, connection, _, _) =
+            setup_all("support_bundle_sync_buffer_errors", MockDataInserts::none()).await;
+
+        let row = |id: &str, table_name: &str, error: Option<&str>| SyncBufferRow {
+            record_id: id.to_string(),
+            table_name: table_name.to_string(),
+            action: SyncAction::Upsert,
+            integration_error: error.map(str::to_string),
+            ..Default::default()
+        };
+        let repo = SyncBufferRowRepository::new(&connection);
+        repo.upsert_one(&row("a", "item", Some("missing unit")))
+            .unwrap();
+        repo.upsert_one(&row("b", "item", Some("missing unit")))
+            .unwrap();
+        repo.upsert_one(&row("c", "name", Some("bad json")))
+            .unwrap();
+        repo.upsert_one(&row("d", "name", None)).unwrap();
+
+        let summaries =
+            sync_buffer_errors(&ServiceContext::new_without_triggers(connection)).unwrap();
+
+        assert_eq!(
+            summaries,
+            json!([
+                {
+                    "table_name": "item",
+                    "action": "Upsert",
+                    "integration_error": "missing unit",
+                    "count": 2,
+                    "example_record_id": "a",
+                },
+                {
+                    "table_name": "name",
+                    "action": "Upsert",
+                    "integration_error": "bad json",
+                    "count": 1,
+                    "example_record_id": "c",
+                },
+            ])
+        );
+    }
+}
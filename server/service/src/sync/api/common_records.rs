@@ -88,6 +88,10 @@ impl CommonSyncRecord {
                 // append table name to record_id to avoid name overwrite
                 format!("{}{:#?}", record_id, ChangelogTableName::NameOmsFields)
             }
+            _ if table_name == *"item_oms_fields" => {
+                // append table name to record_id to avoid item overwrite
+                format!("{}{:#?}", record_id, ChangelogTableName::ItemOmsFields)
+            }
             _ => record_id,
         };
 
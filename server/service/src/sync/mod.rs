@@ -61,6 +61,12 @@ pub(crate) enum GetActiveStoresOnSiteError {
     SiteIdNotSet,
 }
 
+impl From<RepositoryError> for GetActiveStoresOnSiteError {
+    fn from(error: RepositoryError) -> Self {
+        GetActiveStoresOnSiteError::DatabaseError(error)
+    }
+}
+
 impl ActiveStoresOnSite {
     pub(crate) fn get(
         connection: &StorageConnection,
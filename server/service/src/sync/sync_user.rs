@@ -44,6 +44,7 @@ impl SyncUser {
             username,
             password: password.clone(),
             central_server_url,
+            client_ip: None,
         })
         .await
         {
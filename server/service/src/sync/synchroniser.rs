@@ -1,13 +1,14 @@
 use crate::{
+    recall::apply_recalls_to_site,
     service_provider::{ServiceContext, ServiceProvider},
     sync::{sync_status::logger::SyncStep, CentralServerConfig},
 };
 use log::warn;
 use repository::{RepositoryError, StorageConnection, SyncAction};
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 use thiserror::Error;
-use util::format_error;
+use util::{format_error, metrics};
 
 use super::{
     api::{SyncApiError, SyncApiSettings, SyncApiV5},
@@ -135,7 +136,15 @@ impl Synchroniser {
         let ctx = self.service_provider.basic_context()?;
         let mut logger = SyncLogger::start(&ctx.connection)?;
 
+        let start = Instant::now();
         let sync_result = self.sync_inner(&mut logger, &ctx).await;
+        metrics::SYNC_DURATION.observe_since(&[], start);
+        let result = if sync_result.is_ok() {
+            "success"
+        } else {
+            "error"
+        };
+        metrics::SYNC_RUNS.inc(&[("result", result)]);
 
         if let Err(error) = &sync_result {
             logger.error(error)?;
@@ -273,6 +282,11 @@ impl Synchroniser {
 
         logger.done_step(SyncStep::Integrate)?;
 
+        // Recalls pulled from central put stock of the recalled batches on hold
+        if let Err(error) = apply_recalls_to_site(&ctx.connection) {
+            log::error!("Problem applying synced recalls: {}", error);
+        }
+
         if !is_initialised {
             self.remote.advance_push_cursor(&ctx.connection)?;
             if let Some(v6_sync) = &v6_sync {
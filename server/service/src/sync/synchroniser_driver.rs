@@ -1,5 +1,6 @@
 use std::{future::Future, sync::Arc};
 
+use crate::log_context::LogContext;
 use crate::service_provider::ServiceProvider;
 use crate::sync::is_initialised;
 
@@ -90,9 +91,13 @@ impl SynchroniserDriver {
         // Pause file sync
         self.file_sync_trigger.pause();
 
-        let _ = Synchroniser::new(get_sync_settings(&service_provider), service_provider)
-            .unwrap()
-            .sync()
+        // Each sync run gets its own correlation id, to group its log lines
+        let _ = LogContext::new(None)
+            .scope(
+                Synchroniser::new(get_sync_settings(&service_provider), service_provider)
+                    .unwrap()
+                    .sync(),
+            )
             .await;
 
         // Unpause file sync
@@ -52,6 +52,9 @@ impl SyncRecordTester for StocktakeRecordTester {
             inventory_addition_id: None,
             inventory_reduction_id: None,
             is_locked: true,
+            is_blind: false,
+            variance_quantity_threshold: None,
+            variance_value_threshold: None,
         };
         let stocktake_line_row = StocktakeLineRow {
             id: uuid(),
@@ -70,6 +73,7 @@ impl SyncRecordTester for StocktakeRecordTester {
             sell_price_per_pack: Some(0.0),
             note: None,
             inventory_adjustment_reason_id: None,
+            requires_recount: false,
         };
         result.push(TestStepData {
             central_upsert: json!({"item": [{
@@ -0,0 +1,49 @@
+use repository::{AllocationRuleRow, AllocationStrategyType};
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "allocation_rule";
+
+const ALLOCATION_RULE1: (&str, &str) = (
+    "test_allocation_rule",
+    r#"{
+        "id": "test_allocation_rule",
+        "store_id": "store_b",
+        "name_tag_id": "59F2635D22B346ADA0088D6261926465",
+        "strategy": "PICK_PATH",
+        "expiring_soon_days": 30,
+        "min_remaining_shelf_life_days": 10,
+        "avoid_item_variant_id": "5fb99f9c-03f4-47f2-965b-c9ecd083c675",
+        "priority": 2
+    }"#,
+);
+
+fn allocation_rule1() -> AllocationRuleRow {
+    AllocationRuleRow {
+        id: ALLOCATION_RULE1.0.to_string(),
+        store_id: "store_b".to_string(),
+        name_tag_id: Some("59F2635D22B346ADA0088D6261926465".to_string()),
+        strategy: AllocationStrategyType::PickPath,
+        expiring_soon_days: Some(30),
+        min_remaining_shelf_life_days: Some(10),
+        avoid_item_variant_id: Some("5fb99f9c-03f4-47f2-965b-c9ecd083c675".to_string()),
+        priority: 2,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        ALLOCATION_RULE1,
+        allocation_rule1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: ALLOCATION_RULE1.0.to_string(),
+        push_data: json!(allocation_rule1()),
+    }]
+}
@@ -6,7 +6,7 @@ use crate::sync::{
 use chrono::NaiveDate;
 use repository::{
     mock::{mock_item_a, mock_stock_line_a},
-    InvoiceLineRow, InvoiceLineRowDelete, InvoiceLineType,
+    InvoiceLineDiscrepancyType, InvoiceLineRow, InvoiceLineRowDelete, InvoiceLineType,
 };
 use serde_json::json;
 const TABLE_NAME: &str = "trans_line";
@@ -95,6 +95,10 @@ fn trans_line_1_pull_record() -> TestSyncIncomingRecord {
             return_reason_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: None,
+            linked_invoice_line_id: None,
+            discrepancy_type: None,
+            discrepancy_number_of_packs: 0.0,
+            discrepancy_reason: None,
         },
     )
 }
@@ -124,6 +128,10 @@ fn trans_line_1_push_record() -> TestSyncOutgoingRecord {
             inventory_adjustment_reason_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: None,
+            linked_invoice_line_id: None,
+            discrepancy_type: None,
+            discrepancy_number_of_packs: 0.0,
+            discrepancy_reason: None,
         }),
     }
 }
@@ -212,6 +220,10 @@ fn trans_line_2_pull_record() -> TestSyncIncomingRecord {
             return_reason_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: None,
+            linked_invoice_line_id: None,
+            discrepancy_type: None,
+            discrepancy_number_of_packs: 0.0,
+            discrepancy_reason: None,
         },
     )
 }
@@ -241,6 +253,10 @@ fn trans_line_2_push_record() -> TestSyncOutgoingRecord {
             inventory_adjustment_reason_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: None,
+            linked_invoice_line_id: None,
+            discrepancy_type: None,
+            discrepancy_number_of_packs: 0.0,
+            discrepancy_reason: None,
         }),
     }
 }
@@ -303,7 +319,11 @@ const TRANS_LINE_OM_FIELDS: (&str, &str) = (
         "om_tax": 33.3,
         "om_total_before_tax": 105.4,
         "om_total_after_tax": 130.5,
-        "om_item_variant_id": "5fb99f9c-03f4-47f2-965b-c9ecd083c675"
+        "om_item_variant_id": "5fb99f9c-03f4-47f2-965b-c9ecd083c675",
+        "om_linked_invoice_line_id": "outbound_line_id",
+        "om_discrepancy_type": "SHORT",
+        "om_discrepancy_number_of_packs": 2.0,
+        "om_discrepancy_reason": "Short shipped"
     }"#,
 );
 fn trans_line_om_fields_pull_record() -> TestSyncIncomingRecord {
@@ -333,6 +353,10 @@ fn trans_line_om_fields_pull_record() -> TestSyncIncomingRecord {
             return_reason_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: Some("5fb99f9c-03f4-47f2-965b-c9ecd083c675".to_string()),
+            linked_invoice_line_id: Some("outbound_line_id".to_string()),
+            discrepancy_type: Some(InvoiceLineDiscrepancyType::Short),
+            discrepancy_number_of_packs: 2.0,
+            discrepancy_reason: Some("Short shipped".to_string()),
         },
     )
 }
@@ -362,6 +386,10 @@ fn trans_line_om_fields_push_record() -> TestSyncOutgoingRecord {
             inventory_adjustment_reason_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: Some("5fb99f9c-03f4-47f2-965b-c9ecd083c675".to_string()),
+            linked_invoice_line_id: Some("outbound_line_id".to_string()),
+            discrepancy_type: Some(InvoiceLineDiscrepancyType::Short),
+            discrepancy_number_of_packs: 2.0,
+            discrepancy_reason: Some("Short shipped".to_string()),
         }),
     }
 }
@@ -453,6 +481,10 @@ fn trans_line_om_fields_unset_tax_pull_record() -> TestSyncIncomingRecord {
             return_reason_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: None,
+            linked_invoice_line_id: None,
+            discrepancy_type: None,
+            discrepancy_number_of_packs: 0.0,
+            discrepancy_reason: None,
         },
     )
 }
@@ -482,6 +514,10 @@ fn trans_line_om_fields_unset_tax_push_record() -> TestSyncOutgoingRecord {
             inventory_adjustment_reason_id: None,
             foreign_currency_price_before_tax: Some(0.0),
             item_variant_id: None,
+            linked_invoice_line_id: None,
+            discrepancy_type: None,
+            discrepancy_number_of_packs: 0.0,
+            discrepancy_reason: None,
         }),
     }
 }
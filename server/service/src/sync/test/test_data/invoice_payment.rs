@@ -0,0 +1,71 @@
+use chrono::NaiveDate;
+use repository::{InvoicePaymentRow, InvoicePaymentType, PaymentMethod};
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "invoice_payment";
+
+const INVOICE_PAYMENT1: (&str, &str) = (
+    "test_invoice_payment",
+    r#"{
+        "id": "test_invoice_payment",
+        "store_id": "store_b",
+        "name_link_id": "name_store_a",
+        "invoice_id": "outbound_shipment_a",
+        "customer_return_id": null,
+        "type": "PAYMENT",
+        "payment_method": "CASH",
+        "receipt_number": 1,
+        "amount": 10.5,
+        "currency_id": null,
+        "currency_rate": 1.0,
+        "home_currency_amount": 10.5,
+        "reference": null,
+        "comment": "Paid at the counter",
+        "user_id": "user_account_a",
+        "payment_datetime": "2024-09-01T10:00:00",
+        "cancelled_datetime": null
+    }"#,
+);
+
+fn invoice_payment1() -> InvoicePaymentRow {
+    InvoicePaymentRow {
+        id: INVOICE_PAYMENT1.0.to_string(),
+        store_id: "store_b".to_string(),
+        name_link_id: "name_store_a".to_string(),
+        invoice_id: Some("outbound_shipment_a".to_string()),
+        customer_return_id: None,
+        r#type: InvoicePaymentType::Payment,
+        payment_method: Some(PaymentMethod::Cash),
+        receipt_number: 1,
+        amount: 10.5,
+        currency_id: None,
+        currency_rate: 1.0,
+        home_currency_amount: 10.5,
+        reference: None,
+        comment: Some("Paid at the counter".to_string()),
+        user_id: Some("user_account_a".to_string()),
+        payment_datetime: NaiveDate::from_ymd_opt(2024, 9, 1)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap(),
+        cancelled_datetime: None,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        INVOICE_PAYMENT1,
+        invoice_payment1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: INVOICE_PAYMENT1.0.to_string(),
+        push_data: json!(invoice_payment1()),
+    }]
+}
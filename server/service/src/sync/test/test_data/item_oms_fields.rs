@@ -0,0 +1,36 @@
+use crate::sync::test::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+use repository::ItemOmsFieldsRow;
+use serde_json::json;
+
+const TABLE_NAME: &str = "item_oms_fields";
+
+const ITEM_OMS_FIELDS_1: (&str, &str) = (
+    "item_a",
+    r#"{
+        "id": "item_a",
+        "is_serialised": true
+}"#,
+);
+
+fn item_oms_fields_1() -> ItemOmsFieldsRow {
+    ItemOmsFieldsRow {
+        id: ITEM_OMS_FIELDS_1.0.to_owned(),
+        is_serialised: true,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        ITEM_OMS_FIELDS_1,
+        item_oms_fields_1(),
+    )]
+}
+
+pub(crate) fn test_v6_central_push_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: ITEM_OMS_FIELDS_1.0.to_string(),
+        push_data: json!(item_oms_fields_1()),
+    }]
+}
@@ -0,0 +1,45 @@
+use repository::{LmisCodeMappingRow, LmisCodeMappingType};
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "lmis_code_mapping";
+
+const LMIS_CODE_MAPPING1: (&str, &str) = (
+    "test_lmis_code_mapping",
+    r#"{
+        "id": "test_lmis_code_mapping",
+        "program_id": "program_test",
+        "type": "ITEM",
+        "internal_id": "item_a",
+        "external_code": "C100",
+        "deleted_datetime": null
+    }"#,
+);
+
+fn lmis_code_mapping1() -> LmisCodeMappingRow {
+    LmisCodeMappingRow {
+        id: LMIS_CODE_MAPPING1.0.to_string(),
+        program_id: "program_test".to_string(),
+        r#type: LmisCodeMappingType::Item,
+        internal_id: "item_a".to_string(),
+        external_code: "C100".to_string(),
+        deleted_datetime: None,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        LMIS_CODE_MAPPING1,
+        lmis_code_mapping1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: LMIS_CODE_MAPPING1.0.to_string(),
+        push_data: json!(lmis_code_mapping1()),
+    }]
+}
@@ -3,6 +3,7 @@ use self::special::name_to_name_store_join;
 use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
 
 pub(crate) mod activity_log;
+pub(crate) mod allocation_rule;
 pub(crate) mod asset;
 pub(crate) mod asset_catalogue_item;
 pub(crate) mod asset_category;
@@ -19,8 +20,11 @@ pub(crate) mod indicator_attribute;
 pub(crate) mod indicator_value;
 pub(crate) mod invoice;
 pub(crate) mod invoice_line;
+pub(crate) mod invoice_payment;
 pub(crate) mod item;
+pub(crate) mod item_oms_fields;
 pub(crate) mod item_variant;
+pub(crate) mod lmis_code_mapping;
 pub(crate) mod location;
 pub(crate) mod location_movement;
 pub(crate) mod master_list;
@@ -28,6 +32,7 @@ pub(crate) mod master_list_line;
 pub(crate) mod master_list_name_join;
 pub(crate) mod name;
 pub(crate) mod name_oms_fields;
+pub(crate) mod name_price_category;
 pub(crate) mod name_property;
 pub(crate) mod name_store_join;
 pub(crate) mod name_tag;
@@ -35,16 +40,27 @@ pub(crate) mod name_tag_join;
 pub(crate) mod packaging_variant;
 pub(crate) mod period;
 pub(crate) mod period_schedule;
+pub(crate) mod pick_list;
+pub(crate) mod pick_list_line;
+pub(crate) mod pick_path_location;
+pub(crate) mod prescription_line_direction;
+pub(crate) mod price_list;
+pub(crate) mod price_list_line;
 pub(crate) mod program_indicator;
 pub(crate) mod program_requisition_settings;
 pub(crate) mod property;
 pub(crate) mod reason;
+pub(crate) mod recall;
+pub(crate) mod recall_batch;
+pub(crate) mod recall_stock_line;
 pub(crate) mod report;
 pub(crate) mod requisition;
 pub(crate) mod requisition_line;
 pub(crate) mod rnr_form;
 pub(crate) mod rnr_form_line;
 pub(crate) mod sensor;
+pub(crate) mod serial_number;
+pub(crate) mod serial_number_movement;
 pub(crate) mod special;
 pub(crate) mod stock_line;
 pub(crate) mod stocktake;
@@ -52,6 +68,7 @@ pub(crate) mod stocktake_line;
 pub(crate) mod store;
 pub(crate) mod store_preference;
 pub(crate) mod sync_file_reference;
+pub(crate) mod tax_rule;
 pub(crate) mod temperature_breach;
 pub(crate) mod temperature_log;
 pub(crate) mod unit;
@@ -61,6 +78,7 @@ pub(crate) mod vaccination;
 pub(crate) mod vaccine_course;
 pub(crate) mod vaccine_course_dose;
 pub(crate) mod vaccine_course_item;
+pub(crate) mod vaccine_open_vial;
 
 pub(crate) fn get_all_pull_upsert_central_test_records() -> Vec<TestSyncIncomingRecord> {
     let mut test_records = Vec::new();
@@ -86,6 +104,7 @@ pub(crate) fn get_all_pull_upsert_central_test_records() -> Vec<TestSyncIncoming
     test_records.append(&mut barcode::test_pull_upsert_records());
     // Open mSupply Central
     test_records.append(&mut name_oms_fields::test_pull_upsert_records());
+    test_records.append(&mut item_oms_fields::test_pull_upsert_records());
     test_records.append(&mut asset_class::test_pull_upsert_records());
     test_records.append(&mut asset_category::test_pull_upsert_records());
     test_records.append(&mut asset_type::test_pull_upsert_records());
@@ -104,7 +123,14 @@ pub(crate) fn get_all_pull_upsert_central_test_records() -> Vec<TestSyncIncoming
     test_records.append(&mut program_indicator::test_pull_upsert_records());
     test_records.append(&mut indicator_attribute::test_pull_upsert_records());
     test_records.append(&mut item_variant::test_pull_upsert_records());
+    test_records.append(&mut lmis_code_mapping::test_pull_upsert_records());
     test_records.append(&mut packaging_variant::test_pull_upsert_records());
+    test_records.append(&mut recall::test_pull_upsert_records());
+    test_records.append(&mut recall_batch::test_pull_upsert_records());
+    test_records.append(&mut price_list::test_pull_upsert_records());
+    test_records.append(&mut price_list_line::test_pull_upsert_records());
+    test_records.append(&mut tax_rule::test_pull_upsert_records());
+    test_records.append(&mut name_price_category::test_pull_upsert_records());
 
     test_records
 }
@@ -135,6 +161,16 @@ pub(crate) fn get_all_pull_upsert_remote_test_records() -> Vec<TestSyncIncomingR
     test_records.append(&mut rnr_form::test_pull_upsert_records());
     test_records.append(&mut rnr_form_line::test_pull_upsert_records());
     test_records.append(&mut vaccination::test_pull_upsert_records());
+    test_records.append(&mut recall_stock_line::test_pull_upsert_records());
+    test_records.append(&mut serial_number::test_pull_upsert_records());
+    test_records.append(&mut serial_number_movement::test_pull_upsert_records());
+    test_records.append(&mut invoice_payment::test_pull_upsert_records());
+    test_records.append(&mut prescription_line_direction::test_pull_upsert_records());
+    test_records.append(&mut pick_list::test_pull_upsert_records());
+    test_records.append(&mut pick_list_line::test_pull_upsert_records());
+    test_records.append(&mut pick_path_location::test_pull_upsert_records());
+    test_records.append(&mut vaccine_open_vial::test_pull_upsert_records());
+    test_records.append(&mut allocation_rule::test_pull_upsert_records());
 
     test_records
 }
@@ -203,9 +239,17 @@ pub(crate) fn get_all_sync_v6_records() -> Vec<TestSyncOutgoingRecord> {
     test_records.append(&mut vaccine_course::test_v6_records());
     test_records.append(&mut vaccine_course_item::test_v6_records());
     test_records.append(&mut name_oms_fields::test_v6_central_push_records());
+    test_records.append(&mut item_oms_fields::test_v6_central_push_records());
     test_records.append(&mut item_variant::test_v6_central_push_records());
+    test_records.append(&mut lmis_code_mapping::test_v6_records());
     test_records.append(&mut packaging_variant::test_v6_central_push_records());
     test_records.append(&mut property::test_v6_central_push_records());
+    test_records.append(&mut recall::test_v6_records());
+    test_records.append(&mut recall_batch::test_v6_records());
+    test_records.append(&mut price_list::test_v6_records());
+    test_records.append(&mut price_list_line::test_v6_records());
+    test_records.append(&mut tax_rule::test_v6_records());
+    test_records.append(&mut name_price_category::test_v6_records());
 
     // Remote
     test_records.append(&mut asset::test_v6_records());
@@ -219,6 +263,16 @@ pub(crate) fn get_all_sync_v6_records() -> Vec<TestSyncOutgoingRecord> {
     test_records.append(&mut demographic::test_v6_records());
     test_records.append(&mut vaccine_course_dose::test_v6_records());
     test_records.append(&mut vaccination::test_v6_records());
+    test_records.append(&mut recall_stock_line::test_v6_records());
+    test_records.append(&mut serial_number::test_v6_records());
+    test_records.append(&mut serial_number_movement::test_v6_records());
+    test_records.append(&mut invoice_payment::test_v6_records());
+    test_records.append(&mut prescription_line_direction::test_v6_records());
+    test_records.append(&mut pick_list::test_v6_records());
+    test_records.append(&mut pick_list_line::test_v6_records());
+    test_records.append(&mut pick_path_location::test_v6_records());
+    test_records.append(&mut vaccine_open_vial::test_v6_records());
+    test_records.append(&mut allocation_rule::test_v6_records());
 
     test_records
 }
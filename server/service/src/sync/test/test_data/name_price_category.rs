@@ -0,0 +1,37 @@
+use repository::NamePriceCategoryRow;
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "name_price_category";
+
+const NAME_PRICE_CATEGORY_1: (&str, &str) = (
+    "name_a",
+    r#"{
+        "name_id": "name_a",
+        "price_category": "INSURED"
+    }"#,
+);
+
+fn name_price_category_1() -> NamePriceCategoryRow {
+    NamePriceCategoryRow {
+        name_id: NAME_PRICE_CATEGORY_1.0.to_string(),
+        price_category: "INSURED".to_string(),
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        NAME_PRICE_CATEGORY_1,
+        name_price_category_1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: NAME_PRICE_CATEGORY_1.0.to_string(),
+        push_data: json!(name_price_category_1()),
+    }]
+}
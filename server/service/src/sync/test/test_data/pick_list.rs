@@ -0,0 +1,49 @@
+use chrono::NaiveDate;
+use repository::{PickListRow, PickListStatus};
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "pick_list";
+
+const PICK_LIST1: (&str, &str) = (
+    "test_pick_list",
+    r#"{
+        "id": "test_pick_list",
+        "store_id": "store_b",
+        "status": "IN_PROGRESS",
+        "created_datetime": "2024-09-01T10:00:00",
+        "completed_datetime": null,
+        "user_id": "user_account_a"
+    }"#,
+);
+
+fn pick_list1() -> PickListRow {
+    PickListRow {
+        id: PICK_LIST1.0.to_string(),
+        store_id: "store_b".to_string(),
+        status: PickListStatus::InProgress,
+        created_datetime: NaiveDate::from_ymd_opt(2024, 9, 1)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap(),
+        completed_datetime: None,
+        user_id: "user_account_a".to_string(),
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        PICK_LIST1,
+        pick_list1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: PICK_LIST1.0.to_string(),
+        push_data: json!(pick_list1()),
+    }]
+}
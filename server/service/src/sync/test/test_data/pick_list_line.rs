@@ -0,0 +1,61 @@
+use chrono::NaiveDate;
+use repository::PickListLineRow;
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "pick_list_line";
+
+const PICK_LIST_LINE1: (&str, &str) = (
+    "test_pick_list_line",
+    r#"{
+        "id": "test_pick_list_line",
+        "pick_list_id": "test_pick_list",
+        "invoice_id": "outbound_shipment_a",
+        "invoice_line_id": "outbound_shipment_a_line_a",
+        "item_link_id": "item_a",
+        "stock_line_id": "item_a_line_a",
+        "location_id": null,
+        "line_number": 1,
+        "number_of_packs": 10.0,
+        "picked_number_of_packs": 8.0,
+        "picked_datetime": "2024-09-01T11:00:00"
+    }"#,
+);
+
+fn pick_list_line1() -> PickListLineRow {
+    PickListLineRow {
+        id: PICK_LIST_LINE1.0.to_string(),
+        pick_list_id: "test_pick_list".to_string(),
+        invoice_id: "outbound_shipment_a".to_string(),
+        invoice_line_id: "outbound_shipment_a_line_a".to_string(),
+        item_link_id: "item_a".to_string(),
+        stock_line_id: Some("item_a_line_a".to_string()),
+        location_id: None,
+        line_number: 1,
+        number_of_packs: 10.0,
+        picked_number_of_packs: Some(8.0),
+        picked_datetime: Some(
+            NaiveDate::from_ymd_opt(2024, 9, 1)
+                .unwrap()
+                .and_hms_opt(11, 0, 0)
+                .unwrap(),
+        ),
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        PICK_LIST_LINE1,
+        pick_list_line1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: PICK_LIST_LINE1.0.to_string(),
+        push_data: json!(pick_list_line1()),
+    }]
+}
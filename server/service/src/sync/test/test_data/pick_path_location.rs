@@ -0,0 +1,41 @@
+use repository::PickPathLocationRow;
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "pick_path_location";
+
+const PICK_PATH_LOCATION1: (&str, &str) = (
+    "test_pick_path_location",
+    r#"{
+        "id": "test_pick_path_location",
+        "store_id": "store_b",
+        "location_id": "location_1",
+        "sort_order": 1
+    }"#,
+);
+
+fn pick_path_location1() -> PickPathLocationRow {
+    PickPathLocationRow {
+        id: PICK_PATH_LOCATION1.0.to_string(),
+        store_id: "store_b".to_string(),
+        location_id: "location_1".to_string(),
+        sort_order: 1,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        PICK_PATH_LOCATION1,
+        pick_path_location1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: PICK_PATH_LOCATION1.0.to_string(),
+        push_data: json!(pick_path_location1()),
+    }]
+}
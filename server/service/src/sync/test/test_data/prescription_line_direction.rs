@@ -0,0 +1,51 @@
+use repository::{AdministrationRoute, PrescriptionLineDirectionRow};
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "prescription_line_direction";
+
+const PRESCRIPTION_LINE_DIRECTION1: (&str, &str) = (
+    "test_prescription_line_direction",
+    r#"{
+        "id": "test_prescription_line_direction",
+        "invoice_line_id": "outbound_shipment_a_line_a",
+        "sig": "1T BD x5/7",
+        "dose": 1.0,
+        "dose_unit": "tablet",
+        "frequency_per_day": 2.0,
+        "duration_days": 5,
+        "route": "ORAL",
+        "directions": "Take 1 tablet twice a day for 5 days"
+    }"#,
+);
+
+fn prescription_line_direction1() -> PrescriptionLineDirectionRow {
+    PrescriptionLineDirectionRow {
+        id: PRESCRIPTION_LINE_DIRECTION1.0.to_string(),
+        invoice_line_id: "outbound_shipment_a_line_a".to_string(),
+        sig: Some("1T BD x5/7".to_string()),
+        dose: Some(1.0),
+        dose_unit: Some("tablet".to_string()),
+        frequency_per_day: Some(2.0),
+        duration_days: Some(5),
+        route: Some(AdministrationRoute::Oral),
+        directions: "Take 1 tablet twice a day for 5 days".to_string(),
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        PRESCRIPTION_LINE_DIRECTION1,
+        prescription_line_direction1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: PRESCRIPTION_LINE_DIRECTION1.0.to_string(),
+        push_data: json!(prescription_line_direction1()),
+    }]
+}
@@ -0,0 +1,54 @@
+use chrono::NaiveDate;
+use repository::PriceListRow;
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "price_list";
+
+const PRICE_LIST_1: (&str, &str) = (
+    "test_price_list",
+    r#"{
+        "id": "test_price_list",
+        "name": "Insured",
+        "store_id": null,
+        "name_id": null,
+        "name_tag_id": null,
+        "price_category": "INSURED",
+        "valid_from": "2024-01-01",
+        "valid_to": null,
+        "priority": 1,
+        "is_active": true
+    }"#,
+);
+
+fn price_list_1() -> PriceListRow {
+    PriceListRow {
+        id: PRICE_LIST_1.0.to_string(),
+        name: "Insured".to_string(),
+        store_id: None,
+        name_id: None,
+        name_tag_id: None,
+        price_category: Some("INSURED".to_string()),
+        valid_from: NaiveDate::from_ymd_opt(2024, 1, 1),
+        valid_to: None,
+        priority: 1,
+        is_active: true,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        PRICE_LIST_1,
+        price_list_1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: PRICE_LIST_1.0.to_string(),
+        push_data: json!(price_list_1()),
+    }]
+}
@@ -0,0 +1,45 @@
+use repository::PriceListLineRow;
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "price_list_line";
+
+const PRICE_LIST_LINE_1: (&str, &str) = (
+    "test_price_list_line",
+    r#"{
+        "id": "test_price_list_line",
+        "price_list_id": "test_price_list",
+        "item_id": "item_a",
+        "min_quantity": 10.0,
+        "price_per_unit": 2.5,
+        "discount_percentage": null
+    }"#,
+);
+
+fn price_list_line_1() -> PriceListLineRow {
+    PriceListLineRow {
+        id: PRICE_LIST_LINE_1.0.to_string(),
+        price_list_id: "test_price_list".to_string(),
+        item_id: "item_a".to_string(),
+        min_quantity: 10.0,
+        price_per_unit: Some(2.5),
+        discount_percentage: None,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        PRICE_LIST_LINE_1,
+        price_list_line_1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: PRICE_LIST_LINE_1.0.to_string(),
+        push_data: json!(price_list_line_1()),
+    }]
+}
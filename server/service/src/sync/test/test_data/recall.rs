@@ -0,0 +1,51 @@
+use chrono::NaiveDate;
+use repository::RecallRow;
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "recall";
+
+const RECALL1: (&str, &str) = (
+    "test_recall",
+    r#"{
+        "id": "test_recall",
+        "item_link_id": "item_a",
+        "reason": "Contaminated batch",
+        "recall_date": "2025-01-10",
+        "created_datetime": "2025-01-10T10:00:00",
+        "store_id": "store_a",
+        "user_id": "user_account_a"
+    }"#,
+);
+
+fn recall1() -> RecallRow {
+    RecallRow {
+        id: RECALL1.0.to_string(),
+        item_link_id: "item_a".to_string(),
+        reason: "Contaminated batch".to_string(),
+        recall_date: NaiveDate::from_ymd_opt(2025, 1, 10).unwrap(),
+        created_datetime: NaiveDate::from_ymd_opt(2025, 1, 10)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap(),
+        store_id: "store_a".to_string(),
+        user_id: "user_account_a".to_string(),
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        RECALL1,
+        recall1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: RECALL1.0.to_string(),
+        push_data: json!(recall1()),
+    }]
+}
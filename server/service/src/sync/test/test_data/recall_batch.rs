@@ -0,0 +1,39 @@
+use repository::RecallBatchRow;
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "recall_batch";
+
+const RECALL_BATCH1: (&str, &str) = (
+    "test_recall_batch",
+    r#"{
+        "id": "test_recall_batch",
+        "recall_id": "test_recall",
+        "batch": "B1"
+    }"#,
+);
+
+fn recall_batch1() -> RecallBatchRow {
+    RecallBatchRow {
+        id: RECALL_BATCH1.0.to_string(),
+        recall_id: "test_recall".to_string(),
+        batch: "B1".to_string(),
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        RECALL_BATCH1,
+        recall_batch1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: RECALL_BATCH1.0.to_string(),
+        push_data: json!(recall_batch1()),
+    }]
+}
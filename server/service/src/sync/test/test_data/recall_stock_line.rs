@@ -0,0 +1,43 @@
+use repository::RecallStockLineRow;
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "recall_stock_line";
+
+const RECALL_STOCK_LINE1: (&str, &str) = (
+    "test_recall_stock_line",
+    r#"{
+        "id": "test_recall_stock_line",
+        "recall_id": "test_recall",
+        "store_id": "store_b",
+        "stock_line_id": "item_a_line_a",
+        "quarantined_number_of_packs": 30.0
+    }"#,
+);
+
+fn recall_stock_line1() -> RecallStockLineRow {
+    RecallStockLineRow {
+        id: RECALL_STOCK_LINE1.0.to_string(),
+        recall_id: "test_recall".to_string(),
+        store_id: "store_b".to_string(),
+        stock_line_id: "item_a_line_a".to_string(),
+        quarantined_number_of_packs: 30.0,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        RECALL_STOCK_LINE1,
+        recall_stock_line1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: RECALL_STOCK_LINE1.0.to_string(),
+        push_data: json!(recall_stock_line1()),
+    }]
+}
@@ -0,0 +1,59 @@
+use chrono::NaiveDate;
+use repository::{SerialNumberRow, SerialNumberStatus};
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "serial_number";
+
+const SERIAL_NUMBER1: (&str, &str) = (
+    "test_serial_number",
+    r#"{
+        "id": "test_serial_number",
+        "store_id": "store_b",
+        "item_link_id": "item_a",
+        "serial": "SN0001",
+        "gtin": "09506000134352",
+        "stock_line_id": "item_a_line_a",
+        "status": "ISSUED",
+        "received_invoice_line_id": "inbound_shipment_a_line_a",
+        "issued_invoice_line_id": "outbound_shipment_a_line_a",
+        "issued_name_link_id": "name_store_a",
+        "created_datetime": "2024-09-01T10:00:00"
+    }"#,
+);
+
+fn serial_number1() -> SerialNumberRow {
+    SerialNumberRow {
+        id: SERIAL_NUMBER1.0.to_string(),
+        store_id: "store_b".to_string(),
+        item_link_id: "item_a".to_string(),
+        serial: "SN0001".to_string(),
+        gtin: Some("09506000134352".to_string()),
+        stock_line_id: "item_a_line_a".to_string(),
+        status: SerialNumberStatus::Issued,
+        received_invoice_line_id: "inbound_shipment_a_line_a".to_string(),
+        issued_invoice_line_id: Some("outbound_shipment_a_line_a".to_string()),
+        issued_name_link_id: Some("name_store_a".to_string()),
+        created_datetime: NaiveDate::from_ymd_opt(2024, 9, 1)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap(),
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        SERIAL_NUMBER1,
+        serial_number1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: SERIAL_NUMBER1.0.to_string(),
+        push_data: json!(serial_number1()),
+    }]
+}
@@ -0,0 +1,51 @@
+use chrono::NaiveDate;
+use repository::{SerialNumberMovementRow, SerialNumberMovementType};
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "serial_number_movement";
+
+const SERIAL_NUMBER_MOVEMENT1: (&str, &str) = (
+    "test_serial_number_movement",
+    r#"{
+        "id": "test_serial_number_movement",
+        "serial_number_id": "test_serial_number",
+        "store_id": "store_b",
+        "type": "ISSUED",
+        "invoice_id": "outbound_shipment_a",
+        "invoice_line_id": "outbound_shipment_a_line_a",
+        "datetime": "2024-09-02T10:00:00"
+    }"#,
+);
+
+fn serial_number_movement1() -> SerialNumberMovementRow {
+    SerialNumberMovementRow {
+        id: SERIAL_NUMBER_MOVEMENT1.0.to_string(),
+        serial_number_id: "test_serial_number".to_string(),
+        store_id: "store_b".to_string(),
+        r#type: SerialNumberMovementType::Issued,
+        invoice_id: "outbound_shipment_a".to_string(),
+        invoice_line_id: "outbound_shipment_a_line_a".to_string(),
+        datetime: NaiveDate::from_ymd_opt(2024, 9, 2)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap(),
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        SERIAL_NUMBER_MOVEMENT1,
+        serial_number_movement1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: SERIAL_NUMBER_MOVEMENT1.0.to_string(),
+        push_data: json!(serial_number_movement1()),
+    }]
+}
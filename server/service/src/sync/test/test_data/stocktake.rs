@@ -53,6 +53,9 @@ fn stocktake_pull_record() -> TestSyncIncomingRecord {
             inventory_reduction_id: Some("inbound_shipment_b".to_string()),
             is_locked: false,
             stocktake_date: Some(NaiveDate::from_ymd_opt(2021, 7, 30).unwrap()),
+            is_blind: false,
+            variance_quantity_threshold: None,
+            variance_value_threshold: None,
         },
     )
 }
@@ -79,6 +82,10 @@ fn stocktake_push_record() -> TestSyncOutgoingRecord {
             stock_take_time: NaiveTime::from_num_seconds_from_midnight_opt(47061, 0).unwrap(),
             created_datetime: Some(created_datetime),
             finalised_datetime: Some(created_datetime),
+            om_status: Some(StocktakeStatus::Finalised),
+            is_blind: false,
+            variance_quantity_threshold: None,
+            variance_value_threshold: None,
         }),
     }
 }
@@ -132,6 +139,9 @@ fn stocktake_om_field_pull_record() -> TestSyncIncomingRecord {
             inventory_reduction_id: None,
             is_locked: false,
             stocktake_date: Some(NaiveDate::from_ymd_opt(2021, 7, 30).unwrap()),
+            is_blind: false,
+            variance_quantity_threshold: None,
+            variance_value_threshold: None,
         },
     )
 }
@@ -165,14 +175,113 @@ fn stocktake_om_field_push_record() -> TestSyncOutgoingRecord {
                     .and_hms_opt(15, 15, 15)
                     .unwrap()
             ),
+            om_status: Some(StocktakeStatus::Finalised),
+            is_blind: false,
+            variance_quantity_threshold: None,
+            variance_value_threshold: None,
+        }),
+    }
+}
+
+const STOCKTAKE_BLIND: (&str, &str) = (
+    "Ba375950f0d211eb8dddb54df6d741bc",
+    r#"{
+      "Description": "Blind",
+      "ID": "Ba375950f0d211eb8dddb54df6d741bc",
+      "Locked": false,
+      "comment": "",
+      "created_by_ID": "",
+      "finalised_by_ID": "",
+      "invad_additions_ID": "",
+      "invad_reductions_ID": "",
+      "programID": "",
+      "serial_number": 4,
+      "status": "sg",
+      "stock_take_created_date": "2021-07-30",
+      "stock_take_date": "2021-07-30",
+      "stock_take_time": 54915,
+      "store_ID": "store_a",
+      "type": "",
+      "om_created_datetime": "2021-07-30T15:15:15",
+      "om_finalised_datetime": "",
+      "om_status": "PENDING_APPROVAL",
+      "om_is_blind": true,
+      "om_variance_quantity_threshold": 5.0,
+      "om_variance_value_threshold": 100.0
+    }"#,
+);
+fn stocktake_blind_pull_record() -> TestSyncIncomingRecord {
+    TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        STOCKTAKE_BLIND,
+        StocktakeRow {
+            id: STOCKTAKE_BLIND.0.to_string(),
+            user_id: "".to_string(),
+            store_id: "store_a".to_string(),
+            stocktake_number: 4,
+            comment: None,
+            description: Some("Blind".to_string()),
+            status: StocktakeStatus::PendingApproval,
+            created_datetime: NaiveDate::from_ymd_opt(2021, 7, 30)
+                .unwrap()
+                .and_hms_opt(15, 15, 15)
+                .unwrap(),
+            finalised_datetime: None,
+            inventory_addition_id: None,
+            inventory_reduction_id: None,
+            is_locked: false,
+            stocktake_date: Some(NaiveDate::from_ymd_opt(2021, 7, 30).unwrap()),
+            is_blind: true,
+            variance_quantity_threshold: Some(5.0),
+            variance_value_threshold: Some(100.0),
+        },
+    )
+}
+fn stocktake_blind_push_record() -> TestSyncOutgoingRecord {
+    TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: STOCKTAKE_BLIND.0.to_string(),
+        push_data: json!(LegacyStocktakeRow {
+            ID: STOCKTAKE_BLIND.0.to_string(),
+            user_id: "".to_string(),
+            store_ID: "store_a".to_string(),
+            status: LegacyStocktakeStatus::Sg,
+            Description: Some("Blind".to_string()),
+            comment: None,
+            inventory_addition_id: None,
+            inventory_reduction_id: None,
+            serial_number: 4,
+            stock_take_created_date: NaiveDate::from_ymd_opt(2021, 7, 30).unwrap(),
+            stock_take_time: NaiveTime::from_hms_opt(15, 15, 15).unwrap(),
+            is_locked: false,
+            stocktake_date: Some(NaiveDate::from_ymd_opt(2021, 7, 30).unwrap()),
+            created_datetime: Some(
+                NaiveDate::from_ymd_opt(2021, 7, 30)
+                    .unwrap()
+                    .and_hms_opt(15, 15, 15)
+                    .unwrap()
+            ),
+            finalised_datetime: None,
+            om_status: Some(StocktakeStatus::PendingApproval),
+            is_blind: true,
+            variance_quantity_threshold: Some(5.0),
+            variance_value_threshold: Some(100.0),
         }),
     }
 }
 
 pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
-    vec![stocktake_pull_record(), stocktake_om_field_pull_record()]
+    vec![
+        stocktake_pull_record(),
+        stocktake_om_field_pull_record(),
+        stocktake_blind_pull_record(),
+    ]
 }
 
 pub(crate) fn test_push_records() -> Vec<TestSyncOutgoingRecord> {
-    vec![stocktake_push_record(), stocktake_om_field_push_record()]
+    vec![
+        stocktake_push_record(),
+        stocktake_om_field_push_record(),
+        stocktake_blind_push_record(),
+    ]
 }
@@ -55,6 +55,7 @@ fn stocktake_line_pull_record() -> TestSyncIncomingRecord {
             sell_price_per_pack: Some(15.0),
             note: None,
             inventory_adjustment_reason_id: None,
+            requires_recount: false,
         },
     )
 }
@@ -80,6 +81,7 @@ fn stocktake_line_push_record() -> TestSyncOutgoingRecord {
             sell_price: 15.0,
             note: None,
             inventory_adjustment_reason_id: None,
+            requires_recount: false,
         }),
     }
 }
@@ -132,6 +134,7 @@ fn stocktake_line_om_field_pull_record() -> TestSyncIncomingRecord {
             sell_price_per_pack: Some(15.0),
             note: Some("om note".to_string()),
             inventory_adjustment_reason_id: None,
+            requires_recount: false,
         },
     )
 }
@@ -157,6 +160,7 @@ fn stocktake_line_om_field_push_record() -> TestSyncOutgoingRecord {
             sell_price: 15.0,
             note: Some("om note".to_string()),
             inventory_adjustment_reason_id: None,
+            requires_recount: false,
         }),
     }
 }
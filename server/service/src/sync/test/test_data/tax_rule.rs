@@ -0,0 +1,46 @@
+use chrono::NaiveDate;
+use repository::TaxRuleRow;
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "tax_rule";
+
+const TAX_RULE_1: (&str, &str) = (
+    "test_tax_rule",
+    r#"{
+        "id": "test_tax_rule",
+        "item_id": "item_a",
+        "price_category": "EXEMPT",
+        "tax_percentage": 0.0,
+        "valid_from": null,
+        "valid_to": "2030-12-31"
+    }"#,
+);
+
+fn tax_rule_1() -> TaxRuleRow {
+    TaxRuleRow {
+        id: TAX_RULE_1.0.to_string(),
+        item_id: Some("item_a".to_string()),
+        price_category: Some("EXEMPT".to_string()),
+        tax_percentage: 0.0,
+        valid_from: None,
+        valid_to: NaiveDate::from_ymd_opt(2030, 12, 31),
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        TAX_RULE_1,
+        tax_rule_1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: TAX_RULE_1.0.to_string(),
+        push_data: json!(tax_rule_1()),
+    }]
+}
@@ -26,6 +26,7 @@ fn vaccine_course1() -> VaccineCourseRow {
         coverage_rate: 0.0,
         is_active: false,
         wastage_rate: 1.0,
+        open_vial_discard_hours: None,
         deleted_datetime: None,
     }
 }
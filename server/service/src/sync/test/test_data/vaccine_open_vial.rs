@@ -0,0 +1,62 @@
+use chrono::NaiveDate;
+use repository::VaccineOpenVialRow;
+use serde_json::json;
+
+use super::{TestSyncIncomingRecord, TestSyncOutgoingRecord};
+
+const TABLE_NAME: &str = "vaccine_open_vial";
+
+const VACCINE_OPEN_VIAL1: (&str, &str) = (
+    "test_vaccine_open_vial",
+    r#"{
+        "id": "test_vaccine_open_vial",
+        "store_id": "store_b",
+        "stock_line_id": "item_a_line_a",
+        "item_link_id": "item_a",
+        "vaccine_course_id": "test_vaccine_course",
+        "opened_datetime": "2024-09-01T09:00:00",
+        "discard_after_datetime": "2024-09-01T15:00:00",
+        "total_doses": 10,
+        "doses_remaining": 4,
+        "discarded_datetime": "2024-09-01T15:30:00",
+        "wastage_invoice_id": null
+    }"#,
+);
+
+fn vaccine_open_vial1() -> VaccineOpenVialRow {
+    let datetime = |hour, minute| {
+        NaiveDate::from_ymd_opt(2024, 9, 1)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    };
+    VaccineOpenVialRow {
+        id: VACCINE_OPEN_VIAL1.0.to_string(),
+        store_id: "store_b".to_string(),
+        stock_line_id: "item_a_line_a".to_string(),
+        item_link_id: "item_a".to_string(),
+        vaccine_course_id: "test_vaccine_course".to_string(),
+        opened_datetime: datetime(9, 0),
+        discard_after_datetime: datetime(15, 0),
+        total_doses: 10,
+        doses_remaining: 4,
+        discarded_datetime: Some(datetime(15, 30)),
+        wastage_invoice_id: None,
+    }
+}
+
+pub(crate) fn test_pull_upsert_records() -> Vec<TestSyncIncomingRecord> {
+    vec![TestSyncIncomingRecord::new_pull_upsert(
+        TABLE_NAME,
+        VACCINE_OPEN_VIAL1,
+        vaccine_open_vial1(),
+    )]
+}
+
+pub(crate) fn test_v6_records() -> Vec<TestSyncOutgoingRecord> {
+    vec![TestSyncOutgoingRecord {
+        table_name: TABLE_NAME.to_string(),
+        record_id: VACCINE_OPEN_VIAL1.0.to_string(),
+        push_data: json!(vaccine_open_vial1()),
+    }]
+}
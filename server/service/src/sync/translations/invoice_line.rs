@@ -8,9 +8,9 @@ use crate::sync::{
 };
 use chrono::NaiveDate;
 use repository::{
-    ChangelogRow, ChangelogTableName, EqualFilter, InvoiceLine, InvoiceLineFilter,
-    InvoiceLineRepository, InvoiceLineRow, InvoiceLineRowDelete, InvoiceLineType,
-    InvoiceRowRepository, InvoiceType, ItemRowRepository, StockLineRowRepository,
+    ChangelogRow, ChangelogTableName, EqualFilter, InvoiceLine, InvoiceLineDiscrepancyType,
+    InvoiceLineFilter, InvoiceLineRepository, InvoiceLineRow, InvoiceLineRowDelete,
+    InvoiceLineType, InvoiceRowRepository, InvoiceType, ItemRowRepository, StockLineRowRepository,
     StorageConnection, SyncBufferRow,
 };
 use serde::{Deserialize, Serialize};
@@ -84,6 +84,18 @@ pub struct LegacyTransLineRow {
     pub foreign_currency_price_before_tax: Option<f64>,
     #[serde(rename = "om_item_variant_id")]
     pub item_variant_id: Option<String>,
+    #[serde(rename = "om_linked_invoice_line_id")]
+    #[serde(default)]
+    pub linked_invoice_line_id: Option<String>,
+    #[serde(rename = "om_discrepancy_type")]
+    #[serde(default)]
+    pub discrepancy_type: Option<InvoiceLineDiscrepancyType>,
+    #[serde(rename = "om_discrepancy_number_of_packs")]
+    #[serde(default)]
+    pub discrepancy_number_of_packs: f64,
+    #[serde(rename = "om_discrepancy_reason")]
+    #[serde(default)]
+    pub discrepancy_reason: Option<String>,
 }
 // Needs to be added to all_translators()
 #[deny(dead_code)]
@@ -140,6 +152,10 @@ impl SyncTranslation for InvoiceLineTranslation {
             inventory_adjustment_reason_id,
             foreign_currency_price_before_tax,
             item_variant_id,
+            linked_invoice_line_id,
+            discrepancy_type,
+            discrepancy_number_of_packs,
+            discrepancy_reason,
         } = serde_json::from_str::<LegacyTransLineRow>(&sync_record.data)?;
         let inventory_adjustment_reason_id =
             inventory_adjustment_reason_id.and_then(|inventory_adjustment_reason_id| {
@@ -273,6 +289,10 @@ impl SyncTranslation for InvoiceLineTranslation {
             return_reason_id: None, // TODO
             foreign_currency_price_before_tax,
             item_variant_id,
+            linked_invoice_line_id,
+            discrepancy_type,
+            discrepancy_number_of_packs,
+            discrepancy_reason,
         };
 
         Ok(PullTranslateResult::upsert(result))
@@ -325,6 +345,10 @@ impl SyncTranslation for InvoiceLineTranslation {
                     return_reason_id: _, // TODO
                     foreign_currency_price_before_tax,
                     item_variant_id,
+                    linked_invoice_line_id,
+                    discrepancy_type,
+                    discrepancy_number_of_packs,
+                    discrepancy_reason,
                 },
             item_row,
             ..
@@ -352,6 +376,10 @@ impl SyncTranslation for InvoiceLineTranslation {
             inventory_adjustment_reason_id,
             foreign_currency_price_before_tax,
             item_variant_id,
+            linked_invoice_line_id,
+            discrepancy_type,
+            discrepancy_number_of_packs,
+            discrepancy_reason,
         };
         Ok(PushTranslateResult::upsert(
             changelog,
@@ -1,4 +1,5 @@
 pub(crate) mod activity_log;
+pub(crate) mod allocation_rule;
 pub(crate) mod asset;
 pub(crate) mod asset_catalogue_item;
 pub(crate) mod asset_category;
@@ -20,8 +21,11 @@ pub(crate) mod indicator_attribute;
 pub(crate) mod indicator_value;
 pub(crate) mod invoice;
 pub(crate) mod invoice_line;
+pub(crate) mod invoice_payment;
 pub(crate) mod item;
+pub(crate) mod item_oms_fields;
 pub(crate) mod item_variant;
+pub(crate) mod lmis_code_mapping;
 pub(crate) mod location;
 pub(crate) mod location_movement;
 pub(crate) mod master_list;
@@ -29,6 +33,7 @@ pub(crate) mod master_list_line;
 pub(crate) mod master_list_name_join;
 pub(crate) mod name;
 pub(crate) mod name_oms_fields;
+pub(crate) mod name_price_category;
 pub(crate) mod name_property;
 pub(crate) mod name_store_join;
 pub(crate) mod name_tag;
@@ -36,16 +41,27 @@ pub(crate) mod name_tag_join;
 pub(crate) mod packaging_variant;
 pub(crate) mod period;
 pub(crate) mod period_schedule;
+pub(crate) mod pick_list;
+pub(crate) mod pick_list_line;
+pub(crate) mod pick_path_location;
+pub(crate) mod prescription_line_direction;
+pub(crate) mod price_list;
+pub(crate) mod price_list_line;
 pub(crate) mod program_indicator;
 pub(crate) mod program_requisition_settings;
 pub(crate) mod property;
 pub(crate) mod reason;
+pub(crate) mod recall;
+pub(crate) mod recall_batch;
+pub(crate) mod recall_stock_line;
 pub(crate) mod report;
 pub(crate) mod requisition;
 pub(crate) mod requisition_line;
 pub(crate) mod rnr_form;
 pub(crate) mod rnr_form_line;
 pub(crate) mod sensor;
+pub(crate) mod serial_number;
+pub(crate) mod serial_number_movement;
 pub(crate) mod special;
 pub(crate) mod stock_line;
 pub(crate) mod stocktake;
@@ -53,6 +69,7 @@ pub(crate) mod stocktake_line;
 pub(crate) mod store;
 pub(crate) mod store_preference;
 pub(crate) mod sync_file_reference;
+pub(crate) mod tax_rule;
 pub(crate) mod temperature_breach;
 pub(crate) mod temperature_log;
 pub(crate) mod unit;
@@ -63,6 +80,7 @@ pub(crate) mod vaccination;
 pub(crate) mod vaccine_course;
 pub(crate) mod vaccine_course_dose;
 pub(crate) mod vaccine_course_item;
+pub(crate) mod vaccine_open_vial;
 
 use repository::*;
 use thiserror::Error;
@@ -123,6 +141,7 @@ pub(crate) fn all_translators() -> SyncTranslators {
         temperature_log::boxed(),
         // Special translations
         name_oms_fields::boxed(),
+        item_oms_fields::boxed(),
         special::name_to_name_store_join::boxed(),
         // Merge
         special::name_merge::boxed(),
@@ -142,6 +161,7 @@ pub(crate) fn all_translators() -> SyncTranslators {
         // RnR Form
         rnr_form::boxed(),
         rnr_form_line::boxed(),
+        lmis_code_mapping::boxed(),
         // Vaccine course
         vaccine_course::boxed(),
         vaccine_course_dose::boxed(),
@@ -149,9 +169,29 @@ pub(crate) fn all_translators() -> SyncTranslators {
         demographic::boxed(),
         // Vaccination
         vaccination::boxed(),
+        vaccine_open_vial::boxed(),
         // Item Variant
         item_variant::boxed(),
         packaging_variant::boxed(),
+        // Recall
+        recall::boxed(),
+        recall_batch::boxed(),
+        recall_stock_line::boxed(),
+        serial_number::boxed(),
+        serial_number_movement::boxed(),
+        invoice_payment::boxed(),
+        // Pricing
+        price_list::boxed(),
+        price_list_line::boxed(),
+        tax_rule::boxed(),
+        name_price_category::boxed(),
+        // Prescription
+        prescription_line_direction::boxed(),
+        // Pick list
+        pick_list::boxed(),
+        pick_list_line::boxed(),
+        pick_path_location::boxed(),
+        allocation_rule::boxed(),
     ]
 }
 
@@ -70,6 +70,21 @@ pub struct LegacyStocktakeRow {
     #[serde(deserialize_with = "empty_str_as_option")]
     #[serde(default)]
     pub finalised_datetime: Option<NaiveDateTime>,
+
+    /// Legacy status only knows new and finalised, the blind stocktake statuses are synced
+    /// separately
+    #[serde(deserialize_with = "empty_str_as_option")]
+    #[serde(default)]
+    pub om_status: Option<StocktakeStatus>,
+    #[serde(rename = "om_is_blind")]
+    #[serde(default)]
+    pub is_blind: bool,
+    #[serde(rename = "om_variance_quantity_threshold")]
+    #[serde(default)]
+    pub variance_quantity_threshold: Option<f64>,
+    #[serde(rename = "om_variance_value_threshold")]
+    #[serde(default)]
+    pub variance_value_threshold: Option<f64>,
 }
 
 // Needs to be added to all_translators()
@@ -112,9 +127,10 @@ impl SyncTranslation for StocktakeTranslation {
             ),
         };
 
-        let status = match stocktake_status(&data.status) {
-            Some(status) => status,
-            None => {
+        let status = match (data.om_status, stocktake_status(&data.status)) {
+            (Some(om_status), _) => om_status,
+            (None, Some(status)) => status,
+            (None, None) => {
                 return Ok(PullTranslateResult::Ignored(format!(
                     "Unexpected stocktake status: {:?}",
                     data.status
@@ -136,6 +152,9 @@ impl SyncTranslation for StocktakeTranslation {
             inventory_reduction_id: data.inventory_reduction_id,
             stocktake_date: data.stocktake_date,
             is_locked: data.is_locked,
+            is_blind: data.is_blind,
+            variance_quantity_threshold: data.variance_quantity_threshold,
+            variance_value_threshold: data.variance_value_threshold,
         };
 
         Ok(PullTranslateResult::upsert(result))
@@ -160,6 +179,9 @@ impl SyncTranslation for StocktakeTranslation {
             stocktake_date,
             inventory_addition_id,
             inventory_reduction_id,
+            is_blind,
+            variance_quantity_threshold,
+            variance_value_threshold,
         } = StocktakeRowRepository::new(connection)
             .find_one_by_id(&changelog.record_id)?
             .ok_or(anyhow::Error::msg("Stocktake row not found"))?;
@@ -180,6 +202,10 @@ impl SyncTranslation for StocktakeTranslation {
             stock_take_time: created_datetime.time(),
             created_datetime: Some(created_datetime),
             finalised_datetime,
+            om_status: Some(status),
+            is_blind,
+            variance_quantity_threshold,
+            variance_value_threshold,
         };
 
         Ok(PushTranslateResult::upsert(
@@ -209,7 +235,10 @@ fn stocktake_status(status: &LegacyStocktakeStatus) -> Option<StocktakeStatus> {
 
 fn legacy_stocktake_status(status: &StocktakeStatus) -> LegacyStocktakeStatus {
     match status {
-        StocktakeStatus::New => LegacyStocktakeStatus::Sg,
+        StocktakeStatus::New
+        | StocktakeStatus::Recount
+        | StocktakeStatus::PendingApproval
+        | StocktakeStatus::Approved => LegacyStocktakeStatus::Sg,
         StocktakeStatus::Finalised => LegacyStocktakeStatus::Fn,
     }
 }
@@ -48,6 +48,9 @@ pub struct LegacyStocktakeLineRow {
     #[serde(rename = "optionID")]
     #[serde(deserialize_with = "empty_str_as_option_string")]
     pub inventory_adjustment_reason_id: Option<String>,
+    #[serde(rename = "om_requires_recount")]
+    #[serde(default)]
+    pub requires_recount: bool,
 }
 // Needs to be added to all_translators()
 #[deny(dead_code)]
@@ -98,6 +101,7 @@ impl SyncTranslation for StocktakeLineTranslation {
             sell_price,
             note,
             inventory_adjustment_reason_id,
+            requires_recount,
         } = serde_json::from_str::<LegacyStocktakeLineRow>(&sync_record.data)?;
 
         // TODO is this correct?
@@ -145,6 +149,7 @@ impl SyncTranslation for StocktakeLineTranslation {
             sell_price_per_pack: Some(sell_price),
             note,
             inventory_adjustment_reason_id,
+            requires_recount,
         };
 
         Ok(PullTranslateResult::upsert(result))
@@ -184,6 +189,7 @@ impl SyncTranslation for StocktakeLineTranslation {
                     sell_price_per_pack,
                     note,
                     inventory_adjustment_reason_id,
+                    requires_recount,
                 },
             item,
             stock_line,
@@ -209,6 +215,7 @@ impl SyncTranslation for StocktakeLineTranslation {
             sell_price: sell_price_per_pack.unwrap_or(0.0),
             note,
             inventory_adjustment_reason_id,
+            requires_recount,
         };
 
         Ok(PushTranslateResult::upsert(
@@ -1,11 +1,13 @@
 use std::sync::Arc;
 
 use actix_rt::task::JoinHandle;
+use chrono::Duration;
 use repository::{
-    mock::{MockData, MockDataInserts},
+    mock::{mock_store_a, MockData, MockDataInserts},
     test_db::setup_all_with_data,
-    StorageConnection, StorageConnectionManager,
+    StockLineRow, StorageConnection, StorageConnectionManager,
 };
+use util::{date_now_with_offset, inline_init};
 
 use crate::{
     processors::Processors,
@@ -46,11 +48,13 @@ pub(crate) async fn setup_all_with_data_and_service_provider(
             cors_origins: vec![],
             base_dir: None,
             machine_uid: None,
+            metrics_enabled: false,
         },
         database: db_settings,
         sync: None,
         logging: None,
         backup: None,
+        oidc: None,
     });
     let (sync_trigger, _) = SynchroniserDriver::init(file_sync_trigger);
     let (site_is_initialise_trigger, _) = SiteIsInitialisedCallback::init();
@@ -85,3 +89,22 @@ pub(crate) async fn setup_all_and_service_provider(
 ) -> ServiceTestContext {
     setup_all_with_data_and_service_provider(db_name, inserts, MockData::default()).await
 }
+
+/// Stock line in store a with 10 available packs of one unit, expiring in `expiry_days`
+pub(crate) fn stock_line_expiring_in(
+    id: &str,
+    item_id: &str,
+    location_id: Option<String>,
+    expiry_days: i64,
+) -> StockLineRow {
+    inline_init(|r: &mut StockLineRow| {
+        r.id = id.to_string();
+        r.store_id = mock_store_a().id;
+        r.item_link_id = item_id.to_string();
+        r.location_id = location_id;
+        r.pack_size = 1.0;
+        r.available_number_of_packs = 10.0;
+        r.total_number_of_packs = 10.0;
+        r.expiry_date = Some(date_now_with_offset(Duration::days(expiry_days)));
+    })
+}
@@ -9,6 +9,8 @@ use util::uuid::uuid;
 use bcrypt::{hash, verify, BcryptError, DEFAULT_COST};
 use log::{error, warn};
 
+use crate::login_security::{get_login_security_settings, PasswordPolicyViolation};
+
 pub struct CreateUserAccount {
     pub username: String,
     pub password: String,
@@ -20,6 +22,7 @@ pub type UserAccount = UserAccountRow;
 #[derive(Debug)]
 pub enum CreateUserAccountError {
     UserNameExist,
+    PasswordPolicyViolation(Vec<PasswordPolicyViolation>),
     PasswordHashError(BcryptError),
     DatabaseError(RepositoryError),
 }
@@ -30,6 +33,20 @@ impl From<RepositoryError> for CreateUserAccountError {
     }
 }
 
+#[derive(Debug)]
+pub enum UpdatePasswordError {
+    UserDoesNotExist,
+    PasswordPolicyViolation(Vec<PasswordPolicyViolation>),
+    PasswordHashError(BcryptError),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for UpdatePasswordError {
+    fn from(err: RepositoryError) -> Self {
+        UpdatePasswordError::DatabaseError(err)
+    }
+}
+
 #[derive(Debug)]
 pub enum VerifyPasswordError {
     UsernameDoesNotExist,
@@ -134,6 +151,13 @@ impl<'a> UserAccountService<'a> {
                     return Err(CreateUserAccountError::UserNameExist);
                 }
 
+                let violations = get_login_security_settings(con)?
+                    .password_policy
+                    .validate(&user.password);
+                if !violations.is_empty() {
+                    return Err(CreateUserAccountError::PasswordPolicyViolation(violations));
+                }
+
                 let hashed_password = UserAccountService::hash_password(&user.password)
                     .map_err(CreateUserAccountError::PasswordHashError)?;
 
@@ -157,6 +181,34 @@ impl<'a> UserAccountService<'a> {
             )
     }
 
+    /// Sets a new password for a user on this site, the password must comply with the local
+    /// password policy
+    pub fn update_password(
+        &self,
+        user_id: &str,
+        password: &str,
+    ) -> Result<UserAccount, UpdatePasswordError> {
+        let repo = UserAccountRowRepository::new(self.connection);
+        let user = repo
+            .find_one_by_id(user_id)?
+            .ok_or(UpdatePasswordError::UserDoesNotExist)?;
+
+        let violations = get_login_security_settings(self.connection)?
+            .password_policy
+            .validate(password);
+        if !violations.is_empty() {
+            return Err(UpdatePasswordError::PasswordPolicyViolation(violations));
+        }
+
+        let row = UserAccountRow {
+            hashed_password: UserAccountService::hash_password(password)
+                .map_err(UpdatePasswordError::PasswordHashError)?,
+            ..user
+        };
+        repo.upsert_one(&row)?;
+        Ok(row)
+    }
+
     pub fn find_user_active_on_this_site(
         &self,
         user_id: &str,
@@ -195,7 +247,7 @@ impl<'a> UserAccountService<'a> {
             return Err(VerifyPasswordError::EmptyHashedPassword);
         }
 
-        // verify password  
+        // verify password
         let valid = verify(password, &user.hashed_password).map_err(|err| {
             error!("verify_password: {}", err);
             VerifyPasswordError::InvalidCredentialsBackend(err)
@@ -211,7 +263,10 @@ impl<'a> UserAccountService<'a> {
 #[cfg(test)]
 mod user_account_test {
     use repository::{
-        mock::{mock_user_account_a, mock_user_account_b, mock_user_empty_hashed_password, MockDataInserts},
+        mock::{
+            mock_user_account_a, mock_user_account_b, mock_user_empty_hashed_password,
+            MockDataInserts,
+        },
         test_db::{self, setup_all},
         PermissionType,
     };
@@ -350,8 +405,7 @@ mod user_account_test {
     async fn test_missing_hashed_password() {
         let (_, _, connection_manager, _) = setup_all(
             "test_missing_hashed_password",
-            MockDataInserts::none()
-                .user_accounts()
+            MockDataInserts::none().user_accounts(),
         )
         .await;
         let service_provider = ServiceProvider::new(connection_manager, "app_data");
@@ -359,8 +413,11 @@ mod user_account_test {
 
         let user_service = UserAccountService::new(&context.connection);
 
-        let result = user_service.verify_password(&mock_user_empty_hashed_password().username, "password");
-        assert!(matches!(result, Err(VerifyPasswordError::EmptyHashedPassword)));
+        let result =
+            user_service.verify_password(&mock_user_empty_hashed_password().username, "password");
+        assert!(matches!(
+            result,
+            Err(VerifyPasswordError::EmptyHashedPassword)
+        ));
     }
-
 }
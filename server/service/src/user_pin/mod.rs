@@ -0,0 +1,384 @@
+use bcrypt::{hash, verify, BcryptError, DEFAULT_COST};
+use chrono::{Duration, NaiveDateTime, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use repository::{
+    RepositoryError, StorageConnection, UserAccountRow, UserAccountRowRepository, UserPinRow,
+    UserPinRowRepository,
+};
+use util::{hash::sha256, uuid::uuid};
+
+use crate::{
+    login_security::{get_login_security_settings, LoginSecuritySettings},
+    service_provider::ServiceContext,
+    user_account::{UserAccountService, VerifyPasswordError},
+};
+
+const MIN_PIN_LENGTH: usize = 4;
+const MAX_PIN_LENGTH: usize = 8;
+const DEVICE_TOKEN_LENGTH: usize = 32;
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SetUserPin {
+    /// Token issued to the device when a PIN was set on it before, a new token is issued when
+    /// it's None or no longer known (e.g. all PINs of the device have expired)
+    pub device_token: Option<String>,
+    pub pin: String,
+    /// The user's password, a PIN can only be set after a full password authentication
+    pub password: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetUserPinResult {
+    pub user_pin: UserPinRow,
+    /// Needs to be kept by the device, PINs can only be used with the token of their device
+    pub device_token: String,
+}
+
+#[derive(Debug)]
+pub enum SetUserPinError {
+    UserDoesNotExist,
+    InvalidPassword,
+    /// PIN must be 4 to 8 digits
+    InvalidPin,
+    PinHashError(BcryptError),
+    DatabaseError(RepositoryError),
+}
+
+#[derive(Debug)]
+pub enum VerifyUserPinError {
+    /// Unknown user or wrong PIN
+    InvalidCredentials,
+    /// No PIN was set for the user on this device or it has expired or has been removed after too
+    /// many failed attempts
+    PinNotAvailable,
+    PinHashError(BcryptError),
+    DatabaseError(RepositoryError),
+}
+
+pub trait UserPinServiceTrait: Sync + Send {
+    /// Sets the PIN of the ctx user for the given device, replacing any previous PIN
+    fn set_user_pin(
+        &self,
+        ctx: &ServiceContext,
+        input: SetUserPin,
+    ) -> Result<SetUserPinResult, SetUserPinError> {
+        set_user_pin(ctx, input)
+    }
+
+    fn remove_user_pin(
+        &self,
+        ctx: &ServiceContext,
+        device_token: &str,
+    ) -> Result<(), RepositoryError> {
+        let repo = UserPinRowRepository::new(&ctx.connection);
+        if let Some(pin) = repo.find_one_by_user_and_device(&ctx.user_id, &sha256(device_token))? {
+            repo.delete(&pin.id)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct UserPinService {}
+impl UserPinServiceTrait for UserPinService {}
+
+fn set_user_pin(
+    ctx: &ServiceContext,
+    input: SetUserPin,
+) -> Result<SetUserPinResult, SetUserPinError> {
+    let user = UserAccountRowRepository::new(&ctx.connection)
+        .find_one_by_id(&ctx.user_id)?
+        .ok_or(SetUserPinError::UserDoesNotExist)?;
+
+    match UserAccountService::new(&ctx.connection).verify_password(&user.username, &input.password)
+    {
+        Ok(_) => {}
+        Err(VerifyPasswordError::DatabaseError(error)) => return Err(error.into()),
+        Err(_) => return Err(SetUserPinError::InvalidPassword),
+    }
+    if !is_valid_pin(&input.pin) {
+        return Err(SetUserPinError::InvalidPin);
+    }
+
+    let settings = get_login_security_settings(&ctx.connection)?;
+    let repo = UserPinRowRepository::new(&ctx.connection);
+    // Only tokens issued by this server are accepted, so a device can't be impersonated by
+    // choosing its identifier
+    let known_device_token = match input.device_token {
+        Some(device_token) => repo
+            .find_one_by_device(&sha256(&device_token))?
+            .map(|_| device_token),
+        None => None,
+    };
+    let device_token = known_device_token.unwrap_or_else(generate_device_token);
+    let device_token_hash = sha256(&device_token);
+
+    let now = Utc::now().naive_utc();
+    let row = UserPinRow {
+        id: repo
+            .find_one_by_user_and_device(&user.id, &device_token_hash)?
+            .map(|existing| existing.id)
+            .unwrap_or_else(uuid),
+        user_id: user.id,
+        device_token_hash,
+        pin_hash: hash(&input.pin, DEFAULT_COST).map_err(SetUserPinError::PinHashError)?,
+        created_datetime: now,
+        expiry_datetime: now + Duration::seconds(settings.pin_validity_seconds as i64),
+        failed_attempts: 0,
+    };
+    repo.upsert_one(&row)?;
+
+    Ok(SetUserPinResult {
+        user_pin: row,
+        device_token,
+    })
+}
+
+fn generate_device_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(DEVICE_TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+fn is_valid_pin(pin: &str) -> bool {
+    (MIN_PIN_LENGTH..=MAX_PIN_LENGTH).contains(&pin.len())
+        && pin.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Finds the user and checks the PIN they set on the device.
+/// A wrong PIN counts as failed attempt, the PIN is removed once the configured maximum is reached.
+pub fn verify_user_pin(
+    connection: &StorageConnection,
+    settings: &LoginSecuritySettings,
+    username: &str,
+    device_token: &str,
+    pin: &str,
+    now: NaiveDateTime,
+) -> Result<UserAccountRow, VerifyUserPinError> {
+    let user = UserAccountRowRepository::new(connection)
+        .find_one_by_user_name(username)?
+        .ok_or(VerifyUserPinError::InvalidCredentials)?;
+
+    let repo = UserPinRowRepository::new(connection);
+    let user_pin = repo
+        .find_one_by_user_and_device(&user.id, &sha256(device_token))?
+        .ok_or(VerifyUserPinError::PinNotAvailable)?;
+    if user_pin.expiry_datetime <= now {
+        repo.delete(&user_pin.id)?;
+        return Err(VerifyUserPinError::PinNotAvailable);
+    }
+
+    let valid = verify(pin, &user_pin.pin_hash).map_err(VerifyUserPinError::PinHashError)?;
+    if !valid {
+        let failed_attempts = user_pin.failed_attempts + 1;
+        if failed_attempts as u32 >= settings.max_failed_pin_attempts {
+            repo.delete(&user_pin.id)?;
+        } else {
+            repo.upsert_one(&UserPinRow {
+                failed_attempts,
+                ..user_pin
+            })?;
+        }
+        return Err(VerifyUserPinError::InvalidCredentials);
+    }
+
+    if user_pin.failed_attempts > 0 {
+        repo.upsert_one(&UserPinRow {
+            failed_attempts: 0,
+            ..user_pin
+        })?;
+    }
+
+    Ok(user)
+}
+
+impl From<RepositoryError> for SetUserPinError {
+    fn from(error: RepositoryError) -> Self {
+        SetUserPinError::DatabaseError(error)
+    }
+}
+
+impl From<RepositoryError> for VerifyUserPinError {
+    fn from(error: RepositoryError) -> Self {
+        VerifyUserPinError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, RwLock};
+
+    use repository::{
+        activity_log::{ActivityLogFilter, ActivityLogRepository},
+        mock::{mock_store_a, mock_user_account_b, MockDataInserts},
+        test_db::setup_all,
+        ActivityLogType, KeyType, KeyValueStoreRepository, UserPinRowRepository,
+    };
+    use util::{assert_matches, hash::sha256};
+
+    use crate::{
+        auth_data::AuthData,
+        login::{LoginError, LoginFailure, LoginService, PinLoginInput},
+        service_provider::ServiceProvider,
+        token_bucket::TokenBucket,
+        user_account::UserAccountService,
+        user_pin::{SetUserPin, SetUserPinError},
+    };
+
+    #[actix_rt::test]
+    async fn user_pin_switching() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "user_pin_switching",
+            MockDataInserts::none()
+                .names()
+                .stores()
+                .user_accounts()
+                .user_store_joins(),
+        )
+        .await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context("".to_string(), mock_user_account_b().id)
+            .unwrap();
+        let service = &service_provider.user_pin_service;
+        let auth_data = AuthData {
+            auth_token_secret: "secret".to_string(),
+            token_bucket: Arc::new(RwLock::new(TokenBucket::new())),
+            no_ssl: true,
+            debug_no_access_control: false,
+        };
+        KeyValueStoreRepository::new(&connection)
+            .set_i32(KeyType::SettingsSyncSiteId, Some(mock_store_a().site_id))
+            .unwrap();
+        UserAccountService::new(&connection)
+            .update_password(&mock_user_account_b().id, "password_b")
+            .unwrap();
+
+        let input = SetUserPin {
+            device_token: None,
+            pin: "1234".to_string(),
+            password: "password_b".to_string(),
+        };
+
+        // Errors
+        assert_matches!(
+            service.set_user_pin(
+                &context,
+                SetUserPin {
+                    password: "wrong".to_string(),
+                    ..input.clone()
+                }
+            ),
+            Err(SetUserPinError::InvalidPassword)
+        );
+        assert_matches!(
+            service.set_user_pin(
+                &context,
+                SetUserPin {
+                    pin: "12a4".to_string(),
+                    ..input.clone()
+                }
+            ),
+            Err(SetUserPinError::InvalidPin)
+        );
+
+        // Success, only the hashes are stored
+        let result = service.set_user_pin(&context, input.clone()).unwrap();
+        assert_ne!(result.user_pin.pin_hash, "1234");
+        assert_ne!(result.user_pin.device_token_hash, result.device_token);
+        let device_token = result.device_token;
+
+        // Device token is kept when setting the PIN again, a token the server didn't issue is
+        // replaced
+        let input = SetUserPin {
+            device_token: Some(device_token.clone()),
+            ..input
+        };
+        let result = service.set_user_pin(&context, input.clone()).unwrap();
+        assert_eq!(result.device_token, device_token);
+        let result = service
+            .set_user_pin(
+                &context,
+                SetUserPin {
+                    device_token: Some("tablet_2".to_string()),
+                    ..input.clone()
+                },
+            )
+            .unwrap();
+        assert_ne!(result.device_token, "tablet_2");
+        let other_device_token = result.device_token;
+        service
+            .remove_user_pin(&context, &other_device_token)
+            .unwrap();
+
+        let pin_login = |pin: &str, device_token: &str| {
+            LoginService::pin_login(
+                &service_provider,
+                &auth_data,
+                PinLoginInput {
+                    username: mock_user_account_b().username,
+                    device_token: device_token.to_string(),
+                    pin: pin.to_string(),
+                    client_ip: None,
+                },
+                0,
+            )
+        };
+
+        // A full login is required when the password isn't known, e.g. after a restart
+        assert_matches!(
+            pin_login("1234", &device_token).await,
+            Err(LoginError::LoginFailure(LoginFailure::PinNotAvailable))
+        );
+        auth_data.token_bucket.write().unwrap().put(
+            &mock_user_account_b().id,
+            "password_b",
+            "full_login_token",
+            usize::MAX,
+        );
+
+        // Switching with the PIN logs in the PIN owner
+        pin_login("1234", &device_token).await.unwrap();
+        let logged_in_users: Vec<Option<String>> = ActivityLogRepository::new(&connection)
+            .query_by_filter(ActivityLogFilter::new())
+            .unwrap()
+            .into_iter()
+            .filter(|log| log.activity_log_row.r#type == ActivityLogType::UserLoggedIn)
+            .map(|log| log.activity_log_row.user_id)
+            .collect();
+        assert_eq!(logged_in_users, vec![Some(mock_user_account_b().id)]);
+
+        // PIN only works on the device it was set on
+        assert_matches!(
+            pin_login("1234", &other_device_token).await,
+            Err(LoginError::LoginFailure(LoginFailure::PinNotAvailable))
+        );
+
+        // PIN is removed after too many wrong entries
+        for _ in 0..3 {
+            assert_matches!(
+                pin_login("4321", &device_token).await,
+                Err(LoginError::LoginFailure(LoginFailure::InvalidCredentials))
+            );
+        }
+        assert_matches!(
+            pin_login("1234", &device_token).await,
+            Err(LoginError::LoginFailure(LoginFailure::PinNotAvailable))
+        );
+        assert_eq!(
+            UserPinRowRepository::new(&connection)
+                .find_one_by_user_and_device(&mock_user_account_b().id, &sha256(&device_token))
+                .unwrap(),
+            None
+        );
+
+        // Removing a PIN, the device got a new token since none of its PINs were left
+        let device_token = service.set_user_pin(&context, input).unwrap().device_token;
+        service.remove_user_pin(&context, &device_token).unwrap();
+        assert_matches!(
+            pin_login("1234", &device_token).await,
+            Err(LoginError::LoginFailure(LoginFailure::PinNotAvailable))
+        );
+    }
+}
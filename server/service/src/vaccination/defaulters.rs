@@ -0,0 +1,400 @@
+use std::collections::HashMap;
+
+use chrono::{Local, NaiveDate};
+use repository::{
+    vaccine_course::vaccine_course_row::{VaccineCourseRow, VaccineCourseRowRepository},
+    EqualFilter, ProgramEnrolment, ProgramEnrolmentFilter, ProgramEnrolmentRepository,
+    ProgramEventFilter, ProgramEventRepository, ProgramEventRow, RepositoryError,
+    VaccinationCardRepository, VaccinationCardRow,
+};
+use util::escape_csv_value;
+
+use crate::{
+    service_provider::ServiceContext,
+    static_files::{StaticFileCategory, StaticFileService},
+};
+
+use super::{
+    get_vaccination_card::get_suggested_date, tracing_attempt::VACCINATION_TRACING_EVENT_TYPE,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct VaccinationDefaulterFilter {
+    pub program_id: Option<String>,
+    pub vaccine_course_id: Option<String>,
+    /// Only include patients whose next dose is overdue by more than this many days
+    pub min_days_overdue: i64,
+    /// Date to calculate overdue days from, defaults to today
+    pub reference_date: Option<NaiveDate>,
+}
+
+#[derive(Clone, Debug)]
+pub struct VaccinationDefaulter {
+    /// Enrolment including the patient row, which holds the patient's contact details
+    pub enrolment: ProgramEnrolment,
+    /// The next dose that is due for the patient in the vaccine course
+    pub dose: VaccinationCardRow,
+    pub due_date: NaiveDate,
+    pub days_overdue: i64,
+    pub last_tracing_attempt: Option<ProgramEventRow>,
+}
+
+#[derive(Clone, Debug)]
+pub struct VaccineCourseDefaulters {
+    pub vaccine_course: VaccineCourseRow,
+    pub defaulters: Vec<VaccinationDefaulter>,
+}
+
+/// Returns patients whose next dose is overdue by more than `min_days_overdue`, grouped by
+/// vaccine course. Due dates are calculated the same way as on the vaccination card.
+/// Only patients enrolled in programs of the `allowed_ctx` contexts are returned.
+pub fn get_vaccination_defaulters(
+    ctx: &ServiceContext,
+    filter: VaccinationDefaulterFilter,
+    allowed_ctx: Vec<String>,
+) -> Result<Vec<VaccineCourseDefaulters>, RepositoryError> {
+    let reference_date = filter
+        .reference_date
+        .unwrap_or_else(|| Local::now().date_naive());
+
+    let mut enrolment_filter = ProgramEnrolmentFilter::new()
+        .is_immunisation_program(true)
+        .context_id(EqualFilter::default().restrict_results(&allowed_ctx));
+    if let Some(program_id) = &filter.program_id {
+        enrolment_filter = enrolment_filter.program_id(EqualFilter::equal_to(program_id));
+    }
+    let enrolments = ProgramEnrolmentRepository::new(&ctx.connection)
+        .query_by_filter(enrolment_filter)?
+        .into_iter()
+        .map(|enrolment| (enrolment.row.id.clone(), enrolment))
+        .collect::<HashMap<_, _>>();
+
+    if enrolments.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let card_rows = VaccinationCardRepository::new(&ctx.connection)
+        .query_by_enrolment_ids(enrolments.keys().cloned().collect())?;
+
+    // Doses per enrolment and course, in the same (min age) order as the vaccination card
+    let mut rows_by_enrolment_course: HashMap<(String, String), Vec<VaccinationCardRow>> =
+        HashMap::new();
+    for row in card_rows {
+        if let Some(vaccine_course_id) = &filter.vaccine_course_id {
+            if &row.vaccine_course_id != vaccine_course_id {
+                continue;
+            }
+        }
+        rows_by_enrolment_course
+            .entry((
+                row.program_enrolment_id.clone(),
+                row.vaccine_course_id.clone(),
+            ))
+            .or_default()
+            .push(row);
+    }
+
+    let last_tracing_attempts = get_last_tracing_attempts(ctx, enrolments.values())?;
+
+    let mut defaulters_by_course: HashMap<String, Vec<VaccinationDefaulter>> = HashMap::new();
+    for ((enrolment_id, vaccine_course_id), course_rows) in rows_by_enrolment_course {
+        let Some(enrolment) = enrolments.get(&enrolment_id) else {
+            continue;
+        };
+        // Next dose is the first one in the course that hasn't been given
+        let Some(next_dose) = course_rows.iter().find(|row| row.given != Some(true)) else {
+            continue;
+        };
+        let Some(due_date) = get_suggested_date(
+            next_dose,
+            enrolment.patient_row.date_of_birth,
+            course_rows.clone(),
+        ) else {
+            continue;
+        };
+
+        let days_overdue = (reference_date - due_date).num_days();
+        if days_overdue <= filter.min_days_overdue {
+            continue;
+        }
+
+        defaulters_by_course
+            .entry(vaccine_course_id)
+            .or_default()
+            .push(VaccinationDefaulter {
+                enrolment: enrolment.clone(),
+                dose: next_dose.clone(),
+                due_date,
+                days_overdue,
+                last_tracing_attempt: last_tracing_attempts
+                    .get(&enrolment.row.document_name)
+                    .cloned(),
+            });
+    }
+
+    let course_repo = VaccineCourseRowRepository::new(&ctx.connection);
+    let mut result = Vec::new();
+    for (vaccine_course_id, mut defaulters) in defaulters_by_course {
+        let Some(vaccine_course) = course_repo.find_one_by_id(&vaccine_course_id)? else {
+            continue;
+        };
+        // Most overdue first
+        defaulters.sort_by_key(|defaulter| std::cmp::Reverse(defaulter.days_overdue));
+        result.push(VaccineCourseDefaulters {
+            vaccine_course,
+            defaulters,
+        });
+    }
+    result.sort_by(|a, b| a.vaccine_course.name.cmp(&b.vaccine_course.name));
+
+    Ok(result)
+}
+
+/// Latest tracing attempt event per enrolment document name
+fn get_last_tracing_attempts<'a>(
+    ctx: &ServiceContext,
+    enrolments: impl Iterator<Item = &'a ProgramEnrolment>,
+) -> Result<HashMap<String, ProgramEventRow>, RepositoryError> {
+    let patient_ids = enrolments
+        .map(|enrolment| enrolment.patient_row.id.clone())
+        .collect();
+
+    let events = ProgramEventRepository::new(&ctx.connection).query_by_filter(
+        ProgramEventFilter::new()
+            .patient_id(EqualFilter::equal_any(patient_ids))
+            .r#type(EqualFilter::equal_to(VACCINATION_TRACING_EVENT_TYPE)),
+    )?;
+
+    let mut result: HashMap<String, ProgramEventRow> = HashMap::new();
+    for event in events {
+        let row = event.program_event_row;
+        let Some(document_name) = row.document_name.clone() else {
+            continue;
+        };
+        match result.get(&document_name) {
+            Some(existing) if existing.datetime >= row.datetime => {}
+            _ => {
+                result.insert(document_name, row);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+const CSV_HEADERS: [&str; 12] = [
+    "Vaccine course",
+    "Dose",
+    "Due date",
+    "Days overdue",
+    "Patient code",
+    "First name",
+    "Last name",
+    "Date of birth",
+    "Phone",
+    "Email",
+    "Address",
+    "Last tracing attempt",
+];
+
+pub fn defaulters_to_csv(courses: &[VaccineCourseDefaulters]) -> String {
+    let mut lines = vec![CSV_HEADERS.join(",")];
+
+    for course in courses {
+        for defaulter in &course.defaulters {
+            let patient = &defaulter.enrolment.patient_row;
+            let address = vec![patient.address1.clone(), patient.address2.clone()]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(", ");
+            let last_tracing_attempt = defaulter
+                .last_tracing_attempt
+                .as_ref()
+                .map(|event| {
+                    format!(
+                        "{} {}",
+                        event.datetime.format("%Y-%m-%d"),
+                        event.data.clone().unwrap_or_default()
+                    )
+                })
+                .unwrap_or_default();
+
+            let values = [
+                course.vaccine_course.name.clone(),
+                defaulter.dose.label.clone(),
+                defaulter.due_date.format("%Y-%m-%d").to_string(),
+                defaulter.days_overdue.to_string(),
+                patient.code.clone(),
+                patient.first_name.clone().unwrap_or_default(),
+                patient.last_name.clone().unwrap_or_default(),
+                patient
+                    .date_of_birth
+                    .map(|dob| dob.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default(),
+                patient.phone.clone().unwrap_or_default(),
+                patient.email.clone().unwrap_or_default(),
+                address,
+                last_tracing_attempt,
+            ];
+
+            lines.push(
+                values
+                    .iter()
+                    .map(|value| escape_csv_value(value))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+        }
+    }
+
+    lines.join("\n")
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ExportVaccinationDefaultersError {
+    FileError(String),
+    DatabaseError(RepositoryError),
+}
+
+impl From<RepositoryError> for ExportVaccinationDefaultersError {
+    fn from(error: RepositoryError) -> Self {
+        ExportVaccinationDefaultersError::DatabaseError(error)
+    }
+}
+
+/// Generates a CSV file of the defaulter list and returns the static file id
+pub fn export_vaccination_defaulters(
+    ctx: &ServiceContext,
+    base_dir: &Option<String>,
+    filter: VaccinationDefaulterFilter,
+    allowed_ctx: Vec<String>,
+) -> Result<String, ExportVaccinationDefaultersError> {
+    let courses = get_vaccination_defaulters(ctx, filter, allowed_ctx)?;
+    let csv = defaulters_to_csv(&courses);
+
+    let file_service = StaticFileService::new(base_dir)
+        .map_err(|err| ExportVaccinationDefaultersError::FileError(format!("{}", err)))?;
+    let file = file_service
+        .store_file(
+            &format!(
+                "{}_vaccination_defaulters.csv",
+                Local::now().format("%Y%m%d_%H%M%S")
+            ),
+            StaticFileCategory::Temporary,
+            csv.as_bytes(),
+        )
+        .map_err(|err| ExportVaccinationDefaultersError::FileError(format!("{}", err)))?;
+
+    Ok(file.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{
+            context_immunisation_program, mock_immunisation_program_enrolment_a, mock_patient,
+            mock_vaccine_course_a, mock_vaccine_course_a_dose_b, MockDataInserts,
+        },
+        NameRow, NameRowRepository,
+    };
+
+    use crate::test_helpers::{setup_all_and_service_provider, ServiceTestContext};
+
+    use super::{defaulters_to_csv, get_vaccination_defaulters, VaccinationDefaulterFilter};
+
+    #[actix_rt::test]
+    async fn test_get_vaccination_defaulters() {
+        let ServiceTestContext {
+            connection,
+            service_context,
+            ..
+        } = setup_all_and_service_provider(
+            "test_get_vaccination_defaulters",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        NameRowRepository::new(&connection)
+            .upsert_one(&NameRow {
+                date_of_birth: NaiveDate::from_ymd_opt(2024, 1, 1),
+                phone: Some("0800 123 456".to_string()),
+                ..mock_patient()
+            })
+            .unwrap();
+
+        // Dose A was given (without a date), so dose B is due from 1 month of age
+        let filter = VaccinationDefaulterFilter {
+            min_days_overdue: 10,
+            reference_date: NaiveDate::from_ymd_opt(2024, 2, 5),
+            ..Default::default()
+        };
+
+        let allowed_ctx = vec![context_immunisation_program().id];
+
+        // Only 5 days overdue
+        let result =
+            get_vaccination_defaulters(&service_context, filter.clone(), allowed_ctx.clone())
+                .unwrap();
+        assert!(result.is_empty());
+
+        let result = get_vaccination_defaulters(
+            &service_context,
+            VaccinationDefaulterFilter {
+                reference_date: NaiveDate::from_ymd_opt(2024, 3, 1),
+                ..filter.clone()
+            },
+            allowed_ctx.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].vaccine_course.id, mock_vaccine_course_a().id);
+        let defaulter = &result[0].defaulters[0];
+        assert_eq!(
+            defaulter.enrolment.row.id,
+            mock_immunisation_program_enrolment_a().id
+        );
+        assert_eq!(
+            defaulter.dose.vaccine_course_dose_id,
+            mock_vaccine_course_a_dose_b().id
+        );
+        assert_eq!(
+            defaulter.due_date,
+            NaiveDate::from_ymd_opt(2024, 1, 31).unwrap()
+        );
+        assert_eq!(defaulter.days_overdue, 30);
+
+        let csv = defaulters_to_csv(&result);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].starts_with("Vaccine Course A,Vaccine Course A Dose B,2024-01-31,30,"));
+        assert!(lines[1].contains("0800 123 456"));
+
+        // Filtered by another course
+        let result = get_vaccination_defaulters(
+            &service_context,
+            VaccinationDefaulterFilter {
+                vaccine_course_id: Some("other_course".to_string()),
+                reference_date: NaiveDate::from_ymd_opt(2024, 3, 1),
+                ..filter.clone()
+            },
+            allowed_ctx,
+        )
+        .unwrap();
+        assert!(result.is_empty());
+
+        // User without access to the immunisation program context
+        let result = get_vaccination_defaulters(
+            &service_context,
+            VaccinationDefaulterFilter {
+                reference_date: NaiveDate::from_ymd_opt(2024, 3, 1),
+                ..filter
+            },
+            vec!["other_context".to_string()],
+        )
+        .unwrap();
+        assert!(result.is_empty());
+    }
+}
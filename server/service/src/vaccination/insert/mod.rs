@@ -16,7 +16,11 @@ mod validate;
 use generate::{generate, GenerateInput, GenerateResult};
 use validate::validate;
 
-use super::{generate::CreatePrescription, query::get_vaccination};
+use super::{
+    generate::CreatePrescription,
+    open_vial::{use_dose_from_open_vial, DiscardOpenVialError},
+    query::get_vaccination,
+};
 
 #[derive(PartialEq, Debug)]
 pub enum InsertVaccinationError {
@@ -83,12 +87,20 @@ pub fn insert_vaccination(
                 finalise_prescription,
             }) = create_prescription
             {
+                let stock_line_id = insert_stock_out_line_input.stock_line_id.clone();
                 // Create prescription (in NEW status)
                 insert_prescription(ctx, create_prescription)?;
                 // Add the prescription line
                 insert_stock_out_line(ctx, insert_stock_out_line_input)?;
                 // Finalise the prescription - also link clinician
                 update_prescription(ctx, finalise_prescription)?;
+                // Take the dose from an open vial (opening a new one if required)
+                use_dose_from_open_vial(
+                    ctx,
+                    store_id,
+                    &stock_line_id,
+                    &vaccination.vaccine_course_dose_id,
+                )?;
             }
 
             activity_log_entry(
@@ -105,6 +117,20 @@ pub fn insert_vaccination(
     Ok(vaccination)
 }
 
+impl From<DiscardOpenVialError> for InsertVaccinationError {
+    fn from(error: DiscardOpenVialError) -> Self {
+        match error {
+            DiscardOpenVialError::DatabaseError(error) => {
+                InsertVaccinationError::DatabaseError(error)
+            }
+            error => InsertVaccinationError::InternalError(format!(
+                "Could not discard expired open vial: {:?}",
+                error
+            )),
+        }
+    }
+}
+
 impl From<RepositoryError> for InsertVaccinationError {
     fn from(error: RepositoryError) -> Self {
         InsertVaccinationError::DatabaseError(error)
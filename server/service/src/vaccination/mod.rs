@@ -1,12 +1,20 @@
+use defaulters::{
+    ExportVaccinationDefaultersError, VaccinationDefaulterFilter, VaccineCourseDefaulters,
+};
 use get_vaccination_card::VaccinationCard;
-use repository::{RepositoryError, Vaccination};
+use open_vial::{DiscardOpenVial, DiscardOpenVialError, VaccineWastage};
+use repository::{ProgramEnrolment, RepositoryError, Vaccination, VaccineOpenVialRow};
+use tracing_attempt::{LogTracingAttempt, LogTracingAttemptError};
 
 use crate::service_provider::ServiceContext;
 
+pub mod defaulters;
 mod generate;
 pub mod get_vaccination_card;
 pub mod insert;
+pub mod open_vial;
 pub mod query;
+pub mod tracing_attempt;
 pub mod update;
 mod validate;
 
@@ -27,6 +35,58 @@ pub trait VaccinationServiceTrait: Sync + Send {
         get_vaccination_card::get_vaccination_card(ctx, program_enrolment_id)
     }
 
+    fn get_vaccination_defaulters(
+        &self,
+        ctx: &ServiceContext,
+        filter: VaccinationDefaulterFilter,
+        allowed_ctx: Vec<String>,
+    ) -> Result<Vec<VaccineCourseDefaulters>, RepositoryError> {
+        defaulters::get_vaccination_defaulters(ctx, filter, allowed_ctx)
+    }
+
+    fn export_vaccination_defaulters(
+        &self,
+        ctx: &ServiceContext,
+        base_dir: &Option<String>,
+        filter: VaccinationDefaulterFilter,
+        allowed_ctx: Vec<String>,
+    ) -> Result<String, ExportVaccinationDefaultersError> {
+        defaulters::export_vaccination_defaulters(ctx, base_dir, filter, allowed_ctx)
+    }
+
+    fn log_tracing_attempt(
+        &self,
+        ctx: &ServiceContext,
+        input: LogTracingAttempt,
+        allowed_ctx: Vec<String>,
+    ) -> Result<ProgramEnrolment, LogTracingAttemptError> {
+        tracing_attempt::log_tracing_attempt(ctx, input, allowed_ctx)
+    }
+
+    fn get_open_vials(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+    ) -> Result<Vec<VaccineOpenVialRow>, RepositoryError> {
+        open_vial::get_open_vials(ctx, store_id)
+    }
+
+    fn discard_open_vial(
+        &self,
+        ctx: &ServiceContext,
+        input: DiscardOpenVial,
+    ) -> Result<VaccineOpenVialRow, DiscardOpenVialError> {
+        open_vial::discard_open_vial(ctx, input)
+    }
+
+    fn get_vaccine_wastage(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+    ) -> Result<Vec<VaccineWastage>, RepositoryError> {
+        open_vial::get_vaccine_wastage(ctx, store_id)
+    }
+
     fn insert_vaccination(
         &self,
         ctx: &ServiceContext,
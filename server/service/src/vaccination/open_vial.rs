@@ -0,0 +1,545 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use repository::{
+    vaccine_course::{
+        vaccine_course_dose_row::VaccineCourseDoseRowRepository,
+        vaccine_course_row::VaccineCourseRowRepository,
+    },
+    EqualFilter, Item, ItemFilter, ItemLinkRowRepository, ItemRepository, RepositoryError,
+    StockLineFilter, StockLineRepository, StockLineRowRepository, StorageConnection,
+    VaccineOpenVialFilter, VaccineOpenVialRepository, VaccineOpenVialRow,
+    VaccineOpenVialRowRepository,
+};
+use util::uuid::uuid;
+
+use crate::{
+    invoice::inventory_adjustment::{
+        insert_inventory_adjustment, AdjustmentType, InsertInventoryAdjustment,
+        InsertInventoryAdjustmentError,
+    },
+    service_provider::ServiceContext,
+    stocktake_line::validate::check_active_adjustment_reasons,
+};
+
+/// Used when the vaccine course doesn't define an open vial policy, i.e. discard at the end of
+/// the session
+pub const DEFAULT_OPEN_VIAL_DISCARD_HOURS: i64 = 6;
+
+/// Takes a dose for a vaccination from an open vial of the stock line, opening a new vial if none
+/// can be used under the open vial policy. Open vials past the policy are discarded, their
+/// remaining doses are wastage. Single dose vials are not tracked.
+pub(crate) fn use_dose_from_open_vial(
+    ctx: &ServiceContext,
+    store_id: &str,
+    stock_line_id: &str,
+    vaccine_course_dose_id: &str,
+) -> Result<Option<VaccineOpenVialRow>, DiscardOpenVialError> {
+    let connection = &ctx.connection;
+    let stock_line = StockLineRepository::new(connection)
+        .query_by_filter(
+            StockLineFilter::new().id(EqualFilter::equal_to(stock_line_id)),
+            None,
+        )?
+        .pop()
+        .ok_or(RepositoryError::NotFound)?;
+
+    let doses_per_vial = stock_line.item_row.vaccine_doses;
+    if doses_per_vial <= 1 {
+        return Ok(None);
+    }
+
+    let now = Utc::now().naive_utc();
+    let repo = VaccineOpenVialRowRepository::new(connection);
+
+    let (usable_vials, expired_vials): (Vec<_>, Vec<_>) =
+        VaccineOpenVialRepository::new(connection)
+            .query_by_filter(
+                VaccineOpenVialFilter::new()
+                    .store_id(EqualFilter::equal_to(store_id))
+                    .stock_line_id(EqualFilter::equal_to(stock_line_id))
+                    .is_open(true),
+            )?
+            .into_iter()
+            .partition(|vial| vial.discard_after_datetime > now);
+
+    if !expired_vials.is_empty() {
+        let reason_id = expired_vial_reason_id(connection)?;
+        for vial in expired_vials {
+            write_off_open_vial(ctx, vial, reason_id.clone())?;
+        }
+    }
+
+    let vial = match usable_vials.into_iter().next() {
+        Some(vial) => VaccineOpenVialRow {
+            doses_remaining: vial.doses_remaining - 1,
+            ..vial
+        },
+        None => {
+            let vaccine_course_id = VaccineCourseDoseRowRepository::new(connection)
+                .find_one_by_id(vaccine_course_dose_id)?
+                .ok_or(RepositoryError::NotFound)?
+                .vaccine_course_id;
+            let vaccine_course = VaccineCourseRowRepository::new(connection)
+                .find_one_by_id(&vaccine_course_id)?
+                .ok_or(RepositoryError::NotFound)?;
+
+            open_vial(
+                store_id,
+                &stock_line.stock_line_row.item_link_id,
+                stock_line_id,
+                &vaccine_course_id,
+                vaccine_course.open_vial_discard_hours,
+                doses_per_vial,
+                now,
+            )
+        }
+    };
+
+    repo.upsert_one(&vial)?;
+
+    Ok(Some(vial))
+}
+
+/// Vials discarded because of the open vial policy weren't discarded by a user, when adjustment
+/// reasons are in use the first active reduction reason is recorded
+fn expired_vial_reason_id(
+    connection: &StorageConnection,
+) -> Result<Option<String>, RepositoryError> {
+    let reason_id = check_active_adjustment_reasons(connection, 1.0)?
+        .and_then(|reasons| reasons.into_iter().next())
+        .map(|reason| reason.inventory_adjustment_reason_row.id);
+    Ok(reason_id)
+}
+
+/// Puts a dose back into the most recently opened vial of the stock line, used when a
+/// vaccination prescription is reversed
+pub(crate) fn return_dose_to_open_vial(
+    connection: &StorageConnection,
+    store_id: &str,
+    stock_line_id: &str,
+) -> Result<(), RepositoryError> {
+    let vial = VaccineOpenVialRepository::new(connection)
+        .query_by_filter(
+            VaccineOpenVialFilter::new()
+                .store_id(EqualFilter::equal_to(store_id))
+                .stock_line_id(EqualFilter::equal_to(stock_line_id)),
+        )?
+        .into_iter()
+        .rev()
+        .find(|vial| vial.discarded_datetime.is_none() && vial.doses_remaining < vial.total_doses);
+
+    if let Some(vial) = vial {
+        VaccineOpenVialRowRepository::new(connection).upsert_one(&VaccineOpenVialRow {
+            doses_remaining: vial.doses_remaining + 1,
+            ..vial
+        })?;
+    }
+
+    Ok(())
+}
+
+fn open_vial(
+    store_id: &str,
+    item_link_id: &str,
+    stock_line_id: &str,
+    vaccine_course_id: &str,
+    open_vial_discard_hours: Option<i32>,
+    doses_per_vial: i32,
+    now: NaiveDateTime,
+) -> VaccineOpenVialRow {
+    let discard_hours = open_vial_discard_hours
+        .map(i64::from)
+        .unwrap_or(DEFAULT_OPEN_VIAL_DISCARD_HOURS);
+
+    VaccineOpenVialRow {
+        id: uuid(),
+        store_id: store_id.to_string(),
+        stock_line_id: stock_line_id.to_string(),
+        item_link_id: item_link_id.to_string(),
+        vaccine_course_id: vaccine_course_id.to_string(),
+        opened_datetime: now,
+        discard_after_datetime: now + Duration::hours(discard_hours),
+        total_doses: doses_per_vial,
+        // First dose is used straight away
+        doses_remaining: doses_per_vial - 1,
+        discarded_datetime: None,
+        wastage_invoice_id: None,
+    }
+}
+
+/// Vials that can still be used, vials past the open vial policy are not included
+pub fn get_open_vials(
+    ctx: &ServiceContext,
+    store_id: &str,
+) -> Result<Vec<VaccineOpenVialRow>, RepositoryError> {
+    let now = Utc::now().naive_utc();
+    let vials = VaccineOpenVialRepository::new(&ctx.connection)
+        .query_by_filter(
+            VaccineOpenVialFilter::new()
+                .store_id(EqualFilter::equal_to(store_id))
+                .is_open(true),
+        )?
+        .into_iter()
+        .filter(|vial| vial.discard_after_datetime > now)
+        .collect();
+    Ok(vials)
+}
+
+#[derive(PartialEq, Debug)]
+pub enum DiscardOpenVialError {
+    OpenVialDoesNotExist,
+    OpenVialAlreadyClosed,
+    AdjustmentReasonNotProvided,
+    AdjustmentReasonNotValid,
+    InternalError(String),
+    DatabaseError(RepositoryError),
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct DiscardOpenVial {
+    pub id: String,
+    pub inventory_adjustment_reason_id: Option<String>,
+}
+
+/// Discards the doses remaining in an open vial, writing them off with an inventory adjustment
+pub fn discard_open_vial(
+    ctx: &ServiceContext,
+    input: DiscardOpenVial,
+) -> Result<VaccineOpenVialRow, DiscardOpenVialError> {
+    let vial = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let vial = VaccineOpenVialRowRepository::new(connection)
+                .find_one_by_id(&input.id)?
+                .filter(|vial| vial.store_id == ctx.store_id)
+                .ok_or(DiscardOpenVialError::OpenVialDoesNotExist)?;
+
+            if vial.discarded_datetime.is_some() || vial.doses_remaining <= 0 {
+                return Err(DiscardOpenVialError::OpenVialAlreadyClosed);
+            }
+
+            write_off_open_vial(ctx, vial, input.inventory_adjustment_reason_id.clone())
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(vial)
+}
+
+fn write_off_open_vial(
+    ctx: &ServiceContext,
+    vial: VaccineOpenVialRow,
+    inventory_adjustment_reason_id: Option<String>,
+) -> Result<VaccineOpenVialRow, DiscardOpenVialError> {
+    let connection = &ctx.connection;
+    let stock_line_row = StockLineRowRepository::new(connection)
+        .find_one_by_id(&vial.stock_line_id)?
+        .ok_or(DiscardOpenVialError::InternalError(
+            "Stock line for open vial not found".to_string(),
+        ))?;
+
+    let wastage = insert_inventory_adjustment(
+        ctx,
+        InsertInventoryAdjustment {
+            stock_line_id: vial.stock_line_id.clone(),
+            adjustment: vial.doses_remaining as f64
+                / vial.total_doses as f64
+                / stock_line_row.pack_size,
+            adjustment_type: AdjustmentType::Reduction,
+            inventory_adjustment_reason_id,
+        },
+    )?;
+
+    let vial = VaccineOpenVialRow {
+        discarded_datetime: Some(Utc::now().naive_utc()),
+        wastage_invoice_id: Some(wastage.invoice_row.id),
+        ..vial
+    };
+    VaccineOpenVialRowRepository::new(connection).upsert_one(&vial)?;
+
+    Ok(vial)
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct VaccineWastage {
+    pub item: Item,
+    pub doses_opened: i64,
+    pub doses_administered: i64,
+    pub doses_wasted: i64,
+}
+
+impl VaccineWastage {
+    /// Proportion of used doses (administered or discarded) that were discarded
+    pub fn wastage_rate(&self) -> f64 {
+        let doses_used = self.doses_administered + self.doses_wasted;
+        if doses_used == 0 {
+            return 0.0;
+        }
+        self.doses_wasted as f64 / doses_used as f64
+    }
+}
+
+/// Open vial wastage per vaccine item for the store, ordered by item name
+pub fn get_vaccine_wastage(
+    ctx: &ServiceContext,
+    store_id: &str,
+) -> Result<Vec<VaccineWastage>, RepositoryError> {
+    let connection = &ctx.connection;
+    let vials = VaccineOpenVialRepository::new(connection)
+        .query_by_filter(VaccineOpenVialFilter::new().store_id(EqualFilter::equal_to(store_id)))?;
+
+    let item_link_ids: Vec<String> = vials.iter().map(|v| v.item_link_id.clone()).collect();
+    let item_id_by_link_id: HashMap<String, String> = ItemLinkRowRepository::new(connection)
+        .find_many_by_id(&item_link_ids)?
+        .into_iter()
+        .map(|link| (link.id, link.item_id))
+        .collect();
+
+    let mut doses_by_item_id: HashMap<String, (i64, i64, i64)> = HashMap::new();
+    for vial in vials {
+        let item_id = item_id_by_link_id
+            .get(&vial.item_link_id)
+            .cloned()
+            .unwrap_or(vial.item_link_id);
+        let (opened, administered, wasted) = doses_by_item_id.entry(item_id).or_default();
+
+        *opened += vial.total_doses as i64;
+        *administered += (vial.total_doses - vial.doses_remaining) as i64;
+        if vial.discarded_datetime.is_some() {
+            *wasted += vial.doses_remaining as i64;
+        }
+    }
+
+    let item_ids: Vec<String> = doses_by_item_id.keys().cloned().collect();
+    let mut result: Vec<VaccineWastage> = ItemRepository::new(connection)
+        .query_by_filter(ItemFilter::new().id(EqualFilter::equal_any(item_ids)), None)?
+        .into_iter()
+        .filter_map(|item| {
+            let (doses_opened, doses_administered, doses_wasted) =
+                doses_by_item_id.remove(&item.item_row.id)?;
+            Some(VaccineWastage {
+                item,
+                doses_opened,
+                doses_administered,
+                doses_wasted,
+            })
+        })
+        .collect();
+    result.sort_by(|a, b| a.item.item_row.name.cmp(&b.item.item_row.name));
+
+    Ok(result)
+}
+
+impl From<RepositoryError> for DiscardOpenVialError {
+    fn from(error: RepositoryError) -> Self {
+        DiscardOpenVialError::DatabaseError(error)
+    }
+}
+
+impl From<InsertInventoryAdjustmentError> for DiscardOpenVialError {
+    fn from(error: InsertInventoryAdjustmentError) -> Self {
+        match error {
+            InsertInventoryAdjustmentError::AdjustmentReasonNotProvided => {
+                DiscardOpenVialError::AdjustmentReasonNotProvided
+            }
+            InsertInventoryAdjustmentError::AdjustmentReasonNotValid => {
+                DiscardOpenVialError::AdjustmentReasonNotValid
+            }
+            InsertInventoryAdjustmentError::DatabaseError(error) => {
+                DiscardOpenVialError::DatabaseError(error)
+            }
+            error => DiscardOpenVialError::InternalError(format!(
+                "Could not create wastage adjustment: {:?}",
+                error
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{
+            mock_immunisation_encounter_a, mock_name_1, mock_stock_line_vaccine_item_a,
+            mock_store_a, mock_user_account_a, mock_vaccine_course_a_dose_b, mock_vaccine_item_a,
+            MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        InventoryAdjustmentReasonRow, InventoryAdjustmentType, InvoiceLineFilter,
+        InvoiceLineRepository, VaccineOpenVialRow, VaccineOpenVialRowRepository,
+    };
+
+    use crate::{service_provider::ServiceProvider, vaccination::insert::InsertVaccination};
+
+    use super::{use_dose_from_open_vial, DiscardOpenVial, DiscardOpenVialError};
+
+    #[actix_rt::test]
+    async fn open_vial_wastage() {
+        fn expired_reason() -> InventoryAdjustmentReasonRow {
+            InventoryAdjustmentReasonRow {
+                id: "open_vial_expired".to_string(),
+                reason: "Open vial policy".to_string(),
+                is_active: true,
+                r#type: InventoryAdjustmentType::Negative,
+            }
+        }
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "open_vial_wastage",
+            MockDataInserts::all(),
+            MockData {
+                inventory_adjustment_reasons: vec![expired_reason()],
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider
+            .context(mock_store_a().id, mock_user_account_a().id)
+            .unwrap();
+        let service = service_provider.vaccination_service;
+        let store_id = &mock_store_a().id;
+
+        // Giving a dose opens a vial (vaccine item A has 2 doses per vial)
+        service
+            .insert_vaccination(
+                &context,
+                store_id,
+                InsertVaccination {
+                    id: "vaccination_from_open_vial".to_string(),
+                    encounter_id: mock_immunisation_encounter_a().id,
+                    vaccine_course_dose_id: mock_vaccine_course_a_dose_b().id,
+                    facility_name_id: Some(mock_name_1().id),
+                    given: true,
+                    stock_line_id: Some(mock_stock_line_vaccine_item_a().id),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let open_vials = service.get_open_vials(&context, store_id).unwrap();
+        assert_eq!(open_vials.len(), 1);
+        let vial = open_vials[0].clone();
+        assert_eq!(vial.stock_line_id, mock_stock_line_vaccine_item_a().id);
+        assert_eq!(vial.total_doses, 2);
+        assert_eq!(vial.doses_remaining, 1);
+        // No policy on the vaccine course, discard at the end of the session
+        assert_eq!(
+            vial.discard_after_datetime - vial.opened_datetime,
+            chrono::Duration::hours(6)
+        );
+
+        // Errors
+        assert_eq!(
+            service.discard_open_vial(
+                &context,
+                DiscardOpenVial {
+                    id: "invalid".to_string(),
+                    ..Default::default()
+                }
+            ),
+            Err(DiscardOpenVialError::OpenVialDoesNotExist)
+        );
+        assert_eq!(
+            service.discard_open_vial(
+                &context,
+                DiscardOpenVial {
+                    id: vial.id.clone(),
+                    inventory_adjustment_reason_id: None,
+                }
+            ),
+            Err(DiscardOpenVialError::AdjustmentReasonNotProvided)
+        );
+
+        // Success - remaining dose is written off
+        let discarded = service
+            .discard_open_vial(
+                &context,
+                DiscardOpenVial {
+                    id: vial.id.clone(),
+                    inventory_adjustment_reason_id: Some(expired_reason().id),
+                },
+            )
+            .unwrap();
+        assert!(discarded.discarded_datetime.is_some());
+
+        let wastage_line = InvoiceLineRepository::new(&context.connection)
+            .query_by_filter(InvoiceLineFilter::new().invoice_id(
+                repository::EqualFilter::equal_to(&discarded.wastage_invoice_id.unwrap()),
+            ))
+            .unwrap()
+            .pop()
+            .unwrap();
+        // 1 of 2 doses per vial, 5 vials per pack
+        assert_eq!(wastage_line.invoice_line_row.number_of_packs, 0.1);
+        assert_eq!(
+            wastage_line.invoice_line_row.inventory_adjustment_reason_id,
+            Some(expired_reason().id)
+        );
+
+        assert_eq!(
+            service.discard_open_vial(
+                &context,
+                DiscardOpenVial {
+                    id: vial.id.clone(),
+                    inventory_adjustment_reason_id: Some(expired_reason().id),
+                }
+            ),
+            Err(DiscardOpenVialError::OpenVialAlreadyClosed)
+        );
+        assert_eq!(service.get_open_vials(&context, store_id).unwrap(), vec![]);
+
+        // Wastage statistics
+        let wastage = service.get_vaccine_wastage(&context, store_id).unwrap();
+        assert_eq!(wastage.len(), 1);
+        assert_eq!(wastage[0].item.item_row.id, mock_vaccine_item_a().id);
+        assert_eq!(wastage[0].doses_opened, 2);
+        assert_eq!(wastage[0].doses_administered, 1);
+        assert_eq!(wastage[0].doses_wasted, 1);
+        assert_eq!(wastage[0].wastage_rate(), 0.5);
+
+        // Vials past the open vial policy are not listed and are discarded when the next dose is
+        // taken from the stock line
+        let use_dose = || {
+            use_dose_from_open_vial(
+                &context,
+                store_id,
+                &mock_stock_line_vaccine_item_a().id,
+                &mock_vaccine_course_a_dose_b().id,
+            )
+            .unwrap()
+            .unwrap()
+        };
+        let expiring = use_dose();
+        let vial_repo = VaccineOpenVialRowRepository::new(&context.connection);
+        vial_repo
+            .upsert_one(&VaccineOpenVialRow {
+                discard_after_datetime: expiring.opened_datetime,
+                ..expiring.clone()
+            })
+            .unwrap();
+        assert_eq!(service.get_open_vials(&context, store_id).unwrap(), vec![]);
+
+        let opened = use_dose();
+        assert_ne!(opened.id, expiring.id);
+        assert_eq!(
+            service.get_open_vials(&context, store_id).unwrap(),
+            vec![opened]
+        );
+        let expired = vial_repo.find_one_by_id(&expiring.id).unwrap().unwrap();
+        assert!(expired.discarded_datetime.is_some());
+        let wastage_line = InvoiceLineRepository::new(&context.connection)
+            .query_by_filter(InvoiceLineFilter::new().invoice_id(
+                repository::EqualFilter::equal_to(&expired.wastage_invoice_id.unwrap()),
+            ))
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert_eq!(wastage_line.invoice_line_row.number_of_packs, 0.1);
+
+        let wastage = service.get_vaccine_wastage(&context, store_id).unwrap();
+        assert_eq!(wastage[0].doses_opened, 6);
+        assert_eq!(wastage[0].doses_administered, 3);
+        assert_eq!(wastage[0].doses_wasted, 2);
+    }
+}
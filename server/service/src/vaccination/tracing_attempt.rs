@@ -0,0 +1,193 @@
+use chrono::{NaiveDateTime, Utc};
+use repository::{
+    EqualFilter, ProgramEnrolment, ProgramEnrolmentFilter, ProgramEnrolmentRepository,
+    RepositoryError,
+};
+
+use crate::{
+    programs::program_event::{EventInput, ProgramEventService, ProgramEventServiceTrait},
+    service_provider::ServiceContext,
+};
+
+/// Program event type used to record attempts to trace a vaccination defaulter
+pub const VACCINATION_TRACING_EVENT_TYPE: &str = "VaccinationTracingAttempt";
+
+#[derive(PartialEq, Debug)]
+pub enum LogTracingAttemptError {
+    ProgramEnrolmentDoesNotExist,
+    NotAllowedToMutateDocument,
+    OutcomeNotProvided,
+    DatabaseError(RepositoryError),
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct LogTracingAttempt {
+    pub program_enrolment_id: String,
+    /// e.g. "Phoned, no answer" or "Home visit, will attend on Monday"
+    pub outcome: String,
+    /// Defaults to now
+    pub datetime: Option<NaiveDateTime>,
+}
+
+/// Records a tracing attempt for a patient on an immunisation program as a program event on the
+/// patient's enrolment document.
+pub fn log_tracing_attempt(
+    ctx: &ServiceContext,
+    input: LogTracingAttempt,
+    allowed_ctx: Vec<String>,
+) -> Result<ProgramEnrolment, LogTracingAttemptError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            if input.outcome.trim().is_empty() {
+                return Err(LogTracingAttemptError::OutcomeNotProvided);
+            }
+
+            let enrolment = ProgramEnrolmentRepository::new(connection)
+                .query_by_filter(
+                    ProgramEnrolmentFilter::new()
+                        .id(EqualFilter::equal_to(&input.program_enrolment_id)),
+                )?
+                .pop()
+                .ok_or(LogTracingAttemptError::ProgramEnrolmentDoesNotExist)?;
+            if !allowed_ctx.contains(&enrolment.program_row.context_id) {
+                return Err(LogTracingAttemptError::NotAllowedToMutateDocument);
+            }
+
+            let datetime = input.datetime.unwrap_or(Utc::now().naive_utc());
+
+            ProgramEventService {}.upsert_events(
+                connection,
+                enrolment.patient_row.id.clone(),
+                datetime,
+                &enrolment.program_row.context_id,
+                vec![EventInput {
+                    active_start_datetime: datetime,
+                    document_type: enrolment.row.document_type.clone(),
+                    document_name: Some(enrolment.row.document_name.clone()),
+                    r#type: VACCINATION_TRACING_EVENT_TYPE.to_string(),
+                    name: Some(input.outcome.clone()),
+                }],
+            )?;
+
+            Ok(enrolment)
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(result)
+}
+
+impl From<RepositoryError> for LogTracingAttemptError {
+    fn from(error: RepositoryError) -> Self {
+        LogTracingAttemptError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{
+            context_immunisation_program, mock_immunisation_program_enrolment_a, mock_patient,
+            MockDataInserts,
+        },
+        NameRow, NameRowRepository,
+    };
+
+    use crate::{
+        test_helpers::{setup_all_and_service_provider, ServiceTestContext},
+        vaccination::defaulters::{get_vaccination_defaulters, VaccinationDefaulterFilter},
+    };
+
+    use super::{log_tracing_attempt, LogTracingAttempt, LogTracingAttemptError};
+
+    #[actix_rt::test]
+    async fn test_log_tracing_attempt() {
+        let ServiceTestContext {
+            connection,
+            service_context,
+            ..
+        } = setup_all_and_service_provider("test_log_tracing_attempt", MockDataInserts::all())
+            .await;
+
+        NameRowRepository::new(&connection)
+            .upsert_one(&NameRow {
+                date_of_birth: NaiveDate::from_ymd_opt(2024, 1, 1),
+                ..mock_patient()
+            })
+            .unwrap();
+        let allowed_ctx = vec![context_immunisation_program().id];
+
+        // Errors
+        assert_eq!(
+            log_tracing_attempt(
+                &service_context,
+                LogTracingAttempt {
+                    program_enrolment_id: "invalid".to_string(),
+                    outcome: "Phoned".to_string(),
+                    ..Default::default()
+                },
+                allowed_ctx.clone(),
+            )
+            .unwrap_err(),
+            LogTracingAttemptError::ProgramEnrolmentDoesNotExist
+        );
+        assert_eq!(
+            log_tracing_attempt(
+                &service_context,
+                LogTracingAttempt {
+                    program_enrolment_id: mock_immunisation_program_enrolment_a().id,
+                    outcome: " ".to_string(),
+                    ..Default::default()
+                },
+                allowed_ctx.clone(),
+            )
+            .unwrap_err(),
+            LogTracingAttemptError::OutcomeNotProvided
+        );
+        assert_eq!(
+            log_tracing_attempt(
+                &service_context,
+                LogTracingAttempt {
+                    program_enrolment_id: mock_immunisation_program_enrolment_a().id,
+                    outcome: "Phoned".to_string(),
+                    ..Default::default()
+                },
+                vec!["other_context".to_string()],
+            )
+            .unwrap_err(),
+            LogTracingAttemptError::NotAllowedToMutateDocument
+        );
+
+        // Success, latest attempt is shown on the defaulter list
+        for (day, outcome) in [(2, "Phoned, no answer"), (5, "Home visit")] {
+            log_tracing_attempt(
+                &service_context,
+                LogTracingAttempt {
+                    program_enrolment_id: mock_immunisation_program_enrolment_a().id,
+                    outcome: outcome.to_string(),
+                    datetime: NaiveDate::from_ymd_opt(2024, 3, day)
+                        .unwrap()
+                        .and_hms_opt(10, 0, 0),
+                },
+                allowed_ctx.clone(),
+            )
+            .unwrap();
+        }
+
+        let result = get_vaccination_defaulters(
+            &service_context,
+            VaccinationDefaulterFilter {
+                reference_date: NaiveDate::from_ymd_opt(2024, 3, 6),
+                ..Default::default()
+            },
+            allowed_ctx,
+        )
+        .unwrap();
+        let last_attempt = result[0].defaulters[0]
+            .last_tracing_attempt
+            .clone()
+            .unwrap();
+        assert_eq!(last_attempt.data, Some("Home visit".to_string()));
+    }
+}
@@ -21,7 +21,11 @@ mod validate;
 use generate::{generate, CreateCustomerReturn, GenerateInput, GenerateResult};
 use validate::{validate, ValidateResult};
 
-use super::{generate::CreatePrescription, query::get_vaccination};
+use super::{
+    generate::CreatePrescription,
+    open_vial::{return_dose_to_open_vial, use_dose_from_open_vial, DiscardOpenVialError},
+    query::get_vaccination,
+};
 
 #[derive(PartialEq, Debug)]
 pub enum UpdateVaccinationError {
@@ -90,10 +94,19 @@ pub fn update_vaccination(
                 finalise_return,
             }) = create_customer_return
             {
+                let returned_stock_line_ids: Vec<String> = create_return
+                    .customer_return_lines
+                    .iter()
+                    .filter_map(|line| line.stock_line_id.clone())
+                    .collect();
                 // Create customer return (in NEW status, with line)
                 insert_customer_return(ctx, create_return)?;
                 // Finalise, reintroducing stock, and add comment
                 update_customer_return(ctx, finalise_return)?;
+                // Dose wasn't used, put it back in the open vial
+                for stock_line_id in returned_stock_line_ids {
+                    return_dose_to_open_vial(connection, store_id, &stock_line_id)?;
+                }
             }
 
             // Create new prescription if needed
@@ -103,12 +116,20 @@ pub fn update_vaccination(
                 finalise_prescription,
             }) = create_prescription
             {
+                let stock_line_id = insert_stock_out_line_input.stock_line_id.clone();
                 // Create prescription (in NEW status)
                 insert_prescription(ctx, create_prescription)?;
                 // Add the prescription line
                 insert_stock_out_line(ctx, insert_stock_out_line_input)?;
                 // Finalise the prescription - also link clinician
                 update_prescription(ctx, finalise_prescription)?;
+                // Take the dose from an open vial (opening a new one if required)
+                use_dose_from_open_vial(
+                    ctx,
+                    store_id,
+                    &stock_line_id,
+                    &vaccination.vaccine_course_dose_id,
+                )?;
             }
 
             activity_log_entry(
@@ -125,6 +146,20 @@ pub fn update_vaccination(
     Ok(vaccination)
 }
 
+impl From<DiscardOpenVialError> for UpdateVaccinationError {
+    fn from(error: DiscardOpenVialError) -> Self {
+        match error {
+            DiscardOpenVialError::DatabaseError(error) => {
+                UpdateVaccinationError::DatabaseError(error)
+            }
+            error => UpdateVaccinationError::InternalError(format!(
+                "Could not discard expired open vial: {:?}",
+                error
+            )),
+        }
+    }
+}
+
 impl From<RepositoryError> for UpdateVaccinationError {
     fn from(error: RepositoryError) -> Self {
         UpdateVaccinationError::DatabaseError(error)
@@ -42,6 +42,7 @@ pub struct InsertVaccineCourse {
     pub coverage_rate: f64,
     pub is_active: bool,
     pub wastage_rate: f64,
+    pub open_vial_discard_hours: Option<i32>,
 }
 
 pub fn insert_vaccine_course(
@@ -121,6 +122,7 @@ pub fn generate(
         coverage_rate,
         is_active,
         wastage_rate,
+        open_vial_discard_hours,
     }: InsertVaccineCourse,
 ) -> VaccineCourseRow {
     VaccineCourseRow {
@@ -131,6 +133,7 @@ pub fn generate(
         coverage_rate,
         is_active,
         wastage_rate,
+        open_vial_discard_hours,
         deleted_datetime: None,
     }
 }
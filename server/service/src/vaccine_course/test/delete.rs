@@ -55,6 +55,7 @@ mod delete {
             coverage_rate: 100.0,
             is_active: true,
             wastage_rate: 0.1,
+            open_vial_discard_hours: None,
         };
 
         let _result = service
@@ -38,6 +38,7 @@ mod query {
             coverage_rate: 100.0,
             is_active: true,
             wastage_rate: 0.1,
+            open_vial_discard_hours: None,
         };
 
         let _result = service
@@ -56,6 +57,7 @@ mod query {
             coverage_rate: 100.0,
             is_active: true,
             wastage_rate: 0.1,
+            open_vial_discard_hours: None,
         };
 
         assert_eq!(
@@ -75,6 +77,7 @@ mod query {
             coverage_rate: 100.0,
             is_active: true,
             wastage_rate: 0.1,
+            open_vial_discard_hours: None,
         };
 
         let result = service
@@ -142,6 +145,7 @@ mod query {
             coverage_rate: 100.0,
             is_active: true,
             wastage_rate: 0.1,
+            open_vial_discard_hours: None,
         };
 
         let result = service
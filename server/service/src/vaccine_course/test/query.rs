@@ -32,6 +32,7 @@ mod query {
             coverage_rate: 100.0,
             is_active: true,
             wastage_rate: 0.1,
+            open_vial_discard_hours: None,
         };
 
         let _result = service
@@ -70,6 +71,7 @@ mod query {
             coverage_rate: 100.0,
             is_active: true,
             wastage_rate: 0.1,
+            open_vial_discard_hours: None,
         };
 
         let _result = service
@@ -86,6 +88,7 @@ mod query {
             coverage_rate: 100.0,
             is_active: true,
             wastage_rate: 0.1,
+            open_vial_discard_hours: None,
         };
 
         let _result = service
@@ -81,6 +81,7 @@ pub struct UpdateVaccineCourse {
     pub coverage_rate: f64,
     pub is_active: bool,
     pub wastage_rate: f64,
+    pub open_vial_discard_hours: Option<i32>,
 }
 
 pub fn update_vaccine_course(
@@ -200,6 +201,7 @@ fn generate(
         coverage_rate,
         is_active,
         wastage_rate,
+        open_vial_discard_hours,
     }: UpdateVaccineCourse,
 ) -> Result<GenerateResult, RepositoryError> {
     let updated_course = VaccineCourseRow {
@@ -210,6 +212,7 @@ fn generate(
         coverage_rate,
         is_active,
         wastage_rate,
+        open_vial_discard_hours,
         deleted_datetime: None,
     };
 
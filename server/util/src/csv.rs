@@ -0,0 +1,21 @@
+/// Quotes a CSV field if it contains a separator, quote or line break
+pub fn escape_csv_value(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_escape_csv_value() {
+        assert_eq!(escape_csv_value("plain"), "plain");
+        assert_eq!(escape_csv_value("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv_value("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_csv_value("line\nbreak"), "\"line\nbreak\"");
+    }
+}
@@ -1,6 +1,7 @@
 pub mod canonical_json;
 pub mod constants;
 pub mod hash;
+pub mod metrics;
 pub mod timezone;
 pub mod uuid;
 
@@ -30,3 +31,6 @@ pub use file::*;
 
 mod gs1;
 pub use gs1::*;
+
+mod csv;
+pub use csv::*;
@@ -0,0 +1,265 @@
+//! Minimal in process metrics, rendered in the Prometheus text exposition format.
+//! Metrics are always recorded (cheap), they are only exposed when the metrics endpoint is enabled
+
+use std::{collections::BTreeMap, fmt::Write, sync::Mutex, time::Instant};
+
+type Labels = Vec<(&'static str, String)>;
+
+fn to_labels(labels: &[(&'static str, &str)]) -> Labels {
+    labels
+        .iter()
+        .map(|(name, value)| (*name, value.to_string()))
+        .collect()
+}
+
+fn format_labels(labels: &Labels, extra: Option<(&str, String)>) -> String {
+    let labels: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| (*name, value.clone()))
+        .chain(extra)
+        .map(|(name, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{}=\"{}\"", name, value)
+        })
+        .collect();
+
+    if labels.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", labels.join(","))
+    }
+}
+
+pub struct Counter {
+    name: &'static str,
+    help: &'static str,
+    values: Mutex<BTreeMap<Labels, f64>>,
+}
+
+impl Counter {
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Counter {
+            name,
+            help,
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn inc(&self, labels: &[(&'static str, &str)]) {
+        self.inc_by(labels, 1.0)
+    }
+
+    pub fn inc_by(&self, labels: &[(&'static str, &str)], value: f64) {
+        if let Ok(mut values) = self.values.lock() {
+            *values.entry(to_labels(labels)).or_insert(0.0) += value;
+        }
+    }
+
+    fn render(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} counter", self.name);
+        if let Ok(values) = self.values.lock() {
+            for (labels, value) in values.iter() {
+                let _ = writeln!(
+                    out,
+                    "{}{} {}",
+                    self.name,
+                    format_labels(labels, None),
+                    value
+                );
+            }
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+struct HistogramValues {
+    /// Count of observations in each bucket (not cumulative)
+    bucket_counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+pub struct Histogram {
+    name: &'static str,
+    help: &'static str,
+    /// Upper bounds of the buckets, in increasing order
+    buckets: &'static [f64],
+    values: Mutex<BTreeMap<Labels, HistogramValues>>,
+}
+
+/// Buckets for durations in seconds, from 5ms to 5 minutes
+pub const DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0,
+];
+
+impl Histogram {
+    pub const fn new(name: &'static str, help: &'static str, buckets: &'static [f64]) -> Self {
+        Histogram {
+            name,
+            help,
+            buckets,
+            values: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn observe(&self, labels: &[(&'static str, &str)], value: f64) {
+        let Ok(mut values) = self.values.lock() else {
+            return;
+        };
+        let entry = values
+            .entry(to_labels(labels))
+            .or_insert_with(|| HistogramValues {
+                bucket_counts: vec![0; self.buckets.len()],
+                ..Default::default()
+            });
+
+        if let Some(index) = self.buckets.iter().position(|bound| value <= *bound) {
+            entry.bucket_counts[index] += 1;
+        }
+        entry.sum += value;
+        entry.count += 1;
+    }
+
+    /// Observes seconds elapsed since `start`
+    pub fn observe_since(&self, labels: &[(&'static str, &str)], start: Instant) {
+        self.observe(labels, start.elapsed().as_secs_f64())
+    }
+
+    fn render(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} histogram", self.name);
+        let Ok(values) = self.values.lock() else {
+            return;
+        };
+        for (labels, values) in values.iter() {
+            let mut cumulative = 0;
+            for (bound, count) in self.buckets.iter().zip(values.bucket_counts.iter()) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{} {}",
+                    self.name,
+                    format_labels(labels, Some(("le", bound.to_string()))),
+                    cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{}_bucket{} {}",
+                self.name,
+                format_labels(labels, Some(("le", "+Inf".to_string()))),
+                values.count
+            );
+            let labels = format_labels(labels, None);
+            let _ = writeln!(out, "{}_sum{} {}", self.name, labels, values.sum);
+            let _ = writeln!(out, "{}_count{} {}", self.name, labels, values.count);
+        }
+    }
+}
+
+/// Value sampled when metrics are rendered, e.g. DB pool state
+pub struct Gauge {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub value: f64,
+}
+
+pub static SYNC_RUNS: Counter = Counter::new(
+    "omsupply_sync_runs_total",
+    "Number of completed sync runs by result",
+);
+pub static SYNC_DURATION: Histogram = Histogram::new(
+    "omsupply_sync_duration_seconds",
+    "Duration of sync runs",
+    DURATION_BUCKETS,
+);
+pub static PROCESSOR_RUNS: Counter = Counter::new(
+    "omsupply_processor_runs_total",
+    "Number of processor runs by processor and result",
+);
+pub static PROCESSOR_DURATION: Histogram = Histogram::new(
+    "omsupply_processor_duration_seconds",
+    "Duration of processor runs by processor",
+    DURATION_BUCKETS,
+);
+pub static GRAPHQL_REQUESTS: Counter = Counter::new(
+    "omsupply_graphql_requests_total",
+    "Number of graphql requests by operation type and result",
+);
+pub static GRAPHQL_DURATION: Histogram = Histogram::new(
+    "omsupply_graphql_request_duration_seconds",
+    "Duration of graphql requests by operation type",
+    DURATION_BUCKETS,
+);
+pub static DB_CONNECTION_WAIT: Histogram = Histogram::new(
+    "omsupply_db_connection_wait_seconds",
+    "Time spent waiting for a connection from the DB pool",
+    DURATION_BUCKETS,
+);
+pub static DB_CONNECTION_ERRORS: Counter = Counter::new(
+    "omsupply_db_connection_errors_total",
+    "Number of failures to get a connection from the DB pool",
+);
+
+/// Renders all metrics followed by `gauges`
+pub fn render_metrics(gauges: &[Gauge]) -> String {
+    let mut out = String::new();
+    SYNC_RUNS.render(&mut out);
+    SYNC_DURATION.render(&mut out);
+    PROCESSOR_RUNS.render(&mut out);
+    PROCESSOR_DURATION.render(&mut out);
+    GRAPHQL_REQUESTS.render(&mut out);
+    GRAPHQL_DURATION.render(&mut out);
+    DB_CONNECTION_WAIT.render(&mut out);
+    DB_CONNECTION_ERRORS.render(&mut out);
+
+    for gauge in gauges {
+        let _ = writeln!(out, "# HELP {} {}", gauge.name, gauge.help);
+        let _ = writeln!(out, "# TYPE {} gauge", gauge.name);
+        let _ = writeln!(out, "{} {}", gauge.name, gauge.value);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_counter_and_histogram() {
+        let counter = Counter::new("test_total", "Test counter");
+        counter.inc(&[("result", "success")]);
+        counter.inc_by(&[("result", "success")], 2.0);
+        counter.inc(&[("result", "error \"quoted\"")]);
+
+        let histogram = Histogram::new("test_seconds", "Test histogram", &[0.1, 1.0]);
+        histogram.observe(&[], 0.05);
+        histogram.observe(&[], 0.5);
+        histogram.observe(&[], 2.0);
+
+        let mut out = String::new();
+        counter.render(&mut out);
+        histogram.render(&mut out);
+
+        assert_eq!(
+            out,
+            r#"# HELP test_total Test counter
+# TYPE test_total counter
+test_total{result="error \"quoted\""} 1
+test_total{result="success"} 3
+# HELP test_seconds Test histogram
+# TYPE test_seconds histogram
+test_seconds_bucket{le="0.1"} 1
+test_seconds_bucket{le="1"} 2
+test_seconds_bucket{le="+Inf"} 3
+test_seconds_sum 2.55
+test_seconds_count 3
+"#
+        );
+    }
+}